@@ -1,31 +1,89 @@
-const CACHE_NAME = 'xiangqi-pwa-v2';
+// Bump the version whenever the precached file list changes. Deploys do not need a bump:
+// the page and the code bundles are fetched network first, see below.
+const CACHE_NAME = 'xiangqi-pwa-v4';
 const REPO_NAME = '/GameCoTuong';
+// Everything needed to play against the AI or hotseat without a network.
+// File names are stable because Trunk.toml disables file hashing, so the code bundles must
+// never be served cache first or installed clients would keep an old build forever.
 const ASSETS = [
     './',
     './index.html',
     './manifest.json',
     './icon.png',
-    // Trunk generated files will need to be cached dynamically or via a build step that injects them.
-    // For now, we rely on the browser cache for those or the runtime caching below.
+    './GameCoTuong.js',
+    './GameCoTuong_bg.wasm',
+    './worker.js',
+    './worker_bg.wasm',
+    './sounds/move.mp3',
+    './sounds/capture.mp3',
+    './sounds/check.mp3',
+    './sounds/checkmate.mp3',
 ];
 
 self.addEventListener('install', (e) => {
     e.waitUntil(
-        caches.open(CACHE_NAME).then((cache) => {
-            return cache.addAll(ASSETS).catch(err => console.error(err));
-        })
+        caches.open(CACHE_NAME)
+            .then((cache) => cache.addAll(ASSETS))
+            .then(() => self.skipWaiting())
+            .catch(err => console.error(err))
+    );
+});
+
+self.addEventListener('activate', (e) => {
+    e.waitUntil(
+        caches.keys()
+            .then((keys) => Promise.all(
+                keys.filter((key) => key !== CACHE_NAME).map((key) => caches.delete(key))
+            ))
+            .then(() => self.clients.claim())
     );
 });
 
 self.addEventListener('fetch', (e) => {
+    const url = new URL(e.request.url);
+    // Online play (WebSocket, leaderboard, live games) always goes to the server
+    if (e.request.method !== 'GET' || url.origin !== self.location.origin) {
+        return;
+    }
+
+    // Pages: network first so deploys show up, cache when offline
+    if (e.request.mode === 'navigate') {
+        e.respondWith(networkFirst(e.request, './index.html'));
+        return;
+    }
+
+    // Code bundles: network first too, so the JS glue, the wasm and the worker always come
+    // from the same build and match the server's protocol version
+    if (/\.(js|wasm)$/.test(url.pathname)) {
+        e.respondWith(networkFirst(e.request, e.request));
+        return;
+    }
+
+    // Icons, sounds and the manifest rarely change: cache first
+
     e.respondWith(
         caches.match(e.request).then((response) => {
             return response || fetch(e.request).then((response) => {
-                return caches.open(CACHE_NAME).then((cache) => {
-                    cache.put(e.request, response.clone());
-                    return response;
-                });
+                if (response.ok) {
+                    const copy = response.clone();
+                    caches.open(CACHE_NAME).then((cache) => cache.put(e.request, copy));
+                }
+                return response;
             });
         })
     );
 });
+
+// Fetches `request`, refreshing the cached copy under `cacheKey`; the cached copy is used
+// only when the network fails.
+function networkFirst(request, cacheKey) {
+    return fetch(request)
+        .then((response) => {
+            if (response.ok) {
+                const copy = response.clone();
+                caches.open(CACHE_NAME).then((cache) => cache.put(cacheKey, copy));
+            }
+            return response;
+        })
+        .catch(() => caches.match(cacheKey));
+}
@@ -0,0 +1,106 @@
+use cotuong_core::engine::config::StrengthLimit;
+use cotuong_core::logic::board::Color;
+use serde::{Deserialize, Serialize};
+
+/// Skill change for a won or lost game.
+const RESULT_STEP: f64 = 0.08;
+/// Extra skill change when the player was clearly accurate or sloppy.
+const ACCURACY_STEP: f64 = 0.04;
+/// Average centipawn loss per move below which the player counts as accurate.
+const ACCURATE_CP_LOSS: f64 = 40.0;
+/// Average centipawn loss per move above which the player counts as sloppy.
+const SLOPPY_CP_LOSS: f64 = 120.0;
+/// Largest loss counted for a single move, so one hung piece or mate does not swamp the average.
+pub const MAX_MOVE_CP_LOSS: i32 = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl GameOutcome {
+    /// The result for `side` of a game won by `winner`, or drawn.
+    pub fn for_side(winner: Option<Color>, side: Color) -> Self {
+        match winner {
+            Some(winner) if winner == side => Self::Win,
+            Some(_) => Self::Loss,
+            None => Self::Draw,
+        }
+    }
+}
+
+/// The player's record against the "Adaptive" level and the engine strength it led to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveProfile {
+    /// 0.0 is the weakest engine, 1.0 the strongest this level goes.
+    pub skill: f64,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Smoothed average centipawn loss per move over recent games.
+    pub avg_cp_loss: Option<f64>,
+}
+
+impl Default for AdaptiveProfile {
+    fn default() -> Self {
+        Self {
+            skill: 0.2,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            avg_cp_loss: None,
+        }
+    }
+}
+
+impl AdaptiveProfile {
+    /// Moves the engine towards the player's level after a finished game.
+    pub fn record_game(&mut self, outcome: GameOutcome, cp_loss: Option<f64>) {
+        let mut step = match outcome {
+            GameOutcome::Win => {
+                self.wins += 1;
+                RESULT_STEP
+            }
+            GameOutcome::Loss => {
+                self.losses += 1;
+                -RESULT_STEP
+            }
+            GameOutcome::Draw => {
+                self.draws += 1;
+                0.0
+            }
+        };
+
+        if let Some(loss) = cp_loss {
+            if loss < ACCURATE_CP_LOSS {
+                step += ACCURACY_STEP;
+            } else if loss > SLOPPY_CP_LOSS {
+                step -= ACCURACY_STEP;
+            }
+            self.avg_cp_loss = Some(
+                self.avg_cp_loss
+                    .map_or(loss, |avg| avg.mul_add(0.7, loss * 0.3)),
+            );
+        }
+
+        self.skill = (self.skill + step).clamp(0.0, 1.0);
+    }
+
+    pub fn strength_limit(&self) -> StrengthLimit {
+        StrengthLimit::for_skill(self.skill)
+    }
+
+    /// Thinking time grows with skill, from 1s to 5s.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn think_time_ms(&self) -> u64 {
+        self.skill.clamp(0.0, 1.0).mul_add(4000.0, 1000.0) as u64
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn skill_percent(&self) -> u32 {
+        (self.skill.clamp(0.0, 1.0) * 100.0).round() as u32
+    }
+}
@@ -0,0 +1,150 @@
+use crate::i18n::{t, use_language, Msg};
+use crate::network::{fetch_json, post_json};
+use cotuong_core::engine::config::EngineConfig;
+use leptos::{
+    component, create_effect, create_signal, event_target_value, spawn_local, view, IntoView,
+    ReadSignal, SignalGet, SignalSet, SignalUpdate,
+};
+use shared::{ArenaStanding, ArenaSubmission};
+
+/// Message shown after the server answers an upload with `status`.
+const fn submit_message(status: u16) -> Msg {
+    match status {
+        201 => Msg::ArenaSubmitted,
+        400 => Msg::ArenaInvalidName,
+        401 => Msg::ArenaNeedsSession,
+        403 => Msg::ArenaNeedsRatedGame,
+        409 => Msg::ArenaNameTaken,
+        429 => Msg::ArenaTooMany,
+        503 => Msg::ArenaUnavailable,
+        _ => Msg::ArenaRejected,
+    }
+}
+
+/// Enters the Red or Black config from the panel above into the server's engine arena and
+/// shows the arena's table.
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn ArenaPanel(
+    red_config: ReadSignal<EngineConfig>,
+    black_config: ReadSignal<EngineConfig>,
+) -> impl IntoView {
+    let (show, set_show) = create_signal(false);
+    let (name, set_name) = create_signal(String::new());
+    let (use_black, set_use_black) = create_signal(false);
+    let (standings, set_standings) = create_signal(Vec::<ArenaStanding>::new());
+    let (message, set_message) = create_signal(Option::<Msg>::None);
+    let language = use_language();
+    // Bumped to refetch the table
+    let (refresh, set_refresh) = create_signal(0u32);
+
+    create_effect(move |_| {
+        refresh.get();
+        if !show.get() {
+            return;
+        }
+        spawn_local(async move {
+            match fetch_json::<Vec<ArenaStanding>>("/arena/leaderboard").await {
+                Ok(result) => set_standings.set(result),
+                Err(e) => {
+                    leptos::logging::log!("[NET] Failed to load arena table: {:?}", e);
+                    set_message.set(Some(Msg::ArenaLoadFailed));
+                }
+            }
+        });
+    });
+
+    let submit = move |_| {
+        let submission = ArenaSubmission {
+            name: name.get().trim().to_string(),
+            config: if use_black.get() {
+                black_config.get()
+            } else {
+                red_config.get()
+            },
+        };
+        spawn_local(async move {
+            match post_json("/arena/configs", &submission).await {
+                Ok(status) => {
+                    set_message.set(Some(submit_message(status)));
+                    set_refresh.update(|n| *n += 1);
+                }
+                Err(e) => {
+                    leptos::logging::log!("[NET] Failed to submit arena config: {:?}", e);
+                    set_message.set(Some(Msg::ServerUnreachable));
+                }
+            }
+        });
+    };
+
+    view! {
+        <div class="leaderboard-panel">
+            <button
+                class="control-btn"
+                on:click=move |_| set_show.update(|v| *v = !*v)
+            >
+                {move || if show.get() { Msg::ArenaHide } else { Msg::ArenaShow }.text(language.get())}
+            </button>
+
+            {move || show.get().then(|| view! {
+                <div class="leaderboard-body">
+                    <div class="arena-submit">
+                        <input
+                            type="text"
+                            class="leaderboard-search"
+                            placeholder=t(Msg::ArenaNamePlaceholder)
+                            maxlength="32"
+                            prop:value=move || name.get()
+                            on:input=move |ev| set_name.set(event_target_value(&ev))
+                        />
+                        <select on:change=move |ev| set_use_black.set(event_target_value(&ev) == "black")>
+                            <option value="red" selected=move || !use_black.get()>{t(Msg::RedConfig)}</option>
+                            <option value="black" selected=move || use_black.get()>{t(Msg::BlackConfig)}</option>
+                        </select>
+                        <button
+                            class="control-btn"
+                            disabled=move || name.get().trim().is_empty()
+                            on:click=submit
+                        >
+                            {t(Msg::ArenaSubmit)}
+                        </button>
+                        <button class="control-btn" on:click=move |_| set_refresh.update(|n| *n += 1)>
+                            "↻"
+                        </button>
+                    </div>
+
+                    {move || message.get().map(|m| view! { <div class="arena-message">{m.text(language.get())}</div> })}
+
+                    <table class="leaderboard-table">
+                        <thead>
+                            <tr>
+                                <th>"#"</th>
+                                <th>{t(Msg::ArenaConfig)}</th>
+                                <th>"Elo"</th>
+                                <th>{t(Msg::Wins)}</th>
+                                <th>{t(Msg::Losses)}</th>
+                                <th>{t(Msg::Draws)}</th>
+                                <th>{t(Msg::ArenaPending)}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {move || standings.get().into_iter().map(|entry| view! {
+                                <tr>
+                                    <td>{entry.rank}</td>
+                                    <td>{entry.name}</td>
+                                    <td style="color: #f0d9b5; font-weight: bold;">
+                                        {entry.elo.map_or_else(|| "–".to_string(), |elo| format!("{elo:+}"))}
+                                    </td>
+                                    <td>{entry.wins}</td>
+                                    <td>{entry.losses}</td>
+                                    <td>{entry.draws}</td>
+                                    <td>{entry.pending}</td>
+                                </tr>
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                </div>
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,94 @@
+use crate::app::Difficulty;
+use crate::i18n::{t, Msg};
+use leptos::{
+    component, view, Callable, Callback, IntoView, ReadSignal, SignalGet, SignalSet, WriteSignal,
+};
+
+/// Depth the bench suite is searched to: deep enough for the per-node cost to dominate,
+/// shallow enough to finish within seconds on a phone.
+pub const BENCHMARK_DEPTH: u8 = 6;
+
+/// Nodes per move the timed levels need to play at the strength they were tuned for.
+const FULL_STRENGTH_NODES: u64 = 2_000_000;
+
+/// The timed levels, quickest first.
+const TIMED_LEVELS: [(Difficulty, Msg); 3] = [
+    (Difficulty::Level3, Msg::Level3),
+    (Difficulty::Level4, Msg::Level4),
+    (Difficulty::Level5, Msg::Level5),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BenchmarkStatus {
+    #[default]
+    Idle,
+    /// Positions searched so far out of the total.
+    Running(usize, usize),
+    /// Nodes per second, `None` if the worker could not run the suite.
+    Done(Option<u64>),
+}
+
+/// The quickest timed level that still searches `FULL_STRENGTH_NODES` per move on a device
+/// searching `nps` nodes per second; slower devices need more time for the same search.
+fn recommended_level(nps: u64) -> Option<(Difficulty, Msg)> {
+    TIMED_LEVELS.into_iter().find(|(level, _)| {
+        level
+            .think_time_ms()
+            .is_some_and(|ms| nps.saturating_mul(ms) / 1000 >= FULL_STRENGTH_NODES)
+    })
+}
+
+/// "Benchmark my device": times the engine on the bench suite and suggests the level that
+/// plays at full strength without making the player wait longer than needed.
+#[component]
+pub fn BenchmarkPanel(
+    benchmark: ReadSignal<BenchmarkStatus>,
+    on_benchmark: Callback<()>,
+    set_difficulty: WriteSignal<Difficulty>,
+) -> impl IntoView {
+    let start = move || {
+        view! {
+            <button class="control-btn" on:click=move |_| on_benchmark.call(())>{t(Msg::Benchmark)}</button>
+        }
+    };
+
+    view! {
+        <div class="review-panel">
+            <div class="log-header">
+                <span>{t(Msg::BenchmarkTitle)}</span>
+            </div>
+            {move || match benchmark.get() {
+                BenchmarkStatus::Idle => start().into_view(),
+                BenchmarkStatus::Running(done, total) => {
+                    let percent = done * 100 / total.max(1);
+                    view! {
+                        <div class="explorer-empty">{t(Msg::BenchmarkRunning)}{format!(" {done}/{total}")}</div>
+                        <div class="explorer-bar">
+                            <div style=format!("width: {percent}%;")></div>
+                        </div>
+                    }.into_view()
+                }
+                BenchmarkStatus::Done(None) => view! {
+                    <div class="explorer-empty">{t(Msg::BenchmarkFailed)}</div>
+                    {start()}
+                }.into_view(),
+                BenchmarkStatus::Done(Some(nps)) => {
+                    let advice = recommended_level(nps).map_or_else(
+                        || view! { <div class="explorer-empty">{t(Msg::BenchmarkSlow)}</div> }.into_view(),
+                        |(level, label)| view! {
+                            <div class="explorer-empty">{t(Msg::BenchmarkFullStrength)}" "{t(label)}</div>
+                            <button class="control-btn" on:click=move |_| set_difficulty.set(level)>
+                                {t(Msg::UseLevel)}
+                            </button>
+                        }.into_view(),
+                    );
+                    view! {
+                        <div class="explorer-empty">{format!("⚡ {} k ", nps / 1000)}{t(Msg::NodesPerSecond)}</div>
+                        {advice}
+                        {start()}
+                    }.into_view()
+                }
+            }}
+        </div>
+    }
+}
@@ -1,101 +1,317 @@
+use crate::app::adaptive::AdaptiveProfile;
 use crate::app::export::{export_config, handle_file_upload};
-use cotuong_core::engine::config::EngineConfig;
+use crate::app::scoreboard::Scoreboard;
+use crate::app::{Difficulty, GameMode, MoveConfirmation};
+use crate::components::theme::Theme;
+use crate::i18n::{piece_name, t, use_language, Language, Msg};
+use cotuong_core::engine::config::{
+    tt_size_for_memory, EngineConfig, EngineProfile, TT_SIZE_MAX_MB, TT_SIZE_MIN_MB,
+};
+use cotuong_core::logic::board::{Color, PieceType};
+use cotuong_core::logic::handicap::HandicapKind;
 use leptos::{
     component, event_target_value, view, IntoView, ReadSignal, SignalGet, SignalSet, WriteSignal,
 };
+use serde::{Deserialize, Serialize};
+
+const PREFS_KEY: &str = "cotuong.prefs";
+/// Key used before `UserPrefs` existed, when only the theme was saved.
+const LEGACY_THEME_KEY: &str = "cotuong.theme";
+/// Bump when a field changes meaning and add a step to `UserPrefs::migrate`.
+const PREFS_VERSION: u32 = 2;
+/// Memory assumed when the browser does not report it (`navigator.deviceMemory` is
+/// Chromium only): a modest phone.
+const UNKNOWN_DEVICE_MEMORY_MB: usize = 2048;
+
+/// Transposition table size for this device, from `navigator.deviceMemory`.
+pub fn device_tt_size_mb() -> usize {
+    let memory_gb = web_sys::window()
+        .and_then(|w| js_sys::Reflect::get(&w.navigator(), &"deviceMemory".into()).ok())
+        .and_then(|v| v.as_f64());
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let memory_mb = memory_gb.map_or(UNKNOWN_DEVICE_MEMORY_MB, |gb| (gb * 1024.0) as usize);
+    tt_size_for_memory(memory_mb)
+}
+
+/// Default engine settings sized for this device, for reviews and the benchmark.
+pub fn analysis_config() -> EngineConfig {
+    EngineConfig {
+        tt_size_mb: device_tt_size_mb(),
+        ..EngineConfig::default()
+    }
+}
+
+/// Everything the client remembers between visits, stored as JSON in `localStorage`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserPrefs {
+    pub version: u32,
+    pub game_mode: GameMode,
+    pub difficulty: Difficulty,
+    pub player_side: Color,
+    pub red_config: EngineConfig,
+    pub black_config: EngineConfig,
+    pub theme: Theme,
+    pub puzzle_best_streak: u32,
+    /// Ids of the tutorial lessons finished at least once.
+    pub completed_lessons: Vec<String>,
+    /// Ids of the training positions solved at least once.
+    pub solved_training: Vec<String>,
+    pub handicap: HandicapKind,
+    pub adaptive: AdaptiveProfile,
+    pub language: Language,
+    pub confirm_moves: MoveConfirmation,
+    /// Blindfold play: pieces hidden on the board.
+    pub blindfold: bool,
+    /// Human vs Human on one device: the board turns to the side to move after each move.
+    pub hotseat: bool,
+    /// Results of finished offline games, per mode and level.
+    pub scores: Scoreboard,
+}
+
+impl Default for UserPrefs {
+    fn default() -> Self {
+        Self {
+            version: PREFS_VERSION,
+            game_mode: GameMode::HumanVsComputer,
+            difficulty: Difficulty::Level1,
+            player_side: Color::Red,
+            red_config: EngineConfig::default(),
+            black_config: EngineConfig::default(),
+            theme: Theme::default(),
+            puzzle_best_streak: 0,
+            completed_lessons: Vec::new(),
+            solved_training: Vec::new(),
+            handicap: HandicapKind::None,
+            adaptive: AdaptiveProfile::default(),
+            language: Language::default(),
+            confirm_moves: MoveConfirmation::default(),
+            blindfold: false,
+            hotseat: false,
+            scores: Scoreboard::default(),
+        }
+    }
+}
+
+impl UserPrefs {
+    /// Reads the saved preferences, upgrading older formats; falls back to the defaults.
+    pub fn load() -> Self {
+        let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) else {
+            return Self::default();
+        };
+        let saved = storage
+            .get_item(PREFS_KEY)
+            .ok()
+            .flatten()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok());
+
+        match saved {
+            Some(value) => Self::migrate(value),
+            None => {
+                // First visit since preferences were introduced: keep the old theme
+                let theme = storage
+                    .get_item(LEGACY_THEME_KEY)
+                    .ok()
+                    .flatten()
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+                let _ = storage.remove_item(LEGACY_THEME_KEY);
+                Self {
+                    theme,
+                    language: Language::detect().unwrap_or_default(),
+                    ..Self::default()
+                }
+                .with_tt_size(device_tt_size_mb())
+            }
+        }
+    }
+
+    /// Upgrades a stored JSON document from any earlier `version` to the current one.
+    fn migrate(value: serde_json::Value) -> Self {
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0);
+        if version > u64::from(PREFS_VERSION) {
+            // Written by a newer client; ignore rather than misread it
+            return Self::default();
+        }
+
+        // Version 1 is the first format; missing fields fall back to their defaults
+        let mut prefs: Self = serde_json::from_value(value).unwrap_or_default();
+        // Version 2 sizes the hash table for the device; before, it was never chosen
+        let tt_size = if version < 2 {
+            device_tt_size_mb()
+        } else {
+            prefs.red_config.tt_size_mb
+        };
+        prefs.version = PREFS_VERSION;
+        prefs.with_tt_size(tt_size)
+    }
+
+    /// Gives both engines a `size_mb` table, kept within what a browser worker can hold.
+    fn with_tt_size(mut self, size_mb: usize) -> Self {
+        let size_mb = size_mb.clamp(TT_SIZE_MIN_MB, TT_SIZE_MAX_MB);
+        self.red_config.tt_size_mb = size_mb;
+        self.black_config.tt_size_mb = size_mb;
+        self
+    }
+
+    pub fn save(&self) {
+        let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_string(self) {
+            let _ = storage.set_item(PREFS_KEY, &json);
+        }
+    }
+}
 
 #[component]
 pub fn ConfigPanel(
-    show_config: ReadSignal<bool>,
     red_config: ReadSignal<EngineConfig>,
     set_red_config: WriteSignal<EngineConfig>,
     black_config: ReadSignal<EngineConfig>,
     set_black_config: WriteSignal<EngineConfig>,
 ) -> impl IntoView {
+    let language = use_language();
+
     view! {
-        <div style=move || if show_config.get() { "display: block;" } else { "display: none;" }>
-            <div class="config-panel">
-                <div class="config-column">
-                    <div class="config-title" style="color: #ff6b6b;">"Cấu hình Đỏ (Red)"</div>
-                    <div style="margin-bottom: 15px; text-align: center;">
-                        <label style="display: block; margin-bottom: 5px; color: #ccc; font-size: 0.9em;">"Load JSON Config"</label>
-                        <input type="file" accept=".json" on:change=handle_file_upload(set_red_config) style="color: #ccc;" />
-                        <button style="margin-top: 5px; font-size: 0.8em;" on:click=move |_| export_config(&red_config.get(), "red_config.json")>"Export JSON"</button>
-                    </div>
-                    {
-                        move || {
-                            let config = red_config.get();
-                            view! {
-                                <div>
-                                    <Slider label="Tốt (Pawn)" val=config.val_pawn min=0 max=200 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_pawn = v; set_red_config.set(c); } />
-                                    <Slider label="Sĩ (Advisor)" val=config.val_advisor min=0 max=400 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_advisor = v; set_red_config.set(c); } />
-                                    <Slider label="Tượng (Elephant)" val=config.val_elephant min=0 max=400 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_elephant = v; set_red_config.set(c); } />
-                                    <Slider label="Mã (Horse)" val=config.val_horse min=0 max=800 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_horse = v; set_red_config.set(c); } />
-                                    <Slider label="Pháo (Cannon)" val=config.val_cannon min=0 max=900 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_cannon = v; set_red_config.set(c); } />
-                                    <Slider label="Xe (Rook)" val=config.val_rook min=0 max=1800 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_rook = v; set_red_config.set(c); } />
-                                    <Slider label="Tướng (King)" val=config.val_king min=5000 max=20000 step=100 on_input=move |v| { let mut c = red_config.get(); c.val_king = v; set_red_config.set(c); } />
-                                    <hr style="border-color: #444; margin: 10px 0;"/>
-                                    <Slider label="Hash Move" val=config.score_hash_move min=0 max=5_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_hash_move = v; set_red_config.set(c); } />
-                                    <Slider label="Capture Base" val=config.score_capture_base min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_capture_base = v; set_red_config.set(c); } />
-                                    <Slider label="Killer Move" val=config.score_killer_move min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_killer_move = v; set_red_config.set(c); } />
-                                    <Slider label="History Max" val=config.score_history_max min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_history_max = v; set_red_config.set(c); } />
-                                    <Dropdown label="Pruning Method" val=config.pruning_method options=vec![
-                                        (0, "Dynamic Limiting"),
-                                        (1, "Late Move Reductions (LMR)"),
-                                        (2, "Both (Aggressive)"),
-                                    ] on_set=move |v| { let mut c = red_config.get(); c.pruning_method = v; set_red_config.set(c); } />
-                                    <FloatSlider label="Multiplier" val=config.pruning_multiplier min=0.1 max=2.0 step=0.1 on_input=move |v| { let mut c = red_config.get(); c.pruning_multiplier = v; set_red_config.set(c); } />
-                                    <hr style="border-color: #444; margin: 10px 0;"/>
-                                    <Slider label="Mate Score" val=config.mate_score min=10000 max=50000 step=1000 on_input=move |v| { let mut c = red_config.get(); c.mate_score = v; set_red_config.set(c); } />
-
-                                </div>
-                            }
+        <div class="config-panel">
+            <div class="config-column">
+                <div class="config-title" style="color: #ff6b6b;">{t(Msg::RedConfig)}</div>
+                <div style="margin-bottom: 15px; text-align: center;">
+                    <label style="display: block; margin-bottom: 5px; color: #ccc; font-size: 0.9em;">{t(Msg::ConfigLoadJson)}</label>
+                    <input type="file" accept=".json" on:change=handle_file_upload(set_red_config) style="color: #ccc;" />
+                    <button style="margin-top: 5px; font-size: 0.8em;" on:click=move |_| export_config(&red_config.get(), "red_config.json")>{t(Msg::ConfigExportJson)}</button>
+                </div>
+                {
+                    move || {
+                        let config = red_config.get();
+                        let lang = language.get();
+                        let piece = |piece_type| piece_name(piece_type, lang);
+                        view! {
+                            <div>
+                                <Dropdown label=Msg::ConfigStyle.text(lang) val=-1 options=profile_options(lang) on_set=move |v| { if let Some(p) = usize::try_from(v).ok().and_then(|i| EngineProfile::ALL.get(i)) { let mut c = p.config(); c.tt_size_mb = red_config.get().tt_size_mb; set_red_config.set(c); } } />
+                                <Slider label=piece(PieceType::Soldier) val=config.val_pawn min=0 max=200 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_pawn = v; set_red_config.set(c); } />
+                                <Slider label=piece(PieceType::Advisor) val=config.val_advisor min=0 max=400 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_advisor = v; set_red_config.set(c); } />
+                                <Slider label=piece(PieceType::Elephant) val=config.val_elephant min=0 max=400 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_elephant = v; set_red_config.set(c); } />
+                                <Slider label=piece(PieceType::Horse) val=config.val_horse min=0 max=800 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_horse = v; set_red_config.set(c); } />
+                                <Slider label=piece(PieceType::Cannon) val=config.val_cannon min=0 max=900 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_cannon = v; set_red_config.set(c); } />
+                                <Slider label=piece(PieceType::Chariot) val=config.val_rook min=0 max=1800 step=1 on_input=move |v| { let mut c = red_config.get(); c.val_rook = v; set_red_config.set(c); } />
+                                <Slider label=piece(PieceType::General) val=config.val_king min=5000 max=20000 step=100 on_input=move |v| { let mut c = red_config.get(); c.val_king = v; set_red_config.set(c); } />
+                                <hr style="border-color: #444; margin: 10px 0;"/>
+                                <Slider label=Msg::ConfigHashMove.text(lang) val=config.score_hash_move min=0 max=5_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_hash_move = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigCaptureBase.text(lang) val=config.score_capture_base min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_capture_base = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigKillerMove.text(lang) val=config.score_killer_move min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_killer_move = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigHistoryMax.text(lang) val=config.score_history_max min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = red_config.get(); c.score_history_max = v; set_red_config.set(c); } />
+                                <Dropdown label=Msg::ConfigPruningMethod.text(lang) val=config.pruning_method options=vec![
+                                    (0, Msg::PruneDynamic.text(lang)),
+                                    (1, Msg::PruneLmr.text(lang)),
+                                    (2, Msg::PruneBoth.text(lang)),
+                                ] on_set=move |v| { let mut c = red_config.get(); c.pruning_method = v; set_red_config.set(c); } />
+                                <FloatSlider label=Msg::ConfigMultiplier.text(lang) val=config.pruning_multiplier min=0.1 max=2.0 step=0.1 on_input=move |v| { let mut c = red_config.get(); c.pruning_multiplier = v; set_red_config.set(c); } />
+                                <hr style="border-color: #444; margin: 10px 0;"/>
+                                <Slider label=Msg::ConfigMateScore.text(lang) val=config.mate_score min=10000 max=50000 step=1000 on_input=move |v| { let mut c = red_config.get(); c.mate_score = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigKingSafety.text(lang) val=config.king_safety_weight min=0 max=300 step=10 on_input=move |v| { let mut c = red_config.get(); c.king_safety_weight = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigContempt.text(lang) val=config.contempt min=-100 max=100 step=5 on_input=move |v| { let mut c = red_config.get(); c.contempt = v; set_red_config.set(c); } />
+                                <Dropdown label=Msg::ConfigTtSize.text(lang) val=i32::try_from(config.tt_size_mb).unwrap_or(i32::MAX) options=tt_size_options() on_set=move |v| { let mut c = red_config.get(); c.tt_size_mb = usize::try_from(v).unwrap_or(TT_SIZE_MIN_MB); set_red_config.set(c); } />
+                                <hr style="border-color: #444; margin: 10px 0;"/>
+                                <Slider label=Msg::ConfigResignScore.text(lang) val=config.resign_score min=300 max=3000 step=100 on_input=move |v| { let mut c = red_config.get(); c.resign_score = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigResignMoves.text(lang) val=i32::try_from(config.resign_moves).unwrap_or(i32::MAX) min=0 max=20 step=1 on_input=move |v| { let mut c = red_config.get(); c.resign_moves = u32::try_from(v).unwrap_or(0); set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigDrawScore.text(lang) val=config.draw_score min=0 max=100 step=5 on_input=move |v| { let mut c = red_config.get(); c.draw_score = v; set_red_config.set(c); } />
+                                <Slider label=Msg::ConfigDrawMoves.text(lang) val=i32::try_from(config.draw_moves).unwrap_or(i32::MAX) min=0 max=100 step=5 on_input=move |v| { let mut c = red_config.get(); c.draw_moves = u32::try_from(v).unwrap_or(0); set_red_config.set(c); } />
+
+                            </div>
                         }
                     }
+                }
+            </div>
+            <div class="config-column">
+                <div class="config-title" style="color: #a8e6cf;">{t(Msg::BlackConfig)}</div>
+                <div style="margin-bottom: 15px; text-align: center;">
+                    <label style="display: block; margin-bottom: 5px; color: #ccc; font-size: 0.9em;">{t(Msg::ConfigLoadJson)}</label>
+                    <input type="file" accept=".json" on:change=handle_file_upload(set_black_config) style="color: #ccc;" />
+                    <button style="margin-top: 5px; font-size: 0.8em;" on:click=move |_| export_config(&black_config.get(), "black_config.json")>{t(Msg::ConfigExportJson)}</button>
                 </div>
-                <div class="config-column">
-                    <div class="config-title" style="color: #a8e6cf;">"Cấu hình Đen (Black)"</div>
-                    <div style="margin-bottom: 15px; text-align: center;">
-                        <label style="display: block; margin-bottom: 5px; color: #ccc; font-size: 0.9em;">"Load JSON Config"</label>
-                        <input type="file" accept=".json" on:change=handle_file_upload(set_black_config) style="color: #ccc;" />
-                        <button style="margin-top: 5px; font-size: 0.8em;" on:click=move |_| export_config(&black_config.get(), "black_config.json")>"Export JSON"</button>
-                    </div>
-                    {
-                        move || {
-                            let config = black_config.get();
-                            view! {
-                                <div>
-                                    <Slider label="Tốt (Pawn)" val=config.val_pawn min=0 max=200 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_pawn = v; set_black_config.set(c); } />
-                                    <Slider label="Sĩ (Advisor)" val=config.val_advisor min=0 max=400 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_advisor = v; set_black_config.set(c); } />
-                                    <Slider label="Tượng (Elephant)" val=config.val_elephant min=0 max=400 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_elephant = v; set_black_config.set(c); } />
-                                    <Slider label="Mã (Horse)" val=config.val_horse min=0 max=800 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_horse = v; set_black_config.set(c); } />
-                                    <Slider label="Pháo (Cannon)" val=config.val_cannon min=0 max=900 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_cannon = v; set_black_config.set(c); } />
-                                    <Slider label="Xe (Rook)" val=config.val_rook min=0 max=1800 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_rook = v; set_black_config.set(c); } />
-                                    <Slider label="Tướng (King)" val=config.val_king min=5000 max=20000 step=100 on_input=move |v| { let mut c = black_config.get(); c.val_king = v; set_black_config.set(c); } />
-                                    <hr style="border-color: #444; margin: 10px 0;"/>
-                                    <Slider label="Hash Move" val=config.score_hash_move min=0 max=5_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_hash_move = v; set_black_config.set(c); } />
-                                    <Slider label="Capture Base" val=config.score_capture_base min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_capture_base = v; set_black_config.set(c); } />
-                                    <Slider label="Killer Move" val=config.score_killer_move min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_killer_move = v; set_black_config.set(c); } />
-                                    <Slider label="History Max" val=config.score_history_max min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_history_max = v; set_black_config.set(c); } />
-                                    <Dropdown label="Pruning Method" val=config.pruning_method options=vec![
-                                        (0, "Dynamic Limiting"),
-                                        (1, "Late Move Reductions (LMR)"),
-                                        (2, "Both (Aggressive)"),
-                                    ] on_set=move |v| { let mut c = black_config.get(); c.pruning_method = v; set_black_config.set(c); } />
-                                    <FloatSlider label="Multiplier" val=config.pruning_multiplier min=0.1 max=2.0 step=0.1 on_input=move |v| { let mut c = black_config.get(); c.pruning_multiplier = v; set_black_config.set(c); } />
-                                    <hr style="border-color: #444; margin: 10px 0;"/>
-                                    <Slider label="Mate Score" val=config.mate_score min=10000 max=50000 step=1000 on_input=move |v| { let mut c = black_config.get(); c.mate_score = v; set_black_config.set(c); } />
-
-                                </div>
-                            }
+                {
+                    move || {
+                        let config = black_config.get();
+                        let lang = language.get();
+                        let piece = |piece_type| piece_name(piece_type, lang);
+                        view! {
+                            <div>
+                                <Dropdown label=Msg::ConfigStyle.text(lang) val=-1 options=profile_options(lang) on_set=move |v| { if let Some(p) = usize::try_from(v).ok().and_then(|i| EngineProfile::ALL.get(i)) { let mut c = p.config(); c.tt_size_mb = black_config.get().tt_size_mb; set_black_config.set(c); } } />
+                                <Slider label=piece(PieceType::Soldier) val=config.val_pawn min=0 max=200 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_pawn = v; set_black_config.set(c); } />
+                                <Slider label=piece(PieceType::Advisor) val=config.val_advisor min=0 max=400 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_advisor = v; set_black_config.set(c); } />
+                                <Slider label=piece(PieceType::Elephant) val=config.val_elephant min=0 max=400 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_elephant = v; set_black_config.set(c); } />
+                                <Slider label=piece(PieceType::Horse) val=config.val_horse min=0 max=800 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_horse = v; set_black_config.set(c); } />
+                                <Slider label=piece(PieceType::Cannon) val=config.val_cannon min=0 max=900 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_cannon = v; set_black_config.set(c); } />
+                                <Slider label=piece(PieceType::Chariot) val=config.val_rook min=0 max=1800 step=1 on_input=move |v| { let mut c = black_config.get(); c.val_rook = v; set_black_config.set(c); } />
+                                <Slider label=piece(PieceType::General) val=config.val_king min=5000 max=20000 step=100 on_input=move |v| { let mut c = black_config.get(); c.val_king = v; set_black_config.set(c); } />
+                                <hr style="border-color: #444; margin: 10px 0;"/>
+                                <Slider label=Msg::ConfigHashMove.text(lang) val=config.score_hash_move min=0 max=5_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_hash_move = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigCaptureBase.text(lang) val=config.score_capture_base min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_capture_base = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigKillerMove.text(lang) val=config.score_killer_move min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_killer_move = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigHistoryMax.text(lang) val=config.score_history_max min=0 max=2_000_000 step=100_000 on_input=move |v| { let mut c = black_config.get(); c.score_history_max = v; set_black_config.set(c); } />
+                                <Dropdown label=Msg::ConfigPruningMethod.text(lang) val=config.pruning_method options=vec![
+                                    (0, Msg::PruneDynamic.text(lang)),
+                                    (1, Msg::PruneLmr.text(lang)),
+                                    (2, Msg::PruneBoth.text(lang)),
+                                ] on_set=move |v| { let mut c = black_config.get(); c.pruning_method = v; set_black_config.set(c); } />
+                                <FloatSlider label=Msg::ConfigMultiplier.text(lang) val=config.pruning_multiplier min=0.1 max=2.0 step=0.1 on_input=move |v| { let mut c = black_config.get(); c.pruning_multiplier = v; set_black_config.set(c); } />
+                                <hr style="border-color: #444; margin: 10px 0;"/>
+                                <Slider label=Msg::ConfigMateScore.text(lang) val=config.mate_score min=10000 max=50000 step=1000 on_input=move |v| { let mut c = black_config.get(); c.mate_score = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigKingSafety.text(lang) val=config.king_safety_weight min=0 max=300 step=10 on_input=move |v| { let mut c = black_config.get(); c.king_safety_weight = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigContempt.text(lang) val=config.contempt min=-100 max=100 step=5 on_input=move |v| { let mut c = black_config.get(); c.contempt = v; set_black_config.set(c); } />
+                                <Dropdown label=Msg::ConfigTtSize.text(lang) val=i32::try_from(config.tt_size_mb).unwrap_or(i32::MAX) options=tt_size_options() on_set=move |v| { let mut c = black_config.get(); c.tt_size_mb = usize::try_from(v).unwrap_or(TT_SIZE_MIN_MB); set_black_config.set(c); } />
+                                <hr style="border-color: #444; margin: 10px 0;"/>
+                                <Slider label=Msg::ConfigResignScore.text(lang) val=config.resign_score min=300 max=3000 step=100 on_input=move |v| { let mut c = black_config.get(); c.resign_score = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigResignMoves.text(lang) val=i32::try_from(config.resign_moves).unwrap_or(i32::MAX) min=0 max=20 step=1 on_input=move |v| { let mut c = black_config.get(); c.resign_moves = u32::try_from(v).unwrap_or(0); set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigDrawScore.text(lang) val=config.draw_score min=0 max=100 step=5 on_input=move |v| { let mut c = black_config.get(); c.draw_score = v; set_black_config.set(c); } />
+                                <Slider label=Msg::ConfigDrawMoves.text(lang) val=i32::try_from(config.draw_moves).unwrap_or(i32::MAX) min=0 max=100 step=5 on_input=move |v| { let mut c = black_config.get(); c.draw_moves = u32::try_from(v).unwrap_or(0); set_black_config.set(c); } />
+
+                            </div>
                         }
                     }
-                </div>
+                }
             </div>
         </div>
     }
 }
 
+pub const fn profile_label(profile: EngineProfile) -> Msg {
+    match profile {
+        EngineProfile::Balanced => Msg::ProfileBalanced,
+        EngineProfile::Aggressive => Msg::ProfileAggressive,
+        EngineProfile::Positional => Msg::ProfilePositional,
+        EngineProfile::Materialist => Msg::ProfileMaterialist,
+        EngineProfile::Gambit => Msg::ProfileGambit,
+    }
+}
+
+/// Options for the style picker; the leading entry keeps the picker blank after edits.
+fn profile_options(language: Language) -> Vec<(i32, &'static str)> {
+    std::iter::once((-1, Msg::ConfigPick.text(language)))
+        .chain(
+            (0..)
+                .zip(EngineProfile::ALL)
+                .map(|(i, profile)| (i, profile_label(profile).text(language))),
+        )
+        .collect()
+}
+
+/// Table sizes from `TT_SIZE_MIN_MB` to `TT_SIZE_MAX_MB`; the table only uses powers of two.
+fn tt_size_options() -> Vec<(i32, &'static str)> {
+    vec![
+        (16, "16 MB"),
+        (32, "32 MB"),
+        (64, "64 MB"),
+        (128, "128 MB"),
+        (256, "256 MB"),
+    ]
+}
+
 #[component]
 fn Slider<F>(
     label: &'static str,
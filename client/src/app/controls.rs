@@ -1,12 +1,49 @@
 #![allow(clippy::too_many_lines)]
-use crate::app::{Difficulty, GameMode};
+use crate::app::adaptive::AdaptiveProfile;
+use crate::app::export::{DEFAULT_GIF_FRAME_MS, GIF_FRAME_DELAYS_MS};
+use crate::app::{Difficulty, GameMode, MoveConfirmation};
+use crate::audio::SoundSettings;
+use crate::components::board::BoardOverlay;
+use crate::components::theme::{BoardStyle, PieceStyle, Theme};
+use crate::i18n::{
+    adaptive_label, mate_found_label, mate_not_found_label, t, use_language, Language, Msg,
+};
 use cotuong_core::logic::board::Color;
 use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::handicap::HandicapKind;
 use leptos::{
-    component, event_target_value, view, Callable, Callback, IntoView, ReadSignal, SignalGet,
-    SignalSet, WriteSignal,
+    component, create_signal, event_target_checked, event_target_value, view, Callable, Callback,
+    IntoView, ReadSignal, SignalGet, SignalSet, SignalUpdate, WriteSignal,
 };
 
+pub const fn handicap_label(handicap: HandicapKind) -> Msg {
+    match handicap {
+        HandicapKind::None => Msg::HandicapNone,
+        HandicapKind::Horse => Msg::HandicapHorse,
+        HandicapKind::TwoHorses => Msg::HandicapTwoHorses,
+        HandicapKind::Chariot => Msg::HandicapChariot,
+        HandicapKind::TwoMoves => Msg::HandicapTwoMoves,
+    }
+}
+
+/// Where the mate search for the shown position stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MateSearch {
+    Running,
+    /// Moves to mate for the side to move.
+    Found(usize),
+    /// No mate within this many moves.
+    NotFound(u8),
+}
+
+const fn confirmation_label(mode: MoveConfirmation) -> Msg {
+    match mode {
+        MoveConfirmation::Off => Msg::ConfirmOff,
+        MoveConfirmation::Important => Msg::ConfirmImportant,
+        MoveConfirmation::Always => Msg::ConfirmAlways,
+    }
+}
+
 #[component]
 #[allow(clippy::too_many_arguments)]
 pub fn ControlsArea(
@@ -14,21 +51,54 @@ pub fn ControlsArea(
     set_game_mode: WriteSignal<GameMode>,
     player_side: ReadSignal<Color>,
     set_player_side: WriteSignal<Color>,
+    set_flipped: WriteSignal<bool>,
+    blindfold: ReadSignal<bool>,
+    set_blindfold: WriteSignal<bool>,
+    hotseat: ReadSignal<bool>,
+    set_hotseat: WriteSignal<bool>,
+    overlay: ReadSignal<BoardOverlay>,
+    set_overlay: WriteSignal<BoardOverlay>,
     difficulty: ReadSignal<Difficulty>,
     set_difficulty: WriteSignal<Difficulty>,
+    adaptive: ReadSignal<AdaptiveProfile>,
+    handicap: ReadSignal<HandicapKind>,
+    set_handicap: WriteSignal<HandicapKind>,
+    language: ReadSignal<Language>,
     is_paused: ReadSignal<bool>,
     set_is_paused: WriteSignal<bool>,
     game_state: ReadSignal<GameState>,
     set_game_state: WriteSignal<GameState>,
     is_thinking: ReadSignal<bool>,
     set_is_thinking: WriteSignal<bool>,
+    on_find_mate: Callback<()>,
+    mate_result: ReadSignal<Option<MateSearch>>,
     on_export_csv: Callback<()>,
+    on_export_json: Callback<()>,
+    on_export_pgn: Callback<()>,
+    on_export_png: Callback<()>,
+    /// Called with the frame delay in milliseconds.
+    on_export_gif: Callback<u32>,
+    /// Frames encoded so far and in total while a GIF is being made.
+    gif_progress: ReadSignal<Option<(usize, usize)>>,
 ) -> impl IntoView {
+    let lang = use_language();
+    let text = move |msg: Msg| msg.text(lang.get());
+    let (gif_frame_ms, set_gif_frame_ms) = create_signal(DEFAULT_GIF_FRAME_MS);
+    let toggle_overlay = move |layer: BoardOverlay| {
+        set_overlay.update(|current| {
+            *current = if *current == layer {
+                BoardOverlay::None
+            } else {
+                layer
+            };
+        });
+    };
+
     view! {
         <div class="controls-area">
             <div class="controls-config">
                 <div class="control-group">
-                    <span class="control-label">"Chế độ"</span>
+                    <span class="control-label">{t(Msg::Mode)}</span>
                     <select
                         on:change=move |ev| {
                             let val = event_target_value(&ev);
@@ -49,6 +119,18 @@ pub fn ControlsArea(
                                     set_game_mode.set(GameMode::Online);
                                     set_is_paused.set(false);
                                 },
+                                "Puzzle" => {
+                                    set_game_mode.set(GameMode::Puzzle);
+                                    set_is_paused.set(false);
+                                },
+                                "Tutorial" => {
+                                    set_game_mode.set(GameMode::Tutorial);
+                                    set_is_paused.set(false);
+                                },
+                                "Training" => {
+                                    set_game_mode.set(GameMode::Training);
+                                    set_is_paused.set(false);
+                                },
                                 _ => {},
                             }
                         }
@@ -57,17 +139,23 @@ pub fn ControlsArea(
                             GameMode::ComputerVsComputer => "ComputerVsComputer",
                             GameMode::HumanVsHuman => "HumanVsHuman",
                             GameMode::Online => "Online",
+                            GameMode::Puzzle => "Puzzle",
+                            GameMode::Tutorial => "Tutorial",
+                            GameMode::Training => "Training",
                         }
                     >
-                        <option value="HumanVsComputer">"Người vs Máy"</option>
-                        <option value="ComputerVsComputer">"Máy vs Máy"</option>
-                        <option value="HumanVsHuman">"Người vs Người"</option>
-                        <option value="Online">"🌐 Chơi Online"</option>
+                        <option value="HumanVsComputer">{t(Msg::ModeHumanVsComputer)}</option>
+                        <option value="ComputerVsComputer">{t(Msg::ModeComputerVsComputer)}</option>
+                        <option value="HumanVsHuman">{t(Msg::ModeHumanVsHuman)}</option>
+                        <option value="Online">{t(Msg::ModeOnline)}</option>
+                        <option value="Puzzle">{t(Msg::ModePuzzle)}</option>
+                        <option value="Tutorial">{t(Msg::ModeTutorial)}</option>
+                        <option value="Training">{t(Msg::ModeTraining)}</option>
                     </select>
                 </div>
 
                 <div class="control-group">
-                    <span class="control-label">"Chọn bên"</span>
+                    <span class="control-label">{t(Msg::Side)}</span>
                     <select
                         on:change=move |ev| {
                             let val = event_target_value(&ev);
@@ -82,13 +170,13 @@ pub fn ControlsArea(
                             Color::Black => "Black",
                         }
                     >
-                        <option value="Red">"Đỏ (Đi trước)"</option>
-                        <option value="Black">"Đen (Đi sau)"</option>
+                        <option value="Red">{t(Msg::SideRedFirst)}</option>
+                        <option value="Black">{t(Msg::SideBlackSecond)}</option>
                     </select>
                 </div>
 
                 <div class="control-group">
-                    <span class="control-label">"Độ khó"</span>
+                    <span class="control-label">{t(Msg::Difficulty)}</span>
                     <select
                         on:change=move |ev| {
                             let val = event_target_value(&ev);
@@ -98,6 +186,7 @@ pub fn ControlsArea(
                                 "Level3" => set_difficulty.set(Difficulty::Level3),
                                 "Level4" => set_difficulty.set(Difficulty::Level4),
                                 "Level5" => set_difficulty.set(Difficulty::Level5),
+                                "Adaptive" => set_difficulty.set(Difficulty::Adaptive),
                                 _ => {},
                             }
                         }
@@ -107,13 +196,65 @@ pub fn ControlsArea(
                             Difficulty::Level3 => "Level3",
                             Difficulty::Level4 => "Level4",
                             Difficulty::Level5 => "Level5",
+                            Difficulty::Adaptive => "Adaptive",
+                        }
+                    >
+                        <option value="Level1">{t(Msg::Level1)}</option>
+                        <option value="Level2">{t(Msg::Level2)}</option>
+                        <option value="Level3">{t(Msg::Level3)}</option>
+                        <option value="Level4">{t(Msg::Level4)}</option>
+                        <option value="Level5">{t(Msg::Level5)}</option>
+                        <option value="Adaptive">
+                            {move || {
+                                let profile = adaptive.get();
+                                adaptive_label(
+                                    language.get(),
+                                    profile.skill_percent(),
+                                    profile.wins,
+                                    profile.draws,
+                                    profile.losses,
+                                )
+                            }}
+                        </option>
+                    </select>
+                </div>
+
+                // Online handicaps are picked in the lobby; puzzles, lessons and training have their own positions
+                {move || (!matches!(game_mode.get(), GameMode::Online | GameMode::Puzzle | GameMode::Tutorial | GameMode::Training)).then(|| view! {
+                    <div class="control-group">
+                        <span class="control-label">{t(Msg::Handicap)}</span>
+                        <select
+                            title=t(Msg::HandicapTitle)
+                            on:change=move |ev| {
+                                let val = event_target_value(&ev);
+                                if let Some(kind) = HandicapKind::ALL.into_iter().find(|h| format!("{h:?}") == val) {
+                                    set_handicap.set(kind);
+                                    set_game_state.set(kind.initial_state());
+                                    set_is_thinking.set(false);
+                                }
+                            }
+                            prop:value=move || format!("{:?}", handicap.get())
+                        >
+                            {HandicapKind::ALL.into_iter().map(|kind| view! {
+                                <option value=format!("{kind:?}")>{t(handicap_label(kind))}</option>
+                            }).collect::<Vec<_>>()}
+                        </select>
+                    </div>
+                })}
+
+                <div class="control-group">
+                    <span class="control-label">{t(Msg::GifSpeed)}</span>
+                    <select
+                        on:change=move |ev| {
+                            if let Ok(ms) = event_target_value(&ev).parse() {
+                                set_gif_frame_ms.set(ms);
+                            }
                         }
+                        prop:value=move || gif_frame_ms.get().to_string()
                     >
-                        <option value="Level1">"Mức 1 (1s)"</option>
-                        <option value="Level2">"Mức 2 (2s)"</option>
-                        <option value="Level3">"Mức 3 (5s)"</option>
-                        <option value="Level4">"Mức 4 (10s)"</option>
-                        <option value="Level5">"Mức 5 (20s)"</option>
+                        {GIF_FRAME_DELAYS_MS.into_iter().map(|ms| view! {
+                            <option value=ms.to_string()>{format!("{:.1} s", f64::from(ms) / 1000.0)}</option>
+                        }).collect::<Vec<_>>()}
                     </select>
                 </div>
             </div>
@@ -122,9 +263,9 @@ pub fn ControlsArea(
                 {move || {
                     if game_mode.get() == GameMode::ComputerVsComputer {
                         if is_paused.get() {
-                            view! { <button class="control-btn btn-primary" on:click=move |_| set_is_paused.set(false)>"▶ Bắt đầu"</button> }.into_view()
+                            view! { <button class="control-btn btn-primary" on:click=move |_| set_is_paused.set(false)>{t(Msg::Start)}</button> }.into_view()
                         } else {
-                            view! { <button class="control-btn btn-danger" on:click=move |_| set_is_paused.set(true)>"⏸ Tạm dừng"</button> }.into_view()
+                            view! { <button class="control-btn btn-danger" on:click=move |_| set_is_paused.set(true)>{t(Msg::Pause)}</button> }.into_view()
                         }
                     } else {
                         view! {}.into_view()
@@ -132,12 +273,12 @@ pub fn ControlsArea(
                 }}
 
                 <button class="control-btn btn-info" on:click=move |_| {
-                    set_game_state.set(GameState::new());
+                    set_game_state.set(handicap.get().initial_state());
                     set_is_thinking.set(false);
                     if game_mode.get() == GameMode::ComputerVsComputer {
                         set_is_paused.set(true);
                     }
-                }>"Chơi mới"</button>
+                }>{t(Msg::NewGame)}</button>
 
                 <button class="control-btn btn-warning" on:click=move |_| {
                     if is_thinking.get() {
@@ -146,15 +287,211 @@ pub fn ControlsArea(
                     let mut state = game_state.get();
                     let mode = game_mode.get();
 
-                    if mode == GameMode::HumanVsComputer && state.turn == Color::Red && state.history.len() >= 2 {
+                    if matches!(mode, GameMode::HumanVsComputer | GameMode::Training) && state.turn == Color::Red && state.history.len() >= 2 {
                         state.undo_move();
                     }
                     state.undo_move();
                     set_game_state.set(state);
-                }>"Đi lại"</button>
+                }>{t(Msg::Undo)}</button>
+
+                <button class="control-btn" on:click=move |_| set_flipped.update(|f| *f = !*f)>{t(Msg::FlipBoard)}</button>
+                <button
+                    class="control-btn"
+                    title=t(Msg::BlindfoldTitle)
+                    on:click=move |_| set_blindfold.update(|b| *b = !*b)
+                >
+                    {move || text(if blindfold.get() { Msg::BlindfoldOff } else { Msg::Blindfold })}
+                </button>
+                {move || (game_mode.get() == GameMode::HumanVsHuman).then(|| view! {
+                    <button
+                        class="control-btn"
+                        title=t(Msg::HotseatTitle)
+                        on:click=move |_| set_hotseat.update(|h| *h = !*h)
+                    >
+                        {move || text(if hotseat.get() { Msg::HotseatOff } else { Msg::Hotseat })}
+                    </button>
+                })}
+                <button
+                    class="control-btn"
+                    title=t(Msg::ThreatsTitle)
+                    on:click=move |_| toggle_overlay(BoardOverlay::Threats)
+                >
+                    {move || text(if overlay.get() == BoardOverlay::Threats { Msg::HideThreats } else { Msg::ShowThreats })}
+                </button>
+                // Analysis aid, kept out of online games like the mate search
+                {move || (game_mode.get() != GameMode::Online).then(|| view! {
+                    <button
+                        class="control-btn"
+                        title=t(Msg::HeatmapTitle)
+                        on:click=move |_| toggle_overlay(BoardOverlay::Influence)
+                    >
+                        {move || text(if overlay.get() == BoardOverlay::Influence { Msg::HideHeatmap } else { Msg::ShowHeatmap })}
+                    </button>
+                })}
 
-                <button class="control-btn" on:click=move |_| on_export_csv.call(())>"Xuất CSV"</button>
+                // Not offered online, where it would be an engine hint against a human
+                {move || (game_mode.get() != GameMode::Online).then(|| view! {
+                    <button class="control-btn" on:click=move |_| on_find_mate.call(())>{t(Msg::FindMate)}</button>
+                })}
+
+                <button class="control-btn" on:click=move |_| on_export_csv.call(())>{t(Msg::ExportCsv)}</button>
+                <button class="control-btn" on:click=move |_| on_export_json.call(())>{t(Msg::ExportJson)}</button>
+                <button class="control-btn" on:click=move |_| on_export_pgn.call(())>{t(Msg::ExportPgn)}</button>
+                <button class="control-btn" on:click=move |_| on_export_png.call(())>{t(Msg::ExportPng)}</button>
+                <button
+                    class="control-btn"
+                    disabled=move || gif_progress.get().is_some()
+                    on:click=move |_| on_export_gif.call(gif_frame_ms.get())
+                >
+                    {move || gif_progress.get().map_or_else(
+                        || text(Msg::ExportGif).to_string(),
+                        |(done, total)| format!("{} {done}/{total}", text(Msg::GifEncoding)),
+                    )}
+                </button>
             </div>
+
+            {move || mate_result.get().map(|search| {
+                let message = match search {
+                    MateSearch::Running => text(Msg::MateSearching).to_string(),
+                    MateSearch::Found(moves) => mate_found_label(lang.get(), moves),
+                    MateSearch::NotFound(moves) => mate_not_found_label(lang.get(), moves),
+                };
+                view! { <div class="mate-result">{message}</div> }
+            })}
         </div>
     }
 }
+
+/// Preferences that apply to every page: board look, sound, move confirmation and language.
+#[component]
+#[allow(clippy::too_many_arguments)]
+pub fn SettingsPanel(
+    theme: ReadSignal<Theme>,
+    set_theme: WriteSignal<Theme>,
+    sound_settings: ReadSignal<SoundSettings>,
+    set_sound_settings: WriteSignal<SoundSettings>,
+    language: ReadSignal<Language>,
+    set_language: WriteSignal<Language>,
+    confirm_moves: ReadSignal<MoveConfirmation>,
+    set_confirm_moves: WriteSignal<MoveConfirmation>,
+) -> impl IntoView {
+    view! {
+        <div class="controls-area">
+            <div class="controls-config">
+                <div class="control-group">
+                    <span class="control-label">{t(Msg::BoardStyle)}</span>
+                    <select
+                        on:change=move |ev| {
+                            let val = event_target_value(&ev);
+                            if let Some(style) = BoardStyle::ALL.into_iter().find(|s| format!("{s:?}") == val) {
+                                set_theme.update(|t| t.board = style);
+                            }
+                        }
+                        prop:value=move || format!("{:?}", theme.get().board)
+                    >
+                        {BoardStyle::ALL.into_iter().map(|style| view! {
+                            <option value=format!("{style:?}")>{t(style.label())}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="control-group">
+                    <span class="control-label">{t(Msg::PieceStyle)}</span>
+                    <select
+                        on:change=move |ev| {
+                            let val = event_target_value(&ev);
+                            if let Some(style) = PieceStyle::ALL.into_iter().find(|s| format!("{s:?}") == val) {
+                                set_theme.update(|t| t.pieces = style);
+                            }
+                        }
+                        prop:value=move || format!("{:?}", theme.get().pieces)
+                    >
+                        {PieceStyle::ALL.into_iter().map(|style| view! {
+                            <option value=format!("{style:?}")>{t(style.label())}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="control-group">
+                    <span class="control-label">{t(Msg::Sound)}</span>
+                    <div class="sound-settings">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || sound_settings.get().enabled
+                            on:change=move |ev| {
+                                let enabled = event_target_checked(&ev);
+                                set_sound_settings.update(|s| s.enabled = enabled);
+                            }
+                        />
+                        <input
+                            type="range"
+                            min="0"
+                            max="100"
+                            disabled=move || !sound_settings.get().enabled
+                            prop:value=move || (sound_settings.get().volume * 100.0).round().to_string()
+                            on:input=move |ev| {
+                                if let Ok(percent) = event_target_value(&ev).parse::<f64>() {
+                                    set_sound_settings.update(|s| s.volume = (percent / 100.0).clamp(0.0, 1.0));
+                                }
+                            }
+                        />
+                    </div>
+                </div>
+
+                <div class="control-group">
+                    <span class="control-label">{t(Msg::ConfirmMoves)}</span>
+                    <select
+                        on:change=move |ev| {
+                            let val = event_target_value(&ev);
+                            if let Some(mode) = MoveConfirmation::ALL.into_iter().find(|m| format!("{m:?}") == val) {
+                                set_confirm_moves.set(mode);
+                            }
+                        }
+                        prop:value=move || format!("{:?}", confirm_moves.get())
+                    >
+                        {MoveConfirmation::ALL.into_iter().map(|mode| view! {
+                            <option value=format!("{mode:?}")>{t(confirmation_label(mode))}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+
+                <div class="control-group">
+                    <span class="control-label">{t(Msg::LanguageLabel)}</span>
+                    <select
+                        on:change=move |ev| {
+                            let val = event_target_value(&ev);
+                            if let Some(lang) = Language::ALL.into_iter().find(|l| l.code() == val) {
+                                set_language.set(lang);
+                            }
+                        }
+                        prop:value=move || language.get().code()
+                    >
+                        {Language::ALL.into_iter().map(|lang| view! {
+                            <option value=lang.code()>{lang.label()}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Covers the screen between turns of a one-device Human vs Human game, until the player
+/// now to move is holding it and taps ready.
+#[component]
+pub fn HandoffOverlay(
+    handoff: ReadSignal<Option<Color>>,
+    set_handoff: WriteSignal<Option<Color>>,
+) -> impl IntoView {
+    view! {
+        {move || handoff.get().map(|turn| view! {
+            <div class="handoff-overlay" on:click=move |_| set_handoff.set(None)>
+                <div class="handoff-card">
+                    <div>{t(Msg::PassDevice)}</div>
+                    <strong>{t(if turn == Color::Red { Msg::RedToMove } else { Msg::BlackToMove })}</strong>
+                    <button class="control-btn btn-primary">{t(Msg::HandoffReady)}</button>
+                </div>
+            </div>
+        })}
+    }
+}
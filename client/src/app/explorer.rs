@@ -0,0 +1,106 @@
+use crate::app::GameMode;
+use crate::i18n::{book_moves_label, t, use_language, Msg};
+use cotuong_core::engine::Move;
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::{GameState, GameStatus};
+use cotuong_core::logic::opening::book_moves;
+use leptos::{
+    component, view, IntoView, ReadSignal, Signal, SignalGet, SignalGetUntracked, SignalSet,
+    WriteSignal,
+};
+use std::rc::Rc;
+
+/// Book replies for the position on the board, with each one's share of the book weight.
+/// Clicking a reply plays it as the user's move whenever the user could move on the board.
+#[component]
+#[allow(clippy::needless_pass_by_value)]
+pub fn OpeningExplorer(
+    game_state: ReadSignal<GameState>,
+    set_game_state: WriteSignal<GameState>,
+    game_mode: ReadSignal<GameMode>,
+    player_side: ReadSignal<Color>,
+    read_only: Signal<bool>,
+    on_move: Rc<dyn Fn(Move)>,
+) -> impl IntoView {
+    let language = use_language();
+    let can_play = move |state: &GameState| {
+        state.status == GameStatus::Playing
+            && !read_only.get()
+            && match game_mode.get() {
+                GameMode::HumanVsHuman => true,
+                GameMode::HumanVsComputer | GameMode::Online => state.turn == player_side.get(),
+                GameMode::ComputerVsComputer
+                | GameMode::Puzzle
+                | GameMode::Tutorial
+                | GameMode::Training => false,
+            }
+    };
+
+    view! {
+        {move || {
+            if matches!(
+                game_mode.get(),
+                GameMode::Puzzle | GameMode::Tutorial | GameMode::Training
+            ) {
+                return view! {}.into_view();
+            }
+            let lang = language.get();
+            let state = game_state.get();
+            let moves = book_moves(&state.board, state.turn);
+            let total: u32 = moves.iter().map(|m| m.weight).sum();
+            let clickable = can_play(&state);
+            let on_move = on_move.clone();
+
+            view! {
+                <div class="explorer-panel">
+                    <div class="log-header">
+                        <span>{t(Msg::ExplorerTitle)}</span>
+                        <span style="font-size: 0.8em; opacity: 0.8;">{book_moves_label(lang, moves.len())}</span>
+                    </div>
+                    {moves.is_empty().then(|| view! {
+                        <div class="explorer-empty">{t(Msg::ExplorerOutOfBook)}</div>
+                    })}
+                    <ul class="log-list">
+                        {moves.into_iter().map(|book| {
+                            let share = book.weight * 100 / total.max(1);
+                            let on_move = on_move.clone();
+                            let play = move |_| {
+                                if !clickable {
+                                    return;
+                                }
+                                let mut next = game_state.get_untracked();
+                                if next.make_move(book.from, book.to).is_ok() {
+                                    set_game_state.set(next);
+                                    #[allow(clippy::cast_possible_truncation)]
+                                    on_move(Move {
+                                        from_row: book.from.row as u8,
+                                        from_col: book.from.col as u8,
+                                        to_row: book.to.row as u8,
+                                        to_col: book.to.col as u8,
+                                        score: 0,
+                                    });
+                                }
+                            };
+                            view! {
+                                <li class="explorer-item" class:clickable=clickable on:click=play>
+                                    <div class="move-info">
+                                        <span>{book.name}</span>
+                                        <span style="color: #f0d9b5;">{format!("{share}%")}</span>
+                                    </div>
+                                    <div class="explorer-bar">
+                                        <div style=format!("width: {share}%;")></div>
+                                    </div>
+                                    <div class="ai-stats">{format!(
+                                        "({},{}) → ({},{}) · {} {}",
+                                        book.from.row, book.from.col, book.to.row, book.to.col,
+                                        Msg::ExplorerWeight.text(lang), book.weight
+                                    )}</div>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                </div>
+            }.into_view()
+        }}
+    }
+}
@@ -1,8 +1,20 @@
+use crate::components::annotations::{Annotation, Annotations};
+use crate::components::board::{render_frame, render_png};
+use crate::components::theme::Theme;
+use crate::i18n::{Language, Msg};
 use cotuong_core::engine::config::EngineConfig;
-use cotuong_core::logic::game::GameState;
+use cotuong_core::engine::eval::SimpleEvaluator;
+use cotuong_core::engine::review::{positions, review_pgn, GameReview};
+use cotuong_core::engine::Evaluator;
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::{GameState, MoveAnnotation, MoveRecord};
+use cotuong_core::logic::handicap::HandicapKind;
 use leptos::{document, SignalSet, WriteSignal};
+use serde::Serialize;
+use std::sync::Arc;
 use wasm_bindgen::closure::Closure;
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
 
 pub fn handle_file_upload(setter: WriteSignal<EngineConfig>) -> impl Fn(web_sys::Event) {
     move |ev: web_sys::Event| {
@@ -56,27 +68,22 @@ pub fn handle_file_upload(setter: WriteSignal<EngineConfig>) -> impl Fn(web_sys:
 
 pub fn export_config(config: &EngineConfig, filename: &str) {
     if let Ok(json) = serde_json::to_string_pretty(&config) {
-        if let Ok(blob) = web_sys::Blob::new_with_str_sequence(&js_sys::Array::of1(&json.into())) {
-            if let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) {
-                if let Ok(el) = document().create_element("a") {
-                    if let Ok(anchor) = el.dyn_into::<web_sys::HtmlAnchorElement>() {
-                        anchor.set_href(&url);
-                        anchor.set_download(filename);
-                        anchor.click();
-                        let _ = web_sys::Url::revoke_object_url(&url);
-                    }
-                }
-            }
-        }
+        download_text(json, filename);
     }
 }
 
 pub fn export_csv(state: &GameState) {
     use std::fmt::Write;
 
-    let mut csv = String::from("Turn,From,To,Piece,Captured,Note\n");
-    for (i, record) in state.history.iter().enumerate() {
-        let turn = if i % 2 == 0 { "Red" } else { "Black" };
+    let mut csv = String::from(
+        "Turn,From,To,Piece,Captured,Source,Depth,Nodes,TimeMs,Score,Notation,Glyph,Eval,ClockMs\n",
+    );
+    for record in &state.history {
+        let turn = if record.color == Color::Red {
+            "Red"
+        } else {
+            "Black"
+        };
         let from = format!("({},{})", record.from.row, record.from.col);
         let to = format!("({},{})", record.to.row, record.to.col);
         let piece = format!("{:?}", record.piece.piece_type);
@@ -84,20 +91,191 @@ pub fn export_csv(state: &GameState) {
             .captured
             .map(|p| format!("{:?}", p.piece_type))
             .unwrap_or_default();
-        let note = record.note.clone().unwrap_or_default();
-        let _ = writeln!(csv, "{turn},{from},{to},{piece},{captured},{note}");
+        // Engine figures get a column each; book and hand-played moves leave them empty
+        let engine = match &record.annotation {
+            Some(MoveAnnotation::Book) => "book,,,,".to_string(),
+            Some(MoveAnnotation::Engine {
+                depth,
+                nodes,
+                time_ms,
+                explanation,
+            }) => format!("engine,{depth},{nodes},{time_ms},{}", explanation.score),
+            None => ",,,,".to_string(),
+        };
+        let notation = record.notation.clone().unwrap_or_default();
+        let glyph = record.glyph.clone().unwrap_or_default();
+        let eval = record.eval.map(|e| e.to_string()).unwrap_or_default();
+        let clock = record.clock_ms.map(|ms| ms.to_string()).unwrap_or_default();
+        let _ = writeln!(
+            csv,
+            "{turn},{from},{to},{piece},{captured},{engine},{notation},{glyph},{eval},{clock}"
+        );
     }
 
-    if let Ok(blob) = web_sys::Blob::new_with_str_sequence(&js_sys::Array::of1(&csv.into())) {
-        if let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) {
-            if let Ok(el) = document().create_element("a") {
-                if let Ok(anchor) = el.dyn_into::<web_sys::HtmlAnchorElement>() {
-                    anchor.set_href(&url);
-                    anchor.set_download("xiangqi_game.csv");
-                    anchor.click();
-                    let _ = web_sys::Url::revoke_object_url(&url);
-                }
-            }
+    download_text(csv, "xiangqi_game.csv");
+}
+
+/// The game as PGN, with the review's accuracy and mistakes embedded when one was run.
+pub fn export_pgn(state: &GameState, review: Option<&GameReview>, language: Language) {
+    let pgn = review_pgn(
+        state,
+        review,
+        Msg::Red.text(language),
+        Msg::Black.text(language),
+    );
+    download_text(pgn, "xiangqi_game.pgn");
+}
+
+/// Full game record for later review: the moves plus the board annotations per position.
+#[derive(Serialize)]
+struct GameExport<'a> {
+    fen: String,
+    /// Setup the moves start from.
+    handicap: HandicapKind,
+    moves: &'a [MoveRecord],
+    annotations: &'a Annotations,
+}
+
+pub fn export_json(state: &GameState, annotations: &Annotations) {
+    let export = GameExport {
+        fen: state.board.to_fen_string(state.turn),
+        handicap: state.handicap,
+        moves: &state.history,
+        annotations,
+    };
+    if let Ok(json) = serde_json::to_string_pretty(&export) {
+        download_text(json, "xiangqi_game.json");
+    }
+}
+
+/// Caption under an exported board: the review's score after the last move when the game
+/// was reviewed, otherwise a static evaluation. Both are from Red's side.
+fn eval_caption(state: &GameState, review: Option<&GameReview>, language: Language) -> String {
+    let reviewed = state
+        .history
+        .len()
+        .checked_sub(1)
+        .and_then(|ply| review?.moves.iter().find(|m| m.ply == ply))
+        .map(|m| match m.mover {
+            Color::Red => m.score_after,
+            Color::Black => -m.score_after,
+        });
+    let (label, score) = reviewed.map_or_else(
+        || {
+            let evaluator = SimpleEvaluator::new(Arc::new(EngineConfig::default()));
+            (Msg::ImageStaticEval, evaluator.evaluate(&state.board))
+        },
+        |score| (Msg::ImageEval, score),
+    );
+    format!("{}: {:+.2}", label.text(language), f64::from(score) / 100.0)
+}
+
+/// Downloads the shown position as a PNG, from the side it is viewed from.
+pub fn export_png(
+    state: &GameState,
+    review: Option<&GameReview>,
+    theme: Theme,
+    orientation: Color,
+    annotations: &[Annotation],
+    language: Language,
+) {
+    let caption = eval_caption(state, review, language);
+    if let Some(url) = render_png(state, theme, orientation, annotations, &caption) {
+        download_url(&url, "xiangqi_position.png");
+    }
+}
+
+/// Frame delays offered for GIF export, in milliseconds.
+pub const GIF_FRAME_DELAYS_MS: [u32; 3] = [500, 1000, 2000];
+pub const DEFAULT_GIF_FRAME_MS: u32 = 1000;
+/// NeuQuant sampling factor, 1 (best) to 30 (fastest); boards have few colors to keep.
+const GIF_QUANTIZE_SPEED: i32 = 20;
+/// The final position stays up this many frame delays before the animation loops.
+const GIF_LAST_FRAME_HOLD: u16 = 3;
+
+/// Lets the browser paint and handle input between two slices of work.
+async fn next_tick() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let _ = web_sys::window().map(|w| w.set_timeout_with_callback(&resolve));
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Encodes every position of the game as a looping GIF, a frame every `frame_ms`.
+/// `on_progress(done, total)` is called after each frame.
+async fn encode_gif(
+    state: &GameState,
+    theme: Theme,
+    orientation: Color,
+    frame_ms: u32,
+    language: Language,
+    on_progress: impl Fn(usize, usize),
+) -> Option<Vec<u8>> {
+    let positions = positions(state);
+    let total = positions.len();
+    let delay = u16::try_from(frame_ms / 10).unwrap_or(u16::MAX);
+    let mut encoder = None;
+    for (ply, position) in positions.iter().enumerate() {
+        let caption = format!("{} {ply}/{}", Msg::GifMove.text(language), total - 1);
+        let (width, height, mut pixels) = render_frame(position, theme, orientation, &caption)?;
+        let (width, height) = (u16::try_from(width).ok()?, u16::try_from(height).ok()?);
+        if encoder.is_none() {
+            let mut created = gif::Encoder::new(Vec::new(), width, height, &[]).ok()?;
+            created.set_repeat(gif::Repeat::Infinite).ok()?;
+            encoder = Some(created);
+        }
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut pixels, GIF_QUANTIZE_SPEED);
+        frame.delay = if ply + 1 == total {
+            delay.saturating_mul(GIF_LAST_FRAME_HOLD)
+        } else {
+            delay
+        };
+        encoder.as_mut()?.write_frame(&frame).ok()?;
+        on_progress(ply + 1, total);
+        next_tick().await;
+    }
+    encoder?.into_inner().ok()
+}
+
+/// Downloads the whole game as an animated GIF, from the side it is viewed from.
+pub async fn export_gif(
+    state: GameState,
+    theme: Theme,
+    orientation: Color,
+    frame_ms: u32,
+    language: Language,
+    on_progress: impl Fn(usize, usize),
+) {
+    let Some(bytes) = encode_gif(&state, theme, orientation, frame_ms, language, on_progress).await
+    else {
+        leptos::logging::log!("[EXPORT] GIF encoding failed");
+        return;
+    };
+    let array = js_sys::Uint8Array::from(bytes.as_slice());
+    if let Ok(blob) = web_sys::Blob::new_with_u8_array_sequence(&js_sys::Array::of1(&array)) {
+        download_blob(&blob, "xiangqi_game.gif");
+    }
+}
+
+fn download_text(contents: String, filename: &str) {
+    if let Ok(blob) = web_sys::Blob::new_with_str_sequence(&js_sys::Array::of1(&contents.into())) {
+        download_blob(&blob, filename);
+    }
+}
+
+fn download_blob(blob: &web_sys::Blob, filename: &str) {
+    if let Ok(url) = web_sys::Url::create_object_url_with_blob(blob) {
+        download_url(&url, filename);
+        let _ = web_sys::Url::revoke_object_url(&url);
+    }
+}
+
+fn download_url(url: &str, filename: &str) {
+    if let Ok(el) = document().create_element("a") {
+        if let Ok(anchor) = el.dyn_into::<web_sys::HtmlAnchorElement>() {
+            anchor.set_href(url);
+            anchor.set_download(filename);
+            anchor.click();
         }
     }
 }
@@ -0,0 +1,271 @@
+use crate::app::{GameMode, OnlineStatus};
+use crate::i18n::{
+    awaiting_answer_label, challenge_label, minutes_label, reconnect_label, t, use_language,
+    Language, Msg,
+};
+use crate::network::NetworkClient;
+use leptos::{
+    component, create_signal, event_target_value, view, web_sys, IntoView, ReadSignal, SignalGet,
+    SignalGetUntracked, SignalSet, WriteSignal,
+};
+use shared::{DisconnectPolicy, FriendStatus, GameMessage, Presence};
+
+/// Minutes per side offered when challenging a friend, none for an untimed game; games
+/// have no clock yet, so this is only a label.
+const TIME_CONTROLS: [Option<u32>; 4] = [None, Some(5), Some(10), Some(15)];
+/// What a challenger can ask for when a player drops out of the game.
+const DISCONNECT_POLICIES: [DisconnectPolicy; 3] = [
+    DisconnectPolicy::Forfeit,
+    DisconnectPolicy::Reconnect { minutes: 3 },
+    DisconnectPolicy::Adjudicate,
+];
+
+fn policy_label(policy: DisconnectPolicy, language: Language) -> String {
+    match policy {
+        DisconnectPolicy::Forfeit => Msg::PolicyForfeit.text(language).to_string(),
+        DisconnectPolicy::Reconnect { minutes } => reconnect_label(language, minutes),
+        DisconnectPolicy::Adjudicate => Msg::PolicyAdjudicate.text(language).to_string(),
+    }
+}
+
+fn time_control_label(minutes: Option<u32>, language: Language) -> String {
+    minutes.map_or_else(
+        || Msg::FriendsUntimed.text(language).to_string(),
+        |minutes| minutes_label(language, minutes),
+    )
+}
+
+/// A challenge from a friend waiting for an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncomingChallenge {
+    pub id: String,
+    pub from: String,
+    pub time_control: Option<String>,
+    pub disconnect_policy: DisconnectPolicy,
+}
+
+/// A challenge this player sent: its id and the friend's name.
+pub type SentChallenge = (String, String);
+
+const fn presence_icon(presence: Presence) -> &'static str {
+    match presence {
+        Presence::Online => "🟢",
+        Presence::Playing => "🟡",
+        Presence::Offline => "⚫",
+    }
+}
+
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn FriendsPanel(
+    game_mode: ReadSignal<GameMode>,
+    online_status: ReadSignal<OnlineStatus>,
+    network_client: ReadSignal<Option<NetworkClient>>,
+    friends: ReadSignal<Vec<FriendStatus>>,
+    incoming: ReadSignal<Vec<IncomingChallenge>>,
+    sent: ReadSignal<Option<SentChallenge>>,
+    set_sent: WriteSignal<Option<SentChallenge>>,
+) -> impl IntoView {
+    let (show, set_show) = create_signal(false);
+    let (new_friend, set_new_friend) = create_signal(String::new());
+    let (time_control, set_time_control) = create_signal(0_usize);
+    let (disconnect_policy, set_disconnect_policy) = create_signal(0_usize);
+    let language = use_language();
+
+    let send = move |msg: GameMessage| {
+        if let Some(client) = network_client.get_untracked() {
+            client.send(&msg);
+        }
+    };
+
+    // Presence is a snapshot, so opening the panel asks for a fresh list
+    let toggle = move |_: web_sys::MouseEvent| {
+        let open = !show.get_untracked();
+        set_show.set(open);
+        if open {
+            send(GameMessage::ListFriends);
+        }
+    };
+
+    let add_friend = move || {
+        let name = new_friend.get_untracked().trim().to_string();
+        if !name.is_empty() {
+            send(GameMessage::AddFriend(name));
+            set_new_friend.set(String::new());
+        }
+    };
+
+    view! {
+        {move || {
+            if game_mode.get() != GameMode::Online
+                || !matches!(
+                    online_status.get(),
+                    OnlineStatus::None | OnlineStatus::Finding | OnlineStatus::Spectating
+                )
+            {
+                return view! {}.into_view();
+            }
+
+            view! {
+                <div class="leaderboard-panel">
+                    {move || incoming.get().into_iter().map(|challenge| {
+                        let accept_id = challenge.id.clone();
+                        let decline_id = challenge.id;
+                        view! {
+                            <div class="challenge-card">
+                                <span>
+                                    {move || {
+                                        let lang = language.get();
+                                        let time = challenge
+                                            .time_control
+                                            .clone()
+                                            .unwrap_or_else(|| time_control_label(None, lang));
+                                        let policy = policy_label(challenge.disconnect_policy, lang).to_lowercase();
+                                        challenge_label(lang, &challenge.from, &time, &policy)
+                                    }}
+                                </span>
+                                <button
+                                    class="control-btn"
+                                    on:click=move |_| send(GameMessage::AcceptChallenge(accept_id.clone()))
+                                >
+                                    {t(Msg::FriendsAccept)}
+                                </button>
+                                <button
+                                    class="control-btn"
+                                    on:click=move |_| send(GameMessage::DeclineChallenge(decline_id.clone()))
+                                >
+                                    {t(Msg::FriendsDecline)}
+                                </button>
+                            </div>
+                        }
+                    }).collect::<Vec<_>>()}
+
+                    {move || sent.get().map(|(id, to)| view! {
+                        <div class="challenge-card">
+                            <span>{move || awaiting_answer_label(language.get(), &to)}</span>
+                            <button
+                                class="control-btn"
+                                on:click=move |_| {
+                                    send(GameMessage::DeclineChallenge(id.clone()));
+                                    set_sent.set(None);
+                                }
+                            >
+                                {t(Msg::FriendsCancel)}
+                            </button>
+                        </div>
+                    })}
+
+                    <button class="control-btn" on:click=toggle>
+                        {move || if show.get() { Msg::FriendsHide } else { Msg::FriendsShow }.text(language.get())}
+                    </button>
+
+                    {move || show.get().then(|| view! {
+                        <div class="leaderboard-body">
+                            <div class="friend-add">
+                                <input
+                                    type="text"
+                                    class="leaderboard-search"
+                                    placeholder=t(Msg::FriendsNamePlaceholder)
+                                    maxlength="20"
+                                    prop:value=move || new_friend.get()
+                                    on:input=move |ev| set_new_friend.set(event_target_value(&ev))
+                                    on:keydown=move |ev: web_sys::KeyboardEvent| {
+                                        if ev.key() == "Enter" {
+                                            add_friend();
+                                        }
+                                    }
+                                />
+                                <button class="control-btn" on:click=move |_| add_friend()>
+                                    {t(Msg::FriendsAdd)}
+                                </button>
+                            </div>
+
+                            <label>
+                                {t(Msg::FriendsTime)}
+                                <select
+                                    class="leaderboard-search"
+                                    on:change=move |ev| {
+                                        set_time_control.set(event_target_value(&ev).parse().unwrap_or(0));
+                                    }
+                                >
+                                    {TIME_CONTROLS.into_iter().enumerate().map(|(i, minutes)| view! {
+                                        <option value=i.to_string() selected=move || time_control.get() == i>
+                                            {move || time_control_label(minutes, language.get())}
+                                        </option>
+                                    }).collect::<Vec<_>>()}
+                                </select>
+                            </label>
+
+                            <label>
+                                {t(Msg::FriendsOnDisconnect)}
+                                <select
+                                    class="leaderboard-search"
+                                    on:change=move |ev| {
+                                        set_disconnect_policy.set(event_target_value(&ev).parse().unwrap_or(0));
+                                    }
+                                >
+                                    {DISCONNECT_POLICIES.into_iter().enumerate().map(|(i, policy)| view! {
+                                        <option value=i.to_string() selected=move || disconnect_policy.get() == i>
+                                            {move || policy_label(policy, language.get())}
+                                        </option>
+                                    }).collect::<Vec<_>>()}
+                                </select>
+                            </label>
+
+                            {move || friends.get().is_empty().then(|| view! {
+                                <div style="color: #aaa; text-align: center;">{t(Msg::FriendsNone)}</div>
+                            })}
+
+                            <table class="leaderboard-table friends-table">
+                                <tbody>
+                                    {move || friends.get().into_iter().map(|friend| {
+                                        let challenge_name = friend.name.clone();
+                                        let remove_name = friend.name.clone();
+                                        let can_challenge = friend.presence == Presence::Online;
+                                        view! {
+                                            <tr>
+                                                <td>{presence_icon(friend.presence)} " " {friend.name}</td>
+                                                <td>
+                                                    <button
+                                                        class="control-btn"
+                                                        disabled=move || !can_challenge || sent.get().is_some()
+                                                        on:click=move |_| {
+                                                            // Sent as "minutes+increment", which reads the same in every language
+                                                            let label = TIME_CONTROLS
+                                                                .get(time_control.get_untracked())
+                                                                .copied()
+                                                                .flatten()
+                                                                .map(|minutes| format!("{minutes}+0"));
+                                                            let policy = DISCONNECT_POLICIES
+                                                                .get(disconnect_policy.get_untracked())
+                                                                .copied()
+                                                                .unwrap_or_default();
+                                                            send(GameMessage::ChallengeFriend {
+                                                                friend_id: challenge_name.clone(),
+                                                                time_control: label,
+                                                                disconnect_policy: policy,
+                                                            });
+                                                        }
+                                                    >
+                                                        {t(Msg::FriendsChallenge)}
+                                                    </button>
+                                                    <button
+                                                        class="control-btn"
+                                                        title=t(Msg::FriendsRemove)
+                                                        on:click=move |_| send(GameMessage::RemoveFriend(remove_name.clone()))
+                                                    >
+                                                        "✕"
+                                                    </button>
+                                                </td>
+                                            </tr>
+                                        }
+                                    }).collect::<Vec<_>>()}
+                                </tbody>
+                            </table>
+                        </div>
+                    })}
+                </div>
+            }.into_view()
+        }}
+    }
+}
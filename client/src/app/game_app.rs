@@ -1,106 +1,431 @@
-use crate::components::board::BoardView;
-use cotuong_core::engine::config::EngineConfig;
+use crate::audio::{AudioManager, Sound, SoundSettings};
+use crate::components::annotations::{Annotation, Annotations};
+use crate::components::board::{BoardOverlay, BoardView, Premove};
+use cotuong_core::engine::adjudication::{Adjudication, Adjudicator};
+use cotuong_core::engine::bench::BENCH_POSITIONS;
+use cotuong_core::engine::trace::TraceLimits;
 use cotuong_core::engine::Move;
 use cotuong_core::engine::SearchLimit;
 use cotuong_core::logic::board::{BoardCoordinate, Color};
-use cotuong_core::logic::game::{GameState, GameStatus};
+use cotuong_core::logic::game::{GameState, GameStatus, MoveAnnotation};
 use cotuong_core::logic::rules::is_in_check;
+use cotuong_core::session::SessionResult;
 use cotuong_core::worker::{GameWorker, Input, Output};
 use gloo_worker::{Spawnable, WorkerBridge};
 use leptos::{
-    component, create_effect, create_signal, set_timeout, store_value, view, web_sys, Callback,
-    IntoView, SignalGet, SignalSet, SignalUpdate, SignalWithUntracked,
+    component, create_effect, create_memo, create_signal, ev, set_timeout, spawn_local,
+    store_value, view, web_sys, window_event_listener, Callback, IntoView, Signal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, SignalWithUntracked,
 };
-use shared::{GameMessage, ServerMessage};
+use leptos_router::{use_location, use_navigate, NavigateOptions, Route, Router, Routes};
+use shared::{ErrorCode, FriendStatus, GameMessage, LiveGame, MatchScore, ServerMessage};
 use std::rc::Rc;
 use std::time::Duration;
+use wasm_bindgen::JsCast;
 
-use crate::app::config::ConfigPanel;
-use crate::app::controls::ControlsArea;
-use crate::app::export::export_csv;
-use crate::app::log::{LogPanel, ThinkingIndicator};
-use crate::app::online::OnlineStatusPanel;
+use crate::app::adaptive::{GameOutcome, MAX_MOVE_CP_LOSS};
+use crate::app::arena::ArenaPanel;
+use crate::app::benchmark::{BenchmarkPanel, BenchmarkStatus, BENCHMARK_DEPTH};
+use crate::app::config::{analysis_config, ConfigPanel, UserPrefs};
+use crate::app::controls::{ControlsArea, HandoffOverlay, MateSearch, SettingsPanel};
+use crate::app::explorer::OpeningExplorer;
+use crate::app::export::{export_csv, export_gif, export_json, export_pgn, export_png};
+use crate::app::friends::{FriendsPanel, IncomingChallenge, SentChallenge};
+use crate::app::import::ImportPanel;
+use crate::app::install::InstallButton;
+use crate::app::layout::{router_base, HomeView, NavBar, Section};
+use crate::app::leaderboard::LeaderboardPanel;
+use crate::app::live_games::LiveGamesPanel;
+use crate::app::log::{step_view_ply, LogPanel, ThinkingIndicator};
+use crate::app::online::{abort_reason_message, ErrorToast, OnlineStatusPanel};
+use crate::app::profile::ProfilePage;
+use crate::app::puzzle::PuzzlePanel;
+use crate::app::review::{EvalGraph, ReviewPanel, ReviewStatus};
+use crate::app::scoreboard::{ScoreKey, Scoreboard, ScoreboardPanel};
+use crate::app::search_tree::{SearchTreePanel, TraceStatus, TRACE_DEPTH};
+use crate::app::share::{FenPanel, ReplayLoader, SharePanel, SharedPosition};
 use crate::app::styles::GAME_STYLES;
+use crate::app::training::TrainingPanel;
+use crate::app::tutorial::TutorialPanel;
 use crate::app::{Difficulty, GameMode, OnlineStatus};
+use crate::i18n::{error_message, provide_language, server_restart_label, t, Msg};
 use crate::network::NetworkClient;
 
+/// Depth of the "find mate" button, in the searching side's moves.
+const MATE_SEARCH_MOVES: u8 = 3;
+/// Fixed search depth for every position of a post-game review.
+const REVIEW_DEPTH: u8 = 5;
+
+/// Plies of the online game `state` has seen, numbered like the server's move sequence.
+fn local_ply(state: &GameState, sync_base: u32) -> u32 {
+    sync_base.saturating_add(u32::try_from(state.history.len()).unwrap_or(u32::MAX))
+}
+
 #[component]
-#[allow(clippy::too_many_lines)]
 pub fn App() -> impl IntoView {
-    let (game_state, set_game_state) = create_signal(GameState::new());
-    let (difficulty, set_difficulty) = create_signal(Difficulty::Level1);
-    let (game_mode, set_game_mode) = create_signal(GameMode::HumanVsComputer);
-    let (player_side, set_player_side) = create_signal(Color::Red);
+    view! {
+        <Router base=router_base()>
+            <Shell />
+        </Router>
+    }
+}
+
+/// Holds the state every page shares, so a game in progress survives moving between pages.
+#[component]
+#[allow(clippy::too_many_lines)]
+fn Shell() -> impl IntoView {
+    let prefs = UserPrefs::load();
+    let (game_state, set_game_state) = create_signal(prefs.handicap.initial_state());
+    let (difficulty, set_difficulty) = create_signal(prefs.difficulty);
+    let (game_mode, set_game_mode) = create_signal(prefs.game_mode);
+    let (player_side, set_player_side) = create_signal(prefs.player_side);
+    let (flipped, set_flipped) = create_signal(false);
+    let (blindfold, set_blindfold) = create_signal(prefs.blindfold);
+    let (hotseat, set_hotseat) = create_signal(prefs.hotseat);
+    let (scores, set_scores) = create_signal(prefs.scores);
+    let (session_scores, set_session_scores) = create_signal(Scoreboard::default());
+    let (handoff, set_handoff) = create_signal(Option::<Color>::None);
+    let (overlay, set_overlay) = create_signal(BoardOverlay::None);
     let (is_thinking, set_is_thinking) = create_signal(false);
-    let (is_paused, set_is_paused) = create_signal(false);
-    let (show_config, set_show_config) = create_signal(false);
+    // Computer vs computer waits for Start, as when the mode is picked by hand
+    let (is_paused, set_is_paused) = create_signal(prefs.game_mode == GameMode::ComputerVsComputer);
+    let (theme, set_theme) = create_signal(prefs.theme);
+    let (puzzle_best_streak, set_puzzle_best_streak) = create_signal(prefs.puzzle_best_streak);
+    let (completed_lessons, set_completed_lessons) = create_signal(prefs.completed_lessons);
+    let (solved_training, set_solved_training) = create_signal(prefs.solved_training);
+    let (handicap, set_handicap) = create_signal(prefs.handicap);
+    let (adaptive, set_adaptive) = create_signal(prefs.adaptive);
+    let (language, set_language) = create_signal(prefs.language);
+    let (confirm_moves, set_confirm_moves) = create_signal(prefs.confirm_moves);
+    provide_language(language);
+    create_effect(move |_| {
+        let code = language.get().code();
+        if let Some(root) = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.document_element())
+        {
+            let _ = root.set_attribute("lang", code);
+        }
+    });
+    // Player's centipawn loss per move in the current game, for the adaptive level
+    let cp_losses = store_value(Vec::<i32>::new());
+    // Engine's own score after its last move, from its side
+    let last_engine_score = store_value(Option::<i32>::None);
+    // Both engines' score runs in computer-vs-computer games, for resigning and draws
+    let adjudicator = store_value(Adjudicator::default());
+    let (sound_settings, set_sound_settings) = create_signal(SoundSettings::load());
+    let (premove, set_premove) = create_signal(Option::<Premove>::None);
+    let (annotations, set_annotations) = create_signal(Annotations::default());
+    let (view_ply, set_view_ply) = create_signal(Option::<usize>::None);
+
+    create_effect(move |_| sound_settings.get().save());
+
+    // Adaptive level and scores: a fresh game clears the move log, a finished one is scored
+    // and adjusts the strength
+    create_effect(move |previous: Option<GameStatus>| {
+        let (status, moves) = game_state.with(|s| (s.status.clone(), s.history.len()));
+        if moves == 0 {
+            cp_losses.set_value(Vec::new());
+            last_engine_score.set_value(None);
+            adjudicator.set_value(Adjudicator::default());
+        }
+
+        let just_ended = previous == Some(GameStatus::Playing) && status != GameStatus::Playing;
+        if !just_ended {
+            return status;
+        }
+        let (mode, level) = (game_mode.get_untracked(), difficulty.get_untracked());
+        let winner = game_state
+            .with_untracked(SessionResult::from_state)
+            .and_then(|result| result.winner);
+        // Two-player games are scored from Red's side
+        let side = if mode == GameMode::HumanVsHuman {
+            Color::Red
+        } else {
+            player_side.get_untracked()
+        };
+        let outcome = GameOutcome::for_side(winner, side);
+
+        if let Some(key) = ScoreKey::for_game(mode, level) {
+            set_session_scores.update(|s| s.record(key, outcome));
+            set_scores.update(|s| s.record(key, outcome));
+        }
+        if mode == GameMode::HumanVsComputer && level == Difficulty::Adaptive {
+            let average = cp_losses.with_value(|losses| {
+                #[allow(clippy::cast_precision_loss)]
+                let count = losses.len() as f64;
+                (!losses.is_empty()).then(|| f64::from(losses.iter().sum::<i32>()) / count)
+            });
+            set_adaptive.update(|a| a.record_game(outcome, average));
+        }
+        status
+    });
 
     // Network State
     let (network_client, set_network_client) = create_signal(Option::<NetworkClient>::None);
     let (server_msg, set_server_msg) = create_signal(Option::<ServerMessage>::None);
     let (online_status, set_online_status) = create_signal(OnlineStatus::None);
+    // Ply number of the first move in the online game's local history, which a
+    // `GameStateCorrection` starts over from
+    let sync_base = store_value(0_u32);
+    // Sequence number of our last move while the server has not acknowledged it
+    let (unacked_move, set_unacked_move) = create_signal(Option::<u32>::None);
+    // Seconds left to make our move after a `MoveTimeWarning`, counting down until we move
+    let (move_time_left, set_move_time_left) = create_signal(Option::<u64>::None);
+    // Seconds the server still holds the seat of an opponent who lost the connection
+    let (opponent_away, set_opponent_away) = create_signal(Option::<u64>::None);
 
     // Game End State
     let (game_end_winner, set_game_end_winner) = create_signal(Option::<Option<Color>>::None);
     let (game_end_reason, set_game_end_reason) = create_signal(String::new());
+    let (match_score, set_match_score) = create_signal(MatchScore::default());
     let (is_ready_for_rematch, set_is_ready_for_rematch) = create_signal(false);
+    // Server id of the game played online, for replay links
+    let (online_game_id, set_online_game_id) = create_signal(Option::<String>::None);
+
+    // Spectator State
+    let (live_games, set_live_games) = create_signal(Vec::<LiveGame>::new());
+    let (spectated_game, set_spectated_game) = create_signal(Option::<LiveGame>::None);
+
+    // Friends State
+    let (friends, set_friends) = create_signal(Vec::<FriendStatus>::new());
+    let (incoming_challenges, set_incoming_challenges) =
+        create_signal(Vec::<IncomingChallenge>::new());
+    let (sent_challenge, set_sent_challenge) = create_signal(Option::<SentChallenge>::None);
 
     // Dual Configs
-    let (red_config, set_red_config) = create_signal(EngineConfig::default());
-    let (black_config, set_black_config) = create_signal(EngineConfig::default());
+    let (red_config, set_red_config) = create_signal(prefs.red_config);
+    let (black_config, set_black_config) = create_signal(prefs.black_config);
+
+    create_effect(move |_| {
+        UserPrefs {
+            game_mode: game_mode.get(),
+            difficulty: difficulty.get(),
+            player_side: player_side.get(),
+            red_config: red_config.get(),
+            black_config: black_config.get(),
+            theme: theme.get(),
+            puzzle_best_streak: puzzle_best_streak.get(),
+            completed_lessons: completed_lessons.get(),
+            solved_training: solved_training.get(),
+            handicap: handicap.get(),
+            adaptive: adaptive.get(),
+            language: language.get(),
+            confirm_moves: confirm_moves.get(),
+            blindfold: blindfold.get(),
+            hotseat: hotseat.get(),
+            scores: scores.get(),
+            ..UserPrefs::default()
+        }
+        .save();
+    });
+
+    // Pages and game modes follow each other: opening a page puts its board in a mode it
+    // shows, and picking a mode that belongs to another page goes there
+    let location = use_location();
+    let section = create_memo(move |_| {
+        location
+            .pathname
+            .with(|path| Section::from_path(path.strip_prefix(router_base()).unwrap_or(path)))
+    });
+    create_effect(move |_| {
+        let section = section.get();
+        if let Some(mode) = section.default_mode() {
+            if !section.accepts(game_mode.get_untracked()) {
+                set_game_mode.set(mode);
+                set_is_paused.set(false);
+            }
+        }
+    });
+    let navigate = use_navigate();
+    create_effect(move |_| {
+        let mode = game_mode.get();
+        if !section.get_untracked().accepts(mode) {
+            navigate(Section::for_mode(mode).path(), NavigateOptions::default());
+        }
+    });
 
     // Worker Bridge
     let (worker_bridge, set_worker_bridge) =
         create_signal(Option::<WorkerBridge<GameWorker>>::None);
+    let (mate_result, set_mate_result) = create_signal(Option::<MateSearch>::None);
+    let (review, set_review) = create_signal(ReviewStatus::Idle);
+    // Whether the worker's review answers still belong to the game on the board
+    let review_pending = store_value(false);
+    let (benchmark, set_benchmark) = create_signal(BenchmarkStatus::Idle);
+    let (search_trace, set_search_trace) = create_signal(TraceStatus::Idle);
 
-    create_effect(move |_| {
-        let bridge = GameWorker::spawner()
-            .callback(move |output| match output {
-                Output::MoveFound(mv, stats) => {
-                    let mut current_state = game_state.get();
+    // Answers from any engine worker; in Computer vs Computer each side has its own
+    let on_engine_output = move |output: Output| match output {
+        Output::MoveFound(mv, stats, explanation) => {
+            let mut current_state = game_state.get();
+            if game_mode.get_untracked() == GameMode::HumanVsComputer {
+                // The player's reply cost whatever the engine gained since its last move
+                if let Some(previous) = last_engine_score.get_value() {
+                    let loss = mv.score.saturating_sub(previous).clamp(0, MAX_MOVE_CP_LOSS);
+                    cp_losses.update_value(|l| l.push(loss));
+                }
+                last_engine_score.set_value(Some(mv.score));
+            }
+            if game_mode.get_untracked() == GameMode::ComputerVsComputer {
+                let (red, black) = (red_config.get_untracked(), black_config.get_untracked());
+                let (mover, opponent) = if current_state.turn == Color::Red {
+                    (&red, &black)
+                } else {
+                    (&black, &red)
+                };
+                let turn = current_state.turn;
+                let verdict = adjudicator
+                    .try_update_value(|a| a.record(turn, mv.score, mover, opponent))
+                    .flatten();
+                if let Some(verdict) = verdict {
+                    match verdict {
+                        Adjudication::Resign => current_state.resign(turn),
+                        Adjudication::DrawAgreed => current_state.agree_draw(),
+                    }
+                    set_game_state.set(current_state);
+                    set_is_thinking.set(false);
+                    return;
+                }
+            }
+            if let (Some(from), Some(to)) = (
+                BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize),
+                BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize),
+            ) {
+                match current_state.make_move(from, to) {
+                    Ok(()) => {
+                        #[allow(clippy::cast_precision_loss)]
+                        let time_s = stats.time_ms as f64 / 1000.0;
+                        web_sys::console::log_1(
+                            &format!(
+                                "🤖 Engine Move: Depth {}, Nodes {} ({:.1}s), TT {}% hit, {} stores, {}‰ full",
+                                stats.depth,
+                                stats.nodes,
+                                time_s,
+                                stats.tt.hit_rate(),
+                                stats.tt.stores,
+                                stats.tt.hashfull
+                            )
+                            .into(),
+                        );
+                        if let Some(last) = current_state.history.last_mut() {
+                            last.eval = Some(if last.color == Color::Red {
+                                explanation.score
+                            } else {
+                                -explanation.score
+                            });
+                            last.annotation = Some(MoveAnnotation::Engine {
+                                depth: stats.depth,
+                                nodes: stats.nodes,
+                                time_ms: stats.time_ms,
+                                explanation,
+                            });
+                        }
+                        set_game_state.set(current_state);
+                        set_is_thinking.set(false);
+                    }
+                    Err(e) => {
+                        if e == cotuong_core::logic::rules::MoveError::ThreeFoldRepetition {
+                            web_sys::console::log_1(
+                                &format!("⚠️ Move rejected (3-fold), retrying... {mv:?}").into(),
+                            );
+                        } else {
+                            web_sys::console::log_1(&format!("❌ Move error: {e:?}").into());
+                        }
+                        set_is_thinking.set(false);
+                    }
+                }
+            }
+        }
+        Output::MateSearched(fen, line) => {
+            // Ignore answers for a position that is no longer on the board
+            if fen != game_state.with_untracked(|s| s.board.to_fen_string(s.turn)) {
+                return;
+            }
+            let search = match line.as_deref() {
+                Some(mate_line @ [first, ..]) => {
                     if let (Some(from), Some(to)) = (
-                        BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize),
-                        BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize),
+                        BoardCoordinate::new(first.from_row as usize, first.from_col as usize),
+                        BoardCoordinate::new(first.to_row as usize, first.to_col as usize),
                     ) {
-                        match current_state.make_move(from, to) {
-                            Ok(()) => {
-                                #[allow(clippy::cast_precision_loss)]
-                                let time_s = stats.time_ms as f64 / 1000.0;
-                                web_sys::console::log_1(
-                                    &format!(
-                                        "🤖 Engine Move: Depth {}, Nodes {} ({:.1}s)",
-                                        stats.depth, stats.nodes, time_s
-                                    )
-                                    .into(),
-                                );
-                                if let Some(last) = current_state.history.last_mut() {
-                                    last.note = Some(format!(
-                                        "🤖 Depth: {}, Nodes: {}, Time: {}ms",
-                                        stats.depth, stats.nodes, stats.time_ms
-                                    ));
-                                }
-                                set_game_state.set(current_state);
-                                set_is_thinking.set(false);
+                        let arrow = Annotation::Arrow { from, to };
+                        set_annotations.update(|a| {
+                            if !a.for_position(&fen).contains(&arrow) {
+                                a.toggle(&fen, arrow);
                             }
-                            Err(e) => {
-                                if e == cotuong_core::logic::rules::MoveError::ThreeFoldRepetition {
-                                    web_sys::console::log_1(
-                                        &format!("⚠️ Move rejected (3-fold), retrying... {mv:?}")
-                                            .into(),
-                                    );
-                                } else {
-                                    web_sys::console::log_1(
-                                        &format!("❌ Move error: {e:?}").into(),
-                                    );
-                                }
-                                set_is_thinking.set(false);
-                            }
-                        }
+                        });
                     }
+                    MateSearch::Found(mate_line.len().div_ceil(2))
                 }
-            })
-            .spawn("./worker.js");
-        set_worker_bridge.set(Some(bridge));
+                _ => MateSearch::NotFound(MATE_SEARCH_MOVES),
+            };
+            set_mate_result.set(Some(search));
+        }
+        Output::ReviewProgress(done, _, score) => {
+            if review_pending.get_value() {
+                set_review.update(|review| {
+                    if let ReviewStatus::Running(searched, _, evals) = review {
+                        *searched = done;
+                        evals.push(score);
+                    }
+                });
+            }
+        }
+        Output::GameReviewed(result) => {
+            if review_pending.get_value() {
+                review_pending.set_value(false);
+                // Scores and glyphs go into the moves, so exports and replays keep them
+                set_game_state.update(|state| result.annotate(state));
+                set_review.set(ReviewStatus::Done(result));
+            }
+        }
+        Output::BenchmarkProgress(done, total) => {
+            set_benchmark.set(BenchmarkStatus::Running(done, total));
+        }
+        Output::Benchmarked(nps) => set_benchmark.set(BenchmarkStatus::Done(nps)),
+        Output::SearchTraced(tree) => set_search_trace.set(TraceStatus::Done(tree)),
+    };
+    let spawn_engine = move || {
+        GameWorker::spawner()
+            .callback(on_engine_output)
+            .spawn("./worker.js")
+    };
+
+    create_effect(move |_| set_worker_bridge.set(Some(spawn_engine())));
+
+    // Computer vs Computer gives Black a worker of its own, so the two sides' hash tables and
+    // configs never mix; the main worker plays Red
+    let (black_worker, set_black_worker) = create_signal(Option::<WorkerBridge<GameWorker>>::None);
+    create_effect(move |_| {
+        let separate = game_mode.get() == GameMode::ComputerVsComputer;
+        if separate != black_worker.with_untracked(Option::is_some) {
+            set_black_worker.set(separate.then(spawn_engine));
+        }
+    });
+    let engine_for = move |turn: Color| {
+        if turn == Color::Black && game_mode.get_untracked() == GameMode::ComputerVsComputer {
+            black_worker
+        } else {
+            worker_bridge
+        }
+    };
+
+    // Pausing stops the side that is thinking: its worker is replaced, so the move it was
+    // searching for never arrives, while the other side keeps its tables
+    create_effect(move |_| {
+        if !is_paused.get()
+            || game_mode.get_untracked() != GameMode::ComputerVsComputer
+            || !is_thinking.get_untracked()
+        {
+            return;
+        }
+        let fresh = Some(spawn_engine());
+        if game_state.with_untracked(|s| s.turn) == Color::Black {
+            set_black_worker.set(fresh);
+        } else {
+            set_worker_bridge.set(fresh);
+        }
+        set_is_thinking.set(false);
     });
 
     // Initialize Network Client
@@ -115,10 +440,30 @@ pub fn App() -> impl IntoView {
         }
     });
 
+    // Server error toast, cleared automatically after a few seconds
+    let (server_error, set_server_error) = create_signal(Option::<String>::None);
+    let show_server_error = move |text: String| {
+        set_server_error.set(Some(text));
+        set_timeout(move || set_server_error.set(None), Duration::from_secs(4));
+    };
+
     // Handle Server Messages
     create_effect(move |_| {
         if let Some(msg) = server_msg.get() {
             match msg {
+                ServerMessage::ServerHello {
+                    protocol_version,
+                    encoding,
+                } => {
+                    leptos::logging::log!(
+                        "[NET] Server speaks protocol v{} ({:?} frames)",
+                        protocol_version,
+                        encoding
+                    );
+                }
+                ServerMessage::Welcome { player_id, .. } => {
+                    leptos::logging::log!("[NET] Authenticated as {}", player_id);
+                }
                 ServerMessage::WaitingForMatch => {
                     set_online_status.set(OnlineStatus::Finding);
                     leptos::logging::log!("Waiting for match...");
@@ -126,25 +471,54 @@ pub fn App() -> impl IntoView {
                 ServerMessage::MatchFound {
                     opponent_id: _,
                     your_color,
-                    game_id: _,
+                    game_id,
+                    score,
                 } => {
-                    leptos::logging::log!("Match found! You are {:?}", your_color);
+                    leptos::logging::log!("Match found! You are {:?} ({})", your_color, score);
+                    set_match_score.set(score);
+                    set_online_game_id.set(Some(game_id));
+                    // The server drops every other challenge once a game starts
+                    set_incoming_challenges.set(Vec::new());
+                    set_sent_challenge.set(None);
                     set_online_status.set(OnlineStatus::MatchFound);
                     set_game_mode.set(GameMode::Online);
                     set_player_side.set(your_color);
-                    // Reset game
-                    let new_state = GameState::new();
-                    set_game_state.set(new_state);
+                    set_premove.set(None);
+                    // Reset game; the server only pairs players who asked for the same handicap
+                    sync_base.set_value(0);
+                    set_unacked_move.set(None);
+                    set_move_time_left.set(None);
+                    set_opponent_away.set(None);
+                    set_game_state.set(handicap.get_untracked().initial_state());
                 }
                 ServerMessage::GameStart(board) => {
                     set_online_status.set(OnlineStatus::Playing);
                     set_game_mode.set(GameMode::Online);
-                    let mut new_state = GameState::new();
-                    new_state.board = *board;
+                    set_premove.set(None);
+                    let handicap = handicap.get_untracked();
+                    let mut new_state = GameState::from_position(*board, handicap.first_turn());
+                    new_state.handicap = handicap;
+                    sync_base.set_value(0);
+                    set_unacked_move.set(None);
+                    set_move_time_left.set(None);
+                    set_opponent_away.set(None);
                     set_game_state.set(new_state);
                 }
-                ServerMessage::OpponentMove { move_data: m, fen } => {
+                ServerMessage::OpponentMove {
+                    move_data: m,
+                    fen,
+                    seq,
+                } => {
                     let mut state = game_state.get();
+                    let played = local_ply(&state, sync_base.get_value());
+                    if seq > played {
+                        // Missed moves: fetch them, the server sends this one again afterwards
+                        leptos::logging::log!("[NET] Behind at ply {}, move is {}", played, seq);
+                        if let Some(client) = network_client.get() {
+                            client.send(&GameMessage::RequestMoves { from_seq: played });
+                        }
+                        return;
+                    }
                     if let (Some(from), Some(to)) = (
                         BoardCoordinate::new(m.from_row as usize, m.from_col as usize),
                         BoardCoordinate::new(m.to_row as usize, m.to_col as usize),
@@ -170,6 +544,41 @@ pub fn App() -> impl IntoView {
                                         is_valid,
                                     });
                                 }
+
+                                // Play the queued pre-move if it is still legal
+                                if let Some((from, to)) = premove.get_untracked() {
+                                    set_premove.set(None);
+                                    let mut next = state;
+                                    match next.make_move(from, to) {
+                                        Ok(()) => {
+                                            let fen = next.board.to_fen_string(next.turn);
+                                            set_unacked_move.set(Some(
+                                                local_ply(&next, sync_base.get_value())
+                                                    .saturating_sub(1),
+                                            ));
+                                            set_game_state.set(next);
+                                            if let Some(client) = network_client.get() {
+                                                client.send(&GameMessage::MakeMove {
+                                                    move_data: Move {
+                                                        #[allow(clippy::cast_possible_truncation)]
+                                                        from_row: from.row as u8,
+                                                        #[allow(clippy::cast_possible_truncation)]
+                                                        from_col: from.col as u8,
+                                                        #[allow(clippy::cast_possible_truncation)]
+                                                        to_row: to.row as u8,
+                                                        #[allow(clippy::cast_possible_truncation)]
+                                                        to_col: to.col as u8,
+                                                        score: 0,
+                                                    },
+                                                    fen,
+                                                });
+                                            }
+                                        }
+                                        Err(e) => {
+                                            leptos::logging::log!("Dropping pre-move: {:?}", e);
+                                        }
+                                    }
+                                }
                             }
                             Err(e) => {
                                 leptos::logging::log!("Error applying opponent move: {:?}", e);
@@ -183,13 +592,23 @@ pub fn App() -> impl IntoView {
                         }
                     }
                 }
-                ServerMessage::GameStateCorrection { fen, turn } => {
+                ServerMessage::GameStateCorrection { fen, turn, seq } => {
                     match cotuong_core::logic::board::Board::from_fen(&fen) {
                         Ok((board, _)) => {
-                            let mut state = GameState::new();
-                            state.board = board;
-                            state.turn = turn;
+                            let mut state = GameState::from_position(board, turn);
+                            state.handicap = handicap.get_untracked();
+                            set_premove.set(None);
                             leptos::logging::log!("Correcting Game State to: {} ({:?})", fen, turn);
+                            sync_base.set_value(seq);
+                            // The server's position does not include our pending move
+                            if unacked_move.get_untracked().is_some_and(|own| own >= seq) {
+                                show_server_error(
+                                    Msg::MoveTakenBack
+                                        .text(language.get_untracked())
+                                        .to_string(),
+                                );
+                            }
+                            set_unacked_move.set(None);
                             set_game_state.set(state);
                         }
                         Err(e) => {
@@ -197,6 +616,56 @@ pub fn App() -> impl IntoView {
                         }
                     }
                 }
+                ServerMessage::SyncMoves { from_seq, moves } => {
+                    let mut state = game_state.get_untracked();
+                    let played = local_ply(&state, sync_base.get_value());
+                    // Moves before our position are already on the board
+                    let Some(known) = played
+                        .checked_sub(from_seq)
+                        .and_then(|known| usize::try_from(known).ok())
+                    else {
+                        leptos::logging::log!(
+                            "[NET] Sync from {} skips past ply {}",
+                            from_seq,
+                            played
+                        );
+                        return;
+                    };
+                    for m in moves.iter().skip(known) {
+                        let coords = BoardCoordinate::new(m.from_row as usize, m.from_col as usize)
+                            .zip(BoardCoordinate::new(m.to_row as usize, m.to_col as usize));
+                        let Some((from, to)) = coords else {
+                            break;
+                        };
+                        if let Err(e) = state.make_move(from, to) {
+                            // Left to the server's correction once this client verifies a move
+                            leptos::logging::log!("[NET] Synced move does not apply: {:?}", e);
+                            break;
+                        }
+                    }
+                    // A synced list that reaches past our move means it went through
+                    let synced =
+                        u32::try_from(moves.len()).map_or(u32::MAX, |n| from_seq.saturating_add(n));
+                    if unacked_move.get_untracked().is_some_and(|own| own < synced) {
+                        set_unacked_move.set(None);
+                    }
+                    set_premove.set(None);
+                    set_game_state.set(state);
+                }
+                ServerMessage::MoveAccepted { seq } => {
+                    if unacked_move.get_untracked() == Some(seq) {
+                        set_unacked_move.set(None);
+                    }
+                }
+                ServerMessage::MoveTimeWarning { seconds_left } => {
+                    leptos::logging::log!("[GAME] {}s left to move", seconds_left);
+                    set_move_time_left.set(Some(seconds_left));
+                }
+                ServerMessage::OpponentAway { seconds_left } => {
+                    leptos::logging::log!("Opponent away, seat held for {}s", seconds_left);
+                    set_opponent_away.set(Some(seconds_left));
+                }
+                ServerMessage::OpponentReturned => set_opponent_away.set(None),
                 ServerMessage::OpponentDisconnected => {
                     set_online_status.set(OnlineStatus::OpponentDisconnected);
                     leptos::logging::log!("Opponent disconnected!");
@@ -213,18 +682,244 @@ pub fn App() -> impl IntoView {
                         winner,
                         reason
                     );
-                    set_online_status.set(OnlineStatus::GameEnded);
+                    // Spectators stay on the watched board and see the result there
+                    if online_status.get_untracked() != OnlineStatus::Spectating {
+                        set_online_status.set(OnlineStatus::GameEnded);
+                        set_is_ready_for_rematch.set(false);
+                    }
+                    set_premove.set(None);
+                    set_opponent_away.set(None);
                     set_game_end_winner.set(Some(winner));
                     set_game_end_reason.set(reason);
+                }
+                ServerMessage::LiveGames(games) => set_live_games.set(games),
+                ServerMessage::SpectatorUpdate { game, fen } => {
+                    match cotuong_core::logic::board::Board::from_fen(&fen) {
+                        Ok((board, turn)) => {
+                            set_game_state.set(GameState::from_position(board, turn));
+                            set_game_mode.set(GameMode::Online);
+                            set_player_side.set(Color::Red);
+                            set_online_status.set(OnlineStatus::Spectating);
+                            set_game_end_winner.set(None);
+                            set_spectated_game.set(Some(game));
+                        }
+                        Err(e) => {
+                            leptos::logging::log!("Failed to parse spectated FEN: {}", e);
+                        }
+                    }
+                }
+                ServerMessage::Error { code, detail } => {
+                    leptos::logging::log!("[ERR] Server error: {:?} ({:?})", code, detail);
+                    show_server_error(error_message(code, language.get_untracked()).to_string());
+                }
+                ServerMessage::RateLimited {
+                    kind,
+                    retry_after_ms,
+                } => {
+                    leptos::logging::log!(
+                        "[NET] Rate limited ({:?}), retry after {}ms",
+                        kind,
+                        retry_after_ms
+                    );
+                    show_server_error(
+                        error_message(ErrorCode::RateLimited, language.get_untracked()).to_string(),
+                    );
+                }
+                ServerMessage::Pong(_) => {}
+                ServerMessage::CloudEval { hash, eval } => {
+                    leptos::logging::log!("[NET] Cloud eval for {:016x}: {:?}", hash, eval);
+                }
+                ServerMessage::GameAborted { reason } => {
+                    leptos::logging::log!("[GAME] Game aborted: {:?}", reason);
+                    set_online_status.set(OnlineStatus::None);
+                    set_game_state.set(GameState::new());
                     set_is_ready_for_rematch.set(false);
+                    set_premove.set(None);
+                    show_server_error(
+                        abort_reason_message(reason)
+                            .text(language.get_untracked())
+                            .to_string(),
+                    );
+                }
+                ServerMessage::Announcement(text) => {
+                    leptos::logging::log!("[NET] Announcement: {}", text);
+                    show_server_error(format!("📢 {text}"));
                 }
-                ServerMessage::Error(e) => {
-                    leptos::logging::log!("[ERR] Server error: {}", e);
+                ServerMessage::Friends(list) => set_friends.set(list),
+                ServerMessage::ChallengeSent { challenge_id, to } => {
+                    set_sent_challenge.set(Some((challenge_id, to)));
                 }
+                ServerMessage::ChallengeReceived {
+                    challenge_id,
+                    from,
+                    time_control,
+                    disconnect_policy,
+                } => {
+                    leptos::logging::log!("[NET] Challenge from {}", from);
+                    set_incoming_challenges.update(|challenges| {
+                        challenges.push(IncomingChallenge {
+                            id: challenge_id,
+                            from,
+                            time_control,
+                            disconnect_policy,
+                        });
+                    });
+                }
+                ServerMessage::ChallengeClosed { challenge_id } => {
+                    set_incoming_challenges.update(|challenges| {
+                        challenges.retain(|c| c.id != challenge_id);
+                    });
+                    if sent_challenge
+                        .get_untracked()
+                        .is_some_and(|(id, _)| id == challenge_id)
+                    {
+                        set_sent_challenge.set(None);
+                        show_server_error(
+                            Msg::ChallengeDeclined
+                                .text(language.get_untracked())
+                                .to_string(),
+                        );
+                    }
+                }
+                ServerMessage::ServerShuttingDown { seconds } => {
+                    leptos::logging::log!("[NET] Server shutting down in {}s", seconds);
+                    if let Some(window) = web_sys::window() {
+                        let _ = window.alert_with_message(&server_restart_label(
+                            language.get_untracked(),
+                            seconds,
+                        ));
+                    }
+                }
+            }
+        }
+    });
+
+    // A mate search answer only applies to the position it was asked about
+    create_effect(move |_| {
+        game_state.with(|s| s.history.len());
+        set_mate_result.set(None);
+    });
+
+    let on_find_mate = Callback::new(move |()| {
+        set_mate_result.set(Some(MateSearch::Running));
+        let state = game_state.get_untracked();
+        worker_bridge.with_untracked(|bridge| {
+            if let Some(b) = bridge {
+                b.send(Input::FindMate(state, MATE_SEARCH_MOVES * 2 - 1));
             }
+        });
+    });
+
+    // A review only describes the game it was run on
+    let history_len = create_memo(move |_| game_state.with(|s| s.history.len()));
+    create_effect(move |_| {
+        history_len.track();
+        review_pending.set_value(false);
+        set_review.set(ReviewStatus::Idle);
+    });
+
+    // Keep the worker's engine in step with the game, so its hash table carries over from
+    // one move to the next; a fresh game starts it from clean tables
+    create_effect(move |_| {
+        history_len.track();
+        let engines = match game_mode.get_untracked() {
+            GameMode::HumanVsComputer | GameMode::Training => vec![worker_bridge],
+            GameMode::ComputerVsComputer => vec![worker_bridge, black_worker],
+            GameMode::HumanVsHuman | GameMode::Online | GameMode::Puzzle | GameMode::Tutorial => {
+                return
+            }
+        };
+        let state = game_state.get_untracked();
+        for engine in engines {
+            engine.with_untracked(|bridge| {
+                if let Some(b) = bridge {
+                    b.send(if state.history.is_empty() {
+                        Input::NewGame
+                    } else {
+                        Input::PositionUpdate(state.clone())
+                    });
+                }
+            });
         }
     });
 
+    let on_review = Callback::new(move |()| {
+        let state = game_state.get_untracked();
+        review_pending.set_value(true);
+        set_review.set(ReviewStatus::Running(
+            0,
+            state.history.len() + 1,
+            Vec::new(),
+        ));
+        worker_bridge.with_untracked(|bridge| {
+            if let Some(b) = bridge {
+                b.send(Input::ReviewGame(
+                    state,
+                    SearchLimit::Depth(REVIEW_DEPTH),
+                    analysis_config(),
+                ));
+            }
+        });
+    });
+
+    // Runs on the main worker, behind any search it is busy with
+    let on_benchmark = Callback::new(move |()| {
+        set_benchmark.set(BenchmarkStatus::Running(0, BENCH_POSITIONS.len()));
+        worker_bridge.with_untracked(|bridge| {
+            if let Some(b) = bridge {
+                b.send(Input::Benchmark(BENCHMARK_DEPTH, analysis_config()));
+            }
+        });
+    });
+
+    let on_trace = Callback::new(move |()| {
+        set_search_trace.set(TraceStatus::Running);
+        let state = game_state.get_untracked();
+        let config = if state.turn == Color::Red {
+            red_config.get_untracked()
+        } else {
+            black_config.get_untracked()
+        };
+        worker_bridge.with_untracked(|bridge| {
+            if let Some(b) = bridge {
+                b.send(Input::TraceSearch(
+                    state,
+                    TRACE_DEPTH,
+                    config,
+                    TraceLimits::default(),
+                ));
+            }
+        });
+    });
+
+    // Any change to the live game (new move, undo, reset) snaps the view back to it
+    create_effect(move |_| {
+        game_state.with(|s| s.history.len());
+        set_view_ply.set(None);
+    });
+
+    // Arrow keys browse the move history; Home/End jump to the start/live position
+    let _ = window_event_listener(ev::keydown, move |ev: web_sys::KeyboardEvent| {
+        let typing = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|el| matches!(el.tag_name().as_str(), "INPUT" | "SELECT" | "TEXTAREA"));
+        let len = game_state.with_untracked(|s| s.history.len());
+        if typing || len == 0 {
+            return;
+        }
+        let current = view_ply.get_untracked();
+        let next = match ev.key().as_str() {
+            "ArrowLeft" => step_view_ply(current, len, false),
+            "ArrowRight" => step_view_ply(current, len, true),
+            "Home" => Some(0),
+            "End" => None,
+            _ => return,
+        };
+        ev.prevent_default();
+        set_view_ply.set(next);
+    });
+
     // on_move handler for BoardView
     let on_move = {
         Rc::new(move |m: Move| {
@@ -232,12 +927,17 @@ pub fn App() -> impl IntoView {
                 if let Some(client) = network_client.get() {
                     let state = game_state.get();
                     let fen = state.board.to_fen_string(state.turn);
+                    // `state` already has the move; it stays pending until the server acks it
+                    set_unacked_move.set(Some(
+                        local_ply(&state, sync_base.get_value()).saturating_sub(1),
+                    ));
                     leptos::logging::log!("[GAME] Client sending move: {:?}", m);
                     client.send(&GameMessage::MakeMove { move_data: m, fen });
                 }
             }
         }) as Rc<dyn Fn(Move)>
     };
+    let on_move = store_value(on_move);
 
     // AI Move Effect
     create_effect(move |_| {
@@ -251,9 +951,11 @@ pub fn App() -> impl IntoView {
         }
 
         let should_play = match mode {
-            GameMode::HumanVsComputer => state.turn != player_side.get(),
+            GameMode::HumanVsComputer | GameMode::Training => state.turn != player_side.get(),
             GameMode::ComputerVsComputer => true,
-            GameMode::HumanVsHuman | GameMode::Online => false,
+            GameMode::HumanVsHuman | GameMode::Online | GameMode::Puzzle | GameMode::Tutorial => {
+                false
+            }
         };
 
         if should_play && state.status == GameStatus::Playing {
@@ -274,25 +976,33 @@ pub fn App() -> impl IntoView {
                     }
 
                     let should_play_now = match current_mode {
-                        GameMode::HumanVsComputer => current_state.turn != player_side.get(),
+                        GameMode::HumanVsComputer | GameMode::Training => {
+                            current_state.turn != player_side.get()
+                        }
                         GameMode::ComputerVsComputer => true,
-                        GameMode::HumanVsHuman | GameMode::Online => false,
+                        GameMode::HumanVsHuman
+                        | GameMode::Online
+                        | GameMode::Puzzle
+                        | GameMode::Tutorial => false,
                     };
 
                     if should_play_now && current_state.status == GameStatus::Playing {
-                        let config = if current_state.turn == Color::Red {
+                        let mut config = if current_state.turn == Color::Red {
                             red_config.get()
                         } else {
                             black_config.get()
                         };
+                        if let Some(strength) = diff.strength_limit() {
+                            config.strength_limit = Some(strength);
+                        }
+                        if diff == Difficulty::Adaptive {
+                            config.strength_limit = Some(adaptive.get_untracked().strength_limit());
+                        }
 
-                        let limit = match diff {
-                            Difficulty::Level1 => SearchLimit::Time(1000),
-                            Difficulty::Level2 => SearchLimit::Time(2000),
-                            Difficulty::Level3 => SearchLimit::Time(5000),
-                            Difficulty::Level4 => SearchLimit::Time(10000),
-                            Difficulty::Level5 => SearchLimit::Time(20000),
-                        };
+                        let limit = SearchLimit::Time(
+                            diff.think_time_ms()
+                                .unwrap_or_else(|| adaptive.get_untracked().think_time_ms()),
+                        );
 
                         // 1. Check Opening Book
                         {
@@ -303,8 +1013,10 @@ pub fn App() -> impl IntoView {
                             if let Some((from, to)) = book_move {
                                 if current_state.make_move(from, to).is_ok() {
                                     if let Some(last) = current_state.history.last_mut() {
-                                        last.note = Some("📖 Book Move".to_string());
+                                        last.annotation = Some(MoveAnnotation::Book);
                                     }
+                                    // Book moves carry no score to measure the next reply against
+                                    last_engine_score.set_value(None);
                                     set_game_state.set(current_state);
                                     set_is_thinking.set(false);
                                     return;
@@ -312,8 +1024,8 @@ pub fn App() -> impl IntoView {
                             }
                         }
 
-                        // 2. Send to Worker
-                        worker_bridge.with_untracked(|bridge| {
+                        // 2. Send to the side's worker
+                        engine_for(current_state.turn).with_untracked(|bridge| {
                             if let Some(bridge) = bridge {
                                 bridge.send(Input::ComputeMove(
                                     current_state,
@@ -335,13 +1047,44 @@ pub fn App() -> impl IntoView {
         }
     });
 
+    // Countdowns for the move timer, stopped once the turn passes to the opponent, and for
+    // an absent opponent's seat
+    leptos::set_interval(
+        move || {
+            for (left, set_left) in [
+                (move_time_left, set_move_time_left),
+                (opponent_away, set_opponent_away),
+            ] {
+                if left.get_untracked().is_some() {
+                    set_left.update(|left| *left = left.map(|secs| secs.saturating_sub(1)));
+                }
+            }
+        },
+        Duration::from_secs(1),
+    );
+    create_effect(move |_| {
+        let my_turn = game_state.with(|state| state.turn) == player_side.get();
+        if !my_turn && move_time_left.get_untracked().is_some() {
+            set_move_time_left.set(None);
+        }
+    });
+
     // Sound Effects
-    let move_sound = web_sys::HtmlAudioElement::new_with_src("sounds/move.mp3").ok();
-    let capture_sound = web_sys::HtmlAudioElement::new_with_src("sounds/capture.mp3").ok();
-    let check_sound = web_sys::HtmlAudioElement::new_with_src("sounds/check.mp3").ok();
-    let checkmate_sound = web_sys::HtmlAudioElement::new_with_src("sounds/checkmate.mp3").ok();
+    let audio = Rc::new(AudioManager::default());
     let last_len = store_value(0usize);
 
+    // No dedicated clip for the move timer; the check chime is the most urgent one
+    create_effect({
+        let audio = Rc::clone(&audio);
+        move |warned: Option<bool>| {
+            let warning = move_time_left.with(Option::is_some);
+            if warning && warned == Some(false) {
+                audio.play(Sound::Check, sound_settings.get_untracked());
+            }
+            warning
+        }
+    });
+
     create_effect(move |_| {
         let state = game_state.get();
         let current_len = state.history.len();
@@ -349,95 +1092,338 @@ pub fn App() -> impl IntoView {
         last_len.update_value(|prev| {
             if current_len > *prev {
                 if let Some(last_move) = state.history.last() {
-                    let mut sound = &move_sound;
-                    let is_capture = last_move.captured.is_some();
-
-                    if let GameStatus::Checkmate(_) = state.status {
-                        sound = &checkmate_sound;
+                    let sound = if let GameStatus::Checkmate(_) = state.status {
+                        Sound::Checkmate
                     } else if is_in_check(&state.board, state.turn) {
-                        sound = &check_sound;
-                    } else if is_capture {
-                        sound = &capture_sound;
-                    }
+                        Sound::Check
+                    } else if last_move.captured.is_some() {
+                        Sound::Capture
+                    } else {
+                        Sound::Move
+                    };
 
-                    let _ = sound.as_ref().map(|a| a.play());
+                    audio.play(sound, sound_settings.get_untracked());
                 }
             }
             *prev = current_len;
         });
     });
 
-    view! {
-        <div class="game-container" style="font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; min-height: 100vh; background-color: #222; color: #eee; display: flex; flex-direction: column; align-items: center;">
-            <style>
-                {GAME_STYLES}
-            </style>
-
-            <h1 style="margin: 20px 0; color: #f0d9b5; text-shadow: 0 2px 4px rgba(0,0,0,0.5); text-align: center;">"Cờ Tướng"</h1>
+    // Hotseat: in a one-device Human vs Human game the board turns to the side to move, and
+    // each new move is covered until the next player is holding the device
+    create_effect(move |prev_len: Option<usize>| {
+        let (len, turn, playing) =
+            game_state.with(|s| (s.history.len(), s.turn, s.status == GameStatus::Playing));
+        if game_mode.get() != GameMode::HumanVsHuman || !hotseat.get() {
+            set_handoff.set(None);
+            return len;
+        }
+        set_flipped.set(turn != player_side.get_untracked());
+        let moved = prev_len.is_some_and(|prev| len > prev);
+        set_handoff.set((moved && playing).then_some(turn));
+        len
+    });
 
-            <ThinkingIndicator is_thinking=is_thinking />
+    // Exported images show the board from the side it is viewed from on screen
+    let orientation = move || {
+        let side = player_side.get();
+        if flipped.get() {
+            side.opposite()
+        } else {
+            side
+        }
+    };
+    let (gif_progress, set_gif_progress) = create_signal(Option::<(usize, usize)>::None);
 
+    let controls = move || {
+        view! {
             <ControlsArea
                 game_mode=game_mode
                 set_game_mode=set_game_mode
                 player_side=player_side
                 set_player_side=set_player_side
+                set_flipped=set_flipped
+                blindfold=blindfold
+                set_blindfold=set_blindfold
+                hotseat=hotseat
+                set_hotseat=set_hotseat
+                overlay=overlay
+                set_overlay=set_overlay
                 difficulty=difficulty
                 set_difficulty=set_difficulty
+                adaptive=adaptive
+                handicap=handicap
+                set_handicap=set_handicap
+                language=language
                 is_paused=is_paused
                 set_is_paused=set_is_paused
                 game_state=game_state
                 set_game_state=set_game_state
                 is_thinking=is_thinking
                 set_is_thinking=set_is_thinking
+                on_find_mate=on_find_mate
+                mate_result=mate_result
                 on_export_csv=Callback::new(move |()| export_csv(&game_state.get()))
+                on_export_json=Callback::new(move |()| {
+                    annotations.with(|a| export_json(&game_state.get(), a));
+                })
+                on_export_pgn=Callback::new(move |()| {
+                    review.with(|r| {
+                        let result = match r {
+                            ReviewStatus::Done(result) => Some(result),
+                            ReviewStatus::Idle | ReviewStatus::Running(..) => None,
+                        };
+                        export_pgn(&game_state.get(), result, language.get_untracked());
+                    });
+                })
+                on_export_png=Callback::new(move |()| {
+                    // The position on the board, which may be an earlier one being browsed
+                    let mut state = game_state.get();
+                    if let Some(ply) = view_ply.get() {
+                        while state.history.len() > ply && state.undo_move() {}
+                    }
+                    let fen = state.board.to_fen_string(state.turn);
+                    let marks = annotations.with(|a| a.for_position(&fen).to_vec());
+                    review.with(|r| {
+                        let result = match r {
+                            ReviewStatus::Done(result) => Some(result),
+                            ReviewStatus::Idle | ReviewStatus::Running(..) => None,
+                        };
+                        export_png(&state, result, theme.get(), orientation(), &marks, language.get());
+                    });
+                })
+                on_export_gif=Callback::new(move |frame_ms| {
+                    let (state, theme, orientation) = (game_state.get(), theme.get(), orientation());
+                    set_gif_progress.set(Some((0, state.history.len() + 1)));
+                    spawn_local(async move {
+                        let on_progress = move |done, total| set_gif_progress.set(Some((done, total)));
+                        export_gif(state, theme, orientation, frame_ms, language.get_untracked(), on_progress).await;
+                        set_gif_progress.set(None);
+                    });
+                })
+                gif_progress=gif_progress
             />
+        }
+    };
 
-            <OnlineStatusPanel
-                game_mode=game_mode
-                online_status=online_status
-                game_state=game_state
-                player_side=player_side
-                network_client=network_client
-                game_end_winner=game_end_winner
-                game_end_reason=game_end_reason
-                is_ready_for_rematch=is_ready_for_rematch
-                set_online_status=set_online_status
-                set_game_state=set_game_state
-                set_is_ready_for_rematch=set_is_ready_for_rematch
-            />
+    // The watched game while spectating, otherwise the player's own
+    let shared_game_id = Signal::derive(move || {
+        if online_status.get() == OnlineStatus::Spectating {
+            spectated_game.with(|g| g.as_ref().map(|g| g.game_id.clone()))
+        } else {
+            online_game_id.get()
+        }
+    });
 
+    let board = move || {
+        view! {
             <div class="game-layout">
                 <div class="side-column left">
-                    <LogPanel game_state=game_state />
+                    <LogPanel game_state=game_state view_ply=view_ply set_view_ply=set_view_ply />
+                    <EvalGraph review=review view_ply=view_ply set_view_ply=set_view_ply />
                 </div>
 
-                <BoardView game_state=game_state set_game_state=set_game_state game_mode=game_mode player_side=player_side on_move=on_move />
+                <BoardView
+                    game_state=game_state
+                    set_game_state=set_game_state
+                    game_mode=game_mode
+                    player_side=player_side
+                    flipped=flipped
+                    blindfold=blindfold
+                    overlay=overlay
+                    theme=theme
+                    premove=premove
+                    set_premove=set_premove
+                    annotations=annotations
+                    set_annotations=set_annotations
+                    view_ply=view_ply
+                    read_only=Signal::derive(move || online_status.get() == OnlineStatus::Spectating)
+                    confirm_moves=Signal::derive(move || {
+                        confirm_moves.get().applies(game_mode.get(), difficulty.get())
+                    })
+                    on_move=on_move.get_value()
+                />
 
                 <div class="side-column right">
+                    <OpeningExplorer
+                        game_state=game_state
+                        set_game_state=set_game_state
+                        game_mode=game_mode
+                        player_side=player_side
+                        read_only=Signal::derive(move || online_status.get() == OnlineStatus::Spectating)
+                        on_move=on_move.get_value()
+                    />
+                    <ReviewPanel
+                        game_state=game_state
+                        review=review
+                        on_review=on_review
+                        set_view_ply=set_view_ply
+                    />
                 </div>
             </div>
+        }
+    };
 
-            <div style="margin-top: 20px; text-align: center;">
-                <button
-                    style=move || if show_config.get() {
-                        "background: #555; color: white; padding: 15px 30px; font-size: 1.2em; font-weight: bold; border-radius: 8px; border: 1px solid #666; cursor: pointer; transition: all 0.2s ease;"
-                    } else {
-                        "background: #444; color: #eee; padding: 15px 30px; font-size: 1.2em; font-weight: bold; border-radius: 8px; border: 1px solid #555; cursor: pointer; transition: all 0.2s ease;"
-                    }
-                    on:click=move |_| set_show_config.update(|v| *v = !*v)
-                >
-                    "Cấu hình engine"
-                </button>
-            </div>
+    view! {
+        <div class="game-container" style="font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif; min-height: 100vh; background-color: #222; color: #eee; display: flex; flex-direction: column; align-items: center;">
+            <style>
+                {GAME_STYLES}
+            </style>
 
-            <ConfigPanel
-                show_config=show_config
-                red_config=red_config
-                set_red_config=set_red_config
-                black_config=black_config
-                set_black_config=set_black_config
-            />
+            <h1 style="margin: 20px 0; color: #f0d9b5; text-shadow: 0 2px 4px rgba(0,0,0,0.5); text-align: center;">{t(Msg::AppTitle)}</h1>
+
+            <NavBar />
+
+            <InstallButton />
+
+            <ThinkingIndicator is_thinking=is_thinking />
+
+            <ErrorToast message=server_error />
+
+            <HandoffOverlay handoff=handoff set_handoff=set_handoff />
+
+            <Routes>
+                <Route path="/" view=HomeView />
+                <Route path="/play" view=move || view! {
+                    {controls}
+                    <ScoreboardPanel
+                        game_mode=game_mode
+                        difficulty=difficulty
+                        session=session_scores
+                        set_session=set_session_scores
+                        saved=scores
+                        set_saved=set_scores
+                    />
+                    <FenPanel game_state=game_state set_game_state=set_game_state />
+                    {board}
+                } />
+                <Route path="/online" view=move || view! {
+                    {controls}
+
+                    <OnlineStatusPanel
+                        game_mode=game_mode
+                        online_status=online_status
+                        game_state=game_state
+                        player_side=player_side
+                        unacked_move=unacked_move
+                        move_time_left=move_time_left
+                        opponent_away=opponent_away
+                        network_client=network_client
+                        game_end_winner=game_end_winner
+                        game_end_reason=game_end_reason
+                        is_ready_for_rematch=is_ready_for_rematch
+                        match_score=match_score
+                        spectated_game=spectated_game
+                        handicap=handicap
+                        set_handicap=set_handicap
+                        set_online_status=set_online_status
+                        set_game_state=set_game_state
+                        set_is_ready_for_rematch=set_is_ready_for_rematch
+                    />
+
+                    <LiveGamesPanel
+                        game_mode=game_mode
+                        online_status=online_status
+                        network_client=network_client
+                        live_games=live_games
+                        set_live_games=set_live_games
+                    />
+
+                    <FriendsPanel
+                        game_mode=game_mode
+                        online_status=online_status
+                        network_client=network_client
+                        friends=friends
+                        incoming=incoming_challenges
+                        sent=sent_challenge
+                        set_sent=set_sent_challenge
+                    />
+
+                    <LeaderboardPanel game_mode=game_mode />
+
+                    <SharePanel game_state=game_state game_id=shared_game_id />
+
+                    {board}
+                } />
+                <Route path="/analysis" view=move || view! {
+                    {controls}
+                    <SharedPosition set_game_state=set_game_state />
+                    <SharePanel game_state=game_state />
+                    <FenPanel game_state=game_state set_game_state=set_game_state />
+                    <ImportPanel set_game_state=set_game_state />
+                    {board}
+                    <SearchTreePanel trace=search_trace on_trace=on_trace />
+                } />
+                <Route path="/replay/:game_id" view=move || view! {
+                    {controls}
+                    <ReplayLoader game_state=game_state set_game_state=set_game_state />
+                    {board}
+                    <SearchTreePanel trace=search_trace on_trace=on_trace />
+                } />
+                <Route path="/puzzles" view=move || view! {
+                    {controls}
+
+                    <PuzzlePanel
+                        game_mode=game_mode
+                        game_state=game_state
+                        set_game_state=set_game_state
+                        set_player_side=set_player_side
+                        best_streak=puzzle_best_streak
+                        set_best_streak=set_puzzle_best_streak
+                    />
+
+                    <TutorialPanel
+                        game_mode=game_mode
+                        game_state=game_state
+                        set_game_state=set_game_state
+                        set_player_side=set_player_side
+                        completed=completed_lessons
+                        set_completed=set_completed_lessons
+                    />
+
+                    <TrainingPanel
+                        game_mode=game_mode
+                        game_state=game_state
+                        set_game_state=set_game_state
+                        player_side=player_side
+                        set_player_side=set_player_side
+                        solved=solved_training
+                        set_solved=set_solved_training
+                    />
+
+                    {board}
+                } />
+                <Route path="/settings" view=move || view! {
+                    <SettingsPanel
+                        theme=theme
+                        set_theme=set_theme
+                        sound_settings=sound_settings
+                        set_sound_settings=set_sound_settings
+                        language=language
+                        set_language=set_language
+                        confirm_moves=confirm_moves
+                        set_confirm_moves=set_confirm_moves
+                    />
+
+                    <BenchmarkPanel
+                        benchmark=benchmark
+                        on_benchmark=on_benchmark
+                        set_difficulty=set_difficulty
+                    />
+
+                    <ConfigPanel
+                        red_config=red_config
+                        set_red_config=set_red_config
+                        black_config=black_config
+                        set_black_config=set_black_config
+                    />
+
+                    <ArenaPanel red_config=red_config black_config=black_config />
+                } />
+                <Route path="/profile" view=ProfilePage />
+                <Route path="/profile/:name" view=ProfilePage />
+                <Route path="/*any" view=HomeView />
+            </Routes>
         </div>
     }
 }
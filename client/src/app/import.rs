@@ -0,0 +1,108 @@
+use crate::i18n::{
+    detail_text, imported_label, record_error_label, t, use_language, Language, Msg,
+};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::notation::MoveTextError;
+use cotuong_core::logic::record::{import_record, record_start, RecordError, RecordFormat};
+use leptos::{
+    component, create_signal, event_target_value, view, IntoView, SignalGet, SignalGetUntracked,
+    SignalSet, WriteSignal,
+};
+
+const fn format_name(format: RecordFormat) -> Msg {
+    match format {
+        RecordFormat::Coordinates => Msg::RecordCoordinates,
+        RecordFormat::Wxf => Msg::RecordWxf,
+        RecordFormat::Chinese => Msg::RecordChinese,
+    }
+}
+
+fn error_text(error: &RecordError, language: Language) -> String {
+    if error.token.is_empty() {
+        return Msg::RecordNoMoves.text(language).to_string();
+    }
+    let side = if error.color == Color::Red {
+        Msg::Red
+    } else {
+        Msg::Black
+    };
+    let reason = match error.kind {
+        MoveTextError::Unreadable => Msg::RecordUnreadable,
+        MoveTextError::Illegal => Msg::RecordIllegal,
+    };
+    record_error_label(
+        language,
+        error.ply + 1,
+        side.text(language),
+        &error.token,
+        reason.text(language),
+    )
+}
+
+/// What the last import did.
+#[derive(Clone)]
+enum ImportOutcome {
+    BadFen(String),
+    Imported(usize, RecordFormat),
+    Failed(RecordError),
+}
+
+impl ImportOutcome {
+    fn text(&self, language: Language) -> String {
+        match self {
+            Self::BadFen(e) => detail_text(Msg::FenInvalid, e, language),
+            Self::Imported(moves, format) => {
+                imported_label(language, *moves, format_name(*format).text(language))
+            }
+            Self::Failed(e) => error_text(e, language),
+        }
+    }
+}
+
+/// Pastes a game record as a bare move list or PGN, in coordinates (`h2e2`, `H2-E2`), WXF
+/// (`C2=5`) or Chinese (`炮二平五`), and puts it on the analysis board to step through.
+#[component]
+pub fn ImportPanel(set_game_state: WriteSignal<GameState>) -> impl IntoView {
+    let (text, set_text) = create_signal(String::new());
+    let (message, set_message) = create_signal(Option::<ImportOutcome>::None);
+    let language = use_language();
+
+    let import = move |_| {
+        let text = text.get_untracked();
+        let start = match record_start(&text) {
+            Ok(start) => start,
+            Err(e) => {
+                set_message.set(Some(ImportOutcome::BadFen(e)));
+                return;
+            }
+        };
+        match import_record(start, &text) {
+            Ok(record) => {
+                set_message.set(Some(ImportOutcome::Imported(
+                    record.state.history.len(),
+                    record.format,
+                )));
+                set_game_state.set(record.state);
+            }
+            Err(e) => set_message.set(Some(ImportOutcome::Failed(e))),
+        }
+    };
+
+    view! {
+        <details class="share-panel record-import">
+            <summary>{t(Msg::ImportTitle)}</summary>
+            <textarea
+                class="share-link"
+                rows="6"
+                placeholder=t(Msg::ImportPlaceholder)
+                prop:value=move || text.get()
+                on:input=move |ev| set_text.set(event_target_value(&ev))
+            />
+            <button class="control-btn" on:click=import>{t(Msg::ImportButton)}</button>
+            {move || message.get().map(|m| view! {
+                <div class="arena-message" role="alert">{m.text(language.get())}</div>
+            })}
+        </details>
+    }
+}
@@ -0,0 +1,45 @@
+use crate::i18n::{t, Msg};
+use leptos::{
+    component, create_signal, store_value, view, window_event_listener_untyped, IntoView,
+    SignalGet, SignalSet,
+};
+use wasm_bindgen::JsCast;
+
+/// "Install app" button, shown only once the browser reports the PWA as installable.
+#[component]
+pub fn InstallButton() -> impl IntoView {
+    let deferred_prompt = store_value(Option::<web_sys::Event>::None);
+    let (available, set_available) = create_signal(false);
+
+    let _ = window_event_listener_untyped("beforeinstallprompt", move |ev| {
+        // Replace the browser's own mini-infobar with our button
+        ev.prevent_default();
+        deferred_prompt.set_value(Some(ev));
+        set_available.set(true);
+    });
+    let _ = window_event_listener_untyped("appinstalled", move |_| {
+        deferred_prompt.set_value(None);
+        set_available.set(false);
+    });
+
+    // `BeforeInstallPromptEvent` has no web-sys binding, so call `prompt()` dynamically
+    let install = move |_| {
+        let Some(ev) = deferred_prompt.get_value() else {
+            return;
+        };
+        if let Ok(prompt) = js_sys::Reflect::get(&ev, &"prompt".into())
+            .and_then(|f| f.dyn_into::<js_sys::Function>())
+        {
+            let _ = prompt.call0(&ev);
+        }
+        // The event can only be prompted once
+        deferred_prompt.set_value(None);
+        set_available.set(false);
+    };
+
+    view! {
+        {move || available.get().then(|| view! {
+            <button class="control-btn btn-primary install-btn" on:click=install>{t(Msg::InstallApp)}</button>
+        })}
+    }
+}
@@ -0,0 +1,163 @@
+use crate::app::GameMode;
+use crate::i18n::{t, Msg};
+use leptos::{component, document, view, IntoView};
+use leptos_router::A;
+use std::sync::OnceLock;
+
+/// Top-level pages of the app, each with its own route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Home,
+    Play,
+    Online,
+    Analysis,
+    Puzzles,
+    Settings,
+    Profile,
+}
+
+impl Section {
+    pub const ALL: [Self; 7] = [
+        Self::Home,
+        Self::Play,
+        Self::Online,
+        Self::Analysis,
+        Self::Puzzles,
+        Self::Settings,
+        Self::Profile,
+    ];
+
+    pub const fn path(self) -> &'static str {
+        match self {
+            Self::Home => "/",
+            Self::Play => "/play",
+            Self::Online => "/online",
+            Self::Analysis => "/analysis",
+            Self::Puzzles => "/puzzles",
+            Self::Settings => "/settings",
+            Self::Profile => "/profile",
+        }
+    }
+
+    /// The section a path below the router base belongs to; unknown paths show Home.
+    /// Shared game replays open on the analysis board.
+    pub fn from_path(path: &str) -> Self {
+        let first = path
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        if first == "replay" {
+            return Self::Analysis;
+        }
+        Self::ALL
+            .into_iter()
+            .find(|s| *s != Self::Home && s.path().trim_start_matches('/') == first)
+            .unwrap_or(Self::Home)
+    }
+
+    pub const fn label(self) -> Msg {
+        match self {
+            Self::Home => Msg::NavHome,
+            Self::Play => Msg::NavPlay,
+            Self::Online => Msg::NavOnline,
+            Self::Analysis => Msg::NavAnalysis,
+            Self::Puzzles => Msg::NavPuzzles,
+            Self::Settings => Msg::NavSettings,
+            Self::Profile => Msg::NavProfile,
+        }
+    }
+
+    const fn summary(self) -> Option<Msg> {
+        match self {
+            Self::Home => None,
+            Self::Play => Some(Msg::HomePlay),
+            Self::Online => Some(Msg::HomeOnline),
+            Self::Analysis => Some(Msg::HomeAnalysis),
+            Self::Puzzles => Some(Msg::HomePuzzles),
+            Self::Settings => Some(Msg::HomeSettings),
+            Self::Profile => Some(Msg::HomeProfile),
+        }
+    }
+
+    /// Whether the board on this page may be in `mode`. Pages without a board take any.
+    pub const fn accepts(self, mode: GameMode) -> bool {
+        match self {
+            Self::Play => matches!(
+                mode,
+                GameMode::HumanVsComputer | GameMode::ComputerVsComputer | GameMode::HumanVsHuman
+            ),
+            Self::Online => matches!(mode, GameMode::Online),
+            // A free board both sides move on
+            Self::Analysis => matches!(mode, GameMode::HumanVsHuman),
+            Self::Puzzles => matches!(
+                mode,
+                GameMode::Puzzle | GameMode::Tutorial | GameMode::Training
+            ),
+            Self::Home | Self::Settings | Self::Profile => true,
+        }
+    }
+
+    /// Mode the board switches to when the page is opened in a mode it does not accept.
+    pub const fn default_mode(self) -> Option<GameMode> {
+        match self {
+            Self::Play => Some(GameMode::HumanVsComputer),
+            Self::Online => Some(GameMode::Online),
+            Self::Analysis => Some(GameMode::HumanVsHuman),
+            Self::Puzzles => Some(GameMode::Puzzle),
+            Self::Home | Self::Settings | Self::Profile => None,
+        }
+    }
+
+    /// Page a mode picked in the controls is played on.
+    pub const fn for_mode(mode: GameMode) -> Self {
+        match mode {
+            GameMode::HumanVsComputer | GameMode::ComputerVsComputer | GameMode::HumanVsHuman => {
+                Self::Play
+            }
+            GameMode::Online => Self::Online,
+            GameMode::Puzzle | GameMode::Tutorial | GameMode::Training => Self::Puzzles,
+        }
+    }
+}
+
+/// Path the app is served under, e.g. "/GameCoTuong" on GitHub Pages, read from the `<base>`
+/// Trunk fills in with its public URL. Empty when served from the root.
+pub fn router_base() -> &'static str {
+    static BASE: OnceLock<String> = OnceLock::new();
+    BASE.get_or_init(|| {
+        document()
+            .base_uri()
+            .ok()
+            .flatten()
+            .and_then(|uri| web_sys::Url::new(&uri).ok())
+            .map(|url| url.pathname().trim_end_matches('/').to_string())
+            .unwrap_or_default()
+    })
+}
+
+#[component]
+pub fn NavBar() -> impl IntoView {
+    view! {
+        <nav class="main-nav">
+            {Section::ALL.into_iter().map(|section| view! {
+                // The router marks the current page with `aria-current`
+                <A href=section.path() exact={section == Section::Home}>{t(section.label())}</A>
+            }).collect::<Vec<_>>()}
+        </nav>
+    }
+}
+
+#[component]
+pub fn HomeView() -> impl IntoView {
+    view! {
+        <div class="home-grid">
+            {Section::ALL.into_iter().filter_map(|section| section.summary().map(|summary| view! {
+                <A href=section.path() class="home-card">
+                    <div class="home-card-title">{t(section.label())}</div>
+                    <div class="home-card-text">{t(summary)}</div>
+                </A>
+            })).collect::<Vec<_>>()}
+        </div>
+    }
+}
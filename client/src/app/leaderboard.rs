@@ -0,0 +1,139 @@
+use crate::app::profile::profile_path;
+use crate::app::GameMode;
+use crate::i18n::{page_label, t, use_language, Msg};
+use crate::network::fetch_json;
+use leptos::{
+    component, create_effect, create_signal, event_target_value, spawn_local, view, IntoView,
+    ReadSignal, SignalGet, SignalSet, SignalUpdate, SignalWith,
+};
+use leptos_router::A;
+use shared::LeaderboardPage;
+
+const PAGE_SIZE: usize = 10;
+
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn LeaderboardPanel(game_mode: ReadSignal<GameMode>) -> impl IntoView {
+    let (show, set_show) = create_signal(false);
+    let (page, set_page) = create_signal(1usize);
+    let (search, set_search) = create_signal(String::new());
+    let (data, set_data) = create_signal(Option::<LeaderboardPage>::None);
+    let (error, set_error) = create_signal(Option::<Msg>::None);
+    let language = use_language();
+
+    // Refetch whenever the panel is opened or the page/search changes
+    create_effect(move |_| {
+        if !show.get() {
+            return;
+        }
+        let query = String::from(js_sys::encode_uri_component(&search.get()));
+        let path = format!(
+            "/leaderboard?page={}&per_page={PAGE_SIZE}&search={query}",
+            page.get()
+        );
+        spawn_local(async move {
+            match fetch_json::<LeaderboardPage>(&path).await {
+                Ok(result) => {
+                    set_data.set(Some(result));
+                    set_error.set(None);
+                }
+                Err(e) => {
+                    leptos::logging::log!("[NET] Failed to load leaderboard: {:?}", e);
+                    set_error.set(Some(Msg::LeaderboardLoadFailed));
+                }
+            }
+        });
+    });
+
+    let total_pages = move || {
+        data.with(|d| {
+            d.as_ref()
+                .map_or(1, |p| p.total.div_ceil(p.per_page.max(1)).max(1))
+        })
+    };
+
+    view! {
+        {move || {
+            if game_mode.get() != GameMode::Online {
+                return view! {}.into_view();
+            }
+
+            view! {
+                <div class="leaderboard-panel">
+                    <button
+                        class="control-btn"
+                        on:click=move |_| set_show.update(|v| *v = !*v)
+                    >
+                        {move || if show.get() { Msg::LeaderboardHide } else { Msg::LeaderboardShow }.text(language.get())}
+                    </button>
+
+                    {move || show.get().then(|| view! {
+                        <div class="leaderboard-body">
+                            <input
+                                type="text"
+                                class="leaderboard-search"
+                                placeholder=t(Msg::LeaderboardSearch)
+                                prop:value=move || search.get()
+                                on:input=move |ev| {
+                                    set_search.set(event_target_value(&ev));
+                                    set_page.set(1);
+                                }
+                            />
+
+                            {move || error.get().map(|e| view! { <div style="color: #f44336;">{t(e)}</div> })}
+
+                            <table class="leaderboard-table">
+                                <thead>
+                                    <tr>
+                                        <th>"#"</th>
+                                        <th>{t(Msg::LeaderboardPlayer)}</th>
+                                        <th>"Elo"</th>
+                                        <th>{t(Msg::Wins)}</th>
+                                        <th>{t(Msg::Losses)}</th>
+                                        <th>{t(Msg::Draws)}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {move || {
+                                        data.get().map(|p| p.entries).unwrap_or_default().into_iter().map(|entry| {
+                                            view! {
+                                                <tr>
+                                                    <td>{entry.rank}</td>
+                                                    <td class="clickable" title=t(Msg::LeaderboardViewProfile)>
+                                                        <A href=profile_path(&entry.name)>{entry.name}</A>
+                                                    </td>
+                                                    <td style="color: #f0d9b5; font-weight: bold;">{entry.rating}</td>
+                                                    <td>{entry.wins}</td>
+                                                    <td>{entry.losses}</td>
+                                                    <td>{entry.draws}</td>
+                                                </tr>
+                                            }
+                                        }).collect::<Vec<_>>()
+                                    }}
+                                </tbody>
+                            </table>
+
+                            <div class="leaderboard-pagination">
+                                <button
+                                    class="control-btn"
+                                    disabled=move || page.get() <= 1
+                                    on:click=move |_| set_page.update(|p| *p = p.saturating_sub(1).max(1))
+                                >
+                                    "◀"
+                                </button>
+                                <span>{move || page_label(language.get(), page.get(), total_pages())}</span>
+                                <button
+                                    class="control-btn"
+                                    disabled=move || page.get() >= total_pages()
+                                    on:click=move |_| set_page.update(|p| *p += 1)
+                                >
+                                    "▶"
+                                </button>
+                            </div>
+                        </div>
+                    })}
+                </div>
+            }.into_view()
+        }}
+    }
+}
@@ -0,0 +1,120 @@
+use crate::app::{GameMode, OnlineStatus};
+use crate::i18n::{t, use_language, Msg};
+use crate::network::{fetch_json, NetworkClient};
+use leptos::{
+    component, create_signal, spawn_local, view, web_sys, IntoView, ReadSignal, SignalGet,
+    SignalGetUntracked, SignalSet, WriteSignal,
+};
+use shared::{GameMessage, LiveGame};
+
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn LiveGamesPanel(
+    game_mode: ReadSignal<GameMode>,
+    online_status: ReadSignal<OnlineStatus>,
+    network_client: ReadSignal<Option<NetworkClient>>,
+    live_games: ReadSignal<Vec<LiveGame>>,
+    set_live_games: WriteSignal<Vec<LiveGame>>,
+) -> impl IntoView {
+    let (show, set_show) = create_signal(false);
+    let (error, set_error) = create_signal(Option::<Msg>::None);
+    let language = use_language();
+
+    // Opening loads the list over HTTP and subscribes to pushes; closing unsubscribes
+    let toggle = move |_: web_sys::MouseEvent| {
+        let open = !show.get_untracked();
+        set_show.set(open);
+        if let Some(client) = network_client.get_untracked() {
+            client.send(&if open {
+                GameMessage::SubscribeLiveGames
+            } else {
+                GameMessage::UnsubscribeLiveGames
+            });
+        }
+        if !open {
+            return;
+        }
+        spawn_local(async move {
+            match fetch_json::<Vec<LiveGame>>("/live").await {
+                Ok(games) => {
+                    set_live_games.set(games);
+                    set_error.set(None);
+                }
+                Err(e) => {
+                    leptos::logging::log!("[NET] Failed to load live games: {:?}", e);
+                    set_error.set(Some(Msg::LiveLoadFailed));
+                }
+            }
+        });
+    };
+
+    view! {
+        {move || {
+            let status = online_status.get();
+            if game_mode.get() != GameMode::Online
+                || !matches!(status, OnlineStatus::None | OnlineStatus::Spectating)
+            {
+                return view! {}.into_view();
+            }
+
+            view! {
+                <div class="leaderboard-panel">
+                    <button class="control-btn" on:click=toggle>
+                        {move || if show.get() { Msg::LiveHide } else { Msg::LiveShow }.text(language.get())}
+                    </button>
+
+                    {move || show.get().then(|| view! {
+                        <div class="leaderboard-body">
+                            {move || error.get().map(|e| view! { <div style="color: #f44336;">{t(e)}</div> })}
+
+                            {move || live_games.get().is_empty().then(|| view! {
+                                <div style="color: #aaa; text-align: center;">{t(Msg::LiveNone)}</div>
+                            })}
+
+                            <table class="leaderboard-table live-games-table">
+                                <thead>
+                                    <tr>
+                                        <th>"🔴 " {t(Msg::Red)}</th>
+                                        <th>"⚫ " {t(Msg::Black)}</th>
+                                        <th>{t(Msg::LiveMoves)}</th>
+                                        <th>{t(Msg::LiveOpening)}</th>
+                                        <th>{t(Msg::LiveTimeControl)}</th>
+                                        <th></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    {move || {
+                                        live_games.get().into_iter().map(|game| {
+                                            let game_id = game.game_id;
+                                            view! {
+                                                <tr>
+                                                    <td>{game.red_name}</td>
+                                                    <td>{game.black_name}</td>
+                                                    <td>{game.move_count}</td>
+                                                    <td>{game.opening.unwrap_or_else(|| "—".to_string())}</td>
+                                                    <td>{game.time_control.unwrap_or_else(|| Msg::FriendsUntimed.text(language.get()).to_string())}</td>
+                                                    <td>
+                                                        <button
+                                                            class="control-btn"
+                                                            on:click=move |_| {
+                                                                if let Some(client) = network_client.get_untracked() {
+                                                                    client.send(&GameMessage::Spectate(game_id.clone()));
+                                                                }
+                                                            }
+                                                        >
+                                                            {t(Msg::LiveWatch)}
+                                                        </button>
+                                                    </td>
+                                                </tr>
+                                            }
+                                        }).collect::<Vec<_>>()
+                                    }}
+                                </tbody>
+                            </table>
+                        </div>
+                    })}
+                </div>
+            }.into_view()
+        }}
+    }
+}
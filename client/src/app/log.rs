@@ -1,32 +1,85 @@
+use crate::i18n::{
+    annotation_text, moves_played_label, piece_name, t, use_language, viewing_ply_label, Msg,
+};
+use cotuong_core::logic::board::Color;
 use cotuong_core::logic::game::GameState;
-use leptos::{component, view, IntoView, ReadSignal, SignalGet};
+use leptos::{component, view, IntoView, ReadSignal, SignalGet, SignalSet, WriteSignal};
+
+/// Position reached after moving one ply back (`forward == false`) or forward from `current`.
+/// Moving past the last move returns `None`, i.e. the live position.
+pub const fn step_view_ply(current: Option<usize>, len: usize, forward: bool) -> Option<usize> {
+    let ply = match current {
+        Some(ply) => ply,
+        None => len,
+    };
+    if forward {
+        if ply + 1 >= len {
+            None
+        } else {
+            Some(ply + 1)
+        }
+    } else {
+        Some(ply.saturating_sub(1))
+    }
+}
 
 #[component]
-pub fn LogPanel(game_state: ReadSignal<GameState>) -> impl IntoView {
+pub fn LogPanel(
+    game_state: ReadSignal<GameState>,
+    /// Move count of the position being reviewed, `None` while following the live game.
+    view_ply: ReadSignal<Option<usize>>,
+    set_view_ply: WriteSignal<Option<usize>>,
+) -> impl IntoView {
+    let language = use_language();
+
     view! {
         <div class="log-panel">
             <div class="log-header">
-                <span>"📜 Biên bản"</span>
-                <span style="font-size: 0.8em; opacity: 0.8;">{move || format!("{} nước", game_state.get().history.len())}</span>
+                <span>{t(Msg::LogTitle)}</span>
+                <span style="font-size: 0.8em; opacity: 0.8;">{move || moves_played_label(language.get(), game_state.get().history.len())}</span>
             </div>
+            {move || view_ply.get().map(|ply| view! {
+                <div class="log-review">
+                    <span>{move || viewing_ply_label(language.get(), ply, game_state.get().history.len())}</span>
+                    <button class="control-btn" on:click=move |_| set_view_ply.set(None)>{t(Msg::LogLive)}</button>
+                </div>
+            })}
             <ul class="log-list">
                 {move || {
                     let state = game_state.get();
+                    let lang = language.get();
+                    let len = state.history.len();
+                    let selected = view_ply.get().unwrap_or(len);
                     state.history.iter().enumerate().rev().map(|(i, record)| {
-                        let turn_num = (i / 2) + 1;
-                        let side = if i % 2 == 0 { "🔴" } else { "⚫" };
+                        // Handicaps can give a side two moves in a row, so count by mover
+                        let turn_num = state.history[..=i]
+                            .iter()
+                            .filter(|r| r.color == record.color)
+                            .count();
+                        let side = if record.color == Color::Red { "🔴" } else { "⚫" };
+                        let ply = i + 1;
                         view! {
-                            <li class="log-item">
+                            <li
+                                class="log-item"
+                                class:selected=ply == selected
+                                on:click=move |_| set_view_ply.set((ply < len).then_some(ply))
+                            >
                                 <div class="move-info">
-                                    <span>{format!(
-                                        "{turn_num}. {side} ({},{}) → ({},{})",
-                                        record.from.row, record.from.col,
-                                        record.to.row, record.to.col
-                                    )}</span>
-                                    <span style="color: #f0d9b5;">{format!("{:?}", record.piece.piece_type)}</span>
+                                    <span>{match &record.notation {
+                                        Some(notation) => format!(
+                                            "{turn_num}. {side} {notation}{}",
+                                            record.glyph.as_deref().unwrap_or_default()
+                                        ),
+                                        None => format!(
+                                            "{turn_num}. {side} ({},{}) → ({},{})",
+                                            record.from.row, record.from.col,
+                                            record.to.row, record.to.col
+                                        ),
+                                    }}</span>
+                                    <span style="color: #f0d9b5;">{piece_name(record.piece.piece_type, lang)}</span>
                                 </div>
-                                {record.note.as_ref().map(|note| {
-                                    view! { <div class="ai-stats">{note}</div> }
+                                {record.annotation.as_ref().map(|annotation| {
+                                    view! { <div class="ai-stats">{annotation_text(annotation, lang)}</div> }
                                 }).into_view()}
                             </li>
                         }
@@ -41,7 +94,7 @@ pub fn LogPanel(game_state: ReadSignal<GameState>) -> impl IntoView {
 pub fn ThinkingIndicator(is_thinking: ReadSignal<bool>) -> impl IntoView {
     view! {
         <div class="thinking-indicator" style=move || if is_thinking.get() { "visibility: visible;" } else { "visibility: hidden;" }>
-            <span>"Máy đang nghĩ..."</span>
+            <span>{t(Msg::EngineThinking)}</span>
             <div style="width: 10px; height: 10px; background: #a8e6cf; border-radius: 50%; display: inline-block;"></div>
         </div>
     }
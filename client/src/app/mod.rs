@@ -1,28 +1,108 @@
+pub mod adaptive;
+pub mod arena;
+pub mod benchmark;
 pub mod config;
 pub mod controls;
+pub mod explorer;
 pub mod export;
+pub mod friends;
 pub mod game_app;
+pub mod import;
+pub mod install;
+pub mod layout;
+pub mod leaderboard;
+pub mod live_games;
 pub mod log;
 pub mod online;
+pub mod profile;
+pub mod puzzle;
+pub mod review;
+pub mod scoreboard;
+pub mod search_tree;
+pub mod share;
 pub mod styles;
+pub mod training;
+pub mod tutorial;
 
+use cotuong_core::engine::config::StrengthLimit;
 pub use game_app::App;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
     Level1,
     Level2,
     Level3,
     Level4,
     Level5,
+    /// Strength follows the player's results, see `adaptive::AdaptiveProfile`.
+    Adaptive,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl Difficulty {
+    /// The easy levels also weaken the engine; a short think alone still plays too well.
+    pub const fn strength_limit(self) -> Option<StrengthLimit> {
+        match self {
+            Self::Level1 => Some(StrengthLimit::BEGINNER),
+            Self::Level2 => Some(StrengthLimit::CASUAL),
+            Self::Level3 | Self::Level4 | Self::Level5 | Self::Adaptive => None,
+        }
+    }
+
+    /// How long the engine thinks per move; Adaptive's time follows the player's profile.
+    pub const fn think_time_ms(self) -> Option<u64> {
+        match self {
+            Self::Level1 => Some(1000),
+            Self::Level2 => Some(2000),
+            Self::Level3 => Some(5000),
+            Self::Level4 => Some(10000),
+            Self::Level5 => Some(20000),
+            Self::Adaptive => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameMode {
     HumanVsComputer,
     ComputerVsComputer,
     HumanVsHuman,
     Online,
+    /// Solve bundled training positions.
+    Puzzle,
+    /// Scripted lessons that only accept the moves they teach.
+    Tutorial,
+    /// Bundled positions played out against the engine.
+    Training,
+}
+
+/// When a move picked on the board waits for a tap on "Confirm" before it is played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MoveConfirmation {
+    #[default]
+    Off,
+    /// Online games and the two strongest fixed levels, where a slip costs the most.
+    Important,
+    Always,
+}
+
+impl MoveConfirmation {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Important, Self::Always];
+
+    pub const fn applies(self, mode: GameMode, difficulty: Difficulty) -> bool {
+        match self {
+            Self::Off => false,
+            Self::Important => matches!(
+                (mode, difficulty),
+                (GameMode::Online, _)
+                    | (
+                        GameMode::HumanVsComputer,
+                        Difficulty::Level4 | Difficulty::Level5
+                    )
+            ),
+            Self::Always => true,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -33,4 +113,5 @@ pub enum OnlineStatus {
     Playing,              // Đang chơi
     OpponentDisconnected, // Đối thủ ngắt kết nối
     GameEnded,            // Trận đấu kết thúc
+    Spectating,           // Đang xem ván của người khác
 }
@@ -1,10 +1,18 @@
 #![allow(clippy::option_option, clippy::too_many_lines)]
+use crate::app::controls::handicap_label;
 use crate::app::{GameMode, OnlineStatus};
+use crate::i18n::{
+    detail_text, move_number_label, move_time_left_label, opponent_away_label, t, use_language, Msg,
+};
 use crate::network::NetworkClient;
 use cotuong_core::logic::board::Color;
 use cotuong_core::logic::game::GameState;
-use leptos::{component, view, IntoView, ReadSignal, SignalGet, SignalSet, WriteSignal};
-use shared::GameMessage;
+use cotuong_core::logic::handicap::HandicapKind;
+use cotuong_core::session::EndReason;
+use leptos::{
+    component, event_target_value, view, IntoView, ReadSignal, SignalGet, SignalSet, WriteSignal,
+};
+use shared::{AbortReason, GameMessage, LiveGame, MatchScore};
 
 #[component]
 #[allow(clippy::too_many_arguments)]
@@ -14,48 +22,92 @@ pub fn OnlineStatusPanel(
     online_status: ReadSignal<OnlineStatus>,
     game_state: ReadSignal<GameState>,
     player_side: ReadSignal<Color>,
+    unacked_move: ReadSignal<Option<u32>>,
+    move_time_left: ReadSignal<Option<u64>>,
+    opponent_away: ReadSignal<Option<u64>>,
     network_client: ReadSignal<Option<NetworkClient>>,
     game_end_winner: ReadSignal<Option<Option<Color>>>,
     game_end_reason: ReadSignal<String>,
     is_ready_for_rematch: ReadSignal<bool>,
+    match_score: ReadSignal<MatchScore>,
+    spectated_game: ReadSignal<Option<LiveGame>>,
+    handicap: ReadSignal<HandicapKind>,
+    set_handicap: WriteSignal<HandicapKind>,
     set_online_status: WriteSignal<OnlineStatus>,
     set_game_state: WriteSignal<GameState>,
     set_is_ready_for_rematch: WriteSignal<bool>,
 ) -> impl IntoView {
+    let language = use_language();
+
     view! {
         {move || {
             let mode = game_mode.get();
             let status = online_status.get();
             let state = game_state.get();
             let side = player_side.get();
+            // Only rematches have a score worth showing
+            let score = match_score.get();
+            let score_view = move || (score.games() > 0).then(|| view! {
+                <div style="font-size: 1em; color: #f0d9b5;">
+                    {move || detail_text(Msg::OnlineScore, &score.to_string(), language.get())}
+                </div>
+            });
 
             if mode == GameMode::Online {
                 let status_content = match status {
                     OnlineStatus::None => view! {
                         <div style="display: flex; flex-direction: column; align-items: center; gap: 15px; padding: 20px;">
                             <div style="font-size: 1.2em; color: #a8e6cf;">
-                                "🌐 Chế độ chơi Online"
+                                {t(Msg::OnlineTitle)}
                             </div>
+                            <input
+                                type="text"
+                                class="leaderboard-search"
+                                placeholder=t(Msg::OnlineNamePlaceholder)
+                                maxlength="20"
+                                on:change=move |ev| {
+                                    if let Some(client) = network_client.get() {
+                                        client.send(&GameMessage::SetName(event_target_value(&ev)));
+                                    }
+                                }
+                            />
+                            <select
+                                title=t(Msg::OnlineHandicapTitle)
+                                on:change=move |ev| {
+                                    let val = event_target_value(&ev);
+                                    if let Some(kind) = HandicapKind::ALL.into_iter().find(|h| format!("{h:?}") == val) {
+                                        set_handicap.set(kind);
+                                    }
+                                }
+                                prop:value=move || format!("{:?}", handicap.get())
+                            >
+                                {HandicapKind::ALL.into_iter().map(|kind| view! {
+                                    <option value=format!("{kind:?}")>{t(handicap_label(kind))}</option>
+                                }).collect::<Vec<_>>()}
+                            </select>
                             <button
                                 class="control-btn btn-primary"
                                 style="padding: 15px 40px; font-size: 1.1em;"
                                 on:click=move |_| {
                                     if let Some(client) = network_client.get() {
-                                        client.send(&GameMessage::FindMatch);
+                                        match handicap.get() {
+                                            HandicapKind::None => client.send(&GameMessage::FindMatch),
+                                            kind => client.send(&GameMessage::FindHandicapMatch(kind)),
+                                        }
                                         set_online_status.set(OnlineStatus::Finding);
                                     } else {
                                         leptos::logging::log!("❌ Cannot find match: NetworkClient is not initialized (Server might be down)");
                                     }
                                 }
                             >
-                                "🎮 Tìm trận"
+                                {t(Msg::OnlineFindMatch)}
                             </button>
                         </div>
                     }.into_view(),
                     OnlineStatus::Finding => view! {
                         <div style="display: flex; flex-direction: column; align-items: center; gap: 15px; padding: 20px;">
                             <div class="thinking-indicator" style="visibility: visible;">
-                                <span style="font-size: 1.2em;">"🔍 Đang tìm trận..."</span>
+                                <span style="font-size: 1.2em;">{t(Msg::OnlineFinding)}</span>
                             </div>
                             <button
                                 class="control-btn btn-danger"
@@ -66,18 +118,23 @@ pub fn OnlineStatusPanel(
                                     set_online_status.set(OnlineStatus::None);
                                 }
                             >
-                                "❌ Huỷ tìm"
+                                {t(Msg::OnlineCancelFind)}
                             </button>
                         </div>
                     }.into_view(),
                     OnlineStatus::MatchFound => view! {
                         <div style="display: flex; flex-direction: column; align-items: center; gap: 10px; padding: 20px;">
                             <div style="font-size: 1.5em; color: #4CAF50; animation: pulse 1s infinite;">
-                                "✅ Đã tìm thấy đối thủ!"
+                                {t(Msg::OnlineMatchFound)}
                             </div>
                             <div style="font-size: 1.1em; color: #eee;">
-                                {format!("Bạn là bên: {}", if side == Color::Red { "🔴 Đỏ (đi trước)" } else { "⚫ Đen (đi sau)" })}
+                                {move || {
+                                    let lang = language.get();
+                                    let side = if side == Color::Red { Msg::OnlineRedFirst } else { Msg::OnlineBlackSecond };
+                                    detail_text(Msg::OnlineYourSide, side.text(lang), lang)
+                                }}
                             </div>
+                            {score_view()}
                         </div>
                     }.into_view(),
                     OnlineStatus::Playing => {
@@ -87,16 +144,32 @@ pub fn OnlineStatusPanel(
                         } else {
                             "background: linear-gradient(135deg, #555, #444); color: #aaa; padding: 15px 30px; border-radius: 12px; font-size: 1.2em; box-shadow: 0 2px 8px rgba(0,0,0,0.3);"
                         };
-                        let turn_text = if is_my_turn {
-                            if side == Color::Red { "🔴 Lượt của bạn!" } else { "⚫ Lượt của bạn!" }
-                        } else {
-                            "⏳ Đang chờ đối thủ..."
+                        let sending = unacked_move.get().is_some();
+                        let turn_text = move || {
+                            let lang = language.get();
+                            if is_my_turn {
+                                let dot = if side == Color::Red { "🔴" } else { "⚫" };
+                                format!("{dot} {}", Msg::OnlineYourTurn.text(lang))
+                            } else if sending {
+                                Msg::OnlineSending.text(lang).to_string()
+                            } else {
+                                Msg::OnlineWaiting.text(lang).to_string()
+                            }
                         };
                         view! {
                             <div style="display: flex; flex-direction: column; align-items: center; gap: 15px; padding: 15px;">
                                 <div style=turn_style>
                                     {turn_text}
                                 </div>
+                                {move || move_time_left.get().filter(|_| is_my_turn).map(|secs| view! {
+                                    <div class="move-time-warning">{move_time_left_label(language.get(), secs)}</div>
+                                })}
+                                {move || opponent_away.get().map(|secs| view! {
+                                    <div style="color: #FF9800;">
+                                        {opponent_away_label(language.get(), secs)}
+                                    </div>
+                                })}
+                                {score_view()}
                                 <div style="display: flex; gap: 10px;">
                                     <button
                                         class="control-btn btn-danger"
@@ -107,7 +180,7 @@ pub fn OnlineStatusPanel(
                                             }
                                         }
                                     >
-                                        "🏳️ Đầu hàng"
+                                        {t(Msg::OnlineResign)}
                                     </button>
                                 </div>
                             </div>
@@ -116,7 +189,7 @@ pub fn OnlineStatusPanel(
                     OnlineStatus::OpponentDisconnected => view! {
                         <div style="display: flex; flex-direction: column; align-items: center; gap: 15px; padding: 20px;">
                             <div style="font-size: 1.3em; color: #FF9800;">
-                                "⚠️ Đối thủ đã mất kết nối!"
+                                {t(Msg::OnlineOpponentLeft)}
                             </div>
                             <button
                                 class="control-btn btn-primary"
@@ -125,7 +198,7 @@ pub fn OnlineStatusPanel(
                                     set_game_state.set(GameState::new());
                                 }
                             >
-                                "🔄 Tìm trận mới"
+                                {t(Msg::OnlineNewMatch)}
                             </button>
                         </div>
                     }.into_view(),
@@ -136,35 +209,33 @@ pub fn OnlineStatusPanel(
 
                         // Determine win/loss status
                         let (result_icon, result_text, result_color) = match winner {
-                            Some(Some(w)) if w == side => ("🏆", "Bạn thắng!", "#4CAF50"),
-                            Some(Some(_)) => ("😔", "Bạn thua!", "#f44336"),
-                            Some(None) => ("🤝", "Hòa cờ!", "#FF9800"),
-                            None => ("🏁", "Kết thúc", "#aaa"),
+                            Some(Some(w)) if w == side => ("🏆", Msg::OnlineYouWin, "#4CAF50"),
+                            Some(Some(_)) => ("😔", Msg::OnlineYouLose, "#f44336"),
+                            Some(None) => ("🤝", Msg::OnlineDraw, "#FF9800"),
+                            None => ("🏁", Msg::OnlineGameOver, "#aaa"),
                         };
 
                         // Translate reason
-                        let reason_text = match reason.as_str() {
-                            "Checkmate" => "Chiếu hết",
-                            "Surrender" => "Đầu hàng",
-                            "Draw" => "Hòa",
-                            "Disconnect" => "Mất kết nối",
-                            _ => reason.as_str(),
+                        let reason_text = move || {
+                            let lang = language.get();
+                            let text = EndReason::parse(&reason).map_or(reason.as_str(), |r| end_reason_label(r).text(lang));
+                            detail_text(Msg::OnlineReason, text, lang)
                         };
 
                         view! {
                             <div style="display: flex; flex-direction: column; align-items: center; gap: 15px; padding: 20px;">
                                 <div style=format!("font-size: 2em; color: {};", result_color)>
-                                    {format!("{result_icon} {result_text}")}
+                                    {move || format!("{result_icon} {}", result_text.text(language.get()))}
                                 </div>
                                 <div style="font-size: 1em; color: #aaa;">
-                                    {format!("Lý do: {reason_text}")}
+                                    {reason_text}
                                 </div>
 
                                 <div style="display: flex; flex-direction: column; gap: 10px; width: 100%; align-items: center;">
                                     {if ready {
                                         view! {
                                             <div style="background: #4CAF50; color: white; padding: 12px 24px; border-radius: 8px; font-weight: bold;">
-                                                "✅ Đã sẵn sàng - Đang chờ đối thủ..."
+                                                {t(Msg::OnlineReadyWaiting)}
                                             </div>
                                         }.into_view()
                                     } else {
@@ -179,7 +250,7 @@ pub fn OnlineStatusPanel(
                                                     set_is_ready_for_rematch.set(true);
                                                 }
                                             >
-                                                "🎮 Sẵn sàng (Chơi tiếp)"
+                                                {t(Msg::OnlineRematch)}
                                             </button>
                                         }.into_view()
                                     }}
@@ -196,12 +267,61 @@ pub fn OnlineStatusPanel(
                                             set_is_ready_for_rematch.set(false);
                                         }
                                     >
-                                        "🚪 Thoát"
+                                        {t(Msg::OnlineLeave)}
                                     </button>
                                 </div>
                             </div>
                         }.into_view()
                     },
+                    OnlineStatus::Spectating => {
+                        let game = spectated_game.get();
+                        let players = game.as_ref().map_or_else(String::new, |g| {
+                            format!("🔴 {} vs ⚫ {}", g.red_name, g.black_name)
+                        });
+                        let winner = game_end_winner.get();
+                        let move_count = game.as_ref().map_or(0, |g| g.move_count);
+                        let red_to_move = state.turn == Color::Red;
+                        let progress = move || {
+                            let lang = language.get();
+                            match winner {
+                                Some(Some(Color::Red)) => format!("🏁 {}", Msg::RedWins.text(lang)),
+                                Some(Some(Color::Black)) => format!("🏁 {}", Msg::BlackWins.text(lang)),
+                                Some(None) => format!("🏁 {}", Msg::OnlineDraw.text(lang)),
+                                None => format!(
+                                    "{} - {}",
+                                    move_number_label(lang, move_count),
+                                    if red_to_move {
+                                        format!("🔴 {}", Msg::RedToMove.text(lang))
+                                    } else {
+                                        format!("⚫ {}", Msg::BlackToMove.text(lang))
+                                    }
+                                ),
+                            }
+                        };
+
+                        view! {
+                            <div style="display: flex; flex-direction: column; align-items: center; gap: 12px; padding: 20px;">
+                                <div style="font-size: 1.2em; color: #a8e6cf;">
+                                    {t(Msg::SpectateTitle)}
+                                </div>
+                                <div style="font-size: 1.1em; color: #eee;">{players}</div>
+                                <div style="color: #aaa;">{progress}</div>
+                                <button
+                                    class="control-btn"
+                                    style="padding: 10px 20px;"
+                                    on:click=move |_| {
+                                        if let Some(client) = network_client.get() {
+                                            client.send(&GameMessage::StopSpectating);
+                                        }
+                                        set_online_status.set(OnlineStatus::None);
+                                        set_game_state.set(GameState::new());
+                                    }
+                                >
+                                    {t(Msg::SpectateStop)}
+                                </button>
+                            </div>
+                        }.into_view()
+                    },
                 };
 
                 view! {
@@ -215,3 +335,31 @@ pub fn OnlineStatusPanel(
         }}
     }
 }
+
+const fn end_reason_label(reason: EndReason) -> Msg {
+    match reason {
+        EndReason::Checkmate => Msg::EndCheckmate,
+        EndReason::Resignation => Msg::EndResignation,
+        EndReason::DrawAgreement => Msg::EndDrawAgreement,
+        EndReason::Timeout => Msg::EndTimeout,
+        EndReason::Abandoned => Msg::EndAbandoned,
+        EndReason::Adjudication => Msg::EndAdjudication,
+        EndReason::Terminated => Msg::EndTerminated,
+    }
+}
+
+/// User-facing text for each reason a game is aborted.
+pub const fn abort_reason_message(reason: AbortReason) -> Msg {
+    match reason {
+        AbortReason::NoFirstMove => Msg::AbortNoFirstMove,
+    }
+}
+
+#[component]
+pub fn ErrorToast(message: ReadSignal<Option<String>>) -> impl IntoView {
+    view! {
+        {move || message.get().map(|text| view! {
+            <div class="error-toast">{text}</div>
+        })}
+    }
+}
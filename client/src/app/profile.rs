@@ -0,0 +1,172 @@
+use crate::app::layout::Section;
+use crate::i18n::{games_count_label, t, use_language, Msg};
+use crate::network::fetch_json;
+use leptos::{
+    component, create_signal, event_target_value, spawn_local, view, Callback, IntoView, SignalGet,
+    SignalGetUntracked, SignalSet, SignalWith,
+};
+use leptos_router::{use_navigate, use_params_map, NavigateOptions};
+use shared::{PlayerProfile, RatingPoint};
+
+const CHART_WIDTH: f64 = 300.0;
+const CHART_HEIGHT: f64 = 80.0;
+
+/// SVG polyline points for the rating history, scaled to the chart with some headroom.
+fn chart_points(history: &[RatingPoint]) -> String {
+    let ratings: Vec<f64> = history.iter().map(|p| f64::from(p.rating)).collect();
+    let min = ratings.iter().copied().fold(f64::INFINITY, f64::min) - 10.0;
+    let max = ratings.iter().copied().fold(f64::NEG_INFINITY, f64::max) + 10.0;
+    let steps = u32::try_from(ratings.len().saturating_sub(1))
+        .map_or(1.0, f64::from)
+        .max(1.0);
+    ratings
+        .iter()
+        .zip(0u32..)
+        .map(|(rating, i)| {
+            let x = f64::from(i) / steps * CHART_WIDTH;
+            let y = CHART_HEIGHT - (rating - min) / (max - min) * CHART_HEIGHT;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A player's statistics, loaded from `/players/{name}/profile`.
+#[component]
+pub fn ProfileView(name: String, on_back: Callback<()>) -> impl IntoView {
+    let (profile, set_profile) = create_signal(Option::<PlayerProfile>::None);
+    let (error, set_error) = create_signal(Option::<Msg>::None);
+    let language = use_language();
+
+    let path = format!(
+        "/players/{}/profile",
+        String::from(js_sys::encode_uri_component(&name))
+    );
+    spawn_local(async move {
+        match fetch_json::<PlayerProfile>(&path).await {
+            Ok(result) => set_profile.set(Some(result)),
+            Err(e) => {
+                leptos::logging::log!("[NET] Failed to load profile: {:?}", e);
+                set_error.set(Some(Msg::ProfileLoadFailed));
+            }
+        }
+    });
+
+    view! {
+        <div class="profile-view">
+            <button class="control-btn" on:click=move |_| on_back.call(())>
+                {t(Msg::ProfileBack)}
+            </button>
+
+            {move || error.get().map(|e| view! { <div style="color: #f44336;">{t(e)}</div> })}
+
+            {move || profile.get().map(|p| {
+                let games = p.wins + p.losses + p.draws;
+                view! {
+                    <h3>{p.name}</h3>
+                    <div class="profile-stats">
+                        <div><span>"Elo"</span><strong>{p.rating}</strong></div>
+                        <div><span>{t(Msg::ProfileGames)}</span><strong>{games}</strong></div>
+                        <div><span>{t(Msg::Wins)}</span><strong>{p.wins}</strong></div>
+                        <div><span>{t(Msg::Draws)}</span><strong>{p.draws}</strong></div>
+                        <div><span>{t(Msg::Losses)}</span><strong>{p.losses}</strong></div>
+                        <div>
+                            <span>{t(Msg::ReviewAccuracy)}</span>
+                            <strong>
+                                {p.average_accuracy.map_or_else(|| "—".to_string(), |a| format!("{a:.1}%"))}
+                            </strong>
+                        </div>
+                    </div>
+
+                    <div class="profile-section">{t(Msg::ProfileEloChart)}</div>
+                    {if p.rating_history.len() < 2 {
+                        view! { <div style="color: #aaa;">{t(Msg::ProfileTooFewGames)}</div> }.into_view()
+                    } else {
+                        view! {
+                            <svg
+                                class="profile-chart"
+                                viewBox=format!("0 0 {CHART_WIDTH} {CHART_HEIGHT}")
+                                preserveAspectRatio="none"
+                            >
+                                <polyline points=chart_points(&p.rating_history) />
+                            </svg>
+                        }.into_view()
+                    }}
+
+                    <div class="profile-section">{t(Msg::ProfileFavouriteOpenings)}</div>
+                    {if p.favorite_openings.is_empty() {
+                        view! { <div style="color: #aaa;">{t(Msg::ProfileNoOpenings)}</div> }.into_view()
+                    } else {
+                        view! {
+                            <ul class="profile-openings">
+                                {p.favorite_openings.into_iter().map(|o| view! {
+                                    <li>{o.name} <span>{move || games_count_label(language.get(), o.games)}</span></li>
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                        }.into_view()
+                    }}
+                }
+            })}
+        </div>
+    }
+}
+
+/// Route of a player's profile page.
+pub fn profile_path(name: &str) -> String {
+    format!(
+        "{}/{}",
+        Section::Profile.path(),
+        String::from(js_sys::encode_uri_component(name))
+    )
+}
+
+/// The `/profile` page: a name lookup, and the player's profile at `/profile/{name}`.
+#[component]
+pub fn ProfilePage() -> impl IntoView {
+    let params = use_params_map();
+    let (query, set_query) = create_signal(String::new());
+    let name = move || {
+        params.with(|p| {
+            p.get("name")
+                .filter(|n| !n.is_empty())
+                .map(|n| js_sys::decode_uri_component(n).map_or_else(|_| n.clone(), String::from))
+        })
+    };
+    let navigate = use_navigate();
+    let open = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let query = query.get_untracked();
+        let name = query.trim();
+        if !name.is_empty() {
+            navigate(&profile_path(name), NavigateOptions::default());
+        }
+    };
+    let back = use_navigate();
+
+    view! {
+        <div class="leaderboard-panel">
+            <form class="leaderboard-body profile-lookup" on:submit=open>
+                <input
+                    type="text"
+                    class="leaderboard-search"
+                    placeholder=t(Msg::ProfileLookup)
+                    prop:value=move || query.get()
+                    on:input=move |ev| set_query.set(event_target_value(&ev))
+                />
+                <button type="submit" class="control-btn">{t(Msg::ProfileOpen)}</button>
+            </form>
+
+            {move || name().map(|name| {
+                let back = back.clone();
+                view! {
+                    <div class="leaderboard-body">
+                        <ProfileView
+                            name=name
+                            on_back=Callback::new(move |()| back(Section::Online.path(), NavigateOptions::default()))
+                        />
+                    </div>
+                }
+            })}
+        </div>
+    }
+}
@@ -0,0 +1,167 @@
+use crate::app::GameMode;
+use crate::i18n::{mate_in_label, puzzle_number_label, puzzle_streak_label, t, use_language, Msg};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::puzzle::{starter_puzzles, PuzzleStep, PuzzleTheme};
+use leptos::{
+    component, create_effect, create_signal, set_timeout, store_value, view, IntoView, ReadSignal,
+    SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, WriteSignal,
+};
+use std::time::Duration;
+
+/// Delay before the opponent's scripted reply, so the user sees their own move land first.
+const REPLY_DELAY_MS: u64 = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PuzzleProgress {
+    Solving,
+    Solved,
+    Failed,
+}
+
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn PuzzlePanel(
+    game_mode: ReadSignal<GameMode>,
+    game_state: ReadSignal<GameState>,
+    set_game_state: WriteSignal<GameState>,
+    set_player_side: WriteSignal<Color>,
+    best_streak: ReadSignal<u32>,
+    set_best_streak: WriteSignal<u32>,
+) -> impl IntoView {
+    let puzzles = store_value(starter_puzzles());
+    let (index, set_index) = create_signal(0usize);
+    // Number of moves already played from the puzzle position
+    let (ply, set_ply) = create_signal(0usize);
+    let (progress, set_progress) = create_signal(PuzzleProgress::Solving);
+    // Side the user plays, which moves first in the puzzle position
+    let (solver, set_solver) = create_signal(Color::Red);
+    let (streak, set_streak) = create_signal(0u32);
+    let language = use_language();
+
+    let load = move |i: usize| {
+        let Some(Ok(state)) = puzzles.with_value(|p| p.get(i).map(|puzzle| puzzle.initial_state()))
+        else {
+            return;
+        };
+        set_player_side.set(state.turn);
+        set_solver.set(state.turn);
+        set_game_state.set(state);
+        set_index.set(i);
+        set_ply.set(0);
+        set_progress.set(PuzzleProgress::Solving);
+    };
+
+    // Entering the mode opens the current puzzle
+    create_effect(move |_| {
+        if game_mode.get() == GameMode::Puzzle {
+            load(index.get_untracked());
+        }
+    });
+
+    // Judge each move the user plays on the board
+    create_effect(move |_| {
+        let state = game_state.get();
+        if game_mode.get_untracked() != GameMode::Puzzle
+            || progress.get_untracked() != PuzzleProgress::Solving
+        {
+            return;
+        }
+        let current_ply = ply.get_untracked();
+        if state.history.len() != current_ply + 1 {
+            return;
+        }
+        let Some(last) = state.history.last() else {
+            return;
+        };
+        let Some(step) = puzzles.with_value(|p| {
+            p.get(index.get_untracked())
+                .map(|puzzle| puzzle.judge(current_ply, last.from, last.to, &state))
+        }) else {
+            return;
+        };
+
+        match step {
+            PuzzleStep::Continue(reply) => {
+                set_ply.set(current_ply + 2);
+                let Some((from, to)) = reply else {
+                    return;
+                };
+                set_timeout(
+                    move || {
+                        let mut next = game_state.get_untracked();
+                        if next.make_move(from, to).is_ok() {
+                            set_game_state.set(next);
+                        }
+                    },
+                    Duration::from_millis(REPLY_DELAY_MS),
+                );
+            }
+            PuzzleStep::Solved => {
+                set_progress.set(PuzzleProgress::Solved);
+                set_streak.update(|s| *s += 1);
+                if streak.get_untracked() > best_streak.get_untracked() {
+                    set_best_streak.set(streak.get_untracked());
+                }
+            }
+            PuzzleStep::Wrong => {
+                set_progress.set(PuzzleProgress::Failed);
+                set_streak.set(0);
+            }
+        }
+    });
+
+    let next_puzzle = move |_| {
+        let count = puzzles.with_value(Vec::len);
+        if count > 0 {
+            load((index.get_untracked() + 1) % count);
+        }
+    };
+
+    view! {
+        {move || {
+            if game_mode.get() != GameMode::Puzzle {
+                return view! {}.into_view();
+            }
+
+            let lang = language.get();
+            let count = puzzles.with_value(Vec::len);
+            let theme = puzzles.with_value(|p| p.get(index.get()).map(|puzzle| puzzle.theme));
+            let side = if game_state.get().turn == solver.get() {
+                Msg::PuzzleYourMove
+            } else {
+                Msg::PuzzleOpponentMoving
+            };
+
+            view! {
+                <div class="puzzle-panel">
+                    <div class="puzzle-header">
+                        <span>{puzzle_number_label(lang, index.get() + 1, count)}</span>
+                        <span>{match theme {
+                            Some(PuzzleTheme::MateIn(n)) => mate_in_label(lang, n),
+                            Some(PuzzleTheme::Tactic) => Msg::PuzzleTactic.text(lang).to_string(),
+                            None => String::new(),
+                        }}</span>
+                    </div>
+                    <div class="puzzle-streak">
+                        {move || puzzle_streak_label(language.get(), streak.get(), best_streak.get())}
+                    </div>
+                    {move || match progress.get() {
+                        PuzzleProgress::Solving => view! {
+                            <div class="puzzle-status">{t(side)}</div>
+                        }.into_view(),
+                        PuzzleProgress::Solved => view! {
+                            <div class="puzzle-status solved">{t(Msg::PuzzleCorrect)}</div>
+                            <button class="control-btn btn-primary" on:click=next_puzzle>{t(Msg::NextPosition)}</button>
+                        }.into_view(),
+                        PuzzleProgress::Failed => view! {
+                            <div class="puzzle-status failed">{t(Msg::PuzzleWrong)}</div>
+                            <button class="control-btn btn-warning" on:click=move |_| load(index.get_untracked())>{t(Msg::TryAgain)}</button>
+                            <button class="control-btn" on:click=next_puzzle>{t(Msg::PuzzleSkip)}</button>
+                        }.into_view(),
+                    }}
+                </div>
+            }.into_view()
+        }}
+    }
+}
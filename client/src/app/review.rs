@@ -0,0 +1,232 @@
+use crate::i18n::{
+    better_move_label, move_number_label, review_progress_label, t, use_language, Msg,
+};
+use cotuong_core::engine::match_runner::format_pgn_move;
+use cotuong_core::engine::review::{win_chance, GameReview, Judgement, SideSummary};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::{GameState, GameStatus};
+use leptos::{
+    component, view, Callable, Callback, IntoView, ReadSignal, SignalGet, SignalSet, SignalWith,
+    WriteSignal,
+};
+
+#[derive(Debug, Clone, Default)]
+pub enum ReviewStatus {
+    #[default]
+    Idle,
+    /// Positions searched so far out of the total, and their scores from Red's side.
+    Running(usize, usize, Vec<i32>),
+    Done(GameReview),
+}
+
+/// Size of the evaluation graph in SVG units; it is stretched to the panel's width.
+const GRAPH_WIDTH: f64 = 300.0;
+const GRAPH_HEIGHT: f64 = 80.0;
+
+pub const fn judgement_label(judgement: Judgement) -> Msg {
+    match judgement {
+        Judgement::Inaccuracy => Msg::JudgeInaccuracy,
+        Judgement::Mistake => Msg::JudgeMistake,
+        Judgement::Blunder => Msg::JudgeBlunder,
+    }
+}
+
+fn summary_rows(red: &SideSummary, black: &SideSummary) -> Vec<(Msg, String, String)> {
+    vec![
+        (
+            Msg::ReviewAccuracy,
+            format!("{:.1}%", red.accuracy),
+            format!("{:.1}%", black.accuracy),
+        ),
+        (
+            Msg::ReviewAvgLoss,
+            format!("{:.0}", red.avg_cp_loss),
+            format!("{:.0}", black.avg_cp_loss),
+        ),
+        (
+            judgement_label(Judgement::Inaccuracy),
+            red.inaccuracies.to_string(),
+            black.inaccuracies.to_string(),
+        ),
+        (
+            judgement_label(Judgement::Mistake),
+            red.mistakes.to_string(),
+            black.mistakes.to_string(),
+        ),
+        (
+            judgement_label(Judgement::Blunder),
+            red.blunders.to_string(),
+            black.blunders.to_string(),
+        ),
+    ]
+}
+
+/// Post-game review: starts the engine pass over a finished game, then shows each side's
+/// accuracy and the mistakes. Clicking a mistake shows the position it was played in.
+#[component]
+pub fn ReviewPanel(
+    game_state: ReadSignal<GameState>,
+    review: ReadSignal<ReviewStatus>,
+    on_review: Callback<()>,
+    set_view_ply: WriteSignal<Option<usize>>,
+) -> impl IntoView {
+    let language = use_language();
+
+    view! {
+        {move || {
+            let lang = language.get();
+            let (finished, has_moves) = game_state
+                .with(|s| (s.status != GameStatus::Playing, !s.history.is_empty()));
+            let body = match review.get() {
+                ReviewStatus::Idle if finished && has_moves => view! {
+                    <button class="control-btn" on:click=move |_| on_review.call(())>{t(Msg::ReviewGame)}</button>
+                }.into_view(),
+                ReviewStatus::Idle => return view! {}.into_view(),
+                ReviewStatus::Running(done, total, _) => {
+                    let percent = done * 100 / total.max(1);
+                    view! {
+                        <div class="explorer-empty">{review_progress_label(lang, done, total)}</div>
+                        <div class="explorer-bar">
+                            <div style=format!("width: {percent}%;")></div>
+                        </div>
+                    }.into_view()
+                }
+                ReviewStatus::Done(result) => {
+                    let rows = summary_rows(&result.red, &result.black);
+                    let mistakes: Vec<_> = result.mistakes().cloned().collect();
+                    view! {
+                        <table class="review-summary">
+                            <tr><th></th><th>"🔴 " {t(Msg::Red)}</th><th>"⚫ " {t(Msg::Black)}</th></tr>
+                            {rows.into_iter().map(|(label, red, black)| view! {
+                                <tr><td>{label.text(lang)}</td><td>{red}</td><td>{black}</td></tr>
+                            }).collect::<Vec<_>>()}
+                        </table>
+                        {mistakes.is_empty().then(|| view! {
+                            <div class="explorer-empty">{t(Msg::ReviewNoMistakes)}</div>
+                        })}
+                        <ul class="log-list">
+                            {mistakes.into_iter().map(|m| {
+                                let side = if m.mover == Color::Red { "🔴" } else { "⚫" };
+                                let (from, to) = m.played;
+                                let glyph = m.judgement.map_or("", Judgement::glyph);
+                                let label = m.judgement.map_or("", |j| judgement_label(j).text(lang));
+                                let ply = m.ply;
+                                view! {
+                                    <li class="explorer-item clickable" on:click=move |_| set_view_ply.set(Some(ply))>
+                                        <div class="move-info">
+                                            <span>{format!("{}. {side} {}{glyph}", ply / 2 + 1, format_pgn_move(from, to))}</span>
+                                            <span style="color: #f0d9b5;">{format!("-{}", m.cp_loss)}</span>
+                                        </div>
+                                        <div class="ai-stats">{m.best.map_or_else(
+                                            || label.to_string(),
+                                            |(from, to)| better_move_label(lang, label, &format_pgn_move(from, to)),
+                                        )}</div>
+                                    </li>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </ul>
+                    }.into_view()
+                }
+            };
+
+            view! {
+                <div class="review-panel">
+                    <div class="log-header">
+                        <span>{t(Msg::ReviewGame)}</span>
+                    </div>
+                    {body}
+                </div>
+            }.into_view()
+        }}
+    }
+}
+
+/// Red's score as a height on the evaluation graph, Red's advantage upwards.
+fn graph_y(score: i32) -> f64 {
+    GRAPH_HEIGHT * (1.0 - win_chance(score) / 100.0)
+}
+
+/// Advantage graph of the reviewed game, one point per position, drawn as the review runs.
+/// Clicking it shows the position at that move.
+#[component]
+pub fn EvalGraph(
+    review: ReadSignal<ReviewStatus>,
+    view_ply: ReadSignal<Option<usize>>,
+    set_view_ply: WriteSignal<Option<usize>>,
+) -> impl IntoView {
+    let language = use_language();
+
+    view! {
+        {move || {
+            let (evals, positions) = review.with(|r| match r {
+                ReviewStatus::Idle => (Vec::new(), 0),
+                ReviewStatus::Running(_, total, evals) => (evals.clone(), *total),
+                ReviewStatus::Done(result) => (result.evals.clone(), result.evals.len()),
+            });
+            if positions < 2 || evals.is_empty() {
+                return None;
+            }
+            let moves = positions - 1;
+            #[allow(clippy::cast_precision_loss)]
+            let step = GRAPH_WIDTH / moves as f64;
+            #[allow(clippy::cast_precision_loss)]
+            let x = move |ply: usize| ply as f64 * step;
+
+            let line: Vec<String> = evals
+                .iter()
+                .enumerate()
+                .map(|(ply, &score)| format!("{:.1},{:.1}", x(ply), graph_y(score)))
+                .collect();
+            let last = evals.len() - 1;
+            let area = format!("0,{GRAPH_HEIGHT} {} {:.1},{GRAPH_HEIGHT}", line.join(" "), x(last));
+            let shown = move || view_ply.get().unwrap_or(moves).min(last);
+            let marker = move || x(shown());
+            let caption = move || {
+                let ply = shown();
+                let score = evals.get(ply).copied().unwrap_or_default();
+                let lang = language.get();
+                let position = if ply == 0 {
+                    Msg::ReviewStartPosition.text(lang).to_string()
+                } else {
+                    move_number_label(lang, ply.div_ceil(2))
+                };
+                format!("{position} · {:+.1}", f64::from(score) / 100.0)
+            };
+
+            Some(view! {
+                <div class="review-panel eval-graph-panel">
+                    <div class="log-header">
+                        <span>{t(Msg::ReviewGraph)}</span>
+                        <span class="eval-graph-caption">{caption}</span>
+                    </div>
+                    <svg
+                        class="eval-graph"
+                        viewBox=format!("0 0 {GRAPH_WIDTH} {GRAPH_HEIGHT}")
+                        preserveAspectRatio="none"
+                    >
+                        <polygon points=area fill="rgba(211, 47, 47, 0.45)" />
+                        <line
+                            x1="0" y1={GRAPH_HEIGHT / 2.0} x2=GRAPH_WIDTH y2={GRAPH_HEIGHT / 2.0}
+                            stroke="#777" stroke-dasharray="4 3" vector-effect="non-scaling-stroke"
+                        />
+                        <polyline
+                            points=line.join(" ")
+                            fill="none" stroke="#f0d9b5" stroke-width="1.5" vector-effect="non-scaling-stroke"
+                        />
+                        <line
+                            x1=marker y1="0" x2=marker y2=GRAPH_HEIGHT
+                            stroke="#64b5f6" stroke-width="2" vector-effect="non-scaling-stroke"
+                        />
+                        {(0..=last).map(|ply| view! {
+                            <rect
+                                x={x(ply) - step / 2.0} y="0" width=step height=GRAPH_HEIGHT
+                                fill="transparent"
+                                on:click=move |_| set_view_ply.set((ply < moves).then_some(ply))
+                            />
+                        }).collect::<Vec<_>>()}
+                    </svg>
+                </div>
+            })
+        }}
+    }
+}
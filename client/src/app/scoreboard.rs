@@ -0,0 +1,162 @@
+use crate::app::adaptive::GameOutcome;
+use crate::app::{Difficulty, GameMode};
+use crate::i18n::{score_label, streak_label, t, use_language, Msg};
+use leptos::{
+    component, view, IntoView, ReadSignal, SignalGet, SignalUpdate, SignalWith, WriteSignal,
+};
+use serde::{Deserialize, Serialize};
+
+/// Results in one mode and level, from the human player's side; Human vs Human games are
+/// counted from Red's.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Games in a row with the same result: positive for wins, negative for losses.
+    pub streak: i32,
+    pub best_streak: u32,
+}
+
+impl ScoreRecord {
+    pub fn record(&mut self, outcome: GameOutcome) {
+        match outcome {
+            GameOutcome::Win => {
+                self.wins += 1;
+                self.streak = self.streak.max(0) + 1;
+            }
+            GameOutcome::Loss => {
+                self.losses += 1;
+                self.streak = self.streak.min(0) - 1;
+            }
+            GameOutcome::Draw => {
+                self.draws += 1;
+                self.streak = 0;
+            }
+        }
+        if let Ok(wins) = u32::try_from(self.streak) {
+            self.best_streak = self.best_streak.max(wins);
+        }
+    }
+
+    pub const fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+}
+
+/// The mode and level a finished offline game is scored under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreKey {
+    pub mode: GameMode,
+    /// The engine's level; none without an engine opponent.
+    pub difficulty: Option<Difficulty>,
+}
+
+impl ScoreKey {
+    /// Only games against the engine and local two-player games keep a score.
+    pub const fn for_game(mode: GameMode, difficulty: Difficulty) -> Option<Self> {
+        match mode {
+            GameMode::HumanVsComputer => Some(Self {
+                mode,
+                difficulty: Some(difficulty),
+            }),
+            GameMode::HumanVsHuman => Some(Self {
+                mode,
+                difficulty: None,
+            }),
+            GameMode::ComputerVsComputer
+            | GameMode::Online
+            | GameMode::Puzzle
+            | GameMode::Tutorial
+            | GameMode::Training => None,
+        }
+    }
+}
+
+/// A score per mode and level.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scoreboard {
+    entries: Vec<(ScoreKey, ScoreRecord)>,
+}
+
+impl Scoreboard {
+    pub fn get(&self, key: ScoreKey) -> ScoreRecord {
+        self.entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, record)| *record)
+            .unwrap_or_default()
+    }
+
+    pub fn record(&mut self, key: ScoreKey, outcome: GameOutcome) {
+        if let Some((_, record)) = self.entries.iter_mut().find(|(k, _)| *k == key) {
+            record.record(outcome);
+        } else {
+            let mut record = ScoreRecord::default();
+            record.record(outcome);
+            self.entries.push((key, record));
+        }
+    }
+
+    pub fn clear(&mut self, key: ScoreKey) {
+        self.entries.retain(|(k, _)| *k != key);
+    }
+}
+
+/// Wins, draws and losses against the current level, since the page was opened and in total.
+#[component]
+pub fn ScoreboardPanel(
+    game_mode: ReadSignal<GameMode>,
+    difficulty: ReadSignal<Difficulty>,
+    session: ReadSignal<Scoreboard>,
+    set_session: WriteSignal<Scoreboard>,
+    /// Kept in the saved preferences.
+    saved: ReadSignal<Scoreboard>,
+    set_saved: WriteSignal<Scoreboard>,
+) -> impl IntoView {
+    let language = use_language();
+
+    view! {
+        {move || {
+            let key = ScoreKey::for_game(game_mode.get(), difficulty.get())?;
+            let two_player = key.mode == GameMode::HumanVsHuman;
+            let line = move |record: ScoreRecord| {
+                let lang = language.get();
+                let score = score_label(lang, record.wins, record.draws, record.losses, two_player);
+                match streak_label(lang, record.streak).filter(|_| !two_player) {
+                    Some(streak) => format!("{score} · {streak}"),
+                    None => score,
+                }
+            };
+            let best = move || {
+                let best = saved.with(|s| s.get(key)).best_streak;
+                (!two_player && best > 0)
+                    .then(|| format!("{}: {best}", Msg::ScoreBestStreak.text(language.get())))
+            };
+
+            Some(view! {
+                <div class="puzzle-panel scoreboard">
+                    <div class="puzzle-header">
+                        <span>{t(Msg::ScoreTitle)}</span>
+                        {move || (saved.with(|s| s.get(key)).games() > 0).then(|| view! {
+                            <button
+                                class="control-btn"
+                                on:click=move |_| {
+                                    set_session.update(|s| s.clear(key));
+                                    set_saved.update(|s| s.clear(key));
+                                }
+                            >
+                                {t(Msg::ScoreReset)}
+                            </button>
+                        })}
+                    </div>
+                    <div>{t(Msg::ScoreSession)} ": " {move || line(session.with(|s| s.get(key)))}</div>
+                    <div>{t(Msg::ScoreAllTime)} ": " {move || line(saved.with(|s| s.get(key)))}</div>
+                    {move || best().map(|best| view! { <div class="puzzle-streak">{best}</div> })}
+                </div>
+            })
+        }}
+    }
+}
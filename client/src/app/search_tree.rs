@@ -0,0 +1,121 @@
+use crate::i18n::{t, use_language, Language, Msg};
+use cotuong_core::engine::match_runner::format_iccs;
+use cotuong_core::engine::trace::{TraceKind, TraceNode, TraceReason};
+use cotuong_core::engine::Move;
+use cotuong_core::logic::board::BoardCoordinate;
+use leptos::{
+    component, view, Callable, Callback, CollectView, IntoView, ReadSignal, SignalGet, View,
+};
+
+/// Depth the inspector searches to; the recorded tree stops a few plies below the root.
+pub const TRACE_DEPTH: u8 = 5;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TraceStatus {
+    #[default]
+    Idle,
+    Running,
+    /// `None` if the search finished no pass.
+    Done(Option<TraceNode>),
+}
+
+const fn kind_label(kind: TraceKind, language: Language) -> &'static str {
+    match kind {
+        TraceKind::Root => Msg::TraceRoot.text(language),
+        TraceKind::Move | TraceKind::Pruned => "",
+        TraceKind::NullMove => Msg::TraceNullMove.text(language),
+        TraceKind::ProbCut => "ProbCut",
+        TraceKind::Iid => "IID",
+        TraceKind::Singular => Msg::TraceSingular.text(language),
+    }
+}
+
+const fn reason_label(reason: TraceReason) -> Msg {
+    match reason {
+        TraceReason::MateDistance => Msg::TraceMateDistance,
+        TraceReason::Repetition => Msg::TraceRepetition,
+        TraceReason::TranspositionTable => Msg::TraceTranspositionTable,
+        TraceReason::Quiescence => Msg::TraceQuiescence,
+        TraceReason::ProbCut => Msg::TraceProbCut,
+        TraceReason::ReverseFutility => Msg::TraceReverseFutility,
+        TraceReason::NullMove => Msg::TraceNullMoveCut,
+        TraceReason::NoLegalMoves => Msg::TraceNoLegalMoves,
+        TraceReason::MatingMove => Msg::TraceMatingMove,
+        TraceReason::BetaCutoff => Msg::TraceBetaCutoff,
+        TraceReason::TimeUp => Msg::TraceTimeUp,
+        TraceReason::DynamicLimit => Msg::TraceDynamicLimit,
+        TraceReason::LateMovePruning => Msg::TraceLateMovePruning,
+        TraceReason::Futility => Msg::TraceFutility,
+        TraceReason::RepeatsPosition => Msg::TraceRepeatsPosition,
+    }
+}
+
+fn move_label(mv: Move) -> String {
+    BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)
+        .zip(BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize))
+        .map_or_else(String::new, |(from, to)| format_iccs(from, to))
+}
+
+fn node_label(node: &TraceNode, language: Language) -> String {
+    let name = node
+        .mv
+        .map_or_else(|| kind_label(node.kind, language).to_string(), move_label);
+    let mut label = format!("{name} · d{} · [{}, {}]", node.depth, node.alpha, node.beta);
+    if let Some(score) = node.score {
+        label.push_str(&format!(" → {score}"));
+    }
+    if let Some(reason) = node.reason {
+        label.push_str(&format!(" · {}", reason_label(reason).text(language)));
+    }
+    label
+}
+
+/// One node; nodes with children open and close like a file tree.
+fn node_view(node: TraceNode, language: Language) -> View {
+    let class = if node.kind == TraceKind::Pruned {
+        "trace-node pruned"
+    } else {
+        "trace-node"
+    };
+    let label = node_label(&node, language);
+    if node.children.is_empty() {
+        return view! { <div class=class>{label}</div> }.into_view();
+    }
+    let count = node.node_count() - 1;
+    view! {
+        <details class=class open=node.kind == TraceKind::Root>
+            <summary>{label}{format!(" ({count})")}</summary>
+            {node.children.into_iter().map(|child| node_view(child, language)).collect_view()}
+        </details>
+    }
+    .into_view()
+}
+
+/// Debug view of the engine's search tree for the current position: every recorded node
+/// with its window, score and why it stopped, so a missed mate can be traced to the cut.
+#[component]
+pub fn SearchTreePanel(trace: ReadSignal<TraceStatus>, on_trace: Callback<()>) -> impl IntoView {
+    let language = use_language();
+
+    view! {
+        <div class="config-column search-tree">
+            <div class="config-title">{t(Msg::SearchTreeTitle)}</div>
+            <button class="control-btn" on:click=move |_| on_trace.call(())>
+                {move || {
+                    let lang = language.get();
+                    format!("{} ({} {TRACE_DEPTH})", Msg::SearchTreeRecord.text(lang), Msg::Depth.text(lang))
+                }}
+            </button>
+            {move || match trace.get() {
+                TraceStatus::Idle => view! {}.into_view(),
+                TraceStatus::Running => view! {
+                    <div class="explorer-empty">{t(Msg::SearchTreeRunning)}</div>
+                }.into_view(),
+                TraceStatus::Done(None) => view! {
+                    <div class="explorer-empty">{t(Msg::SearchTreeFailed)}</div>
+                }.into_view(),
+                TraceStatus::Done(Some(root)) => node_view(root, language.get()),
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,265 @@
+use crate::app::layout::{router_base, Section};
+use crate::i18n::{detail_text, t, use_language, Msg};
+use crate::network::fetch_json;
+use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::share::{fen_from_param, fen_to_param, moves_from_param, replay};
+use leptos::{
+    component, create_effect, create_signal, event_target_value, spawn_local, view, window,
+    IntoView, MaybeSignal, ReadSignal, Signal, SignalGet, SignalGetUntracked, SignalSet,
+    SignalWith, WriteSignal,
+};
+use leptos_router::{use_params_map, use_query_map};
+use shared::GameReplay;
+use wasm_bindgen_futures::JsFuture;
+
+/// Path of the analysis page opened on `state`'s position.
+pub fn position_path(state: &GameState) -> String {
+    format!(
+        "{}?fen={}",
+        Section::Analysis.path(),
+        fen_to_param(&state.board.to_fen_string(state.turn))
+    )
+}
+
+/// Path of the page replaying the server's game `game_id`.
+pub fn replay_path(game_id: &str) -> String {
+    format!(
+        "/replay/{}",
+        String::from(js_sys::encode_uri_component(game_id))
+    )
+}
+
+/// Full link to `path` below the router base, on the site the app is served from.
+fn share_url(path: &str) -> String {
+    let origin = window().location().origin().unwrap_or_default();
+    format!("{origin}{}{path}", router_base())
+}
+
+/// A FEN pasted on its own or as part of an `/analysis?fen=...` link.
+fn pasted_fen(text: &str) -> String {
+    text.split_once("fen=").map_or_else(
+        || text.to_string(),
+        |(_, param)| fen_from_param(param.split('&').next().unwrap_or_default()),
+    )
+}
+
+/// Rebuilds a game the server sent for a replay link; the error is what was wrong with it.
+fn replay_state(game: &GameReplay) -> Result<GameState, String> {
+    let start = GameState::from_fen(&game.start_fen)?;
+    let moves = moves_from_param(&game.moves).ok_or_else(String::new)?;
+    replay(start, &moves).map_err(|e| format!("{e:?}"))
+}
+
+/// Links to the position on the board and, for server games, to the whole game, shown in a
+/// text box so they can also be copied by hand where the clipboard is not available.
+#[component]
+pub fn SharePanel(
+    game_state: ReadSignal<GameState>,
+    #[prop(optional, into)] game_id: MaybeSignal<Option<String>>,
+) -> impl IntoView {
+    let (link, set_link) = create_signal(Option::<String>::None);
+    let (message, set_message) = create_signal(Option::<Msg>::None);
+    let language = use_language();
+
+    let copy = move |path: String| {
+        let url = share_url(&path);
+        set_link.set(Some(url.clone()));
+        let promise = window().navigator().clipboard().write_text(&url);
+        spawn_local(async move {
+            set_message.set(Some(if JsFuture::from(promise).await.is_ok() {
+                Msg::ShareCopied
+            } else {
+                Msg::ShareCopyBelow
+            }));
+        });
+    };
+
+    view! {
+        <div class="share-panel">
+            <button
+                class="control-btn"
+                on:click=move |_| copy(game_state.with(position_path))
+            >
+                {t(Msg::SharePosition)}
+            </button>
+            {move || game_id.get().map(|id| view! {
+                <button class="control-btn" on:click=move |_| copy(replay_path(&id))>
+                    {t(Msg::ShareGame)}
+                </button>
+            })}
+            {move || message.get().map(|m| view! { <div class="arena-message">{m.text(language.get())}</div> })}
+            {move || link.get().map(|url| view! {
+                <input type="text" class="share-link" readonly prop:value=url />
+            })}
+        </div>
+    }
+}
+
+/// Copies the board's position as a FEN and sets up the board from a pasted one, as a new
+/// game with no moves. The text box takes a FEN by hand where the clipboard cannot be read.
+#[component]
+pub fn FenPanel(
+    game_state: ReadSignal<GameState>,
+    set_game_state: WriteSignal<GameState>,
+) -> impl IntoView {
+    let (fen_text, set_fen_text) = create_signal(String::new());
+    // A message and its detail
+    let (message, set_message) = create_signal(Option::<(Msg, String)>::None);
+    let language = use_language();
+
+    let load = move |text: String| match GameState::from_user_fen(&pasted_fen(&text)) {
+        Ok(state) => {
+            set_fen_text.set(String::new());
+            set_message.set(None);
+            set_game_state.set(state);
+        }
+        Err(e) => {
+            set_fen_text.set(text);
+            set_message.set(Some((Msg::FenInvalid, e)));
+        }
+    };
+
+    let copy = move |_| {
+        let fen = game_state.with(|s| s.board.to_fen_string(s.turn));
+        set_fen_text.set(fen.clone());
+        let promise = window().navigator().clipboard().write_text(&fen);
+        spawn_local(async move {
+            let copied = JsFuture::from(promise).await.is_ok();
+            set_message.set(Some((
+                if copied {
+                    Msg::FenCopied
+                } else {
+                    Msg::FenCopyBelow
+                },
+                String::new(),
+            )));
+        });
+    };
+
+    let paste = move |_| {
+        let promise = window().navigator().clipboard().read_text();
+        spawn_local(async move {
+            match JsFuture::from(promise)
+                .await
+                .ok()
+                .and_then(|v| v.as_string())
+            {
+                Some(text) => load(text),
+                None => set_message.set(Some((Msg::FenClipboardUnreadable, String::new()))),
+            }
+        });
+    };
+
+    view! {
+        <div class="share-panel">
+            <button class="control-btn" on:click=copy>{t(Msg::FenCopy)}</button>
+            <button class="control-btn" on:click=paste>{t(Msg::FenPaste)}</button>
+            <input
+                type="text"
+                class="share-link"
+                placeholder=t(Msg::FenPlaceholder)
+                aria-label="FEN"
+                prop:value=move || fen_text.get()
+                on:input=move |ev| set_fen_text.set(event_target_value(&ev))
+                on:keydown=move |ev: web_sys::KeyboardEvent| {
+                    if ev.key() == "Enter" {
+                        load(fen_text.get_untracked());
+                    }
+                }
+            />
+            {move || message.get().map(|(msg, detail)| view! {
+                <div class="arena-message" role="alert">{detail_text(msg, &detail, language.get())}</div>
+            })}
+        </div>
+    }
+}
+
+/// Loads the position from an `/analysis?fen=...` link onto the board.
+#[component]
+pub fn SharedPosition(set_game_state: WriteSignal<GameState>) -> impl IntoView {
+    let query = use_query_map();
+    let language = use_language();
+    // What is wrong with the linked FEN
+    let (error, set_error) = create_signal(Option::<String>::None);
+
+    create_effect(move |_| {
+        let Some(param) = query.with(|q| q.get("fen").cloned()) else {
+            return;
+        };
+        match GameState::from_user_fen(&fen_from_param(&param)) {
+            Ok(state) => {
+                set_error.set(None);
+                set_game_state.set(state);
+            }
+            Err(e) => set_error.set(Some(e)),
+        }
+    });
+
+    move || {
+        error.get().map(|e| {
+            view! {
+                <div class="arena-message">
+                    {detail_text(Msg::SharedPositionInvalid, &e, language.get())}
+                </div>
+            }
+        })
+    }
+}
+
+/// `/replay/:game_id`: fetches a game from the server and puts it on the analysis board,
+/// where its moves can be stepped through and reviewed.
+#[component]
+pub fn ReplayLoader(
+    game_state: ReadSignal<GameState>,
+    set_game_state: WriteSignal<GameState>,
+) -> impl IntoView {
+    let params = use_params_map();
+    let game_id = Signal::derive(move || {
+        params.with(|p| {
+            p.get("game_id").map(|id| {
+                js_sys::decode_uri_component(id).map_or_else(|_| id.clone(), String::from)
+            })
+        })
+    });
+    let (title, set_title) = create_signal(Option::<String>::None);
+    // A message and its detail
+    let (error, set_error) = create_signal(Option::<(Msg, String)>::None);
+    let language = use_language();
+
+    create_effect(move |_| {
+        let Some(game_id) = game_id.get() else {
+            return;
+        };
+        spawn_local(async move {
+            let path = format!(
+                "/games/{}/replay",
+                String::from(js_sys::encode_uri_component(&game_id))
+            );
+            match fetch_json::<GameReplay>(&path).await {
+                Ok(game) => match replay_state(&game) {
+                    Ok(state) => {
+                        set_error.set(None);
+                        set_title.set(Some(format!(
+                            "{} – {} ({})",
+                            game.red_name, game.black_name, game.result
+                        )));
+                        set_game_state.set(state);
+                    }
+                    Err(e) => set_error.set(Some((Msg::ReplayInvalid, e))),
+                },
+                Err(e) => {
+                    leptos::logging::log!("[NET] Failed to load replay: {:?}", e);
+                    set_error.set(Some((Msg::ReplayNotFound, String::new())));
+                }
+            }
+        });
+    });
+
+    view! {
+        {move || title.get().map(|t| view! { <div class="replay-title">{t}</div> })}
+        {move || error.get().map(|(msg, detail)| view! {
+            <div class="arena-message">{detail_text(msg, &detail, language.get())}</div>
+        })}
+        <SharePanel game_state=game_state game_id=game_id />
+    }
+}
@@ -90,6 +90,129 @@ pub const GAME_STYLES: &str = "
                     background-color: #444;
                 }
 
+                .log-item {
+                    cursor: pointer;
+                }
+
+                .log-item.selected {
+                    border-left: 3px solid #4caf50;
+                    background-color: #2e4a30;
+                }
+
+                .explorer-panel {
+                    width: 90%;
+                    max-width: 500px;
+                    max-height: 400px;
+                    background: #333;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 6px rgba(0,0,0,0.3);
+                    display: flex;
+                    flex-direction: column;
+                    border: 1px solid #444;
+                    margin-top: 10px;
+                }
+
+                .explorer-item {
+                    padding: 10px 15px;
+                    border-bottom: 1px solid #444;
+                    font-size: 14px;
+                    display: flex;
+                    flex-direction: column;
+                    gap: 4px;
+                }
+
+                .explorer-item.clickable {
+                    cursor: pointer;
+                }
+
+                .explorer-item.clickable:hover {
+                    background-color: #2e4a30;
+                }
+
+                .explorer-bar {
+                    height: 4px;
+                    background: #444;
+                    border-radius: 2px;
+                    overflow: hidden;
+                }
+
+                .explorer-bar div {
+                    height: 100%;
+                    background: #f0d9b5;
+                }
+
+                .review-panel {
+                    width: 90%;
+                    max-width: 500px;
+                    max-height: 500px;
+                    background: #333;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 6px rgba(0,0,0,0.3);
+                    display: flex;
+                    flex-direction: column;
+                    border: 1px solid #444;
+                    margin-top: 10px;
+                }
+
+                .review-panel > .control-btn {
+                    margin: 12px 15px;
+                }
+
+                .eval-graph-panel .log-header {
+                    display: flex;
+                    justify-content: space-between;
+                }
+
+                .eval-graph-caption {
+                    font-weight: normal;
+                    font-family: monospace;
+                }
+
+                .eval-graph {
+                    display: block;
+                    width: 100%;
+                    height: 90px;
+                    background: #222;
+                    border-radius: 0 0 8px 8px;
+                    cursor: pointer;
+                }
+
+                .review-summary {
+                    width: 100%;
+                    border-collapse: collapse;
+                    font-size: 14px;
+                }
+
+                .review-summary th,
+                .review-summary td {
+                    padding: 6px 15px;
+                    border-bottom: 1px solid #444;
+                    text-align: right;
+                }
+
+                .review-summary th:first-child,
+                .review-summary td:first-child {
+                    text-align: left;
+                    color: #aaa;
+                }
+
+                .explorer-empty {
+                    padding: 15px;
+                    color: #aaa;
+                    font-size: 0.9em;
+                    text-align: center;
+                }
+
+                .log-review {
+                    display: flex;
+                    justify-content: space-between;
+                    align-items: center;
+                    gap: 8px;
+                    padding: 8px 15px;
+                    background-color: #2e4a30;
+                    font-size: 0.9em;
+                }
+
                 .move-info {
                     display: flex;
                     justify-content: space-between;
@@ -144,6 +267,17 @@ pub const GAME_STYLES: &str = "
                     margin-left: 2px;
                 }
 
+                .sound-settings {
+                    display: flex;
+                    align-items: center;
+                    gap: 8px;
+                    padding: 8px 0;
+                }
+
+                .sound-settings input[type=range] {
+                    flex: 1;
+                }
+
                 select, button.control-btn {
                     width: 100%;
                     padding: 10px 14px;
@@ -234,6 +368,21 @@ pub const GAME_STYLES: &str = "
                     100% { opacity: 0.6; }
                 }
 
+                .move-time-warning {
+                    background: #b71c1c;
+                    color: #fff;
+                    padding: 8px 20px;
+                    border-radius: 8px;
+                    font-size: 1.1em;
+                    font-weight: bold;
+                    animation: time-flash 1s infinite;
+                }
+
+                @keyframes time-flash {
+                    0%, 100% { background: #b71c1c; }
+                    50% { background: #ff5252; }
+                }
+
                 .config-panel {
                     display: flex;
                     gap: 20px;
@@ -254,6 +403,28 @@ pub const GAME_STYLES: &str = "
                     border: 1px solid #444;
                 }
 
+                .search-tree {
+                    max-width: 1000px;
+                    max-height: 600px;
+                    overflow: auto;
+                    font-family: monospace;
+                    font-size: 0.85em;
+                    color: #ccc;
+                }
+
+                .search-tree details,
+                .search-tree .trace-node {
+                    margin-left: 14px;
+                }
+
+                .search-tree summary {
+                    cursor: pointer;
+                }
+
+                .search-tree .trace-node.pruned {
+                    color: #888;
+                }
+
                 .config-title {
                     color: #f0d9b5;
                     font-weight: bold;
@@ -314,6 +485,453 @@ pub const GAME_STYLES: &str = "
                     display: contents; /* Mobile: just show content */
                 }
 
+                .error-toast {
+                    position: fixed;
+                    top: 20px;
+                    left: 50%;
+                    transform: translateX(-50%);
+                    background: #b71c1c;
+                    color: #fff;
+                    padding: 12px 24px;
+                    border-radius: 8px;
+                    box-shadow: 0 4px 15px rgba(0,0,0,0.4);
+                    z-index: 1000;
+                }
+
+                .main-nav {
+                    display: flex;
+                    flex-wrap: wrap;
+                    justify-content: center;
+                    gap: 6px;
+                    margin-bottom: 15px;
+                }
+
+                .main-nav a {
+                    padding: 8px 14px;
+                    border-radius: 8px;
+                    background: #333;
+                    border: 1px solid #444;
+                    color: #ddd;
+                    text-decoration: none;
+                }
+
+                .main-nav a[aria-current=page] {
+                    background: #555;
+                    border-color: #f0d9b5;
+                    color: #f0d9b5;
+                }
+
+                .home-grid {
+                    display: grid;
+                    grid-template-columns: repeat(auto-fit, minmax(220px, 1fr));
+                    gap: 12px;
+                    max-width: 760px;
+                    width: 100%;
+                    padding: 0 10px;
+                    box-sizing: border-box;
+                }
+
+                .home-card {
+                    display: flex;
+                    flex-direction: column;
+                    gap: 6px;
+                    padding: 16px;
+                    border-radius: 12px;
+                    background: #2a2a2a;
+                    border: 1px solid #444;
+                    color: #eee;
+                    text-decoration: none;
+                    transition: border-color 0.2s ease;
+                }
+
+                .home-card:hover {
+                    border-color: #f0d9b5;
+                }
+
+                .home-card-title {
+                    font-size: 1.1em;
+                    font-weight: bold;
+                    color: #f0d9b5;
+                }
+
+                .home-card-text {
+                    font-size: 0.9em;
+                    color: #aaa;
+                }
+
+                .profile-lookup {
+                    flex-direction: row;
+                }
+
+                .profile-lookup input {
+                    flex: 1;
+                    min-width: 0;
+                }
+
+                .profile-lookup button.control-btn {
+                    width: auto;
+                }
+
+                .leaderboard-panel {
+                    max-width: 500px;
+                    width: 100%;
+                    margin: 0 auto 15px auto;
+                    box-sizing: border-box;
+                }
+
+                .leaderboard-body {
+                    background: #2a2a2a;
+                    border: 1px solid #444;
+                    border-radius: 12px;
+                    margin-top: 10px;
+                    padding: 12px;
+                    display: flex;
+                    flex-direction: column;
+                    gap: 10px;
+                }
+
+                .leaderboard-search {
+                    padding: 8px 12px;
+                    border-radius: 8px;
+                    border: 1px solid #555;
+                    background: #3a3a3a;
+                    color: #eee;
+                    font-family: inherit;
+                }
+
+                .leaderboard-table {
+                    width: 100%;
+                    border-collapse: collapse;
+                    font-size: 0.9em;
+                }
+
+                .leaderboard-table th, .leaderboard-table td {
+                    padding: 6px 8px;
+                    border-bottom: 1px solid #3a3a3a;
+                    text-align: left;
+                }
+
+                .leaderboard-table th {
+                    color: #aaa;
+                    font-weight: normal;
+                }
+
+                .leaderboard-pagination {
+                    display: flex;
+                    align-items: center;
+                    justify-content: space-between;
+                    gap: 10px;
+                }
+
+                .leaderboard-pagination button.control-btn {
+                    width: auto;
+                }
+
+                .arena-submit {
+                    display: flex;
+                    gap: 8px;
+                }
+
+                .arena-submit input {
+                    flex: 1;
+                    min-width: 0;
+                }
+
+                .arena-submit button.control-btn {
+                    width: auto;
+                }
+
+                .arena-message {
+                    color: #ccc;
+                    font-size: 0.9em;
+                }
+
+                .share-panel {
+                    max-width: 500px;
+                    width: 100%;
+                    margin: 0 auto 15px auto;
+                    display: flex;
+                    flex-wrap: wrap;
+                    gap: 8px;
+                }
+
+                .share-panel button.control-btn {
+                    width: auto;
+                    flex: 1;
+                }
+
+                .record-import summary {
+                    width: 100%;
+                    cursor: pointer;
+                }
+
+                .share-link {
+                    width: 100%;
+                    padding: 8px 12px;
+                    border-radius: 8px;
+                    border: 1px solid #555;
+                    background: #3a3a3a;
+                    color: #eee;
+                    font-family: inherit;
+                }
+
+                .replay-title {
+                    text-align: center;
+                    color: #f0d9b5;
+                    font-weight: bold;
+                    margin-bottom: 10px;
+                }
+
+                .puzzle-panel {
+                    max-width: 500px;
+                    width: 100%;
+                    margin: 0 auto 15px auto;
+                    padding: 12px;
+                    box-sizing: border-box;
+                    background: #2a2a2a;
+                    border: 1px solid #444;
+                    border-radius: 12px;
+                    display: flex;
+                    flex-direction: column;
+                    gap: 8px;
+                }
+
+                .puzzle-header {
+                    display: flex;
+                    justify-content: space-between;
+                    color: #f0d9b5;
+                    font-weight: bold;
+                }
+
+                .scoreboard .puzzle-header button.control-btn {
+                    width: auto;
+                    padding: 4px 10px;
+                }
+
+                .puzzle-streak {
+                    color: #aaa;
+                    font-size: 0.9em;
+                }
+
+                .puzzle-status {
+                    text-align: center;
+                    font-weight: bold;
+                }
+
+                .puzzle-status.solved {
+                    color: #4caf50;
+                }
+
+                .puzzle-status.failed {
+                    color: #f44336;
+                }
+
+                .lesson-list {
+                    display: flex;
+                    flex-wrap: wrap;
+                    gap: 6px;
+                }
+
+                .lesson-list button.control-btn {
+                    width: auto;
+                }
+
+                .lesson-list button.control-btn.active {
+                    border-color: #f0d9b5;
+                }
+
+                .lesson-instruction {
+                    color: #ddd;
+                    line-height: 1.4;
+                }
+
+                .mate-result {
+                    margin-top: 10px;
+                    text-align: center;
+                    color: #a8e6cf;
+                }
+
+                button.install-btn {
+                    width: auto;
+                    margin-bottom: 10px;
+                }
+
+                .live-games-table button.control-btn,
+                .friends-table button.control-btn,
+                .challenge-card button.control-btn,
+                .friend-add button.control-btn {
+                    width: auto;
+                    padding: 4px 12px;
+                }
+
+                .leaderboard-table td.clickable a {
+                    color: inherit;
+                    text-decoration: underline dotted;
+                }
+
+                .profile-view {
+                    display: flex;
+                    flex-direction: column;
+                    gap: 10px;
+                }
+
+                .profile-view h3 {
+                    margin: 0;
+                    color: #f0d9b5;
+                }
+
+                .profile-stats {
+                    display: grid;
+                    grid-template-columns: repeat(3, 1fr);
+                    gap: 8px;
+                }
+
+                .profile-stats div {
+                    display: flex;
+                    flex-direction: column;
+                    align-items: center;
+                    background: #3a3a3a;
+                    border-radius: 8px;
+                    padding: 6px;
+                }
+
+                .profile-stats span {
+                    color: #aaa;
+                    font-size: 0.8em;
+                }
+
+                .profile-section {
+                    font-weight: bold;
+                    color: #ddd;
+                }
+
+                .profile-chart {
+                    width: 100%;
+                    height: 80px;
+                    background: #3a3a3a;
+                    border-radius: 8px;
+                }
+
+                .profile-chart polyline {
+                    fill: none;
+                    stroke: #e9c46a;
+                    stroke-width: 2;
+                    vector-effect: non-scaling-stroke;
+                }
+
+                .profile-openings {
+                    margin: 0;
+                    padding-left: 20px;
+                }
+
+                .profile-openings span {
+                    color: #aaa;
+                    margin-left: 6px;
+                }
+
+                .friend-add {
+                    display: flex;
+                    gap: 8px;
+                }
+
+                .friend-add input {
+                    flex: 1;
+                }
+
+                .challenge-card {
+                    display: flex;
+                    align-items: center;
+                    gap: 8px;
+                    background: #2a2a2a;
+                    border: 1px solid #e9c46a;
+                    border-radius: 8px;
+                    padding: 8px 12px;
+                    margin-bottom: 10px;
+                }
+
+                .challenge-card span {
+                    flex: 1;
+                }
+
+                .move-entry {
+                    display: flex;
+                    gap: 8px;
+                    margin-top: 8px;
+                }
+
+                .move-entry input {
+                    flex: 1;
+                    min-width: 0;
+                }
+
+                .move-entry button.control-btn {
+                    width: auto;
+                }
+
+                .move-entry-preview {
+                    opacity: 0.8;
+                    margin-top: 4px;
+                    font-family: monospace;
+                }
+
+                .move-entry-error {
+                    color: #f44336;
+                    margin-top: 4px;
+                }
+
+                .handoff-overlay {
+                    position: fixed;
+                    inset: 0;
+                    display: flex;
+                    align-items: center;
+                    justify-content: center;
+                    background: rgba(20, 20, 20, 0.96);
+                    z-index: 900;
+                }
+
+                .handoff-card {
+                    display: flex;
+                    flex-direction: column;
+                    align-items: center;
+                    gap: 12px;
+                    padding: 24px 32px;
+                    border-radius: 12px;
+                    background: #333;
+                    border: 1px solid #555;
+                    font-size: 18px;
+                }
+
+                .handoff-card button.control-btn {
+                    width: auto;
+                    min-width: 140px;
+                }
+
+                .move-confirm {
+                    display: flex;
+                    gap: 8px;
+                    margin-top: 8px;
+                }
+
+                .move-confirm button.control-btn {
+                    width: auto;
+                    min-width: 120px;
+                }
+
+                canvas:focus-visible {
+                    outline: 3px solid #1976d2;
+                    outline-offset: 2px;
+                }
+
+                /* Read by screen readers, not shown */
+                .sr-only {
+                    position: absolute;
+                    width: 1px;
+                    height: 1px;
+                    overflow: hidden;
+                    clip: rect(0, 0, 0, 0);
+                    white-space: nowrap;
+                }
+
                 @media (min-width: 1100px) {
                     .side-column {
                         display: flex;
@@ -329,10 +947,22 @@ pub const GAME_STYLES: &str = "
                     
                     /* Right column aligns left (next to board) */
                     .side-column.right {
+                        flex-direction: column; /* Explorer above the review */
                         justify-content: flex-start;
                         padding-left: 5px; /* Minimal padding */
                     }
 
+                    .explorer-panel {
+                        width: 320px;
+                        align-self: flex-start;
+                        margin-top: 45px; /* Align with board canvas (skip captured pieces) */
+                    }
+
+                    .review-panel {
+                        width: 320px;
+                        align-self: flex-start;
+                    }
+
                     .log-panel {
                         width: 480px;
                         min-width: 350px; /* Prevent it from becoming too narrow */
@@ -0,0 +1,164 @@
+use crate::app::GameMode;
+use crate::i18n::{t, training_goal_label, use_language, Msg};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::training::{
+    training_positions, TrainingGoal, TrainingOutcome, TrainingStage,
+};
+use leptos::{
+    component, create_effect, create_signal, store_value, view, IntoView, ReadSignal, SignalGet,
+    SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, WriteSignal,
+};
+
+/// Whether the goal is a win, and within how many moves.
+const fn goal_text(goal: TrainingGoal) -> (bool, u8) {
+    match goal {
+        TrainingGoal::Win(moves) => (true, moves),
+        TrainingGoal::Hold(moves) => (false, moves),
+    }
+}
+
+const fn stage_label(stage: TrainingStage) -> Msg {
+    match stage {
+        TrainingStage::Endgame => Msg::StageEndgame,
+        TrainingStage::Middlegame => Msg::StageMiddlegame,
+    }
+}
+
+/// Training positions played out against the engine, which answers at the chosen level.
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn TrainingPanel(
+    game_mode: ReadSignal<GameMode>,
+    game_state: ReadSignal<GameState>,
+    set_game_state: WriteSignal<GameState>,
+    player_side: ReadSignal<Color>,
+    set_player_side: WriteSignal<Color>,
+    /// Ids of the positions solved at least once.
+    solved: ReadSignal<Vec<String>>,
+    set_solved: WriteSignal<Vec<String>>,
+) -> impl IntoView {
+    let positions = store_value(training_positions());
+    let (index, set_index) = create_signal(0usize);
+    let (outcome, set_outcome) = create_signal(TrainingOutcome::Playing);
+    let language = use_language();
+
+    let load = move |i: usize| {
+        let Some(Ok(state)) = positions.with_value(|p| p.get(i).map(|pos| pos.initial_state()))
+        else {
+            return;
+        };
+        set_player_side.set(state.turn);
+        set_game_state.set(state);
+        set_index.set(i);
+        set_outcome.set(TrainingOutcome::Playing);
+    };
+
+    // Entering the mode opens the current position
+    create_effect(move |_| {
+        if game_mode.get() == GameMode::Training {
+            load(index.get_untracked());
+        }
+    });
+
+    // Judge the game after every move, the engine's included
+    create_effect(move |_| {
+        let state = game_state.get();
+        if game_mode.get_untracked() != GameMode::Training
+            || outcome.get_untracked() != TrainingOutcome::Playing
+        {
+            return;
+        }
+        let Some((result, id)) = positions.with_value(|p| {
+            p.get(index.get_untracked()).map(|pos| {
+                (
+                    pos.outcome(&state, player_side.get_untracked()),
+                    pos.id.clone(),
+                )
+            })
+        }) else {
+            return;
+        };
+        set_outcome.set(result);
+        if result == TrainingOutcome::Solved && !solved.get_untracked().contains(&id) {
+            set_solved.update(|s| s.push(id));
+        }
+    });
+
+    view! {
+        {move || {
+            if game_mode.get() != GameMode::Training {
+                return view! {}.into_view();
+            }
+
+            let i = index.get();
+            let (goal, stage) = positions
+                .with_value(|p| p.get(i).map(|pos| (pos.goal, pos.stage)))
+                .unwrap_or((TrainingGoal::Win(0), TrainingStage::Endgame));
+            let (win, moves) = goal_text(goal);
+            let count = positions.with_value(Vec::len);
+            let solved_count = move || {
+                let solved = solved.get();
+                positions.with_value(|p| p.iter().filter(|pos| solved.contains(&pos.id)).count())
+            };
+
+            view! {
+                <div class="puzzle-panel">
+                    <div class="lesson-list">
+                        {positions.with_value(|p| p.iter().enumerate().map(|(i, pos)| {
+                            let id = pos.id.clone();
+                            let done = move || solved.get().contains(&id);
+                            view! {
+                                <button
+                                    class="control-btn"
+                                    class:active=move || index.get() == i
+                                    on:click=move |_| load(i)
+                                >
+                                    {move || if done() { "✅ " } else { "🎯 " }}
+                                    {pos.title.clone()}
+                                </button>
+                            }
+                        }).collect::<Vec<_>>())}
+                    </div>
+                    <div class="puzzle-header">
+                        <span>{move || {
+                            let lang = language.get();
+                            format!(
+                                "{} · {}",
+                                stage_label(stage).text(lang),
+                                training_goal_label(lang, win, moves)
+                            )
+                        }}</span>
+                        <span>{move || {
+                            format!("{} {}/{count}", Msg::TrainingSolved.text(language.get()), solved_count())
+                        }}</span>
+                    </div>
+                    {move || match outcome.get() {
+                        TrainingOutcome::Playing => {
+                            let played = game_state.with(|s| {
+                                s.history.iter().filter(|r| r.color == player_side.get()).count()
+                            });
+                            view! {
+                                <div class="puzzle-status">
+                                    {format!("{} {played}/{moves}", Msg::TrainingMoves.text(language.get()))}
+                                </div>
+                            }.into_view()
+                        }
+                        TrainingOutcome::Solved => view! {
+                            <div class="puzzle-status solved">{t(Msg::TrainingComplete)}</div>
+                            {(i + 1 < count).then(|| view! {
+                                <button class="control-btn btn-primary" on:click=move |_| load(i + 1)>
+                                    {t(Msg::NextPosition)}
+                                </button>
+                            })}
+                        }.into_view(),
+                        TrainingOutcome::Failed => view! {
+                            <div class="puzzle-status failed">{t(Msg::TrainingFailed)}</div>
+                            <button class="control-btn btn-warning" on:click=move |_| load(i)>{t(Msg::TryAgain)}</button>
+                        }.into_view(),
+                    }}
+                </div>
+            }.into_view()
+        }}
+    }
+}
@@ -0,0 +1,183 @@
+use crate::app::GameMode;
+use crate::i18n::{lesson_step_label, t, use_language, Msg};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::lesson::lessons;
+use leptos::{
+    component, create_effect, create_signal, set_timeout, store_value, view, IntoView, ReadSignal,
+    SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, WriteSignal,
+};
+use std::time::Duration;
+
+/// Pause after a move, so the user sees it land before the board changes.
+const STEP_DELAY_MS: u64 = 700;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepProgress {
+    Waiting,
+    /// The taught move was played; the next step loads shortly.
+    Passed,
+    /// Another move was played; the step starts over shortly.
+    Refused,
+    LessonDone,
+}
+
+#[component]
+#[allow(clippy::too_many_lines)]
+pub fn TutorialPanel(
+    game_mode: ReadSignal<GameMode>,
+    game_state: ReadSignal<GameState>,
+    set_game_state: WriteSignal<GameState>,
+    set_player_side: WriteSignal<Color>,
+    /// Ids of the lessons finished at least once.
+    completed: ReadSignal<Vec<String>>,
+    set_completed: WriteSignal<Vec<String>>,
+) -> impl IntoView {
+    let lessons = store_value(lessons());
+    let (lesson, set_lesson) = create_signal(0usize);
+    let (step, set_step) = create_signal(0usize);
+    let (progress, set_progress) = create_signal(StepProgress::Waiting);
+    let language = use_language();
+
+    let load = move |lesson_index: usize, step_index: usize| {
+        let Some(Ok(state)) = lessons.with_value(|l| {
+            l.get(lesson_index)
+                .and_then(|lesson| lesson.steps.get(step_index))
+                .map(|step| step.initial_state())
+        }) else {
+            return;
+        };
+        set_player_side.set(Color::Red);
+        set_game_state.set(state);
+        set_lesson.set(lesson_index);
+        set_step.set(step_index);
+        set_progress.set(StepProgress::Waiting);
+    };
+
+    // Entering the mode opens the current lesson where it was left
+    create_effect(move |_| {
+        if game_mode.get() == GameMode::Tutorial {
+            load(lesson.get_untracked(), step.get_untracked());
+        }
+    });
+
+    // Check the move played on the board against the taught moves
+    create_effect(move |_| {
+        let state = game_state.get();
+        if game_mode.get_untracked() != GameMode::Tutorial
+            || progress.get_untracked() != StepProgress::Waiting
+            || state.history.len() != 1
+        {
+            return;
+        }
+        let Some(last) = state.history.last() else {
+            return;
+        };
+        let (lesson_index, step_index) = (lesson.get_untracked(), step.get_untracked());
+        let Some((accepted, step_count, id)) = lessons.with_value(|l| {
+            let lesson = l.get(lesson_index)?;
+            let step = lesson.steps.get(step_index)?;
+            Some((
+                step.accepts(last.from, last.to),
+                lesson.steps.len(),
+                lesson.id.clone(),
+            ))
+        }) else {
+            return;
+        };
+
+        if !accepted {
+            set_progress.set(StepProgress::Refused);
+            set_timeout(
+                move || load(lesson_index, step_index),
+                Duration::from_millis(STEP_DELAY_MS),
+            );
+        } else if step_index + 1 < step_count {
+            set_progress.set(StepProgress::Passed);
+            set_timeout(
+                move || load(lesson_index, step_index + 1),
+                Duration::from_millis(STEP_DELAY_MS),
+            );
+        } else {
+            set_progress.set(StepProgress::LessonDone);
+            if !completed.get_untracked().contains(&id) {
+                set_completed.update(|c| c.push(id));
+            }
+        }
+    });
+
+    view! {
+        {move || {
+            if game_mode.get() != GameMode::Tutorial {
+                return view! {}.into_view();
+            }
+
+            let (lesson_index, step_index) = (lesson.get(), step.get());
+            let (title, instruction, step_count) = lessons.with_value(|l| {
+                l.get(lesson_index).map_or_else(Default::default, |lesson| {
+                    (
+                        lesson.title.clone(),
+                        lesson
+                            .steps
+                            .get(step_index)
+                            .map(|step| step.instruction.clone())
+                            .unwrap_or_default(),
+                        lesson.steps.len(),
+                    )
+                })
+            });
+            let lesson_count = lessons.with_value(Vec::len);
+
+            view! {
+                <div class="puzzle-panel">
+                    <div class="lesson-list">
+                        {lessons.with_value(|l| l.iter().enumerate().map(|(i, lesson)| {
+                            let id = lesson.id.clone();
+                            let done = move || completed.get().contains(&id);
+                            view! {
+                                <button
+                                    class="control-btn"
+                                    class:active=move || lesson.get() == i
+                                    on:click=move |_| load(i, 0)
+                                >
+                                    {move || if done() { "✅ " } else { "📘 " }}
+                                    {lesson.title.clone()}
+                                </button>
+                            }
+                        }).collect::<Vec<_>>())}
+                    </div>
+                    <div class="puzzle-header">
+                        <span>{title}</span>
+                        <span>{move || lesson_step_label(language.get(), step_index + 1, step_count)}</span>
+                    </div>
+                    <div class="lesson-instruction">{instruction}</div>
+                    {move || match progress.get() {
+                        StepProgress::Waiting => view! {
+                            <div class="puzzle-status">{t(Msg::PuzzleYourMove)}</div>
+                        }.into_view(),
+                        StepProgress::Passed => view! {
+                            <div class="puzzle-status solved">{t(Msg::PuzzleCorrect)}</div>
+                        }.into_view(),
+                        StepProgress::Refused => view! {
+                            <div class="puzzle-status failed">{t(Msg::LessonRetry)}</div>
+                        }.into_view(),
+                        StepProgress::LessonDone => view! {
+                            <div class="puzzle-status solved">{t(Msg::LessonComplete)}</div>
+                            {(lesson_index + 1 < lesson_count).then(|| view! {
+                                <button
+                                    class="control-btn btn-primary"
+                                    on:click=move |_| load(lesson_index + 1, 0)
+                                >
+                                    {t(Msg::LessonNext)}
+                                </button>
+                            })}
+                            <button class="control-btn" on:click=move |_| load(lesson_index, 0)>
+                                {t(Msg::LessonRestart)}
+                            </button>
+                        }.into_view(),
+                    }}
+                </div>
+            }.into_view()
+        }}
+    }
+}
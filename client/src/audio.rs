@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlAudioElement;
+
+const STORAGE_KEY: &str = "cotuong.sound";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sound {
+    Move,
+    Capture,
+    Check,
+    Checkmate,
+}
+
+impl Sound {
+    const ALL: [Self; 4] = [Self::Move, Self::Capture, Self::Check, Self::Checkmate];
+
+    const fn src(self) -> &'static str {
+        match self {
+            Self::Move => "sounds/move.mp3",
+            Self::Capture => "sounds/capture.mp3",
+            Self::Check => "sounds/check.mp3",
+            Self::Checkmate => "sounds/checkmate.mp3",
+        }
+    }
+}
+
+/// User sound preferences, persisted in `localStorage`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    /// Between 0.0 (silent) and 1.0 (full volume).
+    pub volume: f64,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 0.8,
+        }
+    }
+}
+
+impl SoundSettings {
+    /// Reads the saved settings, falling back to the defaults.
+    pub fn load() -> Self {
+        web_sys::window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(self) {
+        let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_string(&self) {
+            let _ = storage.set_item(STORAGE_KEY, &json);
+        }
+    }
+}
+
+/// Owns every audio clip of the client so all playback goes through the user's settings.
+pub struct AudioManager {
+    clips: Vec<(Sound, HtmlAudioElement)>,
+}
+
+impl Default for AudioManager {
+    fn default() -> Self {
+        let clips = Sound::ALL
+            .into_iter()
+            .filter_map(|sound| {
+                HtmlAudioElement::new_with_src(sound.src())
+                    .ok()
+                    .map(|clip| (sound, clip))
+            })
+            .collect();
+        Self { clips }
+    }
+}
+
+impl AudioManager {
+    pub fn play(&self, sound: Sound, settings: SoundSettings) {
+        if !settings.enabled || settings.volume <= 0.0 {
+            return;
+        }
+        let Some((_, clip)) = self.clips.iter().find(|(s, _)| *s == sound) else {
+            return;
+        };
+        clip.set_volume(settings.volume.clamp(0.0, 1.0));
+        // Restart so quick successive moves are all heard
+        clip.set_current_time(0.0);
+        let _ = clip.play();
+    }
+}
@@ -0,0 +1,42 @@
+use cotuong_core::logic::board::BoardCoordinate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A mark drawn with the right mouse button, as on lichess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Annotation {
+    Arrow {
+        from: BoardCoordinate,
+        to: BoardCoordinate,
+    },
+    Square(BoardCoordinate),
+}
+
+/// Annotations for every position of a game, keyed by the position's FEN so they
+/// reappear when the same position is reached again (e.g. after undo).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Annotations(HashMap<String, Vec<Annotation>>);
+
+impl Annotations {
+    pub fn for_position(&self, fen: &str) -> &[Annotation] {
+        self.0.get(fen).map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds the annotation, or removes it when the same one is drawn twice.
+    pub fn toggle(&mut self, fen: &str, annotation: Annotation) {
+        let marks = self.0.entry(fen.to_string()).or_default();
+        if let Some(index) = marks.iter().position(|a| *a == annotation) {
+            marks.remove(index);
+        } else {
+            marks.push(annotation);
+        }
+        if marks.is_empty() {
+            self.0.remove(fen);
+        }
+    }
+
+    pub fn clear_position(&mut self, fen: &str) {
+        self.0.remove(fen);
+    }
+}
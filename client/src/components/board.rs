@@ -1,77 +1,286 @@
 use crate::app::GameMode;
+use crate::components::annotations::{Annotation, Annotations};
+use crate::components::theme::Theme;
+use crate::i18n::{t, use_language, Language, Msg};
+use cotuong_core::engine::match_runner::format_iccs;
 use cotuong_core::engine::Move;
-use cotuong_core::logic::board::{BoardCoordinate, Color, Piece, PieceType};
+use cotuong_core::logic::analysis::control_map;
+use cotuong_core::logic::attacks::{attack_map, hanging_pieces};
+use cotuong_core::logic::board::{Bitboard, Board, BoardCoordinate, Color, Piece};
 use cotuong_core::logic::game::GameState;
-use leptos::html::Canvas;
+use cotuong_core::logic::generator::MoveGenerator;
+use cotuong_core::logic::notation::{parse_move, MoveTextError, WxfMove};
+use cotuong_core::logic::rules::is_in_check;
+use leptos::html::{Canvas, Div};
+use leptos::leptos_dom::helpers::TimeoutHandle;
 use leptos::{
-    component, create_effect, create_node_ref, create_signal, view, IntoView, NodeRef, ReadSignal,
-    SignalGet, SignalSet, WriteSignal,
+    component, create_effect, create_node_ref, create_signal, document, ev, event_target_value,
+    on_cleanup, request_animation_frame, set_timeout_with_handle, store_value, view,
+    window_event_listener, window_event_listener_untyped, IntoView, NodeRef, ReadSignal, Signal,
+    SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith, SignalWithUntracked,
+    WriteSignal,
 };
+use std::collections::HashMap;
 use std::rc::Rc;
+use std::time::Duration;
 use wasm_bindgen::JsCast;
 use web_sys::CanvasRenderingContext2d;
 
-// Board constants
+// Drawing units: the drawing code lays the board out on a 50 unit cell, and `BoardLayout`
+// scales the canvas so one unit covers however many device pixels the screen allows.
 const CELL_SIZE: f64 = 50.0;
-const PADDING: f64 = 25.0;
-const BOARD_WIDTH: f64 = 450.0;
-const BOARD_HEIGHT: f64 = 500.0;
-
-// Helper to get symbol
-fn get_piece_symbol(p: PieceType, c: Color) -> &'static str {
-    match p {
-        PieceType::General => {
-            if c == Color::Red {
-                "帥"
-            } else {
-                "將"
-            }
-        }
-        PieceType::Advisor => {
-            if c == Color::Red {
-                "仕"
-            } else {
-                "士"
-            }
+/// Margin around the grid; wide enough for the edge pieces plus the coordinate labels.
+const PADDING: f64 = 40.0;
+const BOARD_WIDTH: f64 = 8.0 * CELL_SIZE + 2.0 * PADDING;
+const BOARD_HEIGHT: f64 = 9.0 * CELL_SIZE + 2.0 * PADDING;
+const MOVE_ANIMATION_MS: f64 = 150.0;
+
+/// Size of the board on screen, measured from its container on mount, resize and rotation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BoardLayout {
+    /// Distance between two grid lines, in CSS pixels.
+    cell: f64,
+    /// `window.devicePixelRatio`: device pixels per CSS pixel.
+    ratio: f64,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        Self {
+            cell: CELL_SIZE,
+            ratio: 1.0,
         }
-        PieceType::Elephant => {
-            if c == Color::Red {
-                "相"
-            } else {
-                "象"
-            }
+    }
+}
+
+impl BoardLayout {
+    /// Smallest cell that still leaves the pieces tappable on a narrow phone.
+    const MIN_CELL: f64 = 28.0;
+    /// Beyond this the board only gets emptier on a large monitor.
+    const MAX_CELL: f64 = 96.0;
+    /// Share of the window height the canvas may take; the captured pieces, the move box and
+    /// the status line share the rest.
+    const MAX_HEIGHT_SHARE: f64 = 0.72;
+    /// Horizontal space the wrapper's padding and the canvas border take around the canvas.
+    const FRAME: f64 = 14.0;
+
+    /// The largest board that fits `width` CSS pixels and the share of a `window_height` window.
+    fn fit(width: f64, window_height: f64, ratio: f64) -> Self {
+        let by_width = (width - Self::FRAME) * CELL_SIZE / BOARD_WIDTH;
+        let by_height = window_height * Self::MAX_HEIGHT_SHARE * CELL_SIZE / BOARD_HEIGHT;
+        Self {
+            cell: by_width
+                .min(by_height)
+                .clamp(Self::MIN_CELL, Self::MAX_CELL),
+            ratio: if ratio > 0.0 { ratio } else { 1.0 },
         }
-        PieceType::Horse => {
-            if c == Color::Red {
-                "傌"
-            } else {
-                "馬"
-            }
+    }
+
+    fn measure(container: &web_sys::Element) -> Option<Self> {
+        let window = web_sys::window()?;
+        let height = window.inner_height().ok()?.as_f64()?;
+        Some(Self::fit(
+            f64::from(container.client_width()),
+            height,
+            window.device_pixel_ratio(),
+        ))
+    }
+
+    /// CSS pixels per drawing unit.
+    fn scale(self) -> f64 {
+        self.cell / CELL_SIZE
+    }
+
+    fn width(self) -> f64 {
+        BOARD_WIDTH * self.scale()
+    }
+
+    fn height(self) -> f64 {
+        BOARD_HEIGHT * self.scale()
+    }
+
+    /// Canvas backing store size in device pixels, so nothing is stretched or blurred.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn pixel_size(self) -> (u32, u32) {
+        (
+            (self.width() * self.ratio).round() as u32,
+            (self.height() * self.ratio).round() as u32,
+        )
+    }
+}
+
+/// Pinch zoom while reviewing: a board point `p` (drawing units) is drawn at `p * scale + offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Zoom {
+    scale: f64,
+    offset: (f64, f64),
+}
+
+impl Default for Zoom {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            offset: (0.0, 0.0),
         }
-        PieceType::Chariot => {
-            if c == Color::Red {
-                "俥"
-            } else {
-                "車"
-            }
+    }
+}
+
+impl Zoom {
+    const MAX_SCALE: f64 = 3.0;
+
+    /// The board point drawn at screen point `at`.
+    fn to_board(self, at: (f64, f64)) -> (f64, f64) {
+        (
+            (at.0 - self.offset.0) / self.scale,
+            (at.1 - self.offset.1) / self.scale,
+        )
+    }
+
+    /// Zooms to `scale` with the board point `anchor` under the screen point `at`, as far as
+    /// that keeps the board filling the canvas.
+    fn anchored(scale: f64, anchor: (f64, f64), at: (f64, f64)) -> Self {
+        let scale = scale.clamp(1.0, Self::MAX_SCALE);
+        let offset = |anchor: f64, at: f64, size: f64| {
+            anchor.mul_add(-scale, at).clamp(size * (1.0 - scale), 0.0)
+        };
+        Self {
+            scale,
+            offset: (
+                offset(anchor.0, at.0, BOARD_WIDTH),
+                offset(anchor.1, at.1, BOARD_HEIGHT),
+            ),
         }
-        PieceType::Cannon => {
-            if c == Color::Red {
-                "炮"
-            } else {
-                "砲"
-            }
+    }
+}
+
+/// Hold time before a press on a piece previews its moves.
+const LONG_PRESS_MS: u64 = 450;
+/// How far, in drawing units, a finger may drift and still count as holding still.
+const TOUCH_SLOP: f64 = 10.0;
+
+/// What the fingers on the board are doing.
+#[derive(Debug, Clone, Copy)]
+enum TouchGesture {
+    Idle,
+    /// One finger down on a piece; becomes a preview if it stays put for `LONG_PRESS_MS`.
+    Press {
+        at: (f64, f64),
+        timer: Option<TimeoutHandle>,
+    },
+    /// The pressed piece's legal moves are shown until the finger lifts.
+    Preview,
+    Pinch {
+        start: Zoom,
+        anchor: (f64, f64),
+        distance: f64,
+    },
+    /// One finger dragging a zoomed board, holding `anchor` under it.
+    Pan {
+        anchor: (f64, f64),
+    },
+}
+
+/// A piece sliding from `from` to `to`, started at `start` (`performance.now()` milliseconds).
+#[derive(Debug, Clone, Copy)]
+struct MoveAnimation {
+    from: BoardCoordinate,
+    to: BoardCoordinate,
+    piece: Piece,
+    captured: Option<Piece>,
+    start: f64,
+}
+
+impl MoveAnimation {
+    /// Eased progress in `0.0..1.0`, or `None` once the animation has finished.
+    fn progress(&self, now: f64) -> Option<f64> {
+        let t = (now - self.start) / MOVE_ANIMATION_MS;
+        (t < 1.0).then(|| 1.0 - (1.0 - t.max(0.0)).powi(3))
+    }
+}
+
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|w| w.performance())
+        .as_ref()
+        .map_or(0.0, web_sys::Performance::now)
+}
+
+/// Extra layer painted over the board to help read the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoardOverlay {
+    #[default]
+    None,
+    /// Squares each side attacks, with hanging pieces circled.
+    Threats,
+    /// Heatmap of which side controls each square and by how many pieces.
+    Influence,
+}
+
+/// Squares each side attacks and the pieces left hanging, for the threat overlay.
+#[derive(Debug, Clone, Copy)]
+struct ThreatOverlay {
+    red: Bitboard,
+    black: Bitboard,
+    hanging: Bitboard,
+}
+
+impl ThreatOverlay {
+    fn new(board: &Board) -> Self {
+        Self {
+            red: attack_map(board, Color::Red),
+            black: attack_map(board, Color::Black),
+            hanging: hanging_pieces(board, Color::Red) | hanging_pieces(board, Color::Black),
         }
-        PieceType::Soldier => {
-            if c == Color::Red {
-                "兵"
-            } else {
-                "卒"
-            }
+    }
+}
+
+/// A move queued while waiting for the opponent, sent as soon as it is the player's turn.
+pub type Premove = (BoardCoordinate, BoardCoordinate);
+
+/// Legal destinations per square, remembered per position (Zobrist hash) so selecting,
+/// reselecting and previewing pieces costs one generator call per piece and position.
+#[derive(Debug, Default)]
+struct LegalMoveCache {
+    positions: HashMap<u64, HashMap<usize, Vec<(usize, usize)>>>,
+}
+
+impl LegalMoveCache {
+    /// Positions kept before the cache starts over; a game rarely revisits older ones.
+    const MAX_POSITIONS: usize = 64;
+
+    fn targets(&mut self, board: &Board, r: usize, c: usize) -> Vec<(usize, usize)> {
+        let Some(from) = BoardCoordinate::new(r, c) else {
+            return Vec::new();
+        };
+        if self.positions.len() >= Self::MAX_POSITIONS
+            && !self.positions.contains_key(&board.zobrist_hash)
+        {
+            self.positions.clear();
         }
+        self.positions
+            .entry(board.zobrist_hash)
+            .or_default()
+            .entry(from.index())
+            .or_insert_with(|| {
+                MoveGenerator::new()
+                    .legal_moves_from(board, from)
+                    .into_iter()
+                    .map(|to| (to.row, to.col))
+                    .collect()
+            })
+            .clone()
     }
 }
 
+/// Runs `step` once per animation frame for as long as it returns `true`.
+fn schedule_frames(step: Rc<dyn Fn() -> bool>) {
+    request_animation_frame(move || {
+        if step() {
+            schedule_frames(step);
+        }
+    });
+}
+
 // Helper to get visual coordinates
 fn get_visual_coords(row: usize, col: usize, side: Color) -> (f64, f64) {
     let (vis_c, vis_r) = if side == Color::Black {
@@ -86,8 +295,30 @@ fn get_visual_coords(row: usize, col: usize, side: Color) -> (f64, f64) {
     (x, y)
 }
 
+/// Square under the point (`x`, `y`) in drawing units: the inverse of `get_visual_coords`.
+fn square_at_point(x: f64, y: f64, side: Color) -> Option<(usize, usize)> {
+    #[allow(clippy::cast_possible_truncation)]
+    let col = ((x - PADDING + CELL_SIZE / 2.0) / CELL_SIZE).floor() as isize;
+    #[allow(clippy::cast_possible_truncation)]
+    let row = 9 - ((y - PADDING + CELL_SIZE / 2.0) / CELL_SIZE).floor() as isize;
+    let (row, col) = if side == Color::Black {
+        (9 - row, 8 - col)
+    } else {
+        (row, col)
+    };
+    ((0..10).contains(&row) && (0..9).contains(&col))
+        .then(|| (row.unsigned_abs(), col.unsigned_abs()))
+}
+
 #[allow(deprecated)]
-fn draw_piece(ctx: &CanvasRenderingContext2d, x: f64, y: f64, piece: Piece, is_selected: bool) {
+fn draw_piece(
+    ctx: &CanvasRenderingContext2d,
+    theme: Theme,
+    x: f64,
+    y: f64,
+    piece: Piece,
+    is_selected: bool,
+) {
     // x, y are passed directly
 
     let radius = 23.0; // Increased from 20.0
@@ -104,18 +335,14 @@ fn draw_piece(ctx: &CanvasRenderingContext2d, x: f64, y: f64, piece: Piece, is_s
     // Body
     ctx.begin_path();
     let _ = ctx.arc(x, y, radius, 0.0, std::f64::consts::PI * 2.0);
-    ctx.set_fill_style(&"#f0d9b5".into());
+    ctx.set_fill_style(&theme.palette().piece_face.into());
     ctx.fill();
 
     // Reset shadow
     ctx.set_shadow_blur(0.0);
 
     // Border
-    let color_str = if piece.color == Color::Red {
-        "#c00"
-    } else {
-        "#000"
-    };
+    let color_str = theme.piece_color(piece.color);
     ctx.set_stroke_style(&color_str.into());
     ctx.set_line_width(2.0);
     ctx.stroke();
@@ -128,33 +355,43 @@ fn draw_piece(ctx: &CanvasRenderingContext2d, x: f64, y: f64, piece: Piece, is_s
 
     // Text
     ctx.set_fill_style(&color_str.into());
-    ctx.set_font("bold 32px KaiTi, serif"); // Increased from 24px
+    ctx.set_font(theme.pieces.font());
     ctx.set_text_align("center");
     ctx.set_text_baseline("middle");
     // Adjust baseline slightly for visual centering if needed, but middle is usually good
 
-    let symbol = get_piece_symbol(piece.piece_type, piece.color);
+    let symbol = theme.pieces.symbol(piece.piece_type, piece.color);
     let _ = ctx.fill_text(symbol, x, y + 2.0);
 }
 
 #[allow(deprecated)]
-#[allow(clippy::too_many_lines)]
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
 fn draw_board(
     ctx: &CanvasRenderingContext2d,
-    ratio: f64,
+    scale: f64,
+    theme: Theme,
     state: &GameState,
     selected: Option<(usize, usize)>,
     valid_moves: &[(usize, usize)],
-    player_side: Color,
+    orientation: Color,
+    animation: Option<(MoveAnimation, f64)>,
+    premove: Option<Premove>,
+    annotations: &[Annotation],
+    threats: Option<ThreatOverlay>,
+    influence: Option<&[i8; 90]>,
+    cursor: Option<(usize, usize)>,
+    zoom: Zoom,
 ) {
-    let _ = ctx.scale(ratio, ratio);
+    let _ = ctx.scale(scale, scale);
+    let _ = ctx.translate(zoom.offset.0, zoom.offset.1);
+    let _ = ctx.scale(zoom.scale, zoom.scale);
 
     // Clear canvas
-    ctx.set_fill_style(&"#eecfa1".into());
-    ctx.fill_rect(0.0, 0.0, BOARD_WIDTH, BOARD_HEIGHT);
+    theme.fill_background(ctx, BOARD_WIDTH, BOARD_HEIGHT);
 
     // Draw Grid
-    ctx.set_stroke_style(&"#5c3a1e".into());
+    let grid_color = theme.palette().grid;
+    ctx.set_stroke_style(&grid_color.into());
     ctx.set_line_width(2.0);
 
     // Horizontal lines
@@ -218,26 +455,133 @@ fn draw_board(
 
     // River Text
     ctx.set_font("24px serif");
-    ctx.set_fill_style(&"#5c3a1e".into());
+    ctx.set_fill_style(&grid_color.into());
     ctx.set_text_align("center");
-    let _ = ctx.fill_text("楚 河", 112.5 + PADDING, 250.0 + 8.0);
-    let _ = ctx.fill_text("漢 界", 337.5 - PADDING, 250.0 + 8.0);
+    let river_y = 4.5f64.mul_add(CELL_SIZE, PADDING) + 8.0;
+    let _ = ctx.fill_text("楚 河", 2.25f64.mul_add(CELL_SIZE, PADDING), river_y);
+    let _ = ctx.fill_text("漢 界", 5.75f64.mul_add(CELL_SIZE, PADDING), river_y);
 
-    // Pieces
+    // Coordinates (ICCS: files a-i from Red's left, ranks 0-9 from Red's back rank)
+    ctx.set_font("12px sans-serif");
+    ctx.set_text_baseline("middle");
+    for (c, file) in (b'a'..=b'i').enumerate() {
+        let (x, _) = get_visual_coords(0, c, orientation);
+        let _ = ctx.fill_text(
+            &char::from(file).to_string(),
+            x,
+            BOARD_HEIGHT - PADDING / 4.0,
+        );
+    }
+    for r in 0..10 {
+        let (_, y) = get_visual_coords(r, 0, orientation);
+        let _ = ctx.fill_text(&r.to_string(), PADDING / 4.0, y);
+    }
+
+    // Attacked squares, tinted per side (both tints mix where the sides contest a square)
+    if let Some(threats) = threats {
+        for (bb, color) in [
+            (threats.red, "rgba(220, 40, 40, 0.22)"),
+            (threats.black, "rgba(40, 90, 220, 0.22)"),
+        ] {
+            ctx.set_fill_style(&color.into());
+            for sq in 0..90 {
+                if bb & (1u128 << sq) != 0 {
+                    let (x, y) = get_visual_coords(sq / 9, sq % 9, orientation);
+                    let half = CELL_SIZE / 2.0 - 3.0;
+                    ctx.fill_rect(x - half, y - half, 2.0 * half, 2.0 * half);
+                }
+            }
+        }
+    }
+
+    // Influence heatmap: deeper tint where more pieces of one side outnumber the other's
+    if let Some(control) = influence {
+        for (sq, &balance) in control.iter().enumerate() {
+            if balance == 0 {
+                continue;
+            }
+            let alpha = f64::from(balance.unsigned_abs().min(4)) * 0.1;
+            let color = if balance > 0 {
+                format!("rgba(220, 40, 40, {alpha:.2})")
+            } else {
+                format!("rgba(40, 90, 220, {alpha:.2})")
+            };
+            ctx.set_fill_style(&color.into());
+            let (x, y) = get_visual_coords(sq / 9, sq % 9, orientation);
+            let half = CELL_SIZE / 2.0;
+            ctx.fill_rect(x - half, y - half, CELL_SIZE, CELL_SIZE);
+        }
+    }
+
+    // Pieces (the destination of an animating move is drawn separately below)
     for r in 0..10 {
         for c in 0..9 {
             if let Some(coord) = BoardCoordinate::new(r, c) {
+                if animation.is_some_and(|(anim, _)| anim.to == coord) {
+                    continue;
+                }
                 if let Some(piece) = state.board.get_piece(coord) {
-                    let (x, y) = get_visual_coords(r, c, player_side);
-                    draw_piece(ctx, x, y, piece, selected == Some((r, c)));
+                    let (x, y) = get_visual_coords(r, c, orientation);
+                    draw_piece(ctx, theme, x, y, piece, selected == Some((r, c)));
                 }
             }
         }
     }
 
+    // Hanging pieces: attacked and not defended
+    if let Some(threats) = threats.filter(|t| t.hanging != 0) {
+        ctx.set_stroke_style(&"rgba(255, 87, 34, 0.9)".into());
+        ctx.set_line_width(3.0);
+        for sq in 0..90 {
+            if threats.hanging & (1u128 << sq) != 0 {
+                let (x, y) = get_visual_coords(sq / 9, sq % 9, orientation);
+                ctx.begin_path();
+                let _ = ctx.arc(x, y, 26.0, 0.0, std::f64::consts::PI * 2.0);
+                ctx.stroke();
+            }
+        }
+    }
+
+    // Moving piece, sliding over the captured piece as it fades out
+    if let Some((anim, t)) = animation {
+        let (x1, y1) = get_visual_coords(anim.from.row, anim.from.col, orientation);
+        let (x2, y2) = get_visual_coords(anim.to.row, anim.to.col, orientation);
+        if let Some(captured) = anim.captured {
+            ctx.set_global_alpha(1.0 - t);
+            draw_piece(ctx, theme, x2, y2, captured, false);
+            ctx.set_global_alpha(1.0);
+        }
+        draw_piece(
+            ctx,
+            theme,
+            (x2 - x1).mul_add(t, x1),
+            (y2 - y1).mul_add(t, y1),
+            anim.piece,
+            false,
+        );
+    }
+
+    // Queued pre-move, grayed out until it is sent
+    if let Some((from, to)) = premove {
+        if let Some(piece) = state.board.get_piece(from) {
+            let (x1, y1) = get_visual_coords(from.row, from.col, orientation);
+            let (x2, y2) = get_visual_coords(to.row, to.col, orientation);
+            ctx.set_stroke_style(&"rgba(120, 120, 120, 0.7)".into());
+            ctx.set_line_width(4.0);
+            ctx.set_line_cap("round");
+            ctx.begin_path();
+            ctx.move_to(x1, y1);
+            ctx.line_to(x2, y2);
+            ctx.stroke();
+            ctx.set_global_alpha(0.45);
+            draw_piece(ctx, theme, x2, y2, piece, false);
+            ctx.set_global_alpha(1.0);
+        }
+    }
+
     // Highlights
     for &(r, c) in valid_moves {
-        let (x, y) = get_visual_coords(r, c, player_side);
+        let (x, y) = get_visual_coords(r, c, orientation);
         ctx.begin_path();
         let _ = ctx.arc(x, y, 8.0, 0.0, std::f64::consts::PI * 2.0);
         let color = if BoardCoordinate::new(r, c)
@@ -254,8 +598,8 @@ fn draw_board(
 
     // Last Move
     if let Some((from, to)) = state.last_move {
-        let (x1, y1) = get_visual_coords(from.row, from.col, player_side);
-        let (x2, y2) = get_visual_coords(to.row, to.col, player_side);
+        let (x1, y1) = get_visual_coords(from.row, from.col, orientation);
+        let (x2, y2) = get_visual_coords(to.row, to.col, orientation);
         ctx.set_stroke_style(&"rgba(255, 165, 0, 0.6)".into());
         ctx.set_line_width(6.0);
         ctx.set_line_cap("round");
@@ -270,20 +614,180 @@ fn draw_board(
             ctx.fill();
         }
     }
+
+    draw_annotations(ctx, annotations, orientation);
+
+    // Keyboard cursor
+    if let Some((r, c)) = cursor {
+        let (x, y) = get_visual_coords(r, c, orientation);
+        let half = CELL_SIZE / 2.0 - 1.0;
+        ctx.set_stroke_style(&"rgba(25, 118, 210, 0.95)".into());
+        ctx.set_line_width(3.0);
+        ctx.stroke_rect(x - half, y - half, 2.0 * half, 2.0 * half);
+    }
+}
+
+/// Paints right-click annotations above everything else.
+fn draw_annotations(
+    ctx: &CanvasRenderingContext2d,
+    annotations: &[Annotation],
+    orientation: Color,
+) {
+    let color = "rgba(21, 120, 27, 0.75)";
+    ctx.set_stroke_style(&color.into());
+    ctx.set_fill_style(&color.into());
+    ctx.set_line_cap("butt");
+
+    for annotation in annotations {
+        match *annotation {
+            Annotation::Square(at) => {
+                let (x, y) = get_visual_coords(at.row, at.col, orientation);
+                ctx.set_line_width(4.0);
+                ctx.begin_path();
+                let _ = ctx.arc(x, y, CELL_SIZE / 2.0 - 2.0, 0.0, std::f64::consts::PI * 2.0);
+                ctx.stroke();
+            }
+            Annotation::Arrow { from, to } => {
+                let (x1, y1) = get_visual_coords(from.row, from.col, orientation);
+                let (x2, y2) = get_visual_coords(to.row, to.col, orientation);
+                let angle = (y2 - y1).atan2(x2 - x1);
+                let head = 18.0;
+                // Stop the shaft under the arrow head so the tip stays sharp
+                let (bx, by) = (x2 - head * angle.cos(), y2 - head * angle.sin());
+
+                ctx.set_line_width(8.0);
+                ctx.begin_path();
+                ctx.move_to(x1, y1);
+                ctx.line_to(bx, by);
+                ctx.stroke();
+
+                ctx.begin_path();
+                ctx.move_to(x2, y2);
+                for side in [-1.0, 1.0] {
+                    let spread = angle + side * 0.5;
+                    ctx.line_to(x2 - head * spread.cos(), y2 - head * spread.sin());
+                }
+                ctx.close_path();
+                ctx.fill();
+            }
+        }
+    }
+    ctx.set_line_cap("round");
+}
+
+/// Height of the caption strip under an exported board, in drawing units.
+const CAPTION_HEIGHT: f64 = 36.0;
+/// Device pixels per drawing unit in exported images, sharp enough for printing.
+const EXPORT_SCALE: f64 = 2.0;
+/// Animation frames stay at one pixel per unit: NeuQuant and GIF's LZW both slow down with
+/// the pixel count, and a game has a frame per position.
+const ANIMATION_SCALE: f64 = 1.0;
+
+/// Draws `state` on an offscreen canvas, the way the board shows it without selection or
+/// zoom, with `caption` in a strip below.
+#[allow(deprecated, clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn render_offscreen(
+    state: &GameState,
+    theme: Theme,
+    orientation: Color,
+    annotations: &[Annotation],
+    caption: &str,
+    scale: f64,
+) -> Option<(web_sys::HtmlCanvasElement, CanvasRenderingContext2d)> {
+    let canvas = document()
+        .create_element("canvas")
+        .ok()?
+        .dyn_into::<web_sys::HtmlCanvasElement>()
+        .ok()?;
+    canvas.set_width((BOARD_WIDTH * scale) as u32);
+    canvas.set_height(((BOARD_HEIGHT + CAPTION_HEIGHT) * scale) as u32);
+    let ctx = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<CanvasRenderingContext2d>()
+        .ok()?;
+
+    draw_board(
+        &ctx,
+        scale,
+        theme,
+        state,
+        None,
+        &[],
+        orientation,
+        None,
+        None,
+        annotations,
+        None,
+        None,
+        None,
+        Zoom::default(),
+    );
+
+    let _ = ctx.set_transform(scale, 0.0, 0.0, scale, 0.0, 0.0);
+    let palette = theme.palette();
+    ctx.set_fill_style(&palette.grid.into());
+    ctx.fill_rect(0.0, BOARD_HEIGHT, BOARD_WIDTH, CAPTION_HEIGHT);
+    ctx.set_fill_style(&palette.background.into());
+    ctx.set_font("bold 16px sans-serif");
+    ctx.set_text_align("center");
+    ctx.set_text_baseline("middle");
+    let _ = ctx.fill_text(
+        caption,
+        BOARD_WIDTH / 2.0,
+        BOARD_HEIGHT + CAPTION_HEIGHT / 2.0,
+    );
+    Some((canvas, ctx))
+}
+
+/// The position as a PNG data URL, see `render_offscreen`.
+pub fn render_png(
+    state: &GameState,
+    theme: Theme,
+    orientation: Color,
+    annotations: &[Annotation],
+    caption: &str,
+) -> Option<String> {
+    let (canvas, _) = render_offscreen(
+        state,
+        theme,
+        orientation,
+        annotations,
+        caption,
+        EXPORT_SCALE,
+    )?;
+    canvas.to_data_url_with_type("image/png").ok()
+}
+
+/// The position as one frame of an animation: width, height and RGBA pixels.
+pub fn render_frame(
+    state: &GameState,
+    theme: Theme,
+    orientation: Color,
+    caption: &str,
+) -> Option<(u32, u32, Vec<u8>)> {
+    let (canvas, ctx) = render_offscreen(state, theme, orientation, &[], caption, ANIMATION_SCALE)?;
+    let (width, height) = (canvas.width(), canvas.height());
+    let image = ctx
+        .get_image_data(0.0, 0.0, f64::from(width), f64::from(height))
+        .ok()?;
+    Some((width, height, image.data().0))
 }
 
 #[component]
 fn CapturedPieces(
-    game_state: ReadSignal<GameState>,
-    player_side: ReadSignal<Color>,
+    #[prop(into)] game_state: Signal<GameState>,
+    /// Side shown at the bottom of the board.
+    orientation: Signal<Color>,
+    theme: ReadSignal<Theme>,
     is_top: bool,
 ) -> impl IntoView {
     let captured_row_style = "display: flex; justify-content: center; gap: 5px; width: 100%; min-height: 30px; margin: 5px 0; flex-wrap: wrap;";
-    let captured_piece_style = |c: Color| {
+    let captured_piece_style = |theme: Theme, c: Color| {
+        let color = theme.piece_color(c);
         format!(
-            "width: 28px; height: 28px; border-radius: 50%; background-color: #f0d9b5; color: {}; border: 1px solid {}; display: flex; justify-content: center; align-items: center; font-family: 'KaiTi', '楷体', serif; font-weight: bold; font-size: 18px; line-height: 1; box-shadow: 1px 1px 2px rgba(0,0,0,0.3);",
-            if c == Color::Red { "#c00" } else { "#000" },
-            if c == Color::Red { "#c00" } else { "#000" }
+            "width: 28px; height: 28px; border-radius: 50%; background-color: {}; color: {color}; border: 1px solid {color}; display: flex; justify-content: center; align-items: center; font-family: 'KaiTi', '楷体', serif; font-weight: bold; font-size: 18px; line-height: 1; box-shadow: 1px 1px 2px rgba(0,0,0,0.3);",
+            theme.palette().piece_face,
         )
     };
 
@@ -291,7 +795,8 @@ fn CapturedPieces(
         <div style=captured_row_style>
             {move || {
                 let state = game_state.get();
-                let side = player_side.get();
+                let side = orientation.get();
+                let theme = theme.get();
                 let target_color = if is_top {
                     if side == Color::Red { Color::Black } else { Color::Red }
                 } else {
@@ -303,8 +808,8 @@ fn CapturedPieces(
                     .filter(|p| p.color == target_color)
                     .map(|p| {
                         view! {
-                            <div style=captured_piece_style(target_color)>
-                                {get_piece_symbol(p.piece_type, target_color)}
+                            <div style=captured_piece_style(theme, target_color)>
+                                {theme.pieces.symbol(p.piece_type, target_color)}
                             </div>
                         }
                     }).collect::<Vec<_>>()
@@ -313,40 +818,74 @@ fn CapturedPieces(
     }
 }
 
+const fn side_name(color: Color) -> Msg {
+    match color {
+        Color::Red => Msg::Red,
+        Color::Black => Msg::Black,
+    }
+}
+
+/// What the game has come to, e.g. "Checkmate! Red wins!"; `None` while it is being played.
+fn result_text(
+    status: cotuong_core::logic::game::GameStatus,
+    language: Language,
+) -> Option<String> {
+    use cotuong_core::logic::game::GameStatus;
+    let winner = |color: Color| {
+        if color == Color::Red {
+            Msg::RedWins
+        } else {
+            Msg::BlackWins
+        }
+    };
+    Some(match status {
+        GameStatus::Playing => return None,
+        GameStatus::Checkmate(color) => format!(
+            "{} {}",
+            Msg::Checkmate.text(language),
+            winner(color).text(language)
+        ),
+        GameStatus::Resigned(Color::Red) => Msg::BlackResignsRedWins.text(language).to_string(),
+        GameStatus::Resigned(Color::Black) => Msg::RedResignsBlackWins.text(language).to_string(),
+        GameStatus::Stalemate => Msg::Stalemate.text(language).to_string(),
+        GameStatus::DrawAgreed => Msg::DrawAgreed.text(language).to_string(),
+    })
+}
+
 #[component]
 fn GameStatus(game_state: ReadSignal<GameState>) -> impl IntoView {
+    let language = use_language();
     view! {
         <div class="status" style="margin-top: 10px; font-size: 1.2em;">
             {move || {
                 let state = game_state.get();
+                let language = language.get();
                 match state.status {
                     cotuong_core::logic::game::GameStatus::Playing => {
                         let (icon, text, color) = if state.turn == Color::Red {
-                            ("🔴", "Lượt Đỏ", "#ff6b6b")
+                            ("🔴", Msg::RedToMove, "#ff6b6b")
                         } else {
-                            ("⚫", "Lượt Đen", "#888")
+                            ("⚫", Msg::BlackToMove, "#888")
                         };
                         view! {
                             <span style=format!("color: {}; font-weight: bold;", color)>
-                                {format!("{icon} {text}")}
+                                {format!("{icon} {}", text.text(language))}
                             </span>
                         }.into_view()
                     },
-                    cotuong_core::logic::game::GameStatus::Checkmate(winner) => {
-                        let (icon, text) = if winner == Color::Red {
-                            ("🏆🔴", "Đỏ thắng!")
-                        } else {
-                            ("🏆⚫", "Đen thắng!")
-                        };
+                    cotuong_core::logic::game::GameStatus::Checkmate(winner)
+                    | cotuong_core::logic::game::GameStatus::Resigned(winner) => {
+                        let icon = if winner == Color::Red { "🏆🔴" } else { "🏆⚫" };
                         view! {
                             <span style="color: #4CAF50; font-weight: bold; font-size: 1.3em;">
-                                {format!("{icon} Chiếu hết! {text}")}
+                                {format!("{icon} {}", result_text(state.status, language).unwrap_or_default())}
                             </span>
                         }.into_view()
                     },
-                    cotuong_core::logic::game::GameStatus::Stalemate => view! {
+                    cotuong_core::logic::game::GameStatus::Stalemate
+                    | cotuong_core::logic::game::GameStatus::DrawAgreed => view! {
                         <span style="color: #FF9800; font-weight: bold;">
-                            "🤝 Hòa cờ!"
+                            {result_text(state.status, language)}
                         </span>
                     }.into_view(),
                 }
@@ -363,196 +902,684 @@ pub fn BoardView(
     set_game_state: WriteSignal<GameState>,
     game_mode: ReadSignal<GameMode>,
     player_side: ReadSignal<Color>,
+    /// Shows the board from the other side, independent of `player_side`.
+    flipped: ReadSignal<bool>,
+    /// Layer drawn over the position; the influence heatmap is left out of online games.
+    overlay: ReadSignal<BoardOverlay>,
+    theme: ReadSignal<Theme>,
+    premove: ReadSignal<Option<Premove>>,
+    set_premove: WriteSignal<Option<Premove>>,
+    annotations: ReadSignal<Annotations>,
+    set_annotations: WriteSignal<Annotations>,
+    /// Number of moves of the position being reviewed, `None` for the live position.
+    view_ply: ReadSignal<Option<usize>>,
+    /// Ignores clicks, e.g. while spectating someone else's game.
+    #[prop(into)]
+    read_only: Signal<bool>,
+    /// Moves picked on the board wait for the confirm button before they are played.
+    #[prop(into)]
+    confirm_moves: Signal<bool>,
+    /// Blindfold play: the pieces are hidden unless revealed with the button under the board.
+    blindfold: ReadSignal<bool>,
     #[prop(optional)] on_move: Option<Rc<dyn Fn(Move)>>,
 ) -> impl IntoView {
     let (selected, set_selected) = create_signal(Option::<(usize, usize)>::None);
     let (valid_moves, set_valid_moves) = create_signal(Vec::<(usize, usize)>::new());
+    // Square under the keyboard focus ring, while the board has focus
+    let (cursor, set_cursor) = create_signal(Option::<(usize, usize)>::None);
+    let language = use_language();
     let canvas_ref: NodeRef<Canvas> = create_node_ref();
+    let container_ref: NodeRef<Div> = create_node_ref();
+    let (layout, set_layout) = create_signal(BoardLayout::default());
+    let (zoom, set_zoom) = create_signal(Zoom::default());
+    let (preview, set_preview) = create_signal(Option::<(usize, usize)>::None);
+    // Move picked while confirmation is on, drawn like a pre-move until confirmed
+    let (pending, set_pending) = create_signal(Option::<Premove>::None);
+    let gesture = store_value(TouchGesture::Idle);
+    let move_cache = store_value(LegalMoveCache::default());
+    let legal_targets = move |board: &Board, r: usize, c: usize| {
+        move_cache
+            .try_update_value(|cache| cache.targets(board, r, c))
+            .unwrap_or_default()
+    };
+    let remeasure = move || {
+        if let Some(measured) = container_ref
+            .get_untracked()
+            .and_then(|container| BoardLayout::measure(&container))
+        {
+            if measured != layout.get_untracked() {
+                set_layout.set(measured);
+            }
+        }
+    };
+    create_effect(move |_| {
+        if container_ref.get().is_some() {
+            remeasure();
+        }
+    });
+    // Some mobile browsers rotate without firing a resize
+    let on_resize = window_event_listener(ev::resize, move |_| remeasure());
+    let on_rotate = window_event_listener_untyped("orientationchange", move |_| remeasure());
+    on_cleanup(move || {
+        on_resize.remove();
+        on_rotate.remove();
+    });
+    let animation = store_value(Option::<MoveAnimation>::None);
+    let last_history_len = store_value(0usize);
+    // Position on screen: the live game, or an earlier one while browsing the history
+    let shown_state = Signal::derive(move || {
+        let mut state = game_state.get();
+        if let Some(ply) = view_ply.get() {
+            while state.history.len() > ply && state.undo_move() {}
+        }
+        state
+    });
+    let (revealed, set_revealed) = create_signal(false);
+    let hidden = move || blindfold.get() && !revealed.get();
+    let orientation = Signal::derive(move || {
+        if flipped.get() {
+            player_side.get().opposite()
+        } else {
+            player_side.get()
+        }
+    });
 
     let draw = move || {
-        let (Some(canvas), Some(window)) = (canvas_ref.get(), web_sys::window()) else {
+        let Some(canvas) = canvas_ref.get() else {
             return;
         };
-        let ratio = window.device_pixel_ratio();
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        canvas.set_width((BOARD_WIDTH * ratio) as u32);
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        canvas.set_height((BOARD_HEIGHT * ratio) as u32);
+        let layout = layout.get();
+        let (width, height) = layout.pixel_size();
+        canvas.set_width(width);
+        canvas.set_height(height);
 
         if let Ok(Some(ctx)) = canvas
             .get_context("2d")
             .map(|res| res.and_then(|o| o.dyn_into::<CanvasRenderingContext2d>().ok()))
         {
+            let mut state = shown_state.get();
+            let live = view_ply.get().is_none();
+            let fen = state.board.to_fen_string(state.turn);
+            let marks = annotations.with(|a| a.for_position(&fen).to_vec());
+            // Blindfold: an empty board, so nothing drawn from the position gives it away
+            let hidden = hidden();
+            if hidden {
+                state.board.clear();
+                state.last_move = None;
+            }
+            // A long-pressed piece shows its moves in place of the selection
+            let previewed = preview.get().and_then(|(r, c)| {
+                let piece = BoardCoordinate::new(r, c).and_then(|at| state.board.get_piece(at))?;
+                Some(((r, c), legal_targets(&state.board, r, c)))
+            });
+            let (shown_selection, moves) = match previewed {
+                Some((square, moves)) => (Some(square), moves),
+                None if live && !hidden => (selected.get(), valid_moves.get()),
+                None => (None, Vec::new()),
+            };
             draw_board(
                 &ctx,
-                ratio,
-                &game_state.get(),
-                selected.get(),
-                &valid_moves.get(),
-                player_side.get(),
+                layout.ratio * layout.scale(),
+                theme.get(),
+                &state,
+                shown_selection,
+                &moves,
+                orientation.get(),
+                animation
+                    .get_value()
+                    .filter(|_| live && !hidden)
+                    .and_then(|anim| anim.progress(now_ms()).map(|t| (anim, t))),
+                pending.get().or(premove.get()).filter(|_| live),
+                &marks,
+                (overlay.get() == BoardOverlay::Threats).then(|| ThreatOverlay::new(&state.board)),
+                (overlay.get() == BoardOverlay::Influence && game_mode.get() != GameMode::Online)
+                    .then(|| control_map(&state.board))
+                    .as_ref(),
+                cursor.get(),
+                zoom.get(),
             );
         }
     };
 
+    // Animate a single newly played move instead of redrawing it in place.
+    // Undo, resets and server corrections change the history by other amounts and just redraw.
     create_effect(move |_| {
+        let state = game_state.get();
+        let len = state.history.len();
+        let previous_len = last_history_len.get_value();
+        last_history_len.set_value(len);
+
+        if len == previous_len + 1 {
+            if let Some(record) = state.history.last() {
+                animation.set_value(Some(MoveAnimation {
+                    from: record.from,
+                    to: record.to,
+                    piece: record.piece,
+                    captured: record.captured,
+                    start: now_ms(),
+                }));
+                schedule_frames(Rc::new(move || {
+                    draw();
+                    animation
+                        .get_value()
+                        .is_some_and(|anim| anim.progress(now_ms()).is_some())
+                }));
+            }
+        }
+
         draw();
     });
 
-    let on_click = move |ev: web_sys::MouseEvent| {
-        let Some(canvas) = canvas_ref.get() else {
+    // While waiting for the opponent online, clicks queue a single pre-move instead
+    let queue_premove = move |r: usize, c: usize, state: &GameState| {
+        let own = player_side.get();
+        let clicked = BoardCoordinate::new(r, c);
+        let own_piece = clicked
+            .and_then(|coord| state.board.get_piece(coord))
+            .is_some_and(|p| p.color == own);
+        set_premove.set(None);
+
+        match selected.get() {
+            Some((fr, fc)) if !own_piece => {
+                if let (Some(from), Some(to)) = (BoardCoordinate::new(fr, fc), clicked) {
+                    if cotuong_core::logic::rules::is_valid_move(&state.board, from, to, own)
+                        .is_ok()
+                    {
+                        set_premove.set(Some((from, to)));
+                    }
+                }
+                set_selected.set(None);
+                set_valid_moves.set(Vec::new());
+            }
+            current if own_piece && current != Some((r, c)) => {
+                set_selected.set(Some((r, c)));
+                set_valid_moves.set(legal_targets(&state.board, r, c));
+            }
+            _ => {
+                set_selected.set(None);
+                set_valid_moves.set(Vec::new());
+            }
+        }
+    };
+
+    // Maps a mouse event to the board square under the cursor
+    let square_at = move |ev: &web_sys::MouseEvent| {
+        let scale = layout.get_untracked().scale();
+        let (x, y) = zoom.get_untracked().to_board((
+            f64::from(ev.offset_x()) / scale,
+            f64::from(ev.offset_y()) / scale,
+        ));
+        square_at_point(x, y, orientation.get_untracked())
+    };
+
+    // Right-button drag draws an arrow, a right click on one square circles it
+    let annotation_start = store_value(Option::<(usize, usize)>::None);
+    let on_mouse_down = move |ev: web_sys::MouseEvent| {
+        if ev.button() == 2 {
+            annotation_start.set_value(square_at(&ev));
+        }
+    };
+    let on_mouse_up = move |ev: web_sys::MouseEvent| {
+        if ev.button() != 2 {
+            return;
+        }
+        let start = annotation_start.get_value();
+        annotation_start.set_value(None);
+        let (Some((fr, fc)), Some((tr, tc))) = (start, square_at(&ev)) else {
             return;
         };
-        let rect = canvas.get_bounding_client_rect();
-        let (scale_x, scale_y) = (BOARD_WIDTH / rect.width(), BOARD_HEIGHT / rect.height());
-        let (click_x, click_y) = (
-            (f64::from(ev.client_x()) - rect.left()) * scale_x,
-            (f64::from(ev.client_y()) - rect.top()) * scale_y,
-        );
+        let (Some(from), Some(to)) = (BoardCoordinate::new(fr, fc), BoardCoordinate::new(tr, tc))
+        else {
+            return;
+        };
+        let annotation = if from == to {
+            Annotation::Square(from)
+        } else {
+            Annotation::Arrow { from, to }
+        };
+        let fen = shown_state.with_untracked(|s| s.board.to_fen_string(s.turn));
+        set_annotations.update(|a| a.toggle(&fen, annotation));
+    };
 
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        let mut col = ((click_x - PADDING + CELL_SIZE / 2.0) / CELL_SIZE).floor() as isize;
-        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-        let row_visual = ((click_y - PADDING + CELL_SIZE / 2.0) / CELL_SIZE).floor() as isize;
-        let mut row = 9 - row_visual;
+    // Plays a move on the live position and reports it to the mode's handler
+    let on_move = store_value(on_move);
+    let commit_move = move |mut state: GameState, from: BoardCoordinate, to: BoardCoordinate| {
+        if state.make_move(from, to).is_err() {
+            return false;
+        }
+        set_game_state.set(state);
+        on_move.with_value(|on_move| {
+            if let Some(cb) = on_move {
+                cb(Move {
+                    #[allow(clippy::cast_possible_truncation)]
+                    from_row: from.row as u8,
+                    #[allow(clippy::cast_possible_truncation)]
+                    from_col: from.col as u8,
+                    #[allow(clippy::cast_possible_truncation)]
+                    to_row: to.row as u8,
+                    #[allow(clippy::cast_possible_truncation)]
+                    to_col: to.col as u8,
+                    score: 0,
+                });
+            }
+        });
+        set_selected.set(None);
+        set_valid_moves.set(Vec::new());
+        true
+    };
 
-        if player_side.get() == Color::Black {
-            col = 8 - col;
-            row = 9 - row;
+    // A pending move only stands for the position it was picked in
+    create_effect(move |_| {
+        game_state.with(|_| ());
+        view_ply.with(|_| ());
+        confirm_moves.with(|_| ());
+        set_pending.set(None);
+    });
+
+    // Whether the side to move is played from this board right now
+    let can_move = move |state: &GameState| {
+        !read_only.get_untracked()
+            && view_ply.get_untracked().is_none()
+            && state.status == cotuong_core::logic::game::GameStatus::Playing
+            && (state.turn == player_side.get_untracked()
+                || matches!(
+                    game_mode.get_untracked(),
+                    GameMode::ComputerVsComputer | GameMode::HumanVsHuman
+                ))
+    };
+
+    // A click or a keyboard press on square (r, c): select a piece, or move the selected one
+    let select_square = move |r: usize, c: usize| {
+        // Selecting wipes the annotations of the current position
+        let fen = shown_state.with_untracked(|s| s.board.to_fen_string(s.turn));
+        if annotations.with_untracked(|a| !a.for_position(&fen).is_empty()) {
+            set_annotations.update(|a| a.clear_position(&fen));
         }
 
-        if (0..9).contains(&col) && (0..10).contains(&row) {
-            #[allow(clippy::cast_sign_loss)]
-            let (r, c) = (row as usize, col as usize);
-            let state = game_state.get();
+        // Past positions are for viewing only
+        if read_only.get() || view_ply.get().is_some() {
+            return;
+        }
 
-            if (game_mode.get() == GameMode::HumanVsComputer || game_mode.get() == GameMode::Online)
-                && state.turn != player_side.get()
-            {
-                return;
-            }
+        let state = game_state.get();
 
-            let current_turn = state.turn;
-            let clicked_piece =
-                BoardCoordinate::new(r, c).and_then(|coord| state.board.get_piece(coord));
-
-            if let Some((from_row, from_col)) = selected.get() {
-                if from_row == r && from_col == c {
-                    set_selected.set(None);
-                    set_valid_moves.set(Vec::new());
-                } else if let Some(p) = clicked_piece {
-                    if p.color == current_turn {
-                        set_selected.set(Some((r, c)));
-                        let mut moves = Vec::new();
-                        for tr in 0..10 {
-                            for tc in 0..9 {
-                                if let (Some(from), Some(to)) =
-                                    (BoardCoordinate::new(r, c), BoardCoordinate::new(tr, tc))
-                                {
-                                    if cotuong_core::logic::rules::is_valid_move(
-                                        &state.board,
-                                        from,
-                                        to,
-                                        current_turn,
-                                    )
-                                    .is_ok()
-                                    {
-                                        moves.push((tr, tc));
-                                    }
-                                }
-                            }
-                        }
-                        set_valid_moves.set(moves);
-                    } else {
-                        let mut new_state = state;
-                        if let (Some(from), Some(to)) = (
-                            BoardCoordinate::new(from_row, from_col),
-                            BoardCoordinate::new(r, c),
-                        ) {
-                            if new_state.make_move(from, to).is_ok() {
-                                set_game_state.set(new_state);
-                                if let Some(cb) = on_move.as_ref() {
-                                    cb(Move {
-                                        #[allow(clippy::cast_possible_truncation)]
-                                        from_row: from_row as u8,
-                                        #[allow(clippy::cast_possible_truncation)]
-                                        from_col: from_col as u8,
-                                        #[allow(clippy::cast_possible_truncation)]
-                                        to_row: r as u8,
-                                        #[allow(clippy::cast_possible_truncation)]
-                                        to_col: c as u8,
-                                        score: 0,
-                                    });
-                                }
-                                set_selected.set(None);
-                                set_valid_moves.set(Vec::new());
-                            }
-                        }
-                    }
-                } else {
-                    let mut new_state = state;
-                    if let (Some(from), Some(to)) = (
-                        BoardCoordinate::new(from_row, from_col),
-                        BoardCoordinate::new(r, c),
-                    ) {
-                        if new_state.make_move(from, to).is_ok() {
-                            set_game_state.set(new_state);
-                            if let Some(cb) = on_move.as_ref() {
-                                cb(Move {
-                                    #[allow(clippy::cast_possible_truncation)]
-                                    from_row: from_row as u8,
-                                    #[allow(clippy::cast_possible_truncation)]
-                                    from_col: from_col as u8,
-                                    #[allow(clippy::cast_possible_truncation)]
-                                    to_row: r as u8,
-                                    #[allow(clippy::cast_possible_truncation)]
-                                    to_col: c as u8,
-                                    score: 0,
-                                });
-                            }
-                            set_selected.set(None);
-                            set_valid_moves.set(Vec::new());
-                        }
+        if state.turn != player_side.get() {
+            match game_mode.get() {
+                GameMode::Online => {
+                    if state.status == cotuong_core::logic::game::GameStatus::Playing {
+                        queue_premove(r, c, &state);
                     }
+                    return;
                 }
-            } else if let Some(p) = clicked_piece {
-                if p.color == current_turn {
-                    set_selected.set(Some((r, c)));
-                    let mut moves = Vec::new();
-                    for tr in 0..10 {
-                        for tc in 0..9 {
-                            if let (Some(from), Some(to)) =
-                                (BoardCoordinate::new(r, c), BoardCoordinate::new(tr, tc))
-                            {
-                                if cotuong_core::logic::rules::is_valid_move(
-                                    &state.board,
-                                    from,
-                                    to,
-                                    current_turn,
-                                )
-                                .is_ok()
-                                {
-                                    moves.push((tr, tc));
-                                }
-                            }
-                        }
+                // Puzzle replies are played by the puzzle panel; lessons only move Red
+                GameMode::HumanVsComputer
+                | GameMode::Puzzle
+                | GameMode::Tutorial
+                | GameMode::Training => return,
+                GameMode::ComputerVsComputer | GameMode::HumanVsHuman => {}
+            }
+        }
+
+        let current_turn = state.turn;
+        let clicked_piece =
+            BoardCoordinate::new(r, c).and_then(|coord| state.board.get_piece(coord));
+
+        match selected.get() {
+            Some((from_row, from_col)) if (from_row, from_col) == (r, c) => {
+                set_selected.set(None);
+                set_valid_moves.set(Vec::new());
+            }
+            _ if clicked_piece.is_some_and(|p| p.color == current_turn) => {
+                set_selected.set(Some((r, c)));
+                set_valid_moves.set(legal_targets(&state.board, r, c));
+            }
+            Some((from_row, from_col)) => {
+                if let (Some(from), Some(to)) = (
+                    BoardCoordinate::new(from_row, from_col),
+                    BoardCoordinate::new(r, c),
+                ) {
+                    if !confirm_moves.get_untracked() {
+                        commit_move(state, from, to);
+                    } else if cotuong_core::logic::rules::is_valid_move(
+                        &state.board,
+                        from,
+                        to,
+                        current_turn,
+                    )
+                    .is_ok()
+                    {
+                        set_pending.set(Some((from, to)));
+                        set_selected.set(None);
+                        set_valid_moves.set(Vec::new());
                     }
-                    set_valid_moves.set(moves);
                 }
             }
+            None => {}
+        }
+    };
+
+    let on_click = move |ev: web_sys::MouseEvent| {
+        if let Some((r, c)) = square_at(&ev) {
+            select_square(r, c);
+        }
+    };
+
+    // Zooming is for studying past or spectated positions; playing always sees the whole board
+    let zoomable = move || view_ply.get().is_some() || read_only.get();
+    create_effect(move |_| {
+        if !zoomable() {
+            set_zoom.set(Zoom::default());
+        }
+    });
+
+    // Touches on the canvas, in drawing units before zoom
+    let touch_points = move |ev: &web_sys::TouchEvent| -> Vec<(f64, f64)> {
+        let Some(canvas) = canvas_ref.get_untracked() else {
+            return Vec::new();
+        };
+        let rect = canvas.get_bounding_client_rect();
+        let left = rect.left() + f64::from(canvas.client_left());
+        let top = rect.top() + f64::from(canvas.client_top());
+        let scale = layout.get_untracked().scale();
+        let touches = ev.touches();
+        (0..touches.length())
+            .filter_map(|i| touches.get(i))
+            .map(|t| {
+                (
+                    (f64::from(t.client_x()) - left) / scale,
+                    (f64::from(t.client_y()) - top) / scale,
+                )
+            })
+            .collect()
+    };
+    let cancel_press = move || {
+        if let TouchGesture::Press {
+            timer: Some(timer), ..
+        } = gesture.get_value()
+        {
+            timer.clear();
+        }
+    };
+
+    let on_touch_start = move |ev: web_sys::TouchEvent| {
+        cancel_press();
+        let current = zoom.get_untracked();
+        let points = touch_points(&ev);
+        let next = match points[..] {
+            [at] if current.scale > 1.0 => {
+                ev.prevent_default();
+                TouchGesture::Pan {
+                    anchor: current.to_board(at),
+                }
+            }
+            [at] => {
+                let (x, y) = current.to_board(at);
+                let square =
+                    square_at_point(x, y, orientation.get_untracked()).filter(|&(r, c)| {
+                        shown_state.with_untracked(|s| {
+                            BoardCoordinate::new(r, c)
+                                .and_then(|coord| s.board.get_piece(coord))
+                                .is_some()
+                        })
+                    });
+                let timer = square.and_then(|square| {
+                    set_timeout_with_handle(
+                        move || {
+                            gesture.set_value(TouchGesture::Preview);
+                            set_preview.set(Some(square));
+                        },
+                        Duration::from_millis(LONG_PRESS_MS),
+                    )
+                    .ok()
+                });
+                TouchGesture::Press { at, timer }
+            }
+            [a, b] if zoomable() => {
+                ev.prevent_default();
+                let mid = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+                TouchGesture::Pinch {
+                    start: current,
+                    anchor: current.to_board(mid),
+                    distance: (a.0 - b.0).hypot(a.1 - b.1).max(1.0),
+                }
+            }
+            _ => TouchGesture::Idle,
+        };
+        gesture.set_value(next);
+    };
+
+    let on_touch_move = move |ev: web_sys::TouchEvent| {
+        let points = touch_points(&ev);
+        match (gesture.get_value(), &points[..]) {
+            (TouchGesture::Press { at, .. }, [now])
+                if (now.0 - at.0).hypot(now.1 - at.1) > TOUCH_SLOP =>
+            {
+                cancel_press();
+                gesture.set_value(TouchGesture::Idle);
+            }
+            (TouchGesture::Preview, _) => ev.prevent_default(),
+            (
+                TouchGesture::Pinch {
+                    start,
+                    anchor,
+                    distance,
+                },
+                [a, b],
+            ) => {
+                ev.prevent_default();
+                let mid = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+                let scale = start.scale * (a.0 - b.0).hypot(a.1 - b.1) / distance;
+                set_zoom.set(Zoom::anchored(scale, anchor, mid));
+            }
+            (TouchGesture::Pan { anchor }, [at]) => {
+                ev.prevent_default();
+                let scale = zoom.get_untracked().scale;
+                set_zoom.set(Zoom::anchored(scale, anchor, *at));
+            }
+            _ => {}
+        }
+    };
+
+    let on_touch_end = move |ev: web_sys::TouchEvent| {
+        cancel_press();
+        match gesture.get_value() {
+            // A quick tap goes on to the click handler as usual
+            TouchGesture::Idle | TouchGesture::Press { .. } => {}
+            // No click after a preview, a pinch or a pan
+            TouchGesture::Preview | TouchGesture::Pinch { .. } | TouchGesture::Pan { .. } => {
+                ev.prevent_default();
+            }
         }
+        set_preview.set(None);
+        gesture.set_value(TouchGesture::Idle);
+    };
+
+    // Arrow keys walk a cursor over the board as it is shown; Enter or Space acts on it
+    let on_key_down = move |ev: web_sys::KeyboardEvent| {
+        let (r, c) = cursor.get_untracked().unwrap_or((0, 4));
+        let (up, right): (isize, isize) = match ev.key().as_str() {
+            "ArrowUp" => (1, 0),
+            "ArrowDown" => (-1, 0),
+            "ArrowLeft" => (0, -1),
+            "ArrowRight" => (0, 1),
+            "Enter" | " " => {
+                ev.prevent_default();
+                set_cursor.set(Some((r, c)));
+                select_square(r, c);
+                return;
+            }
+            "Escape" => {
+                set_pending.set(None);
+                set_selected.set(None);
+                set_valid_moves.set(Vec::new());
+                return;
+            }
+            _ => return,
+        };
+        ev.prevent_default();
+        let sign = if orientation.get_untracked() == Color::Black {
+            -1
+        } else {
+            1
+        };
+        let row = r.saturating_add_signed(up * sign).min(9);
+        let col = c.saturating_add_signed(right * sign).min(8);
+        set_cursor.set(Some((row, col)));
+    };
+
+    // Typed moves: WXF (`C2=5`), ICCS (`h2e2`) or `H2-E2`
+    let (move_text, set_move_text) = create_signal(String::new());
+    let (move_error, set_move_error) = create_signal(Option::<Msg>::None);
+    let submit_text = move || {
+        let text = move_text.get_untracked();
+        let state = game_state.get_untracked();
+        if !can_move(&state) {
+            set_move_error.set(Some(Msg::MoveNotYourTurn));
+            return;
+        }
+        let (from, to) = match parse_move(&state.board, state.turn, &text) {
+            Ok(mv) => mv,
+            Err(MoveTextError::Unreadable) => {
+                set_move_error.set(Some(Msg::MoveUnreadable));
+                return;
+            }
+            Err(MoveTextError::Illegal) => {
+                set_move_error.set(Some(Msg::MoveIllegal));
+                return;
+            }
+        };
+        if commit_move(state, from, to) {
+            set_move_text.set(String::new());
+            set_move_error.set(None);
+        } else {
+            set_move_error.set(Some(Msg::MoveIllegal));
+        }
+    };
+    // The typed move in both notations once it reads as a legal move, so it can be checked
+    // before pressing Enter
+    let move_preview = move || {
+        let text = move_text.get();
+        game_state.with(|state| {
+            let (from, to) = parse_move(&state.board, state.turn, &text).ok()?;
+            let wxf = WxfMove::describe(&state.board, from, to)?;
+            Some(format!("{wxf} · {}", format_iccs(from, to)))
+        })
+    };
+
+    // Read out by screen readers: the last move, check and the end of the game.
+    // Pieces are only named in words in Vietnamese; other languages hear the WXF move.
+    let announcement = move || {
+        let state = game_state.get();
+        let language = language.get();
+        let mut parts = Vec::new();
+        if let Some(record) = state.history.last() {
+            let mut before = state.clone();
+            if let Some(wxf) = before
+                .undo_move()
+                .then(|| WxfMove::describe(&before.board, record.from, record.to))
+                .flatten()
+            {
+                let spoken = if language == Language::Vietnamese {
+                    wxf.spoken()
+                } else {
+                    wxf.to_string()
+                };
+                parts.push(format!(
+                    "{}: {spoken}",
+                    side_name(record.color).text(language)
+                ));
+            }
+        }
+        if let Some(result) = result_text(state.status, language) {
+            parts.push(result);
+        } else if is_in_check(&state.board, state.turn) {
+            parts.push(Msg::Check.text(language).to_string());
+        }
+        parts.join(". ")
     };
 
     view! {
-        <div style="display: flex; flex-direction: column; align-items: center; padding: 5px; box-sizing: border-box;">
-            <CapturedPieces game_state=game_state player_side=player_side is_top=true />
+        <div
+            _ref=container_ref
+            style="display: flex; flex-direction: column; align-items: center; padding: 5px; box-sizing: border-box; width: 100%;"
+        >
+            {move || (!hidden()).then(|| view! {
+                <CapturedPieces game_state=shown_state orientation=orientation theme=theme is_top=true />
+            })}
             <canvas
                 _ref=canvas_ref
-                width=450
-                height=500
-                style="width: min(96vw, 72vh); aspect-ratio: 9/10; background-color: #eecfa1; border: 2px solid #5c3a1e; box-shadow: 0 5px 15px rgba(0,0,0,0.3); cursor: pointer; -webkit-tap-highlight-color: transparent;"
+                tabindex="0"
+                role="application"
+                aria-label=t(Msg::BoardLabel)
+                style=move || {
+                    let palette = theme.get().palette();
+                    let layout = layout.get();
+                    format!(
+                        "width: {:.1}px; height: {:.1}px; background-color: {}; border: 2px solid {}; box-shadow: 0 5px 15px rgba(0,0,0,0.3); cursor: pointer; -webkit-tap-highlight-color: transparent; -webkit-touch-callout: none; user-select: none; touch-action: {};",
+                        layout.width(), layout.height(), palette.background, palette.grid,
+                        // Pinches zoom the board instead of the page while reviewing
+                        if zoomable() { "none" } else { "manipulation" }
+                    )
+                }
                 on:click=on_click
+                on:keydown=on_key_down
+                on:focus=move |_| {
+                    if cursor.get_untracked().is_none() {
+                        set_cursor.set(Some((0, 4)));
+                    }
+                }
+                on:blur=move |_| set_cursor.set(None)
+                on:touchstart=on_touch_start
+                on:touchmove=on_touch_move
+                on:touchend=on_touch_end
+                on:touchcancel=on_touch_end
+                on:mousedown=on_mouse_down
+                on:mouseup=on_mouse_up
+                on:contextmenu=|ev: web_sys::MouseEvent| ev.prevent_default()
             />
-            <CapturedPieces game_state=game_state player_side=player_side is_top=false />
+            {move || (!hidden()).then(|| view! {
+                <CapturedPieces game_state=shown_state orientation=orientation theme=theme is_top=false />
+            })}
+            <div class="move-entry" style=move || format!("width: {:.1}px;", layout.get().width())>
+                <input
+                    type="text"
+                    placeholder=t(Msg::MoveInputPlaceholder)
+                    aria-label=t(Msg::MoveInputLabel)
+                    maxlength="10"
+                    prop:value=move || move_text.get()
+                    on:input=move |ev| set_move_text.set(event_target_value(&ev))
+                    on:keydown=move |ev: web_sys::KeyboardEvent| {
+                        if ev.key() == "Enter" {
+                            submit_text();
+                        }
+                    }
+                />
+                <button class="control-btn" on:click=move |_| submit_text()>{t(Msg::MoveSubmit)}</button>
+                {move || blindfold.get().then(|| view! {
+                    <button class="control-btn" on:click=move |_| set_revealed.update(|r| *r = !*r)>
+                        {move || if revealed.get() { Msg::HidePieces } else { Msg::RevealPieces }.text(language.get())}
+                    </button>
+                })}
+            </div>
+            {move || move_preview().map(|p| view! { <div class="move-entry-preview">{p}</div> })}
+            {move || pending.get().map(|(from, to)| view! {
+                <div class="move-confirm">
+                    <button
+                        class="control-btn btn-primary"
+                        on:click=move |_| {
+                            set_pending.set(None);
+                            commit_move(game_state.get_untracked(), from, to);
+                        }
+                    >
+                        {t(Msg::ConfirmMove)}
+                    </button>
+                    <button class="control-btn" on:click=move |_| set_pending.set(None)>
+                        {t(Msg::CancelMove)}
+                    </button>
+                </div>
+            })}
+            {move || move_error.get().map(|e| view! { <div class="move-entry-error" role="alert">{e.text(language.get())}</div> })}
+            <div class="sr-only" aria-live="polite">{announcement}</div>
             <GameStatus game_state=game_state />
         </div>
     }
@@ -1 +1,3 @@
+pub mod annotations;
 pub mod board;
+pub mod theme;
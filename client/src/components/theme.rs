@@ -0,0 +1,210 @@
+use crate::i18n::Msg;
+use cotuong_core::logic::board::{Color, PieceType};
+use serde::{Deserialize, Serialize};
+use web_sys::CanvasRenderingContext2d;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BoardStyle {
+    #[default]
+    Classic,
+    Wood,
+    Jade,
+    Night,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PieceStyle {
+    /// Traditional characters (帥, 車, 炮...).
+    #[default]
+    Chinese,
+    /// Latin letters as used in WXF notation (K, R, C...).
+    International,
+}
+
+/// Colors used to paint one board style.
+#[derive(Debug, Clone, Copy)]
+pub struct BoardPalette {
+    pub background: &'static str,
+    pub grid: &'static str,
+    pub piece_face: &'static str,
+    pub red: &'static str,
+    pub black: &'static str,
+    /// Stroke color of the wood grain drawn over the background, if any.
+    pub grain: Option<&'static str>,
+}
+
+/// Visual settings for the board, persisted as part of `UserPrefs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Theme {
+    pub board: BoardStyle,
+    pub pieces: PieceStyle,
+}
+
+impl BoardStyle {
+    pub const ALL: [Self; 4] = [Self::Classic, Self::Wood, Self::Jade, Self::Night];
+
+    pub const fn label(self) -> Msg {
+        match self {
+            Self::Classic => Msg::BoardClassic,
+            Self::Wood => Msg::BoardWood,
+            Self::Jade => Msg::BoardJade,
+            Self::Night => Msg::BoardNight,
+        }
+    }
+
+    pub const fn palette(self) -> BoardPalette {
+        match self {
+            Self::Classic => BoardPalette {
+                background: "#eecfa1",
+                grid: "#5c3a1e",
+                piece_face: "#f0d9b5",
+                red: "#c00",
+                black: "#000",
+                grain: None,
+            },
+            Self::Wood => BoardPalette {
+                background: "#c8955c",
+                grid: "#3e2410",
+                piece_face: "#f3dcb2",
+                red: "#b00",
+                black: "#111",
+                grain: Some("rgba(90, 50, 20, 0.18)"),
+            },
+            Self::Jade => BoardPalette {
+                background: "#b9d6c2",
+                grid: "#2f5d46",
+                piece_face: "#f4f1e6",
+                red: "#b71c1c",
+                black: "#1b2b24",
+                grain: None,
+            },
+            Self::Night => BoardPalette {
+                background: "#2b2f36",
+                grid: "#8a94a6",
+                piece_face: "#d9d4c7",
+                red: "#e53935",
+                black: "#222",
+                grain: None,
+            },
+        }
+    }
+}
+
+impl PieceStyle {
+    pub const ALL: [Self; 2] = [Self::Chinese, Self::International];
+
+    pub const fn label(self) -> Msg {
+        match self {
+            Self::Chinese => Msg::PiecesChinese,
+            Self::International => Msg::PiecesInternational,
+        }
+    }
+
+    pub const fn font(self) -> &'static str {
+        match self {
+            Self::Chinese => "bold 32px KaiTi, serif",
+            Self::International => "bold 26px 'Segoe UI', Arial, sans-serif",
+        }
+    }
+
+    pub const fn symbol(self, p: PieceType, c: Color) -> &'static str {
+        let red = matches!(c, Color::Red);
+        match (self, p) {
+            (Self::Chinese, PieceType::General) => {
+                if red {
+                    "帥"
+                } else {
+                    "將"
+                }
+            }
+            (Self::Chinese, PieceType::Advisor) => {
+                if red {
+                    "仕"
+                } else {
+                    "士"
+                }
+            }
+            (Self::Chinese, PieceType::Elephant) => {
+                if red {
+                    "相"
+                } else {
+                    "象"
+                }
+            }
+            (Self::Chinese, PieceType::Horse) => {
+                if red {
+                    "傌"
+                } else {
+                    "馬"
+                }
+            }
+            (Self::Chinese, PieceType::Chariot) => {
+                if red {
+                    "俥"
+                } else {
+                    "車"
+                }
+            }
+            (Self::Chinese, PieceType::Cannon) => {
+                if red {
+                    "炮"
+                } else {
+                    "砲"
+                }
+            }
+            (Self::Chinese, PieceType::Soldier) => {
+                if red {
+                    "兵"
+                } else {
+                    "卒"
+                }
+            }
+            (Self::International, PieceType::General) => "K",
+            (Self::International, PieceType::Advisor) => "A",
+            (Self::International, PieceType::Elephant) => "E",
+            (Self::International, PieceType::Horse) => "H",
+            (Self::International, PieceType::Chariot) => "R",
+            (Self::International, PieceType::Cannon) => "C",
+            (Self::International, PieceType::Soldier) => "P",
+        }
+    }
+}
+
+impl Theme {
+    pub const fn palette(self) -> BoardPalette {
+        self.board.palette()
+    }
+
+    pub const fn piece_color(self, c: Color) -> &'static str {
+        let palette = self.palette();
+        match c {
+            Color::Red => palette.red,
+            Color::Black => palette.black,
+        }
+    }
+
+    /// Paints the board background, including wood grain for textured styles.
+    #[allow(deprecated)]
+    pub fn fill_background(self, ctx: &CanvasRenderingContext2d, width: f64, height: f64) {
+        let palette = self.palette();
+        ctx.set_fill_style(&palette.background.into());
+        ctx.fill_rect(0.0, 0.0, width, height);
+
+        let Some(grain) = palette.grain else {
+            return;
+        };
+        ctx.set_stroke_style(&grain.into());
+        ctx.set_line_width(1.5);
+        for line in 0..40 {
+            let offset = f64::from(line);
+            let y = offset.mul_add(13.0, 4.0);
+            ctx.begin_path();
+            ctx.move_to(0.0, y);
+            for step in 1..=18 {
+                let x = f64::from(step) * 25.0;
+                ctx.line_to(x, (x / 60.0 + offset).sin().mul_add(3.0, y));
+            }
+            ctx.stroke();
+        }
+    }
+}
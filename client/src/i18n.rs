@@ -0,0 +1,1876 @@
+//! UI translations. Each language is one catalog function that matches every `Msg`, so a
+//! new message does not compile until all three languages have it.
+use cotuong_core::engine::explain::MoveExplanation;
+use cotuong_core::engine::match_runner::format_pgn_move;
+use cotuong_core::logic::board::{BoardCoordinate, PieceType};
+use cotuong_core::logic::game::MoveAnnotation;
+use leptos::{provide_context, use_context, ReadSignal, Signal, SignalGet};
+use serde::{Deserialize, Serialize};
+use shared::ErrorCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Vietnamese,
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Self; 3] = [Self::Vietnamese, Self::English, Self::Chinese];
+
+    /// BCP 47 code, as used by `<html lang>` and `navigator.language`.
+    pub const fn code(self) -> &'static str {
+        match self {
+            Self::Vietnamese => "vi",
+            Self::English => "en",
+            Self::Chinese => "zh",
+        }
+    }
+
+    /// The language's own name, so it can be found whatever the current language.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Vietnamese => "Tiếng Việt",
+            Self::English => "English",
+            Self::Chinese => "中文",
+        }
+    }
+
+    /// The browser's preferred language, if it is one we translate.
+    pub fn detect() -> Option<Self> {
+        let preferred = web_sys::window()?.navigator().language()?;
+        let primary = preferred.split('-').next()?.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|lang| lang.code() == primary)
+    }
+}
+
+/// Makes the chosen language available to every component below the caller.
+pub fn provide_language(language: ReadSignal<Language>) {
+    provide_context(language);
+}
+
+/// The current language; Vietnamese when no `provide_language` is above.
+pub fn use_language() -> Signal<Language> {
+    use_context::<ReadSignal<Language>>()
+        .map_or_else(|| Signal::derive(Language::default), Signal::from)
+}
+
+/// Reactive text for `msg`, for use directly in `view!`.
+pub fn t(msg: Msg) -> impl Fn() -> &'static str + Copy {
+    let language = use_language();
+    move || msg.text(language.get())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    Mode,
+    ModeHumanVsComputer,
+    ModeComputerVsComputer,
+    ModeHumanVsHuman,
+    ModeOnline,
+    ModePuzzle,
+    ModeTutorial,
+    ModeTraining,
+    Side,
+    SideRedFirst,
+    SideBlackSecond,
+    Difficulty,
+    Level1,
+    Level2,
+    Level3,
+    Level4,
+    Level5,
+    Handicap,
+    HandicapTitle,
+    HandicapNone,
+    HandicapHorse,
+    HandicapTwoHorses,
+    HandicapChariot,
+    HandicapTwoMoves,
+    BoardStyle,
+    PieceStyle,
+    Sound,
+    LanguageLabel,
+    ConfirmMoves,
+    ConfirmOff,
+    ConfirmImportant,
+    ConfirmAlways,
+    ConfirmMove,
+    CancelMove,
+    Start,
+    Pause,
+    NewGame,
+    Undo,
+    FlipBoard,
+    Blindfold,
+    BlindfoldOff,
+    BlindfoldTitle,
+    RevealPieces,
+    HidePieces,
+    Hotseat,
+    HotseatOff,
+    HotseatTitle,
+    PassDevice,
+    HandoffReady,
+    ScoreTitle,
+    ScoreSession,
+    ScoreAllTime,
+    ScoreBestStreak,
+    ScoreReset,
+    ShowThreats,
+    HideThreats,
+    ThreatsTitle,
+    ShowHeatmap,
+    HideHeatmap,
+    HeatmapTitle,
+    FindMate,
+    ExportCsv,
+    ExportJson,
+    ExportPgn,
+    ExportPng,
+    ExportGif,
+    GifSpeed,
+    GifEncoding,
+    GifMove,
+    ImageEval,
+    ImageStaticEval,
+    Red,
+    Black,
+    RedToMove,
+    BlackToMove,
+    Checkmate,
+    RedWins,
+    BlackWins,
+    Stalemate,
+    BlackResignsRedWins,
+    RedResignsBlackWins,
+    DrawAgreed,
+    Check,
+    GameOverDraw,
+    BoardLabel,
+    MoveInputPlaceholder,
+    MoveInputLabel,
+    MoveSubmit,
+    MoveNotYourTurn,
+    MoveUnreadable,
+    MoveIllegal,
+    Benchmark,
+    BenchmarkTitle,
+    BenchmarkRunning,
+    BenchmarkFailed,
+    BenchmarkSlow,
+    BenchmarkFullStrength,
+    UseLevel,
+    NodesPerSecond,
+    NavHome,
+    NavPlay,
+    NavOnline,
+    NavAnalysis,
+    NavPuzzles,
+    NavSettings,
+    NavProfile,
+    HomePlay,
+    HomeOnline,
+    HomeAnalysis,
+    HomePuzzles,
+    HomeSettings,
+    HomeProfile,
+    ProfileLookup,
+    ProfileOpen,
+    ServerUnreachable,
+    Wins,
+    Losses,
+    Draws,
+    ArenaShow,
+    ArenaHide,
+    ArenaNamePlaceholder,
+    RedConfig,
+    BlackConfig,
+    ArenaSubmit,
+    ArenaConfig,
+    ArenaPending,
+    ArenaLoadFailed,
+    ArenaSubmitted,
+    ArenaInvalidName,
+    ArenaNeedsSession,
+    ArenaNeedsRatedGame,
+    ArenaNameTaken,
+    ArenaTooMany,
+    ArenaUnavailable,
+    ArenaRejected,
+    ShareCopied,
+    ShareCopyBelow,
+    SharePosition,
+    ShareGame,
+    FenInvalid,
+    FenCopied,
+    FenCopyBelow,
+    FenClipboardUnreadable,
+    FenCopy,
+    FenPaste,
+    FenPlaceholder,
+    SharedPositionInvalid,
+    ReplayInvalid,
+    ReplayNotFound,
+    RecordCoordinates,
+    RecordWxf,
+    RecordChinese,
+    RecordNoMoves,
+    RecordUnreadable,
+    RecordIllegal,
+    ImportTitle,
+    ImportPlaceholder,
+    ImportButton,
+    NextPosition,
+    TryAgain,
+    TrainingSolved,
+    TrainingMoves,
+    TrainingComplete,
+    TrainingFailed,
+    StageEndgame,
+    StageMiddlegame,
+    Depth,
+    TraceRoot,
+    TraceNullMove,
+    TraceSingular,
+    TraceMateDistance,
+    TraceRepetition,
+    TraceTranspositionTable,
+    TraceQuiescence,
+    TraceProbCut,
+    TraceReverseFutility,
+    TraceNullMoveCut,
+    TraceNoLegalMoves,
+    TraceMatingMove,
+    TraceBetaCutoff,
+    TraceTimeUp,
+    TraceDynamicLimit,
+    TraceLateMovePruning,
+    TraceFutility,
+    TraceRepeatsPosition,
+    SearchTreeTitle,
+    SearchTreeRecord,
+    SearchTreeRunning,
+    SearchTreeFailed,
+    OnlineScore,
+    OnlineTitle,
+    OnlineNamePlaceholder,
+    OnlineHandicapTitle,
+    OnlineFindMatch,
+    OnlineFinding,
+    OnlineCancelFind,
+    OnlineMatchFound,
+    OnlineYourSide,
+    OnlineRedFirst,
+    OnlineBlackSecond,
+    OnlineYourTurn,
+    OnlineSending,
+    OnlineWaiting,
+    OnlineResign,
+    OnlineOpponentLeft,
+    OnlineNewMatch,
+    OnlineYouWin,
+    OnlineYouLose,
+    OnlineDraw,
+    OnlineGameOver,
+    OnlineReason,
+    OnlineReadyWaiting,
+    OnlineRematch,
+    OnlineLeave,
+    SpectateTitle,
+    SpectateStop,
+    EndCheckmate,
+    EndResignation,
+    EndDrawAgreement,
+    EndTimeout,
+    EndAbandoned,
+    EndAdjudication,
+    EndTerminated,
+    AbortNoFirstMove,
+    ConfigLoadJson,
+    ConfigExportJson,
+    ConfigStyle,
+    ConfigPick,
+    ConfigHashMove,
+    ConfigCaptureBase,
+    ConfigKillerMove,
+    ConfigHistoryMax,
+    ConfigPruningMethod,
+    PruneDynamic,
+    PruneLmr,
+    PruneBoth,
+    ConfigMultiplier,
+    ConfigMateScore,
+    ConfigKingSafety,
+    ConfigContempt,
+    ConfigTtSize,
+    ConfigResignScore,
+    ConfigResignMoves,
+    ConfigDrawScore,
+    ConfigDrawMoves,
+    ProfileBalanced,
+    ProfileAggressive,
+    ProfilePositional,
+    ProfileMaterialist,
+    ProfileGambit,
+    FriendsUntimed,
+    PolicyForfeit,
+    PolicyAdjudicate,
+    FriendsAccept,
+    FriendsDecline,
+    FriendsCancel,
+    FriendsShow,
+    FriendsHide,
+    FriendsNamePlaceholder,
+    FriendsAdd,
+    FriendsTime,
+    FriendsOnDisconnect,
+    FriendsNone,
+    FriendsChallenge,
+    FriendsRemove,
+    BoardClassic,
+    BoardWood,
+    BoardJade,
+    BoardNight,
+    PiecesChinese,
+    PiecesInternational,
+    JudgeInaccuracy,
+    JudgeMistake,
+    JudgeBlunder,
+    ReviewAccuracy,
+    ReviewAvgLoss,
+    ReviewGame,
+    ReviewNoMistakes,
+    ReviewGraph,
+    ReviewStartPosition,
+    MateSearching,
+    MoveTakenBack,
+    ChallengeDeclined,
+    AppTitle,
+    PuzzleYourMove,
+    PuzzleOpponentMoving,
+    PuzzleTactic,
+    PuzzleCorrect,
+    PuzzleWrong,
+    PuzzleSkip,
+    ProfileLoadFailed,
+    ProfileBack,
+    ProfileGames,
+    ProfileEloChart,
+    ProfileTooFewGames,
+    ProfileFavouriteOpenings,
+    ProfileNoOpenings,
+    LiveLoadFailed,
+    LiveShow,
+    LiveHide,
+    LiveNone,
+    LiveMoves,
+    LiveOpening,
+    LiveTimeControl,
+    LiveWatch,
+    LessonRetry,
+    LessonComplete,
+    LessonNext,
+    LessonRestart,
+    LeaderboardLoadFailed,
+    LeaderboardShow,
+    LeaderboardHide,
+    LeaderboardSearch,
+    LeaderboardPlayer,
+    LeaderboardViewProfile,
+    ExplorerTitle,
+    ExplorerOutOfBook,
+    ExplorerWeight,
+    LogTitle,
+    LogLive,
+    EngineThinking,
+    InstallApp,
+}
+
+impl Msg {
+    pub const fn text(self, language: Language) -> &'static str {
+        match language {
+            Language::Vietnamese => vietnamese(self),
+            Language::English => english(self),
+            Language::Chinese => chinese(self),
+        }
+    }
+}
+
+const fn vietnamese(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Mode => "Chế độ",
+        Msg::ModeHumanVsComputer => "Người vs Máy",
+        Msg::ModeComputerVsComputer => "Máy vs Máy",
+        Msg::ModeHumanVsHuman => "Người vs Người",
+        Msg::ModeOnline => "🌐 Chơi Online",
+        Msg::ModePuzzle => "🧩 Giải thế cờ",
+        Msg::ModeTutorial => "📘 Học chơi",
+        Msg::ModeTraining => "🎯 Luyện thế cờ với máy",
+        Msg::Side => "Chọn bên",
+        Msg::SideRedFirst => "Đỏ (Đi trước)",
+        Msg::SideBlackSecond => "Đen (Đi sau)",
+        Msg::Difficulty => "Độ khó",
+        Msg::Level1 => "Mức 1 (Mới chơi)",
+        Msg::Level2 => "Mức 2 (Dễ)",
+        Msg::Level3 => "Mức 3 (5s)",
+        Msg::Level4 => "Mức 4 (10s)",
+        Msg::Level5 => "Mức 5 (20s)",
+        Msg::Handicap => "Chấp",
+        Msg::HandicapTitle => "Bên Đỏ chấp bên Đen",
+        Msg::HandicapNone => "Không chấp",
+        Msg::HandicapHorse => "Chấp 1 mã",
+        Msg::HandicapTwoHorses => "Chấp 2 mã",
+        Msg::HandicapChariot => "Chấp 1 xe",
+        Msg::HandicapTwoMoves => "Chấp 2 nước",
+        Msg::BoardStyle => "Bàn cờ",
+        Msg::PieceStyle => "Quân cờ",
+        Msg::Sound => "Âm thanh",
+        Msg::LanguageLabel => "Ngôn ngữ",
+        Msg::ConfirmMoves => "Xác nhận nước đi",
+        Msg::ConfirmOff => "Không",
+        Msg::ConfirmImportant => "Online và mức 4–5",
+        Msg::ConfirmAlways => "Luôn luôn",
+        Msg::ConfirmMove => "✔ Xác nhận",
+        Msg::CancelMove => "✖ Hủy",
+        Msg::Start => "▶ Bắt đầu",
+        Msg::Pause => "⏸ Tạm dừng",
+        Msg::NewGame => "Chơi mới",
+        Msg::Undo => "Đi lại",
+        Msg::FlipBoard => "⇅ Lật bàn cờ",
+        Msg::Blindfold => "🙈 Chơi mù",
+        Msg::BlindfoldOff => "🙈 Thôi chơi mù",
+        Msg::BlindfoldTitle => "Ẩn quân cờ, chỉ còn bàn trống và biên bản; nhập nước đi bằng ký hiệu",
+        Msg::RevealPieces => "👁 Hiện quân",
+        Msg::HidePieces => "👁 Ẩn quân",
+        Msg::Hotseat => "🔄 Tự xoay bàn",
+        Msg::HotseatOff => "🔄 Thôi tự xoay bàn",
+        Msg::HotseatTitle => "Chơi hai người trên một máy: sau mỗi nước bàn cờ xoay về phía bên đến lượt",
+        Msg::PassDevice => "📱 Đưa máy cho người chơi kia",
+        Msg::HandoffReady => "Sẵn sàng",
+        Msg::ScoreTitle => "📊 Thành tích",
+        Msg::ScoreSession => "Phiên này",
+        Msg::ScoreAllTime => "Tổng cộng",
+        Msg::ScoreBestStreak => "Chuỗi thắng dài nhất",
+        Msg::ScoreReset => "Xóa thành tích",
+        Msg::ShowThreats => "🎯 Vùng khống chế",
+        Msg::HideThreats => "🎯 Ẩn vùng khống chế",
+        Msg::ThreatsTitle => {
+            "Tô màu các ô mỗi bên đang khống chế, khoanh các quân bị bắt không có bảo vệ"
+        }
+        Msg::ShowHeatmap => "🌡️ Bản đồ nhiệt",
+        Msg::HideHeatmap => "🌡️ Ẩn bản đồ nhiệt",
+        Msg::HeatmapTitle => {
+            "Bản đồ nhiệt: ô càng đậm càng nhiều quân một bên khống chế hơn bên kia"
+        }
+        Msg::FindMate => "🔍 Có chiếu bí không?",
+        Msg::ExportCsv => "Xuất CSV",
+        Msg::ExportJson => "Xuất JSON",
+        Msg::ExportPgn => "Xuất PGN",
+        Msg::ExportPng => "Xuất ảnh PNG",
+        Msg::ExportGif => "Xuất GIF cả ván",
+        Msg::GifSpeed => "Tốc độ GIF",
+        Msg::GifEncoding => "Đang tạo GIF",
+        Msg::GifMove => "Nước",
+        Msg::ImageEval => "Đánh giá (Đỏ +)",
+        Msg::ImageStaticEval => "Đánh giá tĩnh (Đỏ +)",
+        Msg::Red => "Đỏ",
+        Msg::Black => "Đen",
+        Msg::RedToMove => "Lượt Đỏ",
+        Msg::BlackToMove => "Lượt Đen",
+        Msg::Checkmate => "Chiếu hết!",
+        Msg::RedWins => "Đỏ thắng!",
+        Msg::BlackWins => "Đen thắng!",
+        Msg::Stalemate => "🤝 Hòa cờ!",
+        Msg::BlackResignsRedWins => "Đen xin thua, Đỏ thắng!",
+        Msg::RedResignsBlackWins => "Đỏ xin thua, Đen thắng!",
+        Msg::DrawAgreed => "🤝 Hai bên đồng ý hòa!",
+        Msg::Check => "Chiếu tướng!",
+        Msg::GameOverDraw => "Hết ván, hòa.",
+        Msg::BoardLabel => {
+            "Bàn cờ. Dùng phím mũi tên để di chuyển, Enter để chọn quân hoặc đi quân."
+        }
+        Msg::MoveInputPlaceholder => "Nhập nước đi (C2=5, h2e2, H2-E2)",
+        Msg::MoveInputLabel => "Nhập nước đi theo ký hiệu WXF hoặc tọa độ ICCS",
+        Msg::MoveSubmit => "Đi",
+        Msg::MoveNotYourTurn => "Chưa tới lượt bạn",
+        Msg::MoveUnreadable => "Không hiểu nước đi, ví dụ: C2=5 hoặc h2e2",
+        Msg::MoveIllegal => "Nước đi không hợp lệ",
+        Msg::Benchmark => "⏱ Đo tốc độ thiết bị",
+        Msg::BenchmarkTitle => "⏱ Tốc độ thiết bị",
+        Msg::BenchmarkRunning => "Đang đo...",
+        Msg::BenchmarkFailed => "Không đo được tốc độ thiết bị",
+        Msg::BenchmarkSlow => {
+            "Thiết bị chậm: các mức có giờ suy nghĩ chơi yếu hơn thiết kế, nên chọn Mức 1–2 hoặc Tự điều chỉnh"
+        }
+        Msg::BenchmarkFullStrength => "Máy chơi đủ sức từ",
+        Msg::UseLevel => "Dùng mức này",
+        Msg::NodesPerSecond => "nút/giây",
+        Msg::NavHome => "🏠 Trang chủ",
+        Msg::NavPlay => "♟ Chơi với máy",
+        Msg::NavOnline => "🌐 Sảnh Online",
+        Msg::NavAnalysis => "🔬 Phân tích",
+        Msg::NavPuzzles => "🧩 Thế cờ",
+        Msg::NavSettings => "⚙️ Cài đặt",
+        Msg::NavProfile => "👤 Hồ sơ",
+        Msg::HomePlay => "Đấu với máy theo mức độ, máy đấu máy hoặc hai người chung một thiết bị",
+        Msg::HomeOnline => "Ghép trận, thách đấu bạn bè, xem ván đang diễn ra và bảng xếp hạng",
+        Msg::HomeAnalysis => "Bày thế cờ tự do, phân tích ván, tìm chiếu bí và xem cây tìm kiếm",
+        Msg::HomePuzzles => "Giải thế cờ, luyện tàn cuộc với máy và học chơi qua các bài hướng dẫn",
+        Msg::HomeSettings => "Giao diện, âm thanh, ngôn ngữ, cấu hình engine và đấu trường cấu hình",
+        Msg::HomeProfile => "Lịch sử Elo, thành tích và khai cuộc yêu thích của người chơi",
+        Msg::ProfileLookup => "Tên người chơi...",
+        Msg::ProfileOpen => "Xem hồ sơ",
+        Msg::ServerUnreachable => "Không kết nối được máy chủ",
+        Msg::Wins => "Thắng",
+        Msg::Losses => "Thua",
+        Msg::Draws => "Hòa",
+        Msg::ArenaShow => "⚔️ Đấu trường cấu hình",
+        Msg::ArenaHide => "⚔️ Ẩn đấu trường cấu hình",
+        Msg::ArenaNamePlaceholder => "Tên cấu hình...",
+        Msg::RedConfig => "Cấu hình Đỏ",
+        Msg::BlackConfig => "Cấu hình Đen",
+        Msg::ArenaSubmit => "Gửi lên đấu trường",
+        Msg::ArenaConfig => "Cấu hình",
+        Msg::ArenaPending => "Chờ đấu",
+        Msg::ArenaLoadFailed => "Không tải được bảng đấu trường",
+        Msg::ArenaSubmitted => "Đã gửi cấu hình, các ván đấu đang được xếp lịch",
+        Msg::ArenaInvalidName => "Tên không hợp lệ (tối đa 32 ký tự)",
+        Msg::ArenaNeedsSession => "Hãy vào chơi trực tuyến một lần để gửi cấu hình",
+        Msg::ArenaNeedsRatedGame => "Hãy chơi xong một ván tính điểm để gửi cấu hình",
+        Msg::ArenaNameTaken => "Tên này đã có trong đấu trường",
+        Msg::ArenaTooMany => "Bạn đã có đủ số cấu hình trong đấu trường",
+        Msg::ArenaUnavailable => "Đấu trường đang tắt hoặc đã đầy",
+        Msg::ArenaRejected => "Máy chủ từ chối cấu hình",
+        Msg::ShareCopied => "Đã sao chép liên kết",
+        Msg::ShareCopyBelow => "Hãy sao chép liên kết bên dưới",
+        Msg::SharePosition => "🔗 Chia sẻ thế cờ",
+        Msg::ShareGame => "🔗 Chia sẻ ván đấu",
+        Msg::FenInvalid => "FEN không hợp lệ",
+        Msg::FenCopied => "Đã sao chép FEN",
+        Msg::FenCopyBelow => "Hãy sao chép FEN bên dưới",
+        Msg::FenClipboardUnreadable => "Không đọc được bộ nhớ tạm, hãy dán FEN vào ô bên dưới",
+        Msg::FenCopy => "📋 Sao chép FEN",
+        Msg::FenPaste => "📥 Dán FEN",
+        Msg::FenPlaceholder => "FEN, ví dụ: 4k4/9/9/9/9/9/9/9/9/3K5 w",
+        Msg::SharedPositionInvalid => "Liên kết thế cờ không hợp lệ",
+        Msg::ReplayInvalid => "Ván đấu không hợp lệ",
+        Msg::ReplayNotFound => "Không tìm thấy ván đấu",
+        Msg::RecordCoordinates => "tọa độ",
+        Msg::RecordWxf => "WXF",
+        Msg::RecordChinese => "chữ Hán",
+        Msg::RecordNoMoves => "Không tìm thấy nước đi nào",
+        Msg::RecordUnreadable => "không đọc được",
+        Msg::RecordIllegal => "không hợp lệ",
+        Msg::ImportTitle => "📥 Nhập biên bản ván cờ",
+        Msg::ImportPlaceholder => "1. 炮二平五 马8进7 2. 马二进三 车9平8 ... hoặc C2=5 H8+7, h2e2 h9g7, PGN",
+        Msg::ImportButton => "Nhập ván",
+        Msg::NextPosition => "Thế cờ tiếp theo",
+        Msg::TryAgain => "Thử lại",
+        Msg::TrainingSolved => "Đã giải",
+        Msg::TrainingMoves => "Nước",
+        Msg::TrainingComplete => "✅ Hoàn thành!",
+        Msg::TrainingFailed => "❌ Chưa đạt",
+        Msg::StageEndgame => "Tàn cuộc",
+        Msg::StageMiddlegame => "Trung cuộc",
+        Msg::Depth => "độ sâu",
+        Msg::TraceRoot => "Gốc",
+        Msg::TraceNullMove => "Nước rỗng",
+        Msg::TraceSingular => "Kiểm tra singular",
+        Msg::TraceMateDistance => "cắt theo khoảng cách chiếu bí",
+        Msg::TraceRepetition => "lặp thế cờ",
+        Msg::TraceTranspositionTable => "lấy từ bảng băm",
+        Msg::TraceQuiescence => "tìm tĩnh",
+        Msg::TraceProbCut => "cắt ProbCut",
+        Msg::TraceReverseFutility => "cắt reverse futility",
+        Msg::TraceNullMoveCut => "cắt nước rỗng",
+        Msg::TraceNoLegalMoves => "hết nước đi",
+        Msg::TraceMatingMove => "nước chiếu bí",
+        Msg::TraceBetaCutoff => "cắt beta",
+        Msg::TraceTimeUp => "hết giờ",
+        Msg::TraceDynamicLimit => "bỏ: giới hạn động",
+        Msg::TraceLateMovePruning => "bỏ: late move pruning",
+        Msg::TraceFutility => "bỏ: futility",
+        Msg::TraceRepeatsPosition => "bỏ: lặp lần ba",
+        Msg::SearchTreeTitle => "Cây tìm kiếm (gỡ lỗi)",
+        Msg::SearchTreeRecord => "🌳 Ghi cây tìm kiếm",
+        Msg::SearchTreeRunning => "Đang tìm...",
+        Msg::SearchTreeFailed => "Không ghi được cây tìm kiếm",
+        Msg::OnlineScore => "Tỉ số",
+        Msg::OnlineTitle => "🌐 Chế độ chơi Online",
+        Msg::OnlineNamePlaceholder => "Tên hiển thị (bảng xếp hạng)",
+        Msg::OnlineHandicapTitle => "Chỉ ghép với người chọn cùng kiểu chấp; bên Đỏ chấp",
+        Msg::OnlineFindMatch => "🎮 Tìm trận",
+        Msg::OnlineFinding => "🔍 Đang tìm trận...",
+        Msg::OnlineCancelFind => "❌ Huỷ tìm",
+        Msg::OnlineMatchFound => "✅ Đã tìm thấy đối thủ!",
+        Msg::OnlineYourSide => "Bạn là bên",
+        Msg::OnlineRedFirst => "🔴 Đỏ (đi trước)",
+        Msg::OnlineBlackSecond => "⚫ Đen (đi sau)",
+        Msg::OnlineYourTurn => "Lượt của bạn!",
+        Msg::OnlineSending => "📡 Đang gửi nước đi...",
+        Msg::OnlineWaiting => "⏳ Đang chờ đối thủ...",
+        Msg::OnlineResign => "🏳️ Đầu hàng",
+        Msg::OnlineOpponentLeft => "⚠️ Đối thủ đã mất kết nối!",
+        Msg::OnlineNewMatch => "🔄 Tìm trận mới",
+        Msg::OnlineYouWin => "Bạn thắng!",
+        Msg::OnlineYouLose => "Bạn thua!",
+        Msg::OnlineDraw => "Hòa cờ!",
+        Msg::OnlineGameOver => "Kết thúc",
+        Msg::OnlineReason => "Lý do",
+        Msg::OnlineReadyWaiting => "✅ Đã sẵn sàng - Đang chờ đối thủ...",
+        Msg::OnlineRematch => "🎮 Sẵn sàng (Chơi tiếp)",
+        Msg::OnlineLeave => "🚪 Thoát",
+        Msg::SpectateTitle => "👁 Đang xem trực tiếp",
+        Msg::SpectateStop => "🚪 Thôi xem",
+        Msg::EndCheckmate => "Chiếu hết",
+        Msg::EndResignation => "Đầu hàng",
+        Msg::EndDrawAgreement => "Hòa",
+        Msg::EndTimeout => "Hết giờ",
+        Msg::EndAbandoned => "Mất kết nối",
+        Msg::EndAdjudication => "Máy phân xử",
+        Msg::EndTerminated => "Bị quản trị viên dừng",
+        Msg::AbortNoFirstMove => "⌛ Ván đấu bị huỷ vì không có nước đi đầu tiên",
+        Msg::ConfigLoadJson => "Tải cấu hình JSON",
+        Msg::ConfigExportJson => "Xuất JSON",
+        Msg::ConfigStyle => "Phong cách",
+        Msg::ConfigPick => "— Chọn —",
+        Msg::ConfigHashMove => "Nước bảng băm (Hash Move)",
+        Msg::ConfigCaptureBase => "Điểm cơ bản nước ăn (Capture Base)",
+        Msg::ConfigKillerMove => "Nước sát thủ (Killer Move)",
+        Msg::ConfigHistoryMax => "Lịch sử tối đa (History Max)",
+        Msg::ConfigPruningMethod => "Cách tỉa nhánh",
+        Msg::PruneDynamic => "Giới hạn động",
+        Msg::PruneLmr => "Giảm độ sâu nước muộn (LMR)",
+        Msg::PruneBoth => "Cả hai (mạnh tay)",
+        Msg::ConfigMultiplier => "Hệ số",
+        Msg::ConfigMateScore => "Điểm chiếu bí",
+        Msg::ConfigKingSafety => "An toàn Tướng (%)",
+        Msg::ConfigContempt => "Khinh hòa (Contempt)",
+        Msg::ConfigTtSize => "Bảng băm (TT)",
+        Msg::ConfigResignScore => "Đầu hàng khi kém (điểm)",
+        Msg::ConfigResignMoves => "Số nước trước khi đầu hàng (0 = không)",
+        Msg::ConfigDrawScore => "Ngưỡng hòa (điểm)",
+        Msg::ConfigDrawMoves => "Số nước cân bằng để cầu hòa (0 = không)",
+        Msg::ProfileBalanced => "Cân bằng",
+        Msg::ProfileAggressive => "Tấn công",
+        Msg::ProfilePositional => "Thế trận",
+        Msg::ProfileMaterialist => "Ăn quân",
+        Msg::ProfileGambit => "Thí quân",
+        Msg::FriendsUntimed => "Không giới hạn",
+        Msg::PolicyForfeit => "Thua ngay",
+        Msg::PolicyAdjudicate => "Máy phân xử",
+        Msg::FriendsAccept => "Nhận",
+        Msg::FriendsDecline => "Từ chối",
+        Msg::FriendsCancel => "Hủy",
+        Msg::FriendsShow => "👥 Bạn bè",
+        Msg::FriendsHide => "👥 Ẩn bạn bè",
+        Msg::FriendsNamePlaceholder => "Tên người chơi",
+        Msg::FriendsAdd => "Thêm bạn",
+        Msg::FriendsTime => "Thời gian: ",
+        Msg::FriendsOnDisconnect => "Khi mất kết nối: ",
+        Msg::FriendsNone => "Chưa có bạn bè nào",
+        Msg::FriendsChallenge => "Thách đấu",
+        Msg::FriendsRemove => "Xóa khỏi danh sách",
+        Msg::BoardClassic => "Cổ điển",
+        Msg::BoardWood => "Vân gỗ",
+        Msg::BoardJade => "Ngọc bích",
+        Msg::BoardNight => "Ban đêm",
+        Msg::PiecesChinese => "Chữ Hán",
+        Msg::PiecesInternational => "Quốc tế (K, R, C...)",
+        Msg::JudgeInaccuracy => "Thiếu chính xác",
+        Msg::JudgeMistake => "Sai lầm",
+        Msg::JudgeBlunder => "Sai lầm nghiêm trọng",
+        Msg::ReviewAccuracy => "Độ chính xác",
+        Msg::ReviewAvgLoss => "Mất điểm TB",
+        Msg::ReviewGame => "📊 Phân tích ván",
+        Msg::ReviewNoMistakes => "Không có sai lầm nào",
+        Msg::ReviewGraph => "📈 Biểu đồ đánh giá",
+        Msg::ReviewStartPosition => "Thế cờ ban đầu",
+        Msg::MateSearching => "Đang tìm chiếu bí...",
+        Msg::MoveTakenBack => "↩️ Nước đi của bạn đã bị hoàn tác",
+        Msg::ChallengeDeclined => "⚔️ Lời thách đấu đã bị từ chối",
+        Msg::AppTitle => "Cờ Tướng",
+        Msg::PuzzleYourMove => "Tới lượt bạn",
+        Msg::PuzzleOpponentMoving => "Đối thủ đang đi...",
+        Msg::PuzzleTactic => "Đòn chiến thuật",
+        Msg::PuzzleCorrect => "✅ Chính xác!",
+        Msg::PuzzleWrong => "❌ Chưa đúng",
+        Msg::PuzzleSkip => "Bỏ qua",
+        Msg::ProfileLoadFailed => "Không tải được hồ sơ người chơi",
+        Msg::ProfileBack => "◀ Bảng xếp hạng",
+        Msg::ProfileGames => "Số ván",
+        Msg::ProfileEloChart => "Biểu đồ Elo",
+        Msg::ProfileTooFewGames => "Chưa đủ ván để vẽ biểu đồ",
+        Msg::ProfileFavouriteOpenings => "Khai cuộc yêu thích",
+        Msg::ProfileNoOpenings => "Chưa có",
+        Msg::LiveLoadFailed => "Không tải được danh sách ván đang diễn ra",
+        Msg::LiveShow => "👁 Ván đang diễn ra",
+        Msg::LiveHide => "👁 Ẩn ván đang diễn ra",
+        Msg::LiveNone => "Chưa có ván nào đang diễn ra",
+        Msg::LiveMoves => "Nước",
+        Msg::LiveOpening => "Khai cuộc",
+        Msg::LiveTimeControl => "Thời gian",
+        Msg::LiveWatch => "Xem",
+        Msg::LessonRetry => "❌ Chưa đúng, thử lại nhé",
+        Msg::LessonComplete => "🎓 Hoàn thành bài học!",
+        Msg::LessonNext => "Bài tiếp theo",
+        Msg::LessonRestart => "Học lại",
+        Msg::LeaderboardLoadFailed => "Không tải được bảng xếp hạng",
+        Msg::LeaderboardShow => "🏆 Bảng xếp hạng",
+        Msg::LeaderboardHide => "🏆 Ẩn bảng xếp hạng",
+        Msg::LeaderboardSearch => "Tìm theo tên người chơi...",
+        Msg::LeaderboardPlayer => "Người chơi",
+        Msg::LeaderboardViewProfile => "Xem hồ sơ",
+        Msg::ExplorerTitle => "📖 Khai cuộc",
+        Msg::ExplorerOutOfBook => "Thế cờ đã ra khỏi sách khai cuộc",
+        Msg::ExplorerWeight => "trọng số",
+        Msg::LogTitle => "📜 Biên bản",
+        Msg::LogLive => "⏭ Hiện tại",
+        Msg::EngineThinking => "Máy đang nghĩ...",
+        Msg::InstallApp => "📲 Cài đặt ứng dụng",
+    }
+}
+
+const fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Mode => "Mode",
+        Msg::ModeHumanVsComputer => "Human vs Computer",
+        Msg::ModeComputerVsComputer => "Computer vs Computer",
+        Msg::ModeHumanVsHuman => "Human vs Human",
+        Msg::ModeOnline => "🌐 Play online",
+        Msg::ModePuzzle => "🧩 Puzzles",
+        Msg::ModeTutorial => "📘 Learn to play",
+        Msg::ModeTraining => "🎯 Training positions",
+        Msg::Side => "Side",
+        Msg::SideRedFirst => "Red (moves first)",
+        Msg::SideBlackSecond => "Black (moves second)",
+        Msg::Difficulty => "Difficulty",
+        Msg::Level1 => "Level 1 (Beginner)",
+        Msg::Level2 => "Level 2 (Easy)",
+        Msg::Level3 => "Level 3 (5s)",
+        Msg::Level4 => "Level 4 (10s)",
+        Msg::Level5 => "Level 5 (20s)",
+        Msg::Handicap => "Handicap",
+        Msg::HandicapTitle => "Red gives Black a handicap",
+        Msg::HandicapNone => "No handicap",
+        Msg::HandicapHorse => "Minus a horse",
+        Msg::HandicapTwoHorses => "Minus two horses",
+        Msg::HandicapChariot => "Minus a chariot",
+        Msg::HandicapTwoMoves => "Two moves ahead",
+        Msg::BoardStyle => "Board",
+        Msg::PieceStyle => "Pieces",
+        Msg::Sound => "Sound",
+        Msg::LanguageLabel => "Language",
+        Msg::ConfirmMoves => "Confirm moves",
+        Msg::ConfirmOff => "Never",
+        Msg::ConfirmImportant => "Online and levels 4–5",
+        Msg::ConfirmAlways => "Always",
+        Msg::ConfirmMove => "✔ Confirm",
+        Msg::CancelMove => "✖ Cancel",
+        Msg::Start => "▶ Start",
+        Msg::Pause => "⏸ Pause",
+        Msg::NewGame => "New game",
+        Msg::Undo => "Undo",
+        Msg::FlipBoard => "⇅ Flip board",
+        Msg::Blindfold => "🙈 Blindfold",
+        Msg::BlindfoldOff => "🙈 Leave blindfold",
+        Msg::BlindfoldTitle => "Hides the pieces, leaving the empty board and the move list; type moves in notation",
+        Msg::RevealPieces => "👁 Show pieces",
+        Msg::HidePieces => "👁 Hide pieces",
+        Msg::Hotseat => "🔄 Auto-flip",
+        Msg::HotseatOff => "🔄 Stop auto-flip",
+        Msg::HotseatTitle => "Two players on one device: after each move the board turns to face the side to move",
+        Msg::PassDevice => "📱 Pass the device to the other player",
+        Msg::HandoffReady => "Ready",
+        Msg::ScoreTitle => "📊 Score",
+        Msg::ScoreSession => "This session",
+        Msg::ScoreAllTime => "All time",
+        Msg::ScoreBestStreak => "Longest winning streak",
+        Msg::ScoreReset => "Reset score",
+        Msg::ShowThreats => "🎯 Controlled squares",
+        Msg::HideThreats => "🎯 Hide controlled squares",
+        Msg::ThreatsTitle => {
+            "Tints the squares each side attacks and circles attacked pieces left undefended"
+        }
+        Msg::ShowHeatmap => "🌡️ Heatmap",
+        Msg::HideHeatmap => "🌡️ Hide heatmap",
+        Msg::HeatmapTitle => "Heatmap: the deeper the tint, the more one side outnumbers the other",
+        Msg::FindMate => "🔍 Is there a mate?",
+        Msg::ExportCsv => "Export CSV",
+        Msg::ExportJson => "Export JSON",
+        Msg::ExportPgn => "Export PGN",
+        Msg::ExportPng => "Export PNG",
+        Msg::ExportGif => "Export game GIF",
+        Msg::GifSpeed => "GIF speed",
+        Msg::GifEncoding => "Making GIF",
+        Msg::GifMove => "Move",
+        Msg::ImageEval => "Evaluation (Red +)",
+        Msg::ImageStaticEval => "Static evaluation (Red +)",
+        Msg::Red => "Red",
+        Msg::Black => "Black",
+        Msg::RedToMove => "Red to move",
+        Msg::BlackToMove => "Black to move",
+        Msg::Checkmate => "Checkmate!",
+        Msg::RedWins => "Red wins!",
+        Msg::BlackWins => "Black wins!",
+        Msg::Stalemate => "🤝 Draw!",
+        Msg::BlackResignsRedWins => "Black resigns, Red wins!",
+        Msg::RedResignsBlackWins => "Red resigns, Black wins!",
+        Msg::DrawAgreed => "🤝 Draw agreed!",
+        Msg::Check => "Check!",
+        Msg::GameOverDraw => "Game over, draw.",
+        Msg::BoardLabel => "Board. Use the arrow keys to move, Enter to pick up or play a piece.",
+        Msg::MoveInputPlaceholder => "Type a move (C2=5, h2e2, H2-E2)",
+        Msg::MoveInputLabel => "Type a move in WXF or ICCS notation",
+        Msg::MoveSubmit => "Play",
+        Msg::MoveNotYourTurn => "It is not your turn",
+        Msg::MoveUnreadable => "Unrecognized move, e.g. C2=5 or h2e2",
+        Msg::MoveIllegal => "Illegal move",
+        Msg::Benchmark => "⏱ Benchmark my device",
+        Msg::BenchmarkTitle => "⏱ Device speed",
+        Msg::BenchmarkRunning => "Measuring...",
+        Msg::BenchmarkFailed => "Could not measure this device",
+        Msg::BenchmarkSlow => {
+            "Slow device: the timed levels play below their intended strength, try Level 1–2 or Adaptive"
+        }
+        Msg::BenchmarkFullStrength => "Full strength from",
+        Msg::UseLevel => "Use this level",
+        Msg::NodesPerSecond => "nodes/s",
+        Msg::NavHome => "🏠 Home",
+        Msg::NavPlay => "♟ Play vs AI",
+        Msg::NavOnline => "🌐 Online lobby",
+        Msg::NavAnalysis => "🔬 Analysis",
+        Msg::NavPuzzles => "🧩 Puzzles",
+        Msg::NavSettings => "⚙️ Settings",
+        Msg::NavProfile => "👤 Profile",
+        Msg::HomePlay => "Play the engine at any level, watch engine vs engine or share one device",
+        Msg::HomeOnline => "Find a match, challenge friends, watch live games and see the leaderboard",
+        Msg::HomeAnalysis => "Set up any position, review games, find mates and inspect the search tree",
+        Msg::HomePuzzles => "Solve puzzles, play out endgames against the engine and learn the game through lessons",
+        Msg::HomeSettings => "Theme, sound, language, engine configs and the config arena",
+        Msg::HomeProfile => "A player's rating history, record and favourite openings",
+        Msg::ProfileLookup => "Player name...",
+        Msg::ProfileOpen => "Open profile",
+        Msg::ServerUnreachable => "Could not reach the server",
+        Msg::Wins => "Wins",
+        Msg::Losses => "Losses",
+        Msg::Draws => "Draws",
+        Msg::ArenaShow => "⚔️ Config arena",
+        Msg::ArenaHide => "⚔️ Hide config arena",
+        Msg::ArenaNamePlaceholder => "Config name...",
+        Msg::RedConfig => "Red config",
+        Msg::BlackConfig => "Black config",
+        Msg::ArenaSubmit => "Submit to the arena",
+        Msg::ArenaConfig => "Config",
+        Msg::ArenaPending => "Pending",
+        Msg::ArenaLoadFailed => "Could not load the arena table",
+        Msg::ArenaSubmitted => "Config submitted, its games are being scheduled",
+        Msg::ArenaInvalidName => "Invalid name (at most 32 characters)",
+        Msg::ArenaNeedsSession => "Play online once to be able to submit configs",
+        Msg::ArenaNeedsRatedGame => "Finish a rated game to be able to submit configs",
+        Msg::ArenaNameTaken => "This name is already in the arena",
+        Msg::ArenaTooMany => "You already have as many configs in the arena as allowed",
+        Msg::ArenaUnavailable => "The arena is off or full",
+        Msg::ArenaRejected => "The server rejected the config",
+        Msg::ShareCopied => "Link copied",
+        Msg::ShareCopyBelow => "Copy the link below",
+        Msg::SharePosition => "🔗 Share position",
+        Msg::ShareGame => "🔗 Share game",
+        Msg::FenInvalid => "Invalid FEN",
+        Msg::FenCopied => "FEN copied",
+        Msg::FenCopyBelow => "Copy the FEN below",
+        Msg::FenClipboardUnreadable => "Could not read the clipboard, paste the FEN into the box below",
+        Msg::FenCopy => "📋 Copy FEN",
+        Msg::FenPaste => "📥 Paste FEN",
+        Msg::FenPlaceholder => "FEN, e.g. 4k4/9/9/9/9/9/9/9/9/3K5 w",
+        Msg::SharedPositionInvalid => "Invalid position link",
+        Msg::ReplayInvalid => "The game is invalid",
+        Msg::ReplayNotFound => "Game not found",
+        Msg::RecordCoordinates => "coordinate",
+        Msg::RecordWxf => "WXF",
+        Msg::RecordChinese => "Chinese",
+        Msg::RecordNoMoves => "No moves found",
+        Msg::RecordUnreadable => "unreadable",
+        Msg::RecordIllegal => "illegal",
+        Msg::ImportTitle => "📥 Import a game record",
+        Msg::ImportPlaceholder => "1. 炮二平五 马8进7 2. 马二进三 车9平8 ... or C2=5 H8+7, h2e2 h9g7, PGN",
+        Msg::ImportButton => "Import",
+        Msg::NextPosition => "Next position",
+        Msg::TryAgain => "Try again",
+        Msg::TrainingSolved => "Solved",
+        Msg::TrainingMoves => "Moves",
+        Msg::TrainingComplete => "✅ Goal reached!",
+        Msg::TrainingFailed => "❌ Goal missed",
+        Msg::StageEndgame => "Endgame",
+        Msg::StageMiddlegame => "Middlegame",
+        Msg::Depth => "depth",
+        Msg::TraceRoot => "Root",
+        Msg::TraceNullMove => "Null move",
+        Msg::TraceSingular => "Singular check",
+        Msg::TraceMateDistance => "mate distance cut",
+        Msg::TraceRepetition => "repetition",
+        Msg::TraceTranspositionTable => "from the hash table",
+        Msg::TraceQuiescence => "quiescence",
+        Msg::TraceProbCut => "ProbCut cut",
+        Msg::TraceReverseFutility => "reverse futility cut",
+        Msg::TraceNullMoveCut => "null move cut",
+        Msg::TraceNoLegalMoves => "no legal moves",
+        Msg::TraceMatingMove => "mating move",
+        Msg::TraceBetaCutoff => "beta cutoff",
+        Msg::TraceTimeUp => "time up",
+        Msg::TraceDynamicLimit => "skipped: dynamic limit",
+        Msg::TraceLateMovePruning => "skipped: late move pruning",
+        Msg::TraceFutility => "skipped: futility",
+        Msg::TraceRepeatsPosition => "skipped: third repetition",
+        Msg::SearchTreeTitle => "Search tree (debug)",
+        Msg::SearchTreeRecord => "🌳 Record the search tree",
+        Msg::SearchTreeRunning => "Searching...",
+        Msg::SearchTreeFailed => "Could not record the search tree",
+        Msg::OnlineScore => "Score",
+        Msg::OnlineTitle => "🌐 Online play",
+        Msg::OnlineNamePlaceholder => "Display name (leaderboard)",
+        Msg::OnlineHandicapTitle => "Only matched with players who pick the same handicap; Red gives it",
+        Msg::OnlineFindMatch => "🎮 Find a match",
+        Msg::OnlineFinding => "🔍 Looking for a match...",
+        Msg::OnlineCancelFind => "❌ Stop looking",
+        Msg::OnlineMatchFound => "✅ Opponent found!",
+        Msg::OnlineYourSide => "You play",
+        Msg::OnlineRedFirst => "🔴 Red (moves first)",
+        Msg::OnlineBlackSecond => "⚫ Black (moves second)",
+        Msg::OnlineYourTurn => "Your turn!",
+        Msg::OnlineSending => "📡 Sending your move...",
+        Msg::OnlineWaiting => "⏳ Waiting for the opponent...",
+        Msg::OnlineResign => "🏳️ Resign",
+        Msg::OnlineOpponentLeft => "⚠️ The opponent disconnected!",
+        Msg::OnlineNewMatch => "🔄 Find a new match",
+        Msg::OnlineYouWin => "You win!",
+        Msg::OnlineYouLose => "You lose!",
+        Msg::OnlineDraw => "Draw!",
+        Msg::OnlineGameOver => "Game over",
+        Msg::OnlineReason => "Reason",
+        Msg::OnlineReadyWaiting => "✅ Ready - waiting for the opponent...",
+        Msg::OnlineRematch => "🎮 Ready (play again)",
+        Msg::OnlineLeave => "🚪 Leave",
+        Msg::SpectateTitle => "👁 Watching live",
+        Msg::SpectateStop => "🚪 Stop watching",
+        Msg::EndCheckmate => "Checkmate",
+        Msg::EndResignation => "Resignation",
+        Msg::EndDrawAgreement => "Draw agreed",
+        Msg::EndTimeout => "Timeout",
+        Msg::EndAbandoned => "Disconnected",
+        Msg::EndAdjudication => "Adjudicated by the engine",
+        Msg::EndTerminated => "Stopped by an administrator",
+        Msg::AbortNoFirstMove => "⌛ The game was aborted because no first move was made",
+        Msg::ConfigLoadJson => "Load JSON config",
+        Msg::ConfigExportJson => "Export JSON",
+        Msg::ConfigStyle => "Style",
+        Msg::ConfigPick => "— Pick —",
+        Msg::ConfigHashMove => "Hash move",
+        Msg::ConfigCaptureBase => "Capture base",
+        Msg::ConfigKillerMove => "Killer move",
+        Msg::ConfigHistoryMax => "History max",
+        Msg::ConfigPruningMethod => "Pruning method",
+        Msg::PruneDynamic => "Dynamic limiting",
+        Msg::PruneLmr => "Late move reductions (LMR)",
+        Msg::PruneBoth => "Both (aggressive)",
+        Msg::ConfigMultiplier => "Multiplier",
+        Msg::ConfigMateScore => "Mate score",
+        Msg::ConfigKingSafety => "King safety (%)",
+        Msg::ConfigContempt => "Contempt",
+        Msg::ConfigTtSize => "Hash table (TT)",
+        Msg::ConfigResignScore => "Resign when behind by (points)",
+        Msg::ConfigResignMoves => "Moves before resigning (0 = never)",
+        Msg::ConfigDrawScore => "Draw threshold (points)",
+        Msg::ConfigDrawMoves => "Level moves before offering a draw (0 = never)",
+        Msg::ProfileBalanced => "Balanced",
+        Msg::ProfileAggressive => "Aggressive",
+        Msg::ProfilePositional => "Positional",
+        Msg::ProfileMaterialist => "Materialist",
+        Msg::ProfileGambit => "Gambit",
+        Msg::FriendsUntimed => "Untimed",
+        Msg::PolicyForfeit => "Lose at once",
+        Msg::PolicyAdjudicate => "Engine adjudicates",
+        Msg::FriendsAccept => "Accept",
+        Msg::FriendsDecline => "Decline",
+        Msg::FriendsCancel => "Cancel",
+        Msg::FriendsShow => "👥 Friends",
+        Msg::FriendsHide => "👥 Hide friends",
+        Msg::FriendsNamePlaceholder => "Player name",
+        Msg::FriendsAdd => "Add friend",
+        Msg::FriendsTime => "Time: ",
+        Msg::FriendsOnDisconnect => "On disconnect: ",
+        Msg::FriendsNone => "No friends yet",
+        Msg::FriendsChallenge => "Challenge",
+        Msg::FriendsRemove => "Remove from the list",
+        Msg::BoardClassic => "Classic",
+        Msg::BoardWood => "Wood grain",
+        Msg::BoardJade => "Jade",
+        Msg::BoardNight => "Night",
+        Msg::PiecesChinese => "Chinese characters",
+        Msg::PiecesInternational => "International (K, R, C...)",
+        Msg::JudgeInaccuracy => "Inaccuracy",
+        Msg::JudgeMistake => "Mistake",
+        Msg::JudgeBlunder => "Blunder",
+        Msg::ReviewAccuracy => "Accuracy",
+        Msg::ReviewAvgLoss => "Avg. loss",
+        Msg::ReviewGame => "📊 Game review",
+        Msg::ReviewNoMistakes => "No mistakes",
+        Msg::ReviewGraph => "📈 Evaluation graph",
+        Msg::ReviewStartPosition => "Starting position",
+        Msg::MateSearching => "Searching for a mate...",
+        Msg::MoveTakenBack => "↩️ Your move was taken back",
+        Msg::ChallengeDeclined => "⚔️ Your challenge was declined",
+        Msg::AppTitle => "Xiangqi",
+        Msg::PuzzleYourMove => "Your move",
+        Msg::PuzzleOpponentMoving => "The opponent is moving...",
+        Msg::PuzzleTactic => "Tactic",
+        Msg::PuzzleCorrect => "✅ Correct!",
+        Msg::PuzzleWrong => "❌ Not quite",
+        Msg::PuzzleSkip => "Skip",
+        Msg::ProfileLoadFailed => "Could not load the player's profile",
+        Msg::ProfileBack => "◀ Leaderboard",
+        Msg::ProfileGames => "Games",
+        Msg::ProfileEloChart => "Elo history",
+        Msg::ProfileTooFewGames => "Not enough games for a chart yet",
+        Msg::ProfileFavouriteOpenings => "Favourite openings",
+        Msg::ProfileNoOpenings => "None yet",
+        Msg::LiveLoadFailed => "Could not load the live games",
+        Msg::LiveShow => "👁 Live games",
+        Msg::LiveHide => "👁 Hide live games",
+        Msg::LiveNone => "No games in progress",
+        Msg::LiveMoves => "Moves",
+        Msg::LiveOpening => "Opening",
+        Msg::LiveTimeControl => "Time",
+        Msg::LiveWatch => "Watch",
+        Msg::LessonRetry => "❌ Not quite, try again",
+        Msg::LessonComplete => "🎓 Lesson complete!",
+        Msg::LessonNext => "Next lesson",
+        Msg::LessonRestart => "Start over",
+        Msg::LeaderboardLoadFailed => "Could not load the leaderboard",
+        Msg::LeaderboardShow => "🏆 Leaderboard",
+        Msg::LeaderboardHide => "🏆 Hide leaderboard",
+        Msg::LeaderboardSearch => "Search by player name...",
+        Msg::LeaderboardPlayer => "Player",
+        Msg::LeaderboardViewProfile => "View profile",
+        Msg::ExplorerTitle => "📖 Openings",
+        Msg::ExplorerOutOfBook => "The position is out of the opening book",
+        Msg::ExplorerWeight => "weight",
+        Msg::LogTitle => "📜 Moves",
+        Msg::LogLive => "⏭ Live",
+        Msg::EngineThinking => "The engine is thinking...",
+        Msg::InstallApp => "📲 Install the app",
+    }
+}
+
+const fn chinese(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Mode => "模式",
+        Msg::ModeHumanVsComputer => "人机对战",
+        Msg::ModeComputerVsComputer => "电脑对战",
+        Msg::ModeHumanVsHuman => "双人对战",
+        Msg::ModeOnline => "🌐 在线对战",
+        Msg::ModePuzzle => "🧩 残局练习",
+        Msg::ModeTutorial => "📘 入门教程",
+        Msg::ModeTraining => "🎯 对弈练习局面",
+        Msg::Side => "执子",
+        Msg::SideRedFirst => "红方（先走）",
+        Msg::SideBlackSecond => "黑方（后走）",
+        Msg::Difficulty => "难度",
+        Msg::Level1 => "1级（入门）",
+        Msg::Level2 => "2级（简单）",
+        Msg::Level3 => "3级（5秒）",
+        Msg::Level4 => "4级（10秒）",
+        Msg::Level5 => "5级（20秒）",
+        Msg::Handicap => "让子",
+        Msg::HandicapTitle => "红方让黑方",
+        Msg::HandicapNone => "不让子",
+        Msg::HandicapHorse => "让单马",
+        Msg::HandicapTwoHorses => "让双马",
+        Msg::HandicapChariot => "让一车",
+        Msg::HandicapTwoMoves => "让两先",
+        Msg::BoardStyle => "棋盘",
+        Msg::PieceStyle => "棋子",
+        Msg::Sound => "声音",
+        Msg::LanguageLabel => "语言",
+        Msg::ConfirmMoves => "走子确认",
+        Msg::ConfirmOff => "不需要",
+        Msg::ConfirmImportant => "在线及4–5级",
+        Msg::ConfirmAlways => "总是",
+        Msg::ConfirmMove => "✔ 确认",
+        Msg::CancelMove => "✖ 取消",
+        Msg::Start => "▶ 开始",
+        Msg::Pause => "⏸ 暂停",
+        Msg::NewGame => "新局",
+        Msg::Undo => "悔棋",
+        Msg::FlipBoard => "⇅ 翻转棋盘",
+        Msg::Blindfold => "🙈 盲棋",
+        Msg::BlindfoldOff => "🙈 退出盲棋",
+        Msg::BlindfoldTitle => "隐藏棋子，只留空棋盘和棋谱；用记法输入着法",
+        Msg::RevealPieces => "👁 显示棋子",
+        Msg::HidePieces => "👁 隐藏棋子",
+        Msg::Hotseat => "🔄 自动翻转",
+        Msg::HotseatOff => "🔄 关闭自动翻转",
+        Msg::HotseatTitle => "两人共用一台设备：每走一步棋盘转向轮到走棋的一方",
+        Msg::PassDevice => "📱 请把设备交给对方",
+        Msg::HandoffReady => "准备好了",
+        Msg::ScoreTitle => "📊 战绩",
+        Msg::ScoreSession => "本次",
+        Msg::ScoreAllTime => "总计",
+        Msg::ScoreBestStreak => "最长连胜",
+        Msg::ScoreReset => "清除战绩",
+        Msg::ShowThreats => "🎯 控制区域",
+        Msg::HideThreats => "🎯 隐藏控制区域",
+        Msg::ThreatsTitle => "为双方控制的格子着色，并圈出受攻击且无保护的棋子",
+        Msg::ShowHeatmap => "🌡️ 热力图",
+        Msg::HideHeatmap => "🌡️ 隐藏热力图",
+        Msg::HeatmapTitle => "热力图：颜色越深，一方的控制优势越大",
+        Msg::FindMate => "🔍 有没有杀棋？",
+        Msg::ExportCsv => "导出 CSV",
+        Msg::ExportJson => "导出 JSON",
+        Msg::ExportPgn => "导出 PGN",
+        Msg::ExportPng => "导出 PNG 图片",
+        Msg::ExportGif => "导出整局 GIF",
+        Msg::GifSpeed => "GIF 速度",
+        Msg::GifEncoding => "正在生成 GIF",
+        Msg::GifMove => "着法",
+        Msg::ImageEval => "评估（红方为正）",
+        Msg::ImageStaticEval => "静态评估（红方为正）",
+        Msg::Red => "红方",
+        Msg::Black => "黑方",
+        Msg::RedToMove => "红方走棋",
+        Msg::BlackToMove => "黑方走棋",
+        Msg::Checkmate => "将死！",
+        Msg::RedWins => "红方胜！",
+        Msg::BlackWins => "黑方胜！",
+        Msg::Stalemate => "🤝 和棋！",
+        Msg::BlackResignsRedWins => "黑方认输，红方胜！",
+        Msg::RedResignsBlackWins => "红方认输，黑方胜！",
+        Msg::DrawAgreed => "🤝 双方议和！",
+        Msg::Check => "将军！",
+        Msg::GameOverDraw => "对局结束，和棋。",
+        Msg::BoardLabel => "棋盘。用方向键移动，按回车选子或走子。",
+        Msg::MoveInputPlaceholder => "输入着法（C2=5, h2e2, H2-E2）",
+        Msg::MoveInputLabel => "以 WXF 或 ICCS 记法输入着法",
+        Msg::MoveSubmit => "走",
+        Msg::MoveNotYourTurn => "还没轮到你",
+        Msg::MoveUnreadable => "无法识别的着法，例如 C2=5 或 h2e2",
+        Msg::MoveIllegal => "着法不合规则",
+        Msg::Benchmark => "⏱ 测试设备速度",
+        Msg::BenchmarkTitle => "⏱ 设备速度",
+        Msg::BenchmarkRunning => "正在测试...",
+        Msg::BenchmarkFailed => "无法测试设备速度",
+        Msg::BenchmarkSlow => "设备较慢：限时难度达不到设计棋力，建议选择1–2级或自适应",
+        Msg::BenchmarkFullStrength => "发挥全部棋力的最低难度：",
+        Msg::UseLevel => "使用该难度",
+        Msg::NodesPerSecond => "节点/秒",
+        Msg::NavHome => "🏠 首页",
+        Msg::NavPlay => "♟ 人机对战",
+        Msg::NavOnline => "🌐 在线大厅",
+        Msg::NavAnalysis => "🔬 分析",
+        Msg::NavPuzzles => "🧩 残局",
+        Msg::NavSettings => "⚙️ 设置",
+        Msg::NavProfile => "👤 资料",
+        Msg::HomePlay => "按难度与电脑对弈、观看电脑对战或同屏双人对战",
+        Msg::HomeOnline => "匹配对手、挑战好友、观看对局和排行榜",
+        Msg::HomeAnalysis => "自由摆局、复盘、寻找杀棋并查看搜索树",
+        Msg::HomePuzzles => "解残局、与电脑练习局面并通过教程学习下棋",
+        Msg::HomeSettings => "主题、声音、语言、引擎配置与配置竞技场",
+        Msg::HomeProfile => "棋手的等级分历史、战绩与常用开局",
+        Msg::ProfileLookup => "棋手名称...",
+        Msg::ProfileOpen => "查看资料",
+        Msg::ServerUnreachable => "无法连接服务器",
+        Msg::Wins => "胜",
+        Msg::Losses => "负",
+        Msg::Draws => "和",
+        Msg::ArenaShow => "⚔️ 配置竞技场",
+        Msg::ArenaHide => "⚔️ 隐藏配置竞技场",
+        Msg::ArenaNamePlaceholder => "配置名称...",
+        Msg::RedConfig => "红方配置",
+        Msg::BlackConfig => "黑方配置",
+        Msg::ArenaSubmit => "提交到竞技场",
+        Msg::ArenaConfig => "配置",
+        Msg::ArenaPending => "待赛",
+        Msg::ArenaLoadFailed => "无法加载竞技场排名",
+        Msg::ArenaSubmitted => "配置已提交，对局正在排期",
+        Msg::ArenaInvalidName => "名称无效（最多 32 个字符）",
+        Msg::ArenaNeedsSession => "请先进行一次在线对弈再提交配置",
+        Msg::ArenaNeedsRatedGame => "完成一局计分对局后才能提交配置",
+        Msg::ArenaNameTaken => "竞技场中已有此名称",
+        Msg::ArenaTooMany => "你在竞技场中的配置已达上限",
+        Msg::ArenaUnavailable => "竞技场已关闭或已满",
+        Msg::ArenaRejected => "服务器拒绝了该配置",
+        Msg::ShareCopied => "链接已复制",
+        Msg::ShareCopyBelow => "请复制下方的链接",
+        Msg::SharePosition => "🔗 分享局面",
+        Msg::ShareGame => "🔗 分享对局",
+        Msg::FenInvalid => "FEN 无效",
+        Msg::FenCopied => "FEN 已复制",
+        Msg::FenCopyBelow => "请复制下方的 FEN",
+        Msg::FenClipboardUnreadable => "无法读取剪贴板，请将 FEN 粘贴到下方输入框",
+        Msg::FenCopy => "📋 复制 FEN",
+        Msg::FenPaste => "📥 粘贴 FEN",
+        Msg::FenPlaceholder => "FEN，例如：4k4/9/9/9/9/9/9/9/9/3K5 w",
+        Msg::SharedPositionInvalid => "局面链接无效",
+        Msg::ReplayInvalid => "对局数据无效",
+        Msg::ReplayNotFound => "未找到对局",
+        Msg::RecordCoordinates => "坐标",
+        Msg::RecordWxf => "WXF",
+        Msg::RecordChinese => "中文",
+        Msg::RecordNoMoves => "未找到任何着法",
+        Msg::RecordUnreadable => "无法识别",
+        Msg::RecordIllegal => "不合规则",
+        Msg::ImportTitle => "📥 导入棋谱",
+        Msg::ImportPlaceholder => {
+            "1. 炮二平五 马8进7 2. 马二进三 车9平8 ... 或 C2=5 H8+7、h2e2 h9g7、PGN"
+        }
+        Msg::ImportButton => "导入",
+        Msg::NextPosition => "下一局",
+        Msg::TryAgain => "重试",
+        Msg::TrainingSolved => "已完成",
+        Msg::TrainingMoves => "步数",
+        Msg::TrainingComplete => "✅ 达成目标！",
+        Msg::TrainingFailed => "❌ 未达成",
+        Msg::StageEndgame => "残局",
+        Msg::StageMiddlegame => "中局",
+        Msg::Depth => "深度",
+        Msg::TraceRoot => "根节点",
+        Msg::TraceNullMove => "空着",
+        Msg::TraceSingular => "单一着法检验",
+        Msg::TraceMateDistance => "杀棋距离剪枝",
+        Msg::TraceRepetition => "重复局面",
+        Msg::TraceTranspositionTable => "取自置换表",
+        Msg::TraceQuiescence => "静态搜索",
+        Msg::TraceProbCut => "ProbCut 剪枝",
+        Msg::TraceReverseFutility => "反向无益剪枝",
+        Msg::TraceNullMoveCut => "空着剪枝",
+        Msg::TraceNoLegalMoves => "无合法着法",
+        Msg::TraceMatingMove => "杀着",
+        Msg::TraceBetaCutoff => "beta 截断",
+        Msg::TraceTimeUp => "超时",
+        Msg::TraceDynamicLimit => "跳过：动态限制",
+        Msg::TraceLateMovePruning => "跳过：后期着法剪枝",
+        Msg::TraceFutility => "跳过：无益剪枝",
+        Msg::TraceRepeatsPosition => "跳过：三次重复",
+        Msg::SearchTreeTitle => "搜索树（调试）",
+        Msg::SearchTreeRecord => "🌳 记录搜索树",
+        Msg::SearchTreeRunning => "搜索中...",
+        Msg::SearchTreeFailed => "无法记录搜索树",
+        Msg::OnlineScore => "比分",
+        Msg::OnlineTitle => "🌐 在线对弈",
+        Msg::OnlineNamePlaceholder => "显示名称（排行榜）",
+        Msg::OnlineHandicapTitle => "只与选择相同让子方式的玩家匹配；由红方让子",
+        Msg::OnlineFindMatch => "🎮 寻找对局",
+        Msg::OnlineFinding => "🔍 正在匹配...",
+        Msg::OnlineCancelFind => "❌ 取消匹配",
+        Msg::OnlineMatchFound => "✅ 已找到对手！",
+        Msg::OnlineYourSide => "你执",
+        Msg::OnlineRedFirst => "🔴 红方（先行）",
+        Msg::OnlineBlackSecond => "⚫ 黑方（后行）",
+        Msg::OnlineYourTurn => "轮到你了！",
+        Msg::OnlineSending => "📡 正在发送着法...",
+        Msg::OnlineWaiting => "⏳ 等待对手...",
+        Msg::OnlineResign => "🏳️ 认输",
+        Msg::OnlineOpponentLeft => "⚠️ 对手已断开连接！",
+        Msg::OnlineNewMatch => "🔄 寻找新对局",
+        Msg::OnlineYouWin => "你赢了！",
+        Msg::OnlineYouLose => "你输了！",
+        Msg::OnlineDraw => "和棋！",
+        Msg::OnlineGameOver => "对局结束",
+        Msg::OnlineReason => "原因",
+        Msg::OnlineReadyWaiting => "✅ 已准备 - 等待对手...",
+        Msg::OnlineRematch => "🎮 准备（再来一局）",
+        Msg::OnlineLeave => "🚪 离开",
+        Msg::SpectateTitle => "👁 正在观战",
+        Msg::SpectateStop => "🚪 停止观战",
+        Msg::EndCheckmate => "将死",
+        Msg::EndResignation => "认输",
+        Msg::EndDrawAgreement => "议和",
+        Msg::EndTimeout => "超时",
+        Msg::EndAbandoned => "断线",
+        Msg::EndAdjudication => "引擎裁定",
+        Msg::EndTerminated => "被管理员终止",
+        Msg::AbortNoFirstMove => "⌛ 因无人走出第一步，对局已取消",
+        Msg::ConfigLoadJson => "加载 JSON 配置",
+        Msg::ConfigExportJson => "导出 JSON",
+        Msg::ConfigStyle => "风格",
+        Msg::ConfigPick => "— 选择 —",
+        Msg::ConfigHashMove => "置换表着法",
+        Msg::ConfigCaptureBase => "吃子基础分",
+        Msg::ConfigKillerMove => "杀手着法",
+        Msg::ConfigHistoryMax => "历史分上限",
+        Msg::ConfigPruningMethod => "剪枝方式",
+        Msg::PruneDynamic => "动态限制",
+        Msg::PruneLmr => "后期着法削减（LMR）",
+        Msg::PruneBoth => "两者（激进）",
+        Msg::ConfigMultiplier => "系数",
+        Msg::ConfigMateScore => "杀棋分",
+        Msg::ConfigKingSafety => "将帅安全（%）",
+        Msg::ConfigContempt => "藐视因子",
+        Msg::ConfigTtSize => "置换表（TT）",
+        Msg::ConfigResignScore => "落后多少分时认输",
+        Msg::ConfigResignMoves => "认输前的步数（0 = 不认输）",
+        Msg::ConfigDrawScore => "和棋阈值（分）",
+        Msg::ConfigDrawMoves => "均势多少步后求和（0 = 不求和）",
+        Msg::ProfileBalanced => "均衡",
+        Msg::ProfileAggressive => "进攻",
+        Msg::ProfilePositional => "局面",
+        Msg::ProfileMaterialist => "重子力",
+        Msg::ProfileGambit => "弃子",
+        Msg::FriendsUntimed => "不限时",
+        Msg::PolicyForfeit => "立即判负",
+        Msg::PolicyAdjudicate => "引擎裁定",
+        Msg::FriendsAccept => "接受",
+        Msg::FriendsDecline => "拒绝",
+        Msg::FriendsCancel => "取消",
+        Msg::FriendsShow => "👥 好友",
+        Msg::FriendsHide => "👥 隐藏好友",
+        Msg::FriendsNamePlaceholder => "棋手名称",
+        Msg::FriendsAdd => "添加好友",
+        Msg::FriendsTime => "时间：",
+        Msg::FriendsOnDisconnect => "断线时：",
+        Msg::FriendsNone => "还没有好友",
+        Msg::FriendsChallenge => "挑战",
+        Msg::FriendsRemove => "从列表中移除",
+        Msg::BoardClassic => "经典",
+        Msg::BoardWood => "木纹",
+        Msg::BoardJade => "翡翠",
+        Msg::BoardNight => "夜间",
+        Msg::PiecesChinese => "汉字",
+        Msg::PiecesInternational => "国际 (K, R, C...)",
+        Msg::JudgeInaccuracy => "不精确",
+        Msg::JudgeMistake => "失误",
+        Msg::JudgeBlunder => "严重失误",
+        Msg::ReviewAccuracy => "准确率",
+        Msg::ReviewAvgLoss => "平均失分",
+        Msg::ReviewGame => "📊 对局分析",
+        Msg::ReviewNoMistakes => "没有失误",
+        Msg::ReviewGraph => "📈 局面评估图",
+        Msg::ReviewStartPosition => "初始局面",
+        Msg::MateSearching => "正在搜索杀棋...",
+        Msg::MoveTakenBack => "↩️ 你的着法已被撤回",
+        Msg::ChallengeDeclined => "⚔️ 你的挑战被拒绝了",
+        Msg::AppTitle => "象棋",
+        Msg::PuzzleYourMove => "轮到你了",
+        Msg::PuzzleOpponentMoving => "对手正在走棋...",
+        Msg::PuzzleTactic => "战术",
+        Msg::PuzzleCorrect => "✅ 正确！",
+        Msg::PuzzleWrong => "❌ 不对",
+        Msg::PuzzleSkip => "跳过",
+        Msg::ProfileLoadFailed => "无法加载棋手资料",
+        Msg::ProfileBack => "◀ 排行榜",
+        Msg::ProfileGames => "对局数",
+        Msg::ProfileEloChart => "等级分走势",
+        Msg::ProfileTooFewGames => "对局太少，暂无图表",
+        Msg::ProfileFavouriteOpenings => "常用开局",
+        Msg::ProfileNoOpenings => "暂无",
+        Msg::LiveLoadFailed => "无法加载进行中的对局",
+        Msg::LiveShow => "👁 进行中的对局",
+        Msg::LiveHide => "👁 隐藏进行中的对局",
+        Msg::LiveNone => "暂无进行中的对局",
+        Msg::LiveMoves => "步数",
+        Msg::LiveOpening => "开局",
+        Msg::LiveTimeControl => "时限",
+        Msg::LiveWatch => "观战",
+        Msg::LessonRetry => "❌ 不对，再试一次",
+        Msg::LessonComplete => "🎓 课程完成！",
+        Msg::LessonNext => "下一课",
+        Msg::LessonRestart => "重新学习",
+        Msg::LeaderboardLoadFailed => "无法加载排行榜",
+        Msg::LeaderboardShow => "🏆 排行榜",
+        Msg::LeaderboardHide => "🏆 隐藏排行榜",
+        Msg::LeaderboardSearch => "按棋手名称搜索...",
+        Msg::LeaderboardPlayer => "棋手",
+        Msg::LeaderboardViewProfile => "查看资料",
+        Msg::ExplorerTitle => "📖 开局库",
+        Msg::ExplorerOutOfBook => "局面已超出开局库",
+        Msg::ExplorerWeight => "权重",
+        Msg::LogTitle => "📜 棋谱",
+        Msg::LogLive => "⏭ 当前",
+        Msg::EngineThinking => "电脑思考中...",
+        Msg::InstallApp => "📲 安装应用",
+    }
+}
+
+/// "Adaptive" difficulty with the player's current skill and record.
+pub fn adaptive_label(
+    language: Language,
+    percent: u32,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+) -> String {
+    match language {
+        Language::Vietnamese => {
+            format!("Tự điều chỉnh ({percent}% · {wins}T/{draws}H/{losses}B)")
+        }
+        Language::English => format!("Adaptive ({percent}% · {wins}W/{draws}D/{losses}L)"),
+        Language::Chinese => format!("自适应（{percent}% · {wins}胜/{draws}和/{losses}负）"),
+    }
+}
+
+/// A score as wins, draws and losses, or Red's wins, draws and Black's wins in a two-player game.
+pub fn score_label(
+    language: Language,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+    two_player: bool,
+) -> String {
+    match (language, two_player) {
+        (Language::Vietnamese, false) => format!("{wins}T/{draws}H/{losses}B"),
+        (Language::Vietnamese, true) => format!("Đỏ {wins} · Hòa {draws} · Đen {losses}"),
+        (Language::English, false) => format!("{wins}W/{draws}D/{losses}L"),
+        (Language::English, true) => format!("Red {wins} · Draws {draws} · Black {losses}"),
+        (Language::Chinese, false) => format!("{wins}胜/{draws}和/{losses}负"),
+        (Language::Chinese, true) => format!("红 {wins} · 和 {draws} · 黑 {losses}"),
+    }
+}
+
+/// The current run of wins (positive) or losses (negative); nothing after a draw.
+pub fn streak_label(language: Language, streak: i32) -> Option<String> {
+    let count = streak.unsigned_abs();
+    let text = match (language, streak > 0) {
+        _ if streak == 0 => return None,
+        (Language::Vietnamese, true) => format!("🔥 thắng {count} ván liền"),
+        (Language::Vietnamese, false) => format!("thua {count} ván liền"),
+        (Language::English, true) => format!("🔥 {count} wins in a row"),
+        (Language::English, false) => format!("{count} losses in a row"),
+        (Language::Chinese, true) => format!("🔥 {count} 连胜"),
+        (Language::Chinese, false) => format!("{count} 连败"),
+    };
+    Some(text)
+}
+
+/// Countdown shown while an online move is running out of time.
+pub fn move_time_left_label(language: Language, seconds: u64) -> String {
+    match language {
+        Language::Vietnamese => format!("⏰ Còn {seconds} giây để đi!"),
+        Language::English => format!("⏰ {seconds} seconds left to move!"),
+        Language::Chinese => format!("⏰ 还剩 {seconds} 秒走棋！"),
+    }
+}
+
+/// Shown while the server holds a dropped opponent's seat.
+pub fn opponent_away_label(language: Language, seconds: u64) -> String {
+    match language {
+        Language::Vietnamese => {
+            format!("📴 Đối thủ mất kết nối, chờ kết nối lại ({seconds} giây)")
+        }
+        Language::English => {
+            format!("📴 The opponent disconnected, waiting for them to return ({seconds} s)")
+        }
+        Language::Chinese => format!("📴 对手已断线，等待重新连接（{seconds} 秒）"),
+    }
+}
+
+/// A time control offered when challenging a friend.
+pub fn minutes_label(language: Language, minutes: u32) -> String {
+    match language {
+        Language::Vietnamese => format!("{minutes} phút"),
+        Language::English => format!("{minutes} min"),
+        Language::Chinese => format!("{minutes} 分钟"),
+    }
+}
+
+/// The disconnect policy that holds a dropped player's seat for `minutes`.
+pub fn reconnect_label(language: Language, minutes: u32) -> String {
+    match language {
+        Language::Vietnamese => format!("Chờ kết nối lại {minutes} phút"),
+        Language::English => format!("Wait {minutes} min for a reconnect"),
+        Language::Chinese => format!("等待重连 {minutes} 分钟"),
+    }
+}
+
+/// A friend's challenge with its time control and disconnect policy.
+pub fn challenge_label(language: Language, from: &str, time: &str, policy: &str) -> String {
+    match language {
+        Language::Vietnamese => format!("⚔️ {from} thách đấu bạn ({time}, mất kết nối: {policy})"),
+        Language::English => format!("⚔️ {from} challenges you ({time}, on disconnect: {policy})"),
+        Language::Chinese => format!("⚔️ {from} 向你发起挑战（{time}，断线时：{policy}）"),
+    }
+}
+
+/// Shown while a challenge sent to `name` is unanswered.
+pub fn awaiting_answer_label(language: Language, name: &str) -> String {
+    match language {
+        Language::Vietnamese => format!("⏳ Đang chờ {name} trả lời..."),
+        Language::English => format!("⏳ Waiting for {name} to answer..."),
+        Language::Chinese => format!("⏳ 等待 {name} 回应..."),
+    }
+}
+
+/// Progress of a game review, in positions searched.
+pub fn review_progress_label(language: Language, done: usize, total: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("Đang phân tích... {done}/{total} thế cờ"),
+        Language::English => format!("Reviewing... {done}/{total} positions"),
+        Language::Chinese => format!("分析中... {done}/{total} 个局面"),
+    }
+}
+
+/// A reviewed move's verdict and the move the engine preferred.
+pub fn better_move_label(language: Language, verdict: &str, better: &str) -> String {
+    match language {
+        Language::Vietnamese => format!("{verdict} · tốt hơn {better}"),
+        Language::English => format!("{verdict} · better was {better}"),
+        Language::Chinese => format!("{verdict} · 更好：{better}"),
+    }
+}
+
+/// A forced mate found by the mate search, in moves by the side to move.
+pub fn mate_found_label(language: Language, moves: usize) -> String {
+    match language {
+        Language::Vietnamese => {
+            format!("✅ Có chiếu bí sau {moves} nước (mũi tên chỉ nước đầu tiên)")
+        }
+        Language::English => format!("✅ Mate in {moves} (the arrow shows the first move)"),
+        Language::Chinese => format!("✅ {moves} 步杀（箭头为第一步）"),
+    }
+}
+
+/// The mate search found no mate within `moves` moves.
+pub fn mate_not_found_label(language: Language, moves: u8) -> String {
+    match language {
+        Language::Vietnamese => format!("Không tìm thấy chiếu bí trong {moves} nước"),
+        Language::English => format!("No mate found within {moves} moves"),
+        Language::Chinese => format!("{moves} 步内未找到杀棋"),
+    }
+}
+
+/// Warning that the server restarts in `seconds`.
+pub fn server_restart_label(language: Language, seconds: u64) -> String {
+    match language {
+        Language::Vietnamese => {
+            format!("⚠️ Máy chủ sẽ khởi động lại sau {seconds} giây. Ván đấu sẽ được lưu lại.")
+        }
+        Language::English => {
+            format!("⚠️ The server restarts in {seconds} seconds. Your game will be saved.")
+        }
+        Language::Chinese => format!("⚠️ 服务器将在 {seconds} 秒后重启，对局会被保存。"),
+    }
+}
+
+/// Which of the `count` puzzles is on the board, counted from 1.
+pub fn puzzle_number_label(language: Language, number: usize, count: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("🧩 Thế cờ {number}/{count}"),
+        Language::English => format!("🧩 Puzzle {number}/{count}"),
+        Language::Chinese => format!("🧩 第 {number}/{count} 题"),
+    }
+}
+
+/// A puzzle whose solution mates in `moves` moves.
+pub fn mate_in_label(language: Language, moves: u8) -> String {
+    match language {
+        Language::Vietnamese => format!("Chiếu bí sau {moves} nước"),
+        Language::English => format!("Mate in {moves}"),
+        Language::Chinese => format!("{moves} 步杀"),
+    }
+}
+
+/// Puzzles solved in a row, and the most ever.
+pub fn puzzle_streak_label(language: Language, streak: u32, best: u32) -> String {
+    match language {
+        Language::Vietnamese => format!("🔥 Chuỗi: {streak} · Kỷ lục: {best}"),
+        Language::English => format!("🔥 Streak: {streak} · Best: {best}"),
+        Language::Chinese => format!("🔥 连续：{streak} · 纪录：{best}"),
+    }
+}
+
+/// How many games a player has played with an opening.
+pub fn games_count_label(language: Language, games: u32) -> String {
+    match language {
+        Language::Vietnamese => format!("{games} ván"),
+        Language::English if games == 1 => "1 game".to_string(),
+        Language::English => format!("{games} games"),
+        Language::Chinese => format!("{games} 局"),
+    }
+}
+
+/// Which of a lesson's `count` steps is shown, counted from 1.
+pub fn lesson_step_label(language: Language, step: usize, count: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("Bước {step}/{count}"),
+        Language::English => format!("Step {step}/{count}"),
+        Language::Chinese => format!("第 {step}/{count} 步"),
+    }
+}
+
+/// A page of a paged list, counted from 1.
+pub fn page_label(language: Language, page: usize, pages: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("Trang {page} / {pages}"),
+        Language::English => format!("Page {page} / {pages}"),
+        Language::Chinese => format!("第 {page} / {pages} 页"),
+    }
+}
+
+/// How many replies the opening book has for the position.
+pub fn book_moves_label(language: Language, moves: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("{moves} nước trong sách"),
+        Language::English => format!("{moves} in the book"),
+        Language::Chinese => format!("开局库 {moves} 着"),
+    }
+}
+
+/// Moves played so far in a game.
+pub fn moves_played_label(language: Language, moves: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("{moves} nước"),
+        Language::English => format!("{moves} moves"),
+        Language::Chinese => format!("{moves} 着"),
+    }
+}
+
+/// An earlier position of the game being looked at, as a ply out of the game's `plies`.
+pub fn viewing_ply_label(language: Language, ply: usize, plies: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("Đang xem lại: nước {ply}/{plies}"),
+        Language::English => format!("Looking back: move {ply}/{plies}"),
+        Language::Chinese => format!("回看：第 {ply}/{plies} 着"),
+    }
+}
+
+/// The move a game has reached, counted from 1.
+pub fn move_number_label(language: Language, number: usize) -> String {
+    match language {
+        Language::Vietnamese => format!("Nước thứ {number}"),
+        Language::English => format!("Move {number}"),
+        Language::Chinese => format!("第 {number} 步"),
+    }
+}
+
+/// A training position's goal: win, or hold without losing, within `moves` moves.
+pub fn training_goal_label(language: Language, win: bool, moves: u8) -> String {
+    match (language, win) {
+        (Language::Vietnamese, true) => format!("Thắng trong {moves} nước"),
+        (Language::Vietnamese, false) => format!("Không thua trong {moves} nước"),
+        (Language::English, true) => format!("Win within {moves} moves"),
+        (Language::English, false) => format!("Do not lose within {moves} moves"),
+        (Language::Chinese, true) => format!("{moves} 步内取胜"),
+        (Language::Chinese, false) => format!("{moves} 步内不败"),
+    }
+}
+
+/// Result line of a game record import.
+pub fn imported_label(language: Language, moves: usize, notation: &str) -> String {
+    match language {
+        Language::Vietnamese => format!("Đã nhập {moves} nước (ký hiệu {notation})"),
+        Language::English => format!("Imported {moves} moves ({notation} notation)"),
+        Language::Chinese => format!("已导入 {moves} 步（{notation}记谱）"),
+    }
+}
+
+/// The move of a game record that could not be replayed, numbered from 1.
+pub fn record_error_label(
+    language: Language,
+    ply: usize,
+    side: &str,
+    token: &str,
+    reason: &str,
+) -> String {
+    match language {
+        Language::Vietnamese => format!("Nước {ply} ({side}) «{token}»: {reason}"),
+        Language::English => format!("Move {ply} ({side}) «{token}»: {reason}"),
+        Language::Chinese => format!("第 {ply} 步（{side}）«{token}»：{reason}"),
+    }
+}
+
+/// `msg` followed by a detail such as a parser error; just `msg` when there is none.
+pub fn detail_text(msg: Msg, detail: &str, language: Language) -> String {
+    let text = msg.text(language);
+    match language {
+        _ if detail.is_empty() => text.to_string(),
+        Language::Vietnamese | Language::English => format!("{text}: {detail}"),
+        Language::Chinese => format!("{text}：{detail}"),
+    }
+}
+
+pub const fn piece_name(piece_type: PieceType, language: Language) -> &'static str {
+    match language {
+        Language::Vietnamese => match piece_type {
+            PieceType::General => "Tướng",
+            PieceType::Advisor => "Sĩ",
+            PieceType::Elephant => "Tượng",
+            PieceType::Horse => "Mã",
+            PieceType::Chariot => "Xe",
+            PieceType::Cannon => "Pháo",
+            PieceType::Soldier => "Tốt",
+        },
+        Language::English => match piece_type {
+            PieceType::General => "General",
+            PieceType::Advisor => "Advisor",
+            PieceType::Elephant => "Elephant",
+            PieceType::Horse => "Horse",
+            PieceType::Chariot => "Chariot",
+            PieceType::Cannon => "Cannon",
+            PieceType::Soldier => "Soldier",
+        },
+        Language::Chinese => match piece_type {
+            PieceType::General => "将",
+            PieceType::Advisor => "士",
+            PieceType::Elephant => "象",
+            PieceType::Horse => "马",
+            PieceType::Chariot => "车",
+            PieceType::Cannon => "炮",
+            PieceType::Soldier => "兵",
+        },
+    }
+}
+
+/// The engine's reasons for a move as one line, e.g. `Ăn Mã · Dọa Xe · Điểm +270 · Biến: H2-E2`.
+fn explanation_text(explanation: &MoveExplanation, language: Language) -> String {
+    let word = |vi: &'static str, en: &'static str, zh: &'static str| match language {
+        Language::Vietnamese => vi,
+        Language::English => en,
+        Language::Chinese => zh,
+    };
+    let names = |pieces: &[PieceType]| {
+        pieces
+            .iter()
+            .map(|&p| piece_name(p, language))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut parts = Vec::new();
+    if let Some(captured) = explanation.captured {
+        parts.push(format!(
+            "{} {}",
+            word("Ăn", "Takes", "吃"),
+            piece_name(captured, language)
+        ));
+    }
+    if explanation.gives_check {
+        parts.push(word("Chiếu tướng", "Check", "将军").to_string());
+    }
+    if !explanation.threats.is_empty() {
+        parts.push(format!(
+            "{} {}",
+            word("Dọa", "Threatens", "威胁"),
+            names(&explanation.threats)
+        ));
+    }
+    if !explanation.defends.is_empty() {
+        parts.push(format!(
+            "{} {}",
+            word("Bảo vệ", "Defends", "保护"),
+            names(&explanation.defends)
+        ));
+    }
+    if explanation.material_swing != 0 {
+        parts.push(format!(
+            "{} {:+}",
+            word("Vật chất", "Material", "子力"),
+            explanation.material_swing
+        ));
+    }
+    parts.push(format!(
+        "{} {:+}",
+        word("Điểm", "Score", "评分"),
+        explanation.score
+    ));
+    let line: Vec<String> = explanation
+        .pv
+        .iter()
+        .filter_map(|mv| {
+            let from = BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)?;
+            let to = BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize)?;
+            Some(format_pgn_move(from, to))
+        })
+        .collect();
+    if !line.is_empty() {
+        parts.push(format!(
+            "{} {}",
+            word("Biến:", "Line:", "变着："),
+            line.join(" ")
+        ));
+    }
+    parts.join(" · ")
+}
+
+/// A move's annotation for the move log.
+pub fn annotation_text(annotation: &MoveAnnotation, language: Language) -> String {
+    match annotation {
+        MoveAnnotation::Book => match language {
+            Language::Vietnamese => "📖 Nước trong sách khai cuộc".to_string(),
+            Language::English => "📖 Book move".to_string(),
+            Language::Chinese => "📖 开局库着法".to_string(),
+        },
+        MoveAnnotation::Engine {
+            depth, explanation, ..
+        } => {
+            let reasons = explanation_text(explanation, language);
+            match language {
+                Language::Vietnamese => format!("🤖 {reasons} (độ sâu {depth})"),
+                Language::English => format!("🤖 {reasons} (depth {depth})"),
+                Language::Chinese => format!("🤖 {reasons}（深度 {depth}）"),
+            }
+        }
+    }
+}
+
+/// User-facing text for each server error code.
+pub const fn error_message(code: ErrorCode, language: Language) -> &'static str {
+    match language {
+        Language::Vietnamese => match code {
+            ErrorCode::NotYourTurn => "⏳ Chưa đến lượt của bạn",
+            ErrorCode::IllegalMove => "🚫 Nước đi không hợp lệ, bàn cờ đã được đồng bộ lại",
+            ErrorCode::NotInGame => "❓ Bạn không ở trong ván đấu nào",
+            ErrorCode::GameAlreadyEnded => "🏁 Ván đấu đã kết thúc",
+            ErrorCode::RateLimited => "🐢 Bạn thao tác quá nhanh, vui lòng chờ một chút",
+            ErrorCode::ProtocolVersionMismatch => {
+                "⬆️ Phiên bản ứng dụng đã cũ, vui lòng tải lại trang"
+            }
+            ErrorCode::InvalidToken => "🔒 Phiên kết nối không hợp lệ, vui lòng tải lại trang",
+            ErrorCode::InvalidName => "✏️ Tên phải có từ 1 đến 20 ký tự",
+            ErrorCode::MatchmakingUnavailable => "⚠️ Hệ thống ghép trận đang gặp sự cố",
+            ErrorCode::Internal => "⚠️ Lỗi máy chủ",
+            ErrorCode::GameNotFound => "🔎 Ván đấu không còn diễn ra",
+            ErrorCode::Banned => "⛔ Tên này đã bị quản trị viên cấm",
+            ErrorCode::NameTaken => "✏️ Tên này đã có người dùng",
+            ErrorCode::PlayerNotFound => "👥 Không có người chơi nào tên này",
+            ErrorCode::PlayerUnavailable => "👥 Người này chưa là bạn bè hoặc đang bận",
+            ErrorCode::ChallengeNotFound => "⚔️ Lời thách đấu không còn hiệu lực",
+        },
+        Language::English => match code {
+            ErrorCode::NotYourTurn => "⏳ It is not your turn",
+            ErrorCode::IllegalMove => "🚫 Illegal move, the board has been resynchronized",
+            ErrorCode::NotInGame => "❓ You are not in a game",
+            ErrorCode::GameAlreadyEnded => "🏁 The game is over",
+            ErrorCode::RateLimited => "🐢 Too many actions, please wait a moment",
+            ErrorCode::ProtocolVersionMismatch => "⬆️ The app is out of date, please reload",
+            ErrorCode::InvalidToken => "🔒 Invalid session, please reload",
+            ErrorCode::InvalidName => "✏️ Names must be 1 to 20 characters long",
+            ErrorCode::MatchmakingUnavailable => "⚠️ Matchmaking is having trouble",
+            ErrorCode::Internal => "⚠️ Server error",
+            ErrorCode::GameNotFound => "🔎 That game is no longer being played",
+            ErrorCode::Banned => "⛔ This name was banned by an administrator",
+            ErrorCode::NameTaken => "✏️ Another player already goes by this name",
+            ErrorCode::PlayerNotFound => "👥 No player goes by this name",
+            ErrorCode::PlayerUnavailable => "👥 This player is not a friend or is busy",
+            ErrorCode::ChallengeNotFound => "⚔️ The challenge is no longer valid",
+        },
+        Language::Chinese => match code {
+            ErrorCode::NotYourTurn => "⏳ 还没轮到你",
+            ErrorCode::IllegalMove => "🚫 着法不合规则，棋盘已重新同步",
+            ErrorCode::NotInGame => "❓ 你不在任何对局中",
+            ErrorCode::GameAlreadyEnded => "🏁 对局已结束",
+            ErrorCode::RateLimited => "🐢 操作过快，请稍候",
+            ErrorCode::ProtocolVersionMismatch => "⬆️ 应用版本过旧，请刷新页面",
+            ErrorCode::InvalidToken => "🔒 连接会话无效，请刷新页面",
+            ErrorCode::InvalidName => "✏️ 名字须为 1 到 20 个字符",
+            ErrorCode::MatchmakingUnavailable => "⚠️ 匹配系统出现故障",
+            ErrorCode::Internal => "⚠️ 服务器错误",
+            ErrorCode::GameNotFound => "🔎 该对局已不在进行",
+            ErrorCode::Banned => "⛔ 该名字已被管理员封禁",
+            ErrorCode::NameTaken => "✏️ 该名字已被其他棋手使用",
+            ErrorCode::PlayerNotFound => "👥 没有叫这个名字的棋手",
+            ErrorCode::PlayerUnavailable => "👥 对方不是好友或正忙",
+            ErrorCode::ChallengeNotFound => "⚔️ 该挑战已失效",
+        },
+    }
+}
@@ -1,5 +1,7 @@
 mod app;
+mod audio;
 mod components;
+mod i18n;
 mod network;
 
 use app::App;
@@ -1,22 +1,102 @@
 use leptos::{SignalSet, WriteSignal};
-use shared::{GameMessage, ServerMessage};
+use shared::codec::{self, Frame};
+use shared::{ClientHello, ClientMessage, Encoding, GameMessage, ServerMessage, PROTOCOL_VERSION};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
-use web_sys::{MessageEvent, WebSocket};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{BinaryType, MessageEvent, WebSocket};
+
+const SERVER_ADDR: &str = "127.0.0.1:3000";
+/// Keepalive period; must stay well under the server's `HEARTBEAT_TIMEOUT_SECS` (default 30s).
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+/// `localStorage` key of the last `Welcome` token, so a returning client is the same player
+/// to the server: same rating and friends, and any seat it holds.
+const TOKEN_KEY: &str = "cotuong.player_token";
+
+fn token_storage() -> Option<web_sys::Storage> {
+    web_sys::window().and_then(|w| w.local_storage().ok().flatten())
+}
+
+/// Performs a GET request against the game server's HTTP API and decodes the JSON body.
+#[allow(clippy::future_not_send)]
+pub async fn fetch_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window available"))?;
+    let url = format!("http://{SERVER_ADDR}{path}");
+
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str(&url))
+        .await?
+        .dyn_into()?;
+    if !response.ok() {
+        return Err(JsValue::from_str(&format!("HTTP {}", response.status())));
+    }
+
+    let text = JsFuture::from(response.text()?)
+        .await?
+        .as_string()
+        .ok_or_else(|| JsValue::from_str("Response body is not text"))?;
+    serde_json::from_str(&text).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Sends `body` as JSON in a POST request to the game server's HTTP API and returns the
+/// response status; only network failures are errors. The token of the last online session,
+/// if any, goes along as a bearer token.
+#[allow(clippy::future_not_send)]
+pub async fn post_json<T: serde::Serialize>(path: &str, body: &T) -> Result<u16, JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("No window available"))?;
+    let url = format!("http://{SERVER_ADDR}{path}");
+    let body = serde_json::to_string(body).map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let headers = web_sys::Headers::new()?;
+    headers.set("Content-Type", "application/json")?;
+    if let Some(token) =
+        token_storage().and_then(|storage| storage.get_item(TOKEN_KEY).ok().flatten())
+    {
+        headers.set("Authorization", &format!("Bearer {token}"))?;
+    }
+    let init = web_sys::RequestInit::new();
+    init.set_method("POST");
+    init.set_headers(&headers);
+    init.set_body(&JsValue::from_str(&body));
+
+    let response: web_sys::Response = JsFuture::from(window.fetch_with_str_and_init(&url, &init))
+        .await?
+        .dyn_into()?;
+    Ok(response.status())
+}
 
 #[derive(Clone)]
 pub struct NetworkClient {
     ws: WebSocket,
+    /// Issued by the server in `ServerMessage::Welcome`; attached to every outgoing message.
+    token: Rc<RefCell<Option<String>>>,
+    /// Frame encoding negotiated in `ServerMessage::ServerHello`.
+    encoding: Rc<Cell<Encoding>>,
 }
 
 impl NetworkClient {
     pub fn new(on_message: WriteSignal<Option<ServerMessage>>) -> Result<Self, JsValue> {
-        let url = "ws://127.0.0.1:3000/ws";
+        let url = format!("ws://{SERVER_ADDR}/ws");
 
         leptos::logging::log!("[WS] Connecting to {}", url);
-        let ws = WebSocket::new(url)?;
+        let ws = WebSocket::new(&url)?;
+        ws.set_binary_type(BinaryType::Arraybuffer);
 
+        let ws_open = ws.clone();
         let onopen_callback = Closure::<dyn FnMut()>::new(move || {
             leptos::logging::log!("[WS] Successfully connected to server");
+            let hello = ClientHello {
+                protocol_version: PROTOCOL_VERSION,
+                encodings: Encoding::supported(),
+                resume_token: token_storage()
+                    .and_then(|storage| storage.get_item(TOKEN_KEY).ok().flatten()),
+            };
+            if let Ok(json) = serde_json::to_string(&hello) {
+                if let Err(e) = ws_open.send_with_str(&json) {
+                    leptos::logging::log!("[WS] Failed to send ClientHello: {:?}", e);
+                }
+            }
         });
         ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
         onopen_callback.forget();
@@ -33,36 +113,98 @@ impl NetworkClient {
         ws.set_onclose(Some(onclose_callback.as_ref().unchecked_ref()));
         onclose_callback.forget();
 
+        let token = Rc::new(RefCell::new(None));
+        let token_slot = Rc::clone(&token);
+        let encoding = Rc::new(Cell::new(Encoding::Json));
+        let encoding_slot = Rc::clone(&encoding);
         let onmessage_callback = Closure::<dyn FnMut(_)>::new(move |e: MessageEvent| {
-            if let Ok(txt) = e.data().dyn_into::<js_sys::JsString>() {
-                let txt_str = String::from(txt);
-                match serde_json::from_str::<ServerMessage>(&txt_str) {
-                    Ok(msg) => {
+            let data = e.data();
+            let frame = if let Some(text) = data.as_string() {
+                Frame::Text(text)
+            } else if let Ok(buffer) = data.dyn_into::<js_sys::ArrayBuffer>() {
+                Frame::Binary(js_sys::Uint8Array::new(&buffer).to_vec())
+            } else {
+                return;
+            };
+
+            match codec::decode::<ServerMessage>(&frame) {
+                Ok(msg) => {
+                    if !matches!(msg, ServerMessage::Pong(_)) {
                         leptos::logging::log!("[WS] Received: {:?}", msg);
-                        on_message.set(Some(msg));
                     }
-                    Err(e) => {
-                        leptos::logging::log!(
-                            "[WS] Failed to parse ServerMessage: {}, raw: {}",
-                            e,
-                            txt_str
-                        );
+                    match &msg {
+                        ServerMessage::ServerHello { encoding, .. } => {
+                            encoding_slot.set(*encoding);
+                        }
+                        ServerMessage::Welcome { token, .. } => {
+                            *token_slot.borrow_mut() = Some(token.clone());
+                            if let Some(storage) = token_storage() {
+                                let _ = storage.set_item(TOKEN_KEY, token);
+                            }
+                        }
+                        _ => {}
                     }
+                    on_message.set(Some(msg));
+                }
+                Err(e) => {
+                    leptos::logging::log!(
+                        "[WS] Failed to decode ServerMessage: {}, raw: {:?}",
+                        e,
+                        frame
+                    );
                 }
             }
         });
         ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
         onmessage_callback.forget();
 
-        Ok(Self { ws })
+        let client = Self {
+            ws,
+            token,
+            encoding,
+        };
+        client.start_keepalive();
+        Ok(client)
+    }
+
+    /// Pings the server periodically so it can tell a quiet player from a dead connection.
+    fn start_keepalive(&self) {
+        let client = self.clone();
+        let nonce = Cell::new(0_u64);
+        leptos::set_interval(
+            move || {
+                if client.ws.ready_state() != WebSocket::OPEN {
+                    return;
+                }
+                nonce.set(nonce.get().wrapping_add(1));
+                client.send(&GameMessage::Ping(nonce.get()));
+            },
+            PING_INTERVAL,
+        );
     }
 
     pub fn send(&self, msg: &GameMessage) {
-        if let Ok(json) = serde_json::to_string(msg) {
+        let Some(token) = self.token.borrow().clone() else {
+            leptos::logging::log!("[WS] Not authenticated yet, dropping: {:?}", msg);
+            return;
+        };
+        let envelope = ClientMessage {
+            token,
+            msg: msg.clone(),
+        };
+        if !matches!(msg, GameMessage::Ping(_)) {
             leptos::logging::log!("[WS] Sending: {:?}", msg);
-            if let Err(e) = self.ws.send_with_str(&json) {
-                leptos::logging::log!("[WS] Failed to send: {:?}", e);
+        }
+        let result = match codec::encode(self.encoding.get(), &envelope) {
+            Ok(Frame::Text(text)) => self.ws.send_with_str(&text),
+            Ok(Frame::Binary(bytes)) => self.ws.send_with_u8_array(&bytes),
+            Err(e) => {
+                leptos::logging::log!("[WS] Failed to encode: {}", e);
+                return;
             }
+        };
+        if let Err(e) = result {
+            leptos::logging::log!("[WS] Failed to send: {:?}", e);
         }
     }
 }
@@ -0,0 +1,48 @@
+//! `cargo bench -p cotuong_core --bench movegen`: times the reference generator, which
+//! validates every move on a board copy, against the pin-aware legal generator.
+
+use cotuong_core::engine::bench::BENCH_POSITIONS;
+use cotuong_core::logic::board::Board;
+use cotuong_core::logic::generator::MoveGenerator;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ROUNDS: u32 = 2000;
+
+fn main() {
+    let generator = MoveGenerator::new();
+    let mut reference_total = 0.0;
+    let mut legal_total = 0.0;
+
+    for fen in BENCH_POSITIONS {
+        let Ok((board, turn)) = Board::from_fen(fen) else {
+            eprintln!("bad bench position: {fen}");
+            continue;
+        };
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            black_box(generator.generate_moves(black_box(&board), turn));
+        }
+        let reference = start.elapsed().as_secs_f64() * 1e6 / f64::from(ROUNDS);
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            black_box(generator.generate_legal_moves(black_box(&board), turn));
+        }
+        let legal = start.elapsed().as_secs_f64() * 1e6 / f64::from(ROUNDS);
+
+        let count = generator.generate_legal_moves(&board, turn).len();
+        println!("{count:>3} moves  reference {reference:>7.2} us  legal {legal:>7.2} us  {fen}");
+        reference_total += reference;
+        legal_total += legal;
+    }
+
+    println!("===========================");
+    println!("reference : {reference_total:.2} us");
+    println!("legal     : {legal_total:.2} us");
+    println!(
+        "speedup   : {:.1}x",
+        reference_total / legal_total.max(1e-9)
+    );
+}
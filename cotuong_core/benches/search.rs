@@ -0,0 +1,13 @@
+//! `cargo bench -p cotuong_core` entry point; runs the same suite as the `bench` binary.
+
+use cotuong_core::engine::bench::run_bench;
+use cotuong_core::engine::config::EngineConfig;
+
+const DEPTH: u8 = 8;
+
+fn main() {
+    match run_bench(DEPTH, &EngineConfig::default()) {
+        Ok(report) => println!("{report}"),
+        Err(e) => eprintln!("bench failed: {e}"),
+    }
+}
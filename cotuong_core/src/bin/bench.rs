@@ -0,0 +1,43 @@
+//! Searches the fixed bench suite to a fixed depth and reports nodes and NPS. The node total
+//! is a fingerprint of the search: a change that should not alter it (a pure speed-up) must
+//! leave it identical, and comparing NPS between commits needs the same depth.
+//!
+//! Usage: `cargo run --release -p cotuong_core --bin bench -- [DEPTH] [CONFIG]`
+//!
+//! `DEPTH` defaults to 8; `CONFIG` is a JSON config or profile name (default: balanced).
+
+use cotuong_core::engine::bench::run_bench;
+use cotuong_core::engine::match_runner::load_config;
+use std::process::ExitCode;
+
+const DEFAULT_DEPTH: u8 = 8;
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let depth = match args.next().map(|d| d.parse::<u8>()) {
+        None => DEFAULT_DEPTH,
+        Some(Ok(depth)) => depth,
+        Some(Err(_)) => {
+            eprintln!("usage: bench [DEPTH] [CONFIG]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = match load_config(args.next().as_deref().unwrap_or("balanced")) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match run_bench(depth, &config) {
+        Ok(report) => {
+            println!("{report}");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
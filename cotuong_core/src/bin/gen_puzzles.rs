@@ -0,0 +1,60 @@
+//! Scans games exported from the client ("Xuất JSON") and prints puzzle candidates, one JSON
+//! record per line, in the format of `cotuong_core::logic::puzzle::Puzzle`.
+//!
+//! Usage: `cargo run --release -p cotuong_core --bin gen_puzzles -- game1.json game2.json`
+
+use cotuong_core::engine::puzzle_gen::{puzzles_from_game, GeneratorConfig};
+use cotuong_core::logic::board::BoardCoordinate;
+use cotuong_core::logic::handicap::HandicapKind;
+use serde::Deserialize;
+use std::process::ExitCode;
+
+#[derive(Deserialize)]
+struct ExportedMove {
+    from: BoardCoordinate,
+    to: BoardCoordinate,
+}
+
+#[derive(Deserialize)]
+struct ExportedGame {
+    #[serde(default)]
+    handicap: HandicapKind,
+    moves: Vec<ExportedMove>,
+}
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: gen_puzzles <game.json>...");
+        return ExitCode::FAILURE;
+    }
+
+    let config = GeneratorConfig::default();
+    let mut seen = std::collections::HashSet::new();
+
+    for path in &paths {
+        let game: ExportedGame = match std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| e.to_string()))
+        {
+            Ok(game) => game,
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                continue;
+            }
+        };
+
+        let moves: Vec<_> = game.moves.iter().map(|m| (m.from, m.to)).collect();
+        for puzzle in puzzles_from_game(game.handicap.initial_state(), &moves, &config) {
+            if !seen.insert(puzzle.fen.clone()) {
+                continue;
+            }
+            match serde_json::to_string(&puzzle) {
+                Ok(json) => println!("{json}"),
+                Err(e) => eprintln!("{path}: {e}"),
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,205 @@
+//! Plays two engine configurations against each other from the balanced opening set, prints
+//! the Elo difference with a 95% error margin, and stops early once the SPRT decides.
+//!
+//! Usage: `cargo run --release -p cotuong_core --bin matchrunner -- [options] <A> <B>`
+//!
+//! `A` and `B` are either a JSON config saved with "Export JSON" in the AI panel or a
+//! profile name: balanced, aggressive, positional, materialist, gambit.
+//!
+//! Options:
+//!   --games N        maximum number of games (default 200)
+//!   --depth D        fixed search depth per move (default 4)
+//!   --time MS        search time per move, instead of a fixed depth
+//!   --max-plies N    adjudicate a draw after this many plies (default 300)
+//!   --elo0 E         SPRT null hypothesis (default 0)
+//!   --elo1 E         SPRT alternative hypothesis (default 10)
+//!   --pgn FILE       write every game to FILE as PGN
+//!   --trace-log FILE write the engines' search events to FILE as JSON lines; needs
+//!                    `--features tracing`
+
+use cotuong_core::engine::match_runner::{
+    load_config, parse_opening, play_game, to_pgn, MatchScore, PlayedGame, Sprt, SprtDecision,
+    OPENINGS,
+};
+use cotuong_core::engine::search::AlphaBetaEngine;
+use cotuong_core::engine::SearchLimit;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+struct Options {
+    games: usize,
+    limit: SearchLimit,
+    max_plies: usize,
+    sprt: Sprt,
+    pgn: Option<String>,
+    trace_log: Option<String>,
+    engines: Vec<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        games: 200,
+        limit: SearchLimit::Depth(4),
+        max_plies: 300,
+        sprt: Sprt::default(),
+        pgn: None,
+        trace_log: None,
+        engines: Vec::new(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "--games" => options.games = parse_num(&value("--games")?)?,
+            "--depth" => options.limit = SearchLimit::Depth(parse_num(&value("--depth")?)?),
+            "--time" => options.limit = SearchLimit::Time(parse_num(&value("--time")?)?),
+            "--max-plies" => options.max_plies = parse_num(&value("--max-plies")?)?,
+            "--elo0" => options.sprt.elo0 = parse_num(&value("--elo0")?)?,
+            "--elo1" => options.sprt.elo1 = parse_num(&value("--elo1")?)?,
+            "--pgn" => options.pgn = Some(value("--pgn")?),
+            "--trace-log" => options.trace_log = Some(value("--trace-log")?),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+            _ => options.engines.push(arg),
+        }
+    }
+    if options.engines.len() != 2 {
+        return Err("expected exactly two engines".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_num<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("invalid number: {text}"))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("usage: matchrunner [--games N] [--depth D | --time MS] [--max-plies N] [--elo0 E] [--elo1 E] [--pgn FILE] [--trace-log FILE] <A> <B>");
+            return ExitCode::FAILURE;
+        }
+    };
+    let (name_a, name_b) = match options.engines.as_slice() {
+        [a, b] => (a.as_str(), b.as_str()),
+        _ => return ExitCode::FAILURE,
+    };
+    let (config_a, config_b) = match (load_config(name_a), load_config(name_b)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(path) = &options.trace_log {
+        #[cfg(feature = "tracing")]
+        if let Err(e) = cotuong_core::engine::search_log::log_to_file(path) {
+            eprintln!("{path}: {e}");
+            return ExitCode::FAILURE;
+        }
+        #[cfg(not(feature = "tracing"))]
+        {
+            eprintln!("{path}: --trace-log needs a build with --features tracing");
+            return ExitCode::FAILURE;
+        }
+    }
+    let openings: Vec<_> = OPENINGS.iter().filter_map(|l| parse_opening(l)).collect();
+
+    let mut engine_a = AlphaBetaEngine::new(Arc::new(config_a));
+    let mut engine_b = AlphaBetaEngine::new(Arc::new(config_b));
+    let mut score = MatchScore::default();
+    let mut pgn = String::new();
+    let (lower, upper) = options.sprt.bounds();
+
+    for round in 0..options.games {
+        // Each opening is played twice in a row with colours swapped
+        let opening_index = (round / 2) % openings.len().max(1);
+        let Some(opening) = openings.get(opening_index) else {
+            break;
+        };
+        let a_is_red = round % 2 == 0;
+        // Tags every search event of this game with the game number and who plays Red
+        #[cfg(feature = "tracing")]
+        let _game = tracing::info_span!("game", number = round + 1, a_is_red).entered();
+        let (moves, result) = if a_is_red {
+            play_game(
+                &mut engine_a,
+                &mut engine_b,
+                opening,
+                options.limit,
+                options.max_plies,
+            )
+        } else {
+            play_game(
+                &mut engine_b,
+                &mut engine_a,
+                opening,
+                options.limit,
+                options.max_plies,
+            )
+        };
+        let (red, black) = if a_is_red {
+            (name_a, name_b)
+        } else {
+            (name_b, name_a)
+        };
+        pgn.push_str(&to_pgn(
+            "matchrunner",
+            round + 1,
+            red,
+            black,
+            &moves,
+            result,
+        ));
+        pgn.push('\n');
+
+        let game = PlayedGame {
+            opening: opening_index,
+            a_is_red,
+            moves,
+            result,
+        };
+        score.record(game.score_a());
+
+        let llr = options.sprt.llr(&score);
+        let elo = score.elo().map_or_else(String::new, |(elo, margin)| {
+            format!("{elo:+.1} ± {margin:.1}")
+        });
+        println!(
+            "game {:>4}: {} ({} plies)  +{} ={} -{}  elo {elo}  llr {llr:.2} [{lower:.2}, {upper:.2}]",
+            round + 1,
+            result.pgn(),
+            game.moves.len(),
+            score.wins,
+            score.draws,
+            score.losses,
+        );
+
+        match options.sprt.decide(&score) {
+            SprtDecision::AcceptH1 => {
+                println!(
+                    "SPRT: H1 accepted, {name_a} is stronger by at least {} Elo",
+                    options.sprt.elo1
+                );
+                break;
+            }
+            SprtDecision::AcceptH0 => {
+                println!(
+                    "SPRT: H0 accepted, {name_a} is not stronger by {} Elo",
+                    options.sprt.elo1
+                );
+                break;
+            }
+            SprtDecision::Continue => {}
+        }
+    }
+
+    if let Some(path) = &options.pgn {
+        if let Err(e) = std::fs::write(path, pgn) {
+            eprintln!("{path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    ExitCode::SUCCESS
+}
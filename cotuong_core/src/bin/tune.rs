@@ -0,0 +1,122 @@
+//! Tunes selected `EngineConfig` fields with SPSA self-play and writes the result as a JSON
+//! config that the AI panel's "Load JSON Config" accepts.
+//!
+//! Usage: `cargo run --release -p cotuong_core --bin tune -- [options] [BASE]`
+//!
+//! `BASE` is the starting config, a JSON file or profile name (default: balanced).
+//!
+//! Options:
+//!   --params a,b,c   fields to tune (default val_pawn,val_horse,val_cannon,val_rook,
+//!                    pruning_multiplier,probcut_margin)
+//!   --iterations N   game pairs to play (default 500)
+//!   --rate R         learning rate (default 1.0)
+//!   --depth D        fixed search depth per move (default 3)
+//!   --time MS        search time per move, instead of a fixed depth
+//!   --max-plies N    adjudicate a draw after this many plies (default 200)
+//!   --out FILE       where to write the tuned config (default tuned_config.json)
+
+use cotuong_core::engine::match_runner::{load_config, parse_opening, OPENINGS};
+use cotuong_core::engine::tuner::{SpsaConfig, SpsaTuner, TunableParam};
+use cotuong_core::engine::SearchLimit;
+use std::process::ExitCode;
+
+/// How often the current estimate is written out, so a long run can be stopped early.
+const SAVE_EVERY: usize = 25;
+
+struct Options {
+    spsa: SpsaConfig,
+    base: String,
+    out: String,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        spsa: SpsaConfig::default(),
+        base: "balanced".to_string(),
+        out: "tuned_config.json".to_string(),
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "--params" => {
+                options.spsa.params = value("--params")?
+                    .split(',')
+                    .map(|name| {
+                        TunableParam::from_name(name.trim())
+                            .ok_or_else(|| format!("unknown parameter {name}"))
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "--iterations" => options.spsa.iterations = parse_num(&value("--iterations")?)?,
+            "--rate" => options.spsa.learning_rate = parse_num(&value("--rate")?)?,
+            "--depth" => options.spsa.limit = SearchLimit::Depth(parse_num(&value("--depth")?)?),
+            "--time" => options.spsa.limit = SearchLimit::Time(parse_num(&value("--time")?)?),
+            "--max-plies" => options.spsa.max_plies = parse_num(&value("--max-plies")?)?,
+            "--out" => options.out = value("--out")?,
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}")),
+            _ => options.base = arg,
+        }
+    }
+    if options.spsa.params.is_empty() {
+        return Err("no parameters to tune".to_string());
+    }
+    Ok(options)
+}
+
+fn parse_num<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("invalid number: {text}"))
+}
+
+fn save(tuner: &SpsaTuner, path: &str) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(&tuner.current()).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| format!("{path}: {e}"))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("usage: tune [--params a,b] [--iterations N] [--rate R] [--depth D | --time MS] [--max-plies N] [--out FILE] [BASE]");
+            return ExitCode::FAILURE;
+        }
+    };
+    let base = match load_config(&options.base) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let openings: Vec<_> = OPENINGS.iter().filter_map(|l| parse_opening(l)).collect();
+    let mut tuner = SpsaTuner::new(base, options.spsa);
+
+    while !tuner.is_done() {
+        let Some(opening) = openings.get(tuner.iteration() % openings.len().max(1)) else {
+            break;
+        };
+        let result = tuner.step(opening);
+        let values: Vec<String> = tuner
+            .values()
+            .map(|(param, value)| format!("{}={value:.2}", param.name()))
+            .collect();
+        println!(
+            "iter {:>4}: {result:+.1}  {}",
+            tuner.iteration(),
+            values.join(" ")
+        );
+        if tuner.iteration().is_multiple_of(SAVE_EVERY) {
+            if let Err(e) = save(&tuner, &options.out) {
+                eprintln!("{e}");
+            }
+        }
+    }
+
+    if let Err(e) = save(&tuner, &options.out) {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+    println!("wrote {}", options.out);
+    ExitCode::SUCCESS
+}
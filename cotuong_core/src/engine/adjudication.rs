@@ -0,0 +1,142 @@
+use crate::engine::config::EngineConfig;
+use crate::logic::board::Color;
+
+/// A game ended by the engines themselves rather than on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adjudication {
+    /// The side to move gives up.
+    Resign,
+    /// The side to move offered a draw and the opponent took it.
+    DrawAgreed,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct ScoreRun {
+    last: Option<i32>,
+    /// Consecutive moves at or below `-resign_score`.
+    losing: u32,
+    /// Consecutive moves within `draw_score` of level.
+    level: u32,
+}
+
+/// Follows both engines' scores through one game and applies their `resign_*` and `draw_*`
+/// settings. Scores are from the mover's side, as returned with each searched move.
+#[derive(Debug, Clone, Default)]
+pub struct Adjudicator {
+    sides: [ScoreRun; 2],
+}
+
+impl Adjudicator {
+    /// Records the score of the move `mover` is about to play and says whether it resigns,
+    /// or offers a draw the opponent accepts, instead of playing it.
+    pub fn record(
+        &mut self,
+        mover: Color,
+        score: i32,
+        mover_config: &EngineConfig,
+        opponent_config: &EngineConfig,
+    ) -> Option<Adjudication> {
+        let opponent = self.sides[mover.opposite().index()];
+        let side = &mut self.sides[mover.index()];
+        side.last = Some(score);
+        side.losing = if score <= -mover_config.resign_score {
+            side.losing + 1
+        } else {
+            0
+        };
+        side.level = if score.abs() <= mover_config.draw_score {
+            side.level + 1
+        } else {
+            0
+        };
+
+        if mover_config.resign_moves > 0 && side.losing >= mover_config.resign_moves {
+            return Some(Adjudication::Resign);
+        }
+        let offers = mover_config.draw_moves > 0 && side.level >= mover_config.draw_moves;
+        if offers
+            && opponent
+                .last
+                .is_some_and(|s| accepts_draw(opponent_config, s))
+        {
+            return Some(Adjudication::DrawAgreed);
+        }
+        None
+    }
+}
+
+/// Whether an engine whose latest score is `score` takes a draw offer: it does unless it is
+/// ahead by more than its `draw_score`.
+#[must_use]
+pub const fn accepts_draw(config: &EngineConfig, score: i32) -> bool {
+    score <= config.draw_score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resigns_after_losing_streak() {
+        let config = EngineConfig {
+            resign_score: 1000,
+            resign_moves: 3,
+            ..Default::default()
+        };
+        let mut adjudicator = Adjudicator::default();
+        assert_eq!(
+            adjudicator.record(Color::Red, -1500, &config, &config),
+            None
+        );
+        assert_eq!(
+            adjudicator.record(Color::Red, -1500, &config, &config),
+            None
+        );
+        // One recovery resets the run
+        assert_eq!(adjudicator.record(Color::Red, -200, &config, &config), None);
+        for _ in 0..2 {
+            assert_eq!(
+                adjudicator.record(Color::Red, -1200, &config, &config),
+                None
+            );
+        }
+        assert_eq!(
+            adjudicator.record(Color::Red, -1200, &config, &config),
+            Some(Adjudication::Resign)
+        );
+
+        let stubborn = EngineConfig {
+            resign_moves: 0,
+            ..config
+        };
+        let mut adjudicator = Adjudicator::default();
+        for _ in 0..10 {
+            assert_eq!(
+                adjudicator.record(Color::Black, -5000, &stubborn, &config),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn test_draw_needs_offer_and_acceptance() {
+        let config = EngineConfig {
+            draw_score: 20,
+            draw_moves: 2,
+            ..Default::default()
+        };
+        let mut adjudicator = Adjudicator::default();
+        assert_eq!(adjudicator.record(Color::Red, 5, &config, &config), None);
+        // Black is clearly better, so it turns Red's offer down
+        assert_eq!(
+            adjudicator.record(Color::Black, 300, &config, &config),
+            None
+        );
+        assert_eq!(adjudicator.record(Color::Red, -10, &config, &config), None);
+        assert_eq!(adjudicator.record(Color::Black, 10, &config, &config), None);
+        assert_eq!(
+            adjudicator.record(Color::Red, 0, &config, &config),
+            Some(Adjudication::DrawAgreed)
+        );
+    }
+}
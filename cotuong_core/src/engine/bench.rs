@@ -0,0 +1,141 @@
+use crate::engine::config::EngineConfig;
+use crate::engine::match_runner::format_iccs;
+use crate::engine::search::AlphaBetaEngine;
+use crate::engine::{SearchLimit, Searcher};
+use crate::logic::board::{Board, BoardCoordinate};
+use crate::logic::game::GameState;
+use std::fmt;
+use std::sync::Arc;
+
+/// Fixed positions searched by `bench`, from the opening through to bare endgames. Keep
+/// this list stable: node counts are only comparable across commits on the same suite.
+pub const BENCH_POSITIONS: &[&str] = &[
+    "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w",
+    "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C2C4/9/RNBAKABNR b",
+    "rn1aka3/8r/c3b1n1b/p1p1p1p1p/6c2/7C1/P1P1P1P1P/4C1N2/R7R/1NBAKAB2 w",
+    "3akab2/r6r1/2c1b1n2/p1p1n3p/1R4p2/9/P1P1P1P1P/2N1C1N2/4K3R/2BA1AB2 w",
+    "3akab2/r3nr3/4b1n2/p1P1p3p/6pc1/3N4P/P3P1P2/2C1B1N2/3R3R1/2BAKA3 w",
+    "3a1aC2/4k1r2/2n1b4/p2cp3p/1r3n1R1/2p2N3/P3P3P/2N1B4/9/R2AKAB2 w",
+    "2bakab2/9/2c6/p7p/6p2/P3p4/1n2P1P2/3CB4/6R2/3AKA3 w",
+    "3a1k1n1/1C7/3ab4/2P5p/4C4/p2R5/3Nc3P/9/4K4/1r7 w",
+    "4ka3/9/4b4/2P5R/9/8P/5rp2/1r7/4A4/3AK4 w",
+    "3ak4/4a4/9/9/9/9/9/9/4A4/R3K4 w",
+];
+
+#[derive(Debug, Clone)]
+pub struct BenchEntry {
+    pub fen: &'static str,
+    /// Best move in ICCS notation, empty if the search found none.
+    pub best_move: String,
+    pub nodes: u32,
+    pub time_ms: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BenchReport {
+    pub entries: Vec<BenchEntry>,
+}
+
+impl BenchReport {
+    #[must_use]
+    pub fn total_nodes(&self) -> u64 {
+        self.entries.iter().map(|e| u64::from(e.nodes)).sum()
+    }
+
+    #[must_use]
+    pub fn total_ms(&self) -> u64 {
+        self.entries.iter().map(|e| e.time_ms).sum()
+    }
+
+    /// Nodes per second over the whole suite.
+    #[must_use]
+    pub fn nps(&self) -> u64 {
+        self.total_nodes() * 1000 / self.total_ms().max(1)
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>2} {:<5} {:>10} nodes {:>7} ms  {}",
+                i + 1,
+                entry.best_move,
+                entry.nodes,
+                entry.time_ms,
+                entry.fen
+            )?;
+        }
+        writeln!(f, "===========================")?;
+        writeln!(f, "Total time (ms) : {}", self.total_ms())?;
+        writeln!(f, "Nodes searched  : {}", self.total_nodes())?;
+        write!(f, "Nodes/second    : {}", self.nps())
+    }
+}
+
+/// Searches every bench position to `depth` with a fresh engine, so results do not depend
+/// on what an earlier position left in the transposition table.
+pub fn run_bench(depth: u8, config: &EngineConfig) -> Result<BenchReport, String> {
+    run_bench_with_progress(depth, config, |_, _| {})
+}
+
+/// `run_bench`, reporting the positions searched so far out of the total after each one.
+/// Times come from the search itself, so this also runs inside a browser worker.
+pub fn run_bench_with_progress(
+    depth: u8,
+    config: &EngineConfig,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<BenchReport, String> {
+    let config = Arc::new(config.clone());
+    let mut report = BenchReport::default();
+    for (i, &fen) in BENCH_POSITIONS.iter().enumerate() {
+        let (board, turn) = Board::from_fen(fen)?;
+        let state = GameState::from_position(board, turn);
+        let mut engine = AlphaBetaEngine::new(config.clone());
+        let result = engine.search(&state, SearchLimit::Depth(depth), &[]);
+
+        let (best_move, nodes, time_ms) = result.map_or_else(
+            || (String::new(), 0, 0),
+            |(mv, stats)| {
+                let squares = BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)
+                    .zip(BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize));
+                let iccs = squares.map_or_else(String::new, |(from, to)| format_iccs(from, to));
+                (iccs, stats.nodes, stats.time_ms)
+            },
+        );
+        report.entries.push(BenchEntry {
+            fen,
+            best_move,
+            nodes,
+            time_ms,
+        });
+        on_progress(i + 1, BENCH_POSITIONS.len());
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_positions_parse() {
+        for fen in BENCH_POSITIONS {
+            assert!(Board::from_fen(fen).is_ok(), "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_bench_is_deterministic() {
+        let config = EngineConfig {
+            tt_size_mb: 1,
+            ..Default::default()
+        };
+        let first = run_bench(2, &config).unwrap();
+        let second = run_bench(2, &config).unwrap();
+        assert_eq!(first.entries.len(), BENCH_POSITIONS.len());
+        assert_eq!(first.total_nodes(), second.total_nodes());
+        assert!(first.entries.iter().all(|e| !e.best_move.is_empty()));
+    }
+}
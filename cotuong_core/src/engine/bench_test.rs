@@ -13,11 +13,7 @@ mod tests {
         let config = Arc::new(EngineConfig::default());
         let mut engine = AlphaBetaEngine::new(config);
         let board = Board::new();
-        let game_state = GameState {
-            board,
-            turn: Color::Red,
-            ..Default::default()
-        };
+        let game_state = GameState::from_position(board, Color::Red);
 
         // Warmup
         engine.search(&game_state, SearchLimit::Depth(2), &[]);
@@ -83,11 +79,7 @@ mod tests {
         board.zobrist_hash = board.calculate_initial_hash();
         board.calculate_initial_score();
 
-        let game_state = GameState {
-            board,
-            turn: Color::Red,
-            ..Default::default()
-        };
+        let game_state = GameState::from_position(board, Color::Red);
 
         // Warmup
         engine.search(&game_state, SearchLimit::Depth(2), &[]);
@@ -109,17 +101,15 @@ mod tests {
     #[test]
     fn bench_dynamic_limiting() {
         println!("--- Benchmarking Dynamic Limiting (Method 0) ---");
-        let mut config = EngineConfig::default();
-        config.pruning_method = 0; // Dynamic Limiting
+        let config = EngineConfig {
+            pruning_method: 0, // Dynamic Limiting
+            ..Default::default()
+        };
         let config = Arc::new(config);
         let mut engine = AlphaBetaEngine::new(config);
 
         let board = Board::new();
-        let game_state = GameState {
-            board,
-            turn: Color::Red,
-            ..Default::default()
-        };
+        let game_state = GameState::from_position(board, Color::Red);
 
         // Warmup
         engine.search(&game_state, SearchLimit::Depth(2), &[]);
@@ -141,17 +131,15 @@ mod tests {
     #[test]
     fn bench_aggressive() {
         println!("--- Benchmarking Aggressive Pruning (Method 2) ---");
-        let mut config = EngineConfig::default();
-        config.pruning_method = 2; // Both (Aggressive)
+        let config = EngineConfig {
+            pruning_method: 2, // Both (Aggressive)
+            ..Default::default()
+        };
         let config = Arc::new(config);
         let mut engine = AlphaBetaEngine::new(config);
 
         let board = Board::new();
-        let game_state = GameState {
-            board,
-            turn: Color::Red,
-            ..Default::default()
-        };
+        let game_state = GameState::from_position(board, Color::Red);
 
         // Warmup
         engine.search(&game_state, SearchLimit::Depth(2), &[]);
@@ -173,17 +161,15 @@ mod tests {
     #[test]
     fn bench_lmr() {
         println!("--- Benchmarking LMR (Method 1) ---");
-        let mut config = EngineConfig::default();
-        config.pruning_method = 1; // LMR
+        let config = EngineConfig {
+            pruning_method: 1, // LMR
+            ..Default::default()
+        };
         let config = Arc::new(config);
         let mut engine = AlphaBetaEngine::new(config);
 
         let board = Board::new();
-        let game_state = GameState {
-            board,
-            turn: Color::Red,
-            ..Default::default()
-        };
+        let game_state = GameState::from_position(board, Color::Red);
 
         // Warmup
         engine.search(&game_state, SearchLimit::Depth(2), &[]);
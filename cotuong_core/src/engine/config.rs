@@ -18,6 +18,10 @@ pub struct EngineConfig {
     // Penalties
     pub hanging_piece_penalty: i32,
     pub king_exposed_cannon_penalty: i32,
+    /// Percent applied to all king danger terms, 100 is neutral.
+    pub king_safety_weight: i32,
+    /// Points a draw is worth to the side that started the search; positive avoids draws.
+    pub contempt: i32,
 
     // Search Parameters
     pub score_hash_move: i32,
@@ -37,11 +41,162 @@ pub struct EngineConfig {
     pub singular_extension_min_depth: u8,
     pub singular_extension_margin: i32,
 
+    // Quiescence: also try quiet checking moves at the first quiescence ply
+    pub qsearch_checks: bool,
+
     // Checkmate scoring
     pub mate_score: i32, // Base score for checkmate (higher = stronger preference)
 
     // Transposition Table
     pub tt_size_mb: usize,
+
+    // Adjudication, applied by `engine::adjudication` in engine-vs-engine games
+    /// Resign after `resign_moves` moves in a row scored at or below `-resign_score`; 0
+    /// moves never resigns.
+    pub resign_score: i32,
+    pub resign_moves: u32,
+    /// Offer a draw after `draw_moves` moves in a row within `draw_score` of level, and accept
+    /// one unless ahead by more than `draw_score`; 0 moves never offers.
+    pub draw_score: i32,
+    pub draw_moves: u32,
+
+    // Deliberate weakening, `None` for full strength
+    pub strength_limit: Option<StrengthLimit>,
+
+    // Reproducible searches for tests and bug reports
+    /// Search to a fixed depth even when given time, start every search from empty tables
+    /// and draw the weakened levels' random choices from `seed`.
+    pub deterministic: bool,
+    pub seed: u64,
+}
+
+/// Caps applied on top of the search limit so the easy levels make human-like mistakes
+/// instead of playing perfectly for a shorter time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrengthLimit {
+    pub max_depth: u8,
+    /// Nodes allowed after the first depth, which is always completed.
+    pub max_nodes: u32,
+    /// Root moves scoring within this many points of the best one are picked at random.
+    pub move_margin: i32,
+    /// Each root move's score is shifted by a random amount up to this size either way.
+    pub eval_noise: i32,
+}
+
+impl StrengthLimit {
+    /// Misses most tactics deeper than one move and often drifts into weaker moves.
+    pub const BEGINNER: Self = Self {
+        max_depth: 2,
+        max_nodes: 5_000,
+        move_margin: 120,
+        eval_noise: 80,
+    };
+    /// Sees short tactics but still plays loosely.
+    pub const CASUAL: Self = Self {
+        max_depth: 3,
+        max_nodes: 40_000,
+        move_margin: 50,
+        eval_noise: 30,
+    };
+
+    /// Sliding scale from `BEGINNER` (0.0) to a clean depth-6 search (1.0).
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn for_skill(skill: f64) -> Self {
+        let s = skill.clamp(0.0, 1.0);
+        let lerp = |from: f64, to: f64| s.mul_add(to - from, from).round();
+        Self {
+            max_depth: lerp(f64::from(Self::BEGINNER.max_depth), 6.0) as u8,
+            max_nodes: lerp(f64::from(Self::BEGINNER.max_nodes), 400_000.0) as u32,
+            move_margin: lerp(f64::from(Self::BEGINNER.move_margin), 0.0) as i32,
+            eval_noise: lerp(f64::from(Self::BEGINNER.eval_noise), 0.0) as i32,
+        }
+    }
+}
+
+/// Bundled playing styles, each a full `EngineConfig` built on the defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EngineProfile {
+    #[default]
+    Balanced,
+    /// Chases the enemy king and avoids draws.
+    Aggressive,
+    /// Values structure and king cover over quick captures.
+    Positional,
+    /// Grabs material and is happy to trade down into a draw.
+    Materialist,
+    /// Gives up pawns for activity and plays on at almost any cost.
+    Gambit,
+}
+
+impl EngineProfile {
+    pub const ALL: [Self; 5] = [
+        Self::Balanced,
+        Self::Aggressive,
+        Self::Positional,
+        Self::Materialist,
+        Self::Gambit,
+    ];
+
+    #[must_use]
+    pub fn config(self) -> EngineConfig {
+        let base = EngineConfig::default();
+        match self {
+            Self::Balanced => base,
+            Self::Aggressive => EngineConfig {
+                king_safety_weight: 150,
+                king_exposed_cannon_penalty: 30,
+                contempt: 40,
+                qsearch_checks: true,
+                draw_moves: 0,
+                ..base
+            },
+            Self::Positional => EngineConfig {
+                val_pawn: base.val_pawn * 6 / 5,
+                king_safety_weight: 130,
+                hanging_piece_penalty: 20,
+                score_capture_base: 120_000,
+                ..base
+            },
+            Self::Materialist => EngineConfig {
+                val_horse: base.val_horse * 11 / 10,
+                val_cannon: base.val_cannon * 11 / 10,
+                val_rook: base.val_rook * 11 / 10,
+                hanging_piece_penalty: 30,
+                king_safety_weight: 70,
+                contempt: -20,
+                draw_score: 30,
+                draw_moves: 20,
+                ..base
+            },
+            Self::Gambit => EngineConfig {
+                val_pawn: base.val_pawn * 7 / 10,
+                king_safety_weight: 170,
+                king_exposed_cannon_penalty: 35,
+                hanging_piece_penalty: 5,
+                contempt: 80,
+                qsearch_checks: true,
+                resign_moves: 0,
+                draw_moves: 0,
+                ..base
+            },
+        }
+    }
+}
+
+/// Smallest and largest transposition table `tt_size_for_memory` hands out, in MB. The
+/// upper bound is the default size, which a browser worker can always allocate.
+pub const TT_SIZE_MIN_MB: usize = 16;
+pub const TT_SIZE_MAX_MB: usize = 256;
+
+/// A transposition table size for a device with `memory_mb` of RAM: a sixteenth of it,
+/// rounded down to a power of two (the table's own granularity) and clamped to
+/// `TT_SIZE_MIN_MB..=TT_SIZE_MAX_MB`, leaving room for a second engine and the page.
+#[must_use]
+pub fn tt_size_for_memory(memory_mb: usize) -> usize {
+    let share = memory_mb / 16;
+    let size = if share == 0 { 0 } else { 1 << share.ilog2() };
+    size.clamp(TT_SIZE_MIN_MB, TT_SIZE_MAX_MB)
 }
 
 impl Default for EngineConfig {
@@ -57,6 +212,8 @@ impl Default for EngineConfig {
 
             hanging_piece_penalty: 10,
             king_exposed_cannon_penalty: 20,
+            king_safety_weight: 100,
+            contempt: 0,
 
             score_hash_move: 200_000,
             score_capture_base: 200_000, // Aggressive capturing
@@ -73,9 +230,21 @@ impl Default for EngineConfig {
             singular_extension_min_depth: 8,
             singular_extension_margin: 20,
 
+            qsearch_checks: false,
+
             mate_score: 300_000, // Increased to be higher than score_capture_base (200,000)
 
             tt_size_mb: 256,
+
+            resign_score: 1200, // Two chariots down
+            resign_moves: 4,
+            draw_score: 15,
+            draw_moves: 40,
+
+            strength_limit: None,
+
+            deterministic: false,
+            seed: 0,
         }
     }
 }
@@ -93,6 +262,8 @@ struct EngineConfigJson {
 
     hanging_piece_penalty: Option<i32>,
     king_exposed_cannon_penalty: Option<i32>,
+    king_safety_weight: Option<i32>,
+    contempt: Option<i32>,
 
     score_hash_move: Option<f32>,
     score_capture_base: Option<f32>,
@@ -109,9 +280,21 @@ struct EngineConfigJson {
     singular_extension_min_depth: Option<u8>,
     singular_extension_margin: Option<i32>,
 
+    qsearch_checks: Option<bool>,
+
     mate_score: Option<i32>,
 
     tt_size_mb: Option<usize>,
+
+    resign_score: Option<i32>,
+    resign_moves: Option<u32>,
+    draw_score: Option<i32>,
+    draw_moves: Option<u32>,
+
+    strength_limit: Option<StrengthLimit>,
+
+    deterministic: Option<bool>,
+    seed: Option<u64>,
 }
 
 impl EngineConfig {
@@ -135,6 +318,10 @@ impl EngineConfig {
             king_exposed_cannon_penalty: json_config
                 .king_exposed_cannon_penalty
                 .unwrap_or(default.king_exposed_cannon_penalty),
+            king_safety_weight: json_config
+                .king_safety_weight
+                .unwrap_or(default.king_safety_weight),
+            contempt: json_config.contempt.unwrap_or(default.contempt),
 
             score_hash_move: apply_scale(default.score_hash_move, json_config.score_hash_move),
             score_capture_base: apply_scale(
@@ -168,9 +355,21 @@ impl EngineConfig {
                 .singular_extension_margin
                 .unwrap_or(default.singular_extension_margin),
 
+            qsearch_checks: json_config.qsearch_checks.unwrap_or(default.qsearch_checks),
+
             mate_score: json_config.mate_score.unwrap_or(default.mate_score),
 
             tt_size_mb: json_config.tt_size_mb.unwrap_or(default.tt_size_mb),
+
+            resign_score: json_config.resign_score.unwrap_or(default.resign_score),
+            resign_moves: json_config.resign_moves.unwrap_or(default.resign_moves),
+            draw_score: json_config.draw_score.unwrap_or(default.draw_score),
+            draw_moves: json_config.draw_moves.unwrap_or(default.draw_moves),
+
+            strength_limit: json_config.strength_limit,
+
+            deterministic: json_config.deterministic.unwrap_or(default.deterministic),
+            seed: json_config.seed.unwrap_or(default.seed),
         })
     }
 }
@@ -185,6 +384,40 @@ fn apply_scale(default_val: i32, scale: Option<f32>) -> i32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_profiles_differ_from_default() {
+        assert_eq!(EngineProfile::Balanced.config().contempt, 0);
+        for profile in EngineProfile::ALL.into_iter().skip(1) {
+            let config = profile.config();
+            assert_ne!(config.king_safety_weight, 100, "{profile:?}");
+            assert!(config.strength_limit.is_none());
+        }
+        assert!(EngineProfile::Gambit.config().contempt > 0);
+        assert!(EngineProfile::Materialist.config().contempt < 0);
+    }
+
+    #[test]
+    fn test_tt_size_for_memory() {
+        assert_eq!(tt_size_for_memory(0), TT_SIZE_MIN_MB);
+        assert_eq!(tt_size_for_memory(512), 32);
+        assert_eq!(tt_size_for_memory(1024), 64);
+        // Not a power of two: rounded down
+        assert_eq!(tt_size_for_memory(3 * 1024), 128);
+        assert_eq!(tt_size_for_memory(4 * 1024), 256);
+        assert_eq!(tt_size_for_memory(64 * 1024), TT_SIZE_MAX_MB);
+    }
+
+    #[test]
+    fn test_strength_for_skill() {
+        assert_eq!(StrengthLimit::for_skill(0.0), StrengthLimit::BEGINNER);
+        assert_eq!(StrengthLimit::for_skill(-1.0), StrengthLimit::BEGINNER);
+        let top = StrengthLimit::for_skill(1.0);
+        assert_eq!((top.max_depth, top.move_margin, top.eval_noise), (6, 0, 0));
+        let mid = StrengthLimit::for_skill(0.5);
+        assert_eq!(mid.max_depth, 4);
+        assert!(mid.move_margin < StrengthLimit::BEGINNER.move_margin);
+    }
+
     #[test]
     fn test_load_config_default() {
         let json = "{}";
@@ -12,13 +12,45 @@ impl SimpleEvaluator {
     pub const fn new(config: Arc<EngineConfig>) -> Self {
         Self { config }
     }
+
+    /// Score of a drawn position for the side to move at `ply`. The side that started the
+    /// search moves on even plies and sees the draw as `-contempt`.
+    #[must_use]
+    pub fn draw_score(&self, ply: u8) -> i32 {
+        if ply.is_multiple_of(2) {
+            -self.config.contempt
+        } else {
+            self.config.contempt
+        }
+    }
+}
+
+/// Slow path behind the `verify-eval` feature: recomputes material and PST from every square
+/// and logs when the incremental values have drifted.
+#[cfg(feature = "verify-eval")]
+fn verify_incremental(board: &Board) {
+    let incremental = (
+        board.red_material,
+        board.black_material,
+        board.red_pst,
+        board.black_pst,
+    );
+    let scratch = board.score_from_scratch();
+    if incremental != scratch {
+        log::error!(
+            "incremental eval drifted: {incremental:?} != {scratch:?} for {}",
+            board.to_fen_string(crate::logic::board::Color::Red)
+        );
+    }
 }
 
 impl Evaluator for SimpleEvaluator {
     fn evaluate(&self, board: &Board) -> i32 {
         use crate::logic::eval_constants::*;
 
-        // 1. Material & PST (Base)
+        // 1. Material & PST (Base), kept incrementally by the board
+        #[cfg(feature = "verify-eval")]
+        verify_incremental(board);
         let red_material = board.red_material + board.red_pst;
         let black_material = board.black_material + board.black_pst;
 
@@ -275,7 +307,7 @@ impl Evaluator for SimpleEvaluator {
             }
         }
 
-        score -= red_danger - black_danger; // Danger is bad
+        score -= (red_danger - black_danger) * self.config.king_safety_weight / 100; // Danger is bad
 
         score
     }
@@ -288,8 +320,10 @@ mod tests {
 
     #[test]
     fn test_king_exposed_penalty() {
-        let mut config = EngineConfig::default();
-        config.king_exposed_cannon_penalty = 100; // Use a large penalty for clarity
+        let config = EngineConfig {
+            king_exposed_cannon_penalty: 100, // Use a large penalty for clarity
+            ..Default::default()
+        };
         let evaluator = SimpleEvaluator::new(Arc::new(config));
 
         // 1. Setup Red King exposed to Black Cannon (1 piece between) - Check
@@ -419,4 +453,32 @@ mod tests {
             "Penalty should NOT be applied for 2 pieces"
         );
     }
+
+    #[test]
+    fn test_king_safety_weight_and_contempt() {
+        let mut board = Board::new();
+        board.clear();
+        let place = |board: &mut Board, r, c, piece, color| {
+            board.add_piece(BoardCoordinate::new(r, c).unwrap(), piece, color);
+        };
+        place(&mut board, 0, 4, PieceType::General, Color::Red);
+        place(&mut board, 9, 3, PieceType::General, Color::Black);
+        place(&mut board, 5, 4, PieceType::Cannon, Color::Black);
+
+        let eval_with = |weight: i32| {
+            let config = EngineConfig {
+                king_safety_weight: weight,
+                contempt: 30,
+                ..Default::default()
+            };
+            let evaluator = SimpleEvaluator::new(Arc::new(config));
+            assert_eq!(evaluator.draw_score(0), -30);
+            assert_eq!(evaluator.draw_score(1), 30);
+            evaluator.evaluate(&board)
+        };
+
+        // Red's exposed king costs more when king safety is weighted up
+        assert!(eval_with(200) < eval_with(100));
+        assert!(eval_with(100) < eval_with(0));
+    }
 }
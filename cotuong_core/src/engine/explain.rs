@@ -0,0 +1,207 @@
+use crate::engine::search::AlphaBetaEngine;
+use crate::engine::Move;
+use crate::logic::board::{Board, BoardCoordinate, Color, Piece, PieceType};
+use crate::logic::game::GameState;
+use crate::logic::rules::{gives_check, is_valid_move};
+use serde::{Deserialize, Serialize};
+
+/// Plies of the expected continuation kept in an explanation, the move itself included.
+pub const PV_PREVIEW_LEN: usize = 5;
+
+/// Why the engine picked a move, in terms a player can check on the board.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveExplanation {
+    /// Search score of the move, from the mover's side.
+    pub score: i32,
+    pub captured: Option<PieceType>,
+    /// Material the mover is up (or down, when negative) at the end of the PV preview,
+    /// compared with before the move.
+    pub material_swing: i32,
+    pub gives_check: bool,
+    /// Enemy pieces the moved piece attacks from its new square but did not attack before.
+    pub threats: Vec<PieceType>,
+    /// Own pieces the moved piece protects from its new square.
+    pub defends: Vec<PieceType>,
+    /// The expected continuation, starting with the move itself.
+    pub pv: Vec<Move>,
+}
+
+fn coordinates(mv: &Move) -> Option<(BoardCoordinate, BoardCoordinate)> {
+    Some((
+        BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)?,
+        BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize)?,
+    ))
+}
+
+/// Red material minus black material, from `color`'s side.
+fn material_balance(board: &Board, color: Color) -> i32 {
+    let (red, black, _, _) = board.score_from_scratch();
+    if color == Color::Red {
+        red - black
+    } else {
+        black - red
+    }
+}
+
+/// Pieces of `owner` (generals left out) that the piece on `from` could take if they were
+/// the opponent's: its targets when `owner` is the opponent, what it protects otherwise.
+fn covered(
+    board: &Board,
+    from: BoardCoordinate,
+    mover: Color,
+    owner: Color,
+) -> Vec<BoardCoordinate> {
+    let mut squares = Vec::new();
+    for row in 0..10 {
+        for col in 0..9 {
+            let Some(pos) = BoardCoordinate::new(row, col) else {
+                continue;
+            };
+            let Some(piece) = board.get_piece(pos) else {
+                continue;
+            };
+            if pos == from || piece.color != owner || piece.piece_type == PieceType::General {
+                continue;
+            }
+            let attackable = if owner == mover {
+                let mut flipped = board.clone();
+                flipped.set_piece(
+                    pos,
+                    Some(Piece {
+                        piece_type: piece.piece_type,
+                        color: mover.opposite(),
+                    }),
+                );
+                is_valid_move(&flipped, from, pos, mover).is_ok()
+            } else {
+                is_valid_move(board, from, pos, mover).is_ok()
+            };
+            if attackable {
+                squares.push(pos);
+            }
+        }
+    }
+    squares
+}
+
+/// Explains `mv`, the engine's choice in `game_state`, right after the search that found it,
+/// while the hash table still holds the expected continuation.
+pub fn explain_move(
+    engine: &mut AlphaBetaEngine,
+    game_state: &GameState,
+    mv: Move,
+) -> MoveExplanation {
+    let mover = game_state.turn;
+    let board = game_state.board.clone();
+    let pv = engine.principal_variation(game_state, mv, PV_PREVIEW_LEN);
+
+    let Some((from, to)) = coordinates(&mv) else {
+        return MoveExplanation {
+            score: mv.score,
+            captured: None,
+            material_swing: 0,
+            gives_check: false,
+            threats: Vec::new(),
+            defends: Vec::new(),
+            pv,
+        };
+    };
+
+    let captured = board.get_piece(to).map(|p| p.piece_type);
+    let check = gives_check(&board, &mv, mover);
+    let attacked_before = covered(&board, from, mover, mover.opposite());
+
+    let mut after = board.clone();
+    after.apply_move(&mv, mover);
+    let threats = covered(&after, to, mover, mover.opposite())
+        .into_iter()
+        .filter(|pos| !attacked_before.contains(pos))
+        .filter_map(|pos| after.get_piece(pos).map(|p| p.piece_type))
+        .collect();
+    let defends = covered(&after, to, mover, mover)
+        .into_iter()
+        .filter_map(|pos| after.get_piece(pos).map(|p| p.piece_type))
+        .collect();
+
+    let mut line_end = board.clone();
+    let mut turn = mover;
+    for step in &pv {
+        line_end.apply_move(step, turn);
+        turn = turn.opposite();
+    }
+
+    MoveExplanation {
+        score: mv.score,
+        captured,
+        material_swing: material_balance(&line_end, mover) - material_balance(&board, mover),
+        gives_check: check,
+        threats,
+        defends,
+        pv,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::EngineConfig;
+    use crate::engine::{SearchLimit, Searcher};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_explains_capture() {
+        let mut game = GameState::new();
+        game.board.clear();
+        game.board.add_piece(
+            BoardCoordinate::new(0, 4).unwrap(),
+            PieceType::General,
+            Color::Red,
+        );
+        game.board.add_piece(
+            BoardCoordinate::new(9, 3).unwrap(),
+            PieceType::General,
+            Color::Black,
+        );
+        game.board.add_piece(
+            BoardCoordinate::new(0, 0).unwrap(),
+            PieceType::Chariot,
+            Color::Red,
+        );
+        game.board.add_piece(
+            BoardCoordinate::new(5, 0).unwrap(),
+            PieceType::Horse,
+            Color::Black,
+        );
+        game.board.add_piece(
+            BoardCoordinate::new(5, 3).unwrap(),
+            PieceType::Soldier,
+            Color::Black,
+        );
+        game.board.calculate_initial_score();
+        game.board.zobrist_hash = game.board.calculate_initial_hash();
+
+        let mut engine = AlphaBetaEngine::new(Arc::new(EngineConfig::default()));
+        let capture = Move {
+            from_row: 0,
+            from_col: 0,
+            to_row: 5,
+            to_col: 0,
+            score: 250,
+        };
+        let explanation = explain_move(&mut engine, &game, capture);
+        assert_eq!(explanation.captured, Some(PieceType::Horse));
+        assert!(!explanation.gives_check);
+        assert_eq!(explanation.threats, vec![PieceType::Soldier]);
+        assert!(explanation.material_swing > 0);
+        assert_eq!(explanation.pv.first(), Some(&capture));
+
+        // After a real search the preview follows the hash table
+        let (best, _) = engine
+            .search(&game, SearchLimit::Depth(4), &[])
+            .expect("a legal move");
+        let explanation = explain_move(&mut engine, &game, best);
+        assert_eq!(explanation.pv.first(), Some(&best));
+        assert!(explanation.pv.len() > 1);
+        assert!(explanation.pv.len() <= PV_PREVIEW_LEN);
+    }
+}
@@ -0,0 +1,502 @@
+use crate::engine::adjudication::{Adjudication, Adjudicator};
+use crate::engine::config::{EngineConfig, EngineProfile};
+use crate::engine::search::AlphaBetaEngine;
+use crate::engine::{Move, SearchLimit, Searcher};
+use crate::logic::board::{BoardCoordinate, Color};
+use crate::logic::game::{GameState, GameStatus};
+use crate::logic::puzzle::parse_iccs;
+use crate::session::SessionResult;
+use std::fmt::Write;
+
+/// Short openings, in ICCS, that leave both sides roughly level. Each one is played twice
+/// with the engines swapping colours, so a lopsided line cancels out.
+pub const OPENINGS: &[&str] = &[
+    "h2e2 h9g7",
+    "h2e2 b9c7",
+    "h2e2 h7e7",
+    "b2e2 h9g7",
+    "c3c4 g6g5",
+    "c3c4 b9c7",
+    "g3g4 c6c5",
+    "c0e2 h9g7",
+    "g0e2 c6c5",
+    "b0c2 h9g7",
+    "h0g2 b9c7",
+    "b2d2 h9g7",
+    "h2f2 b9c7",
+    "h2e2 h9g7 h0g2 i9h9",
+    "c3c4 h9g7 b0c2 b9a7",
+    "b0c2 c6c5 c3c4 h9g7",
+];
+
+/// Resolves a profile name (case-insensitive) or the path of a config exported from the
+/// client's "Export JSON" button.
+pub fn load_config(spec: &str) -> Result<EngineConfig, String> {
+    if let Some(profile) = EngineProfile::ALL
+        .into_iter()
+        .find(|p| format!("{p:?}").eq_ignore_ascii_case(spec))
+    {
+        return Ok(profile.config());
+    }
+    let text = std::fs::read_to_string(spec).map_err(|e| format!("{spec}: {e}"))?;
+    serde_json::from_str(&text).map_err(|e| format!("{spec}: {e}"))
+}
+
+/// Parses an opening line; `None` if any move is malformed.
+#[must_use]
+pub fn parse_opening(line: &str) -> Option<Vec<(BoardCoordinate, BoardCoordinate)>> {
+    line.split_whitespace().map(parse_iccs).collect()
+}
+
+/// Formats a move in ICCS notation, the inverse of `parse_iccs`.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub fn format_iccs(from: BoardCoordinate, to: BoardCoordinate) -> String {
+    let square = |c: BoardCoordinate| format!("{}{}", char::from(b'a' + c.col as u8), c.row);
+    format!("{}{}", square(from), square(to))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    RedWin,
+    BlackWin,
+    Draw,
+}
+
+impl GameResult {
+    #[must_use]
+    pub const fn pgn(self) -> &'static str {
+        match self {
+            Self::RedWin => "1-0",
+            Self::BlackWin => "0-1",
+            Self::Draw => "1/2-1/2",
+        }
+    }
+
+    /// Result of a finished game, `None` while it is still being played.
+    #[must_use]
+    pub fn from_state(state: &GameState) -> Option<Self> {
+        SessionResult::from_state(state).map(Self::from_session)
+    }
+
+    #[must_use]
+    pub fn from_session(result: SessionResult) -> Self {
+        result.winner.map_or(Self::Draw, Self::win_for)
+    }
+
+    const fn win_for(color: Color) -> Self {
+        match color {
+            Color::Red => Self::RedWin,
+            Color::Black => Self::BlackWin,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayedGame {
+    pub opening: usize,
+    /// True if engine A played Red.
+    pub a_is_red: bool,
+    pub moves: Vec<(BoardCoordinate, BoardCoordinate)>,
+    pub result: GameResult,
+}
+
+impl PlayedGame {
+    /// Score of engine A: 1 for a win, 0.5 for a draw, 0 for a loss.
+    #[must_use]
+    pub fn score_a(&self) -> f64 {
+        match (self.result, self.a_is_red) {
+            (GameResult::Draw, _) => 0.5,
+            (GameResult::RedWin, true) | (GameResult::BlackWin, false) => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// How many times an engine may propose a move the rules reject before it forfeits.
+const MAX_REJECTED_MOVES: usize = 8;
+
+fn to_coords(mv: &Move) -> Option<(BoardCoordinate, BoardCoordinate)> {
+    Some((
+        BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)?,
+        BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize)?,
+    ))
+}
+
+/// Plays one game from `opening` and adjudicates a draw after `max_plies`. A side that cannot
+/// produce a legal move loses, as in the rules. Each engine's resign and draw settings can end
+/// the game earlier.
+pub fn play_game(
+    red: &mut AlphaBetaEngine,
+    black: &mut AlphaBetaEngine,
+    opening: &[(BoardCoordinate, BoardCoordinate)],
+    limit: SearchLimit,
+    max_plies: usize,
+) -> (Vec<(BoardCoordinate, BoardCoordinate)>, GameResult) {
+    let mut state = GameState::new();
+    let mut moves = Vec::new();
+    for &(from, to) in opening {
+        if state.make_move(from, to).is_err() {
+            break;
+        }
+        moves.push((from, to));
+    }
+
+    let mut adjudicator = Adjudicator::default();
+    while state.status == GameStatus::Playing && moves.len() < max_plies {
+        let (engine, opponent) = match state.turn {
+            Color::Red => (&mut *red, &*black),
+            Color::Black => (&mut *black, &*red),
+        };
+        let mut rejected = Vec::new();
+        let played = loop {
+            if rejected.len() >= MAX_REJECTED_MOVES {
+                break None;
+            }
+            let Some((mv, _)) = engine.search(&state, limit, &rejected) else {
+                break None;
+            };
+            if rejected.is_empty() {
+                match adjudicator.record(state.turn, mv.score, engine.config(), opponent.config()) {
+                    Some(Adjudication::Resign) => state.resign(state.turn),
+                    Some(Adjudication::DrawAgreed) => state.agree_draw(),
+                    None => {}
+                }
+                if state.status != GameStatus::Playing {
+                    break None;
+                }
+            }
+            match to_coords(&mv) {
+                Some((from, to)) if state.make_move(from, to).is_ok() => break Some((from, to)),
+                _ => rejected.push(mv),
+            }
+        };
+        match played {
+            Some(mv) => moves.push(mv),
+            None if state.status == GameStatus::Playing => {
+                return (moves, GameResult::win_for(state.turn.opposite()));
+            }
+            None => break,
+        }
+    }
+
+    (
+        moves,
+        GameResult::from_state(&state).unwrap_or(GameResult::Draw),
+    )
+}
+
+/// Win, draw and loss counts from engine A's point of view.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl MatchScore {
+    pub fn record(&mut self, score_a: f64) {
+        if score_a > 0.75 {
+            self.wins += 1;
+        } else if score_a < 0.25 {
+            self.losses += 1;
+        } else {
+            self.draws += 1;
+        }
+    }
+
+    #[must_use]
+    pub const fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Mean score and per-game variance, or `None` before any game is played.
+    fn mean_and_variance(&self) -> Option<(f64, f64)> {
+        let n = f64::from(self.games());
+        if n == 0.0 {
+            return None;
+        }
+        let (w, d, l) = (
+            f64::from(self.wins) / n,
+            f64::from(self.draws) / n,
+            f64::from(self.losses) / n,
+        );
+        let mean = w + d / 2.0;
+        let variance = w.mul_add(
+            (1.0 - mean).powi(2),
+            d.mul_add((0.5 - mean).powi(2), l * mean.powi(2)),
+        );
+        Some((mean, variance))
+    }
+
+    /// Elo difference of A over B with a 95% error margin. Infinite while one side has
+    /// scored every point.
+    #[must_use]
+    pub fn elo(&self) -> Option<(f64, f64)> {
+        let (mean, variance) = self.mean_and_variance()?;
+        let margin = 1.96 * (variance / f64::from(self.games())).sqrt();
+        let elo = score_to_elo(mean);
+        let spread = (score_to_elo(mean + margin) - score_to_elo(mean - margin)) / 2.0;
+        // A clean sweep gives inf - inf; the margin is unbounded either way
+        Some((
+            elo,
+            if spread.is_nan() {
+                f64::INFINITY
+            } else {
+                spread
+            },
+        ))
+    }
+}
+
+fn score_to_elo(score: f64) -> f64 {
+    if score <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if score >= 1.0 {
+        return f64::INFINITY;
+    }
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// Sequential probability ratio test of H0 "A is `elo0` stronger" against H1 "A is `elo1`
+/// stronger", stopping once either is accepted at the given error rates.
+#[derive(Debug, Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtDecision {
+    AcceptH0,
+    AcceptH1,
+    Continue,
+}
+
+impl Default for Sprt {
+    fn default() -> Self {
+        Self {
+            elo0: 0.0,
+            elo1: 10.0,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+}
+
+impl Sprt {
+    /// `(lower, upper)` bounds on the log-likelihood ratio.
+    #[must_use]
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Log-likelihood ratio under the normal approximation of the per-game score.
+    #[must_use]
+    pub fn llr(&self, score: &MatchScore) -> f64 {
+        let Some((mean, variance)) = score.mean_and_variance() else {
+            return 0.0;
+        };
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let (s0, s1) = (elo_to_score(self.elo0), elo_to_score(self.elo1));
+        f64::from(score.games()) * (s1 - s0) * (2.0f64.mul_add(mean, -s0) - s1) / (2.0 * variance)
+    }
+
+    #[must_use]
+    pub fn decide(&self, score: &MatchScore) -> SprtDecision {
+        let llr = self.llr(score);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            SprtDecision::AcceptH1
+        } else if llr <= lower {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        }
+    }
+}
+
+/// A move as written in PGN movetext, e.g. `H2-E2`.
+#[must_use]
+pub fn format_pgn_move(from: BoardCoordinate, to: BoardCoordinate) -> String {
+    let iccs = format_iccs(from, to).to_uppercase();
+    let (a, b) = iccs.split_at(2);
+    format!("{a}-{b}")
+}
+
+/// Text attached to one move of a PGN: a suffix such as `?!` and an optional `{comment}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgnAnnotation {
+    pub glyph: &'static str,
+    pub comment: Option<String>,
+}
+
+/// Writes a game as PGN with ICCS moves, the format XiangQi PGN readers accept.
+#[must_use]
+pub fn to_pgn(
+    event: &str,
+    round: usize,
+    red: &str,
+    black: &str,
+    moves: &[(BoardCoordinate, BoardCoordinate)],
+    result: GameResult,
+) -> String {
+    let tags = [
+        ("Event", event.to_string()),
+        ("Round", round.to_string()),
+        ("Red", red.to_string()),
+        ("Black", black.to_string()),
+    ];
+    write_pgn(&tags, moves, &[], result.pgn())
+}
+
+/// Writes a PGN with `tags` after the game tag and `annotations[i]` attached to move `i`.
+/// `result` is the PGN result token, `*` for a game still in progress.
+#[must_use]
+pub fn write_pgn(
+    tags: &[(&str, String)],
+    moves: &[(BoardCoordinate, BoardCoordinate)],
+    annotations: &[PgnAnnotation],
+    result: &str,
+) -> String {
+    let mut pgn = String::new();
+    let _ = writeln!(pgn, "[Game \"Chinese Chess\"]");
+    for (name, value) in tags {
+        let _ = writeln!(pgn, "[{name} \"{}\"]", value.replace('"', "'"));
+    }
+    let _ = writeln!(pgn, "[Result \"{result}\"]");
+    let _ = writeln!(pgn, "[Format \"ICCS\"]");
+    pgn.push('\n');
+
+    let mut line = String::new();
+    for (i, pair) in moves.chunks(2).enumerate() {
+        let mut text = format!("{}.", i + 1);
+        let mut after_comment = false;
+        for (j, &(from, to)) in pair.iter().enumerate() {
+            // Black's move needs its number again once a comment split it from Red's
+            if after_comment {
+                let _ = write!(text, " {}...", i + 1);
+            }
+            let annotation = annotations.get(i * 2 + j);
+            let glyph = annotation.map_or("", |a| a.glyph);
+            let _ = write!(text, " {}{glyph}", format_pgn_move(from, to));
+            after_comment = false;
+            if let Some(comment) = annotation.and_then(|a| a.comment.as_deref()) {
+                let _ = write!(text, " {{{}}}", comment.replace('}', ")"));
+                after_comment = true;
+            }
+        }
+        if !line.is_empty() && line.len() + text.len() >= 80 {
+            let _ = writeln!(pgn, "{line}");
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&text);
+    }
+    if !line.is_empty() {
+        line.push(' ');
+    }
+    let _ = writeln!(pgn, "{line}{result}");
+    pgn
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::EngineConfig;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_openings_are_legal() {
+        for line in OPENINGS {
+            let moves = parse_opening(line).unwrap();
+            let mut state = GameState::new();
+            for (from, to) in moves {
+                assert!(state.make_move(from, to).is_ok(), "{line}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_iccs_round_trip() {
+        let (from, to) = parse_iccs("h2e2").unwrap();
+        assert_eq!(format_iccs(from, to), "h2e2");
+    }
+
+    #[test]
+    fn test_elo_estimate() {
+        let even = MatchScore {
+            wins: 10,
+            draws: 20,
+            losses: 10,
+        };
+        let (elo, margin) = even.elo().unwrap();
+        assert!(elo.abs() < 1e-9);
+        assert!(margin > 0.0);
+
+        let ahead = MatchScore {
+            wins: 30,
+            draws: 10,
+            losses: 10,
+        };
+        // 70% score is about +147 Elo
+        let (elo, _) = ahead.elo().unwrap();
+        assert!((elo - 147.2).abs() < 1.0, "{elo}");
+        assert_eq!(MatchScore::default().elo(), None);
+    }
+
+    #[test]
+    fn test_sprt_decisions() {
+        let sprt = Sprt::default();
+        let clearly_better = MatchScore {
+            wins: 400,
+            draws: 200,
+            losses: 200,
+        };
+        assert_eq!(sprt.decide(&clearly_better), SprtDecision::AcceptH1);
+        let clearly_worse = MatchScore {
+            wins: 200,
+            draws: 200,
+            losses: 400,
+        };
+        assert_eq!(sprt.decide(&clearly_worse), SprtDecision::AcceptH0);
+        let early = MatchScore {
+            wins: 2,
+            draws: 1,
+            losses: 1,
+        };
+        assert_eq!(sprt.decide(&early), SprtDecision::Continue);
+    }
+
+    #[test]
+    fn test_play_short_game() {
+        let config = Arc::new(EngineConfig {
+            tt_size_mb: 1,
+            ..Default::default()
+        });
+        let mut red = AlphaBetaEngine::new(config.clone());
+        let mut black = AlphaBetaEngine::new(config);
+        let opening = parse_opening(OPENINGS[0]).unwrap();
+        let (moves, result) = play_game(&mut red, &mut black, &opening, SearchLimit::Depth(1), 12);
+        assert_eq!(moves.len(), 12);
+        assert_eq!(result, GameResult::Draw);
+
+        let pgn = to_pgn("test", 1, "A", "B", &moves, result);
+        assert!(pgn.contains("1. H2-E2 H9-G7"));
+        assert!(pgn.trim_end().ends_with("1/2-1/2"));
+    }
+}
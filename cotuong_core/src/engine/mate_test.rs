@@ -1,10 +1,11 @@
 #[cfg(test)]
 mod tests {
     use crate::engine::config::EngineConfig;
-    use crate::engine::search::AlphaBetaEngine;
+    use crate::engine::search::{search_mate, AlphaBetaEngine};
     use crate::engine::{SearchLimit, Searcher};
     use crate::logic::board::{Board, BoardCoordinate, Color};
-    use crate::logic::game::GameState;
+    use crate::logic::game::{GameState, GameStatus};
+    use crate::logic::puzzle::{starter_puzzles, PuzzleTheme};
     use std::sync::Arc;
 
     #[test]
@@ -88,11 +89,7 @@ mod tests {
         let config = Arc::new(EngineConfig::default());
         let mut engine = AlphaBetaEngine::new(config);
 
-        let game_state = GameState {
-            board,
-            turn: Color::Red,
-            ..Default::default()
-        };
+        let game_state = GameState::from_position(board, Color::Red);
 
         let limit = SearchLimit::Depth(4);
         let (best_move, _) = engine.search(&game_state, limit, &[]).unwrap();
@@ -176,4 +173,42 @@ mod tests {
         assert!(diff_3_5 > 0);
         assert!(diff_5_7 > 0);
     }
+
+    #[test]
+    fn test_search_mate_solves_puzzles() {
+        for puzzle in starter_puzzles() {
+            let PuzzleTheme::MateIn(n) = puzzle.theme else {
+                continue;
+            };
+            let mut state = puzzle.initial_state().unwrap();
+            let solver = state.turn;
+            let line = search_mate(&state, 2 * n - 1).expect("mate not found");
+            assert_eq!(line.len(), usize::from(2 * n - 1), "{}", puzzle.fen);
+
+            for mv in &line {
+                let from = BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize).unwrap();
+                let to = BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize).unwrap();
+                state.make_move(from, to).unwrap();
+            }
+            assert_eq!(state.status, GameStatus::Checkmate(solver), "{}", puzzle.fen);
+        }
+    }
+
+    #[test]
+    fn test_search_mate_none_in_opening() {
+        assert_eq!(search_mate(&GameState::new(), 3), None);
+    }
+
+    #[test]
+    fn test_quiescence_sees_mate_below_horizon() {
+        // Chariot on rank 8 cuts the general off, the other chariot mates on rank 9 (i5i9).
+        // At depth 1 the reply is only seen by quiescence, which must not stand pat in check.
+        let (board, turn) = Board::from_fen("4k4/R8/9/9/8R/9/9/9/9/3K5 w").unwrap();
+        let state = GameState::from_position(board, turn);
+        let config = Arc::new(EngineConfig::default());
+        let mut engine = AlphaBetaEngine::new(config.clone());
+        let (mv, _) = engine.search(&state, SearchLimit::Depth(1), &[]).unwrap();
+        assert_eq!((mv.from_row, mv.from_col, mv.to_row, mv.to_col), (5, 8, 9, 8));
+        assert!(mv.score > config.mate_score - 10, "score {}", mv.score);
+    }
 }
@@ -1,18 +1,32 @@
+use crate::engine::tt::TTStats;
 use crate::logic::board::Board;
 use crate::logic::game::GameState;
 use serde::{Deserialize, Serialize};
 
+pub mod adjudication;
+pub mod bench;
 pub mod config;
 pub mod eval;
+pub mod explain;
+pub mod match_runner;
 pub mod move_list;
 pub mod movegen;
+pub mod puzzle_gen;
+pub mod review;
 pub mod search;
+#[cfg(feature = "tracing")]
+pub mod search_log;
+pub mod time_manager;
+pub mod trace;
 pub mod tt;
+pub mod tuner;
 pub mod zobrist;
 
 #[cfg(test)]
 mod bench_test;
 mod mate_test;
+#[cfg(test)]
+mod strength_test;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Move {
@@ -28,6 +42,8 @@ pub struct Move {
 pub enum SearchLimit {
     Depth(u8),
     Time(u64), // milliseconds
+    /// Whole-game clock of the side to move; the engine decides how much of it to spend.
+    Clock { remaining_ms: u64, increment_ms: u64 },
 }
 
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
@@ -35,6 +51,8 @@ pub struct SearchStats {
     pub depth: u8,
     pub nodes: u32,
     pub time_ms: u64,
+    /// Transposition table usage during this search.
+    pub tt: TTStats,
 }
 
 pub trait Evaluator {
@@ -0,0 +1,185 @@
+use crate::engine::config::EngineConfig;
+use crate::engine::search::{search_mate, AlphaBetaEngine};
+use crate::engine::{Move, SearchLimit, Searcher};
+use crate::logic::board::BoardCoordinate;
+use crate::logic::game::{GameState, GameStatus};
+use crate::logic::generator::MoveGenerator;
+use crate::logic::puzzle::{Puzzle, PuzzleTheme};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy)]
+pub struct GeneratorConfig {
+    /// Search depth used to score positions.
+    pub depth: u8,
+    /// Minimum gain for the side to move, compared with the score before the opponent's
+    /// last move, for the position to count as a blunder worth punishing.
+    pub swing_threshold: i32,
+    /// How much better the best move must be than the second best to be the only solution.
+    pub uniqueness_margin: i32,
+    /// Longest mate to look for, in the solver's moves.
+    pub max_mate_moves: u8,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            swing_threshold: 300,
+            uniqueness_margin: 200,
+            max_mate_moves: 3,
+        }
+    }
+}
+
+fn to_coords(mv: &Move) -> Option<(BoardCoordinate, BoardCoordinate)> {
+    Some((
+        BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)?,
+        BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize)?,
+    ))
+}
+
+/// True if, after `first` is played, every reply still loses to a mate within `plies - 2`.
+fn forces_mate_after(state: &GameState, first: &Move, plies: u8) -> bool {
+    let mut next = state.clone();
+    let Some((from, to)) = to_coords(first) else {
+        return false;
+    };
+    if next.make_move(from, to).is_err() {
+        return false;
+    }
+    if next.status != GameStatus::Playing {
+        return true;
+    }
+    if plies < 3 {
+        return false;
+    }
+    MoveGenerator::new()
+        .generate_moves(&next.board, next.turn)
+        .iter()
+        .all(|reply| {
+            let mut after = next.clone();
+            to_coords(reply).is_some_and(|(from, to)| after.make_move(from, to).is_ok())
+                && search_mate(&after, plies - 2).is_some()
+        })
+}
+
+/// Shortest forced mate for the side to move, if its first move is the only one that works.
+fn unique_mate(state: &GameState, max_plies: u8) -> Option<Puzzle> {
+    let line = search_mate(state, max_plies)?;
+    let first = *line.first()?;
+    let plies = u8::try_from(line.len()).ok()?;
+
+    let alternatives = MoveGenerator::new().generate_moves(&state.board, state.turn);
+    let ambiguous = alternatives
+        .iter()
+        .filter(|mv| to_coords(mv) != to_coords(&first))
+        .any(|mv| forces_mate_after(state, mv, plies));
+    if ambiguous {
+        return None;
+    }
+
+    Some(Puzzle {
+        fen: state.board.to_fen_string(state.turn),
+        solution_moves: line.iter().filter_map(to_coords).collect(),
+        theme: PuzzleTheme::MateIn(plies.div_ceil(2)),
+    })
+}
+
+/// One-move tactic: the engine's best move must clearly beat every other move.
+fn unique_tactic(
+    engine: &mut AlphaBetaEngine,
+    state: &GameState,
+    config: &GeneratorConfig,
+) -> Option<Puzzle> {
+    let limit = SearchLimit::Depth(config.depth);
+    let (best, _) = engine.search(state, limit, &[])?;
+    let second_score = engine
+        .search(state, limit, &[best])
+        .map_or(i32::MIN, |(mv, _)| mv.score);
+    if best.score.saturating_sub(second_score) < config.uniqueness_margin {
+        return None;
+    }
+
+    Some(Puzzle {
+        fen: state.board.to_fen_string(state.turn),
+        solution_moves: vec![to_coords(&best)?],
+        theme: PuzzleTheme::Tactic,
+    })
+}
+
+/// Replays a game and returns puzzles for the positions where a player could punish the
+/// opponent's last move. Stops at the first illegal move.
+pub fn puzzles_from_game(
+    start: GameState,
+    moves: &[(BoardCoordinate, BoardCoordinate)],
+    config: &GeneratorConfig,
+) -> Vec<Puzzle> {
+    let mut engine = AlphaBetaEngine::new(Arc::new(EngineConfig::default()));
+    let limit = SearchLimit::Depth(config.depth);
+    let max_plies = config.max_mate_moves.saturating_mul(2).saturating_sub(1);
+
+    let mut puzzles: Vec<Puzzle> = Vec::new();
+    let mut state = start;
+    // Score before the previous move, from the point of view of the side that made it
+    let mut previous_score: Option<i32> = None;
+
+    for &(from, to) in moves {
+        let score = engine.search(&state, limit, &[]).map(|(mv, _)| mv.score);
+
+        // The opponent just moved from a position scored `prev`; we now see `score`.
+        // Without a mistake these roughly cancel out.
+        let swing = match (previous_score, score) {
+            (Some(prev), Some(now)) => now.saturating_add(prev),
+            _ => 0,
+        };
+
+        let puzzle = unique_mate(&state, max_plies).or_else(|| {
+            (swing >= config.swing_threshold)
+                .then(|| unique_tactic(&mut engine, &state, config))
+                .flatten()
+        });
+        if let Some(puzzle) = puzzle {
+            if !puzzles.iter().any(|p| p.fen == puzzle.fen) {
+                puzzles.push(puzzle);
+            }
+        }
+
+        previous_score = score;
+        if state.make_move(from, to).is_err() {
+            break;
+        }
+    }
+
+    puzzles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::Board;
+    use crate::logic::puzzle::parse_iccs;
+
+    #[test]
+    fn test_finds_mate_after_blunder() {
+        // Black pushes a pawn instead of defending; Red mates along the back rank
+        let (board, turn) = Board::from_fen("4k4/R8/8R/9/p8/9/9/9/9/3K5 b").unwrap();
+        let mut start = GameState::new();
+        start.board = board;
+        start.turn = turn;
+
+        let config = GeneratorConfig {
+            depth: 2,
+            ..GeneratorConfig::default()
+        };
+        let blunder = parse_iccs("a5a4").unwrap();
+        let finish = parse_iccs("i7i9").unwrap();
+        let puzzles = puzzles_from_game(start, &[blunder, finish], &config);
+
+        let mate = puzzles
+            .iter()
+            .find(|p| p.theme == PuzzleTheme::MateIn(1))
+            .expect("mate puzzle");
+        assert_eq!(mate.fen, "4k4/R8/8R/9/9/p8/9/9/9/3K5 w");
+        assert_eq!(mate.solution_moves, vec![finish]);
+    }
+}
@@ -0,0 +1,409 @@
+use crate::engine::match_runner::{format_pgn_move, write_pgn, GameResult, PgnAnnotation};
+use crate::engine::search::AlphaBetaEngine;
+use crate::engine::{SearchLimit, Searcher};
+use crate::logic::board::{BoardCoordinate, Color};
+use crate::logic::game::GameState;
+use crate::logic::opening::opening_name;
+use serde::{Deserialize, Serialize};
+
+/// Scores are capped here before comparing moves, so a missed mate counts as a lost rook
+/// or two rather than swamping every average.
+pub const MAX_REVIEW_SCORE: i32 = 1500;
+/// Steepness of the score to winning chance curve; a rook up is about a 92% chance.
+const WIN_CHANCE_SCALE: f64 = 0.004;
+/// Move suffixes a PGN may carry, from a stored `MoveRecord::glyph`.
+const PGN_GLYPHS: [&str; 6] = ["!!", "!", "!?", "?!", "?", "??"];
+
+/// How bad a move was, judged by how much winning chance it threw away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// Judgement for a drop in winning chance, in percentage points.
+    #[must_use]
+    pub fn from_drop(drop: f64) -> Option<Self> {
+        if drop >= 30.0 {
+            Some(Self::Blunder)
+        } else if drop >= 20.0 {
+            Some(Self::Mistake)
+        } else if drop >= 10.0 {
+            Some(Self::Inaccuracy)
+        } else {
+            None
+        }
+    }
+
+    /// Suffix for the move in PGN.
+    #[must_use]
+    pub const fn glyph(self) -> &'static str {
+        match self {
+            Self::Inaccuracy => "?!",
+            Self::Mistake => "?",
+            Self::Blunder => "??",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveReview {
+    pub ply: usize,
+    pub mover: Color,
+    pub played: (BoardCoordinate, BoardCoordinate),
+    /// The engine's choice in the same position, `None` if it found no move.
+    pub best: Option<(BoardCoordinate, BoardCoordinate)>,
+    /// Scores from the mover's side, before the move (with best play) and after it.
+    pub score_before: i32,
+    pub score_after: i32,
+    pub cp_loss: i32,
+    /// 0 to 100, how much of the winning chance the move kept.
+    pub accuracy: f64,
+    pub judgement: Option<Judgement>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SideSummary {
+    pub moves: u32,
+    pub avg_cp_loss: f64,
+    pub accuracy: f64,
+    pub inaccuracies: u32,
+    pub mistakes: u32,
+    pub blunders: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GameReview {
+    pub moves: Vec<MoveReview>,
+    /// Score of every position from Red's side, the start position first.
+    pub evals: Vec<i32>,
+    pub red: SideSummary,
+    pub black: SideSummary,
+}
+
+impl GameReview {
+    #[must_use]
+    pub const fn side(&self, color: Color) -> &SideSummary {
+        match color {
+            Color::Red => &self.red,
+            Color::Black => &self.black,
+        }
+    }
+
+    /// Writes the scores and judgement glyphs into the reviewed game's moves, keeping any
+    /// glyph already there.
+    pub fn annotate(&self, game: &mut GameState) {
+        for (ply, record) in game.history.iter_mut().enumerate() {
+            if let Some(&score) = self.evals.get(ply + 1) {
+                record.eval = Some(score);
+            }
+            let judgement = self.moves.get(ply).and_then(|m| m.judgement);
+            if let (None, Some(judgement)) = (&record.glyph, judgement) {
+                record.glyph = Some(judgement.glyph().to_string());
+            }
+        }
+    }
+
+    /// Moves judged a mistake or a blunder, in game order.
+    pub fn mistakes(&self) -> impl Iterator<Item = &MoveReview> {
+        self.moves
+            .iter()
+            .filter(|m| matches!(m.judgement, Some(Judgement::Mistake | Judgement::Blunder)))
+    }
+}
+
+/// Winning chance in percent for a score from the mover's side.
+#[must_use]
+pub fn win_chance(score: i32) -> f64 {
+    let x = -WIN_CHANCE_SCALE * f64::from(score);
+    50.0 + 50.0 * (2.0 / (1.0 + x.exp()) - 1.0)
+}
+
+/// Accuracy of a move that dropped the winning chance by `drop` percentage points.
+fn move_accuracy(drop: f64) -> f64 {
+    103.166_8f64
+        .mul_add((-0.043_54 * drop.max(0.0)).exp(), -3.166_9)
+        .clamp(0.0, 100.0)
+}
+
+/// `score`, from the side to move in `position`, from Red's side.
+const fn red_score(position: &GameState, score: i32) -> i32 {
+    match position.turn {
+        Color::Red => score,
+        Color::Black => -score,
+    }
+}
+
+/// Every position of the game, from the start position to the current one.
+#[must_use]
+pub fn positions(game: &GameState) -> Vec<GameState> {
+    let mut state = game.clone();
+    let mut positions = vec![state.clone()];
+    while state.undo_move() {
+        positions.push(state.clone());
+    }
+    positions.reverse();
+    positions
+}
+
+/// Searches every position of `game` with `limit` and grades each move against the
+/// engine's choice. `on_progress(done, total, score)` is called after each position with
+/// its score from Red's side.
+pub fn review_game(
+    engine: &mut AlphaBetaEngine,
+    game: &GameState,
+    limit: SearchLimit,
+    mut on_progress: impl FnMut(usize, usize, i32),
+) -> GameReview {
+    let positions = positions(game);
+    let total = positions.len();
+    // Best move and score from the side to move's point of view, for every position
+    let evals: Vec<_> = positions
+        .iter()
+        .enumerate()
+        .map(|(i, position)| {
+            let result = engine.search(position, limit, &[]);
+            let (best, score) = result.map_or((None, -MAX_REVIEW_SCORE), |(mv, _)| {
+                let best = BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize)
+                    .zip(BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize));
+                (best, mv.score.clamp(-MAX_REVIEW_SCORE, MAX_REVIEW_SCORE))
+            });
+            on_progress(i + 1, total, red_score(position, score));
+            (best, score)
+        })
+        .collect();
+
+    let mut review = GameReview {
+        evals: positions
+            .iter()
+            .zip(&evals)
+            .map(|(position, &(_, score))| red_score(position, score))
+            .collect(),
+        ..GameReview::default()
+    };
+    for (ply, record) in game.history.iter().enumerate() {
+        let (Some(&(best, score_before)), Some(&(_, next_score)), Some(next)) =
+            (evals.get(ply), evals.get(ply + 1), positions.get(ply + 1))
+        else {
+            break;
+        };
+        let mover = record.color;
+        let played = (record.from, record.to);
+        // A handicap can give the mover a second move in a row
+        let mut score_after = if next.turn == mover {
+            next_score
+        } else {
+            -next_score
+        };
+        if best == Some(played) {
+            // The engine's own move cannot lose anything; differences are search noise
+            score_after = score_before;
+        }
+        let drop = win_chance(score_before) - win_chance(score_after);
+        review.moves.push(MoveReview {
+            ply,
+            mover,
+            played,
+            best,
+            score_before,
+            score_after,
+            cp_loss: (score_before - score_after).max(0),
+            accuracy: move_accuracy(drop),
+            judgement: Judgement::from_drop(drop),
+        });
+    }
+
+    for color in [Color::Red, Color::Black] {
+        let moves: Vec<&MoveReview> = review.moves.iter().filter(|m| m.mover == color).collect();
+        if moves.is_empty() {
+            continue;
+        }
+        let count = |judgement| {
+            u32::try_from(
+                moves
+                    .iter()
+                    .filter(|m| m.judgement == Some(judgement))
+                    .count(),
+            )
+            .unwrap_or(u32::MAX)
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let n = moves.len() as f64;
+        let summary = SideSummary {
+            moves: u32::try_from(moves.len()).unwrap_or(u32::MAX),
+            avg_cp_loss: moves.iter().map(|m| f64::from(m.cp_loss)).sum::<f64>() / n,
+            accuracy: moves.iter().map(|m| m.accuracy).sum::<f64>() / n,
+            inaccuracies: count(Judgement::Inaccuracy),
+            mistakes: count(Judgement::Mistake),
+            blunders: count(Judgement::Blunder),
+        };
+        match color {
+            Color::Red => review.red = summary,
+            Color::Black => review.black = summary,
+        }
+    }
+    review
+}
+
+/// `[%clk h:mm:ss]` for the time a side had left.
+fn clock_comment(ms: u64) -> String {
+    let seconds = ms / 1000;
+    format!(
+        "[%clk {}:{:02}:{:02}]",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// The game as PGN, with each side's accuracy in the tags when a review is given, every
+/// judged move marked with its glyph, its loss and the engine's choice, and what each
+/// move's record carries (annotation, stored glyph, eval, clock) in its comment.
+#[must_use]
+pub fn review_pgn(game: &GameState, review: Option<&GameReview>, red: &str, black: &str) -> String {
+    let start = positions(game).into_iter().next().unwrap_or_default();
+    let mut tags = vec![("Red", red.to_string()), ("Black", black.to_string())];
+    let start_fen = start.board.to_fen_string(start.turn);
+    if start_fen != GameState::new().board.to_fen_string(Color::Red) {
+        tags.push(("FEN", start_fen));
+    }
+    if let Some(opening) = opening_name(game) {
+        tags.push(("Opening", opening));
+    }
+    if let Some(review) = review {
+        tags.push(("RedAccuracy", format!("{:.1}", review.red.accuracy)));
+        tags.push(("BlackAccuracy", format!("{:.1}", review.black.accuracy)));
+    }
+
+    let moves: Vec<_> = game.history.iter().map(|r| (r.from, r.to)).collect();
+    let annotations: Vec<PgnAnnotation> = game
+        .history
+        .iter()
+        .enumerate()
+        .map(|(ply, record)| {
+            let mut annotation = PgnAnnotation::default();
+            let mut comments = Vec::new();
+            let judged = review
+                .and_then(|r| r.moves.get(ply))
+                .map(|m| (m, m.judgement));
+            if let Some((m, Some(judgement))) = judged {
+                annotation.glyph = judgement.glyph();
+                comments.push(m.best.map_or_else(
+                    || format!("-{}", m.cp_loss),
+                    |(from, to)| format!("-{}, best: {}", m.cp_loss, format_pgn_move(from, to)),
+                ));
+            } else if let Some(glyph) = &record.glyph {
+                annotation.glyph = PGN_GLYPHS.into_iter().find(|g| g == glyph).unwrap_or("");
+            }
+            if let Some(note) = &record.annotation {
+                comments.push(note.pgn_comment());
+            }
+            if let Some(eval) = record.eval {
+                comments.push(format!("[%eval {:+.2}]", f64::from(eval) / 100.0));
+            }
+            if let Some(ms) = record.clock_ms {
+                comments.push(clock_comment(ms));
+            }
+            annotation.comment = (!comments.is_empty()).then(|| comments.join("; "));
+            annotation
+        })
+        .collect();
+    let result = GameResult::from_state(game).map_or("*", GameResult::pgn);
+    write_pgn(&tags, &moves, &annotations, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::EngineConfig;
+    use crate::engine::explain::MoveExplanation;
+    use crate::engine::match_runner::parse_opening;
+    use crate::logic::game::MoveAnnotation;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_win_chance_and_accuracy() {
+        assert!((win_chance(0) - 50.0).abs() < 1e-9);
+        assert!(win_chance(600) > 90.0 && win_chance(-600) < 10.0);
+        assert!((move_accuracy(0.0) - 100.0).abs() < 0.01);
+        assert!(move_accuracy(30.0) < 30.0);
+        assert_eq!(Judgement::from_drop(5.0), None);
+        assert_eq!(Judgement::from_drop(35.0), Some(Judgement::Blunder));
+    }
+
+    #[test]
+    fn test_review_finds_blunder() {
+        let mut game = GameState::new();
+        // Red's central cannon grabs the pawn and is taken by the horse
+        for (from, to) in parse_opening("h2e2 h9g7 e2e6 g7e6").unwrap() {
+            game.make_move(from, to).unwrap();
+        }
+        let mut engine = AlphaBetaEngine::new(Arc::new(EngineConfig {
+            tt_size_mb: 1,
+            ..Default::default()
+        }));
+        let mut calls = 0;
+        let mut scores = Vec::new();
+        let review = review_game(
+            &mut engine,
+            &game,
+            SearchLimit::Depth(3),
+            |done, total, score| {
+                calls += 1;
+                assert!(done <= total);
+                scores.push(score);
+            },
+        );
+        assert_eq!(calls, game.history.len() + 1);
+        assert_eq!(review.evals, scores);
+        // Black is a cannon up once the horse takes it
+        assert!(review.evals[4] < -200);
+        assert_eq!(review.moves.len(), game.history.len());
+        assert_eq!(review.red.moves + review.black.moves, 4);
+        let blunders: Vec<usize> = review.mistakes().map(|m| m.ply).collect();
+        assert_eq!(blunders, vec![2]);
+        assert_eq!(review.red.blunders, 1);
+        assert!(review.red.accuracy < review.black.accuracy);
+        for side in [&review.red, &review.black] {
+            assert!((0.0..=100.0).contains(&side.accuracy));
+        }
+
+        let pgn = review_pgn(&game, Some(&review), "A", "B");
+        assert!(pgn.contains("[RedAccuracy"));
+        assert!(pgn.contains("[Opening \"Pháo đầu – Bình phong mã\"]"));
+        assert!(pgn.contains("2. E2-E6?? {-"), "{pgn}");
+        assert!(pgn.contains(", best: "), "{pgn}");
+        assert!(pgn.trim_end().ends_with('*'));
+    }
+
+    #[test]
+    fn test_pgn_carries_move_annotations() {
+        let mut game = GameState::new();
+        for (from, to) in parse_opening("h2e2 h9g7").unwrap() {
+            game.make_move(from, to).unwrap();
+        }
+        game.history[0].annotation = Some(MoveAnnotation::Book);
+        game.history[0].glyph = Some("!".to_string());
+        game.history[0].clock_ms = Some(295_000);
+        game.history[1].eval = Some(35);
+        game.history[1].annotation = Some(MoveAnnotation::Engine {
+            depth: 12,
+            nodes: 50_000,
+            time_ms: 1250,
+            explanation: MoveExplanation {
+                score: -35,
+                captured: None,
+                material_swing: 0,
+                gives_check: false,
+                threats: Vec::new(),
+                defends: Vec::new(),
+                pv: Vec::new(),
+            },
+        });
+        let pgn = review_pgn(&game, None, "A", "B");
+        assert!(pgn.contains("H2-E2! {book; [%clk 0:04:55]}"), "{pgn}");
+        assert!(pgn.contains("H9-G7 {-0.35/12 1.2s; [%eval +0.35]}"), "{pgn}");
+    }
+}
@@ -1,11 +1,19 @@
-use crate::engine::config::EngineConfig;
+use crate::engine::config::{EngineConfig, StrengthLimit};
 use crate::engine::eval::SimpleEvaluator;
+#[cfg(feature = "tracing")]
+use crate::engine::search_log;
+use crate::engine::time_manager::TimeManager;
+use crate::engine::trace::{SearchTrace, TraceKind, TraceLimits, TraceNode, TraceReason};
 use crate::engine::tt::{TTFlag, TranspositionTable};
 use crate::engine::{Evaluator, Move, SearchLimit, SearchStats, Searcher};
 use crate::logic::board::{Board, BoardCoordinate, Color, PieceType};
 use crate::logic::game::GameState;
 use crate::logic::generator::MoveGenerator;
-use crate::logic::rules::{is_flying_general, is_in_check};
+use crate::logic::repetition::RepetitionTable;
+use crate::logic::rules::{gives_check, is_flying_general, is_in_check, is_valid_move};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
 use std::sync::Arc;
 
 pub struct AlphaBetaEngine {
@@ -13,38 +21,62 @@ pub struct AlphaBetaEngine {
     evaluator: SimpleEvaluator,
     tt: TranspositionTable,
     killer_moves: [[Option<Move>; 2]; 64],
-    history_stack: Vec<u64>,
+    /// Hashes of the game so far and of the current search line.
+    repetitions: RepetitionTable,
     pub history_table: Box<[[i32; 90]]>,
     nodes_searched: u32,
     start_time: f64,
     time_limit: Option<f64>,
+    node_limit: Option<u32>,
     dynamic_limits: [usize; 64],
     lmr_table: [[u8; 64]; 64],
     mate_score_table: [i32; 256],
+    /// Records the search tree while set, see `set_trace`.
+    trace: Option<SearchTrace>,
 }
 
 use crate::engine::movegen::EngineMoveGen;
 
+/// Hard cap on the ply quiescence may reach; evasion chains are otherwise unbounded.
+const MAX_QSEARCH_PLY: u8 = 120;
+
+/// Depth a deterministic engine searches when given a time or clock limit.
+pub const DETERMINISTIC_DEPTH: u8 = 6;
+
 impl AlphaBetaEngine {
     pub fn new(config: Arc<EngineConfig>) -> Self {
         let dynamic_limits = Self::precompute_limits(&config);
         let mate_score_table = Self::precompute_mate_scores(&config);
         Self {
             evaluator: SimpleEvaluator::new(config.clone()),
+            tt: TranspositionTable::new(config.tt_size_mb),
             config,
-            tt: TranspositionTable::new(64),
             killer_moves: [[None; 2]; 64],
-            history_stack: Vec::with_capacity(64),
+            repetitions: RepetitionTable::new(),
             history_table: vec![[0; 90]; 90].into_boxed_slice(),
             nodes_searched: 0,
             start_time: 0.0,
             time_limit: None,
+            node_limit: None,
             dynamic_limits,
             lmr_table: Self::precompute_lmr(),
             mate_score_table,
+            trace: None,
         }
     }
 
+    /// Forgets the previous game: hash table, killer moves and history scores.
+    pub fn new_game(&mut self) {
+        self.tt.clear();
+        self.killer_moves = [[None; 2]; 64];
+        self.history_table.fill([0; 90]);
+    }
+
+    #[must_use]
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
     pub fn update_config(&mut self, config: Arc<EngineConfig>) {
         if config.tt_size_mb != self.config.tt_size_mb {
             self.tt = TranspositionTable::new(config.tt_size_mb);
@@ -55,6 +87,65 @@ impl AlphaBetaEngine {
         self.config = config;
     }
 
+    /// Records the tree of each search within `limits` from now on, or stops recording.
+    /// Debugging aid: recording costs time, so leave it off for play.
+    pub fn set_trace(&mut self, limits: Option<TraceLimits>) {
+        self.trace = limits.map(SearchTrace::new);
+    }
+
+    /// The tree of the last root pass the last search finished, while tracing.
+    pub fn take_trace(&mut self) -> Option<TraceNode> {
+        self.trace.as_mut().and_then(SearchTrace::take_last)
+    }
+
+    fn trace_next(&mut self, kind: TraceKind, mv: Option<Move>) {
+        if let Some(trace) = &mut self.trace {
+            trace.set_next(kind, mv);
+        }
+    }
+
+    fn trace_reason(&mut self, reason: TraceReason) {
+        if let Some(trace) = &mut self.trace {
+            trace.reason(reason);
+        }
+    }
+
+    fn trace_pruned(&mut self, mv: Move, reason: TraceReason, ply: u8) {
+        if let Some(trace) = &mut self.trace {
+            trace.pruned(mv, reason, ply);
+        }
+    }
+
+    /// The line the last search expects after `first` is played in `game_state`, read back
+    /// from the hash table. Starts with `first` and stops early at a missing or illegal entry.
+    pub fn principal_variation(
+        &mut self,
+        game_state: &GameState,
+        first: Move,
+        max_len: usize,
+    ) -> Vec<Move> {
+        let mut board = game_state.board.clone();
+        let mut turn = game_state.turn;
+        let mut line = Vec::new();
+        let mut next = Some(first);
+        while let Some(mv) = next.filter(|_| line.len() < max_len) {
+            let (Some(from), Some(to)) = (
+                BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize),
+                BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize),
+            ) else {
+                break;
+            };
+            if is_valid_move(&board, from, to, turn).is_err() {
+                break;
+            }
+            board.apply_move(&mv, turn);
+            turn = turn.opposite();
+            line.push(mv);
+            next = self.tt.get_move(board.zobrist_hash);
+        }
+        line
+    }
+
     fn precompute_lmr() -> [[u8; 64]; 64] {
         let mut table = [[0; 64]; 64];
         for (depth, row) in table.iter_mut().enumerate() {
@@ -133,6 +224,12 @@ impl AlphaBetaEngine {
     }
 
     fn check_time(&self) -> bool {
+        if self
+            .node_limit
+            .is_some_and(|max| self.nodes_searched >= max)
+        {
+            return true;
+        }
         if let Some(limit) = self.time_limit {
             if self.nodes_searched.is_multiple_of(1024) {
                 let elapsed = Self::now() - self.start_time;
@@ -145,7 +242,7 @@ impl AlphaBetaEngine {
     }
 
     fn is_repetition(&self, hash: u64) -> bool {
-        self.history_stack.iter().filter(|&&h| h == hash).count() >= 2
+        self.repetitions.occurs_at_least(hash, 2)
     }
 
     /// Calculate the mate score penalty using precomputed table.
@@ -173,6 +270,7 @@ impl AlphaBetaEngine {
             let reduction = self.config.probcut_reduction;
 
             if depth > reduction {
+                self.trace_next(TraceKind::ProbCut, None);
                 if let Some(score) = self.alpha_beta(
                     board,
                     -beta - margin,
@@ -204,6 +302,7 @@ impl AlphaBetaEngine {
         if depth >= 3 && beta.abs() < 15000 && !is_in_check(board, turn) {
             let r = if depth > 6 { 3 } else { 2 };
             board.apply_null_move();
+            self.trace_next(TraceKind::NullMove, None);
 
             let result = self.alpha_beta(
                 board,
@@ -249,6 +348,7 @@ impl AlphaBetaEngine {
                 {
                     let margin = self.config.singular_extension_margin;
                     let singular_beta = entry.score - margin;
+                    self.trace_next(TraceKind::Singular, None);
 
                     if let Some(score) = self.alpha_beta(
                         board,
@@ -271,6 +371,28 @@ impl AlphaBetaEngine {
 
     #[allow(clippy::too_many_arguments)]
     fn alpha_beta(
+        &mut self,
+        board: &mut Board,
+        alpha: i32,
+        beta: i32,
+        depth: u8,
+        turn: Color,
+        ply: u8,
+        excluded_move: Option<Move>,
+    ) -> Option<i32> {
+        let Some(trace) = &mut self.trace else {
+            return self.search_node(board, alpha, beta, depth, turn, ply, excluded_move);
+        };
+        trace.enter(alpha, beta, depth, ply);
+        let score = self.search_node(board, alpha, beta, depth, turn, ply, excluded_move);
+        if let Some(trace) = &mut self.trace {
+            trace.exit(score);
+        }
+        score
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search_node(
         &mut self,
         board: &mut Board,
         mut alpha: i32,
@@ -298,16 +420,18 @@ impl AlphaBetaEngine {
             alpha = alpha.max(-mat);
             beta = beta.min(mat);
             if alpha >= beta {
+                self.trace_reason(TraceReason::MateDistance);
                 return Some(alpha);
             }
         }
 
         let hash = board.zobrist_hash;
         if self.is_repetition(hash) {
-            return Some(0);
+            self.trace_reason(TraceReason::Repetition);
+            return Some(self.evaluator.draw_score(ply));
         }
 
-        self.history_stack.push(hash);
+        self.repetitions.push(hash);
 
         // TT Probe
         let tt_entry = self.tt.probe(hash);
@@ -315,39 +439,45 @@ impl AlphaBetaEngine {
             if entry.depth >= depth {
                 match entry.flag {
                     TTFlag::Exact => {
-                        self.history_stack.pop();
+                        self.repetitions.pop();
+                        self.trace_reason(TraceReason::TranspositionTable);
                         return Some(entry.score);
                     }
                     TTFlag::LowerBound => {
                         if entry.score >= beta {
-                            self.history_stack.pop();
+                            self.repetitions.pop();
+                            self.trace_reason(TraceReason::TranspositionTable);
                             return Some(entry.score);
                         }
                         alpha = alpha.max(entry.score);
                     }
                     TTFlag::UpperBound => {
                         if entry.score <= alpha {
-                            self.history_stack.pop();
+                            self.repetitions.pop();
+                            self.trace_reason(TraceReason::TranspositionTable);
                             return Some(entry.score);
                         }
                         beta = beta.min(entry.score);
                     }
                 }
                 if alpha >= beta {
-                    self.history_stack.pop();
+                    self.repetitions.pop();
+                    self.trace_reason(TraceReason::TranspositionTable);
                     return Some(entry.score);
                 }
             }
         }
 
         if depth == 0 {
-            let score = self.quiescence(board, alpha, beta, turn);
-            self.history_stack.pop();
+            let score = self.quiescence(board, alpha, beta, turn, ply, 0);
+            self.repetitions.pop();
+            self.trace_reason(TraceReason::Quiescence);
             return Some(score);
         }
 
         if let Some(res) = self.probcut(board, beta, depth, turn, ply) {
-            self.history_stack.pop();
+            self.repetitions.pop();
+            self.trace_reason(TraceReason::ProbCut);
             return res;
         }
 
@@ -362,13 +492,15 @@ impl AlphaBetaEngine {
             };
             let margin = 120 * i32::from(depth);
             if eval - margin >= beta {
-                self.history_stack.pop();
+                self.repetitions.pop();
+                self.trace_reason(TraceReason::ReverseFutility);
                 return Some(eval);
             }
         }
 
         if let Some(res) = self.null_move_pruning(board, beta, depth, turn, ply) {
-            self.history_stack.pop();
+            self.repetitions.pop();
+            self.trace_reason(TraceReason::NullMove);
             return res;
         }
 
@@ -376,6 +508,7 @@ impl AlphaBetaEngine {
 
         // Internal Iterative Deepening (IID)
         if best_move_tt.is_none() && depth >= 4 {
+            self.trace_next(TraceKind::Iid, None);
             let _ = self.alpha_beta(board, alpha, beta, depth - 2, turn, ply, None);
             if let Some(entry) = self.tt.probe(hash) {
                 best_move_tt = entry.best_move;
@@ -410,13 +543,15 @@ impl AlphaBetaEngine {
             });
 
             if moves.is_empty() {
-                self.history_stack.pop();
+                self.repetitions.pop();
+                self.trace_reason(TraceReason::NoLegalMoves);
                 return Some(-self.calculate_mate_score(ply));
             }
         }
 
         if moves.is_empty() {
-            self.history_stack.pop();
+            self.repetitions.pop();
+            self.trace_reason(TraceReason::NoLegalMoves);
             return Some(-self.calculate_mate_score(ply));
         }
 
@@ -463,6 +598,7 @@ impl AlphaBetaEngine {
             // 2. It is NOT a capture (captures are important)
             // 3. Not in check (already handled by limit calculation usually, but good to be safe)
             if !in_check && moves_searched >= dynamic_limit && !is_capture {
+                self.trace_pruned(mv, TraceReason::DynamicLimit, ply);
                 continue;
             }
 
@@ -473,6 +609,7 @@ impl AlphaBetaEngine {
                 && !is_capture
                 && moves_searched >= (8 + 5 * (depth as usize * depth as usize))
             {
+                self.trace_pruned(mv, TraceReason::LateMovePruning, ply);
                 continue;
             }
 
@@ -491,6 +628,7 @@ impl AlphaBetaEngine {
             {
                 let margin = 150 * i32::from(depth);
                 if static_eval + margin < alpha {
+                    self.trace_pruned(mv, TraceReason::Futility, ply);
                     continue;
                 }
             }
@@ -511,28 +649,25 @@ impl AlphaBetaEngine {
             {
                 let generator = MoveGenerator::new();
                 if !generator.has_legal_moves(board, turn.opposite()) {
-                    self.history_stack.pop();
+                    self.repetitions.pop();
                     board.undo_move(&mv, captured, turn);
+                    self.trace_pruned(mv, TraceReason::MatingMove, ply);
+                    self.trace_reason(TraceReason::MatingMove);
                     return Some(self.calculate_mate_score(ply + 1));
                 }
             }
 
             // Repetition Check (Pruning)
             // Check if this position has occurred 2 times before (so this is the 3rd)
-            let mut rep_count = 0;
-            for &h in &self.history_stack {
-                if h == board.zobrist_hash {
-                    rep_count += 1;
-                }
-            }
-
-            if rep_count >= 2 {
+            if self.is_repetition(board.zobrist_hash) {
                 board.undo_move(&mv, captured, turn);
                 has_repetition_move = true;
+                self.trace_pruned(mv, TraceReason::RepeatsPosition, ply);
                 continue;
             }
 
             legal_moves_count += 1;
+            self.trace_next(TraceKind::Move, Some(mv));
 
             let score;
 
@@ -567,7 +702,7 @@ impl AlphaBetaEngine {
                 match val {
                     None => {
                         board.undo_move(&mv, captured, turn);
-                        self.history_stack.pop();
+                        self.repetitions.pop();
                         return None;
                     }
                     Some(v) => score = -v,
@@ -624,7 +759,7 @@ impl AlphaBetaEngine {
                 match val {
                     None => {
                         board.undo_move(&mv, captured, turn);
-                        self.history_stack.pop();
+                        self.repetitions.pop();
                         return None;
                     }
                     Some(v) => score = -v,
@@ -652,33 +787,48 @@ impl AlphaBetaEngine {
                     }
                 }
                 tt_flag = TTFlag::LowerBound;
+                self.trace_reason(TraceReason::BetaCutoff);
                 break;
             }
         }
 
         if legal_moves_count == 0 {
-            self.history_stack.pop();
+            self.repetitions.pop();
             // Checkmate or Stalemate
             if in_check {
                 // Checkmate
+                self.trace_reason(TraceReason::NoLegalMoves);
                 return Some(-self.calculate_mate_score(ply));
             }
             if has_repetition_move {
                 // All legal moves were pruned due to repetition -> Draw
-                return Some(0);
+                self.trace_reason(TraceReason::Repetition);
+                return Some(self.evaluator.draw_score(ply));
             }
             // Stalemate (Loss in Xiangqi)
+            self.trace_reason(TraceReason::NoLegalMoves);
             return Some(-self.calculate_mate_score(ply));
         }
 
         self.tt
             .store(hash, best_move_this_node, best_score, depth, tt_flag);
 
-        self.history_stack.pop();
+        self.repetitions.pop();
         Some(best_score)
     }
 
-    fn quiescence(&mut self, board: &mut Board, mut alpha: i32, beta: i32, turn: Color) -> i32 {
+    /// Captures-only search below the horizon. A side in check may not stand pat and searches
+    /// every evasion instead; with `qsearch_checks` the first quiescence ply also tries quiet
+    /// checking moves.
+    fn quiescence(
+        &mut self,
+        board: &mut Board,
+        mut alpha: i32,
+        beta: i32,
+        turn: Color,
+        ply: u8,
+        qply: u8,
+    ) -> i32 {
         self.nodes_searched += 1;
 
         // Q-Search doesn't check time strictly to avoid partial evaluations,
@@ -690,17 +840,30 @@ impl AlphaBetaEngine {
             -self.evaluator.evaluate(board)
         };
 
-        if stand_pat >= beta {
-            return beta;
+        if ply >= MAX_QSEARCH_PLY {
+            return stand_pat;
         }
-        if alpha < stand_pat {
-            alpha = stand_pat;
+
+        let in_check = is_in_check(board, turn);
+        if !in_check {
+            if stand_pat >= beta {
+                return beta;
+            }
+            if alpha < stand_pat {
+                alpha = stand_pat;
+            }
         }
 
         let movegen = EngineMoveGen::new(&self.config, &self.history_table);
-        let captures = movegen.generate_captures(board, turn, &self.killer_moves);
+        let with_checks = !in_check && qply == 0 && self.config.qsearch_checks;
+        let moves = if in_check || with_checks {
+            movegen.generate_moves(board, turn, None, &self.killer_moves, 0)
+        } else {
+            movegen.generate_captures(board, turn, &self.killer_moves)
+        };
 
-        for mv in captures {
+        let mut legal_moves = 0;
+        for mv in moves {
             let captured = board.get_piece(unsafe {
                 BoardCoordinate::new_unchecked(mv.to_row as usize, mv.to_col as usize)
             });
@@ -708,25 +871,33 @@ impl AlphaBetaEngine {
             // Delta Pruning
             // If stand_pat + capture_value + margin < alpha, we can skip this capture.
             // We need to know the value of the captured piece.
-            if let Some(cap_piece) = captured {
-                let cap_val = self.get_piece_value(cap_piece.piece_type);
-                // Margin of 200 for safety (e.g. positional gains)
-                if stand_pat + cap_val + 200 < alpha {
-                    continue;
+            // Evasions are never pruned, a missed one would turn a mate into a stand pat.
+            if !in_check {
+                if let Some(cap_piece) = captured {
+                    let cap_val = self.get_piece_value(cap_piece.piece_type);
+                    // Margin of 200 for safety (e.g. positional gains)
+                    if stand_pat + cap_val + 200 < alpha {
+                        continue;
+                    }
                 }
             }
 
             board.apply_move(&mv, turn);
 
             // Legality Check (Crucial for Q-Search to avoid illegal captures)
-            if crate::logic::rules::is_in_check(board, turn)
-                || crate::logic::rules::is_flying_general(board)
-            {
+            if is_in_check(board, turn) || is_flying_general(board) {
                 board.undo_move(&mv, captured, turn);
                 continue;
             }
+            legal_moves += 1;
 
-            let score = -self.quiescence(board, -beta, -alpha, turn.opposite());
+            // Outside of check, the full move list is only for finding quiet checks
+            if with_checks && captured.is_none() && !is_in_check(board, turn.opposite()) {
+                board.undo_move(&mv, captured, turn);
+                continue;
+            }
+
+            let score = -self.quiescence(board, -beta, -alpha, turn.opposite(), ply + 1, qply + 1);
 
             board.undo_move(&mv, captured, turn);
 
@@ -738,6 +909,10 @@ impl AlphaBetaEngine {
             }
         }
 
+        if in_check && legal_moves == 0 {
+            return -self.calculate_mate_score(ply);
+        }
+
         alpha
     }
 
@@ -755,6 +930,121 @@ impl AlphaBetaEngine {
         }
     }
 
+    /// Weakened search: every root move gets a full-window score from a shallow search, the
+    /// scores are blurred with noise, and a random move close to the best one is played.
+    fn search_limited(
+        &mut self,
+        game_state: &GameState,
+        limit: SearchLimit,
+        excluded_moves: &[Move],
+        strength: StrengthLimit,
+    ) -> Option<(Move, SearchStats)> {
+        self.nodes_searched = 0;
+        self.start_time = Self::now();
+        self.repetitions.clear();
+        self.repetitions
+            .extend(game_state.history.iter().map(|record| record.hash));
+        self.tt.new_search();
+
+        let max_depth = match limit {
+            SearchLimit::Depth(d) => d.min(strength.max_depth),
+            SearchLimit::Time(_) | SearchLimit::Clock { .. } => strength.max_depth,
+        };
+        // A weakened engine has no use for extra time, so it stays within the optimum
+        let time_limit =
+            TimeManager::new(limit, game_state.history.len(), None).map(|tm| tm.optimum());
+
+        let mut board = game_state.board.clone();
+        let turn = game_state.turn;
+
+        let mut root_moves = MoveGenerator::new().generate_legal_moves(&board, turn);
+        root_moves.retain(|m| {
+            !excluded_moves.iter().any(|ex| {
+                m.from_row == ex.from_row
+                    && m.from_col == ex.from_col
+                    && m.to_row == ex.to_row
+                    && m.to_col == ex.to_col
+            })
+        });
+        if root_moves.len() > 1 {
+            // Same rule as the full search: never walk into a third repetition by choice
+            root_moves.retain(|mv| {
+                let captured = piece_at(&board, mv.to_row, mv.to_col);
+                board.apply_move(mv, turn);
+                let repeats = self.is_repetition(board.zobrist_hash);
+                board.undo_move(mv, captured, turn);
+                !repeats
+            });
+        }
+        if root_moves.is_empty() {
+            return None;
+        }
+
+        let mut scored = Vec::new();
+        let mut final_depth = 0;
+        for d in 1..=max_depth.max(1) {
+            // The first depth always completes so there is a real score for every move
+            self.time_limit = if d == 1 { None } else { time_limit };
+            self.node_limit = (d > 1).then_some(strength.max_nodes);
+
+            let mut this_depth = Vec::with_capacity(root_moves.len());
+            for mv in &root_moves {
+                let captured = piece_at(&board, mv.to_row, mv.to_col);
+                board.apply_move(mv, turn);
+                let score = self.alpha_beta(
+                    &mut board,
+                    -500_000,
+                    500_000,
+                    d - 1,
+                    turn.opposite(),
+                    1,
+                    None,
+                );
+                board.undo_move(mv, captured, turn);
+                match score {
+                    Some(s) => this_depth.push(Move { score: -s, ..*mv }),
+                    None => break,
+                }
+            }
+            if this_depth.len() < root_moves.len() {
+                break;
+            }
+            scored = this_depth;
+            final_depth = d;
+        }
+        self.node_limit = None;
+
+        let mut rng = if self.config.deterministic {
+            StdRng::seed_from_u64(self.config.seed ^ game_state.board.zobrist_hash)
+        } else {
+            StdRng::from_entropy()
+        };
+        let noise = strength.eval_noise.max(0);
+        let noisy: Vec<(Move, i32)> = scored
+            .iter()
+            .map(|mv| (*mv, mv.score.saturating_add(rng.gen_range(-noise..=noise))))
+            .collect();
+        let best = noisy.iter().map(|&(_, s)| s).max()?;
+        let candidates: Vec<Move> = noisy
+            .iter()
+            .filter(|&&(_, s)| s >= best.saturating_sub(strength.move_margin))
+            .map(|&(mv, _)| mv)
+            .collect();
+        let chosen = *candidates.choose(&mut rng)?;
+
+        let elapsed = Self::now() - self.start_time;
+        Some((
+            chosen,
+            SearchStats {
+                depth: final_depth,
+                nodes: self.nodes_searched,
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                time_ms: elapsed as u64,
+                tt: self.tt.stats(),
+            },
+        ))
+    }
+
     fn get_piece_value(&self, pt: PieceType) -> i32 {
         match pt {
             PieceType::General => self.config.val_king,
@@ -775,32 +1065,71 @@ impl Searcher for AlphaBetaEngine {
         limit: SearchLimit,
         excluded_moves: &[Move],
     ) -> Option<(Move, SearchStats)> {
+        // The result may then depend only on the position and the config, not on earlier
+        // searches or the speed of the machine
+        let limit = if self.config.deterministic {
+            self.new_game();
+            match limit {
+                SearchLimit::Depth(_) => limit,
+                SearchLimit::Time(_) | SearchLimit::Clock { .. } => {
+                    SearchLimit::Depth(DETERMINISTIC_DEPTH)
+                }
+            }
+        } else {
+            limit
+        };
+        if let Some(strength) = self.config.strength_limit {
+            return self.search_limited(game_state, limit, excluded_moves, strength);
+        }
+
         self.nodes_searched = 0;
         self.start_time = Self::now();
-        self.history_stack.clear();
+        self.repetitions.clear();
 
-        let (max_depth, time_limit) = match limit {
-            SearchLimit::Depth(d) => (d.min(63), None),
-            #[allow(clippy::cast_precision_loss)]
-            SearchLimit::Time(t) => (64, Some(t as f64)), // Max depth 64 (was 20)
+        let max_depth = match limit {
+            SearchLimit::Depth(d) => d.min(63),
+            SearchLimit::Time(_) | SearchLimit::Clock { .. } => 64,
         };
-        self.time_limit = time_limit;
-        let soft_limit = time_limit.map(|t| t * 0.6);
+        // Replying to a capture on the same square is usually forced; the time manager
+        // cuts the search short once such a move has stayed best for a few iterations
+        let recapture_square = game_state
+            .history
+            .last()
+            .filter(|record| record.captured.is_some())
+            .and_then(|record| {
+                Some((
+                    u8::try_from(record.to.row).ok()?,
+                    u8::try_from(record.to.col).ok()?,
+                ))
+            });
+        let mut time_manager = TimeManager::new(limit, game_state.history.len(), recapture_square);
+        self.time_limit = time_manager.as_ref().map(TimeManager::maximum);
 
         let mut board = game_state.board.clone();
         let board = &mut board;
         let turn = game_state.turn;
 
         // Initialize history stack
-        self.history_stack.clear();
-        for record in &game_state.history {
-            self.history_stack.push(record.hash);
-        }
+        self.repetitions.clear();
+        self.repetitions
+            .extend(game_state.history.iter().map(|record| record.hash));
+
+        let mut root_moves = MoveGenerator::new().generate_legal_moves(board, turn);
+        root_moves.retain(|m| {
+            !excluded_moves.iter().any(|ex| {
+                m.from_row == ex.from_row
+                    && m.from_col == ex.from_col
+                    && m.to_row == ex.to_row
+                    && m.to_col == ex.to_col
+            })
+        });
 
         let mut best_move = None;
         let mut final_depth = 0;
         let mut previous_score: Option<i32> = None;
 
+        self.tt.new_search();
+
         // History Aging
         // Decay history scores to adapt to new positions
         for row in self.history_table.iter_mut() {
@@ -810,10 +1139,10 @@ impl Searcher for AlphaBetaEngine {
         }
 
         for d in 1..=max_depth {
-            // Check soft limit before starting new depth
-            if let Some(sl) = soft_limit {
-                let elapsed = Self::now() - self.start_time;
-                if elapsed > sl {
+            // Another depth takes longer than all the previous ones together, so only start
+            // it while within the time manager's current target
+            if let Some(tm) = &time_manager {
+                if tm.should_stop(Self::now() - self.start_time) {
                     break;
                 }
             }
@@ -842,28 +1171,15 @@ impl Searcher for AlphaBetaEngine {
                 let movegen = EngineMoveGen::new(&self.config, &self.history_table);
                 let mut moves = movegen.generate_moves(board, turn, tt_move, &self.killer_moves, d);
 
-                // Filter excluded moves at root
-                if !excluded_moves.is_empty() {
-                    moves.retain(|m| {
-                        !excluded_moves.iter().any(|ex| {
-                            m.from_row == ex.from_row
-                                && m.from_col == ex.from_col
-                                && m.to_row == ex.to_row
-                                && m.to_col == ex.to_col
-                        })
-                    });
-                }
-
-                // Filter for legal moves immediately to handle single-move exception
-                moves.retain(|mv| {
-                    let captured = board.get_piece(unsafe {
-                        BoardCoordinate::new_unchecked(mv.to_row as usize, mv.to_col as usize)
-                    });
-                    board.apply_move(mv, turn);
-                    let legal = !crate::logic::rules::is_in_check(board, turn)
-                        && !crate::logic::rules::is_flying_general(board);
-                    board.undo_move(mv, captured, turn);
-                    legal
+                // Keep the ordering, but only for legal, non-excluded moves; this also
+                // handles the single-move exception below
+                moves.retain(|m| {
+                    root_moves.iter().any(|r| {
+                        m.from_row == r.from_row
+                            && m.from_col == r.from_col
+                            && m.to_row == r.to_row
+                            && m.to_col == r.to_col
+                    })
                 });
 
                 let is_single_move = moves.len() == 1;
@@ -874,6 +1190,9 @@ impl Searcher for AlphaBetaEngine {
 
                 let mut time_out = false;
                 let mut moves_searched = 0;
+                if let Some(trace) = &mut self.trace {
+                    trace.begin_root(d, alpha, beta);
+                }
 
                 for mv in moves {
                     let captured = board.get_piece(unsafe {
@@ -884,21 +1203,16 @@ impl Searcher for AlphaBetaEngine {
                     if !is_single_move {
                         // 3-Fold Repetition Check at Root
                         // Check if this position has occurred 2 times before (so this is the 3rd)
-                        let mut rep_count = 0;
-                        for &h in &self.history_stack {
-                            if h == board.zobrist_hash {
-                                rep_count += 1;
-                            }
-                        }
-
-                        if rep_count >= 2 {
+                        if self.is_repetition(board.zobrist_hash) {
                             board.undo_move(&mv, captured, turn);
+                            self.trace_pruned(mv, TraceReason::RepeatsPosition, 0);
                             continue;
                         }
                     }
 
                     // Absolute Checkmate Detection at Root - REMOVED for performance
                     // Trust the search to find mates.
+                    self.trace_next(TraceKind::Move, Some(mv));
 
                     let score_opt;
                     if moves_searched == 0 {
@@ -950,6 +1264,9 @@ impl Searcher for AlphaBetaEngine {
                     moves_searched += 1;
                 }
 
+                if let Some(trace) = &mut self.trace {
+                    trace.end_root(Some(best_score_this_iteration), !time_out);
+                }
                 if time_out {
                     // If we timed out during a depth, don't use partial results unless we have nothing else
                     if best_move.is_none() && current_best_move_this_iteration.is_some() {
@@ -959,8 +1276,21 @@ impl Searcher for AlphaBetaEngine {
                     break;
                 }
 
+                #[cfg(feature = "tracing")]
+                if best_score_this_iteration <= alpha_orig || best_score_this_iteration >= beta_orig
+                {
+                    search_log::aspiration_fail(
+                        d,
+                        alpha_orig,
+                        beta_orig,
+                        best_score_this_iteration,
+                    );
+                }
                 if best_score_this_iteration <= alpha_orig {
-                    // Fail Low
+                    // Fail Low: the best move so far is in trouble, worth spending more on
+                    if let Some(tm) = &mut time_manager {
+                        tm.fail_low();
+                    }
                     alpha = (alpha_orig.saturating_sub(delta)).max(-500000);
                     delta = delta.saturating_add(delta / 2);
                     continue;
@@ -976,6 +1306,18 @@ impl Searcher for AlphaBetaEngine {
                     best_move = Some(mv);
                     final_depth = d;
                     previous_score = Some(best_score_this_iteration);
+                    if let Some(tm) = &mut time_manager {
+                        tm.iteration_done(d, mv, best_score_this_iteration);
+                    }
+                    #[cfg(feature = "tracing")]
+                    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    search_log::iteration(
+                        d,
+                        mv,
+                        best_score_this_iteration,
+                        self.nodes_searched,
+                        (Self::now() - self.start_time) as u64,
+                    );
                 }
                 break;
             }
@@ -986,7 +1328,19 @@ impl Searcher for AlphaBetaEngine {
         }
 
         let elapsed = Self::now() - self.start_time;
-        best_move.map(|mv| {
+        #[cfg(feature = "tracing")]
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        search_log::search_done(
+            final_depth,
+            self.nodes_searched,
+            elapsed as u64,
+            self.tt.stats(),
+        );
+        best_move.map(|mut mv| {
+            // Score of the last completed depth, from the side to move's point of view
+            if let Some(score) = previous_score {
+                mv.score = score;
+            }
             (
                 mv,
                 SearchStats {
@@ -994,8 +1348,110 @@ impl Searcher for AlphaBetaEngine {
                     nodes: self.nodes_searched,
                     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
                     time_ms: elapsed as u64,
+                    tt: self.tt.stats(),
                 },
             )
         })
     }
 }
+
+/// Attacker moves deeper than this many plies from the mate must give check.
+/// Keeps the search narrow enough for mate-in-3 and beyond while still finding
+/// quiet first moves in short mates.
+const QUIET_MOVE_PLIES: u8 = 3;
+
+/// Looks for a forced mate for the side to move within `max_plies` half-moves.
+///
+/// Returns the mating line (attacker and defender moves alternating, ending with the
+/// mating move) or `None` if no mate was proven. Shorter mates are tried first.
+/// Since a side without legal moves loses, "mate" includes stalemating the opponent.
+pub fn search_mate(game_state: &GameState, max_plies: u8) -> Option<Vec<Move>> {
+    let generator = MoveGenerator::new();
+    let mut board = game_state.board.clone();
+    let attacker = game_state.turn;
+
+    (1..=max_plies).step_by(2).find_map(|plies| {
+        let mut line = Vec::new();
+        mate_attack(&generator, &mut board, attacker, plies, &mut line).then_some(line)
+    })
+}
+
+/// OR node: true if one of `attacker`'s moves mates within `plies_left`.
+fn mate_attack(
+    generator: &MoveGenerator,
+    board: &mut Board,
+    attacker: Color,
+    plies_left: u8,
+    line: &mut Vec<Move>,
+) -> bool {
+    let defender = attacker.opposite();
+    let mut candidates: Vec<(Move, bool)> = generator
+        .generate_moves(board, attacker)
+        .into_iter()
+        .filter_map(|mv| {
+            let gives_check = gives_check(board, &mv, attacker);
+            (gives_check || plies_left <= QUIET_MOVE_PLIES).then_some((mv, gives_check))
+        })
+        .collect();
+    // Checks first: they are by far the most likely to mate
+    candidates.sort_by_key(|&(_, gives_check)| !gives_check);
+
+    for (mv, _) in candidates {
+        let captured = piece_at(board, mv.to_row, mv.to_col);
+        board.apply_move(&mv, attacker);
+        let mut rest = Vec::new();
+        let mated = if plies_left <= 1 {
+            !generator.has_legal_moves(board, defender)
+        } else {
+            mate_defend(generator, board, attacker, plies_left - 1, &mut rest)
+        };
+        board.undo_move(&mv, captured, attacker);
+
+        if mated {
+            line.push(mv);
+            line.extend(rest);
+            return true;
+        }
+    }
+    false
+}
+
+/// AND node: true if every defender reply still allows mate within `plies_left`.
+/// `line` receives the reply that holds out longest.
+fn mate_defend(
+    generator: &MoveGenerator,
+    board: &mut Board,
+    attacker: Color,
+    plies_left: u8,
+    line: &mut Vec<Move>,
+) -> bool {
+    let defender = attacker.opposite();
+    let mut best_defence: Option<Vec<Move>> = None;
+
+    for reply in generator.generate_moves(board, defender) {
+        let captured = piece_at(board, reply.to_row, reply.to_col);
+        board.apply_move(&reply, defender);
+        let mut rest = Vec::new();
+        let mated = mate_attack(generator, board, attacker, plies_left - 1, &mut rest);
+        board.undo_move(&reply, captured, defender);
+
+        if !mated {
+            return false;
+        }
+        if best_defence
+            .as_ref()
+            .is_none_or(|longest| rest.len() + 1 > longest.len())
+        {
+            rest.insert(0, reply);
+            best_defence = Some(rest);
+        }
+    }
+
+    // No replies at all means the defender is already lost
+    line.extend(best_defence.unwrap_or_default());
+    true
+}
+
+fn piece_at(board: &Board, row: u8, col: u8) -> Option<crate::logic::board::Piece> {
+    BoardCoordinate::new(row as usize, col as usize).and_then(|pos| board.get_piece(pos))
+}
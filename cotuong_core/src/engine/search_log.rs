@@ -0,0 +1,68 @@
+//! `tracing` events for the engine's decisions, behind the `tracing` feature: one per
+//! finished iteration, one per aspiration window failure and one per search with the hash
+//! table's statistics. `log_to_file` collects them as JSON lines for offline analysis.
+
+use crate::engine::match_runner::format_iccs;
+use crate::engine::tt::TTStats;
+use crate::engine::Move;
+use crate::logic::board::BoardCoordinate;
+use std::fs::File;
+use std::io;
+use std::sync::Mutex;
+
+const TARGET: &str = "cotuong_core::search";
+
+fn iccs(mv: Move) -> String {
+    BoardCoordinate::new(usize::from(mv.from_row), usize::from(mv.from_col))
+        .zip(BoardCoordinate::new(
+            usize::from(mv.to_row),
+            usize::from(mv.to_col),
+        ))
+        .map_or_else(String::new, |(from, to)| format_iccs(from, to))
+}
+
+pub(crate) fn iteration(depth: u8, mv: Move, score: i32, nodes: u32, time_ms: u64) {
+    tracing::info!(
+        target: TARGET,
+        depth,
+        best_move = iccs(mv),
+        score,
+        nodes,
+        time_ms,
+        "iteration"
+    );
+}
+
+/// The root search at `depth` scored `score` outside `[alpha, beta]` and is searched again.
+pub(crate) fn aspiration_fail(depth: u8, alpha: i32, beta: i32, score: i32) {
+    let side = if score <= alpha { "low" } else { "high" };
+    tracing::info!(target: TARGET, depth, alpha, beta, score, side, "aspiration_fail");
+}
+
+pub(crate) fn search_done(depth: u8, nodes: u32, time_ms: u64, tt: TTStats) {
+    tracing::info!(
+        target: TARGET,
+        depth,
+        nodes,
+        time_ms,
+        tt_probes = tt.probes,
+        tt_hits = tt.hits,
+        tt_stores = tt.stores,
+        tt_hashfull = tt.hashfull,
+        "search_done"
+    );
+}
+
+/// Writes every event from now on to `path`, one JSON object per line.
+///
+/// # Errors
+/// If the file cannot be created or another subscriber is already installed.
+pub fn log_to_file(path: &str) -> io::Result<()> {
+    let file = File::create(path)?;
+    tracing_subscriber::fmt()
+        .json()
+        .flatten_event(true)
+        .with_writer(Mutex::new(file))
+        .try_init()
+        .map_err(|e| io::Error::other(e.to_string()))
+}
@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::config::{EngineConfig, StrengthLimit};
+    use crate::engine::search::AlphaBetaEngine;
+    use crate::engine::{SearchLimit, Searcher};
+    use crate::logic::board::Board;
+    use crate::logic::game::GameState;
+    use crate::logic::puzzle::parse_iccs;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    fn beginner_engine() -> AlphaBetaEngine {
+        AlphaBetaEngine::new(Arc::new(EngineConfig {
+            strength_limit: Some(StrengthLimit::BEGINNER),
+            ..EngineConfig::default()
+        }))
+    }
+
+    #[test]
+    fn test_limited_engine_respects_depth_cap() {
+        let mut engine = beginner_engine();
+        let (_, stats) = engine
+            .search(&GameState::new(), SearchLimit::Time(1000), &[])
+            .unwrap();
+        assert!(stats.depth >= 1);
+        assert!(stats.depth <= StrengthLimit::BEGINNER.max_depth);
+    }
+
+    #[test]
+    fn test_limited_engine_varies_opening_moves() {
+        let mut engine = beginner_engine();
+        let moves: HashSet<_> = (0..20)
+            .filter_map(|_| engine.search(&GameState::new(), SearchLimit::Time(1000), &[]))
+            .map(|(mv, _)| (mv.from_row, mv.from_col, mv.to_row, mv.to_col))
+            .collect();
+        assert!(moves.len() > 1, "beginner level should not always play the same move");
+    }
+
+    #[test]
+    fn test_deterministic_engine_repeats_itself() {
+        let config = Arc::new(EngineConfig {
+            strength_limit: Some(StrengthLimit::BEGINNER),
+            deterministic: true,
+            seed: 7,
+            ..EngineConfig::default()
+        });
+        let state = GameState::new();
+        let mut engine = AlphaBetaEngine::new(config.clone());
+        let (first, first_stats) = engine.search(&state, SearchLimit::Time(1), &[]).unwrap();
+        for _ in 0..5 {
+            // A 1 ms limit would cut the search short if it were not turned into a depth
+            let (mv, stats) = engine.search(&state, SearchLimit::Time(1), &[]).unwrap();
+            assert_eq!(
+                (mv, stats.depth, stats.nodes),
+                (first, first_stats.depth, first_stats.nodes)
+            );
+        }
+
+        // Full strength: an engine that searched before counts the same nodes as a new one
+        let config = Arc::new(EngineConfig {
+            deterministic: true,
+            tt_size_mb: 1,
+            ..EngineConfig::default()
+        });
+        let mut used = AlphaBetaEngine::new(config.clone());
+        let mut after_move = GameState::new();
+        let (from, to) = parse_iccs("h2e2").unwrap();
+        after_move.make_move(from, to).unwrap();
+        used.search(&after_move, SearchLimit::Depth(4), &[]);
+        let (mv, stats) = used.search(&state, SearchLimit::Depth(4), &[]).unwrap();
+        let (fresh_mv, fresh_stats) = AlphaBetaEngine::new(config)
+            .search(&state, SearchLimit::Depth(4), &[])
+            .unwrap();
+        assert_eq!((mv, stats.nodes), (fresh_mv, fresh_stats.nodes));
+    }
+
+    #[test]
+    fn test_limited_engine_still_takes_free_chariot() {
+        // The chariots attack each other; Red should capture first
+        let (board, turn) = Board::from_fen("4k4/9/9/9/4r4/4R4/9/9/9/3K5 w").unwrap();
+        let state = GameState::from_position(board, turn);
+
+        let mut engine = beginner_engine();
+        for _ in 0..10 {
+            let (mv, _) = engine.search(&state, SearchLimit::Time(1000), &[]).unwrap();
+            assert_eq!((mv.from_row, mv.from_col, mv.to_row, mv.to_col), (4, 4, 5, 4));
+        }
+    }
+}
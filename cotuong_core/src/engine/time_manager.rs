@@ -0,0 +1,195 @@
+use crate::engine::{Move, SearchLimit};
+
+/// Kept back from every clock allocation for message passing and rendering.
+const MOVE_OVERHEAD_MS: f64 = 50.0;
+/// Moves the game is assumed to still last at the start, and at the very least later on.
+const MAX_MOVES_LEFT: usize = 45;
+const MIN_MOVES_LEFT: usize = 15;
+/// Share of a fixed per-move time used before starting another iteration looks pointless.
+const FIXED_TIME_OPTIMUM: f64 = 0.6;
+/// A score drop of this much (or a root fail low) stretches the budget by the most.
+const FALLING_SCORE: i32 = 100;
+/// Budget multiplier once an obvious recapture stayed best for a few iterations.
+const OBVIOUS_MOVE_SCALE: f64 = 0.3;
+const OBVIOUS_MOVE_STABLE_ITERATIONS: u32 = 3;
+
+/// Decides how long one search may run: an optimum checked between iterations, which
+/// grows when the best move keeps changing or the score falls and shrinks for an obvious
+/// recapture, and a hard maximum the search never exceeds.
+#[derive(Debug, Clone)]
+pub struct TimeManager {
+    optimum: f64,
+    maximum: f64,
+    /// Decaying count of best-move changes between iterations.
+    instability: f64,
+    /// 1.0 when the score holds, up to 2.0 when it is dropping.
+    falling: f64,
+    obvious: bool,
+    last_best: Option<Move>,
+    last_score: Option<i32>,
+    stable_iterations: u32,
+    /// Square of the opponent's last capture, where an obvious recapture would land.
+    recapture_square: Option<(u8, u8)>,
+}
+
+impl TimeManager {
+    /// Budget for the move at `ply` (half-moves played so far). `None` for depth-limited
+    /// searches, which have no time budget.
+    #[must_use]
+    pub fn new(limit: SearchLimit, ply: usize, recapture_square: Option<(u8, u8)>) -> Option<Self> {
+        #[allow(clippy::cast_precision_loss)]
+        let (optimum, maximum) = match limit {
+            SearchLimit::Depth(_) => return None,
+            SearchLimit::Time(ms) => {
+                let ms = ms as f64;
+                (ms * FIXED_TIME_OPTIMUM, ms)
+            }
+            SearchLimit::Clock {
+                remaining_ms,
+                increment_ms,
+            } => {
+                let usable = (remaining_ms as f64 - MOVE_OVERHEAD_MS).max(1.0);
+                let moves_left = MAX_MOVES_LEFT.saturating_sub(ply / 4).max(MIN_MOVES_LEFT);
+                let optimum =
+                    (usable / moves_left as f64 + increment_ms as f64 * 0.75).min(usable * 0.5);
+                (optimum, (optimum * 4.0).min(usable * 0.3).max(optimum))
+            }
+        };
+        Some(Self {
+            optimum,
+            maximum,
+            instability: 0.0,
+            falling: 1.0,
+            obvious: false,
+            last_best: None,
+            last_score: None,
+            stable_iterations: 0,
+            recapture_square,
+        })
+    }
+
+    /// Hard limit in milliseconds; the search aborts mid-iteration past it.
+    #[must_use]
+    pub const fn maximum(&self) -> f64 {
+        self.maximum
+    }
+
+    /// Current target in milliseconds, after the adjustments so far.
+    #[must_use]
+    pub fn optimum(&self) -> f64 {
+        let obvious = if self.obvious {
+            OBVIOUS_MOVE_SCALE
+        } else {
+            1.0
+        };
+        let scale = (1.0 + 0.5 * self.instability) * self.falling * obvious;
+        (self.optimum * scale.clamp(0.25, 3.0)).min(self.maximum)
+    }
+
+    /// Whether to stop instead of starting another iteration after `elapsed_ms`.
+    #[must_use]
+    pub fn should_stop(&self, elapsed_ms: f64) -> bool {
+        elapsed_ms >= self.optimum()
+    }
+
+    /// The root search failed low: the best move is in trouble, so allow more time.
+    pub const fn fail_low(&mut self) {
+        self.falling = 2.0;
+    }
+
+    /// Records the result of a completed iteration at `depth`.
+    pub fn iteration_done(&mut self, depth: u8, best: Move, score: i32) {
+        let same_move = self.last_best.is_some_and(|last| {
+            (last.from_row, last.from_col, last.to_row, last.to_col)
+                == (best.from_row, best.from_col, best.to_row, best.to_col)
+        });
+        self.instability *= 0.5;
+        if same_move {
+            self.stable_iterations += 1;
+        } else {
+            self.stable_iterations = 0;
+            if depth > 1 {
+                self.instability += 1.0;
+            }
+        }
+
+        let drop = self
+            .last_score
+            .map_or(0, |last| (last - score).clamp(0, FALLING_SCORE));
+        self.falling = f64::from(drop).mul_add(1.0 / f64::from(FALLING_SCORE), 1.0);
+
+        self.obvious = self.recapture_square == Some((best.to_row, best.to_col))
+            && self.stable_iterations >= OBVIOUS_MOVE_STABLE_ITERATIONS
+            && drop == 0;
+
+        self.last_best = Some(best);
+        self.last_score = Some(score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(to_row: u8, to_col: u8) -> Move {
+        Move {
+            from_row: 0,
+            from_col: 0,
+            to_row,
+            to_col,
+            score: 0,
+        }
+    }
+
+    #[test]
+    fn test_budgets() {
+        assert!(TimeManager::new(SearchLimit::Depth(5), 0, None).is_none());
+
+        let fixed = TimeManager::new(SearchLimit::Time(1000), 0, None).unwrap();
+        assert!((fixed.optimum() - 600.0).abs() < 1e-9);
+        assert!((fixed.maximum() - 1000.0).abs() < 1e-9);
+
+        let clock = SearchLimit::Clock {
+            remaining_ms: 300_000,
+            increment_ms: 2000,
+        };
+        let early = TimeManager::new(clock, 0, None).unwrap();
+        let late = TimeManager::new(clock, 120, None).unwrap();
+        assert!(early.optimum() < late.optimum());
+        assert!(early.maximum() <= 300_000.0 * 0.3);
+
+        // Almost flagged: never plan to use more than half of what is left
+        let short = SearchLimit::Clock {
+            remaining_ms: 500,
+            increment_ms: 5000,
+        };
+        let tm = TimeManager::new(short, 40, None).unwrap();
+        assert!(tm.optimum() <= 225.0 && tm.maximum() <= 450.0);
+    }
+
+    #[test]
+    fn test_adjustments() {
+        let limit = SearchLimit::Time(1000);
+        let mut stable = TimeManager::new(limit, 0, None).unwrap();
+        let mut unstable = stable.clone();
+        for depth in 1..=6 {
+            stable.iteration_done(depth, mv(5, 4), 20);
+            unstable.iteration_done(depth, mv(5, depth % 2), 20);
+        }
+        assert!(unstable.optimum() > stable.optimum());
+
+        let mut falling = TimeManager::new(limit, 0, None).unwrap();
+        falling.iteration_done(1, mv(5, 4), 50);
+        falling.iteration_done(2, mv(5, 4), -50);
+        assert!(falling.optimum() > stable.optimum());
+        falling.fail_low();
+        assert!((falling.optimum() - 1000.0).abs() < 1e-9);
+
+        let mut recapture = TimeManager::new(limit, 0, Some((5, 4))).unwrap();
+        for depth in 1..=4 {
+            recapture.iteration_done(depth, mv(5, 4), 20);
+        }
+        assert!(recapture.optimum() < stable.optimum() / 2.0);
+        assert!(recapture.should_stop(300.0));
+    }
+}
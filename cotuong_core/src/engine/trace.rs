@@ -0,0 +1,301 @@
+use crate::engine::Move;
+use serde::{Deserialize, Serialize};
+
+/// Why a node stopped searching, or why a move was skipped without a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceReason {
+    MateDistance,
+    Repetition,
+    TranspositionTable,
+    Quiescence,
+    ProbCut,
+    ReverseFutility,
+    NullMove,
+    NoLegalMoves,
+    /// One of the moves left the opponent without a legal reply.
+    MatingMove,
+    BetaCutoff,
+    TimeUp,
+    DynamicLimit,
+    LateMovePruning,
+    Futility,
+    /// The move would repeat a position for the third time.
+    RepeatsPosition,
+}
+
+/// What started a search below a node: a move, or one of the reduced probes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceKind {
+    Root,
+    Move,
+    NullMove,
+    ProbCut,
+    /// Internal iterative deepening: a shallower search of the same node for a first move.
+    Iid,
+    /// Singular extension check: the same node without the hash move.
+    Singular,
+    /// A move skipped without a search; `reason` says why.
+    Pruned,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceNode {
+    pub kind: TraceKind,
+    /// The move leading here; `None` at the root and for probes that play no move.
+    pub mv: Option<Move>,
+    pub depth: u8,
+    pub alpha: i32,
+    pub beta: i32,
+    /// From the side to move's point of view; `None` when time ran out or the move was pruned.
+    pub score: Option<i32>,
+    pub reason: Option<TraceReason>,
+    pub children: Vec<TraceNode>,
+}
+
+impl TraceNode {
+    /// Nodes in this subtree, this one included.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        1 + self.children.iter().map(Self::node_count).sum::<usize>()
+    }
+}
+
+/// How much of the tree is kept. Nodes past either cap are still searched, only not recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceLimits {
+    pub max_nodes: usize,
+    /// Deepest ply below the root that is recorded.
+    pub max_ply: u8,
+}
+
+impl Default for TraceLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 5000,
+            max_ply: 4,
+        }
+    }
+}
+
+/// Records the tree of one root pass at a time; `AlphaBetaEngine` feeds it while tracing.
+#[derive(Debug, Clone)]
+pub struct SearchTrace {
+    limits: TraceLimits,
+    /// Nodes entered but not yet finished, root first.
+    open: Vec<TraceNode>,
+    recorded: usize,
+    /// Searches entered below the last open node without being recorded, past a cap.
+    unrecorded: usize,
+    /// What the next entered node stands for, set by the caller before it recurses and
+    /// restored after each child, so a re-search of the same move is labelled alike.
+    next: (TraceKind, Option<Move>),
+    /// Root of the last root pass that ran to the end.
+    last: Option<TraceNode>,
+}
+
+impl SearchTrace {
+    #[must_use]
+    pub const fn new(limits: TraceLimits) -> Self {
+        Self {
+            limits,
+            open: Vec::new(),
+            recorded: 0,
+            unrecorded: 0,
+            next: (TraceKind::Move, None),
+            last: None,
+        }
+    }
+
+    pub(crate) fn begin_root(&mut self, depth: u8, alpha: i32, beta: i32) {
+        self.open.clear();
+        self.recorded = 1;
+        self.unrecorded = 0;
+        self.open.push(TraceNode {
+            kind: TraceKind::Root,
+            mv: None,
+            depth,
+            alpha,
+            beta,
+            score: None,
+            reason: None,
+            children: Vec::new(),
+        });
+    }
+
+    /// Closes the root pass; an unfinished one leaves the previous tree in place.
+    pub(crate) fn end_root(&mut self, score: Option<i32>, finished: bool) {
+        let root = self.open.drain(..).next();
+        if finished {
+            self.last = root.map(|mut root| {
+                root.score = score;
+                root
+            });
+        }
+    }
+
+    pub(crate) fn set_next(&mut self, kind: TraceKind, mv: Option<Move>) {
+        self.next = (kind, mv);
+    }
+
+    /// Whether a node at `ply` below the node being searched gets recorded.
+    fn has_room(&self, ply: u8) -> bool {
+        !self.open.is_empty()
+            && self.unrecorded == 0
+            && self.recorded < self.limits.max_nodes
+            && ply <= self.limits.max_ply
+    }
+
+    /// Opens a node for a search at `ply`. Every `enter` is paired with an `exit`.
+    pub(crate) fn enter(&mut self, alpha: i32, beta: i32, depth: u8, ply: u8) {
+        if !self.has_room(ply) {
+            self.unrecorded += 1;
+            return;
+        }
+        self.recorded += 1;
+        let (kind, mv) = self.next;
+        self.open.push(TraceNode {
+            kind,
+            mv,
+            depth,
+            alpha,
+            beta,
+            score: None,
+            reason: None,
+            children: Vec::new(),
+        });
+    }
+
+    /// Closes the node opened by the matching `enter`.
+    pub(crate) fn exit(&mut self, score: Option<i32>) {
+        if self.unrecorded > 0 {
+            self.unrecorded -= 1;
+            return;
+        }
+        if self.open.len() < 2 {
+            return;
+        }
+        if let Some(mut node) = self.open.pop() {
+            node.score = score;
+            if score.is_none() && node.reason.is_none() {
+                node.reason = Some(TraceReason::TimeUp);
+            }
+            self.next = (node.kind, node.mv);
+            if let Some(parent) = self.open.last_mut() {
+                parent.children.push(node);
+            }
+        }
+    }
+
+    /// Why the node being searched stopped; the first reason given sticks.
+    pub(crate) fn reason(&mut self, reason: TraceReason) {
+        if self.unrecorded > 0 {
+            return;
+        }
+        if let Some(node) = self.open.last_mut() {
+            node.reason.get_or_insert(reason);
+        }
+    }
+
+    /// Records `mv` as skipped by the node being searched at `ply`.
+    pub(crate) fn pruned(&mut self, mv: Move, reason: TraceReason, ply: u8) {
+        if !self.has_room(ply + 1) {
+            return;
+        }
+        self.recorded += 1;
+        if let Some(parent) = self.open.last_mut() {
+            parent.children.push(TraceNode {
+                kind: TraceKind::Pruned,
+                mv: Some(mv),
+                depth: 0,
+                alpha: parent.alpha,
+                beta: parent.beta,
+                score: None,
+                reason: Some(reason),
+                children: Vec::new(),
+            });
+        }
+    }
+
+    /// The tree of the last finished root pass.
+    #[must_use]
+    pub const fn last(&self) -> Option<&TraceNode> {
+        self.last.as_ref()
+    }
+
+    pub fn take_last(&mut self) -> Option<TraceNode> {
+        self.last.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::config::EngineConfig;
+    use crate::engine::search::AlphaBetaEngine;
+    use crate::engine::{SearchLimit, Searcher};
+    use crate::logic::board::Board;
+    use crate::logic::game::GameState;
+    use crate::logic::puzzle::parse_iccs;
+    use std::sync::Arc;
+
+    fn engine() -> AlphaBetaEngine {
+        AlphaBetaEngine::new(Arc::new(EngineConfig {
+            tt_size_mb: 1,
+            ..EngineConfig::default()
+        }))
+    }
+
+    fn deepest_ply(node: &TraceNode) -> usize {
+        node.children.iter().map(|c| 1 + deepest_ply(c)).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_trace_leaves_search_unchanged() {
+        let state = GameState::new();
+        let (plain, plain_stats) = engine()
+            .search(&state, SearchLimit::Depth(4), &[])
+            .unwrap();
+
+        let mut traced = engine();
+        let limits = TraceLimits {
+            max_nodes: 300,
+            max_ply: 2,
+        };
+        traced.set_trace(Some(limits));
+        let (mv, stats) = traced.search(&state, SearchLimit::Depth(4), &[]).unwrap();
+        assert_eq!((mv, stats.nodes), (plain, plain_stats.nodes));
+
+        let tree = traced.take_trace().unwrap();
+        assert_eq!(tree.kind, TraceKind::Root);
+        assert_eq!(tree.depth, 4);
+        assert!(tree.node_count() <= limits.max_nodes);
+        assert!(deepest_ply(&tree) <= usize::from(limits.max_ply));
+        assert!(tree.children.iter().any(|c| c.kind == TraceKind::Move));
+    }
+
+    #[test]
+    fn test_trace_shows_mate() {
+        let (board, turn) = Board::from_fen("3k5/9/9/9/R8/9/9/9/9/4K4 w").unwrap();
+        let state = GameState::from_position(board, turn);
+        let mut engine = engine();
+        engine.set_trace(Some(TraceLimits::default()));
+        engine.search(&state, SearchLimit::Depth(2), &[]).unwrap();
+
+        let (from, to) = parse_iccs("a5d5").unwrap();
+        let tree = engine.take_trace().unwrap();
+        let mate = tree
+            .children
+            .iter()
+            .find(|c| {
+                c.mv.is_some_and(|m| {
+                    (usize::from(m.from_row), usize::from(m.from_col)) == (from.row, from.col)
+                        && (usize::from(m.to_row), usize::from(m.to_col)) == (to.row, to.col)
+                })
+            })
+            .unwrap();
+        assert_eq!(mate.reason, Some(TraceReason::NoLegalMoves));
+        assert!(mate.score.unwrap() < -20_000);
+        // Taken, not copied
+        assert!(engine.take_trace().is_none());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::engine::Move;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TTFlag {
@@ -14,6 +15,8 @@ pub struct TTEntry {
     pub score: i32,
     pub depth: u8,
     pub flag: TTFlag,
+    /// `TranspositionTable::generation` when the entry was last written.
+    pub generation: u8,
 }
 
 impl Default for TTEntry {
@@ -24,48 +27,101 @@ impl Default for TTEntry {
             score: 0,
             depth: 0,
             flag: TTFlag::Exact,
+            generation: 0,
         }
     }
 }
 
+/// Table usage for one search, reported in `SearchStats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TTStats {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    /// Permille of sampled slots written during this search (UCI `hashfull`).
+    pub hashfull: u16,
+}
+
+impl TTStats {
+    /// Share of probes that found their position, in percent.
+    #[must_use]
+    pub fn hit_rate(&self) -> u64 {
+        self.hits * 100 / self.probes.max(1)
+    }
+}
+
+/// Two entries per index: slot 0 keeps the deepest result of the current search, slot 1 is
+/// always replaced, so shallow entries near the leaves cannot push out expensive ones and
+/// deep entries cannot lock a bucket forever. An entry pushed out of slot 0 moves to slot 1.
+type Bucket = [TTEntry; 2];
+
 pub struct TranspositionTable {
-    entries: Vec<TTEntry>,
+    buckets: Vec<Bucket>,
     mask: usize,
+    /// Bumped by `new_search`; entries from older searches are replaced first.
+    generation: u8,
+    /// Counters since the last `new_search`.
+    stats: TTStats,
 }
 
 impl TranspositionTable {
     pub fn new(size_mb: usize) -> Self {
-        let entry_size = std::mem::size_of::<TTEntry>();
-        let num_entries = (size_mb * 1024 * 1024) / entry_size;
+        let bucket_size = std::mem::size_of::<Bucket>();
+        let num_buckets = (size_mb * 1024 * 1024) / bucket_size;
 
         // Power of 2 size for efficient masking
         let mut size = 1;
-        while size <= num_entries {
+        while size <= num_buckets {
             size *= 2;
         }
         size /= 2; // Keep it within memory limit
 
         if size == 0 {
-            size = 1024; // Minimum size
+            size = 512; // Minimum size
         }
 
         Self {
-            entries: vec![TTEntry::default(); size],
+            buckets: vec![[TTEntry::default(); 2]; size],
             mask: size - 1,
+            generation: 0,
+            stats: TTStats::default(),
+        }
+    }
+
+    /// Call once per root search. Entries from earlier searches stay probeable but lose
+    /// their claim on the depth-preferred slot, so a long game does not fill the table with
+    /// deep results for positions that can no longer occur.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.stats = TTStats::default();
+    }
+
+    /// Usage since the last `new_search`, with `hashfull` sampled from the first thousand
+    /// buckets.
+    pub fn stats(&self) -> TTStats {
+        let sample = &self.buckets[..self.buckets.len().min(1000)];
+        let used = sample
+            .iter()
+            .flatten()
+            .filter(|e| e.key != 0 && e.generation == self.generation)
+            .count();
+        TTStats {
+            hashfull: u16::try_from(used * 1000 / (sample.len() * 2).max(1)).unwrap_or(1000),
+            ..self.stats
         }
     }
 
-    pub fn probe(&self, key: u64) -> Option<TTEntry> {
+    pub fn probe(&mut self, key: u64) -> Option<TTEntry> {
         let idx = (key as usize) & self.mask;
-        let entry = self.entries[idx];
-        if entry.key == key {
-            Some(entry)
-        } else {
-            None
+        let entry = self.buckets[idx].iter().find(|e| e.key == key).copied();
+        self.stats.probes += 1;
+        if entry.is_some() {
+            self.stats.hits += 1;
         }
+        entry
     }
 
-    pub fn get_move(&self, key: u64) -> Option<Move> {
+    pub fn get_move(&mut self, key: u64) -> Option<Move> {
         self.probe(key).and_then(|e| e.best_move)
     }
 
@@ -77,25 +133,106 @@ impl TranspositionTable {
         depth: u8,
         flag: TTFlag,
     ) {
+        self.stats.stores += 1;
+        let generation = self.generation;
         let idx = (key as usize) & self.mask;
-        let entry = &mut self.entries[idx];
-
-        // Replacement scheme: Always replace if different key (collision) or deeper search
-        // Simple "always replace" is often good enough or "replace if depth >= entry.depth"
-        if entry.key != key || depth >= entry.depth {
-            *entry = TTEntry {
-                key,
-                best_move,
-                score,
-                depth,
-                flag,
-            };
+        let [deep, recent] = &mut self.buckets[idx];
+        let entry = TTEntry {
+            key,
+            best_move,
+            score,
+            depth,
+            flag,
+            generation,
+        };
+
+        if deep.key == key {
+            // Same position: keep the deeper result, but mark it as still in use
+            if depth >= deep.depth {
+                *deep = entry;
+            } else {
+                deep.generation = generation;
+            }
+        } else if deep.generation != generation || depth >= deep.depth {
+            // The displaced entry is still worth more than whatever slot 1 holds
+            if deep.key != 0 {
+                *recent = *deep;
+            }
+            *deep = entry;
+        } else {
+            *recent = entry;
         }
     }
 
     pub fn clear(&mut self) {
-        for entry in &mut self.entries {
-            *entry = TTEntry::default();
+        for bucket in &mut self.buckets {
+            *bucket = [TTEntry::default(); 2];
         }
+        self.generation = 0;
+        self.stats = TTStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Different key, same bucket in any table smaller than 2^40 buckets.
+    const COLLIDING: u64 = 1 << 40;
+
+    #[test]
+    fn test_deep_entry_survives_shallow_collisions() {
+        let mut tt = TranspositionTable::new(1);
+        tt.store(7, None, 100, 10, TTFlag::Exact);
+        tt.store(7 + COLLIDING, None, 5, 2, TTFlag::LowerBound);
+        tt.store(7 + 2 * COLLIDING, None, 6, 1, TTFlag::UpperBound);
+
+        assert_eq!(tt.probe(7).map(|e| e.depth), Some(10));
+        assert!(tt.probe(7 + COLLIDING).is_none());
+        assert_eq!(tt.probe(7 + 2 * COLLIDING).map(|e| e.score), Some(6));
+    }
+
+    #[test]
+    fn test_new_search_ages_out_deep_entries() {
+        let mut tt = TranspositionTable::new(1);
+        tt.store(7, None, 100, 10, TTFlag::Exact);
+        tt.new_search();
+        tt.store(7 + COLLIDING, None, 5, 2, TTFlag::Exact);
+
+        // The old entry gave up slot 0 but is still found in slot 1
+        assert_eq!(tt.probe(7).map(|e| e.generation), Some(0));
+        assert_eq!(tt.probe(7 + COLLIDING).map(|e| e.generation), Some(1));
+        tt.store(7 + 2 * COLLIDING, None, 6, 1, TTFlag::Exact);
+        assert!(tt.probe(7).is_none());
+
+        tt.clear();
+        assert!(tt.probe(7 + COLLIDING).is_none());
+    }
+
+    #[test]
+    fn test_deeper_result_moves_old_entry_to_slot_one() {
+        let mut tt = TranspositionTable::new(1);
+        tt.store(7, None, 100, 4, TTFlag::Exact);
+        tt.store(7 + COLLIDING, None, 5, 9, TTFlag::LowerBound);
+
+        assert_eq!(tt.probe(7).map(|e| e.depth), Some(4));
+        assert_eq!(tt.probe(7 + COLLIDING).map(|e| e.depth), Some(9));
+    }
+
+    #[test]
+    fn test_stats_count_this_search_only() {
+        let mut tt = TranspositionTable::new(1);
+        tt.store(3, None, 0, 1, TTFlag::Exact);
+        tt.store(4, None, 0, 1, TTFlag::Exact);
+        assert!(tt.probe(3).is_some());
+        assert!(tt.probe(5).is_none());
+
+        let stats = tt.stats();
+        assert_eq!((stats.probes, stats.hits, stats.stores), (2, 1, 2));
+        assert_eq!(stats.hit_rate(), 50);
+        assert!(stats.hashfull > 0);
+
+        tt.new_search();
+        assert_eq!(tt.stats(), TTStats::default());
     }
 }
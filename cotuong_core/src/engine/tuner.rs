@@ -0,0 +1,350 @@
+use crate::engine::config::EngineConfig;
+use crate::engine::match_runner::{play_game, GameResult};
+use crate::engine::search::AlphaBetaEngine;
+use crate::engine::SearchLimit;
+use crate::logic::board::BoardCoordinate;
+use rand::Rng;
+use std::sync::Arc;
+
+/// `EngineConfig` fields the tuner knows how to perturb.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TunableParam {
+    Pawn,
+    Advisor,
+    Elephant,
+    Horse,
+    Cannon,
+    Rook,
+    PruningMultiplier,
+    ProbcutMargin,
+    SingularExtensionMargin,
+    HangingPiecePenalty,
+    KingExposedCannonPenalty,
+    KingSafetyWeight,
+}
+
+impl TunableParam {
+    pub const ALL: [Self; 12] = [
+        Self::Pawn,
+        Self::Advisor,
+        Self::Elephant,
+        Self::Horse,
+        Self::Cannon,
+        Self::Rook,
+        Self::PruningMultiplier,
+        Self::ProbcutMargin,
+        Self::SingularExtensionMargin,
+        Self::HangingPiecePenalty,
+        Self::KingExposedCannonPenalty,
+        Self::KingSafetyWeight,
+    ];
+
+    /// The config field name, as it appears in the JSON.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Pawn => "val_pawn",
+            Self::Advisor => "val_advisor",
+            Self::Elephant => "val_elephant",
+            Self::Horse => "val_horse",
+            Self::Cannon => "val_cannon",
+            Self::Rook => "val_rook",
+            Self::PruningMultiplier => "pruning_multiplier",
+            Self::ProbcutMargin => "probcut_margin",
+            Self::SingularExtensionMargin => "singular_extension_margin",
+            Self::HangingPiecePenalty => "hanging_piece_penalty",
+            Self::KingExposedCannonPenalty => "king_exposed_cannon_penalty",
+            Self::KingSafetyWeight => "king_safety_weight",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// Allowed `(min, max)` range; values are clamped into it after every step.
+    #[must_use]
+    pub const fn range(self) -> (f64, f64) {
+        match self {
+            Self::Pawn => (10.0, 200.0),
+            Self::Advisor | Self::Elephant => (40.0, 400.0),
+            Self::Horse | Self::Cannon => (200.0, 900.0),
+            Self::Rook => (500.0, 1800.0),
+            Self::PruningMultiplier => (0.1, 2.0),
+            Self::ProbcutMargin => (50.0, 500.0),
+            Self::SingularExtensionMargin => (5.0, 100.0),
+            Self::HangingPiecePenalty | Self::KingExposedCannonPenalty => (0.0, 100.0),
+            Self::KingSafetyWeight => (0.0, 300.0),
+        }
+    }
+
+    /// Size of the SPSA perturbation, roughly the smallest change expected to matter.
+    #[must_use]
+    pub const fn perturbation(self) -> f64 {
+        match self {
+            Self::Pawn => 5.0,
+            Self::Advisor | Self::Elephant => 10.0,
+            Self::Horse | Self::Cannon => 20.0,
+            Self::Rook => 40.0,
+            Self::PruningMultiplier => 0.1,
+            Self::ProbcutMargin => 20.0,
+            Self::SingularExtensionMargin | Self::HangingPiecePenalty => 4.0,
+            Self::KingExposedCannonPenalty => 5.0,
+            Self::KingSafetyWeight => 15.0,
+        }
+    }
+
+    #[must_use]
+    pub fn get(self, config: &EngineConfig) -> f64 {
+        match self {
+            Self::Pawn => f64::from(config.val_pawn),
+            Self::Advisor => f64::from(config.val_advisor),
+            Self::Elephant => f64::from(config.val_elephant),
+            Self::Horse => f64::from(config.val_horse),
+            Self::Cannon => f64::from(config.val_cannon),
+            Self::Rook => f64::from(config.val_rook),
+            Self::PruningMultiplier => f64::from(config.pruning_multiplier),
+            Self::ProbcutMargin => f64::from(config.probcut_margin),
+            Self::SingularExtensionMargin => f64::from(config.singular_extension_margin),
+            Self::HangingPiecePenalty => f64::from(config.hanging_piece_penalty),
+            Self::KingExposedCannonPenalty => f64::from(config.king_exposed_cannon_penalty),
+            Self::KingSafetyWeight => f64::from(config.king_safety_weight),
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn set(self, config: &mut EngineConfig, value: f64) {
+        let (min, max) = self.range();
+        let value = value.clamp(min, max);
+        let int = value.round() as i32;
+        match self {
+            Self::Pawn => config.val_pawn = int,
+            Self::Advisor => config.val_advisor = int,
+            Self::Elephant => config.val_elephant = int,
+            Self::Horse => config.val_horse = int,
+            Self::Cannon => config.val_cannon = int,
+            Self::Rook => config.val_rook = int,
+            Self::PruningMultiplier => config.pruning_multiplier = value as f32,
+            Self::ProbcutMargin => config.probcut_margin = int,
+            Self::SingularExtensionMargin => config.singular_extension_margin = int,
+            Self::HangingPiecePenalty => config.hanging_piece_penalty = int,
+            Self::KingExposedCannonPenalty => config.king_exposed_cannon_penalty = int,
+            Self::KingSafetyWeight => config.king_safety_weight = int,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpsaConfig {
+    pub params: Vec<TunableParam>,
+    pub iterations: usize,
+    /// Step size, in units of each parameter's perturbation, for a full-point result.
+    pub learning_rate: f64,
+    pub limit: SearchLimit,
+    pub max_plies: usize,
+}
+
+impl Default for SpsaConfig {
+    fn default() -> Self {
+        Self {
+            params: vec![
+                TunableParam::Pawn,
+                TunableParam::Horse,
+                TunableParam::Cannon,
+                TunableParam::Rook,
+                TunableParam::PruningMultiplier,
+                TunableParam::ProbcutMargin,
+            ],
+            iterations: 500,
+            learning_rate: 1.0,
+            limit: SearchLimit::Depth(3),
+            max_plies: 200,
+        }
+    }
+}
+
+/// Simultaneous perturbation stochastic approximation over a set of config fields. Each
+/// iteration plays `theta + c·Δ` against `theta - c·Δ` for a pair of games with colours
+/// swapped, then moves `theta` towards whichever side scored better.
+pub struct SpsaTuner {
+    config: SpsaConfig,
+    base: EngineConfig,
+    theta: Vec<f64>,
+    iteration: usize,
+    /// Reused between iterations so the transposition tables are allocated once.
+    plus_engine: AlphaBetaEngine,
+    minus_engine: AlphaBetaEngine,
+}
+
+/// Standard SPSA gain sequence exponents.
+const ALPHA: f64 = 0.602;
+const GAMMA: f64 = 0.101;
+
+impl SpsaTuner {
+    #[must_use]
+    pub fn new(base: EngineConfig, config: SpsaConfig) -> Self {
+        let theta = config.params.iter().map(|p| p.get(&base)).collect();
+        let shared = Arc::new(base.clone());
+        Self {
+            config,
+            base,
+            theta,
+            iteration: 0,
+            plus_engine: AlphaBetaEngine::new(shared.clone()),
+            minus_engine: AlphaBetaEngine::new(shared),
+        }
+    }
+
+    /// The base config with the current parameter estimates applied.
+    #[must_use]
+    pub fn current(&self) -> EngineConfig {
+        self.with_offsets(&[])
+    }
+
+    #[must_use]
+    pub const fn iteration(&self) -> usize {
+        self.iteration
+    }
+
+    #[must_use]
+    pub const fn is_done(&self) -> bool {
+        self.iteration >= self.config.iterations
+    }
+
+    /// Current value of every tuned parameter.
+    pub fn values(&self) -> impl Iterator<Item = (TunableParam, f64)> + '_ {
+        self.config
+            .params
+            .iter()
+            .copied()
+            .zip(self.theta.iter().copied())
+    }
+
+    fn with_offsets(&self, offsets: &[f64]) -> EngineConfig {
+        let mut config = self.base.clone();
+        for (i, (param, value)) in self.values().enumerate() {
+            param.set(&mut config, value + offsets.get(i).copied().unwrap_or(0.0));
+        }
+        config
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn gains(&self) -> (f64, f64) {
+        let k = self.iteration as f64;
+        // Stability constant, about a tenth of the run as usual for SPSA
+        let stability = self.config.iterations as f64 / 10.0;
+        let a = self.config.learning_rate * (stability + 1.0).powf(ALPHA);
+        (
+            a / (stability + k + 1.0).powf(ALPHA),
+            1.0 / (k + 1.0).powf(GAMMA),
+        )
+    }
+
+    /// Runs one iteration with `opening` and returns the plus side's score minus the minus
+    /// side's, in `-1.0..=1.0`.
+    pub fn step(&mut self, opening: &[(BoardCoordinate, BoardCoordinate)]) -> f64 {
+        let mut rng = rand::thread_rng();
+        let (a_k, c_k) = self.gains();
+        let deltas: Vec<f64> = self
+            .config
+            .params
+            .iter()
+            .map(|_| if rng.gen::<bool>() { 1.0 } else { -1.0 })
+            .collect();
+        let shifts: Vec<f64> = self
+            .config
+            .params
+            .iter()
+            .zip(&deltas)
+            .map(|(p, d)| c_k * p.perturbation() * d)
+            .collect();
+        let minus: Vec<f64> = shifts.iter().map(|s| -s).collect();
+
+        self.plus_engine
+            .update_config(Arc::new(self.with_offsets(&shifts)));
+        self.minus_engine
+            .update_config(Arc::new(self.with_offsets(&minus)));
+        let (limit, max_plies) = (self.config.limit, self.config.max_plies);
+
+        let (_, first) = play_game(
+            &mut self.plus_engine,
+            &mut self.minus_engine,
+            opening,
+            limit,
+            max_plies,
+        );
+        let (_, second) = play_game(
+            &mut self.minus_engine,
+            &mut self.plus_engine,
+            opening,
+            limit,
+            max_plies,
+        );
+        let points = |result: GameResult, plus_is_red: bool| match (result, plus_is_red) {
+            (GameResult::Draw, _) => 0.0,
+            (GameResult::RedWin, true) | (GameResult::BlackWin, false) => 1.0,
+            _ => -1.0,
+        };
+        let result = (points(first, true) + points(second, false)) / 2.0;
+
+        for ((value, param), delta) in self.theta.iter_mut().zip(&self.config.params).zip(&deltas) {
+            let (min, max) = param.range();
+            *value = a_k
+                .mul_add(param.perturbation() * result * delta, *value)
+                .clamp(min, max);
+        }
+        self.iteration += 1;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::match_runner::{parse_opening, OPENINGS};
+
+    #[test]
+    fn test_param_round_trip() {
+        let mut config = EngineConfig::default();
+        for param in TunableParam::ALL {
+            assert_eq!(TunableParam::from_name(param.name()), Some(param));
+            let (min, max) = param.range();
+            let value = param.get(&config);
+            assert!(
+                (min..=max).contains(&value),
+                "{param:?} default out of range"
+            );
+            param.set(&mut config, max + 1000.0);
+            assert!((param.get(&config) - max).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_spsa_step_stays_in_range() {
+        let base = EngineConfig {
+            tt_size_mb: 1,
+            ..Default::default()
+        };
+        let mut tuner = SpsaTuner::new(
+            base,
+            SpsaConfig {
+                iterations: 2,
+                limit: SearchLimit::Depth(1),
+                max_plies: 20,
+                ..Default::default()
+            },
+        );
+        let opening = parse_opening(OPENINGS[0]).unwrap();
+        while !tuner.is_done() {
+            let result = tuner.step(&opening);
+            assert!((-1.0..=1.0).contains(&result));
+        }
+        assert_eq!(tuner.iteration(), 2);
+        for (param, value) in tuner.values() {
+            let (min, max) = param.range();
+            assert!((min..=max).contains(&value));
+        }
+        assert_eq!(tuner.current().tt_size_mb, 1);
+    }
+}
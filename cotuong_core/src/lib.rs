@@ -1,3 +1,4 @@
 pub mod engine;
 pub mod logic;
+pub mod session;
 pub mod worker;
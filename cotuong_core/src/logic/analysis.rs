@@ -0,0 +1,60 @@
+use crate::logic::attacks::piece_attacks;
+use crate::logic::board::{BitboardIterator, Board, Color};
+
+/// How many pieces of `color` attack each square, indexed like `Board::grid`.
+#[must_use]
+pub fn attack_counts(board: &Board, color: Color) -> [u8; 90] {
+    let mut counts = [0u8; 90];
+    for from in BitboardIterator::new(board.get_color_bb(color)) {
+        for sq in BitboardIterator::new(piece_attacks(board, from)) {
+            counts[sq] += 1;
+        }
+    }
+    counts
+}
+
+/// Who controls each square: Red's attackers minus Black's, so positive squares are Red's.
+#[must_use]
+pub fn control_map(board: &Board) -> [i8; 90] {
+    let red = attack_counts(board, Color::Red);
+    let black = attack_counts(board, Color::Black);
+    core::array::from_fn(|sq| {
+        // At most 16 pieces attack one square, so both counts fit in an i8
+        i8::try_from(red[sq]).unwrap_or(i8::MAX) - i8::try_from(black[sq]).unwrap_or(i8::MAX)
+    })
+}
+
+/// How many squares each side controls, as `(red, black)`: a rough measure of space.
+#[must_use]
+pub fn controlled_squares(board: &Board) -> (usize, usize) {
+    let control = control_map(board);
+    (
+        control.iter().filter(|&&c| c > 0).count(),
+        control.iter().filter(|&&c| c < 0).count(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::BoardCoordinate;
+
+    #[test]
+    fn test_control_map() {
+        let board = Board::new();
+        let control = control_map(&board);
+        // The starting position is symmetric: every square Red controls has a mirror for Black
+        for sq in 0..90 {
+            assert_eq!(control[sq], -control[89 - sq], "square {sq}");
+        }
+        let (red, black) = controlled_squares(&board);
+        assert_eq!(red, black);
+        assert!(red > 0);
+
+        // Of both sides, only Red's b0 horse reaches c2
+        let c2 = BoardCoordinate::new(2, 2).unwrap().index();
+        assert_eq!(attack_counts(&board, Color::Red)[c2], 1);
+        assert_eq!(attack_counts(&board, Color::Black)[c2], 0);
+        assert_eq!(control[c2], 1);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::logic::board::{Bitboard, BitboardIterator, Board, Color, PieceType};
+use crate::logic::lookup::AttackTables;
+
+const DIRECTIONS: [(isize, isize); 4] = [(0, 1), (0, -1), (1, 0), (-1, 0)];
+
+fn square(row: isize, col: isize) -> Option<usize> {
+    ((0..10).contains(&row) && (0..9).contains(&col)).then(|| (row * 9 + col).unsigned_abs())
+}
+
+/// Squares along the four lines from `sq` that a chariot (`screens == 0`) or cannon
+/// (`screens == 1`) attacks: those after exactly `screens` pieces, up to the next piece.
+fn ray_attacks(board: &Board, sq: usize, screens: usize) -> Bitboard {
+    let (row, col) = Board::index_to_coord(sq);
+    let mut attacks = 0;
+    for (dr, dc) in DIRECTIONS {
+        let mut jumped = 0;
+        let (mut r, mut c) = (row.cast_signed() + dr, col.cast_signed() + dc);
+        while let Some(target) = square(r, c) {
+            let occupied = board.occupied & (1u128 << target) != 0;
+            if jumped == screens {
+                attacks |= 1u128 << target;
+                if occupied {
+                    break;
+                }
+            } else if occupied {
+                jumped += 1;
+            }
+            r += dr;
+            c += dc;
+        }
+    }
+    attacks
+}
+
+/// Squares the piece on `sq` attacks, whoever stands on them: a friendly piece there is
+/// defended, an enemy one can be captured. Ignores pins and the flying-general rule.
+#[must_use]
+pub fn piece_attacks(board: &Board, sq: usize) -> Bitboard {
+    let Some(piece) = board.grid.get(sq).copied().flatten() else {
+        return 0;
+    };
+    let tables = AttackTables::get();
+    let free = |leg: usize| board.occupied & (1u128 << leg) == 0;
+    let mask = |targets: &[usize]| targets.iter().fold(0, |bb, &t| bb | (1u128 << t));
+    match piece.piece_type {
+        PieceType::Chariot => ray_attacks(board, sq, 0),
+        PieceType::Cannon => ray_attacks(board, sq, 1),
+        PieceType::Horse => tables.horse_moves[sq]
+            .iter()
+            .filter(|&&(_, leg)| free(leg))
+            .fold(0, |bb, &(target, _)| bb | (1u128 << target)),
+        PieceType::Elephant => tables.elephant_moves[sq]
+            .iter()
+            .filter(|&&(_, eye)| free(eye))
+            .fold(0, |bb, &(target, _)| bb | (1u128 << target)),
+        PieceType::Advisor => mask(&tables.advisor_moves[sq]),
+        PieceType::General => mask(&tables.general_moves[sq]),
+        PieceType::Soldier => mask(&tables.soldier_moves[piece.color.index()][sq]),
+    }
+}
+
+/// Every square attacked by at least one piece of `color`.
+#[must_use]
+pub fn attack_map(board: &Board, color: Color) -> Bitboard {
+    BitboardIterator::new(board.get_color_bb(color)).fold(0, |bb, sq| bb | piece_attacks(board, sq))
+}
+
+/// Pieces of `color`, generals aside, that the opponent attacks and nothing defends.
+#[must_use]
+pub fn hanging_pieces(board: &Board, color: Color) -> Bitboard {
+    let general = board.bitboards[color.index() * 7];
+    board.get_color_bb(color)
+        & !general
+        & attack_map(board, color.opposite())
+        & !attack_map(board, color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::BoardCoordinate;
+
+    fn bit(row: usize, col: usize) -> Bitboard {
+        1u128 << BoardCoordinate::new(row, col).unwrap().index()
+    }
+
+    #[test]
+    fn test_attack_maps() {
+        let board = Board::new();
+        let red = attack_map(&board, Color::Red);
+        // The b2 cannon fires over the b7 cannon: b8 and the b9 horse, not the squares before
+        assert_ne!(red & bit(8, 1), 0);
+        assert_ne!(red & bit(9, 1), 0);
+        assert_eq!(red & bit(5, 1), 0);
+        assert_ne!(red & bit(2, 2), 0, "horse on b0 covers c2");
+        assert_eq!(hanging_pieces(&board, Color::Red), 0);
+        assert_eq!(hanging_pieces(&board, Color::Black), 0);
+
+        let mut board = Board::new();
+        board.clear();
+        board.add_piece(
+            BoardCoordinate::new(0, 4).unwrap(),
+            PieceType::General,
+            Color::Red,
+        );
+        board.add_piece(
+            BoardCoordinate::new(9, 3).unwrap(),
+            PieceType::General,
+            Color::Black,
+        );
+        board.add_piece(
+            BoardCoordinate::new(4, 0).unwrap(),
+            PieceType::Chariot,
+            Color::Red,
+        );
+        board.add_piece(
+            BoardCoordinate::new(4, 6).unwrap(),
+            PieceType::Horse,
+            Color::Black,
+        );
+        board.add_piece(
+            BoardCoordinate::new(6, 5).unwrap(),
+            PieceType::Soldier,
+            Color::Black,
+        );
+        assert_eq!(hanging_pieces(&board, Color::Black), bit(4, 6));
+        assert_eq!(hanging_pieces(&board, Color::Red), 0);
+
+        // A soldier behind the horse defends it
+        board.add_piece(
+            BoardCoordinate::new(5, 6).unwrap(),
+            PieceType::Soldier,
+            Color::Black,
+        );
+        assert_eq!(hanging_pieces(&board, Color::Black), 0);
+    }
+}
@@ -358,8 +358,37 @@ impl Board {
         self.black_pst = 0;
     }
 
-    // Helper to add a piece
+    /// Adds a piece, keeping material and PST in step.
     pub fn add_piece(&mut self, pos: BoardCoordinate, piece_type: PieceType, color: Color) {
+        self.place_raw(pos, piece_type, color);
+        self.adjust_score(pos, piece_type, color, 1);
+    }
+
+    fn remove_piece(&mut self, pos: BoardCoordinate, piece_type: PieceType, color: Color) {
+        self.remove_raw(pos, piece_type, color);
+        self.adjust_score(pos, piece_type, color, -1);
+    }
+
+    fn adjust_score(
+        &mut self,
+        pos: BoardCoordinate,
+        piece_type: PieceType,
+        color: Color,
+        sign: i32,
+    ) {
+        let val = sign * get_piece_value(piece_type);
+        let pst = sign * get_pst_value(piece_type, color, pos.row, pos.col);
+        if color == Color::Red {
+            self.red_material += val;
+            self.red_pst += pst;
+        } else {
+            self.black_material += val;
+            self.black_pst += pst;
+        }
+    }
+
+    // Bitboard/grid bookkeeping only; apply_move/undo_move adjust the score themselves
+    fn place_raw(&mut self, pos: BoardCoordinate, piece_type: PieceType, color: Color) {
         let sq = pos.index();
         let bit = 1u128 << sq;
         let idx = color.index() * 7 + piece_type.index();
@@ -371,8 +400,7 @@ impl Board {
         self.occupied_cols[pos.col] |= 1 << pos.row;
     }
 
-    // Helper to remove a piece
-    fn remove_piece(&mut self, pos: BoardCoordinate, piece_type: PieceType, color: Color) {
+    fn remove_raw(&mut self, pos: BoardCoordinate, piece_type: PieceType, color: Color) {
         let sq = pos.index();
         let bit = 1u128 << sq;
         let idx = color.index() * 7 + piece_type.index();
@@ -423,12 +451,11 @@ impl Board {
         hash
     }
 
-    pub fn calculate_initial_score(&mut self) {
-        self.red_material = 0;
-        self.black_material = 0;
-        self.red_pst = 0;
-        self.black_pst = 0;
-
+    /// `(red_material, black_material, red_pst, black_pst)` summed over the whole board,
+    /// ignoring the incrementally kept fields. Used to (re)build them and to verify them.
+    #[must_use]
+    pub fn score_from_scratch(&self) -> (i32, i32, i32, i32) {
+        let mut totals = (0, 0, 0, 0);
         for r in 0..10 {
             for c in 0..9 {
                 let pos = unsafe { BoardCoordinate::new_unchecked(r, c) };
@@ -437,15 +464,25 @@ impl Board {
                     let pst = get_pst_value(piece.piece_type, piece.color, r, c);
 
                     if piece.color == Color::Red {
-                        self.red_material += val;
-                        self.red_pst += pst;
+                        totals.0 += val;
+                        totals.2 += pst;
                     } else {
-                        self.black_material += val;
-                        self.black_pst += pst;
+                        totals.1 += val;
+                        totals.3 += pst;
                     }
                 }
             }
         }
+        totals
+    }
+
+    pub fn calculate_initial_score(&mut self) {
+        (
+            self.red_material,
+            self.black_material,
+            self.red_pst,
+            self.black_pst,
+        ) = self.score_from_scratch();
     }
 
     pub fn apply_null_move(&mut self) {
@@ -480,7 +517,7 @@ impl Board {
         };
 
         // Remove from source
-        self.remove_piece(from, piece.piece_type, piece.color);
+        self.remove_raw(from, piece.piece_type, piece.color);
         self.zobrist_hash ^= keys.get_piece_key(piece.piece_type, piece.color, from_row, from_col);
 
         // Update Score (Remove from source)
@@ -493,7 +530,7 @@ impl Board {
 
         // 2. Remove captured piece (if any)
         if let Some(captured) = self.get_piece(to) {
-            self.remove_piece(to, captured.piece_type, captured.color);
+            self.remove_raw(to, captured.piece_type, captured.color);
             self.zobrist_hash ^=
                 keys.get_piece_key(captured.piece_type, captured.color, to_row, to_col);
 
@@ -511,7 +548,7 @@ impl Board {
         }
 
         // 3. Place piece at destination
-        self.add_piece(to, piece.piece_type, piece.color);
+        self.place_raw(to, piece.piece_type, piece.color);
         self.zobrist_hash ^= keys.get_piece_key(piece.piece_type, piece.color, to_row, to_col);
 
         // Update Score (Add to dest)
@@ -555,7 +592,7 @@ impl Board {
         };
 
         // Remove from destination
-        self.remove_piece(to, piece.piece_type, piece.color);
+        self.remove_raw(to, piece.piece_type, piece.color);
         self.zobrist_hash ^= keys.get_piece_key(piece.piece_type, piece.color, to_row, to_col);
 
         // Update Score (Remove from dest)
@@ -567,7 +604,7 @@ impl Board {
         }
 
         // Place back at source
-        self.add_piece(from, piece.piece_type, piece.color);
+        self.place_raw(from, piece.piece_type, piece.color);
         self.zobrist_hash ^= keys.get_piece_key(piece.piece_type, piece.color, from_row, from_col);
 
         // Update Score (Add to source)
@@ -580,7 +617,7 @@ impl Board {
 
         // 2. Restore captured piece (if any)
         if let Some(cap) = captured {
-            self.add_piece(to, cap.piece_type, cap.color);
+            self.place_raw(to, cap.piece_type, cap.color);
             self.zobrist_hash ^= keys.get_piece_key(cap.piece_type, cap.color, to_row, to_col);
 
             // Update Score (Restore captured)
@@ -598,6 +635,30 @@ impl Board {
     }
 }
 
+pub struct BitboardIterator {
+    bb: u128,
+}
+
+impl BitboardIterator {
+    pub const fn new(bb: u128) -> Self {
+        Self { bb }
+    }
+}
+
+impl Iterator for BitboardIterator {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bb == 0 {
+            None
+        } else {
+            let lsb = self.bb.trailing_zeros() as usize;
+            self.bb &= self.bb - 1;
+            Some(lsb)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -653,28 +714,64 @@ mod tests {
         assert_eq!(piece.piece_type, PieceType::Soldier);
         assert_eq!(piece.color, Color::Red);
     }
-}
-
-pub struct BitboardIterator {
-    bb: u128,
-}
-
-impl BitboardIterator {
-    pub const fn new(bb: u128) -> Self {
-        Self { bb }
-    }
-}
 
-impl Iterator for BitboardIterator {
-    type Item = usize;
+    #[test]
+    fn test_incremental_score_matches_scratch() {
+        use crate::logic::generator::MoveGenerator;
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.bb == 0 {
-            None
-        } else {
-            let lsb = self.bb.trailing_zeros() as usize;
-            self.bb &= self.bb - 1;
-            Some(lsb)
+        let mut rng = StdRng::seed_from_u64(2579);
+        let mut board = Board::new();
+        let mut turn = Color::Red;
+        let mut played = Vec::new();
+        for _ in 0..60 {
+            let moves = MoveGenerator::new().generate_moves(&board, turn);
+            let Some(mv) = moves.choose(&mut rng).copied() else {
+                break;
+            };
+            let captured = board
+                .get_piece(BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize).unwrap());
+            board.apply_move(&mv, turn);
+            let incremental = (
+                board.red_material,
+                board.black_material,
+                board.red_pst,
+                board.black_pst,
+            );
+            assert_eq!(incremental, board.score_from_scratch());
+            played.push((mv, captured, turn));
+            turn = turn.opposite();
+        }
+        while let Some((mv, captured, turn)) = played.pop() {
+            board.undo_move(&mv, captured, turn);
         }
+        let fresh = Board::new();
+        assert_eq!(
+            (
+                board.red_material,
+                board.red_pst,
+                board.black_material,
+                board.black_pst
+            ),
+            (
+                fresh.red_material,
+                fresh.red_pst,
+                fresh.black_material,
+                fresh.black_pst
+            )
+        );
+
+        // Editing squares directly keeps the score too
+        board.set_piece(BoardCoordinate::new(0, 1).unwrap(), None);
+        let incremental = (
+            board.red_material,
+            board.black_material,
+            board.red_pst,
+            board.black_pst,
+        );
+        assert_eq!(incremental, board.score_from_scratch());
+        assert!(board.red_material < fresh.red_material);
     }
 }
@@ -1,7 +1,10 @@
+use crate::engine::explain::MoveExplanation;
 use crate::engine::Move;
 use crate::logic::board::{Board, BoardCoordinate, Color};
 use crate::logic::generator::MoveGenerator;
-use crate::logic::rules::{is_valid_move, MoveError};
+use crate::logic::handicap::HandicapKind;
+use crate::logic::notation::WxfMove;
+use crate::logic::rules::{is_valid_move, validate_position, MoveError};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -9,6 +12,47 @@ pub enum GameStatus {
     Playing,
     Checkmate(Color), // Winner
     Stalemate,
+    Resigned(Color), // Winner
+    DrawAgreed,
+}
+
+/// How a move was chosen, kept as data so the UI can word it in the player's language and
+/// each exporter can write it in its own format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveAnnotation {
+    /// Played from the opening book.
+    Book,
+    /// Found by the engine's search.
+    Engine {
+        depth: u8,
+        nodes: u32,
+        time_ms: u64,
+        explanation: MoveExplanation,
+    },
+}
+
+impl MoveAnnotation {
+    /// Short PGN comment: `book`, or the score in pawns from the mover's side over the
+    /// depth and the time taken, e.g. `+0.35/12 1.2s`.
+    #[must_use]
+    pub fn pgn_comment(&self) -> String {
+        match self {
+            Self::Book => "book".to_string(),
+            Self::Engine {
+                depth,
+                time_ms,
+                explanation,
+                ..
+            } => {
+                #[allow(clippy::cast_precision_loss)]
+                let seconds = *time_ms as f64 / 1000.0;
+                format!(
+                    "{:+.2}/{depth} {seconds:.1}s",
+                    f64::from(explanation.score) / 100.0
+                )
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,8 +63,23 @@ pub struct MoveRecord {
     pub piece: crate::logic::board::Piece,
     pub captured: Option<crate::logic::board::Piece>,
     pub color: Color,
-    pub note: Option<String>, // For AI stats or other info
+    /// Set by whoever chose the move, `None` for moves played on the board.
+    #[serde(default)]
+    pub annotation: Option<MoveAnnotation>,
     pub hash: u64,
+    /// The move in WXF notation, e.g. `C2=5`, written when it is played.
+    #[serde(default)]
+    pub notation: Option<String>,
+    /// Time the mover had left after the move, in milliseconds; timed games only.
+    #[serde(default)]
+    pub clock_ms: Option<u64>,
+    /// Score of the position after the move from Red's side, in centipawns, when an engine
+    /// looked at it.
+    #[serde(default)]
+    pub eval: Option<i32>,
+    /// Annotation glyph such as `!`, `?!` or `??`.
+    #[serde(default)]
+    pub glyph: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +89,10 @@ pub struct GameState {
     pub status: GameStatus,
     pub last_move: Option<(BoardCoordinate, BoardCoordinate)>,
     pub history: Vec<MoveRecord>,
+    #[serde(default)]
+    pub handicap: HandicapKind,
+    /// Hash of the position the game started from, its first occurrence for repetitions.
+    pub initial_hash: u64,
 }
 
 impl Default for GameState {
@@ -41,15 +104,38 @@ impl Default for GameState {
 impl GameState {
     #[must_use]
     pub fn new() -> Self {
+        Self::from_position(Board::new(), Color::Red)
+    }
+
+    /// A game starting from `board` with `turn` to move, with no moves played yet.
+    #[must_use]
+    pub fn from_position(board: Board, turn: Color) -> Self {
         Self {
-            board: Board::new(),
-            turn: Color::Red,
+            initial_hash: board.zobrist_hash,
+            board,
+            turn,
             status: GameStatus::Playing,
             last_move: None,
             history: Vec::new(),
+            handicap: HandicapKind::None,
         }
     }
 
+    /// A game starting from the position in `fen`, with no moves played yet.
+    pub fn from_fen(fen: &str) -> Result<Self, String> {
+        let (board, turn) = Board::from_fen(fen)?;
+        Ok(Self::from_position(board, turn))
+    }
+
+    /// A game from a FEN the user typed or pasted: besides parsing, the position must pass
+    /// `validate_position`, and one with no legal moves for the side to move starts decided.
+    pub fn from_user_fen(fen: &str) -> Result<Self, String> {
+        let mut state = Self::from_fen(fen.trim())?;
+        validate_position(&state.board, state.turn)?;
+        state.update_status();
+        Ok(state)
+    }
+
     pub fn make_move(
         &mut self,
         from: BoardCoordinate,
@@ -78,27 +164,13 @@ impl GameState {
             .get_piece(from)
             .ok_or(MoveError::NoPieceAtSource)?;
         let captured = next_board.get_piece(to);
+        let notation = WxfMove::describe(&self.board, from, to).map(|wxf| wxf.to_string());
 
         next_board.apply_move(&mv, self.turn);
 
         // 3-Fold Repetition Check
-        let initial_hash = Board::new().zobrist_hash;
-        let mut count = 0;
-
-        // Check history
-        count += self
-            .history
-            .iter()
-            .filter(|r| r.hash == next_board.zobrist_hash)
-            .count();
-
-        // Check initial state
-        if next_board.zobrist_hash == initial_hash {
-            count += 1;
-        }
-
         // If we already have 2 occurrences (so this would be the 3rd), forbid it.
-        if count >= 2 {
+        if self.repetition_count(next_board.zobrist_hash) >= 2 {
             // Exception: If this is the ONLY legal move, allow it.
             if self.has_more_than_one_valid_move(self.turn) {
                 return Err(MoveError::ThreeFoldRepetition);
@@ -112,11 +184,17 @@ impl GameState {
             piece,
             captured,
             color: self.turn,
-            note: None,
+            annotation: None,
             hash: self.board.zobrist_hash,
+            notation,
+            clock_ms: None,
+            eval: None,
+            glyph: None,
         });
 
-        self.turn = self.turn.opposite();
+        if !self.handicap.grants_extra_move(self.history.len() - 1) {
+            self.turn = self.turn.opposite();
+        }
         self.last_move = Some((from, to));
 
         self.update_status();
@@ -124,6 +202,28 @@ impl GameState {
         Ok(())
     }
 
+    /// How many times the position with `hash` has occurred in this game, the start
+    /// position included.
+    #[must_use]
+    pub fn repetition_count(&self, hash: u64) -> usize {
+        let start = usize::from(hash == self.initial_hash);
+        start + self.history.iter().filter(|r| r.hash == hash).count()
+    }
+
+    /// Ends a game in progress with `color` giving up.
+    pub fn resign(&mut self, color: Color) {
+        if self.status == GameStatus::Playing {
+            self.status = GameStatus::Resigned(color.opposite());
+        }
+    }
+
+    /// Ends a game in progress as a draw both sides agreed to.
+    pub fn agree_draw(&mut self) {
+        if self.status == GameStatus::Playing {
+            self.status = GameStatus::DrawAgreed;
+        }
+    }
+
     fn update_status(&mut self) {
         let current_turn = self.turn;
 
@@ -304,9 +404,9 @@ impl GameState {
                 score: 0,
             };
 
-            self.board
-                .undo_move(&mv, record.captured, self.turn.opposite());
-            self.turn = self.turn.opposite();
+            // The mover is recorded because handicaps can give a side two moves in a row
+            self.board.undo_move(&mv, record.captured, record.color);
+            self.turn = record.color;
 
             // Restore last_move from the previous record in history, if any
             if let Some(prev) = self.history.last() {
@@ -344,6 +444,7 @@ mod tests {
         .unwrap();
 
         assert_eq!(game.history.len(), 1);
+        assert_eq!(game.history[0].notation.as_deref(), Some("P5+1"));
         assert_eq!(game.turn, Color::Black);
         assert!(game
             .board
@@ -479,4 +580,40 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_from_user_fen() {
+        let pasted = GameState::from_user_fen("  R3k4/R8/9/9/9/9/9/9/9/3K5 b - - 0 1\n").unwrap();
+        assert_eq!(pasted.turn, Color::Black);
+        assert!(pasted.history.is_empty());
+        assert_eq!(pasted.status, GameStatus::Checkmate(Color::Red));
+
+        // Parses, but Red could take the black general
+        assert!(GameState::from_user_fen("R3k4/R8/9/9/9/9/9/9/9/3K5 w").is_err());
+        assert!(GameState::from_user_fen("not a fen").is_err());
+    }
+
+    #[test]
+    fn test_repetition_counts_the_start_position() {
+        let mut game = GameState::from_fen("3k5/9/9/9/9/9/9/9/R8/4K4 w").unwrap();
+        let square = |row, col| BoardCoordinate::new(row, col).unwrap();
+        let cycle = [
+            ((1, 0), (1, 1)),
+            ((9, 3), (8, 3)),
+            ((1, 1), (1, 0)),
+            ((8, 3), (9, 3)),
+        ];
+        for ((from, to), ply) in cycle.iter().cycle().zip(0..7) {
+            assert!(
+                game.make_move(square(from.0, from.1), square(to.0, to.1))
+                    .is_ok(),
+                "ply {ply}"
+            );
+        }
+        // Back to the FEN position for the third time
+        assert_eq!(
+            game.make_move(square(8, 3), square(9, 3)),
+            Err(MoveError::ThreeFoldRepetition)
+        );
+    }
 }
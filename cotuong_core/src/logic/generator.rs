@@ -1,7 +1,7 @@
 use crate::engine::Move;
 use crate::logic::board::{BitboardIterator, Board, BoardCoordinate, Color, PieceType};
 use crate::logic::lookup::AttackTables;
-use crate::logic::rules::is_valid_move;
+use crate::logic::rules::{is_flying_general, is_in_check, is_valid_move};
 
 pub struct MoveGenerator;
 
@@ -33,13 +33,94 @@ impl MoveGenerator {
                 let (r, c) = Board::index_to_coord(sq);
                 let from = unsafe { BoardCoordinate::new_unchecked(r, c) };
 
-                self.generate_piece_moves(board, from, piece_type, turn, &mut moves);
+                self.generate_piece_moves(board, from, piece_type, turn, &mut |to| {
+                    self.try_add_move(board, from, to, turn, &mut moves);
+                });
             }
         }
 
         moves
     }
 
+    /// Fully legal moves without a board copy per move. Pins and checks are worked out once
+    /// up front (`PinInfo`); only moves that could expose the general are verified by
+    /// make/undo on a scratch board, everything else is legal by construction. Produces the
+    /// same set as `generate_moves`.
+    pub fn generate_legal_moves(&self, board: &Board, turn: Color) -> Vec<Move> {
+        let mut moves = Vec::with_capacity(64);
+        let Some(pins) = PinInfo::new(board, turn) else {
+            // Without a general every position counts as check, so nothing is legal
+            return moves;
+        };
+        let mut scratch: Option<Board> = None;
+
+        let start_idx = turn.index() * 7;
+        for i in 0..7 {
+            let bb = board.bitboards[start_idx + i];
+            for sq in BitboardIterator::new(bb) {
+                let piece_type = match i {
+                    0 => PieceType::General,
+                    1 => PieceType::Advisor,
+                    2 => PieceType::Elephant,
+                    3 => PieceType::Horse,
+                    4 => PieceType::Chariot,
+                    5 => PieceType::Cannon,
+                    6 => PieceType::Soldier,
+                    _ => unreachable!(),
+                };
+
+                // Safety: BitboardIterator returns valid indices 0..89
+                let (r, c) = Board::index_to_coord(sq);
+                let from = unsafe { BoardCoordinate::new_unchecked(r, c) };
+                let pinned = pins.verify_all
+                    || piece_type == PieceType::General
+                    || pins.pinned & (1u128 << sq) != 0;
+
+                self.generate_piece_moves(board, from, piece_type, turn, &mut |to| {
+                    let to_sq = to.index();
+                    if board.grid[to_sq].is_some_and(|p| p.color == turn) {
+                        return;
+                    }
+                    let mv = Move {
+                        from_row: from.row as u8,
+                        from_col: from.col as u8,
+                        to_row: to.row as u8,
+                        to_col: to.col as u8,
+                        score: 0,
+                    };
+                    if pinned || pins.screens & (1u128 << to_sq) != 0 {
+                        let scratch = scratch.get_or_insert_with(|| board.clone());
+                        let captured = scratch.get_piece(to);
+                        scratch.apply_move(&mv, turn);
+                        let legal = !is_in_check(scratch, turn) && !is_flying_general(scratch);
+                        scratch.undo_move(&mv, captured, turn);
+                        if !legal {
+                            return;
+                        }
+                    }
+                    moves.push(mv);
+                });
+            }
+        }
+
+        moves
+    }
+
+    /// Legal destinations of the piece on `from`, whichever side it belongs to; empty for an
+    /// empty square. Only the squares the piece's movement reaches are checked, not all 90.
+    pub fn legal_moves_from(&self, board: &Board, from: BoardCoordinate) -> Vec<BoardCoordinate> {
+        let Some(piece) = board.get_piece(from) else {
+            return Vec::new();
+        };
+        let mut targets = Vec::new();
+        self.generate_piece_moves(board, from, piece.piece_type, piece.color, &mut |to| {
+            if is_valid_move(board, from, to, piece.color).is_ok() {
+                targets.push(to);
+            }
+        });
+        targets
+    }
+
     /// Checks if the current player has at least one legal move.
     /// This is optimized to return `true` as soon as a valid move is found.
     pub fn has_legal_moves(&self, board: &Board, turn: Color) -> bool {
@@ -103,22 +184,24 @@ impl MoveGenerator {
         false
     }
 
+    /// Calls `add` with every square the piece reaches by its movement rules alone, before
+    /// friendly-piece, self-check and flying-general filtering.
     fn generate_piece_moves(
         &self,
         board: &Board,
         from: BoardCoordinate,
         piece_type: PieceType,
         turn: Color,
-        moves: &mut Vec<Move>,
+        add: &mut impl FnMut(BoardCoordinate),
     ) {
         match piece_type {
-            PieceType::General => self.generate_general_moves(board, from, turn, moves),
-            PieceType::Advisor => self.generate_advisor_moves(board, from, turn, moves),
-            PieceType::Elephant => self.generate_elephant_moves(board, from, turn, moves),
-            PieceType::Horse => self.generate_horse_moves(board, from, turn, moves),
-            PieceType::Chariot => self.generate_chariot_moves(board, from, turn, moves),
-            PieceType::Cannon => self.generate_cannon_moves(board, from, turn, moves),
-            PieceType::Soldier => self.generate_soldier_moves(board, from, turn, moves),
+            PieceType::General => self.generate_general_moves(from, add),
+            PieceType::Advisor => self.generate_advisor_moves(from, add),
+            PieceType::Elephant => self.generate_elephant_moves(board, from, add),
+            PieceType::Horse => self.generate_horse_moves(board, from, add),
+            PieceType::Chariot => self.generate_chariot_moves(board, from, add),
+            PieceType::Cannon => self.generate_cannon_moves(board, from, add),
+            PieceType::Soldier => self.generate_soldier_moves(from, turn, add),
         }
     }
 
@@ -157,37 +240,25 @@ impl MoveGenerator {
         }
     }
 
-    fn generate_general_moves(
-        &self,
-        board: &Board,
-        from: BoardCoordinate,
-        turn: Color,
-        moves: &mut Vec<Move>,
-    ) {
+    fn generate_general_moves(&self, from: BoardCoordinate, add: &mut impl FnMut(BoardCoordinate)) {
         let tables = AttackTables::get();
         let sq = from.index();
 
         for &target_sq in &tables.general_moves[sq] {
             let (tr, tc) = Board::index_to_coord(target_sq);
             let to = unsafe { BoardCoordinate::new_unchecked(tr, tc) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
     }
 
-    fn generate_advisor_moves(
-        &self,
-        board: &Board,
-        from: BoardCoordinate,
-        turn: Color,
-        moves: &mut Vec<Move>,
-    ) {
+    fn generate_advisor_moves(&self, from: BoardCoordinate, add: &mut impl FnMut(BoardCoordinate)) {
         let tables = AttackTables::get();
         let sq = from.index();
 
         for &target_sq in &tables.advisor_moves[sq] {
             let (tr, tc) = Board::index_to_coord(target_sq);
             let to = unsafe { BoardCoordinate::new_unchecked(tr, tc) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
     }
 
@@ -195,8 +266,7 @@ impl MoveGenerator {
         &self,
         board: &Board,
         from: BoardCoordinate,
-        turn: Color,
-        moves: &mut Vec<Move>,
+        add: &mut impl FnMut(BoardCoordinate),
     ) {
         let tables = AttackTables::get();
         let sq = from.index();
@@ -206,7 +276,7 @@ impl MoveGenerator {
             if board.grid[eye_sq].is_none() {
                 let (tr, tc) = Board::index_to_coord(target_sq);
                 let to = unsafe { BoardCoordinate::new_unchecked(tr, tc) };
-                self.try_add_move(board, from, to, turn, moves);
+                add(to);
             }
         }
     }
@@ -215,8 +285,7 @@ impl MoveGenerator {
         &self,
         board: &Board,
         from: BoardCoordinate,
-        turn: Color,
-        moves: &mut Vec<Move>,
+        add: &mut impl FnMut(BoardCoordinate),
     ) {
         let tables = AttackTables::get();
         let sq = from.index();
@@ -226,7 +295,7 @@ impl MoveGenerator {
             if board.grid[leg_sq].is_none() {
                 let (tr, tc) = Board::index_to_coord(target_sq);
                 let to = unsafe { BoardCoordinate::new_unchecked(tr, tc) };
-                self.try_add_move(board, from, to, turn, moves);
+                add(to);
             }
         }
     }
@@ -235,8 +304,7 @@ impl MoveGenerator {
         &self,
         board: &Board,
         from: BoardCoordinate,
-        turn: Color,
-        moves: &mut Vec<Move>,
+        add: &mut impl FnMut(BoardCoordinate),
     ) {
         // Use Magic/Rotated bitboards or simple lookups for sliding pieces?
         // We have `get_rook_attacks` in tables.
@@ -250,7 +318,7 @@ impl MoveGenerator {
             let col = attacks.trailing_zeros() as usize;
             attacks &= attacks - 1;
             let to = unsafe { BoardCoordinate::new_unchecked(r, col) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
 
         let file_occ = board.occupied_cols[c];
@@ -259,7 +327,7 @@ impl MoveGenerator {
             let row = attacks.trailing_zeros() as usize;
             attacks &= attacks - 1;
             let to = unsafe { BoardCoordinate::new_unchecked(row, c) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
     }
 
@@ -267,8 +335,7 @@ impl MoveGenerator {
         &self,
         board: &Board,
         from: BoardCoordinate,
-        turn: Color,
-        moves: &mut Vec<Move>,
+        add: &mut impl FnMut(BoardCoordinate),
     ) {
         let tables = AttackTables::get();
         let r = from.row;
@@ -280,7 +347,7 @@ impl MoveGenerator {
             let col = attacks.trailing_zeros() as usize;
             attacks &= attacks - 1;
             let to = unsafe { BoardCoordinate::new_unchecked(r, col) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
 
         let file_occ = board.occupied_cols[c];
@@ -289,16 +356,15 @@ impl MoveGenerator {
             let row = attacks.trailing_zeros() as usize;
             attacks &= attacks - 1;
             let to = unsafe { BoardCoordinate::new_unchecked(row, c) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
     }
 
     fn generate_soldier_moves(
         &self,
-        board: &Board,
         from: BoardCoordinate,
         turn: Color,
-        moves: &mut Vec<Move>,
+        add: &mut impl FnMut(BoardCoordinate),
     ) {
         let tables = AttackTables::get();
         let sq = from.index();
@@ -307,7 +373,7 @@ impl MoveGenerator {
         for &target_sq in &tables.soldier_moves[color_idx][sq] {
             let (tr, tc) = Board::index_to_coord(target_sq);
             let to = unsafe { BoardCoordinate::new_unchecked(tr, tc) };
-            self.try_add_move(board, from, to, turn, moves);
+            add(to);
         }
     }
 
@@ -449,6 +515,74 @@ impl MoveGenerator {
     // We'll remove it to clean up.
 }
 
+/// What `generate_legal_moves` needs to know about the side to move before generating.
+struct PinInfo {
+    /// In check (or generals already facing): verify every move.
+    verify_all: bool,
+    /// Squares whose piece may expose the general by leaving: blockers in front of an enemy
+    /// chariot or the enemy general, both pieces between the general and a cannon, and horse
+    /// legs of enemy horses aimed at the general.
+    pinned: u128,
+    /// Empty squares between the general and an unscreened enemy cannon, where any piece
+    /// would become the screen.
+    screens: u128,
+}
+
+impl PinInfo {
+    fn new(board: &Board, turn: Color) -> Option<Self> {
+        let general = BitboardIterator::new(board.bitboards[turn.index() * 7]).next()?;
+        let (gr, gc) = Board::index_to_coord(general);
+        let mut pinned = 0u128;
+        let mut screens = 0u128;
+
+        for (dr, dc) in [(1i32, 0i32), (-1, 0), (0, 1), (0, -1)] {
+            let mut empty = 0u128;
+            let mut blockers = 0u128;
+            let mut count = 0;
+            let (mut r, mut c) = (gr as i32 + dr, gc as i32 + dc);
+            while (0..10).contains(&r) && (0..9).contains(&c) {
+                let sq = (r * 9 + c) as usize;
+                if let Some(piece) = board.grid[sq] {
+                    let enemy = piece.color != turn;
+                    let slider = enemy
+                        && (piece.piece_type == PieceType::Chariot
+                            || (dc == 0 && piece.piece_type == PieceType::General));
+                    let cannon = enemy && piece.piece_type == PieceType::Cannon;
+                    match count {
+                        0 if cannon => screens |= empty,
+                        1 if slider => pinned |= blockers,
+                        2 if cannon => pinned |= blockers,
+                        _ => {}
+                    }
+                    if count == 2 {
+                        break;
+                    }
+                    blockers |= 1u128 << sq;
+                    count += 1;
+                } else if count == 0 {
+                    empty |= 1u128 << sq;
+                }
+                r += dr;
+                c += dc;
+            }
+        }
+
+        let tables = AttackTables::get();
+        let enemy_horses = board.bitboards[turn.opposite().index() * 7 + PieceType::Horse.index()];
+        for &(horse, leg) in &tables.horse_attackers[general] {
+            if enemy_horses & (1u128 << horse) != 0 {
+                pinned |= 1u128 << leg;
+            }
+        }
+
+        Some(Self {
+            verify_all: is_in_check(board, turn) || is_flying_general(board),
+            pinned,
+            screens,
+        })
+    }
+}
+
 impl Default for MoveGenerator {
     fn default() -> Self {
         Self::new()
@@ -508,6 +642,75 @@ mod tests {
         assert!(moves.is_empty());
     }
 
+    #[test]
+    fn test_legal_generator_matches_reference() {
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        let key = |moves: Vec<Move>| {
+            let mut keys: Vec<_> = moves
+                .iter()
+                .map(|m| (m.from_row, m.from_col, m.to_row, m.to_col))
+                .collect();
+            keys.sort_unstable();
+            keys
+        };
+        let generator = MoveGenerator::new();
+        let mut rng = StdRng::seed_from_u64(2581);
+        let mut positions = 0;
+        for _ in 0..30 {
+            let mut board = Board::new();
+            let mut turn = Color::Red;
+            for _ in 0..120 {
+                let reference = generator.generate_moves(&board, turn);
+                assert_eq!(
+                    key(generator.generate_legal_moves(&board, turn)),
+                    key(reference.clone()),
+                    "{}",
+                    board.to_fen_string(turn)
+                );
+                positions += 1;
+                let Some(mv) = reference.choose(&mut rng) else {
+                    break;
+                };
+                board.apply_move(mv, turn);
+                turn = turn.opposite();
+            }
+        }
+        assert!(positions > 1000);
+    }
+
+    #[test]
+    fn test_legal_moves_from_matches_brute_force() {
+        let generator = MoveGenerator::new();
+        let all_squares: Vec<BoardCoordinate> = (0..10)
+            .flat_map(|r| (0..9).filter_map(move |c| BoardCoordinate::new(r, c)))
+            .collect();
+        let mut board = Board::new();
+        let mut turn = Color::Red;
+        for ply in 0..40 {
+            for &from in &all_squares {
+                let color = board.get_piece(from).map_or(turn, |p| p.color);
+                let expected: Vec<BoardCoordinate> = all_squares
+                    .iter()
+                    .copied()
+                    .filter(|&to| is_valid_move(&board, from, to, color).is_ok())
+                    .collect();
+                let mut found = generator.legal_moves_from(&board, from);
+                found.sort_unstable_by_key(|to| to.index());
+                assert_eq!(found, expected, "{} {from:?}", board.to_fen_string(turn));
+            }
+            // Walk a deterministic line through the game
+            let moves = generator.generate_legal_moves(&board, turn);
+            let Some(mv) = moves.get(ply * 7 % moves.len().max(1)) else {
+                break;
+            };
+            board.apply_move(mv, turn);
+            turn = turn.opposite();
+        }
+    }
+
     #[test]
     fn test_absolute_checkmate_scenario() {
         // Reproduction of the scenario in test_absolute_checkmate
@@ -0,0 +1,117 @@
+use crate::logic::board::{Board, BoardCoordinate, Color};
+use crate::logic::game::GameState;
+use serde::{Deserialize, Serialize};
+
+/// Standard odds games. Red is always the stronger side giving the handicap.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HandicapKind {
+    #[default]
+    None,
+    /// Red plays without the left horse.
+    Horse,
+    /// Red plays without both horses.
+    TwoHorses,
+    /// Red plays without the left chariot.
+    Chariot,
+    /// Black opens with two moves in a row.
+    TwoMoves,
+}
+
+impl HandicapKind {
+    pub const ALL: [Self; 5] = [
+        Self::None,
+        Self::Horse,
+        Self::TwoHorses,
+        Self::Chariot,
+        Self::TwoMoves,
+    ];
+
+    /// Squares emptied on Red's back rank, as columns.
+    const fn removed_columns(self) -> &'static [usize] {
+        match self {
+            Self::None | Self::TwoMoves => &[],
+            Self::Horse => &[1],
+            Self::TwoHorses => &[1, 7],
+            Self::Chariot => &[0],
+        }
+    }
+
+    /// Starting board with the handicapped pieces removed.
+    #[must_use]
+    pub fn board(self) -> Board {
+        let mut board = Board::new();
+        for &col in self.removed_columns() {
+            if let Some(pos) = BoardCoordinate::new(0, col) {
+                board.set_piece(pos, None);
+            }
+        }
+        board
+    }
+
+    /// Side that makes the first move.
+    #[must_use]
+    pub const fn first_turn(self) -> Color {
+        match self {
+            Self::TwoMoves => Color::Black,
+            _ => Color::Red,
+        }
+    }
+
+    /// True when the side that just played the move numbered `ply` (from 0) moves again.
+    #[must_use]
+    pub const fn grants_extra_move(self, ply: usize) -> bool {
+        matches!(self, Self::TwoMoves) && ply == 0
+    }
+
+    #[must_use]
+    pub fn initial_state(self) -> GameState {
+        let mut state = GameState::from_position(self.board(), self.first_turn());
+        state.handicap = self;
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::PieceType;
+
+    fn red_count(board: &Board, piece_type: PieceType) -> usize {
+        (0..10)
+            .flat_map(|r| (0..9).filter_map(move |c| BoardCoordinate::new(r, c)))
+            .filter_map(|pos| board.get_piece(pos))
+            .filter(|p| p.color == Color::Red && p.piece_type == piece_type)
+            .count()
+    }
+
+    #[test]
+    fn test_material_handicaps() {
+        assert_eq!(red_count(&HandicapKind::None.board(), PieceType::Horse), 2);
+        assert_eq!(red_count(&HandicapKind::Horse.board(), PieceType::Horse), 1);
+        assert_eq!(red_count(&HandicapKind::TwoHorses.board(), PieceType::Horse), 0);
+        assert_eq!(red_count(&HandicapKind::Chariot.board(), PieceType::Chariot), 1);
+        assert_eq!(
+            HandicapKind::Chariot.board().to_fen_string(Color::Red),
+            "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/1NBAKABNR w"
+        );
+    }
+
+    #[test]
+    fn test_two_moves_handicap() {
+        let mut state = HandicapKind::TwoMoves.initial_state();
+        assert_eq!(state.turn, Color::Black);
+
+        let (from, to) = crate::logic::puzzle::parse_iccs("h7e7").unwrap();
+        state.make_move(from, to).unwrap();
+        assert_eq!(state.turn, Color::Black, "Black keeps the move");
+        let (from, to) = crate::logic::puzzle::parse_iccs("h9g7").unwrap();
+        state.make_move(from, to).unwrap();
+        assert_eq!(state.turn, Color::Red);
+
+        state.undo_move();
+        assert_eq!(state.turn, Color::Black);
+        state.undo_move();
+        assert_eq!(state.turn, Color::Black);
+        assert_eq!(state.board.zobrist_hash, Board::new().zobrist_hash);
+    }
+}
@@ -0,0 +1,203 @@
+use crate::logic::board::{Board, BoardCoordinate};
+use crate::logic::game::GameState;
+use crate::logic::puzzle::parse_iccs;
+use serde::{Deserialize, Serialize};
+
+/// One exercise of a lesson: a position, what to do in it, and the moves that count.
+/// Red is always the learner's side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LessonStep {
+    pub fen: String,
+    pub instruction: String,
+    /// Any other move, legal or not, is refused and the step starts over.
+    pub accepted: Vec<(BoardCoordinate, BoardCoordinate)>,
+}
+
+/// A scripted lesson. `id` is stable, so progress saved by the client survives reordering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lesson {
+    pub id: String,
+    pub title: String,
+    pub steps: Vec<LessonStep>,
+}
+
+impl LessonStep {
+    /// Builds a step from a FEN and a space separated list of accepted ICCS moves.
+    pub fn parse(fen: &str, instruction: &str, accepted: &str) -> Option<Self> {
+        let accepted = accepted
+            .split_whitespace()
+            .map(parse_iccs)
+            .collect::<Option<Vec<_>>>()?;
+        Board::from_fen(fen).ok()?;
+        Some(Self {
+            fen: fen.to_string(),
+            instruction: instruction.to_string(),
+            accepted,
+        })
+    }
+
+    pub fn initial_state(&self) -> Result<GameState, String> {
+        GameState::from_fen(&self.fen)
+    }
+
+    #[must_use]
+    pub fn accepts(&self, from: BoardCoordinate, to: BoardCoordinate) -> bool {
+        self.accepted.contains(&(from, to))
+    }
+}
+
+type StepData = (&'static str, &'static str, &'static str);
+
+/// Bundled lessons, in teaching order: how each piece moves, the cannon, the facing
+/// generals rule, then the basic mating patterns.
+pub fn lessons() -> Vec<Lesson> {
+    let lesson = |id: &str, title: &str, steps: &[StepData]| Lesson {
+        id: id.to_string(),
+        title: title.to_string(),
+        steps: steps
+            .iter()
+            .filter_map(|(fen, instruction, accepted)| {
+                LessonStep::parse(fen, instruction, accepted)
+            })
+            .collect(),
+    };
+    vec![
+        lesson(
+            "moves",
+            "Cách đi các quân",
+            &[
+                (
+                    "3k5/9/9/9/9/9/9/9/9/R3K4 w",
+                    "Xe đi thẳng theo hàng ngang hoặc dọc, xa bao nhiêu cũng được nếu đường trống. Đưa Xe từ a0 lên a9.",
+                    "a0a9",
+                ),
+                (
+                    "3k5/9/9/9/9/9/9/9/9/1N2K4 w",
+                    "Mã đi chữ nhật: một ô thẳng rồi một ô chéo. Nhảy Mã từ b0 lên c2.",
+                    "b0c2",
+                ),
+                (
+                    "3k5/9/9/9/9/9/9/9/1R7/1N2K4 w",
+                    "Xe ở b1 cản chân Mã, nên Mã không lên a2, c2 được. Hãy nhảy Mã sang d1.",
+                    "b0d1",
+                ),
+                (
+                    "3k5/9/9/9/9/9/9/9/9/2B1K4 w",
+                    "Tượng đi chéo hai ô và không được qua sông. Đưa Tượng từ c0 lên e2.",
+                    "c0e2",
+                ),
+                (
+                    "3k5/9/9/9/9/9/9/9/9/3AK4 w",
+                    "Sĩ đi chéo một ô và chỉ ở trong cung. Đưa Sĩ từ d0 lên e1.",
+                    "d0e1",
+                ),
+                (
+                    "3k5/9/9/9/4P4/9/9/9/9/5K3 w",
+                    "Tốt chỉ tiến thẳng; qua sông rồi mới được đi ngang. Tốt e5 đã qua sông: đi ngang một ô.",
+                    "e5d5 e5f5",
+                ),
+            ],
+        ),
+        lesson(
+            "cannon",
+            "Quân Pháo",
+            &[
+                (
+                    "3k5/9/9/9/9/9/9/1C7/9/4K4 w",
+                    "Khi không ăn quân, Pháo đi như Xe. Đưa Pháo từ b2 sang e2.",
+                    "b2e2",
+                ),
+                (
+                    "1n1k5/9/9/1p7/9/9/9/1C7/9/4K4 w",
+                    "Muốn ăn quân, Pháo phải nhảy qua đúng một quân làm ngòi. Dùng Tốt đen ở b6 làm ngòi, ăn Mã ở b9.",
+                    "b2b9",
+                ),
+            ],
+        ),
+        lesson(
+            "flying-general",
+            "Lộ mặt tướng",
+            &[
+                (
+                    "4k4/9/9/9/9/4R4/9/9/9/4K4 w",
+                    "Hai Tướng không được đối mặt trên một cột trống. Xe e4 đang che giữa nên chỉ được đi dọc cột e: tiến Xe lên e8 chiếu, Tướng đen không dám ăn Xe.",
+                    "e4e8",
+                ),
+                (
+                    "3k5/9/9/9/R8/9/9/9/9/4K4 w",
+                    "Tướng đỏ khống chế cả cột e. Đưa Xe sang cột d chiếu: Tướng đen không còn đường chạy.",
+                    "a5d5",
+                ),
+            ],
+        ),
+        lesson(
+            "basic-mates",
+            "Chiếu bí cơ bản",
+            &[
+                (
+                    "4k4/R8/8R/9/9/9/9/9/9/3K5 w",
+                    "Song Xe: một Xe giữ hàng 8, Xe kia chiếu ở hàng cuối.",
+                    "i7i9",
+                ),
+                (
+                    "3aka3/9/9/9/4C4/9/1C7/9/9/3K5 w",
+                    "Pháo trùng: đưa Pháo thứ hai về cột giữa, sau Pháo kia.",
+                    "b3e3",
+                ),
+                (
+                    "3ak4/9/4P4/9/2N6/9/9/9/9/3K1R3 w",
+                    "Mã chiếu, Tốt và Xe giữ các ô Tướng đen có thể chạy.",
+                    "c5d7",
+                ),
+            ],
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::Color;
+    use crate::logic::game::GameStatus;
+
+    #[test]
+    fn test_lessons_are_playable() {
+        let lessons = lessons();
+        assert_eq!(lessons.len(), 4);
+        for lesson in &lessons {
+            assert!(!lesson.steps.is_empty(), "{}", lesson.id);
+            for step in &lesson.steps {
+                let state = step.initial_state().unwrap();
+                assert_eq!(state.turn, Color::Red, "{}", step.fen);
+                assert!(!step.accepted.is_empty(), "{}", step.fen);
+                for &(from, to) in &step.accepted {
+                    state.clone().make_move(from, to).unwrap_or_else(|e| {
+                        panic!("{}: {:?} illegal: {:?}", step.fen, (from, to), e)
+                    });
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_flying_general_lesson() {
+        let lesson = lessons()
+            .into_iter()
+            .find(|l| l.id == "flying-general")
+            .unwrap();
+
+        // The chariot between the generals may not leave the file
+        let pinned = &lesson.steps[0];
+        let state = pinned.initial_state().unwrap();
+        let (from, sideways) = parse_iccs("e4a4").unwrap();
+        assert!(state.clone().make_move(from, sideways).is_err());
+        assert!(!pinned.accepts(from, sideways));
+
+        // Facing generals leave the black general nowhere to go
+        let mate = &lesson.steps[1];
+        let mut state = mate.initial_state().unwrap();
+        let (from, to) = mate.accepted[0];
+        state.make_move(from, to).unwrap();
+        assert_eq!(state.status, GameStatus::Checkmate(Color::Red));
+    }
+}
@@ -19,6 +19,13 @@ pub struct AttackTables {
     pub general_moves: [Vec<usize>; 90],
     // Soldier: [color_idx][sq] -> target_sq
     pub soldier_moves: [[Vec<usize>; 90]; 2],
+    // Reverse tables for attack detection, indexed by the attacked square.
+    // Horse: (horse_sq, leg_sq) for every square a horse could attack from
+    pub horse_attackers: [Vec<(usize, usize)>; 90],
+    // Same squares as a bitboard, to skip the leg checks when no horse is there
+    pub horse_attack_masks: [u128; 90],
+    // Soldier: [attacker color_idx][sq] -> bitboard of squares a soldier attacks sq from
+    pub soldier_attack_masks: [[u128; 90]; 2],
 }
 
 impl AttackTables {
@@ -51,6 +58,21 @@ impl AttackTables {
             soldier_moves[1][i] = compute_soldier_moves(i, 1); // Black
         }
 
+        let mut horse_attackers: [Vec<(usize, usize)>; 90] = core::array::from_fn(|_| Vec::new());
+        let mut horse_attack_masks = [0u128; 90];
+        let mut soldier_attack_masks = [[0u128; 90]; 2];
+        for from in 0..90 {
+            for &(target, leg) in &horse_moves[from] {
+                horse_attackers[target].push((from, leg));
+                horse_attack_masks[target] |= 1u128 << from;
+            }
+            for color_idx in 0..2 {
+                for &target in &soldier_moves[color_idx][from] {
+                    soldier_attack_masks[color_idx][target] |= 1u128 << from;
+                }
+            }
+        }
+
         Self {
             rook_table,
             cannon_table,
@@ -59,6 +81,9 @@ impl AttackTables {
             advisor_moves,
             general_moves,
             soldier_moves,
+            horse_attackers,
+            horse_attack_masks,
+            soldier_attack_masks,
         }
     }
 
@@ -1,10 +1,20 @@
+pub mod analysis;
+pub mod attacks;
 pub mod board;
 pub mod eval_constants;
 pub mod game;
 pub mod generator;
+pub mod handicap;
+pub mod lesson;
 pub mod lookup;
+pub mod notation;
 pub mod opening;
+pub mod puzzle;
+pub mod record;
+pub mod repetition;
 pub mod rules;
+pub mod share;
+pub mod training;
 
 #[cfg(test)]
 mod repetition_test;
@@ -0,0 +1,461 @@
+use crate::logic::board::{Board, BoardCoordinate, Color, PieceType};
+use crate::logic::puzzle::parse_iccs;
+use crate::logic::rules::is_valid_move;
+use std::fmt;
+
+/// Which piece a WXF move starts from: the one on a file, or the front or rear of two
+/// identical pieces sharing a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WxfOrigin {
+    File(u8),
+    Front,
+    Rear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WxfDirection {
+    Advance,
+    Retreat,
+    Traverse,
+}
+
+/// A move in WXF notation, e.g. `C2=5` or `H8+7`. Files are numbered 1-9 from each
+/// player's own right. Straight movers (general, chariot, cannon, soldier) advance or
+/// retreat by a number of ranks; the other pieces name the file they land on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WxfMove {
+    pub piece: PieceType,
+    pub origin: WxfOrigin,
+    pub direction: WxfDirection,
+    pub target: u8,
+}
+
+const fn moves_straight(piece: PieceType) -> bool {
+    matches!(
+        piece,
+        PieceType::General | PieceType::Chariot | PieceType::Cannon | PieceType::Soldier
+    )
+}
+
+const fn letter(piece: PieceType) -> char {
+    match piece {
+        PieceType::General => 'K',
+        PieceType::Advisor => 'A',
+        PieceType::Elephant => 'E',
+        PieceType::Horse => 'H',
+        PieceType::Chariot => 'R',
+        PieceType::Cannon => 'C',
+        PieceType::Soldier => 'P',
+    }
+}
+
+fn piece_from_letter(c: char) -> Option<PieceType> {
+    Some(match c.to_ascii_uppercase() {
+        'K' | 'G' => PieceType::General,
+        'A' => PieceType::Advisor,
+        'E' | 'B' => PieceType::Elephant,
+        'H' | 'N' => PieceType::Horse,
+        'R' => PieceType::Chariot,
+        'C' => PieceType::Cannon,
+        'P' => PieceType::Soldier,
+        _ => return None,
+    })
+}
+
+fn chinese_piece(c: char) -> Option<PieceType> {
+    Some(match c {
+        '帅' | '帥' | '将' | '將' => PieceType::General,
+        '仕' | '士' => PieceType::Advisor,
+        '相' | '象' => PieceType::Elephant,
+        '马' | '馬' | '傌' => PieceType::Horse,
+        '车' | '車' | '俥' => PieceType::Chariot,
+        '炮' | '砲' | '包' => PieceType::Cannon,
+        '兵' | '卒' => PieceType::Soldier,
+        _ => return None,
+    })
+}
+
+/// A file or rank count 1-9, as Red's characters or Black's digits (ASCII or full width).
+fn chinese_number(c: char) -> Option<u8> {
+    let n = match c {
+        '一' => 1,
+        '二' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        '１'..='９' => u32::from(c) - u32::from('０'),
+        _ => c.to_digit(10)?,
+    };
+    u8::try_from(n).ok().filter(|n| (1..=9).contains(n))
+}
+
+/// WXF file number of board column `col` for `color`.
+fn file_of(col: usize, color: Color) -> u8 {
+    #[allow(clippy::cast_possible_truncation)]
+    let col = col as u8;
+    match color {
+        Color::Red => 9 - col,
+        Color::Black => col + 1,
+    }
+}
+
+/// Board column of WXF file `file` for `color`.
+fn col_of(file: u8, color: Color) -> Option<usize> {
+    if !(1..=9).contains(&file) {
+        return None;
+    }
+    Some(match color {
+        Color::Red => usize::from(9 - file),
+        Color::Black => usize::from(file - 1),
+    })
+}
+
+/// Rows of `color`'s pieces of type `piece` on column `col`, front (closest to the enemy) first.
+fn pieces_on_file(board: &Board, color: Color, piece: PieceType, col: usize) -> Vec<usize> {
+    let mut rows: Vec<usize> = (0..10)
+        .filter(|&row| {
+            BoardCoordinate::new(row, col)
+                .and_then(|pos| board.get_piece(pos))
+                .is_some_and(|p| p.color == color && p.piece_type == piece)
+        })
+        .collect();
+    if color == Color::Red {
+        rows.reverse();
+    }
+    rows
+}
+
+impl WxfMove {
+    /// Parses `C2=5`, `c2.5`, `R++1` or `+R+1` (front chariot advances one rank).
+    pub fn parse(text: &str) -> Option<Self> {
+        let chars: Vec<char> = text.trim().chars().collect();
+        let [a, b, op, target] = chars[..] else {
+            return None;
+        };
+        // The front/rear sign may come before or after the piece letter
+        let (piece, origin) = match (piece_from_letter(a), piece_from_letter(b)) {
+            (Some(piece), None) => (piece, b),
+            (None, Some(piece)) => (piece, a),
+            _ => return None,
+        };
+        let origin = match origin {
+            '+' => WxfOrigin::Front,
+            '-' => WxfOrigin::Rear,
+            c => WxfOrigin::File(u8::try_from(c.to_digit(10)?).ok()?),
+        };
+        let direction = match op {
+            '+' => WxfDirection::Advance,
+            '-' => WxfDirection::Retreat,
+            '=' | '.' => WxfDirection::Traverse,
+            _ => return None,
+        };
+        let target = u8::try_from(target.to_digit(10)?).ok()?;
+        (1..=9).contains(&target).then_some(Self {
+            piece,
+            origin,
+            direction,
+            target,
+        })
+    }
+
+    /// Parses a move written in Chinese, e.g. `炮二平五` (Red counts files in characters),
+    /// `马8进7` (Black in digits) or `前车进一`. Simplified and traditional forms are read.
+    pub fn parse_chinese(text: &str) -> Option<Self> {
+        let chars: Vec<char> = text.trim().chars().collect();
+        let [a, b, op, target] = chars[..] else {
+            return None;
+        };
+        let (piece, origin) = match a {
+            '前' => (chinese_piece(b)?, WxfOrigin::Front),
+            '后' | '後' => (chinese_piece(b)?, WxfOrigin::Rear),
+            _ => (chinese_piece(a)?, WxfOrigin::File(chinese_number(b)?)),
+        };
+        let direction = match op {
+            '进' | '進' => WxfDirection::Advance,
+            '退' => WxfDirection::Retreat,
+            '平' => WxfDirection::Traverse,
+            _ => return None,
+        };
+        Some(Self {
+            piece,
+            origin,
+            direction,
+            target: chinese_number(target)?,
+        })
+    }
+
+    /// Names the move `from`-`to` on `board`, before it is played.
+    #[must_use]
+    pub fn describe(board: &Board, from: BoardCoordinate, to: BoardCoordinate) -> Option<Self> {
+        let piece = board.get_piece(from)?;
+        let color = piece.color;
+        let tandem = pieces_on_file(board, color, piece.piece_type, from.col);
+        let origin = match tandem.as_slice() {
+            [front, _] if *front == from.row => WxfOrigin::Front,
+            [_, _] => WxfOrigin::Rear,
+            _ => WxfOrigin::File(file_of(from.col, color)),
+        };
+        let forward = (to.row > from.row) == (color == Color::Red);
+        let (direction, target) = if to.row == from.row {
+            (WxfDirection::Traverse, file_of(to.col, color))
+        } else {
+            let direction = if forward {
+                WxfDirection::Advance
+            } else {
+                WxfDirection::Retreat
+            };
+            let target = if moves_straight(piece.piece_type) {
+                u8::try_from(to.row.abs_diff(from.row)).ok()?
+            } else {
+                file_of(to.col, color)
+            };
+            (direction, target)
+        };
+        Some(Self {
+            piece: piece.piece_type,
+            origin,
+            direction,
+            target,
+        })
+    }
+
+    /// The legal move this notation stands for when `turn` is to move, if exactly one fits.
+    #[must_use]
+    pub fn resolve(
+        &self,
+        board: &Board,
+        turn: Color,
+    ) -> Option<(BoardCoordinate, BoardCoordinate)> {
+        let starts: Vec<BoardCoordinate> = match self.origin {
+            WxfOrigin::File(file) => {
+                let col = col_of(file, turn)?;
+                pieces_on_file(board, turn, self.piece, col)
+                    .into_iter()
+                    .filter_map(|row| BoardCoordinate::new(row, col))
+                    .collect()
+            }
+            WxfOrigin::Front | WxfOrigin::Rear => (0..9)
+                .filter_map(|col| {
+                    let rows = pieces_on_file(board, turn, self.piece, col);
+                    let row = match (self.origin, rows.as_slice()) {
+                        (WxfOrigin::Front, [front, _]) => *front,
+                        (WxfOrigin::Rear, [_, rear]) => *rear,
+                        _ => return None,
+                    };
+                    BoardCoordinate::new(row, col)
+                })
+                .collect(),
+        };
+
+        let mut found = starts.into_iter().filter_map(|from| {
+            let to = self.destination(from, turn)?;
+            is_valid_move(board, from, to, turn).ok()?;
+            Some((from, to))
+        });
+        let first = found.next()?;
+        found.next().is_none().then_some(first)
+    }
+
+    fn destination(&self, from: BoardCoordinate, turn: Color) -> Option<BoardCoordinate> {
+        if self.direction == WxfDirection::Traverse {
+            return BoardCoordinate::new(from.row, col_of(self.target, turn)?);
+        }
+        let up = (self.direction == WxfDirection::Advance) == (turn == Color::Red);
+        let (rows, col) = if moves_straight(self.piece) {
+            (usize::from(self.target), from.col)
+        } else {
+            let col = col_of(self.target, turn)?;
+            let rows = match (self.piece, col.abs_diff(from.col)) {
+                (PieceType::Advisor, 1) | (PieceType::Horse, 2) => 1,
+                (PieceType::Elephant, 2) | (PieceType::Horse, 1) => 2,
+                _ => return None,
+            };
+            (rows, col)
+        };
+        let row = if up {
+            from.row + rows
+        } else {
+            from.row.checked_sub(rows)?
+        };
+        BoardCoordinate::new(row, col)
+    }
+
+    /// The move as read aloud in Vietnamese, e.g. "Pháo 2 bình 5" or "Xe trước tiến 1".
+    #[must_use]
+    pub fn spoken(&self) -> String {
+        let piece = match self.piece {
+            PieceType::General => "Tướng",
+            PieceType::Advisor => "Sĩ",
+            PieceType::Elephant => "Tượng",
+            PieceType::Horse => "Mã",
+            PieceType::Chariot => "Xe",
+            PieceType::Cannon => "Pháo",
+            PieceType::Soldier => "Tốt",
+        };
+        let origin = match self.origin {
+            WxfOrigin::File(file) => file.to_string(),
+            WxfOrigin::Front => "trước".to_string(),
+            WxfOrigin::Rear => "sau".to_string(),
+        };
+        let direction = match self.direction {
+            WxfDirection::Advance => "tiến",
+            WxfDirection::Retreat => "thoái",
+            WxfDirection::Traverse => "bình",
+        };
+        format!("{piece} {origin} {direction} {}", self.target)
+    }
+}
+
+impl fmt::Display for WxfMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let origin = match self.origin {
+            WxfOrigin::File(file) => char::from(b'0' + file),
+            WxfOrigin::Front => '+',
+            WxfOrigin::Rear => '-',
+        };
+        let op = match self.direction {
+            WxfDirection::Advance => '+',
+            WxfDirection::Retreat => '-',
+            WxfDirection::Traverse => '=',
+        };
+        write!(f, "{}{origin}{op}{}", letter(self.piece), self.target)
+    }
+}
+
+/// Why typed text could not be played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveTextError {
+    /// Not a move in any notation we read.
+    Unreadable,
+    /// A well-formed move that no piece of the side to move can make, or that two could.
+    Illegal,
+}
+
+/// Reads a typed move in WXF (`C2=5`), ICCS (`h2e2`) or the PGN form (`H2-E2`), and checks
+/// it is a legal move for `turn`.
+pub fn parse_move(
+    board: &Board,
+    turn: Color,
+    text: &str,
+) -> Result<(BoardCoordinate, BoardCoordinate), MoveTextError> {
+    let text = text.trim();
+    if let Some(wxf) = WxfMove::parse(text) {
+        return wxf.resolve(board, turn).ok_or(MoveTextError::Illegal);
+    }
+    let iccs: String = text
+        .chars()
+        .filter(|&c| c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let (from, to) = parse_iccs(&iccs).ok_or(MoveTextError::Unreadable)?;
+    is_valid_move(board, from, to, turn).map_err(|_| MoveTextError::Illegal)?;
+    Ok((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::game::GameState;
+
+    fn resolve(state: &GameState, wxf: &str) -> Option<(BoardCoordinate, BoardCoordinate)> {
+        WxfMove::parse(wxf)?.resolve(&state.board, state.turn)
+    }
+
+    #[test]
+    fn test_wxf_round_trip() {
+        let mut state = GameState::new();
+        for (wxf, iccs) in [
+            ("C2=5", "h2e2"),
+            ("H8+7", "h9g7"),
+            ("H2+3", "h0g2"),
+            ("R9=8", "i9h9"),
+            ("P7+1", "c3c4"),
+            ("A4+5", "d9e8"),
+            ("E3+1", "g0i2"),
+            ("K5=4", "e9d9"),
+        ] {
+            let expected = parse_iccs(iccs).unwrap();
+            assert_eq!(resolve(&state, wxf), Some(expected), "{wxf}");
+            let (from, to) = expected;
+            let described = WxfMove::describe(&state.board, from, to).unwrap();
+            assert_eq!(described.to_string(), wxf);
+            state.make_move(from, to).unwrap();
+        }
+        assert_eq!(WxfMove::parse("c2.5").unwrap().to_string(), "C2=5");
+        assert_eq!(WxfMove::parse("C2=5").unwrap().spoken(), "Pháo 2 bình 5");
+        assert_eq!(WxfMove::parse("X2=5"), None);
+        assert_eq!(WxfMove::parse("C2=0"), None);
+        // The red cannon on file 2 is no longer there
+        assert_eq!(resolve(&state, "C2=4"), None);
+    }
+
+    #[test]
+    fn test_wxf_tandem_pieces() {
+        let (board, turn) = Board::from_fen("3k5/9/9/9/R8/9/9/R8/9/4K4 w").unwrap();
+        let state = GameState::from_position(board, turn);
+
+        let front = parse_iccs("a5a7").unwrap();
+        assert_eq!(resolve(&state, "R++2"), Some(front));
+        assert_eq!(resolve(&state, "+R+2"), Some(front));
+        assert_eq!(resolve(&state, "R-+1"), parse_iccs("a2a3"));
+        // Two chariots on file 9: the file number alone is ambiguous
+        assert_eq!(resolve(&state, "R9=8"), None);
+
+        let described = WxfMove::describe(&state.board, front.0, front.1).unwrap();
+        assert_eq!(described.to_string(), "R++2");
+        assert_eq!(described.spoken(), "Xe trước tiến 2");
+    }
+
+    #[test]
+    fn test_parse_move_notations() {
+        let state = GameState::new();
+        let expected = parse_iccs("h2e2");
+        for text in ["C2=5", " c2.5 ", "h2e2", "H2E2", "H2-E2"] {
+            assert_eq!(
+                parse_move(&state.board, state.turn, text).ok(),
+                expected,
+                "{text}"
+            );
+        }
+        assert_eq!(
+            parse_move(&state.board, state.turn, "hello"),
+            Err(MoveTextError::Unreadable)
+        );
+        // Well-formed but not playable: a chariot through its own soldier, a horse three files over
+        assert_eq!(
+            parse_move(&state.board, state.turn, "a0-a4"),
+            Err(MoveTextError::Illegal)
+        );
+        assert_eq!(
+            parse_move(&state.board, state.turn, "H2+5"),
+            Err(MoveTextError::Illegal)
+        );
+    }
+
+    #[test]
+    fn test_parse_chinese() {
+        let mut state = GameState::new();
+        for (chinese, wxf) in [
+            ("炮二平五", "C2=5"),
+            ("馬８進７", "H8+7"),
+            ("马二进三", "H2+3"),
+            ("車9平8", "R9=8"),
+            ("兵七进一", "P7+1"),
+        ] {
+            let parsed = WxfMove::parse_chinese(chinese).unwrap();
+            assert_eq!(parsed.to_string(), wxf);
+            let (from, to) = parsed.resolve(&state.board, state.turn).unwrap();
+            state.make_move(from, to).unwrap();
+        }
+        assert_eq!(
+            WxfMove::parse_chinese("前炮退二").map(|m| m.to_string()),
+            Some("C+-2".to_string())
+        );
+        assert!(WxfMove::parse_chinese("炮十平五").is_none());
+        assert!(WxfMove::parse_chinese("C2=5").is_none());
+    }
+}
@@ -1,87 +1,186 @@
 use crate::logic::board::{Board, BoardCoordinate, Color};
+use crate::logic::game::GameState;
 use rand::seq::SliceRandom;
 
-pub fn get_book_move(board: &Board, turn: Color) -> Option<(BoardCoordinate, BoardCoordinate)> {
+/// One book reply. `weight` is how often it is picked relative to the other replies in the
+/// same position, roughly following how often strong players choose it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub from: BoardCoordinate,
+    pub to: BoardCoordinate,
+    pub weight: u32,
+    pub name: &'static str,
+}
+
+type BookEntry = ((usize, usize), (usize, usize), u32, &'static str);
+
+/// Positions as produced by `Board::to_fen_string` (pieces and side to move only), each with
+/// its replies in array coordinates. Red is at the bottom (rows 0-4).
+const BOOK: &[(&str, &[BookEntry])] = &[
+    // Starting position, Red to move
+    (
+        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w",
+        &[
+            // Central Cannon (Pháo Đầu), from either side
+            ((2, 1), (2, 4), 30, "Pháo đầu (Pháo 8 bình 5)"),
+            ((2, 7), (2, 4), 30, "Pháo đầu (Pháo 2 bình 5)"),
+            // Elephant opening
+            ((0, 2), (2, 4), 10, "Phi tượng (Tượng 7 tiến 5)"),
+            ((0, 6), (2, 4), 10, "Phi tượng (Tượng 3 tiến 5)"),
+            // Pawn opening (Tiên Nhân Chỉ Lộ)
+            ((3, 2), (4, 2), 10, "Tiên nhân chỉ lộ (Binh 7 tiến 1)"),
+            ((3, 6), (4, 6), 10, "Tiên nhân chỉ lộ (Binh 3 tiến 1)"),
+        ],
+    ),
+    // After Red's Cannon 2 to 5, (2,7)->(2,4)
+    (
+        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C2C4/9/RNBAKABNR b",
+        &[
+            // Screen Horses (Bình Phong Mã)
+            ((9, 7), (7, 6), 35, "Bình phong mã (Mã 8 tiến 7)"),
+            ((9, 1), (7, 2), 35, "Bình phong mã (Mã 2 tiến 3)"),
+            // Same Direction Cannon (Thuận Pháo)
+            ((7, 7), (7, 4), 30, "Thuận pháo (Pháo 8 bình 5)"),
+        ],
+    ),
+    // After Red's Cannon 8 to 5, (2,1)->(2,4)
+    (
+        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/4C2C1/9/RNBAKABNR b",
+        &[
+            ((9, 7), (7, 6), 35, "Bình phong mã (Mã 8 tiến 7)"),
+            ((9, 1), (7, 2), 35, "Bình phong mã (Mã 2 tiến 3)"),
+            ((7, 1), (7, 4), 30, "Thuận pháo (Pháo 2 bình 5)"),
+        ],
+    ),
+];
+
+/// Every book reply for the position, most played first. Empty once the game leaves the book.
+pub fn book_moves(board: &Board, turn: Color) -> Vec<BookMove> {
     let fen = board.to_fen_string(turn);
-    // Only match the piece placement and turn, ignore move counts if any
-    // Our to_fen_string returns "pieces turn" e.g. "rnbakabnr/9/... w"
+    let mut moves: Vec<BookMove> = BOOK
+        .iter()
+        .filter(|(position, _)| *position == fen)
+        .flat_map(|(_, entries)| entries.iter())
+        .filter_map(|&((fr, fc), (tr, tc), weight, name)| {
+            Some(BookMove {
+                from: BoardCoordinate::new(fr, fc)?,
+                to: BoardCoordinate::new(tr, tc)?,
+                weight,
+                name,
+            })
+        })
+        .collect();
+    moves.sort_by_key(|m| std::cmp::Reverse(m.weight));
+    moves
+}
 
+/// A book reply picked at random in proportion to the weights.
+pub fn get_book_move(board: &Board, turn: Color) -> Option<(BoardCoordinate, BoardCoordinate)> {
+    let moves = book_moves(board, turn);
     let mut rng = rand::thread_rng();
+    moves
+        .choose_weighted(&mut rng, |m| m.weight)
+        .ok()
+        .map(|m| (m.from, m.to))
+}
+
+/// The book moves `game` opened with and who played them, up to where it left the book.
+/// Each position's book replies are the children of the previous move, so the line is a
+/// walk down the book from the start position.
+pub fn book_line(game: &GameState) -> Vec<(Color, &'static str)> {
+    let mut state = game.clone();
+    while state.undo_move() {}
 
-    let raw_move = match fen.as_str() {
-        // Starting Position (Red to move)
-        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w" => {
-            let moves = [
-                // Central Cannon (Pháo Đầu): C2.5
-                ((2, 1), (2, 4)), // C8 -> C5 (in array coords: row 2, col 1 -> row 2, col 4) ??
-                // Wait, array coords:
-                // Red is at bottom (rows 0-4).
-                // Red Cannons are at row 2, cols 1 and 7.
-                // Move (2, 1) -> (2, 4) is Cannon to center.
-                ((2, 7), (2, 4)), // Other Cannon to center
-                // Elephant (Tiên Nhân Chỉ Lộ): E3.5 or E7.5
-                // Elephants at row 0, cols 2 and 6.
-                // Move (0, 2) -> (2, 4) ?? No, Elephant moves 2 diag.
-                // Elephant moves to (2, 0) or (2, 4)? No.
-                // Elephant moves: (0, 2) -> (2, 0) or (2, 4).
-                // Wait, Elephant is at (0, 2). Valid moves: (2, 0), (2, 4).
-                ((0, 2), (2, 4)),
-                ((0, 6), (2, 4)),
-                // Pawn 3 or 7 (P3.1, P7.1)
-                // Pawns at row 3, cols 0, 2, 4, 6, 8.
-                ((3, 2), (4, 2)),
-                ((3, 6), (4, 6)),
-            ];
-            moves.choose(&mut rng).copied()
+    let mut line = Vec::new();
+    for record in &game.history {
+        let Some(book) = book_moves(&state.board, state.turn)
+            .into_iter()
+            .find(|m| m.from == record.from && m.to == record.to)
+        else {
+            break;
+        };
+        line.push((state.turn, book.name));
+        if state.make_move(record.from, record.to).is_err() {
+            break;
         }
+    }
+    line
+}
 
-        // Response to Central Cannon (Black to move)
-        // Red played C2.5 (2,7)->(2,4) or (2,1)->(2,4)
-        // FEN for (2,7)->(2,4): "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C2C4/9/RNBAKABNR b"
-        // Wait, let's calculate row 2 correctly.
-        // Orig: 1C5C1. (0:1, 1:C, 2..6:5, 7:C, 8:1)
-        // Move (2,7)->(2,4).
-        // New: 0:1, 1:C, 2:1, 3:1, 4:C, 5:1, 6:1, 7:1, 8:1.
-        // Groups: 1, C, 2, C, 4. -> "1C2C4"
-        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C2C4/9/RNBAKABNR b" => {
-            let moves = [
-                // Screen Horse (Bình Phong Mã): H8+7 (9,7)->(7,6) or H2+3 (9,1)->(7,2)
-                ((9, 7), (7, 6)),
-                ((9, 1), (7, 2)),
-                // Same Direction Cannon (Thuận Pháo): C8.5 (7,7)->(7,4)
-                ((7, 7), (7, 4)),
-            ];
-            moves.choose(&mut rng).copied()
+/// A book name without its move notation: "Pháo đầu (Pháo 2 bình 5)" is "Pháo đầu".
+fn family(name: &'static str) -> &'static str {
+    name.split_once(" (").map_or(name, |(family, _)| family)
+}
+
+/// The opening `color` chose in `game`: its last book move before the game left the book,
+/// named without the move notation (e.g. "Pháo đầu"). `None` if that side never played one.
+pub fn classify_opening(game: &GameState, color: Color) -> Option<&'static str> {
+    book_line(game)
+        .into_iter()
+        .rev()
+        .find(|(mover, _)| *mover == color)
+        .map(|(_, name)| family(name))
+}
+
+/// The name of the game's opening, Red's choice then Black's answer, e.g.
+/// "Pháo đầu – Bình phong mã". `None` when the game did not start with a book move.
+pub fn opening_name(game: &GameState) -> Option<String> {
+    let names: Vec<&str> = [Color::Red, Color::Black]
+        .into_iter()
+        .filter_map(|color| classify_opening(game, color))
+        .collect();
+    (!names.is_empty()).then(|| names.join(" – "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_book_moves_are_legal() {
+        for (fen, _) in BOOK {
+            let (board, turn) = Board::from_fen(fen).unwrap();
+            let moves = book_moves(&board, turn);
+            assert!(!moves.is_empty(), "{fen}");
+            for mv in moves {
+                let mut state = GameState::from_position(board.clone(), turn);
+                assert!(
+                    state.make_move(mv.from, mv.to).is_ok(),
+                    "{} in {fen}",
+                    mv.name
+                );
+            }
         }
-        // Red played C8.5 (2,1)->(2,4)
-        // Orig: 1C5C1. Move (2,1)->(2,4).
-        // New: 0:1, 1:1, 2:1, 3:1, 4:C, 5:1, 6:1, 7:C, 8:1.
-        // Groups: 4, C, 2, C, 1. -> "4C2C1"
-        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/4C2C1/9/RNBAKABNR b" => {
-            let moves = [
-                // Screen Horse
-                ((9, 7), (7, 6)),
-                ((9, 1), (7, 2)),
-                // Same Direction Cannon: C2.5 (7,1)->(7,4)
-                ((7, 1), (7, 4)),
-            ];
-            moves.choose(&mut rng).copied()
+    }
+
+    #[test]
+    fn test_book_sorted_by_weight() {
+        let moves = book_moves(&Board::new(), Color::Red);
+        assert!(moves.windows(2).all(|w| w[0].weight >= w[1].weight));
+        assert!(get_book_move(&Board::new(), Color::Red).is_some());
+    }
+
+    #[test]
+    fn test_classify_opening() {
+        let square = |row, col| BoardCoordinate::new(row, col).unwrap();
+        let mut game = GameState::new();
+        assert_eq!(classify_opening(&game, Color::Red), None);
+
+        // Central cannon answered by a screen horse, then out of the book
+        for ((fr, fc), (tr, tc)) in [((2, 7), (2, 4)), ((9, 7), (7, 6)), ((0, 7), (2, 6))] {
+            game.make_move(square(fr, fc), square(tr, tc)).unwrap();
         }
+        assert_eq!(classify_opening(&game, Color::Red), Some("Pháo đầu"));
+        assert_eq!(classify_opening(&game, Color::Black), Some("Bình phong mã"));
+        assert_eq!(book_line(&game).len(), 2);
+        assert_eq!(
+            opening_name(&game).as_deref(),
+            Some("Pháo đầu – Bình phong mã")
+        );
 
-        // Let's rely on the user's provided coordinates if possible, but I need to match the FEN.
-        // Since I can't easily predict the FEN for every move without running the engine,
-        // I will just add the starting position for now, and maybe one or two obvious ones if I can derive the FEN.
-
-        // Actually, I can just print the FEN in the game log to debug and add more later.
-        // For now, I'll implement the structure and the starting position.
-        _ => None,
-    };
-
-    if let Some(((f_r, f_c), (t_r, t_c))) = raw_move {
-        let from = BoardCoordinate::new(f_r, f_c)?;
-        let to = BoardCoordinate::new(t_r, t_c)?;
-        Some((from, to))
-    } else {
-        None
+        // A game that never entered the book has no opening
+        let mut off_book = GameState::new();
+        off_book.make_move(square(0, 0), square(1, 0)).unwrap();
+        assert_eq!(opening_name(&off_book), None);
     }
 }
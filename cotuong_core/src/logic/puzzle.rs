@@ -0,0 +1,234 @@
+use crate::logic::board::{Board, BoardCoordinate};
+use crate::logic::game::{GameState, GameStatus};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PuzzleTheme {
+    /// Checkmate in the given number of the solver's moves.
+    MateIn(u8),
+    /// Win material or reach a winning position.
+    Tactic,
+}
+
+/// A training position. `solution_moves` alternates between the solver's moves and the
+/// opponent's replies, starting with the solver (the side to move in `fen`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub fen: String,
+    pub solution_moves: Vec<(BoardCoordinate, BoardCoordinate)>,
+    pub theme: PuzzleTheme,
+}
+
+/// Outcome of checking one of the solver's moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleStep {
+    /// Right move; the opponent now answers with the given reply.
+    Continue(Option<(BoardCoordinate, BoardCoordinate)>),
+    Solved,
+    Wrong,
+}
+
+/// Parses a move in ICCS notation, e.g. `h2e2`: files `a`-`i` from Red's left, ranks `0`-`9`
+/// from Red's side.
+pub fn parse_iccs(s: &str) -> Option<(BoardCoordinate, BoardCoordinate)> {
+    let bytes = s.as_bytes();
+    if bytes.len() != 4 {
+        return None;
+    }
+    let square = |file: u8, rank: u8| {
+        if !(b'a'..=b'i').contains(&file) || !rank.is_ascii_digit() {
+            return None;
+        }
+        BoardCoordinate::new((rank - b'0') as usize, (file - b'a') as usize)
+    };
+    Some((square(bytes[0], bytes[1])?, square(bytes[2], bytes[3])?))
+}
+
+impl Puzzle {
+    /// Builds a puzzle from a FEN and a space separated list of ICCS moves.
+    pub fn parse(fen: &str, solution: &str, theme: PuzzleTheme) -> Option<Self> {
+        let solution_moves = solution
+            .split_whitespace()
+            .map(parse_iccs)
+            .collect::<Option<Vec<_>>>()?;
+        Board::from_fen(fen).ok()?;
+        Some(Self {
+            fen: fen.to_string(),
+            solution_moves,
+            theme,
+        })
+    }
+
+    /// The position the solver starts from.
+    pub fn initial_state(&self) -> Result<GameState, String> {
+        GameState::from_fen(&self.fen)
+    }
+
+    /// Checks the solver's move at `ply` (0, 2, 4...), given the state after it was played.
+    /// Any mating move ends a mate puzzle, even if it differs from the stored solution.
+    pub fn judge(
+        &self,
+        ply: usize,
+        from: BoardCoordinate,
+        to: BoardCoordinate,
+        after: &GameState,
+    ) -> PuzzleStep {
+        let Some(&expected) = self.solution_moves.get(ply) else {
+            return PuzzleStep::Wrong;
+        };
+
+        if let (PuzzleTheme::MateIn(_), GameStatus::Checkmate(winner)) =
+            (self.theme, &after.status)
+        {
+            if *winner != after.turn {
+                return PuzzleStep::Solved;
+            }
+        }
+
+        if expected != (from, to) {
+            return PuzzleStep::Wrong;
+        }
+        match self.theme {
+            // Reaching the end of a mate line without mate means the line was wrong
+            PuzzleTheme::MateIn(_) if ply + 1 >= self.solution_moves.len() => PuzzleStep::Wrong,
+            PuzzleTheme::Tactic if ply + 1 >= self.solution_moves.len() => PuzzleStep::Solved,
+            _ => PuzzleStep::Continue(self.solution_moves.get(ply + 1).copied()),
+        }
+    }
+}
+
+/// Bundled starter set, easiest first.
+pub fn starter_puzzles() -> Vec<Puzzle> {
+    [
+        // Two chariots: one holds the second rank, the other checks on the back rank
+        (
+            "4k4/R8/8R/9/9/9/9/9/9/3K5 w",
+            "i7i9",
+            PuzzleTheme::MateIn(1),
+        ),
+        // Double cannon on the central file; interposing only opens the front cannon
+        (
+            "3aka3/9/9/9/4C4/9/1C7/9/9/3K5 w",
+            "b3e3",
+            PuzzleTheme::MateIn(1),
+        ),
+        // Horse check with the pawn and chariot guarding the escape squares
+        (
+            "3ak4/9/4P4/9/2N6/9/9/9/9/3K1R3 w",
+            "c5d7",
+            PuzzleTheme::MateIn(1),
+        ),
+        // Force the advisor into the king's way, then check along the back rank
+        (
+            "4ka3/9/9/9/R8/8R/9/9/9/3K5 w",
+            "a5e5 f9e8 i4i9",
+            PuzzleTheme::MateIn(2),
+        ),
+        // Horse fork: check the king and pick up the chariot
+        (
+            "4k4/9/9/1r7/2N6/9/9/9/9/3K5 w",
+            "c5d7 e9e8 d7b6",
+            PuzzleTheme::Tactic,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(fen, solution, theme)| Puzzle::parse(fen, solution, theme))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legal_moves(state: &GameState) -> Vec<(BoardCoordinate, BoardCoordinate)> {
+        let squares: Vec<_> = (0..10)
+            .flat_map(|r| (0..9).filter_map(move |c| BoardCoordinate::new(r, c)))
+            .collect();
+        let mut moves = Vec::new();
+        for &from in &squares {
+            for &to in &squares {
+                if state.clone().make_move(from, to).is_ok() {
+                    moves.push((from, to));
+                }
+            }
+        }
+        moves
+    }
+
+    fn is_mated(state: &GameState) -> bool {
+        state.status == GameStatus::Checkmate(state.turn.opposite())
+    }
+
+    /// Brute force: can the side to move force mate within `n` of its moves?
+    fn forces_mate(state: &GameState, n: u8) -> bool {
+        n > 0
+            && legal_moves(state).into_iter().any(|(from, to)| {
+                let mut next = state.clone();
+                next.make_move(from, to).is_ok()
+                    && (is_mated(&next)
+                        || legal_moves(&next).into_iter().all(|(rf, rt)| {
+                            let mut reply = next.clone();
+                            reply.make_move(rf, rt).is_ok() && forces_mate(&reply, n - 1)
+                        }))
+            })
+    }
+
+    #[test]
+    fn test_parse_iccs() {
+        assert_eq!(
+            parse_iccs("h2e2"),
+            Some((
+                BoardCoordinate::new(2, 7).unwrap(),
+                BoardCoordinate::new(2, 4).unwrap()
+            ))
+        );
+        assert_eq!(parse_iccs("j2e2"), None);
+        assert_eq!(parse_iccs("h2e"), None);
+    }
+
+    #[test]
+    fn test_starter_puzzles_are_sound() {
+        let puzzles = starter_puzzles();
+        assert_eq!(puzzles.len(), 5);
+
+        for puzzle in &puzzles {
+            let mut state = puzzle.initial_state().unwrap();
+            let solver = state.turn;
+            for &(from, to) in &puzzle.solution_moves {
+                state
+                    .make_move(from, to)
+                    .unwrap_or_else(|e| panic!("{}: {:?} illegal: {:?}", puzzle.fen, (from, to), e));
+            }
+
+            if let PuzzleTheme::MateIn(n) = puzzle.theme {
+                assert_eq!(state.status, GameStatus::Checkmate(solver), "{}", puzzle.fen);
+                let start = puzzle.initial_state().unwrap();
+                assert!(forces_mate(&start, n), "{}", puzzle.fen);
+                assert!(!forces_mate(&start, n - 1), "{} is shorter", puzzle.fen);
+            }
+        }
+    }
+
+    #[test]
+    fn test_judge_follows_solution() {
+        let puzzle = &starter_puzzles()[3];
+        let mut state = puzzle.initial_state().unwrap();
+        let (from, to) = puzzle.solution_moves[0];
+        state.make_move(from, to).unwrap();
+        assert_eq!(
+            puzzle.judge(0, from, to, &state),
+            PuzzleStep::Continue(Some(puzzle.solution_moves[1]))
+        );
+
+        let (reply_from, reply_to) = puzzle.solution_moves[1];
+        state.make_move(reply_from, reply_to).unwrap();
+        let (from, to) = puzzle.solution_moves[2];
+        state.make_move(from, to).unwrap();
+        assert_eq!(puzzle.judge(2, from, to, &state), PuzzleStep::Solved);
+
+        let mut wrong = puzzle.initial_state().unwrap();
+        let (from, to) = parse_iccs("a5a9").unwrap();
+        wrong.make_move(from, to).unwrap();
+        assert_eq!(puzzle.judge(0, from, to, &wrong), PuzzleStep::Wrong);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::logic::board::{BoardCoordinate, Color};
+use crate::logic::game::GameState;
+use crate::logic::notation::{MoveTextError, WxfMove};
+use crate::logic::puzzle::parse_iccs;
+use std::fmt;
+
+/// Notation a pasted game record is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// ICCS `h2e2` or the PGN form `H2-E2`.
+    Coordinates,
+    /// WXF, `C2=5`.
+    Wxf,
+    /// Chinese, `炮二平五`.
+    Chinese,
+}
+
+impl RecordFormat {
+    /// Guesses the notation of a record from one of its moves.
+    fn detect(token: &str) -> Option<Self> {
+        if !token.is_ascii() {
+            Some(Self::Chinese)
+        } else if parse_coordinates(token).is_some() {
+            Some(Self::Coordinates)
+        } else if WxfMove::parse(token).is_some() {
+            Some(Self::Wxf)
+        } else {
+            None
+        }
+    }
+
+    /// Reads `token` as a move in this notation for the side to move in `state`.
+    fn read(
+        self,
+        state: &GameState,
+        token: &str,
+    ) -> Result<(BoardCoordinate, BoardCoordinate), MoveTextError> {
+        let wxf = match self {
+            Self::Coordinates => {
+                return parse_coordinates(token).ok_or(MoveTextError::Unreadable);
+            }
+            Self::Wxf => WxfMove::parse(token),
+            Self::Chinese => WxfMove::parse_chinese(token),
+        };
+        wxf.ok_or(MoveTextError::Unreadable)?
+            .resolve(&state.board, state.turn)
+            .ok_or(MoveTextError::Illegal)
+    }
+}
+
+fn parse_coordinates(token: &str) -> Option<(BoardCoordinate, BoardCoordinate)> {
+    let iccs: String = token
+        .chars()
+        .filter(|&c| c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    parse_iccs(&iccs)
+}
+
+/// The move a record could not be replayed past.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError {
+    /// Moves replayed before it.
+    pub ply: usize,
+    /// The side that was to play it.
+    pub color: Color,
+    /// The move as written; empty when the record has no moves at all.
+    pub token: String,
+    pub kind: MoveTextError,
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token.is_empty() {
+            return write!(f, "no moves found");
+        }
+        let reason = match self.kind {
+            MoveTextError::Unreadable => "unreadable",
+            MoveTextError::Illegal => "illegal",
+        };
+        write!(
+            f,
+            "move {} ({:?}) `{}` is {reason}",
+            self.ply + 1,
+            self.color,
+            self.token
+        )
+    }
+}
+
+/// A record replayed from its start position.
+#[derive(Debug, Clone)]
+pub struct ImportedRecord {
+    pub format: RecordFormat,
+    pub state: GameState,
+}
+
+fn is_result(token: &str) -> bool {
+    matches!(token, "1-0" | "0-1" | "1/2-1/2" | "½-½" | "*")
+}
+
+/// The moves of a record, without PGN tags, `{comments}`, move numbers, results and
+/// `!?+#` suffixes, each with the `!`/`?` glyph written after it. Chinese moves written
+/// back to back are split four characters apiece.
+fn move_tokens(text: &str) -> Vec<(String, Option<String>)> {
+    let mut movetext = String::new();
+    let mut in_comment = false;
+    for line in text.lines().filter(|l| !l.trim_start().starts_with('[')) {
+        for c in line.chars() {
+            match c {
+                '{' => in_comment = true,
+                '}' => in_comment = false,
+                ',' | '，' | '、' | '。' | '；' if !in_comment => movetext.push(' '),
+                _ if !in_comment => movetext.push(c),
+                _ => {}
+            }
+        }
+        movetext.push(' ');
+    }
+
+    let mut tokens = Vec::new();
+    for word in movetext.split_whitespace() {
+        if is_result(word) {
+            continue;
+        }
+        // "12." and "12..." before a move, with or without a space after the dots
+        let word = match word.trim_start_matches(|c: char| c.is_ascii_digit()) {
+            rest if rest.starts_with('.') && rest.len() < word.len() => {
+                rest.trim_start_matches('.')
+            }
+            _ => word,
+        };
+        let bare = word.trim_end_matches(['!', '?', '+', '#']);
+        if bare.is_empty() {
+            continue;
+        }
+        let glyph: String = word[bare.len()..]
+            .chars()
+            .filter(|&c| matches!(c, '!' | '?'))
+            .collect();
+        let glyph = (!glyph.is_empty()).then_some(glyph);
+        let chars: Vec<char> = bare.chars().collect();
+        if !bare.is_ascii() && chars.len() > 4 && chars.len().is_multiple_of(4) {
+            tokens.extend(
+                chars
+                    .chunks(4)
+                    .map(|chunk| (chunk.iter().collect::<String>(), None)),
+            );
+            // The glyph belongs to the last move of the run
+            if let Some(last) = tokens.last_mut() {
+                last.1 = glyph;
+            }
+        } else {
+            tokens.push((bare.to_string(), glyph));
+        }
+    }
+    tokens
+}
+
+/// Replays a pasted game record from `start`. The notation is detected from the first
+/// move; any move after it that does not read or play stops the import there.
+pub fn import_record(start: GameState, text: &str) -> Result<ImportedRecord, RecordError> {
+    let tokens = move_tokens(text);
+    let mut state = start;
+    let error = |state: &GameState, ply: usize, token: &str, kind| RecordError {
+        ply,
+        color: state.turn,
+        token: token.to_string(),
+        kind,
+    };
+    let Some(first) = tokens.first() else {
+        return Err(error(&state, 0, "", MoveTextError::Unreadable));
+    };
+    let format = RecordFormat::detect(&first.0)
+        .ok_or_else(|| error(&state, 0, &first.0, MoveTextError::Unreadable))?;
+
+    for (ply, (token, glyph)) in tokens.iter().enumerate() {
+        let (from, to) = format
+            .read(&state, token)
+            .map_err(|kind| error(&state, ply, token, kind))?;
+        state
+            .make_move(from, to)
+            .map_err(|_| error(&state, ply, token, MoveTextError::Illegal))?;
+        if let Some(record) = state.history.last_mut() {
+            record.glyph.clone_from(glyph);
+        }
+    }
+    Ok(ImportedRecord { format, state })
+}
+
+/// The position a record starts from: its `[FEN "..."]` tag, or the usual start.
+pub fn record_start(text: &str) -> Result<GameState, String> {
+    let tag = text.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("[FEN \"")
+            .and_then(|rest| rest.strip_suffix("\"]"))
+    });
+    tag.map_or_else(|| Ok(GameState::new()), GameState::from_user_fen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(text: &str) -> Result<ImportedRecord, RecordError> {
+        import_record(record_start(text).unwrap(), text)
+    }
+
+    #[test]
+    fn each_notation_is_detected() {
+        for (text, format) in [
+            ("1. h2e2 h9g7 2. h0g2", RecordFormat::Coordinates),
+            ("1. H2-E2 H9-G7 2. H0-G2 *", RecordFormat::Coordinates),
+            ("1.C2=5 H8+7 2.H2+3", RecordFormat::Wxf),
+            ("1. 炮二平五 马8进7 2. 马二进三", RecordFormat::Chinese),
+            ("炮二平五馬８進７馬二進三", RecordFormat::Chinese),
+        ] {
+            let record = import(text).unwrap();
+            assert_eq!(record.format, format, "{text}");
+            assert_eq!(record.state.history.len(), 3, "{text}");
+        }
+    }
+
+    #[test]
+    fn pgn_tags_and_comments_are_skipped() {
+        let pgn = "[Game \"Chinese Chess\"]\n[Format \"ICCS\"]\n\n\
+                   1. H2-E2?! {a comment} H9-G7\n2. H0-G2 1-0\n";
+        let record = import(pgn).unwrap();
+        assert_eq!(record.state.history.len(), 3);
+        assert_eq!(record.state.history[0].glyph.as_deref(), Some("?!"));
+        assert_eq!(record.state.history[1].glyph, None);
+    }
+
+    #[test]
+    fn fen_tag_sets_the_start() {
+        let pgn = "[FEN \"4k4/9/9/9/9/9/9/9/5R3/3K5 w - - 0 1\"]\n1. f1f8";
+        let record = import(pgn).unwrap();
+        assert_eq!(record.state.history.len(), 1);
+        assert!(record_start("[FEN \"9/9/9/9/9/9/9/9/9/9 w\"]").is_err());
+    }
+
+    #[test]
+    fn the_failing_move_is_reported() {
+        let error = import("1. C2=5 H8+7 2. C5+9").unwrap_err();
+        assert_eq!(
+            (error.ply, error.color, error.token.as_str(), error.kind),
+            (2, Color::Red, "C5+9", MoveTextError::Illegal)
+        );
+        let error = import("1. h2e2 h9g7 2. xyz").unwrap_err();
+        assert_eq!((error.ply, error.kind), (2, MoveTextError::Unreadable));
+        assert_eq!(error.to_string(), "move 3 (Red) `xyz` is unreadable");
+        assert!(import("").unwrap_err().token.is_empty());
+    }
+}
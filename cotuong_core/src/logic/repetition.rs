@@ -0,0 +1,125 @@
+/// Number of hash-indexed counters; a power of two so the slot is a mask of the hash.
+const SLOTS: usize = 1 << 12;
+
+/// Stack of position hashes along the game and the current search line, with a counter per
+/// hash slot in front of it. Most positions never occurred before, and their slot counter
+/// answers that without walking the stack; only a counter high enough to matter falls back
+/// to counting the exact hash, so collisions never turn into false repetitions.
+#[derive(Debug, Clone)]
+pub struct RepetitionTable {
+    stack: Vec<u64>,
+    slots: Box<[u32]>,
+}
+
+impl Default for RepetitionTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepetitionTable {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::with_capacity(256),
+            slots: vec![0; SLOTS].into_boxed_slice(),
+        }
+    }
+
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    const fn slot(hash: u64) -> usize {
+        hash as usize & (SLOTS - 1)
+    }
+
+    #[inline]
+    pub fn push(&mut self, hash: u64) {
+        self.stack.push(hash);
+        if let Some(count) = self.slots.get_mut(Self::slot(hash)) {
+            *count += 1;
+        }
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<u64> {
+        let hash = self.stack.pop()?;
+        if let Some(count) = self.slots.get_mut(Self::slot(hash)) {
+            *count = count.saturating_sub(1);
+        }
+        Some(hash)
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+        self.slots.fill(0);
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Exact number of times `hash` is on the stack.
+    #[must_use]
+    pub fn count(&self, hash: u64) -> usize {
+        if self.slots.get(Self::slot(hash)).copied().unwrap_or(0) == 0 {
+            return 0;
+        }
+        self.stack.iter().filter(|&&h| h == hash).count()
+    }
+
+    /// Whether `hash` is on the stack at least `times` times; cheaper than `count` when
+    /// the slot counter alone rules it out.
+    #[inline]
+    #[must_use]
+    pub fn occurs_at_least(&self, hash: u64, times: usize) -> bool {
+        let Some(nth) = times.checked_sub(1) else {
+            return true;
+        };
+        let slot = self.slots.get(Self::slot(hash)).copied().unwrap_or(0);
+        if (slot as usize) < times {
+            return false;
+        }
+        self.stack.iter().filter(|&&h| h == hash).nth(nth).is_some()
+    }
+}
+
+impl Extend<u64> for RepetitionTable {
+    fn extend<I: IntoIterator<Item = u64>>(&mut self, iter: I) {
+        for hash in iter {
+            self.push(hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_are_exact_despite_collisions() {
+        let mut table = RepetitionTable::new();
+        let a = 0x1234_5678_0000_0042;
+        // Same slot as `a`, different position
+        let b = a + SLOTS as u64;
+        table.extend([a, b, b]);
+        assert_eq!(table.count(a), 1);
+        assert_eq!(table.count(b), 2);
+        assert!(!table.occurs_at_least(a, 2));
+        assert!(table.occurs_at_least(b, 2));
+        assert_eq!(table.count(7), 0);
+        assert!(table.occurs_at_least(7, 0));
+
+        assert_eq!(table.pop(), Some(b));
+        assert!(!table.occurs_at_least(b, 2));
+        table.clear();
+        assert!(table.is_empty());
+        assert_eq!(table.count(a), 0);
+        assert_eq!(table.pop(), None);
+    }
+}
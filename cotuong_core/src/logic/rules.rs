@@ -41,214 +41,166 @@ pub fn is_valid_move(
     Ok(())
 }
 
-/// Checks if the `color` is currently in check.
+use crate::engine::Move;
 use crate::logic::board::BitboardIterator;
 use crate::logic::lookup::AttackTables;
 
-/// Checks if the `color` is currently in check.
-pub fn is_in_check(board: &Board, color: Color) -> bool {
-    // Locate the General
-    let general_idx = match color {
-        Color::Red => 0,
-        Color::Black => 7,
-    };
-    let general_bb = board.bitboards[general_idx];
+/// True if any of `pieces` sits on the rank or file of `(row, col)` inside the given ray
+/// attack sets (as returned by the rook or cannon tables for that square).
+fn ray_hits(pieces: u128, row: usize, col: usize, rank_attacks: u16, file_attacks: u16) -> bool {
+    BitboardIterator::new(pieces).any(|sq| {
+        let (r, c) = Board::index_to_coord(sq);
+        (r == row && rank_attacks & (1 << c) != 0) || (c == col && file_attacks & (1 << r) != 0)
+    })
+}
 
-    // Get general position (should be exactly one)
-    let general_sq = if let Some(sq) = BitboardIterator::new(general_bb).next() {
-        sq
-    } else {
+/// Checks if the `color` is currently in check. Every test is a lookup in `AttackTables`
+/// from the general's square: rook and cannon rays for the occupancy of its rank and file,
+/// and reverse horse and soldier tables for the squares those pieces attack it from.
+pub fn is_in_check(board: &Board, color: Color) -> bool {
+    let Some(general_sq) = BitboardIterator::new(board.bitboards[color.index() * 7]).next() else {
         return true; // No general means invalid state (or captured), treat as check
     };
 
     let (gr, gc) = Board::index_to_coord(general_sq);
-    let enemy_color = color.opposite();
-    let tables = AttackTables::get();
-
-    // 1. Check Rook/Chariot Attacks
-    let enemy_rooks = board.bitboards[match enemy_color {
-        Color::Red => 4,
-        Color::Black => 11,
-    }];
-    if enemy_rooks != 0 {
-        let rank_occ = board.occupied_rows[gr];
-        let file_occ = board.occupied_cols[gc];
-
-        // Rank Check
-        let rank_attacks = tables.get_rook_attacks(gc, rank_occ, 9);
-        let er_rank = (enemy_rooks >> (gr * 9)) as u16 & 0x1FF;
-        if (er_rank & rank_attacks) != 0 {
-            return true;
-        }
+    let enemy = color.opposite().index();
+    general_attacked(
+        general_sq,
+        color.opposite(),
+        |piece| board.bitboards[enemy * 7 + piece.index()],
+        board.occupied,
+        board.occupied_rows[gr],
+        board.occupied_cols[gc],
+    )
+}
 
-        // File Check
-        let file_attacks = tables.get_rook_attacks(gr, file_occ, 10);
-        let mut attacks = file_attacks;
-        while attacks != 0 {
-            let r = attacks.trailing_zeros() as usize;
-            attacks &= attacks - 1;
-            if (enemy_rooks & (1u128 << (r * 9 + gc))) != 0 {
-                return true;
-            }
-        }
+/// True if `mv` by `turn` leaves the opponent in check. Runs the `is_in_check` lookups
+/// against the occupancy after the move instead of making it, so direct checks by the moved
+/// piece, discovered checks along opened rays or horse legs, and cannon screens added or
+/// removed by the move are all seen.
+pub fn gives_check(board: &Board, mv: &Move, turn: Color) -> bool {
+    let enemy_general = board.bitboards[turn.opposite().index() * 7];
+    let Some(general_sq) = BitboardIterator::new(enemy_general).next() else {
+        return true;
+    };
+    let (fr, fc) = (mv.from_row as usize, mv.from_col as usize);
+    let (tr, tc) = (mv.to_row as usize, mv.to_col as usize);
+    let Some(piece) = BoardCoordinate::new(fr, fc).and_then(|from| board.get_piece(from)) else {
+        return false;
+    };
+    let (from_bit, to_bit) = (
+        1u128 << Board::square_index(fr, fc),
+        1u128 << Board::square_index(tr, tc),
+    );
+    if enemy_general & to_bit != 0 {
+        return true; // Capturing the general, as is_in_check sees it afterwards
     }
 
-    // 2. Check Cannon Attacks
-    let enemy_cannons = board.bitboards[match enemy_color {
-        Color::Red => 5,
-        Color::Black => 12,
-    }];
-    if enemy_cannons != 0 {
-        let rank_occ = board.occupied_rows[gr];
-        let file_occ = board.occupied_cols[gc];
-
-        let rank_attacks = tables.get_cannon_attacks(gc, rank_occ, 9);
-        let er_rank = (enemy_cannons >> (gr * 9)) as u16 & 0x1FF;
-        if (er_rank & rank_attacks) != 0 {
-            return true;
-        }
-
-        let file_attacks = tables.get_cannon_attacks(gr, file_occ, 10);
-        let mut attacks = file_attacks;
-        while attacks != 0 {
-            let r = attacks.trailing_zeros() as usize;
-            attacks &= attacks - 1;
-            if (enemy_cannons & (1u128 << (r * 9 + gc))) != 0 {
-                return true;
+    let (gr, gc) = Board::index_to_coord(general_sq);
+    let mut rank_occ = board.occupied_rows[gr];
+    let mut file_occ = board.occupied_cols[gc];
+    if fr == gr {
+        rank_occ &= !(1 << fc);
+    }
+    if fc == gc {
+        file_occ &= !(1 << fr);
+    }
+    if tr == gr {
+        rank_occ |= 1 << tc;
+    }
+    if tc == gc {
+        file_occ |= 1 << tr;
+    }
+
+    let own = turn.index();
+    general_attacked(
+        general_sq,
+        turn,
+        |piece_type| {
+            let bb = board.bitboards[own * 7 + piece_type.index()];
+            if piece_type == piece.piece_type {
+                (bb & !from_bit) | to_bit
+            } else {
+                bb
             }
-        }
-    }
+        },
+        (board.occupied & !from_bit) | to_bit,
+        rank_occ,
+        file_occ,
+    )
+}
 
-    // 3. Check Horse Attacks
-    let enemy_horses = board.bitboards[match enemy_color {
-        Color::Red => 3,
-        Color::Black => 10,
-    }];
-    if enemy_horses != 0 {
-        // Offsets: (dr, dc, leg_r_off, leg_c_off)
-        // Offsets: (dr, dc, leg_r_off, leg_c_off)
-        // Correct logic: leg is at (Start + dir). Start = Target - move.
-        // For check, we look from General (Target) to Horse (Source).
-        // Move is Source -> Target.
-        // Leg is Source + 1 step orthogonal.
-        // If relative to General, Source is at (dr, dc).
-        // Leg is at (dr, dc) + 1 step towards General? No.
-        // Leg is at Source + 1 step towards Target.
-        // Source = (gr+dr, gc+dc). Target = (gr, gc).
-        // If |dr|=2, step is row-wise towards Target. dr has sign S. Step is -S.
-        // LegRow = (gr+dr) - sign(dr). LegCol = gc+dc.
-        // LegRow relative to Gr: dr - sign(dr). LegCol relative to Gc: dc.
-        // Example: dr=-2, dc=-1. sign(dr)=-1.
-        // LegRelRow = -2 - (-1) = -1. LegRelCol = -1.
-        // Wait, my previous manual calc was (-1, -1).
-        // Yes.
-        // So offsets should be (dr - sign(dr), dc)?
-        // For |dr|=2: LegRel = (dr/2, dc).
-        // For |dr|=1 (|dc|=2): LegRel = (dr, dc/2).
-        // Let's re-verify.
-        // Case 1: (-2, -1). LegRel (-1, -1).
-        // Formula 1: (-1, -1). Correct.
-        // Case 5: (-1, -2). LegRel (-1, -1).
-        // Formula 2: (-1, -1). Correct.
-        // So the table values should be:
-        let offsets = [
-            (-2, -1, -1, -1),
-            (-2, 1, -1, 1),
-            (2, -1, 1, -1),
-            (2, 1, 1, 1),
-            (-1, -2, -1, -1),
-            (-1, 2, -1, 1),
-            (1, -2, 1, -1),
-            (1, 2, 1, 1),
-        ];
+/// True if the general on `general_sq` is attacked by the pieces of `attacker`, given as
+/// bitboards per piece type, for the given occupancy of the board and of the general's rank
+/// and file.
+fn general_attacked(
+    general_sq: usize,
+    attacker: Color,
+    pieces: impl Fn(PieceType) -> u128,
+    occupied: u128,
+    rank_occ: u16,
+    file_occ: u16,
+) -> bool {
+    let (gr, gc) = Board::index_to_coord(general_sq);
+    let tables = AttackTables::get();
 
-        for (dr, dc, lr, lc) in offsets {
-            let tr = gr as isize + dr;
-            let tc = gc as isize + dc;
-
-            if (0..10).contains(&tr) && (0..9).contains(&tc) {
-                let check_sr = tr as usize;
-                let check_sc = tc as usize;
-
-                if (enemy_horses & (1u128 << (check_sr * 9 + check_sc))) != 0 {
-                    // Found enemy horse, check leg
-                    let leg_r = (gr as isize + lr) as usize;
-                    let leg_c = (gc as isize + lc) as usize;
-                    if (board.occupied & (1u128 << (leg_r * 9 + leg_c))) == 0 {
-                        return true;
-                    }
-                }
-            }
-        }
+    // 1. Chariots
+    let rooks = pieces(PieceType::Chariot);
+    if rooks != 0
+        && ray_hits(
+            rooks,
+            gr,
+            gc,
+            tables.get_rook_attacks(gc, rank_occ, 9),
+            tables.get_rook_attacks(gr, file_occ, 10),
+        )
+    {
+        return true;
     }
 
-    // 4. Check Soldier Attacks
-    let enemy_soldiers = board.bitboards[match enemy_color {
-        Color::Red => 6,
-        Color::Black => 13,
-    }];
-    if enemy_soldiers != 0 {
-        // Red soldiers attack upwards (+1 row normally), but from perspective of General being attacked:
-        // If we are Black Gen, Red Soldier below us (row - 1) attacks us.
-        // If we are Red Gen, Black Soldier above us (row + 1) attacks us.
-        let backward_check = if enemy_color == Color::Red { -1 } else { 1 };
-
-        let fr = gr as isize + backward_check;
-        if (0..10).contains(&fr) && (enemy_soldiers & (1u128 << (fr as usize * 9 + gc))) != 0 {
-            return true;
-        }
+    // 2. Cannons
+    let cannons = pieces(PieceType::Cannon);
+    if cannons != 0
+        && ray_hits(
+            cannons,
+            gr,
+            gc,
+            tables.get_cannon_attacks(gc, rank_occ, 9),
+            tables.get_cannon_attacks(gr, file_occ, 10),
+        )
+    {
+        return true;
+    }
 
-        // Side attacks
-        for dc in [-1, 1] {
-            let sc = gc as isize + dc;
-            if (0..9).contains(&sc) && (enemy_soldiers & (1u128 << (gr * 9 + sc as usize))) != 0 {
-                return true;
-            }
-        }
+    // 3. Horses: only look at legs when a horse stands on an attacking square
+    let horses = pieces(PieceType::Horse);
+    if horses & tables.horse_attack_masks[general_sq] != 0
+        && tables.horse_attackers[general_sq]
+            .iter()
+            .any(|&(from, leg)| horses & (1u128 << from) != 0 && occupied & (1u128 << leg) == 0)
+    {
+        return true;
     }
 
-    false
+    // 4. Soldiers
+    pieces(PieceType::Soldier) & tables.soldier_attack_masks[attacker.index()][general_sq] != 0
 }
 
+/// Checks whether the two generals face each other on an open file.
 pub fn is_flying_general(board: &Board) -> bool {
-    let red_gen_bb = board.bitboards[0];
-    let black_gen_bb = board.bitboards[7];
-
-    let red_sq = if let Some(sq) = BitboardIterator::new(red_gen_bb).next() {
-        sq
-    } else {
-        return false;
-    };
-
-    let black_sq = if let Some(sq) = BitboardIterator::new(black_gen_bb).next() {
-        sq
-    } else {
+    let generals = (
+        BitboardIterator::new(board.bitboards[Color::Red.index() * 7]).next(),
+        BitboardIterator::new(board.bitboards[Color::Black.index() * 7]).next(),
+    );
+    let (Some(red_sq), Some(black_sq)) = generals else {
         return false;
     };
 
     let (r1, c1) = Board::index_to_coord(red_sq);
     let (r2, c2) = Board::index_to_coord(black_sq);
-
-    if c1 != c2 {
-        return false;
-    }
-
-    // Check for pieces in between
-    let min_r = r1.min(r2);
-    let max_r = r1.max(r2);
-
-    if max_r > min_r + 1 {
-        let mask = ((1u16 << max_r) - 1) ^ ((1u16 << (min_r + 1)) - 1);
-        let col_occ = board.occupied_cols[c1];
-        if (col_occ & mask) == 0 {
-            return true;
-        }
-    } else {
-        // Generals are adjacent on same file (no pieces between)
-        return true;
-    }
-
-    false
+    // A rook on the red general's square would reach the black one
+    c1 == c2
+        && AttackTables::get().get_rook_attacks(r1, board.occupied_cols[c1], 10) & (1 << r2) != 0
 }
 
 /// Validates the geometry and specific rules for a piece move, IGNORING self-check.
@@ -439,6 +391,35 @@ fn validate_soldier(
     Ok(())
 }
 
+/// Checks that a position set up outside a game, e.g. a pasted FEN, could occur in one: each
+/// side has a single general inside its palace, and the side that just moved did not leave
+/// its general in check for `turn` to capture.
+pub fn validate_position(board: &Board, turn: Color) -> Result<(), String> {
+    for color in [Color::Red, Color::Black] {
+        let mut generals = BitboardIterator::new(board.bitboards[color.index() * 7]);
+        let Some(sq) = generals.next() else {
+            return Err(format!("{color:?} has no general"));
+        };
+        if generals.next().is_some() {
+            return Err(format!("{color:?} has more than one general"));
+        }
+        let (row, col) = Board::index_to_coord(sq);
+        if !BoardCoordinate::new(row, col).is_some_and(|pos| is_in_palace(color, pos)) {
+            return Err(format!("{color:?} general is outside the palace"));
+        }
+    }
+    if is_flying_general(board) {
+        return Err("The generals face each other".to_string());
+    }
+    if is_in_check(board, turn.opposite()) {
+        return Err(format!(
+            "{:?} is in check but it is {turn:?} to move",
+            turn.opposite()
+        ));
+    }
+    Ok(())
+}
+
 fn is_in_palace(color: Color, pos: BoardCoordinate) -> bool {
     if !(3..=5).contains(&pos.col) {
         return false;
@@ -489,3 +470,157 @@ fn count_obstacles(board: &Board, from: BoardCoordinate, to: BoardCoordinate) ->
     }
     count
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_position() {
+        let valid = |fen: &str| {
+            let (board, turn) = Board::from_fen(fen).unwrap();
+            validate_position(&board, turn)
+        };
+        assert!(valid("4k4/9/9/9/9/9/9/9/9/3K5 w").is_ok());
+        assert!(valid(&Board::new().to_fen_string(Color::Black)).is_ok());
+        // No black general, two red generals, a general out of its palace
+        assert!(valid("9/9/9/9/9/9/9/9/9/3K5 w").is_err());
+        assert!(valid("4k4/9/9/9/9/9/9/9/4K4/3K5 w").is_err());
+        assert!(valid("4k4/9/9/9/9/9/9/9/9/K8 w").is_err());
+        // Facing generals
+        assert!(valid("4k4/9/9/9/9/9/9/9/9/4K4 w").is_err());
+        // Black in check with Red to move
+        assert!(valid("4k4/9/9/9/4R4/9/9/9/9/3K5 w").is_err());
+        assert!(valid("4k4/9/9/9/4R4/9/9/9/9/3K5 b").is_ok());
+    }
+
+    fn board_with(pieces: &[(usize, usize, PieceType, Color)]) -> Board {
+        let mut board = Board::new();
+        board.clear();
+        for &(row, col, piece_type, color) in pieces {
+            board.add_piece(BoardCoordinate::new(row, col).unwrap(), piece_type, color);
+        }
+        board
+    }
+
+    #[test]
+    fn test_horse_check_respects_leg() {
+        let mut pieces = vec![
+            (0, 4, PieceType::General, Color::Red),
+            (9, 3, PieceType::General, Color::Black),
+            (2, 5, PieceType::Horse, Color::Black),
+        ];
+        assert!(is_in_check(&board_with(&pieces), Color::Red));
+
+        // A piece on (1, 5) hobbles the horse
+        pieces.push((1, 5, PieceType::Advisor, Color::Red));
+        assert!(!is_in_check(&board_with(&pieces), Color::Red));
+    }
+
+    #[test]
+    fn test_soldier_and_cannon_checks() {
+        let soldier = board_with(&[
+            (0, 4, PieceType::General, Color::Red),
+            (9, 3, PieceType::General, Color::Black),
+            (1, 4, PieceType::Soldier, Color::Black),
+        ]);
+        assert!(is_in_check(&soldier, Color::Red));
+
+        // Soldiers never attack backwards
+        let behind = board_with(&[
+            (1, 4, PieceType::General, Color::Red),
+            (9, 3, PieceType::General, Color::Black),
+            (0, 4, PieceType::Soldier, Color::Black),
+        ]);
+        assert!(!is_in_check(&behind, Color::Red));
+
+        let mut cannon = vec![
+            (0, 4, PieceType::General, Color::Red),
+            (9, 3, PieceType::General, Color::Black),
+            (5, 4, PieceType::Cannon, Color::Black),
+        ];
+        assert!(!is_in_check(&board_with(&cannon), Color::Red));
+        cannon.push((2, 4, PieceType::Horse, Color::Red));
+        assert!(is_in_check(&board_with(&cannon), Color::Red));
+    }
+
+    #[test]
+    fn test_flying_general_and_gives_check() {
+        let board = board_with(&[
+            (0, 4, PieceType::General, Color::Red),
+            (9, 3, PieceType::General, Color::Black),
+            (5, 0, PieceType::Chariot, Color::Red),
+        ]);
+        assert!(!is_flying_general(&board));
+
+        let check = Move {
+            from_row: 5,
+            from_col: 0,
+            to_row: 5,
+            to_col: 3,
+            score: 0,
+        };
+        let quiet = Move { to_col: 2, ..check };
+        assert!(gives_check(&board, &check, Color::Red));
+        assert!(!gives_check(&board, &quiet, Color::Red));
+
+        let open_file = board_with(&[
+            (0, 3, PieceType::General, Color::Red),
+            (9, 3, PieceType::General, Color::Black),
+        ]);
+        assert!(is_flying_general(&open_file));
+    }
+
+    #[test]
+    fn test_gives_check_matches_make_unmake() {
+        use crate::logic::generator::MoveGenerator;
+        use rand::rngs::StdRng;
+        use rand::seq::SliceRandom;
+        use rand::SeedableRng;
+
+        // Discovered check: the horse leaves the chariot's file
+        let discovered = board_with(&[
+            (0, 3, PieceType::General, Color::Red),
+            (9, 4, PieceType::General, Color::Black),
+            (2, 4, PieceType::Chariot, Color::Red),
+            (5, 4, PieceType::Horse, Color::Red),
+        ]);
+        let horse_off = Move {
+            from_row: 5,
+            from_col: 4,
+            to_row: 7,
+            to_col: 5,
+            score: 0,
+        };
+        assert!(gives_check(&discovered, &horse_off, Color::Red));
+
+        let generator = MoveGenerator::new();
+        let mut rng = StdRng::seed_from_u64(2580);
+        let mut checks = 0;
+        for _ in 0..30 {
+            let mut board = Board::new();
+            let mut turn = Color::Red;
+            for _ in 0..120 {
+                let moves = generator.generate_moves(&board, turn);
+                for mv in &moves {
+                    let mut after = board.clone();
+                    after.apply_move(mv, turn);
+                    let expected = is_in_check(&after, turn.opposite());
+                    assert_eq!(
+                        gives_check(&board, mv, turn),
+                        expected,
+                        "{} {mv:?}",
+                        board.to_fen_string(turn)
+                    );
+                    checks += usize::from(expected);
+                }
+                let Some(mv) = moves.choose(&mut rng) else {
+                    break;
+                };
+                board.apply_move(mv, turn);
+                turn = turn.opposite();
+            }
+        }
+        assert!(checks > 100);
+    }
+}
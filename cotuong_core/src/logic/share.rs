@@ -0,0 +1,119 @@
+use crate::logic::board::BoardCoordinate;
+use crate::logic::game::GameState;
+use crate::logic::puzzle::parse_iccs;
+use crate::logic::rules::MoveError;
+
+/// Writes a FEN as a query value, e.g. `?fen=...`. Piece letters, digits and `/` are safe
+/// in a query, so only the spaces between fields need replacing; `_` reads better than `%20`.
+#[must_use]
+pub fn fen_to_param(fen: &str) -> String {
+    fen.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// Reads back a FEN written by `fen_to_param`, also accepting the `+` and percent-encoded
+/// forms browsers and chat apps turn shared links into.
+#[must_use]
+pub fn fen_from_param(param: &str) -> String {
+    percent_decode(param).replace(['_', '+'], " ")
+}
+
+/// Decodes `%XX` escapes; malformed escapes are kept as they are.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while let Some(&b) = bytes.get(i) {
+        let escaped = (b == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        if let Some(decoded) = escaped {
+            out.push(decoded);
+            i += 3;
+        } else {
+            out.push(b);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Packs moves into a path segment as back-to-back ICCS moves, e.g. `h2e2h9g7`.
+#[must_use]
+pub fn moves_to_param(moves: &[(BoardCoordinate, BoardCoordinate)]) -> String {
+    let square = |c: BoardCoordinate| {
+        #[allow(clippy::cast_possible_truncation)]
+        let file = char::from(b'a' + c.col as u8);
+        format!("{file}{}", c.row)
+    };
+    moves
+        .iter()
+        .map(|&(from, to)| format!("{}{}", square(from), square(to)))
+        .collect()
+}
+
+/// Unpacks moves written by `moves_to_param`; `None` if any of them is not ICCS.
+#[must_use]
+pub fn moves_from_param(param: &str) -> Option<Vec<(BoardCoordinate, BoardCoordinate)>> {
+    if !param.is_ascii() || !param.len().is_multiple_of(4) {
+        return None;
+    }
+    param
+        .as_bytes()
+        .chunks(4)
+        .map(|chunk| std::str::from_utf8(chunk).ok().and_then(parse_iccs))
+        .collect()
+}
+
+/// Plays `moves` from `start`, stopping at the first illegal one.
+pub fn replay(
+    mut start: GameState,
+    moves: &[(BoardCoordinate, BoardCoordinate)],
+) -> Result<GameState, MoveError> {
+    for &(from, to) in moves {
+        start.make_move(from, to)?;
+    }
+    Ok(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::{Board, Color};
+
+    #[test]
+    fn fen_round_trips_through_a_query_value() {
+        let fen = Board::new().to_fen_string(Color::Black);
+        let param = fen_to_param(&fen);
+        assert!(!param.contains(' '));
+        assert_eq!(fen_from_param(&param), fen);
+        assert_eq!(fen_from_param(&param.replace('_', "%20")), fen);
+        assert_eq!(fen_from_param(&param.replace('/', "%2F")), fen);
+        assert_eq!(fen_from_param(&param.replace('_', "+")), fen);
+    }
+
+    #[test]
+    fn malformed_escapes_are_kept() {
+        assert_eq!(fen_from_param("9%2"), "9%2");
+        assert_eq!(fen_from_param("9%zz"), "9%zz");
+    }
+
+    #[test]
+    fn moves_round_trip_and_replay() {
+        let moves = moves_from_param("h2e2h9g7").unwrap();
+        assert_eq!(moves_to_param(&moves), "h2e2h9g7");
+        let state = replay(GameState::new(), &moves).unwrap();
+        assert_eq!(state.history.len(), 2);
+        assert_eq!(state.turn, Color::Red);
+    }
+
+    #[test]
+    fn bad_moves_are_rejected() {
+        assert!(moves_from_param("h2e").is_none());
+        assert!(moves_from_param("z2e2").is_none());
+        // Well-formed but illegal: the red chariot cannot jump its soldier
+        let moves = moves_from_param("a0a4").unwrap();
+        assert!(replay(GameState::new(), &moves).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::logic::board::Color;
+use crate::logic::game::{GameState, GameStatus};
+use serde::{Deserialize, Serialize};
+
+/// What the player has to do from a training position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrainingGoal {
+    /// Beat the engine within the given number of the player's moves.
+    Win(u8),
+    /// Play the given number of moves without losing.
+    Hold(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrainingStage {
+    Endgame,
+    Middlegame,
+}
+
+/// Where a game played from a training position stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingOutcome {
+    Playing,
+    Solved,
+    Failed,
+}
+
+/// A position the player plays out against the engine, moving first. `id` is stable, so
+/// progress saved by the client survives reordering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrainingPosition {
+    pub id: String,
+    pub title: String,
+    pub stage: TrainingStage,
+    pub fen: String,
+    pub goal: TrainingGoal,
+}
+
+impl TrainingPosition {
+    pub fn initial_state(&self) -> Result<GameState, String> {
+        GameState::from_user_fen(&self.fen)
+    }
+
+    /// Judges `state`, a game played from this position with the player as `player`.
+    #[must_use]
+    pub fn outcome(&self, state: &GameState, player: Color) -> TrainingOutcome {
+        let winner = match state.status {
+            GameStatus::Playing => None,
+            GameStatus::Checkmate(winner) | GameStatus::Resigned(winner) => Some(winner),
+            GameStatus::Stalemate | GameStatus::DrawAgreed => {
+                return match self.goal {
+                    TrainingGoal::Win(_) => TrainingOutcome::Failed,
+                    TrainingGoal::Hold(_) => TrainingOutcome::Solved,
+                };
+            }
+        };
+        match winner {
+            Some(winner) if winner == player => return TrainingOutcome::Solved,
+            Some(_) => return TrainingOutcome::Failed,
+            None => {}
+        }
+        let played = state.history.iter().filter(|r| r.color == player).count();
+        match self.goal {
+            TrainingGoal::Win(moves) if played >= usize::from(moves) => TrainingOutcome::Failed,
+            TrainingGoal::Hold(moves) if played >= usize::from(moves) => TrainingOutcome::Solved,
+            TrainingGoal::Win(_) | TrainingGoal::Hold(_) => TrainingOutcome::Playing,
+        }
+    }
+}
+
+type PositionData = (
+    &'static str,
+    &'static str,
+    TrainingStage,
+    &'static str,
+    TrainingGoal,
+);
+
+/// Bundled training set: basic winning endgames first, then a defence and two middlegames.
+pub fn training_positions() -> Vec<TrainingPosition> {
+    const POSITIONS: [PositionData; 7] = [
+        (
+            "chariot-vs-general",
+            "Xe thắng Tướng đơn",
+            TrainingStage::Endgame,
+            "4k4/9/9/9/9/9/9/9/9/R2K5 w",
+            TrainingGoal::Win(15),
+        ),
+        (
+            "two-soldiers",
+            "Hai Tốt cao thắng Tướng đơn",
+            TrainingStage::Endgame,
+            "4k4/9/2P1P4/9/9/9/9/9/9/3K5 w",
+            TrainingGoal::Win(25),
+        ),
+        (
+            "cannon-advisor",
+            "Pháo Sĩ thắng Tướng đơn",
+            TrainingStage::Endgame,
+            "5k3/9/9/9/9/9/9/9/4A4/3KC4 w",
+            TrainingGoal::Win(30),
+        ),
+        (
+            "chariot-vs-horse",
+            "Xe thắng Mã",
+            TrainingStage::Endgame,
+            "3k5/9/9/9/9/4n4/9/9/9/R3K4 w",
+            TrainingGoal::Win(40),
+        ),
+        (
+            "hold-chariot-soldier",
+            "Xe Sĩ Tượng toàn giữ trước Xe Tốt",
+            TrainingStage::Endgame,
+            "3k5/9/9/9/4r4/9/4p4/R8/9/2BAKAB2 w",
+            TrainingGoal::Hold(20),
+        ),
+        (
+            "extra-chariot",
+            "Tận dụng ưu thế hơn Xe",
+            TrainingStage::Middlegame,
+            "1nbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w",
+            TrainingGoal::Win(60),
+        ),
+        (
+            "missing-horse",
+            "Giữ vững khi thiếu Mã",
+            TrainingStage::Middlegame,
+            "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/R1BAKABNR w",
+            TrainingGoal::Hold(30),
+        ),
+    ];
+    POSITIONS
+        .into_iter()
+        .map(|(id, title, stage, fen, goal)| TrainingPosition {
+            id: id.to_string(),
+            title: title.to_string(),
+            stage,
+            fen: fen.to_string(),
+            goal,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::puzzle::parse_iccs;
+
+    #[test]
+    fn test_positions_are_valid() {
+        let positions = training_positions();
+        for (i, position) in positions.iter().enumerate() {
+            let state = position.initial_state().unwrap();
+            assert_eq!(state.status, GameStatus::Playing, "{}", position.id);
+            assert!(
+                positions[..i].iter().all(|p| p.id != position.id),
+                "duplicate id {}",
+                position.id
+            );
+        }
+    }
+
+    #[test]
+    fn test_outcome() {
+        let win = &training_positions()[0];
+        let mut state = win.initial_state().unwrap();
+        assert_eq!(win.outcome(&state, Color::Red), TrainingOutcome::Playing);
+
+        let (from, to) = parse_iccs("a0a9").unwrap();
+        state.make_move(from, to).unwrap();
+        assert_eq!(win.outcome(&state, Color::Red), TrainingOutcome::Playing);
+        state.resign(Color::Black);
+        assert_eq!(win.outcome(&state, Color::Red), TrainingOutcome::Solved);
+
+        let hold = TrainingPosition {
+            goal: TrainingGoal::Hold(1),
+            ..win.clone()
+        };
+        let mut state = hold.initial_state().unwrap();
+        state.make_move(from, to).unwrap();
+        assert_eq!(hold.outcome(&state, Color::Red), TrainingOutcome::Solved);
+        state.resign(Color::Red);
+        assert_eq!(hold.outcome(&state, Color::Red), TrainingOutcome::Failed);
+
+        let out_of_moves = TrainingPosition {
+            goal: TrainingGoal::Win(1),
+            ..win.clone()
+        };
+        let mut state = out_of_moves.initial_state().unwrap();
+        state.make_move(from, to).unwrap();
+        assert_eq!(
+            out_of_moves.outcome(&state, Color::Red),
+            TrainingOutcome::Failed
+        );
+    }
+}
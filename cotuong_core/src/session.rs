@@ -0,0 +1,472 @@
+use crate::engine::Move;
+use crate::logic::board::{Board, BoardCoordinate, Color};
+use crate::logic::game::{GameState, GameStatus};
+use crate::logic::handicap::HandicapKind;
+use crate::logic::rules::MoveError;
+use serde::{Deserialize, Serialize};
+
+/// Starting time per side and the time added after every move, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeControl {
+    pub initial_ms: u64,
+    pub increment_ms: u64,
+}
+
+/// Both sides' remaining time. Timestamps are supplied by the caller (any monotonic
+/// millisecond count), so the same clock runs on the server and in the browser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Clock {
+    pub red_ms: u64,
+    pub black_ms: u64,
+    pub increment_ms: u64,
+    /// When the side to move started thinking.
+    pub turn_started_ms: u64,
+}
+
+impl Clock {
+    #[must_use]
+    pub const fn new(control: TimeControl, now_ms: u64) -> Self {
+        Self {
+            red_ms: control.initial_ms,
+            black_ms: control.initial_ms,
+            increment_ms: control.increment_ms,
+            turn_started_ms: now_ms,
+        }
+    }
+
+    const fn stored(&self, color: Color) -> u64 {
+        match color {
+            Color::Red => self.red_ms,
+            Color::Black => self.black_ms,
+        }
+    }
+
+    /// Time `color` has left at `now_ms`, counting down only while it is `to_move`.
+    #[must_use]
+    pub fn remaining_ms(&self, color: Color, to_move: Color, now_ms: u64) -> u64 {
+        let stored = self.stored(color);
+        if color == to_move {
+            stored.saturating_sub(now_ms.saturating_sub(self.turn_started_ms))
+        } else {
+            stored
+        }
+    }
+
+    /// Charges `mover` for its move and hands the clock to the next side.
+    fn punch(&mut self, mover: Color, now_ms: u64) {
+        let left = self.remaining_ms(mover, mover, now_ms) + self.increment_ms;
+        match mover {
+            Color::Red => self.red_ms = left,
+            Color::Black => self.black_ms = left,
+        }
+        self.turn_started_ms = now_ms;
+    }
+}
+
+/// Why a game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EndReason {
+    /// The loser had no legal move, in check or not.
+    Checkmate,
+    Resignation,
+    DrawAgreement,
+    Timeout,
+    /// The loser left or lost the connection.
+    Abandoned,
+    /// A player dropped and an engine judged the position.
+    Adjudication,
+    /// Stopped by an operator; nobody wins.
+    Terminated,
+}
+
+impl EndReason {
+    /// Name used in `ServerMessage::GameEnd`.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Checkmate => "Checkmate",
+            Self::Resignation => "Surrender",
+            Self::DrawAgreement => "Draw",
+            Self::Timeout => "Timeout",
+            Self::Abandoned => "Disconnect",
+            Self::Adjudication => "Adjudication",
+            Self::Terminated => "Terminated",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            Self::Checkmate,
+            Self::Resignation,
+            Self::DrawAgreement,
+            Self::Timeout,
+            Self::Abandoned,
+            Self::Adjudication,
+            Self::Terminated,
+        ]
+        .into_iter()
+        .find(|reason| reason.as_str() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionResult {
+    /// `None` for a draw.
+    pub winner: Option<Color>,
+    pub reason: EndReason,
+}
+
+impl SessionResult {
+    /// Result recorded in a game's status, `None` while it is still being played.
+    #[must_use]
+    pub const fn from_state(state: &GameState) -> Option<Self> {
+        let (winner, reason) = match state.status {
+            GameStatus::Playing => return None,
+            GameStatus::Checkmate(winner) => (Some(winner), EndReason::Checkmate),
+            // Having no legal move loses in Xiangqi; `turn` is the side left without one
+            GameStatus::Stalemate => (Some(state.turn.opposite()), EndReason::Checkmate),
+            GameStatus::Resigned(winner) => (Some(winner), EndReason::Resignation),
+            GameStatus::DrawAgreed => (None, EndReason::DrawAgreement),
+        };
+        Some(Self { winner, reason })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionError {
+    GameOver,
+    NotYourTurn,
+    /// The mover's flag fell before the move arrived; the game is now lost on time.
+    TimeExpired,
+    Illegal(MoveError),
+    /// Accepting a draw nobody offered.
+    NoDrawOffer,
+}
+
+/// One game from start to result: turn order, the clock, the draw offer and resignation
+/// flow, and who won and why. Server and clients drive the same state machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub state: GameState,
+    pub clock: Option<Clock>,
+    /// Side whose draw offer is waiting for an answer.
+    pub draw_offer: Option<Color>,
+    /// Moves played before `state` was set up, for games resumed from a position.
+    pub start_ply: u32,
+    result: Option<SessionResult>,
+}
+
+impl Session {
+    /// A game from the start position of `handicap`.
+    #[must_use]
+    pub fn new(handicap: HandicapKind, time_control: Option<TimeControl>, now_ms: u64) -> Self {
+        Self::from_state(handicap.initial_state(), time_control, now_ms)
+    }
+
+    #[must_use]
+    pub fn from_state(state: GameState, time_control: Option<TimeControl>, now_ms: u64) -> Self {
+        let result = SessionResult::from_state(&state);
+        Self {
+            state,
+            clock: time_control.map(|control| Clock::new(control, now_ms)),
+            draw_offer: None,
+            start_ply: 0,
+            result,
+        }
+    }
+
+    /// A game resumed from a bare position after `move_count` moves, without the history.
+    #[must_use]
+    pub fn from_position(board: Board, turn: Color, move_count: u32) -> Self {
+        let state = GameState::from_position(board, turn);
+        let mut session = Self::from_state(state, None, 0);
+        session.start_ply = move_count;
+        session
+    }
+
+    #[must_use]
+    pub const fn board(&self) -> &Board {
+        &self.state.board
+    }
+
+    #[must_use]
+    pub const fn turn(&self) -> Color {
+        self.state.turn
+    }
+
+    #[must_use]
+    pub const fn result(&self) -> Option<SessionResult> {
+        self.result
+    }
+
+    #[must_use]
+    pub const fn is_over(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Moves played since the game started.
+    #[must_use]
+    pub fn move_count(&self) -> u32 {
+        self.start_ply
+            .saturating_add(u32::try_from(self.state.history.len()).unwrap_or(u32::MAX))
+    }
+
+    /// Time `color` has left at `now_ms`, `None` in untimed games.
+    #[must_use]
+    pub fn remaining_ms(&self, color: Color, now_ms: u64) -> Option<u64> {
+        self.clock
+            .map(|clock| clock.remaining_ms(color, self.turn(), now_ms))
+    }
+
+    fn finish(&mut self, winner: Option<Color>, reason: EndReason) -> SessionResult {
+        let result = SessionResult { winner, reason };
+        self.result = Some(result);
+        self.draw_offer = None;
+        result
+    }
+
+    fn ensure_playing(&self) -> Result<(), SessionError> {
+        if self.is_over() {
+            Err(SessionError::GameOver)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Ends the game if the side to move has run out of time.
+    pub fn check_time(&mut self, now_ms: u64) -> Option<SessionResult> {
+        if self.is_over() {
+            return self.result;
+        }
+        let to_move = self.turn();
+        (self.remaining_ms(to_move, now_ms) == Some(0))
+            .then(|| self.finish(Some(to_move.opposite()), EndReason::Timeout))
+    }
+
+    /// Plays a move for `color`. Returns the result if the move ended the game.
+    pub fn play(
+        &mut self,
+        color: Color,
+        from: BoardCoordinate,
+        to: BoardCoordinate,
+        now_ms: u64,
+    ) -> Result<Option<SessionResult>, SessionError> {
+        self.ensure_playing()?;
+        if color != self.turn() {
+            return Err(SessionError::NotYourTurn);
+        }
+        if self.check_time(now_ms).is_some() {
+            return Err(SessionError::TimeExpired);
+        }
+        self.state
+            .make_move(from, to)
+            .map_err(SessionError::Illegal)?;
+        if let Some(clock) = &mut self.clock {
+            clock.punch(color, now_ms);
+            if let Some(record) = self.state.history.last_mut() {
+                record.clock_ms = Some(clock.stored(color));
+            }
+        }
+        // Moving instead of answering declines the opponent's offer
+        if self.draw_offer == Some(color.opposite()) {
+            self.draw_offer = None;
+        }
+        self.result = SessionResult::from_state(&self.state);
+        Ok(self.result)
+    }
+
+    /// `play` for an engine or wire move.
+    pub fn play_move(
+        &mut self,
+        color: Color,
+        mv: &Move,
+        now_ms: u64,
+    ) -> Result<Option<SessionResult>, SessionError> {
+        let from = BoardCoordinate::new(mv.from_row as usize, mv.from_col as usize);
+        let to = BoardCoordinate::new(mv.to_row as usize, mv.to_col as usize);
+        let (Some(from), Some(to)) = (from, to) else {
+            return Err(SessionError::Illegal(MoveError::OutOfBounds));
+        };
+        self.play(color, from, to, now_ms)
+    }
+
+    pub fn resign(&mut self, color: Color) -> Result<SessionResult, SessionError> {
+        self.ensure_playing()?;
+        self.state.resign(color);
+        Ok(self.finish(Some(color.opposite()), EndReason::Resignation))
+    }
+
+    /// `color` offers a draw; if the opponent had already offered one, it is agreed.
+    pub fn offer_draw(&mut self, color: Color) -> Result<Option<SessionResult>, SessionError> {
+        self.ensure_playing()?;
+        if self.draw_offer == Some(color.opposite()) {
+            return self.accept_draw(color).map(Some);
+        }
+        self.draw_offer = Some(color);
+        Ok(None)
+    }
+
+    pub fn accept_draw(&mut self, color: Color) -> Result<SessionResult, SessionError> {
+        self.ensure_playing()?;
+        if self.draw_offer != Some(color.opposite()) {
+            return Err(SessionError::NoDrawOffer);
+        }
+        self.state.agree_draw();
+        Ok(self.finish(None, EndReason::DrawAgreement))
+    }
+
+    pub fn decline_draw(&mut self, color: Color) {
+        if self.draw_offer == Some(color.opposite()) {
+            self.draw_offer = None;
+        }
+    }
+
+    /// `color` left the game or lost its connection for good.
+    pub fn abandon(&mut self, color: Color) -> Result<SessionResult, SessionError> {
+        self.ensure_playing()?;
+        Ok(self.finish(Some(color.opposite()), EndReason::Abandoned))
+    }
+
+    /// `color` ran out of time under a limit kept outside the session clock, such as a
+    /// server's per-move timeout.
+    pub fn time_out(&mut self, color: Color) -> Result<SessionResult, SessionError> {
+        self.ensure_playing()?;
+        Ok(self.finish(Some(color.opposite()), EndReason::Timeout))
+    }
+
+    /// Ends the game with the result an engine judged from the position, `None` for a draw.
+    pub fn adjudicate(&mut self, winner: Option<Color>) -> Result<SessionResult, SessionError> {
+        self.ensure_playing()?;
+        Ok(self.finish(winner, EndReason::Adjudication))
+    }
+
+    /// Ends the game without a winner on an operator's request.
+    pub fn terminate(&mut self) -> Result<SessionResult, SessionError> {
+        self.ensure_playing()?;
+        Ok(self.finish(None, EndReason::Terminated))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::match_runner::parse_opening;
+
+    fn play_line(session: &mut Session, line: &str, now_ms: u64) {
+        for (from, to) in parse_opening(line).unwrap() {
+            let color = session.turn();
+            session.play(color, from, to, now_ms).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_turns_and_errors() {
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        let (from, to) = parse_opening("h2e2").unwrap()[0];
+        assert_eq!(
+            session.play(Color::Black, from, to, 0),
+            Err(SessionError::NotYourTurn)
+        );
+        assert!(matches!(
+            session.play(Color::Red, to, from, 0),
+            Err(SessionError::Illegal(_))
+        ));
+        assert_eq!(session.play(Color::Red, from, to, 0), Ok(None));
+        assert_eq!(session.move_count(), 1);
+        assert_eq!(session.remaining_ms(Color::Red, 0), None);
+
+        let resumed = Session::from_position(session.board().clone(), session.turn(), 7);
+        assert_eq!(resumed.move_count(), 7);
+        assert_eq!(resumed.turn(), Color::Black);
+    }
+
+    #[test]
+    fn test_draw_offer_flow() {
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        assert_eq!(
+            session.accept_draw(Color::Black),
+            Err(SessionError::NoDrawOffer)
+        );
+        assert_eq!(session.offer_draw(Color::Red), Ok(None));
+        // Black plays on, which declines
+        play_line(&mut session, "h2e2 h9g7", 0);
+        assert_eq!(session.draw_offer, None);
+
+        assert_eq!(session.offer_draw(Color::Red), Ok(None));
+        let result = session.offer_draw(Color::Black).unwrap().unwrap();
+        assert_eq!(result.winner, None);
+        assert_eq!(result.reason, EndReason::DrawAgreement);
+        assert_eq!(session.state.status, GameStatus::DrawAgreed);
+        assert_eq!(session.resign(Color::Red), Err(SessionError::GameOver));
+    }
+
+    #[test]
+    fn test_resign_and_abandon() {
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        let result = session.resign(Color::Black).unwrap();
+        assert_eq!(result.winner, Some(Color::Red));
+        assert_eq!(SessionResult::from_state(&session.state), Some(result));
+
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        let result = session.abandon(Color::Red).unwrap();
+        assert_eq!(result.winner, Some(Color::Black));
+        assert_eq!(result.reason, EndReason::Abandoned);
+        assert_eq!(
+            EndReason::parse(result.reason.as_str()),
+            Some(EndReason::Abandoned)
+        );
+
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        let result = session.time_out(Color::Black).unwrap();
+        assert_eq!(result.winner, Some(Color::Red));
+        assert_eq!(result.reason, EndReason::Timeout);
+        assert_eq!(session.time_out(Color::Red), Err(SessionError::GameOver));
+
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        let result = session.adjudicate(None).unwrap();
+        assert_eq!(result.winner, None);
+        assert_eq!(
+            EndReason::parse(result.reason.as_str()),
+            Some(EndReason::Adjudication)
+        );
+
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        let result = session.terminate().unwrap();
+        assert_eq!(result.winner, None);
+        assert_eq!(EndReason::parse("Terminated"), Some(EndReason::Terminated));
+        assert_eq!(session.terminate(), Err(SessionError::GameOver));
+    }
+
+    #[test]
+    fn test_clock() {
+        let control = TimeControl {
+            initial_ms: 10_000,
+            increment_ms: 1_000,
+        };
+        let mut session = Session::new(HandicapKind::None, Some(control), 0);
+        play_line(&mut session, "h2e2", 3_000);
+        // Red spent 3s and got 1s back; Black's clock is now running
+        assert_eq!(session.state.history[0].clock_ms, Some(8_000));
+        assert_eq!(session.remaining_ms(Color::Red, 5_000), Some(8_000));
+        assert_eq!(session.remaining_ms(Color::Black, 5_000), Some(8_000));
+        assert_eq!(session.check_time(12_999), None);
+
+        let (from, to) = parse_opening("h9g7").unwrap()[0];
+        assert_eq!(
+            session.play(Color::Black, from, to, 13_000),
+            Err(SessionError::TimeExpired)
+        );
+        let result = session.result().unwrap();
+        assert_eq!(result.winner, Some(Color::Red));
+        assert_eq!(result.reason, EndReason::Timeout);
+    }
+
+    #[test]
+    fn test_handicap_extra_move_keeps_turn() {
+        let mut session = Session::new(HandicapKind::TwoMoves, None, 0);
+        assert_eq!(session.turn(), Color::Black);
+        play_line(&mut session, "h9g7", 0);
+        assert_eq!(session.turn(), Color::Black);
+    }
+}
@@ -1,5 +1,9 @@
-use crate::engine::config::EngineConfig;
-use crate::engine::search::AlphaBetaEngine;
+use crate::engine::bench::run_bench_with_progress;
+use crate::engine::config::{EngineConfig, TT_SIZE_MAX_MB, TT_SIZE_MIN_MB};
+use crate::engine::explain::{explain_move, MoveExplanation};
+use crate::engine::review::{review_game, GameReview};
+use crate::engine::search::{search_mate, AlphaBetaEngine};
+use crate::engine::trace::{TraceLimits, TraceNode};
 use crate::engine::{Move, SearchLimit, SearchStats, Searcher};
 use crate::logic::game::GameState;
 use gloo_worker::{HandlerId, Worker, WorkerScope};
@@ -9,15 +13,75 @@ use std::sync::Arc;
 #[derive(Serialize, Deserialize)]
 pub enum Input {
     ComputeMove(GameState, SearchLimit, EngineConfig, Vec<Move>),
+    /// A new game started: drop everything the engine learned about the previous one.
+    NewGame,
+    /// The game reached this position (a move or an undo). The engine keeps its hash table
+    /// while the game goes on, so the next search starts from the previous move's entries.
+    PositionUpdate(GameState),
+    /// Look for a forced mate within the given number of plies.
+    FindMate(GameState, u8),
+    /// Grade every move of a game, searching each position with the given limit.
+    ReviewGame(GameState, SearchLimit, EngineConfig),
+    /// Search the bench suite to the given depth, to measure how fast this device is.
+    Benchmark(u8, EngineConfig),
+    /// Search a position to the given depth and record the tree, for debugging the search.
+    TraceSearch(GameState, u8, EngineConfig, TraceLimits),
 }
 
 #[derive(Serialize, Deserialize)]
 pub enum Output {
-    MoveFound(Move, SearchStats),
+    /// The engine's move, how the search went, and why it picked the move.
+    MoveFound(Move, SearchStats, MoveExplanation),
+    /// FEN of the searched position and the mating line, if any.
+    MateSearched(String, Option<Vec<Move>>),
+    /// Positions searched so far out of the total, and the last one's score from Red's side,
+    /// while a review runs.
+    ReviewProgress(usize, usize, i32),
+    GameReviewed(GameReview),
+    /// Bench positions searched so far out of the total.
+    BenchmarkProgress(usize, usize),
+    /// Nodes per second over the bench suite, `None` if it could not be run.
+    Benchmarked(Option<u64>),
+    /// The recorded tree of the deepest finished pass, if the search got that far.
+    SearchTraced(Option<TraceNode>),
+}
+
+/// Every engine the worker builds goes through here: an imported config may ask for a
+/// larger table than a browser worker can allocate.
+fn worker_config(mut config: EngineConfig) -> EngineConfig {
+    config.tt_size_mb = config.tt_size_mb.clamp(TT_SIZE_MIN_MB, TT_SIZE_MAX_MB);
+    config
 }
 
 pub struct GameWorker {
     engine: Option<AlphaBetaEngine>,
+    /// Position hashes of the game the engine last saw.
+    line: Vec<u64>,
+}
+
+impl GameWorker {
+    /// Follows the game to `game_state`. A position that neither continues nor rewinds the
+    /// known game belongs to another game, whose tables would only get in the way.
+    fn sync_position(&mut self, game_state: &GameState) {
+        let line: Vec<u64> = game_state.history.iter().map(|r| r.hash).collect();
+        let shared = self
+            .line
+            .iter()
+            .zip(&line)
+            .take_while(|(a, b)| a == b)
+            .count();
+        if shared < self.line.len().min(line.len()) {
+            self.new_game();
+        }
+        self.line = line;
+    }
+
+    fn new_game(&mut self) {
+        if let Some(engine) = &mut self.engine {
+            engine.new_game();
+        }
+        self.line.clear();
+    }
 }
 
 impl Worker for GameWorker {
@@ -26,7 +90,10 @@ impl Worker for GameWorker {
     type Output = Output;
 
     fn create(_scope: &WorkerScope<Self>) -> Self {
-        Self { engine: None }
+        Self {
+            engine: None,
+            line: Vec::new(),
+        }
     }
 
     fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {}
@@ -34,7 +101,8 @@ impl Worker for GameWorker {
     fn received(&mut self, scope: &WorkerScope<Self>, msg: Self::Input, id: HandlerId) {
         match msg {
             Input::ComputeMove(game_state, limit, config, excluded_moves) => {
-                let config = Arc::new(config);
+                self.sync_position(&game_state);
+                let config = Arc::new(worker_config(config));
 
                 if let Some(engine) = &mut self.engine {
                     engine.update_config(config);
@@ -56,7 +124,8 @@ impl Worker for GameWorker {
                 // I'll add it to Input.
 
                 if let Some((mv, stats)) = engine.search(&game_state, limit, &excluded_moves) {
-                    scope.respond(id, Output::MoveFound(mv, stats));
+                    let explanation = explain_move(engine, &game_state, mv);
+                    scope.respond(id, Output::MoveFound(mv, stats, explanation));
                 } else {
                     // If no move found (e.g. mate), we might want to respond with something or just nothing?
                     // The app expects a move. If search returns None, it means no legal moves or time out without result?
@@ -69,6 +138,35 @@ impl Worker for GameWorker {
                     // If game over, app shouldn't ask for move.
                 }
             }
+            Input::NewGame => self.new_game(),
+            Input::PositionUpdate(game_state) => self.sync_position(&game_state),
+            Input::FindMate(game_state, max_plies) => {
+                let fen = game_state.board.to_fen_string(game_state.turn);
+                let line = search_mate(&game_state, max_plies);
+                scope.respond(id, Output::MateSearched(fen, line));
+            }
+            Input::ReviewGame(game_state, limit, config) => {
+                // A separate engine, so the review leaves the playing engine's tables alone
+                let mut engine = AlphaBetaEngine::new(Arc::new(worker_config(config)));
+                let review = review_game(&mut engine, &game_state, limit, |done, total, score| {
+                    scope.respond(id, Output::ReviewProgress(done, total, score));
+                });
+                scope.respond(id, Output::GameReviewed(review));
+            }
+            Input::Benchmark(depth, config) => {
+                let config = worker_config(config);
+                let report = run_bench_with_progress(depth, &config, |done, total| {
+                    scope.respond(id, Output::BenchmarkProgress(done, total));
+                });
+                scope.respond(id, Output::Benchmarked(report.ok().map(|r| r.nps())));
+            }
+            Input::TraceSearch(game_state, depth, config, limits) => {
+                // Recording slows the search down, so it runs on an engine of its own
+                let mut engine = AlphaBetaEngine::new(Arc::new(worker_config(config)));
+                engine.set_trace(Some(limits));
+                engine.search(&game_state, SearchLimit::Depth(depth), &[]);
+                scope.respond(id, Output::SearchTraced(engine.take_trace()));
+            }
         }
     }
 }
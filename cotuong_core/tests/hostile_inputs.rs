@@ -0,0 +1,112 @@
+//! Property tests for the inputs a hostile client or a corrupt file controls: FEN strings
+//! and wire moves. Make/unmake itself is covered by `incremental_board.rs`.
+
+use cotuong_core::engine::Move;
+use cotuong_core::logic::board::Board;
+use cotuong_core::logic::generator::MoveGenerator;
+use cotuong_core::logic::handicap::HandicapKind;
+use cotuong_core::session::Session;
+use proptest::prelude::*;
+
+/// Well-formed FEN for any placement of pieces, legal or not: no generals, a dozen
+/// chariots, soldiers on the back rank.
+fn any_placement_fen() -> impl Strategy<Value = String> {
+    let square = prop::sample::select(vec![
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some('k'),
+        Some('a'),
+        Some('b'),
+        Some('n'),
+        Some('r'),
+        Some('c'),
+        Some('p'),
+        Some('K'),
+        Some('A'),
+        Some('B'),
+        Some('N'),
+        Some('R'),
+        Some('C'),
+        Some('P'),
+    ]);
+    (prop::collection::vec(square, 90), prop::bool::ANY).prop_map(|(squares, red)| {
+        let rows: Vec<String> = squares
+            .chunks(9)
+            .map(|row| {
+                let mut text = String::new();
+                let mut empty = 0;
+                for square in row {
+                    match square {
+                        Some(piece) => {
+                            if empty > 0 {
+                                text.push_str(&empty.to_string());
+                                empty = 0;
+                            }
+                            text.push(*piece);
+                        }
+                        None => empty += 1,
+                    }
+                }
+                if empty > 0 {
+                    text.push_str(&empty.to_string());
+                }
+                text
+            })
+            .collect();
+        format!("{} {}", rows.join("/"), if red { "w" } else { "b" })
+    })
+}
+
+fn wire_move() -> impl Strategy<Value = Move> {
+    (any::<u8>(), any::<u8>(), any::<u8>(), any::<u8>()).prop_map(
+        |(from_row, from_col, to_row, to_col)| Move {
+            from_row: from_row % 12,
+            from_col: from_col % 11,
+            to_row: to_row % 12,
+            to_col: to_col % 11,
+            score: 0,
+        },
+    )
+}
+
+proptest! {
+    #[test]
+    fn from_fen_rejects_garbage_without_panicking(
+        fen in prop_oneof![any::<String>(), "[kabnrcpKABNRCP0-9/ wb-]{0,120}"],
+    ) {
+        let _ = Board::from_fen(&fen);
+    }
+
+    #[test]
+    fn parsed_fen_survives_round_trip_and_play(
+        fen in any_placement_fen(),
+        moves in prop::collection::vec(wire_move(), 0..20),
+    ) {
+        let (board, turn) = Board::from_fen(&fen).unwrap();
+        // However odd the position, it can be written back, searched for moves and played on
+        let (again, again_turn) = Board::from_fen(&board.to_fen_string(turn)).unwrap();
+        prop_assert_eq!(&again, &board);
+        prop_assert_eq!(again_turn, turn);
+        MoveGenerator::new().generate_legal_moves(&board, turn);
+
+        let mut session = Session::from_position(board, turn, 0);
+        for mv in &moves {
+            let _ = session.play_move(session.turn(), mv, 0);
+        }
+    }
+
+    #[test]
+    fn wire_moves_only_change_the_game_when_legal(
+        moves in prop::collection::vec(wire_move(), 0..60),
+    ) {
+        let mut session = Session::new(HandicapKind::None, None, 0);
+        for mv in &moves {
+            let before = session.state.history.len();
+            let played = session.play_move(session.turn(), mv, 0).is_ok();
+            prop_assert_eq!(session.state.history.len(), before + usize::from(played));
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Property tests for the state `Board` keeps incrementally: bitboards, occupancy masks,
+//! material, piece-square totals and the zobrist hash must always equal what a full scan of
+//! the grid gives, and undoing moves must restore every field exactly.
+
+use cotuong_core::engine::zobrist::ZobristKeys;
+use cotuong_core::engine::Move;
+use cotuong_core::logic::board::{Board, BoardCoordinate, Color, Piece};
+use cotuong_core::logic::generator::MoveGenerator;
+use cotuong_core::logic::handicap::HandicapKind;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+/// Checks every incrementally kept field of `board` against the grid. `side` is the part of
+/// the hash that is not about pieces, i.e. which side is to move.
+fn assert_consistent(board: &Board, side: u64) -> Result<(), TestCaseError> {
+    let mut bitboards = [0u128; 14];
+    let mut rows = [0u16; 10];
+    let mut cols = [0u16; 9];
+    for (row, col) in (0..10).flat_map(|row| (0..9).map(move |col| (row, col))) {
+        let Some(pos) = BoardCoordinate::new(row, col) else {
+            continue;
+        };
+        if let Some(piece) = board.get_piece(pos) {
+            bitboards[piece.color.index() * 7 + piece.piece_type.index()] |= 1 << pos.index();
+            rows[row] |= 1 << col;
+            cols[col] |= 1 << row;
+        }
+    }
+    prop_assert_eq!(board.bitboards, bitboards);
+    prop_assert_eq!(board.occupied, bitboards.iter().fold(0, |all, bb| all | bb));
+    prop_assert_eq!(board.occupied_rows, rows);
+    prop_assert_eq!(board.occupied_cols, cols);
+    prop_assert_eq!(
+        (
+            board.red_material,
+            board.black_material,
+            board.red_pst,
+            board.black_pst,
+        ),
+        board.score_from_scratch()
+    );
+    prop_assert_eq!(board.zobrist_hash, board.calculate_initial_hash() ^ side);
+    Ok(())
+}
+
+fn captured_at(board: &Board, mv: &Move) -> Option<Piece> {
+    BoardCoordinate::new(usize::from(mv.to_row), usize::from(mv.to_col))
+        .and_then(|to| board.get_piece(to))
+}
+
+proptest! {
+    #[test]
+    fn make_unmake_keeps_incremental_state_exact(
+        handicap in prop::sample::select(HandicapKind::ALL.to_vec()),
+        picks in prop::collection::vec(any::<Index>(), 1..120),
+    ) {
+        let side_key = ZobristKeys::get().side_key;
+        let generator = MoveGenerator::new();
+        let state = handicap.initial_state();
+        let mut board = state.board.clone();
+        let mut turn = state.turn;
+        let mut side = board.zobrist_hash ^ board.calculate_initial_hash();
+        assert_consistent(&board, side)?;
+
+        let mut played: Vec<(Board, Move, Option<Piece>, Color)> = Vec::new();
+        for pick in &picks {
+            let moves = generator.generate_legal_moves(&board, turn);
+            if moves.is_empty() {
+                break;
+            }
+            let mv = *pick.get(&moves);
+            let captured = captured_at(&board, &mv);
+            played.push((board.clone(), mv, captured, turn));
+            board.apply_move(&mv, turn);
+            side ^= side_key;
+            turn = turn.opposite();
+            assert_consistent(&board, side)?;
+
+            // A null move only flips the side to move, and a second one flips it back
+            board.apply_null_move();
+            assert_consistent(&board, side ^ side_key)?;
+            board.apply_null_move();
+            assert_consistent(&board, side)?;
+        }
+
+        while let Some((before, mv, captured, mover)) = played.pop() {
+            board.undo_move(&mv, captured, mover);
+            prop_assert_eq!(&board, &before);
+        }
+    }
+
+    #[test]
+    fn quiet_moves_round_trip(picks in prop::collection::vec(any::<Index>(), 1..60)) {
+        let generator = MoveGenerator::new();
+        let mut board = Board::new();
+        let mut turn = Color::Red;
+        for pick in &picks {
+            let moves = generator.generate_legal_moves(&board, turn);
+            if moves.is_empty() {
+                break;
+            }
+            let mv = *pick.get(&moves);
+            let from = BoardCoordinate::new(usize::from(mv.from_row), usize::from(mv.from_col));
+            let to = BoardCoordinate::new(usize::from(mv.to_row), usize::from(mv.to_col));
+            let (Some(from), Some(to)) = (from, to) else {
+                prop_assert!(false, "generated move off the board: {:?}", mv);
+                return Ok(());
+            };
+
+            // The quiet pair keeps material and PST but leaves the hash to the caller
+            let before = board.clone();
+            let captured = board.move_piece_quiet(from, to);
+            prop_assert_eq!(captured, captured_at(&before, &mv));
+            assert_consistent(&board, board.zobrist_hash ^ board.calculate_initial_hash())?;
+            board.undo_move_quiet(from, to, captured);
+            prop_assert_eq!(&board, &before);
+
+            board.apply_move(&mv, turn);
+            turn = turn.opposite();
+        }
+    }
+}
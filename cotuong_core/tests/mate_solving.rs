@@ -2,23 +2,19 @@ use cotuong_core::engine::config::EngineConfig;
 use cotuong_core::engine::search::AlphaBetaEngine;
 use cotuong_core::engine::{SearchLimit, Searcher};
 use cotuong_core::logic::board::Board;
-use cotuong_core::logic::game::{GameState, GameStatus};
+use cotuong_core::logic::game::GameState;
 use std::sync::Arc;
 
 fn game_from_fen(fen: &str) -> GameState {
     let (board, turn) = Board::from_fen(fen).expect("Invalid FEN");
-    GameState {
-        board,
-        turn,
-        status: GameStatus::Playing,
-        last_move: None,
-        history: Vec::new(),
-    }
+    GameState::from_position(board, turn)
 }
 
 pub fn solve_mate(fen: &str, mate_d: u8, name: &str) {
-    let mut config = EngineConfig::default();
-    config.mate_score = 30000;
+    let config = EngineConfig {
+        mate_score: 30000,
+        ..Default::default()
+    };
 
     // Create game state
     let game_state = game_from_fen(fen);
@@ -74,8 +70,10 @@ fn test_mate_in_one_pawn_move() {
 
     let fen = "4k4/9/2N1P1N2/9/9/9/9/9/9/4K4 w - - 0 1";
     let game_state = game_from_fen(fen);
-    let mut config = EngineConfig::default();
-    config.mate_score = 30000;
+    let config = EngineConfig {
+        mate_score: 30000,
+        ..Default::default()
+    };
     let mut engine = AlphaBetaEngine::new(Arc::new(config));
 
     // Mate in 1 should be found at depth 2
@@ -0,0 +1,203 @@
+//! Differential tests over random positions: the grid rules in `logic::rules` (one
+//! `is_valid_move` per square pair) are the reference every bitboard generator must agree
+//! with — `MoveGenerator`'s legal and checked generators, `legal_moves_from`,
+//! `has_legal_moves`, and the engine's pseudo-legal `EngineMoveGen` once filtered.
+
+use cotuong_core::engine::config::EngineConfig;
+use cotuong_core::engine::movegen::EngineMoveGen;
+use cotuong_core::engine::Move;
+use cotuong_core::logic::board::{Board, BoardCoordinate, Color, PieceType};
+use cotuong_core::logic::generator::MoveGenerator;
+use cotuong_core::logic::rules::{is_flying_general, is_in_check, is_valid_move};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+type Key = (u8, u8, u8, u8);
+
+const PIECES: [PieceType; 6] = [
+    PieceType::Advisor,
+    PieceType::Elephant,
+    PieceType::Horse,
+    PieceType::Chariot,
+    PieceType::Cannon,
+    PieceType::Soldier,
+];
+
+fn all_squares() -> impl Iterator<Item = BoardCoordinate> {
+    (0..10).flat_map(|row| (0..9).filter_map(move |col| BoardCoordinate::new(row, col)))
+}
+
+/// Squares `piece` of `color` can ever stand on in a real game, so the generators are
+/// compared on reachable positions only. Rows count from Red's side.
+fn can_stand_on(piece: PieceType, color: Color, pos: BoardCoordinate) -> bool {
+    let own_row = match color {
+        Color::Red => pos.row,
+        Color::Black => 9 - pos.row,
+    };
+    let palace = own_row <= 2 && (3..=5).contains(&pos.col);
+    match piece {
+        PieceType::General => palace,
+        PieceType::Advisor => palace && !(own_row + pos.col).is_multiple_of(2),
+        PieceType::Elephant => {
+            matches!((own_row, pos.col), (0 | 4, 2 | 6) | (2, 0 | 4 | 8))
+        }
+        PieceType::Soldier => own_row >= 5 || (own_row >= 3 && pos.col.is_multiple_of(2)),
+        PieceType::Horse | PieceType::Chariot | PieceType::Cannon => true,
+    }
+}
+
+/// A position with both generals in their palaces and up to `placements.len()` other pieces
+/// on squares they could reach; placements that do not fit are skipped.
+fn position() -> impl Strategy<Value = (Board, Color)> {
+    let placement = (any::<Index>(), any::<bool>(), any::<Index>());
+    (
+        any::<Index>(),
+        any::<Index>(),
+        prop::collection::vec(placement, 0..28),
+        any::<bool>(),
+    )
+        .prop_map(|(red_general, black_general, placements, red_to_move)| {
+            let squares: Vec<BoardCoordinate> = all_squares().collect();
+            let mut board = Board::new();
+            board.clear();
+            for (color, pick) in [(Color::Red, red_general), (Color::Black, black_general)] {
+                let palace: Vec<BoardCoordinate> = squares
+                    .iter()
+                    .copied()
+                    .filter(|&pos| can_stand_on(PieceType::General, color, pos))
+                    .collect();
+                board.add_piece(*pick.get(&palace), PieceType::General, color);
+            }
+            for (piece, red, square) in placements {
+                let piece = *piece.get(&PIECES);
+                let color = if red { Color::Red } else { Color::Black };
+                let pos = *square.get(&squares);
+                if board.get_piece(pos).is_none() && can_stand_on(piece, color, pos) {
+                    board.add_piece(pos, piece, color);
+                }
+            }
+            board.zobrist_hash = board.calculate_initial_hash();
+            let turn = if red_to_move {
+                Color::Red
+            } else {
+                Color::Black
+            };
+            (board, turn)
+        })
+}
+
+fn keys(moves: impl IntoIterator<Item = Move>) -> Vec<Key> {
+    let mut keys: Vec<Key> = moves
+        .into_iter()
+        .map(|m| (m.from_row, m.from_col, m.to_row, m.to_col))
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+fn key(from: BoardCoordinate, to: BoardCoordinate) -> Option<Key> {
+    Some((
+        u8::try_from(from.row).ok()?,
+        u8::try_from(from.col).ok()?,
+        u8::try_from(to.row).ok()?,
+        u8::try_from(to.col).ok()?,
+    ))
+}
+
+/// Every legal move of `turn`, found by asking the grid rules about all square pairs.
+fn reference_moves(board: &Board, turn: Color) -> Vec<Key> {
+    let mut moves = Vec::new();
+    for from in all_squares() {
+        if board.get_piece(from).is_none_or(|p| p.color != turn) {
+            continue;
+        }
+        for to in all_squares() {
+            if is_valid_move(board, from, to, turn).is_ok() {
+                moves.extend(key(from, to));
+            }
+        }
+    }
+    moves.sort_unstable();
+    moves
+}
+
+fn is_legal(board: &Board, mv: &Move, turn: Color) -> bool {
+    let mut after = board.clone();
+    after.apply_move(mv, turn);
+    !is_in_check(&after, turn) && !is_flying_general(&after)
+}
+
+fn is_capture(board: &Board, (_, _, to_row, to_col): Key) -> bool {
+    BoardCoordinate::new(usize::from(to_row), usize::from(to_col))
+        .and_then(|to| board.get_piece(to))
+        .is_some()
+}
+
+proptest! {
+    #[test]
+    fn bitboard_generators_match_grid_rules((board, turn) in position()) {
+        let fen = board.to_fen_string(turn);
+        let reference = reference_moves(&board, turn);
+        let generator = MoveGenerator::new();
+
+        prop_assert_eq!(
+            &keys(generator.generate_legal_moves(&board, turn)),
+            &reference,
+            "generate_legal_moves: {}",
+            fen
+        );
+        prop_assert_eq!(
+            &keys(generator.generate_moves(&board, turn)),
+            &reference,
+            "generate_moves: {}",
+            fen
+        );
+        prop_assert_eq!(
+            generator.has_legal_moves(&board, turn),
+            !reference.is_empty(),
+            "has_legal_moves: {}",
+            fen
+        );
+        let own = |from: &BoardCoordinate| board.get_piece(*from).is_some_and(|p| p.color == turn);
+        for from in all_squares().filter(own) {
+            let mut found: Vec<Key> = generator
+                .legal_moves_from(&board, from)
+                .into_iter()
+                .filter_map(|to| key(from, to))
+                .collect();
+            found.sort_unstable();
+            let expected: Vec<Key> = reference
+                .iter()
+                .copied()
+                .filter(|&(row, col, _, _)| {
+                    (usize::from(row), usize::from(col)) == (from.row, from.col)
+                })
+                .collect();
+            prop_assert_eq!(found, expected, "legal_moves_from {:?}: {}", from, fen);
+        }
+
+        // The engine generates pseudo-legal moves and drops illegal ones during the search
+        let config = EngineConfig::default();
+        let history = vec![[0; 90]; 90];
+        let killers = [[None; 2]; 64];
+        let engine_gen = EngineMoveGen::new(&config, &history);
+        let mut scratch = board.clone();
+        let pseudo = engine_gen.generate_moves(&mut scratch, turn, None, &killers, 0);
+        let legal = keys(pseudo.into_iter().filter(|mv| is_legal(&board, mv, turn)));
+        prop_assert_eq!(&legal, &reference, "EngineMoveGen::generate_moves: {}", fen);
+
+        let captures = engine_gen.generate_captures(&mut scratch, turn, &killers);
+        let legal_captures = keys(captures.into_iter().filter(|mv| is_legal(&board, mv, turn)));
+        let reference_captures: Vec<Key> = reference
+            .iter()
+            .copied()
+            .filter(|&mv| is_capture(&board, mv))
+            .collect();
+        prop_assert_eq!(
+            legal_captures,
+            reference_captures,
+            "EngineMoveGen::generate_captures: {}",
+            fen
+        );
+    }
+}
@@ -0,0 +1,360 @@
+use crate::game_manager::admin::{AdminGame, QueuedPlayer};
+use crate::game_manager::arena::SubmitError;
+use crate::game_manager::AppState;
+use crate::storage::{CheatReport, ReportStatus, StorageError};
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::stream;
+use serde::Deserialize;
+use shared::{
+    ArenaStanding, ArenaSubmission, CloudEval, GameReplay, LeaderboardPage, LiveGame, PlayerProfile,
+};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    pub page: Option<usize>,
+    pub per_page: Option<usize>,
+    pub search: Option<String>,
+}
+
+pub async fn leaderboard_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LeaderboardQuery>,
+) -> Json<LeaderboardPage> {
+    Json(state.leaderboard(
+        query.page.unwrap_or(1),
+        query.per_page.unwrap_or(DEFAULT_PAGE_SIZE),
+        query.search.as_deref(),
+    ))
+}
+
+/// `GET /players/{name}/profile`: players have no accounts, so they are looked up by name.
+pub async fn profile_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Result<Json<PlayerProfile>, StatusCode> {
+    state
+        .player_profile(&name)
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn live_games_handler(State(state): State<Arc<AppState>>) -> Json<Vec<LiveGame>> {
+    Json(state.live_games().await)
+}
+
+/// Shared evaluation of the position with zobrist hash `hash`, written as 16 hex digits.
+pub async fn eval_handler(
+    State(state): State<Arc<AppState>>,
+    Path(hash): Path<String>,
+) -> Result<Json<Option<CloudEval>>, StatusCode> {
+    let hash = u64::from_str_radix(&hash, 16).map_err(|_| StatusCode::BAD_REQUEST)?;
+    Ok(Json(state.lookup_eval(hash).await))
+}
+
+/// `POST /arena/configs`: enters an engine config into the arena's round-robin. Needs the
+/// admin token or the token of a player who has finished a rated game, since a fresh token
+/// costs nothing; each player may only have `ArenaConfig::max_per_player` entries.
+pub async fn arena_submit_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(submission): Json<ArenaSubmission>,
+) -> StatusCode {
+    let header = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    let owner = if state.admin.authorize(header) {
+        None
+    } else {
+        match header
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .and_then(|token| state.signer.verify(token))
+        {
+            Some(player_id) if state.ratings.contains_key(player_id) => Some(player_id),
+            Some(_) => return StatusCode::FORBIDDEN,
+            None => return StatusCode::UNAUTHORIZED,
+        }
+    };
+    match state
+        .arena
+        .submit(&submission.name, submission.config, owner)
+    {
+        Ok(()) => StatusCode::CREATED,
+        Err(SubmitError::InvalidName) => StatusCode::BAD_REQUEST,
+        Err(SubmitError::NameTaken) => StatusCode::CONFLICT,
+        Err(SubmitError::TooMany) => StatusCode::TOO_MANY_REQUESTS,
+        Err(SubmitError::Disabled | SubmitError::Full) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+pub async fn arena_leaderboard_handler(
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<ArenaStanding>> {
+    Json(state.arena.standings())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PgnQuery {
+    /// Keep the response open and send the whole game again after every change.
+    pub follow: Option<bool>,
+}
+
+/// `GET /games/{id}.pgn`: a live game hosted here as PGN. With `?follow=true` the body is a
+/// stream of PGN games, each one the game so far, ending once the game is over or removed.
+pub async fn pgn_handler(
+    State(state): State<Arc<AppState>>,
+    Path(file): Path<String>,
+    Query(query): Query<PgnQuery>,
+) -> Result<Response, StatusCode> {
+    let game_id = file
+        .strip_suffix(".pgn")
+        .ok_or(StatusCode::NOT_FOUND)?
+        .to_string();
+    let changes = state
+        .follow_game(&game_id)
+        .await
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (pgn, finished) = state
+        .game_pgn(&game_id)
+        .await
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let headers = [(
+        header::CONTENT_TYPE,
+        "application/x-chess-pgn; charset=utf-8",
+    )];
+    if !query.follow.unwrap_or(false) || finished {
+        return Ok((headers, pgn).into_response());
+    }
+
+    let stream = stream::unfold((Some(pgn), Some(changes)), move |(pending, changes)| {
+        let state = Arc::clone(&state);
+        let game_id = game_id.clone();
+        async move {
+            if let Some(pgn) = pending {
+                return Some((Ok::<_, Infallible>(pgn), (None, changes)));
+            }
+            let mut changes = changes?;
+            changes.changed().await.ok()?;
+            let (pgn, finished) = state.game_pgn(&game_id).await?;
+            // The game with its result is the last one sent
+            let changes = (!finished).then_some(changes);
+            Some((Ok(format!("\n{pgn}")), (None, changes)))
+        }
+    });
+    Ok((headers, Body::from_stream(stream)).into_response())
+}
+
+/// `GET /games/{id}/replay`: a game hosted here, or one of the recently finished ones, with
+/// its moves packed for the client's `/replay/{id}` page.
+pub async fn replay_handler(
+    State(state): State<Arc<AppState>>,
+    Path(game_id): Path<String>,
+) -> Result<Json<GameReplay>, StatusCode> {
+    state
+        .game_replay(&game_id)
+        .await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let header = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok());
+    if state.admin.authorize(header) {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CheatReportQuery {
+    pub status: Option<ReportStatus>,
+}
+
+/// `GET /admin/cheat-reports`: reports from the anti-cheat job, newest first.
+pub async fn cheat_reports_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<CheatReportQuery>,
+) -> Result<Json<Vec<CheatReport>>, StatusCode> {
+    require_admin(&state, &headers)?;
+    state
+        .cheat_reports(query.status)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load cheat reports");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewReportRequest {
+    pub status: ReportStatus,
+}
+
+/// `POST /admin/cheat-reports/:id`: records an admin's verdict on a report.
+pub async fn review_report_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(request): Json<ReviewReportRequest>,
+) -> StatusCode {
+    if let Err(status) = require_admin(&state, &headers) {
+        return status;
+    }
+    match state.store.set_report_status(&id, request.status).await {
+        Ok(true) => {
+            tracing::info!(report_id = %id, status = ?request.status, "Cheat report reviewed");
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to update cheat report");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+fn storage_failure(e: &StorageError) -> StatusCode {
+    tracing::error!(error = %e, "Admin request failed");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// `GET /admin/games`: every game hosted by this instance.
+pub async fn admin_games_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AdminGame>>, StatusCode> {
+    require_admin(&state, &headers)?;
+    Ok(Json(state.admin_games().await))
+}
+
+/// `DELETE /admin/games/:id`: ends the game without a winner and closes it.
+pub async fn terminate_game_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(game_id): Path<String>,
+) -> StatusCode {
+    if let Err(status) = require_admin(&state, &headers) {
+        return status;
+    }
+    if state.terminate_game(&game_id).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+/// `GET /admin/queue`: players waiting for an opponent.
+pub async fn admin_queue_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<QueuedPlayer>>, StatusCode> {
+    require_admin(&state, &headers)?;
+    state
+        .matchmaking_queue()
+        .await
+        .map(Json)
+        .map_err(|e| storage_failure(&e))
+}
+
+/// `GET /admin/bans`: banned display names, lowercase.
+pub async fn bans_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<String>>, StatusCode> {
+    require_admin(&state, &headers)?;
+    state
+        .banned_players()
+        .await
+        .map(Json)
+        .map_err(|e| storage_failure(&e))
+}
+
+/// `PUT /admin/bans/:name`: bans the name and answers how many players were disconnected.
+pub async fn ban_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<usize>, StatusCode> {
+    require_admin(&state, &headers)?;
+    state
+        .ban_player(&name)
+        .await
+        .map(Json)
+        .map_err(|e| storage_failure(&e))
+}
+
+/// `DELETE /admin/bans/:name`
+pub async fn unban_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> StatusCode {
+    if let Err(status) = require_admin(&state, &headers) {
+        return status;
+    }
+    match state.unban_player(&name).await {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(e) => storage_failure(&e),
+    }
+}
+
+/// `DELETE /admin/arena/:name`: takes a config out of the arena.
+pub async fn arena_remove_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> StatusCode {
+    if let Err(status) = require_admin(&state, &headers) {
+        return status;
+    }
+    if state.arena.remove(&name) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AnnouncementRequest {
+    pub message: String,
+}
+
+/// `POST /admin/announcements`: shows a message to every player connected to this instance
+/// and answers how many received it.
+pub async fn announcement_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(request): Json<AnnouncementRequest>,
+) -> Result<Json<usize>, StatusCode> {
+    require_admin(&state, &headers)?;
+    let message = request.message.trim();
+    if message.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(Json(state.announce(message)))
+}
+
+pub async fn healthz_handler() -> &'static str {
+    "ok"
+}
+
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.render_metrics().await,
+    )
+}
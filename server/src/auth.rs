@@ -0,0 +1,120 @@
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Issues and checks player tokens of the form `<player_id>.<hex hmac-sha256(player_id)>`.
+///
+/// The key comes from `TOKEN_SECRET` so that tokens stay valid across instances sharing it;
+/// without it a random per-process key is used.
+pub struct TokenSigner {
+    mac: HmacSha256,
+}
+
+impl TokenSigner {
+    pub fn new(secret: &[u8]) -> Self {
+        // Hashing the secret into a block-sized key keeps construction infallible
+        let mut key = [0u8; 64];
+        for (k, d) in key.iter_mut().zip(Sha256::digest(secret)) {
+            *k = d;
+        }
+        Self {
+            mac: <HmacSha256 as KeyInit>::new(&key.into()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        match std::env::var("TOKEN_SECRET") {
+            Ok(secret) if !secret.is_empty() => Self::new(secret.as_bytes()),
+            _ => Self::new(&rand::random::<[u8; 32]>()),
+        }
+    }
+
+    pub fn issue(&self, player_id: &str) -> String {
+        let mut mac = self.mac.clone();
+        mac.update(player_id.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+        format!("{player_id}.{signature}")
+    }
+
+    /// Returns the player id the token was issued for, if the signature is valid.
+    pub fn verify<'a>(&self, token: &'a str) -> Option<&'a str> {
+        let (player_id, signature) = token.rsplit_once('.')?;
+        let signature = hex::decode(signature).ok()?;
+        let mut mac = self.mac.clone();
+        mac.update(player_id.as_bytes());
+        mac.verify_slice(&signature).ok()?;
+        Some(player_id)
+    }
+}
+
+/// Bearer token guarding the admin endpoints, from `ADMIN_TOKEN`. Without it every admin
+/// request is refused.
+pub struct AdminToken {
+    /// Digest of the token, so comparing it takes the same time whatever was sent.
+    digest: Option<[u8; 32]>,
+}
+
+impl AdminToken {
+    pub fn new(token: Option<&str>) -> Self {
+        Self {
+            digest: token
+                .filter(|t| !t.is_empty())
+                .map(|t| Sha256::digest(t.as_bytes()).into()),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("ADMIN_TOKEN").ok().as_deref())
+    }
+
+    /// Whether an `Authorization` header value carries the admin token.
+    pub fn authorize(&self, header: Option<&str>) -> bool {
+        let Some(expected) = self.digest else {
+            return false;
+        };
+        header
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .is_some_and(|token| <[u8; 32]>::from(Sha256::digest(token.as_bytes())) == expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_roundtrip() {
+        let signer = TokenSigner::new(b"secret");
+        let token = signer.issue("player-1");
+        assert_eq!(signer.verify(&token), Some("player-1"));
+    }
+
+    #[test]
+    fn test_token_rejects_tampering() {
+        let signer = TokenSigner::new(b"secret");
+        let token = signer.issue("player-1");
+
+        let (_, signature) = token.rsplit_once('.').unwrap();
+        let spoofed = format!("player-2.{signature}");
+        assert_eq!(signer.verify(&spoofed), None);
+        assert_eq!(signer.verify("player-1"), None);
+        assert_eq!(signer.verify("player-1.zz"), None);
+
+        let other = TokenSigner::new(b"other");
+        assert_eq!(other.verify(&token), None);
+    }
+
+    #[test]
+    fn test_admin_token() {
+        let admin = AdminToken::new(Some("s3cret"));
+        assert!(admin.authorize(Some("Bearer s3cret")));
+        assert!(!admin.authorize(Some("Bearer s3cre")));
+        assert!(!admin.authorize(Some("s3cret")));
+        assert!(!admin.authorize(None));
+
+        let disabled = AdminToken::new(Some(""));
+        assert!(!disabled.authorize(Some("Bearer ")));
+    }
+}
@@ -0,0 +1,454 @@
+//! Load test for a running server: N simulated clients connect over WebSocket, are paired by
+//! the matchmaking queue and play random legal games against each other. Prints latency
+//! percentiles and a count of every error seen.
+//!
+//! Usage: `cargo run --release -p server --bin loadtest -- [options]`
+//!
+//! Options:
+//!   --url URL         WebSocket endpoint (default ws://127.0.0.1:3000/ws)
+//!   --clients N       concurrent clients; an odd one out waits for a match (default 100)
+//!   --games G         games each client plays in a row (default 1)
+//!   --max-plies N     resign once a game reaches this many plies (default 80)
+//!   --move-delay MS   pause before each move, to stay under the move rate limit (default 300)
+//!   --timeout S       give up on a client that hears nothing for S seconds (default 30)
+//!   --bincode         ask for binary frames instead of JSON
+
+use cotuong_core::logic::board::{Board, BoardCoordinate, Color};
+use cotuong_core::logic::game::GameState;
+use cotuong_core::logic::generator::MoveGenerator;
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use shared::codec::{decode, encode, Frame};
+use shared::{ClientHello, ClientMessage, Encoding, GameMessage, ServerMessage, PROTOCOL_VERSION};
+use std::collections::{BTreeMap, HashMap};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+struct Options {
+    url: String,
+    clients: usize,
+    games: usize,
+    max_plies: usize,
+    move_delay: Duration,
+    timeout: Duration,
+    encoding: Encoding,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        url: "ws://127.0.0.1:3000/ws".to_string(),
+        clients: 100,
+        games: 1,
+        max_plies: 80,
+        move_delay: Duration::from_millis(300),
+        timeout: Duration::from_secs(30),
+        encoding: Encoding::Json,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "--url" => options.url = value("--url")?,
+            "--clients" => options.clients = parse_num(&value("--clients")?)?,
+            "--games" => options.games = parse_num(&value("--games")?)?,
+            "--max-plies" => options.max_plies = parse_num(&value("--max-plies")?)?,
+            "--move-delay" => {
+                options.move_delay = Duration::from_millis(parse_num(&value("--move-delay")?)?);
+            }
+            "--timeout" => {
+                options.timeout = Duration::from_secs(parse_num(&value("--timeout")?)?);
+            }
+            "--bincode" => options.encoding = Encoding::Bincode,
+            _ => return Err(format!("unknown option {arg}")),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_num<T: std::str::FromStr>(text: &str) -> Result<T, String> {
+    text.parse().map_err(|_| format!("invalid number: {text}"))
+}
+
+/// What one client measured; merged into a single report at the end.
+#[derive(Default)]
+struct Report {
+    /// Connect through `Welcome`.
+    handshake: Vec<Duration>,
+    /// `FindMatch` through `GameStart`.
+    matchmaking: Vec<Duration>,
+    /// Own `MakeMove` through the opponent's reply, minus the opponent's move delay.
+    move_reply: Vec<Duration>,
+    /// Own `MakeMove` through `MoveAccepted`, i.e. the opponent's verification.
+    move_ack: Vec<Duration>,
+    /// `Ping` through `Pong`.
+    ping: Vec<Duration>,
+    moves_sent: usize,
+    games_finished: usize,
+    errors: BTreeMap<String, usize>,
+}
+
+impl Report {
+    fn error(&mut self, kind: impl Into<String>) {
+        *self.errors.entry(kind.into()).or_default() += 1;
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.handshake.extend(other.handshake);
+        self.matchmaking.extend(other.matchmaking);
+        self.move_reply.extend(other.move_reply);
+        self.move_ack.extend(other.move_ack);
+        self.ping.extend(other.ping);
+        self.moves_sent += other.moves_sent;
+        self.games_finished += other.games_finished;
+        for (kind, count) in other.errors {
+            *self.errors.entry(kind).or_default() += count;
+        }
+    }
+}
+
+/// The sample below which `fraction` of `sorted` lies.
+fn percentile(sorted: &[Duration], fraction: f64) -> Duration {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let index = ((sorted.len() as f64 * fraction).ceil() as usize).saturating_sub(1);
+    sorted.get(index).copied().unwrap_or_default()
+}
+
+fn print_latencies(name: &str, samples: &mut [Duration]) {
+    samples.sort_unstable();
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    println!(
+        "{name:<12} {:>7}  p50 {:>8.1}ms  p90 {:>8.1}ms  p99 {:>8.1}ms  max {:>8.1}ms",
+        samples.len(),
+        ms(percentile(samples, 0.5)),
+        ms(percentile(samples, 0.9)),
+        ms(percentile(samples, 0.99)),
+        ms(samples.last().copied().unwrap_or_default()),
+    );
+}
+
+struct Connection {
+    sink: SplitSink<Socket, Message>,
+    stream: SplitStream<Socket>,
+    encoding: Encoding,
+    token: String,
+    timeout: Duration,
+    next_nonce: u64,
+    pings: HashMap<u64, Instant>,
+}
+
+impl Connection {
+    async fn send_frame(&mut self, frame: Frame) -> Result<(), String> {
+        let message = match frame {
+            Frame::Text(text) => Message::Text(text),
+            Frame::Binary(bytes) => Message::Binary(bytes),
+        };
+        self.sink
+            .send(message)
+            .await
+            .map_err(|e| format!("send: {e}"))
+    }
+
+    async fn send(&mut self, msg: GameMessage) -> Result<(), String> {
+        let message = ClientMessage {
+            token: self.token.clone(),
+            msg,
+        };
+        let frame = encode(self.encoding, &message).map_err(|e| e.to_string())?;
+        self.send_frame(frame).await
+    }
+
+    async fn ping(&mut self) -> Result<(), String> {
+        self.next_nonce += 1;
+        self.pings.insert(self.next_nonce, Instant::now());
+        self.send(GameMessage::Ping(self.next_nonce)).await
+    }
+
+    /// The next server message, skipping WebSocket control frames.
+    async fn recv(&mut self) -> Result<ServerMessage, String> {
+        loop {
+            let message = tokio::time::timeout(self.timeout, self.stream.next())
+                .await
+                .map_err(|_| "timeout".to_string())?
+                .ok_or_else(|| "connection closed".to_string())?
+                .map_err(|e| format!("receive: {e}"))?;
+            let frame = match message {
+                Message::Text(text) => Frame::Text(text),
+                Message::Binary(bytes) => Frame::Binary(bytes),
+                Message::Close(_) => return Err("connection closed".to_string()),
+                Message::Ping(_) | Message::Pong(_) | Message::Frame(_) => continue,
+            };
+            return decode(&frame).map_err(|e| e.to_string());
+        }
+    }
+}
+
+async fn connect(options: &Options, report: &mut Report) -> Result<Connection, String> {
+    let started = Instant::now();
+    let (socket, _) = connect_async(options.url.as_str())
+        .await
+        .map_err(|e| format!("connect: {e}"))?;
+    let (sink, stream) = socket.split();
+    let mut conn = Connection {
+        sink,
+        stream,
+        encoding: Encoding::Json,
+        token: String::new(),
+        timeout: options.timeout,
+        next_nonce: 0,
+        pings: HashMap::new(),
+    };
+    let hello = ClientHello {
+        protocol_version: PROTOCOL_VERSION,
+        encodings: vec![options.encoding],
+        resume_token: None,
+    };
+    let frame = encode(Encoding::Json, &hello).map_err(|e| e.to_string())?;
+    conn.send_frame(frame).await?;
+    match conn.recv().await? {
+        ServerMessage::ServerHello { encoding, .. } => conn.encoding = encoding,
+        other => return Err(format!("expected ServerHello, got {}", label(&other))),
+    }
+    match conn.recv().await? {
+        ServerMessage::Welcome { token, .. } => conn.token = token,
+        other => return Err(format!("expected Welcome, got {}", label(&other))),
+    }
+    report.handshake.push(started.elapsed());
+    Ok(conn)
+}
+
+/// Plays a random legal move for the side to move; a move the repetition rule forbids is
+/// skipped in favour of another.
+fn random_move(state: &mut GameState, rng: &mut StdRng) -> Option<GameMessage> {
+    let mut moves = MoveGenerator::new().generate_legal_moves(&state.board, state.turn);
+    moves.shuffle(rng);
+    moves.into_iter().find_map(|mv| {
+        let from = BoardCoordinate::new(usize::from(mv.from_row), usize::from(mv.from_col))?;
+        let to = BoardCoordinate::new(usize::from(mv.to_row), usize::from(mv.to_col))?;
+        state.make_move(from, to).ok()?;
+        Some(GameMessage::MakeMove {
+            move_data: mv,
+            fen: state.board.to_fen_string(state.turn),
+        })
+    })
+}
+
+async fn play_game(
+    conn: &mut Connection,
+    options: &Options,
+    rng: &mut StdRng,
+    report: &mut Report,
+) -> Result<(), String> {
+    let asked = Instant::now();
+    conn.send(GameMessage::FindMatch).await?;
+    let mut color = Color::Red;
+    loop {
+        match conn.recv().await? {
+            ServerMessage::MatchFound { your_color, .. } => color = your_color,
+            ServerMessage::GameStart(_) => break,
+            // The last opponent left the finished game before we did
+            ServerMessage::WaitingForMatch | ServerMessage::OpponentLeftGame => {}
+            other => unexpected(report, &other),
+        }
+    }
+    report.matchmaking.push(asked.elapsed());
+
+    let mut state = GameState::new();
+    let mut last_sent: Option<(GameMessage, Instant)> = None;
+    let mut resigned = false;
+    loop {
+        if state.turn == color && !resigned {
+            tokio::time::sleep(options.move_delay).await;
+            let msg = if state.history.len() >= options.max_plies {
+                None
+            } else {
+                random_move(&mut state, rng)
+            };
+            if let Some(msg) = msg {
+                conn.send(msg.clone()).await?;
+                conn.ping().await?;
+                last_sent = Some((msg, Instant::now()));
+                report.moves_sent += 1;
+            } else {
+                // Out of plies, or no move the rules allow: end the game from this side
+                conn.send(GameMessage::Surrender).await?;
+                resigned = true;
+            }
+        }
+        match conn.recv().await? {
+            ServerMessage::OpponentMove { move_data, .. } => {
+                if let Some((_, sent)) = last_sent.take() {
+                    report
+                        .move_reply
+                        .push(sent.elapsed().saturating_sub(options.move_delay));
+                }
+                let from = BoardCoordinate::new(
+                    usize::from(move_data.from_row),
+                    usize::from(move_data.from_col),
+                );
+                let to = BoardCoordinate::new(
+                    usize::from(move_data.to_row),
+                    usize::from(move_data.to_col),
+                );
+                let is_valid = from
+                    .zip(to)
+                    .is_some_and(|(from, to)| state.make_move(from, to).is_ok());
+                if !is_valid {
+                    report.error("illegal opponent move");
+                }
+                let fen = state.board.to_fen_string(state.turn);
+                conn.send(GameMessage::VerifyMove { fen, is_valid }).await?;
+            }
+            ServerMessage::MoveAccepted { .. } => {
+                if let Some((_, sent)) = &last_sent {
+                    report.move_ack.push(sent.elapsed());
+                }
+            }
+            ServerMessage::Pong(nonce) => {
+                if let Some(sent) = conn.pings.remove(&nonce) {
+                    report.ping.push(sent.elapsed());
+                }
+            }
+            ServerMessage::RateLimited {
+                kind,
+                retry_after_ms,
+            } => {
+                report.error(format!("rate limited ({kind:?})"));
+                // The move was dropped; send it again once the budget allows
+                if let Some((msg, _)) = &last_sent {
+                    let msg = msg.clone();
+                    tokio::time::sleep(Duration::from_millis(retry_after_ms)).await;
+                    conn.send(msg).await?;
+                }
+            }
+            ServerMessage::GameStateCorrection { fen, turn, .. } => {
+                report.error("state correction");
+                if let Ok((board, _)) = Board::from_fen(&fen) {
+                    state.board = board;
+                    state.turn = turn;
+                }
+            }
+            ServerMessage::GameEnd { .. } => {
+                report.games_finished += 1;
+                return Ok(());
+            }
+            ServerMessage::GameAborted { .. } => {
+                report.error("game aborted");
+                return Ok(());
+            }
+            ServerMessage::OpponentDisconnected | ServerMessage::OpponentLeftGame => {
+                report.error("opponent left");
+                return Ok(());
+            }
+            other => unexpected(report, &other),
+        }
+    }
+}
+
+fn label(msg: &ServerMessage) -> String {
+    let debug = format!("{msg:?}");
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn unexpected(report: &mut Report, msg: &ServerMessage) {
+    match msg {
+        ServerMessage::Error { code, .. } => report.error(format!("error ({code:?})")),
+        ServerMessage::Announcement(_) | ServerMessage::ServerShuttingDown { .. } => {}
+        other => report.error(format!("unexpected {}", label(other))),
+    }
+}
+
+async fn run_client(options: Arc<Options>) -> Report {
+    let mut report = Report::default();
+    let mut rng = StdRng::from_entropy();
+    let mut conn = match connect(&options, &mut report).await {
+        Ok(conn) => conn,
+        Err(e) => {
+            report.error(e);
+            return report;
+        }
+    };
+    for game in 0..options.games {
+        // Both players keep their seats after `GameEnd` until one of them leaves
+        if game > 0 {
+            if let Err(e) = conn.send(GameMessage::PlayerLeft).await {
+                report.error(e);
+                break;
+            }
+        }
+        if let Err(e) = play_game(&mut conn, &options, &mut rng, &mut report).await {
+            report.error(e);
+            break;
+        }
+    }
+    let _ = conn.sink.close().await;
+    report
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => Arc::new(options),
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("usage: loadtest [--url URL] [--clients N] [--games G] [--max-plies N] [--move-delay MS] [--timeout S] [--bincode]");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "{} clients x {} games against {}",
+        options.clients, options.games, options.url
+    );
+    let started = Instant::now();
+    let tasks: Vec<_> = (0..options.clients)
+        .map(|_| tokio::spawn(run_client(Arc::clone(&options))))
+        .collect();
+    let mut report = Report::default();
+    for task in tasks {
+        match task.await {
+            Ok(client) => report.merge(client),
+            Err(e) => report.error(format!("client panicked: {e}")),
+        }
+    }
+
+    println!(
+        "{} games finished, {} moves sent in {:.1}s",
+        report.games_finished,
+        report.moves_sent,
+        started.elapsed().as_secs_f64()
+    );
+    print_latencies("handshake", &mut report.handshake);
+    print_latencies("matchmaking", &mut report.matchmaking);
+    print_latencies("move ack", &mut report.move_ack);
+    print_latencies("move reply", &mut report.move_reply);
+    print_latencies("ping", &mut report.ping);
+    let errors: usize = report.errors.values().sum();
+    let messages = report.moves_sent + report.handshake.len() + report.matchmaking.len();
+    #[allow(clippy::cast_precision_loss)]
+    let rate = errors as f64 * 100.0 / messages.max(1) as f64;
+    println!("errors: {errors} ({rate:.2}% of requests)");
+    for (kind, count) in &report.errors {
+        println!("  {count:>6}  {kind}");
+    }
+    if report.handshake.is_empty() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
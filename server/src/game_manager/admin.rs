@@ -0,0 +1,147 @@
+use crate::game_manager::lifecycle::name_key;
+use crate::game_manager::AppState;
+use crate::storage::StorageResult;
+use cotuong_core::logic::handicap::HandicapKind;
+use serde::Serialize;
+use shared::{ErrorCode, LiveGame, ServerMessage};
+
+/// A game hosted by this instance, as listed for operators.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminGame {
+    #[serde(flatten)]
+    pub game: LiveGame,
+    pub red_player: String,
+    pub black_player: String,
+    /// Finished games stay listed until both players leave or the cleanup task drops them.
+    pub over: bool,
+    pub spectators: usize,
+}
+
+/// A player waiting for an opponent.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedPlayer {
+    pub player_id: String,
+    /// `None` when the player is connected to another instance.
+    pub name: Option<String>,
+    pub handicap: HandicapKind,
+}
+
+impl AppState {
+    /// Every game hosted here, ongoing games first.
+    pub async fn admin_games(&self) -> Vec<AdminGame> {
+        let mut games = Vec::new();
+        for entry in self.games.iter() {
+            let game = entry.value().read().await;
+            games.push(AdminGame {
+                game: game.live_game(entry.key()),
+                red_player: game.red_player.clone(),
+                black_player: game.black_player.clone(),
+                over: game.session.is_over(),
+                spectators: game.spectators.len(),
+            });
+        }
+        games.sort_by(|a, b| {
+            a.over
+                .cmp(&b.over)
+                .then_with(|| a.game.game_id.cmp(&b.game.game_id))
+        });
+        games
+    }
+
+    /// Ends a game hosted here without a winner or rating change and closes it.
+    /// Returns `false` when there is no such game.
+    pub async fn terminate_game(&self, game_id: &str) -> bool {
+        self.close_game(game_id, |game| {
+            // A finished game waiting for a rematch is just closed
+            let result = game.session.terminate().ok()?;
+            tracing::info!(game_id = %game_id, "Game terminated by operator");
+            Some(ServerMessage::GameEnd {
+                winner: result.winner,
+                reason: result.reason.as_str().to_string(),
+            })
+        })
+        .await
+    }
+
+    /// Whether `name` is banned; a storage failure lets the player through.
+    pub async fn is_name_banned(&self, name: &str) -> bool {
+        match self.store.is_banned(&name_key(name)).await {
+            Ok(banned) => banned,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to check ban list");
+                false
+            }
+        }
+    }
+
+    /// Tells a banned player why, then drops their connection; their game ends as a disconnect.
+    pub async fn kick_banned(&self, player_id: &str) {
+        tracing::info!(player_id = %player_id, "Disconnecting banned player");
+        self.send_error(player_id, ErrorCode::Banned, None);
+        self.remove_player(player_id).await;
+    }
+
+    /// Bans a display name on every instance and disconnects the players using it here.
+    /// Returns how many were disconnected.
+    ///
+    /// A banned name cannot be taken again by any player.
+    pub async fn ban_player(&self, name: &str) -> StorageResult<usize> {
+        let key = name_key(name.trim());
+        self.store.set_banned(&key, true).await?;
+        tracing::info!(name = %key, "Name banned");
+
+        let kicked: Vec<String> = self
+            .players
+            .iter()
+            .filter(|player| name_key(&player.name) == key)
+            .map(|player| player.key().clone())
+            .collect();
+        for player_id in &kicked {
+            self.kick_banned(player_id).await;
+        }
+        Ok(kicked.len())
+    }
+
+    pub async fn unban_player(&self, name: &str) -> StorageResult<()> {
+        let key = name_key(name.trim());
+        self.store.set_banned(&key, false).await?;
+        tracing::info!(name = %key, "Name unbanned");
+        Ok(())
+    }
+
+    pub async fn banned_players(&self) -> StorageResult<Vec<String>> {
+        let mut names = self.store.banned().await?;
+        names.sort();
+        Ok(names)
+    }
+
+    /// Sends `text` to every player connected to this instance; returns how many got it.
+    pub fn announce(&self, text: &str) -> usize {
+        tracing::info!(text = %text, "Broadcasting announcement");
+        self.players
+            .iter()
+            .filter(|player| {
+                player
+                    .tx
+                    .send(ServerMessage::Announcement(text.to_string()))
+                    .is_ok()
+            })
+            .count()
+    }
+
+    pub async fn matchmaking_queue(&self) -> StorageResult<Vec<QueuedPlayer>> {
+        let mut queue: Vec<QueuedPlayer> = self
+            .store
+            .queued_players()
+            .await?
+            .into_iter()
+            .map(|(player_id, handicap)| QueuedPlayer {
+                name: self.players.get(&player_id).map(|p| p.name.clone()),
+                player_id,
+                handicap,
+            })
+            .collect();
+        queue.sort_by(|a, b| a.player_id.cmp(&b.player_id));
+        Ok(queue)
+    }
+}
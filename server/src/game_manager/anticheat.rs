@@ -0,0 +1,320 @@
+use crate::game_manager::session::now_ms;
+use crate::game_manager::{AppState, GameSession};
+use crate::storage::{CheatReport, ReportStatus, StorageResult};
+use cotuong_core::engine::config::EngineConfig;
+use cotuong_core::engine::review::{review_game, GameReview};
+use cotuong_core::engine::search::AlphaBetaEngine;
+use cotuong_core::engine::SearchLimit;
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::GameState;
+use std::cmp::Reverse;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Opening moves are known by heart and match the engine for everyone, so they are skipped.
+const OPENING_PLIES: usize = 10;
+const ENGINE_TT_MB: usize = 16;
+
+/// When a player's moves count as engine-like: both the match rate and the average
+/// centipawn loss must cross their threshold, over enough moves to mean something.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AntiCheatConfig {
+    /// Search depth per position; 0 turns the job off.
+    pub depth: u8,
+    pub min_moves: usize,
+    pub min_match_rate: f64,
+    pub max_avg_cp_loss: f64,
+}
+
+impl Default for AntiCheatConfig {
+    fn default() -> Self {
+        Self {
+            depth: 5,
+            min_moves: 20,
+            min_match_rate: 0.85,
+            max_avg_cp_loss: 15.0,
+        }
+    }
+}
+
+pub(super) fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+impl AntiCheatConfig {
+    /// Reads `ANTICHEAT_DEPTH`, `ANTICHEAT_MIN_MOVES`, `ANTICHEAT_MATCH_RATE` and
+    /// `ANTICHEAT_MAX_CP_LOSS`, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            depth: env_or("ANTICHEAT_DEPTH", default.depth),
+            min_moves: env_or("ANTICHEAT_MIN_MOVES", default.min_moves),
+            min_match_rate: env_or("ANTICHEAT_MATCH_RATE", default.min_match_rate),
+            max_avg_cp_loss: env_or("ANTICHEAT_MAX_CP_LOSS", default.max_avg_cp_loss),
+        }
+    }
+
+    pub fn is_suspicious(&self, stats: &SideStats) -> bool {
+        stats.moves >= self.min_moves.max(1)
+            && stats.match_rate >= self.min_match_rate
+            && stats.avg_cp_loss <= self.max_avg_cp_loss
+    }
+}
+
+/// How closely one side's moves past the opening followed the engine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SideStats {
+    pub moves: usize,
+    pub match_rate: f64,
+    pub avg_cp_loss: f64,
+}
+
+impl SideStats {
+    pub fn from_review(review: &GameReview, color: Color) -> Self {
+        let moves: Vec<_> = review
+            .moves
+            .iter()
+            .filter(|m| m.mover == color && m.ply >= OPENING_PLIES)
+            .collect();
+        if moves.is_empty() {
+            return Self {
+                moves: 0,
+                match_rate: 0.0,
+                avg_cp_loss: 0.0,
+            };
+        }
+        let matched = moves.iter().filter(|m| m.best == Some(m.played)).count();
+        let total_loss: i32 = moves.iter().map(|m| m.cp_loss).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let n = moves.len() as f64;
+        #[allow(clippy::cast_precision_loss)]
+        Self {
+            moves: moves.len(),
+            match_rate: matched as f64 / n,
+            avg_cp_loss: f64::from(total_loss) / n,
+        }
+    }
+}
+
+/// A finished rated game waiting for analysis.
+pub struct RatedGame {
+    pub game_id: String,
+    pub red_player: String,
+    pub black_player: String,
+    pub red_name: String,
+    pub black_name: String,
+    pub state: GameState,
+}
+
+impl RatedGame {
+    /// Reviews the game with the engine at the configured depth.
+    pub fn review(&self, config: &AntiCheatConfig) -> GameReview {
+        let mut engine = AlphaBetaEngine::new(Arc::new(EngineConfig {
+            tt_size_mb: ENGINE_TT_MB,
+            ..Default::default()
+        }));
+        review_game(
+            &mut engine,
+            &self.state,
+            SearchLimit::Depth(config.depth),
+            |_, _, _| {},
+        )
+    }
+
+    /// Reports each side that looks engine-assisted.
+    fn reports(&self, review: &GameReview, config: &AntiCheatConfig) -> Vec<CheatReport> {
+        [
+            (Color::Red, &self.red_name),
+            (Color::Black, &self.black_name),
+        ]
+        .into_iter()
+        .filter_map(|(color, player)| {
+            let stats = SideStats::from_review(review, color);
+            config.is_suspicious(&stats).then(|| CheatReport {
+                id: uuid::Uuid::new_v4().to_string(),
+                game_id: self.game_id.clone(),
+                player: player.clone(),
+                color,
+                moves: stats.moves,
+                match_rate: stats.match_rate,
+                avg_cp_loss: stats.avg_cp_loss,
+                created_ms: now_ms(),
+                status: ReportStatus::Open,
+            })
+        })
+        .collect()
+    }
+}
+
+/// Queue of rated games for the background job, which takes the receiving end on start.
+pub struct AntiCheat {
+    pub config: AntiCheatConfig,
+    queue: mpsc::UnboundedSender<RatedGame>,
+    pending: Mutex<Option<mpsc::UnboundedReceiver<RatedGame>>>,
+}
+
+impl AntiCheat {
+    pub fn new(config: AntiCheatConfig) -> Self {
+        let (queue, pending) = mpsc::unbounded_channel();
+        Self {
+            config,
+            queue,
+            pending: Mutex::new(Some(pending)),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(AntiCheatConfig::from_env())
+    }
+
+    /// Queues a finished rated game for analysis.
+    pub fn submit(&self, game_id: &str, game: &GameSession) {
+        if self.config.depth == 0 {
+            return;
+        }
+        let _ = self.queue.send(RatedGame {
+            game_id: game_id.to_string(),
+            red_player: game.red_player.clone(),
+            black_player: game.black_player.clone(),
+            red_name: game.red_name.clone(),
+            black_name: game.black_name.clone(),
+            state: game.session.state.clone(),
+        });
+    }
+}
+
+impl AppState {
+    /// Starts the job analysing finished rated games one at a time, off the async runtime.
+    /// The review also feeds the players' average accuracy.
+    pub fn spawn_anticheat_job(self: Arc<Self>) {
+        let Some(mut rx) = self
+            .anticheat
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.take())
+        else {
+            return;
+        };
+        tokio::spawn(async move {
+            while let Some(game) = rx.recv().await {
+                let config = self.anticheat.config;
+                let analysis = tokio::task::spawn_blocking(move || {
+                    let review = game.review(&config);
+                    let reports = game.reports(&review, &config);
+                    (game, review, reports)
+                })
+                .await;
+                let (game, review, reports) = match analysis {
+                    Ok(analysis) => analysis,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Anti-cheat analysis failed");
+                        continue;
+                    }
+                };
+                for (player_id, side) in [
+                    (&game.red_player, review.red),
+                    (&game.black_player, review.black),
+                ] {
+                    if side.moves > 0 {
+                        self.record_accuracy(player_id, side.accuracy);
+                    }
+                }
+                for report in reports {
+                    tracing::warn!(
+                        game_id = %report.game_id,
+                        player = %report.player,
+                        match_rate = report.match_rate,
+                        avg_cp_loss = report.avg_cp_loss,
+                        "Flagged possible engine use"
+                    );
+                    if let Err(e) = self.store.save_cheat_report(&report).await {
+                        tracing::error!(error = %e, "Failed to save cheat report");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Cheat reports, newest first, optionally only those with `status`.
+    pub async fn cheat_reports(
+        &self,
+        status: Option<ReportStatus>,
+    ) -> StorageResult<Vec<CheatReport>> {
+        let mut reports = self.store.cheat_reports().await?;
+        reports.retain(|r| status.is_none_or(|s| r.status == s));
+        reports.sort_by_key(|r| Reverse(r.created_ms));
+        Ok(reports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cotuong_core::engine::review::MoveReview;
+    use cotuong_core::logic::board::BoardCoordinate;
+
+    fn move_review(ply: usize, matched: bool, cp_loss: i32) -> MoveReview {
+        let square = |row, col| BoardCoordinate::new(row, col).unwrap();
+        let played = (square(0, 0), square(1, 0));
+        let other = (square(0, 8), square(1, 8));
+        MoveReview {
+            ply,
+            mover: if ply.is_multiple_of(2) {
+                Color::Red
+            } else {
+                Color::Black
+            },
+            played,
+            best: Some(if matched { played } else { other }),
+            score_before: 0,
+            score_after: -cp_loss,
+            cp_loss,
+            accuracy: 100.0,
+            judgement: None,
+        }
+    }
+
+    #[test]
+    fn test_flags_engine_like_side_only() {
+        // Red always finds the engine move; Black only every other move and loses material
+        let review = GameReview {
+            moves: (0..60usize)
+                .map(|ply| {
+                    let red = ply.is_multiple_of(2);
+                    let matched = red || ply % 4 == 1;
+                    move_review(ply, matched, if matched { 0 } else { 80 })
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let red = SideStats::from_review(&review, Color::Red);
+        assert_eq!(red.moves, 25);
+        assert!((red.match_rate - 1.0).abs() < 1e-9);
+
+        let game = RatedGame {
+            game_id: "g1".to_string(),
+            red_player: "p1".to_string(),
+            black_player: "p2".to_string(),
+            red_name: "Alice".to_string(),
+            black_name: "Bob".to_string(),
+            state: GameState::new(),
+        };
+        let config = AntiCheatConfig::default();
+        let reports = game.reports(&review, &config);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].player, "Alice");
+        assert_eq!(reports[0].status, ReportStatus::Open);
+
+        // Too short a game is never judged
+        let short = GameReview {
+            moves: review.moves[..30].to_vec(),
+            ..Default::default()
+        };
+        assert!(game.reports(&short, &config).is_empty());
+    }
+}
@@ -0,0 +1,493 @@
+use crate::game_manager::anticheat::env_or;
+use crate::game_manager::AppState;
+use cotuong_core::engine::config::EngineConfig;
+use cotuong_core::engine::match_runner::{
+    parse_opening, play_game, MatchScore, PlayedGame, OPENINGS,
+};
+use cotuong_core::engine::search::AlphaBetaEngine;
+use cotuong_core::engine::SearchLimit;
+use shared::ArenaStanding;
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::{mpsc, Semaphore};
+
+/// Longest config name the arena accepts, in characters.
+pub const MAX_NAME_LEN: usize = 32;
+
+/// How arena matches are played and how much of the server they may use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArenaConfig {
+    /// Thinking time per move in milliseconds; 0 turns the arena off.
+    pub move_ms: u64,
+    /// Openings each pairing plays, each one twice with the colours swapped.
+    pub openings: usize,
+    /// Games still going after this many plies are drawn.
+    pub max_plies: usize,
+    /// Pairings played at the same time, each on its own blocking thread.
+    pub workers: usize,
+    pub max_entries: usize,
+    /// Entries one player may have in the arena at a time; operators are not limited.
+    pub max_per_player: usize,
+    /// Transposition table every entry gets, whatever its config asks for.
+    pub tt_size_mb: usize,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self {
+            move_ms: 100,
+            openings: 2,
+            max_plies: 200,
+            workers: 2,
+            max_entries: 64,
+            max_per_player: 2,
+            tt_size_mb: 16,
+        }
+    }
+}
+
+impl ArenaConfig {
+    /// Reads `ARENA_MOVE_MS`, `ARENA_OPENINGS`, `ARENA_MAX_PLIES`, `ARENA_WORKERS`,
+    /// `ARENA_MAX_ENTRIES`, `ARENA_MAX_PER_PLAYER` and `ARENA_TT_MB`, falling back to the
+    /// defaults.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        Self {
+            move_ms: env_or("ARENA_MOVE_MS", default.move_ms),
+            openings: env_or("ARENA_OPENINGS", default.openings).clamp(1, OPENINGS.len()),
+            max_plies: env_or("ARENA_MAX_PLIES", default.max_plies),
+            workers: env_or("ARENA_WORKERS", default.workers).max(1),
+            max_entries: env_or("ARENA_MAX_ENTRIES", default.max_entries),
+            max_per_player: env_or("ARENA_MAX_PER_PLAYER", default.max_per_player),
+            tt_size_mb: env_or("ARENA_TT_MB", default.tt_size_mb),
+        }
+    }
+
+    pub const fn games_per_pairing(&self) -> u32 {
+        // `openings` is at most `OPENINGS.len()`
+        #[allow(clippy::cast_possible_truncation)]
+        let openings = self.openings as u32;
+        openings * 2
+    }
+
+    /// The config as it is played here: a time limit bounds every move, so the table size is
+    /// the only setting that could cost the server more than the owner's share.
+    fn sandboxed(&self, mut config: EngineConfig) -> EngineConfig {
+        config.tt_size_mb = self.tt_size_mb;
+        // A deterministic search ignores the time limit and runs to its full depth
+        config.deterministic = false;
+        config
+    }
+
+    /// Plays every game of `pairing` and returns the first entry's score in each.
+    fn play(&self, pairing: &Pairing) -> Vec<f64> {
+        let limit = SearchLimit::Time(self.move_ms);
+        let mut a = AlphaBetaEngine::new(Arc::clone(&pairing.config_a));
+        let mut b = AlphaBetaEngine::new(Arc::clone(&pairing.config_b));
+        let mut scores = Vec::new();
+        for (opening, line) in OPENINGS.iter().take(self.openings).enumerate() {
+            let Some(opening_moves) = parse_opening(line) else {
+                continue;
+            };
+            for a_is_red in [true, false] {
+                let (red, black) = if a_is_red {
+                    (&mut a, &mut b)
+                } else {
+                    (&mut b, &mut a)
+                };
+                let (moves, result) = play_game(red, black, &opening_moves, limit, self.max_plies);
+                let game = PlayedGame {
+                    opening,
+                    a_is_red,
+                    moves,
+                    result,
+                };
+                scores.push(game.score_a());
+            }
+        }
+        scores
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitError {
+    Disabled,
+    InvalidName,
+    NameTaken,
+    Full,
+    /// The player already has `ArenaConfig::max_per_player` entries.
+    TooMany,
+}
+
+/// Entry names are unique regardless of case.
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.to_lowercase()
+}
+
+struct Entry {
+    name: String,
+    /// Player id from the submitter's token, kept across reconnects; none for entries an
+    /// operator made.
+    owner: Option<String>,
+    config: Arc<EngineConfig>,
+    score: MatchScore,
+    pending: u32,
+}
+
+impl Entry {
+    /// Share of the points scored, `None` before the first game.
+    fn score_rate(&self) -> Option<f64> {
+        let games = self.score.games();
+        (games > 0).then(|| {
+            (f64::from(self.score.wins) + f64::from(self.score.draws) / 2.0) / f64::from(games)
+        })
+    }
+}
+
+/// Two entries due to play each other.
+struct Pairing {
+    a: String,
+    b: String,
+    config_a: Arc<EngineConfig>,
+    config_b: Arc<EngineConfig>,
+}
+
+/// Uploaded engine configs and their round-robin results. Every new entry is paired with
+/// each one already in the arena; pairings wait in a queue for the worker pool.
+pub struct Arena {
+    pub config: ArenaConfig,
+    entries: Mutex<Vec<Entry>>,
+    queue: mpsc::UnboundedSender<Pairing>,
+    pending: Mutex<Option<mpsc::UnboundedReceiver<Pairing>>>,
+}
+
+impl Arena {
+    pub fn new(config: ArenaConfig) -> Self {
+        let (queue, pending) = mpsc::unbounded_channel();
+        Self {
+            config,
+            entries: Mutex::new(Vec::new()),
+            queue,
+            pending: Mutex::new(Some(pending)),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(ArenaConfig::from_env())
+    }
+
+    fn entries(&self) -> MutexGuard<'_, Vec<Entry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Enters `config` under `name` for the player `owner` (none for an operator) and queues
+    /// its games against every other entry.
+    pub fn submit(
+        &self,
+        name: &str,
+        config: EngineConfig,
+        owner: Option<&str>,
+    ) -> Result<(), SubmitError> {
+        if self.config.move_ms == 0 {
+            return Err(SubmitError::Disabled);
+        }
+        let name = name.trim();
+        if name.is_empty()
+            || name.chars().count() > MAX_NAME_LEN
+            || name.chars().any(char::is_control)
+        {
+            return Err(SubmitError::InvalidName);
+        }
+        let mut entries = self.entries();
+        if entries.iter().any(|e| same_name(&e.name, name)) {
+            return Err(SubmitError::NameTaken);
+        }
+        if entries.len() >= self.config.max_entries {
+            return Err(SubmitError::Full);
+        }
+        if owner.is_some_and(|owner| {
+            entries
+                .iter()
+                .filter(|e| e.owner.as_deref() == Some(owner))
+                .count()
+                >= self.config.max_per_player
+        }) {
+            return Err(SubmitError::TooMany);
+        }
+
+        let config = Arc::new(self.config.sandboxed(config));
+        let games = self.config.games_per_pairing();
+        for other in entries.iter_mut() {
+            other.pending += games;
+            let _ = self.queue.send(Pairing {
+                a: name.to_string(),
+                b: other.name.clone(),
+                config_a: Arc::clone(&config),
+                config_b: Arc::clone(&other.config),
+            });
+        }
+        let pending = games.saturating_mul(u32::try_from(entries.len()).unwrap_or(u32::MAX));
+        entries.push(Entry {
+            name: name.to_string(),
+            owner: owner.map(str::to_string),
+            config,
+            score: MatchScore::default(),
+            pending,
+        });
+        tracing::info!(name = %name, entries = entries.len(), "Config entered into the arena");
+        Ok(())
+    }
+
+    /// Takes the entry out of the arena; its queued games are skipped.
+    pub fn remove(&self, name: &str) -> bool {
+        let mut entries = self.entries();
+        let before = entries.len();
+        entries.retain(|e| !same_name(&e.name, name));
+        entries.len() < before
+    }
+
+    /// Whether both entries of `pairing` are still in the arena. If not, the one that is no
+    /// longer waits for these games.
+    fn is_live(&self, pairing: &Pairing) -> bool {
+        let mut entries = self.entries();
+        let found = [&pairing.a, &pairing.b].map(|name| entries.iter().any(|e| &e.name == name));
+        if found == [true, true] {
+            return true;
+        }
+        let games = self.config.games_per_pairing();
+        for entry in entries
+            .iter_mut()
+            .filter(|e| e.name == pairing.a || e.name == pairing.b)
+        {
+            entry.pending = entry.pending.saturating_sub(games);
+        }
+        false
+    }
+
+    /// Records the games of a finished pairing; `scores` are from `a`'s point of view and
+    /// may be empty if the games could not be played.
+    fn record(&self, a: &str, b: &str, scores: &[f64]) {
+        let games = self.config.games_per_pairing();
+        let mut entries = self.entries();
+        for entry in entries.iter_mut() {
+            let flip = if entry.name == a {
+                false
+            } else if entry.name == b {
+                true
+            } else {
+                continue;
+            };
+            entry.pending = entry.pending.saturating_sub(games);
+            for &score in scores {
+                entry.score.record(if flip { 1.0 - score } else { score });
+            }
+        }
+    }
+
+    /// Entries by share of points scored, then by games played; entries without a game come
+    /// last.
+    pub fn standings(&self) -> Vec<ArenaStanding> {
+        let entries = self.entries();
+        let mut order: Vec<&Entry> = entries.iter().collect();
+        order.sort_by(|x, y| {
+            let rate = |e: &Entry| e.score_rate().unwrap_or(-1.0);
+            rate(y)
+                .partial_cmp(&rate(x))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| y.score.games().cmp(&x.score.games()))
+                .then_with(|| x.name.cmp(&y.name))
+        });
+        order
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| ArenaStanding {
+                rank: i + 1,
+                name: entry.name.clone(),
+                wins: entry.score.wins,
+                draws: entry.score.draws,
+                losses: entry.score.losses,
+                #[allow(clippy::cast_possible_truncation)]
+                elo: entry
+                    .score
+                    .elo()
+                    .map(|(elo, _)| elo)
+                    .filter(|elo| elo.is_finite())
+                    .map(|elo| elo.round() as i32),
+                pending: entry.pending,
+            })
+            .collect()
+    }
+}
+
+impl AppState {
+    /// Starts the job playing queued arena pairings, at most `ArenaConfig::workers` at once
+    /// and each off the async runtime.
+    pub fn spawn_arena_job(self: Arc<Self>) {
+        let Some(mut rx) = self
+            .arena
+            .pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.take())
+        else {
+            return;
+        };
+        let workers = Arc::new(Semaphore::new(self.arena.config.workers.max(1)));
+        tokio::spawn(async move {
+            while let Some(pairing) = rx.recv().await {
+                let Ok(permit) = Arc::clone(&workers).acquire_owned().await else {
+                    return;
+                };
+                if !self.arena.is_live(&pairing) {
+                    continue;
+                }
+                let state = Arc::clone(&self);
+                tokio::spawn(async move {
+                    let (a, b) = (pairing.a.clone(), pairing.b.clone());
+                    let config = state.arena.config;
+                    let played = tokio::task::spawn_blocking(move || config.play(&pairing)).await;
+                    drop(permit);
+                    let scores = played.unwrap_or_else(|e| {
+                        tracing::error!(error = %e, a = %a, b = %b, "Arena pairing failed");
+                        Vec::new()
+                    });
+                    tracing::debug!(a = %a, b = %b, score = scores.iter().sum::<f64>(), "Arena pairing played");
+                    state.arena.record(&a, &b, &scores);
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn arena(max_entries: usize) -> Arena {
+        Arena::new(ArenaConfig {
+            move_ms: 5,
+            openings: 1,
+            max_plies: 16,
+            workers: 2,
+            max_entries,
+            max_per_player: 2,
+            tt_size_mb: 1,
+        })
+    }
+
+    #[test]
+    fn test_submit_validates_name_and_capacity() {
+        let arena = arena(2);
+        assert_eq!(
+            arena.submit("  ", EngineConfig::default(), None),
+            Err(SubmitError::InvalidName)
+        );
+        assert_eq!(
+            arena.submit(&"x".repeat(MAX_NAME_LEN + 1), EngineConfig::default(), None),
+            Err(SubmitError::InvalidName)
+        );
+        assert_eq!(arena.submit("Alpha", EngineConfig::default(), None), Ok(()));
+        assert_eq!(
+            arena.submit("alpha", EngineConfig::default(), None),
+            Err(SubmitError::NameTaken)
+        );
+        assert_eq!(arena.submit("Beta", EngineConfig::default(), None), Ok(()));
+        assert_eq!(
+            arena.submit("Gamma", EngineConfig::default(), None),
+            Err(SubmitError::Full)
+        );
+
+        let standings = arena.standings();
+        assert_eq!(standings.len(), 2);
+        assert!(standings.iter().all(|s| s.pending == 2));
+    }
+
+    #[test]
+    fn test_players_are_limited_to_their_share() {
+        let arena = arena(8);
+        for name in ["A", "B"] {
+            assert_eq!(
+                arena.submit(name, EngineConfig::default(), Some("p1")),
+                Ok(())
+            );
+        }
+        assert_eq!(
+            arena.submit("C", EngineConfig::default(), Some("p1")),
+            Err(SubmitError::TooMany)
+        );
+        assert_eq!(
+            arena.submit("C", EngineConfig::default(), Some("p2")),
+            Ok(())
+        );
+        assert_eq!(arena.submit("D", EngineConfig::default(), None), Ok(()));
+
+        assert!(arena.remove("a"));
+        assert_eq!(
+            arena.submit("E", EngineConfig::default(), Some("p1")),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_submitted_config_is_sandboxed() {
+        let arena = arena(4);
+        let config = EngineConfig {
+            tt_size_mb: 256,
+            deterministic: true,
+            ..EngineConfig::default()
+        };
+        arena.submit("Greedy", config, None).unwrap();
+        let entries = arena.entries();
+        assert_eq!(entries[0].config.tt_size_mb, 1);
+        assert!(!entries[0].config.deterministic);
+    }
+
+    #[test]
+    fn test_removed_entry_games_are_skipped() {
+        let arena = arena(4);
+        arena.submit("A", EngineConfig::default(), None).unwrap();
+        arena.submit("B", EngineConfig::default(), None).unwrap();
+        assert!(arena.remove("a"));
+        let pairing = arena.pending.lock().unwrap().as_mut().unwrap().try_recv();
+        assert!(!arena.is_live(&pairing.unwrap()));
+        assert_eq!(arena.standings()[0].pending, 0);
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_fills_the_table() {
+        let mut state = AppState::new();
+        state.arena = arena(4);
+        let state = Arc::new(state);
+        Arc::clone(&state).spawn_arena_job();
+        for name in ["A", "B", "C"] {
+            state
+                .arena
+                .submit(name, EngineConfig::default(), None)
+                .unwrap();
+        }
+
+        let standings = tokio::time::timeout(Duration::from_secs(60), async {
+            loop {
+                let standings = state.arena.standings();
+                if standings.iter().all(|s| s.pending == 0) {
+                    return standings;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+        // Three pairings of two games each
+        for standing in &standings {
+            assert_eq!(standing.wins + standing.draws + standing.losses, 4);
+        }
+        let wins: u32 = standings.iter().map(|s| s.wins).sum();
+        let losses: u32 = standings.iter().map(|s| s.losses).sum();
+        assert_eq!(wins, losses);
+        assert_eq!(
+            standings.iter().map(|s| s.rank).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+    }
+}
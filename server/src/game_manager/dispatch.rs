@@ -0,0 +1,99 @@
+use crate::game_manager::AppState;
+use crate::storage::Envelope;
+use cotuong_core::logic::handicap::HandicapKind;
+use shared::GameMessage;
+use std::sync::Arc;
+
+impl AppState {
+    /// Routes a player's message to the handler for it.
+    ///
+    /// Game-scoped messages for a game owned by another instance are forwarded there.
+    pub async fn dispatch(&self, player_id: &str, msg: GameMessage) {
+        let is_game_scoped = matches!(
+            msg,
+            GameMessage::MakeMove { .. }
+                | GameMessage::VerifyMove { .. }
+                | GameMessage::RequestMoves { .. }
+                | GameMessage::Surrender
+                | GameMessage::PlayAgain
+                | GameMessage::PlayerLeft
+        );
+        if is_game_scoped && !self.is_playing(player_id) {
+            if let Ok(Some(owner)) = self.store.game_owner(player_id).await {
+                if owner != self.store.instance_id() {
+                    tracing::debug!(player_id = %player_id, owner = %owner, "Forwarding message to owning instance");
+                    self.store.forward(&owner, player_id, msg);
+                    return;
+                }
+            }
+        }
+
+        let player_id = player_id.to_string();
+        match msg {
+            GameMessage::FindMatch => self.find_match(player_id, HandicapKind::None).await,
+            GameMessage::FindHandicapMatch(handicap) => self.find_match(player_id, handicap).await,
+            GameMessage::MakeMove { move_data, fen } => {
+                self.handle_move(player_id, move_data, fen).await;
+            }
+            GameMessage::VerifyMove { fen, is_valid } => {
+                self.handle_verify_move(player_id, fen, is_valid).await;
+            }
+            GameMessage::RequestMoves { from_seq } => {
+                self.handle_request_moves(player_id, from_seq).await;
+            }
+            GameMessage::CancelFindMatch => self.cancel_find_match(&player_id).await,
+            GameMessage::Surrender => self.handle_surrender(player_id).await,
+            GameMessage::PlayAgain => self.handle_play_again(player_id).await,
+            GameMessage::PlayerLeft => self.handle_player_left(player_id).await,
+            GameMessage::SetName(name) => self.set_player_name(&player_id, &name).await,
+            GameMessage::Ping(nonce) => self.handle_ping(&player_id, nonce),
+            GameMessage::SubscribeLiveGames => self.subscribe_live_games(&player_id).await,
+            GameMessage::UnsubscribeLiveGames => self.unsubscribe_live_games(&player_id),
+            GameMessage::Spectate(game_id) => self.spectate(&player_id, &game_id).await,
+            GameMessage::StopSpectating => self.stop_spectating(&player_id).await,
+            GameMessage::QueryEval(hash) => self.query_eval(&player_id, hash).await,
+            GameMessage::SubmitEval(eval) => self.submit_eval(&player_id, eval).await,
+            GameMessage::AddFriend(name) => self.set_friend(&player_id, &name, true).await,
+            GameMessage::RemoveFriend(name) => self.set_friend(&player_id, &name, false).await,
+            GameMessage::ListFriends => self.send_friends(&player_id).await,
+            GameMessage::ChallengeFriend {
+                friend_id,
+                time_control,
+                disconnect_policy,
+            } => {
+                self.challenge_friend(&player_id, &friend_id, time_control, disconnect_policy)
+                    .await;
+            }
+            GameMessage::AcceptChallenge(id) => self.accept_challenge(&player_id, &id).await,
+            GameMessage::DeclineChallenge(id) => self.decline_challenge(&player_id, &id),
+            _ => {}
+        }
+    }
+
+    /// Consumes envelopes sent to this instance by other instances.
+    pub async fn spawn_relay(self: Arc<Self>) {
+        let mut rx = match self.store.subscribe().await {
+            Ok(Some(rx)) => rx,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to subscribe to instance channel");
+                return;
+            }
+        };
+
+        tokio::spawn(async move {
+            while let Some(envelope) = rx.recv().await {
+                match envelope {
+                    Envelope::Deliver { player_id, msg } => {
+                        if let Some(player) = self.players.get(&player_id) {
+                            let _ = player.tx.send(msg);
+                        }
+                    }
+                    Envelope::Forward { player_id, msg } => {
+                        self.dispatch(&player_id, msg).await;
+                    }
+                }
+            }
+        });
+    }
+}
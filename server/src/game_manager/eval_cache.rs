@@ -0,0 +1,192 @@
+use crate::game_manager::anticheat::env_or;
+use crate::game_manager::AppState;
+use shared::{CloudEval, ServerMessage};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Deepest search a client may claim; anything beyond is certainly not a real result.
+pub const MAX_EVAL_DEPTH: u8 = 64;
+/// Largest score a client may submit, just above the engine's mate score.
+pub const MAX_EVAL_SCORE: i32 = 30_000;
+/// Largest score difference between two submissions that still agree.
+pub const EVAL_AGREEMENT_CP: i32 = 50;
+
+const DEFAULT_CAPACITY: usize = 100_000;
+const DEFAULT_QUORUM: usize = 2;
+
+struct Lru {
+    /// Evaluation and the tick it was last used at.
+    entries: HashMap<u64, (CloudEval, u64)>,
+    /// Tick to hash, oldest first.
+    order: BTreeMap<u64, u64>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, hash: u64) -> Option<CloudEval> {
+        self.tick += 1;
+        let (eval, used) = self.entries.get_mut(&hash)?;
+        self.order.remove(used);
+        *used = self.tick;
+        self.order.insert(self.tick, hash);
+        Some(*eval)
+    }
+}
+
+/// Client submissions still waiting for agreement, with who sent them.
+#[derive(Default)]
+struct Pending {
+    votes: HashMap<u64, Vec<(String, CloudEval)>>,
+    /// Positions in the order they were first submitted, oldest first.
+    order: VecDeque<u64>,
+}
+
+const fn agrees(a: &CloudEval, b: &CloudEval) -> bool {
+    a.score.abs_diff(b.score) <= EVAL_AGREEMENT_CP.unsigned_abs()
+}
+
+/// Bounded least-recently-used cache of shared evaluations, in front of the storage so
+/// common positions are answered without a round trip.
+///
+/// The server cannot check an evaluation against its hash, so a client's submission is only
+/// shared once `quorum` different players have sent one that agrees with it.
+pub struct EvalCache {
+    capacity: usize,
+    quorum: usize,
+    lru: Mutex<Lru>,
+    pending: Mutex<Pending>,
+}
+
+impl EvalCache {
+    pub fn new(capacity: usize, quorum: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            quorum: quorum.max(1),
+            lru: Mutex::new(Lru {
+                entries: HashMap::new(),
+                order: BTreeMap::new(),
+                tick: 0,
+            }),
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    /// Reads `EVAL_CACHE_SIZE` (number of positions) and `EVAL_QUORUM` (players who must
+    /// agree on an evaluation), falling back to the defaults.
+    pub fn from_env() -> Self {
+        Self::new(
+            env_or("EVAL_CACHE_SIZE", DEFAULT_CAPACITY),
+            env_or("EVAL_QUORUM", DEFAULT_QUORUM),
+        )
+    }
+
+    pub fn get(&self, hash: u64) -> Option<CloudEval> {
+        self.lru.lock().ok()?.touch(hash)
+    }
+
+    /// Stores `eval` unless a deeper one is already cached; returns whether it was kept.
+    pub fn insert(&self, eval: CloudEval) -> bool {
+        let Ok(mut lru) = self.lru.lock() else {
+            return false;
+        };
+        if let Some(existing) = lru.touch(eval.hash) {
+            if existing.depth > eval.depth {
+                return false;
+            }
+        } else if lru.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = lru.order.pop_first() {
+                lru.entries.remove(&oldest);
+            }
+        }
+        lru.tick += 1;
+        let tick = lru.tick;
+        if let Some((_, used)) = lru.entries.insert(eval.hash, (eval, tick)) {
+            lru.order.remove(&used);
+        }
+        lru.order.insert(tick, eval.hash);
+        true
+    }
+
+    /// Records `submitter`'s evaluation of a position, replacing their earlier one. Once
+    /// enough players agree with it, returns the shallowest of the agreeing evaluations and
+    /// forgets the position's submissions.
+    pub fn vote(&self, submitter: &str, eval: CloudEval) -> Option<CloudEval> {
+        let mut guard = self.pending.lock().ok()?;
+        let pending = &mut *guard;
+        if !pending.votes.contains_key(&eval.hash) {
+            if pending.votes.len() >= self.capacity {
+                if let Some(oldest) = pending.order.pop_front() {
+                    pending.votes.remove(&oldest);
+                }
+            }
+            pending.order.push_back(eval.hash);
+        }
+        let votes = pending.votes.entry(eval.hash).or_default();
+        votes.retain(|(who, _)| who != submitter);
+        votes.push((submitter.to_string(), eval));
+
+        let agreeing: Vec<CloudEval> = votes
+            .iter()
+            .map(|(_, vote)| *vote)
+            .filter(|vote| vote.best_move == eval.best_move && agrees(vote, &eval))
+            .collect();
+        if agreeing.len() < self.quorum {
+            return None;
+        }
+        pending.votes.remove(&eval.hash);
+        pending.order.retain(|hash| *hash != eval.hash);
+        agreeing.into_iter().min_by_key(|vote| vote.depth)
+    }
+
+    pub fn entry_count(&self) -> usize {
+        self.lru.lock().map_or(0, |lru| lru.entries.len())
+    }
+}
+
+impl AppState {
+    /// The shared evaluation of a position: the local cache first, then the storage.
+    pub async fn lookup_eval(&self, hash: u64) -> Option<CloudEval> {
+        if let Some(eval) = self.eval_cache.get(hash) {
+            return Some(eval);
+        }
+        match self.store.load_eval(hash).await {
+            Ok(Some(eval)) => {
+                self.eval_cache.insert(eval);
+                Some(eval)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to load shared evaluation");
+                None
+            }
+        }
+    }
+
+    pub async fn query_eval(&self, player_id: &str, hash: u64) {
+        let eval = self.lookup_eval(hash).await;
+        self.send_to(player_id, ServerMessage::CloudEval { hash, eval });
+    }
+
+    /// Counts a client's evaluation if it is plausible and deeper than what is known, and
+    /// shares it once enough players agree.
+    pub async fn submit_eval(&self, player_id: &str, eval: CloudEval) {
+        if eval.depth == 0 || eval.depth > MAX_EVAL_DEPTH || eval.score.abs() > MAX_EVAL_SCORE {
+            tracing::debug!(player_id = %player_id, ?eval, "Rejected implausible evaluation");
+            return;
+        }
+        if self
+            .lookup_eval(eval.hash)
+            .await
+            .is_some_and(|known| known.depth > eval.depth)
+        {
+            return;
+        }
+        let Some(eval) = self.eval_cache.vote(player_id, eval) else {
+            return;
+        };
+        self.eval_cache.insert(eval);
+        if let Err(e) = self.store.save_eval(&eval).await {
+            tracing::warn!(error = %e, "Failed to save shared evaluation");
+        }
+    }
+}
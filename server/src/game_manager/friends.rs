@@ -0,0 +1,274 @@
+use crate::game_manager::lifecycle::{default_name, name_key, MAX_NAME_LEN};
+use crate::game_manager::AppState;
+use cotuong_core::logic::handicap::HandicapKind;
+use shared::{DisconnectPolicy, ErrorCode, FriendStatus, Presence, ServerMessage};
+
+const MAX_TIME_CONTROL_LEN: usize = 20;
+
+/// A challenge waiting for its answer, between two players connected here.
+#[derive(Debug, Clone)]
+pub struct Challenge {
+    pub from: String,
+    pub to: String,
+    pub time_control: Option<String>,
+    pub disconnect_policy: DisconnectPolicy,
+}
+
+impl AppState {
+    fn player_name(&self, player_id: &str) -> Option<String> {
+        self.players.get(player_id).map(|p| p.name.clone())
+    }
+
+    /// The name a player goes by: the one they use here, else the one on their rating.
+    fn display_name(&self, player_id: &str) -> String {
+        self.player_name(player_id)
+            .or_else(|| self.ratings.get(player_id).map(|r| r.name.clone()))
+            .unwrap_or_else(|| default_name(player_id))
+    }
+
+    /// The player going by `name`: connected here, rated, or already among `friends`.
+    fn player_by_name(&self, name: &str, friends: &[String]) -> Option<String> {
+        let key = name_key(name);
+        self.players
+            .iter()
+            .find(|player| name_key(&player.name) == key)
+            .map(|player| player.key().clone())
+            .or_else(|| {
+                self.ratings
+                    .iter()
+                    .find(|rating| name_key(&rating.name) == key)
+                    .map(|rating| rating.key().clone())
+            })
+            .or_else(|| {
+                friends
+                    .iter()
+                    .find(|id| name_key(&self.display_name(id)) == key)
+                    .cloned()
+            })
+    }
+
+    /// Presence only covers this instance: friends connected elsewhere look offline.
+    fn presence(&self, player_id: &str) -> Presence {
+        if !self.players.contains_key(player_id) {
+            Presence::Offline
+        } else if self.is_playing(player_id) {
+            Presence::Playing
+        } else {
+            Presence::Online
+        }
+    }
+
+    /// The player ids on the player's friend list; on a storage failure the player is told.
+    async fn friend_ids(&self, player_id: &str) -> Option<Vec<String>> {
+        match self.store.friends(player_id).await {
+            Ok(ids) => Some(ids),
+            Err(e) => {
+                tracing::error!(player_id = %player_id, error = %e, "Failed to load friends");
+                self.send_error(player_id, ErrorCode::Internal, None);
+                None
+            }
+        }
+    }
+
+    /// Sends the player's friend list, online friends first.
+    pub async fn send_friends(&self, player_id: &str) {
+        if !self.players.contains_key(player_id) {
+            return;
+        }
+        let Some(ids) = self.friend_ids(player_id).await else {
+            return;
+        };
+        let mut friends: Vec<FriendStatus> = ids
+            .iter()
+            .map(|id| FriendStatus {
+                presence: self.presence(id),
+                name: self.display_name(id),
+            })
+            .collect();
+        friends.sort_by(|a, b| {
+            (a.presence == Presence::Offline)
+                .cmp(&(b.presence == Presence::Offline))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        self.send_to(player_id, ServerMessage::Friends(friends));
+    }
+
+    /// Adds the player going by `name` to, or removes them from, the player's friend list and
+    /// sends the new list. Lists hold player ids, so they follow friends who rename.
+    pub async fn set_friend(&self, player_id: &str, name: &str, friend: bool) {
+        if !self.players.contains_key(player_id) {
+            return;
+        }
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            self.send_error(player_id, ErrorCode::InvalidName, None);
+            return;
+        }
+        let Some(friends) = self.friend_ids(player_id).await else {
+            return;
+        };
+        let Some(friend_id) = self.player_by_name(name, &friends) else {
+            self.send_error(player_id, ErrorCode::PlayerNotFound, None);
+            return;
+        };
+        if friend_id == player_id {
+            self.send_error(player_id, ErrorCode::InvalidName, None);
+            return;
+        }
+        if let Err(e) = self.store.set_friend(player_id, &friend_id, friend).await {
+            tracing::error!(player_id = %player_id, error = %e, "Failed to update friends");
+            self.send_error(player_id, ErrorCode::Internal, None);
+            return;
+        }
+        tracing::info!(player_id = %player_id, friend = %friend_id, added = friend, "Friend list updated");
+        self.send_friends(player_id).await;
+    }
+
+    /// Challenges a friend who is online and not playing.
+    pub async fn challenge_friend(
+        &self,
+        player_id: &str,
+        friend: &str,
+        time_control: Option<String>,
+        disconnect_policy: DisconnectPolicy,
+    ) {
+        let Some(own_name) = self.player_name(player_id) else {
+            return;
+        };
+        if self.is_playing(player_id) {
+            tracing::warn!(player_id = %player_id, "Player already in game, ignoring challenge");
+            return;
+        }
+        let time_control = time_control
+            .map(|label| label.trim().chars().take(MAX_TIME_CONTROL_LEN).collect())
+            .filter(|label: &String| !label.is_empty());
+        let disconnect_policy = disconnect_policy.clamped();
+
+        let Some(friends) = self.friend_ids(player_id).await else {
+            return;
+        };
+        let target = self.player_by_name(friend.trim(), &friends).filter(|id| {
+            friends.contains(id)
+                && id != player_id
+                && self.players.contains_key(id)
+                && !self.is_playing(id)
+        });
+        let Some(target) = target else {
+            self.send_error(player_id, ErrorCode::PlayerUnavailable, None);
+            return;
+        };
+
+        let challenge_id = uuid::Uuid::new_v4().to_string();
+        tracing::info!(challenge_id = %challenge_id, from = %player_id, to = %target, "Friend challenged");
+        self.challenges.insert(
+            challenge_id.clone(),
+            Challenge {
+                from: player_id.to_string(),
+                to: target.clone(),
+                time_control: time_control.clone(),
+                disconnect_policy,
+            },
+        );
+        self.send_to(
+            player_id,
+            ServerMessage::ChallengeSent {
+                challenge_id: challenge_id.clone(),
+                to: self.display_name(&target),
+            },
+        );
+        self.send_to(
+            &target,
+            ServerMessage::ChallengeReceived {
+                challenge_id,
+                from: own_name,
+                time_control,
+                disconnect_policy,
+            },
+        );
+    }
+
+    /// Starts the game of a challenge the player received, taking both players out of the
+    /// matchmaking queue.
+    pub async fn accept_challenge(&self, player_id: &str, challenge_id: &str) {
+        let Some((_, challenge)) = self
+            .challenges
+            .remove_if(challenge_id, |_, c| c.to == player_id)
+        else {
+            self.send_error(player_id, ErrorCode::ChallengeNotFound, None);
+            return;
+        };
+        if !self.players.contains_key(&challenge.from)
+            || self.is_playing(&challenge.from)
+            || self.is_playing(player_id)
+        {
+            self.send_error(player_id, ErrorCode::ChallengeNotFound, None);
+            self.send_to(
+                &challenge.from,
+                ServerMessage::ChallengeClosed {
+                    challenge_id: challenge_id.to_string(),
+                },
+            );
+            return;
+        }
+
+        for id in [&challenge.from, &challenge.to] {
+            if let Err(e) = self.store.dequeue(id).await {
+                tracing::warn!(player_id = %id, error = %e, "Failed to leave matchmaking queue");
+            }
+        }
+        tracing::info!(challenge_id = %challenge_id, "Challenge accepted, starting game");
+        self.start_game(
+            challenge.from,
+            challenge.to,
+            HandicapKind::None,
+            challenge.time_control,
+            challenge.disconnect_policy,
+        )
+        .await;
+    }
+
+    /// Declines a received challenge or withdraws a sent one, telling the other player.
+    pub fn decline_challenge(&self, player_id: &str, challenge_id: &str) {
+        let Some((_, challenge)) = self.challenges.remove_if(challenge_id, |_, c| {
+            c.to == player_id || c.from == player_id
+        }) else {
+            return;
+        };
+        let other = if challenge.to == player_id {
+            &challenge.from
+        } else {
+            &challenge.to
+        };
+        self.send_to(
+            other,
+            ServerMessage::ChallengeClosed {
+                challenge_id: challenge_id.to_string(),
+            },
+        );
+    }
+
+    /// Drops every challenge the player sent or received, telling the other players.
+    pub fn close_challenges(&self, player_id: &str) {
+        let ids: Vec<String> = self
+            .challenges
+            .iter()
+            .filter(|c| c.from == player_id || c.to == player_id)
+            .map(|c| c.key().clone())
+            .collect();
+        for challenge_id in ids {
+            let Some((_, challenge)) = self.challenges.remove(&challenge_id) else {
+                continue;
+            };
+            for id in [&challenge.from, &challenge.to] {
+                if id != player_id {
+                    self.send_to(
+                        id,
+                        ServerMessage::ChallengeClosed {
+                            challenge_id: challenge_id.clone(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
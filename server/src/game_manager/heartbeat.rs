@@ -0,0 +1,67 @@
+use crate::game_manager::AppState;
+use shared::ServerMessage;
+use std::time::{Duration, Instant};
+
+/// How long a player may stay silent before their connection is considered dead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatConfig {
+    pub timeout: Duration,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// Reads `HEARTBEAT_TIMEOUT_SECS`, falling back to the default.
+    pub fn from_env() -> Self {
+        std::env::var("HEARTBEAT_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .map_or_else(Self::default, |secs| Self {
+                timeout: Duration::from_secs(secs),
+            })
+    }
+
+    /// How often the cleanup task looks for stale players, so a dead socket is
+    /// reaped at most 1.5x `timeout` after its last message.
+    pub fn sweep_interval(&self) -> Duration {
+        (self.timeout / 2).max(Duration::from_secs(1))
+    }
+}
+
+impl AppState {
+    /// Records that `player_id` was heard from just now.
+    pub fn touch(&self, player_id: &str, now: Instant) {
+        if let Some(mut player) = self.players.get_mut(player_id) {
+            player.last_seen = now;
+        }
+    }
+
+    pub fn handle_ping(&self, player_id: &str, nonce: u64) {
+        self.send_to(player_id, ServerMessage::Pong(nonce));
+    }
+
+    /// Removes every player that has been silent for longer than the heartbeat timeout.
+    ///
+    /// Dropping the player's sender ends their socket task; their game ends as a disconnect.
+    pub async fn reap_stale_players(&self, now: Instant) -> Vec<String> {
+        let stale: Vec<String> = self
+            .players
+            .iter()
+            .filter(|entry| now.saturating_duration_since(entry.last_seen) > self.heartbeat.timeout)
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for player_id in &stale {
+            tracing::info!(player_id = %player_id, "Reaping player with no heartbeat");
+            self.remove_player(player_id).await;
+        }
+        stale
+    }
+}
@@ -1,103 +1,342 @@
-use crate::game_manager::{session::Player, AppState};
-use cotuong_core::logic::board::{Board, Color};
-use shared::ServerMessage;
+use crate::game_manager::{
+    rate_limit::RateLimiter,
+    session::{now_ms, Player},
+    AppState, GameSession,
+};
+use cotuong_core::engine::config::EngineConfig;
+use cotuong_core::engine::search::AlphaBetaEngine;
+use cotuong_core::engine::{SearchLimit, Searcher};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::game::GameState;
+use cotuong_core::session::{EndReason, Session, SessionResult};
+use shared::{AbortReason, DisconnectPolicy, ErrorCode, GameMessage, ServerMessage};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing; // Added tracing import
 
+pub const MAX_NAME_LEN: usize = 20;
+const DEFAULT_FIRST_MOVE_TIMEOUT: Duration = Duration::from_secs(60);
+/// Search depth for judging a game under `DisconnectPolicy::Adjudicate`.
+const ADJUDICATION_DEPTH: u8 = 5;
+/// Centipawns the side ahead needs to win an adjudicated game; anything closer is a draw.
+const ADJUDICATION_MARGIN: i32 = 300;
+const ADJUDICATION_TT_MB: usize = 16;
+
+/// Names are compared case-insensitively, for uniqueness and bans alike.
+pub fn name_key(name: &str) -> String {
+    name.to_lowercase()
+}
+
+/// The name a player goes by until they pick one.
+pub fn default_name(player_id: &str) -> String {
+    format!("Player-{}", player_id.chars().take(8).collect::<String>())
+}
+
+/// Reads `FIRST_MOVE_TIMEOUT_SECS`: how long a game may wait for its first move before it
+/// is aborted.
+pub fn first_move_timeout_from_env() -> Duration {
+    std::env::var("FIRST_MOVE_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map_or(DEFAULT_FIRST_MOVE_TIMEOUT, Duration::from_secs)
+}
+
 impl AppState {
     pub fn add_player(&self, id: String, tx: crate::game_manager::Tx) {
-        use std::time::Instant;
         tracing::info!(player_id = %id, "Player added to AppState");
+        let name = default_name(&id);
+        let now = Instant::now();
         self.players.insert(
             id,
             Player {
                 tx,
-                last_msg_at: Instant::now(),
+                name,
+                rate_limiter: RateLimiter::new(&self.rate_limit_config, now),
+                last_seen: now,
             },
         );
     }
 
+    pub async fn set_player_name(&self, player_id: &str, name: &str) {
+        let name = name.trim();
+        if !self.players.contains_key(player_id) {
+            return;
+        }
+
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            self.send_error(
+                player_id,
+                ErrorCode::InvalidName,
+                Some(format!(
+                    "Name must be between 1 and {MAX_NAME_LEN} characters"
+                )),
+            );
+            return;
+        }
+        if self.is_name_banned(name).await {
+            self.kick_banned(player_id).await;
+            return;
+        }
+        if self.name_taken(player_id, name) {
+            self.send_error(player_id, ErrorCode::NameTaken, None);
+            return;
+        }
+
+        tracing::info!(player_id = %player_id, name = %name, "Player set display name");
+        if let Some(mut player) = self.players.get_mut(player_id) {
+            player.name = name.to_string();
+        }
+        if let Some(mut rating) = self.ratings.get_mut(player_id) {
+            rating.name = name.to_string();
+        }
+    }
+
+    /// Whether someone else goes by `name`: a player connected here, or one with a rating.
+    fn name_taken(&self, player_id: &str, name: &str) -> bool {
+        let key = name_key(name);
+        self.players
+            .iter()
+            .any(|player| player.key() != player_id && name_key(&player.name) == key)
+            || self
+                .ratings
+                .iter()
+                .any(|rating| rating.key() != player_id && name_key(&rating.name) == key)
+    }
+
     pub async fn remove_player(&self, id: &str) {
         tracing::info!(player_id = %id, "Removing player from AppState");
         self.players.remove(id);
+        self.unsubscribe_live_games(id);
+        self.stop_spectating(id).await;
+        self.close_challenges(id);
 
-        {
-            let mut queue = self.matchmaking_queue.lock().await;
-            if queue.remove(id) {
-                tracing::info!(player_id = %id, "Player removed from matchmaking queue");
+        if let Err(e) = self.store.unregister_player(id).await {
+            tracing::warn!(player_id = %id, error = %e, "Failed to unregister player");
+        }
+        if let Ok(true) = self.store.dequeue(id).await {
+            tracing::info!(player_id = %id, "Player removed from matchmaking queue");
+        }
+
+        // The player's game lives on another instance: let its owner clean up
+        if !self.is_playing(id) {
+            if let Ok(Some(owner)) = self.store.game_owner(id).await {
+                if owner != self.store.instance_id() {
+                    self.store.forward(&owner, id, GameMessage::PlayerLeft);
+                    return;
+                }
             }
         }
 
-        if let Some((_, game_id)) = self.player_to_game.remove(id) {
+        if self.hold_seat(id).await {
+            return;
+        }
+        if let Some(game_id) = self.leave_seat(id) {
             tracing::info!(player_id = %id, game_id = %game_id, "Player was in a game, cleaning up session");
-            if let Some((_, game_lock)) = self.games.remove(&game_id) {
-                let game = game_lock.read().await;
-                let opponent_id = if game.red_player == id {
-                    game.black_player.clone()
-                } else {
-                    game.red_player.clone()
-                };
+            self.end_game_on_disconnect(id, &game_id).await;
+        }
+    }
 
-                let winner = if game.red_player == id {
-                    Color::Black
-                } else {
-                    Color::Red
-                };
+    /// Keeps the seat of a player who dropped out of a game played under
+    /// `DisconnectPolicy::Reconnect` and tells the opponent how long the game waits.
+    async fn hold_seat(&self, id: &str) -> bool {
+        let Some(game_id) = self.game_of(id) else {
+            return false;
+        };
+        let Some(game_lock) = self.games.get(&game_id) else {
+            return false;
+        };
+        let mut game = game_lock.write().await;
+        let DisconnectPolicy::Reconnect { minutes } = game.disconnect_policy else {
+            return false;
+        };
+        // Once both players are gone there is nobody left to wait for
+        if game.session.is_over() || game.away.is_some() {
+            return false;
+        }
+        let window = Duration::from_secs(u64::from(minutes) * 60);
+        game.away = Some((id.to_string(), Instant::now() + window));
+        let opponent_id = if game.red_player == id {
+            game.black_player.clone()
+        } else {
+            game.red_player.clone()
+        };
+        drop(game);
 
-                tracing::info!(game_id = %game_id, disconnected_player = %id, opponent_id = %opponent_id, "Notifying opponent of disconnection");
-                drop(game);
+        tracing::info!(player_id = %id, game_id = %game_id, minutes, "Holding seat for reconnect");
+        self.send_to(
+            &opponent_id,
+            ServerMessage::OpponentAway {
+                seconds_left: window.as_secs(),
+            },
+        );
+        true
+    }
+
+    /// Ends and closes the game `id` dropped out of, under the game's disconnect policy.
+    async fn end_game_on_disconnect(&self, id: &str, game_id: &str) {
+        let Some((_, game_lock)) = self.games.remove(game_id) else {
+            return;
+        };
+        let mut game = game_lock.into_inner();
+        let (color, opponent_id) = if game.red_player == id {
+            (Color::Red, game.black_player.clone())
+        } else {
+            (Color::Black, game.red_player.clone())
+        };
+
+        let result = if game.session.is_over() {
+            None
+        } else if game.disconnect_policy == DisconnectPolicy::Adjudicate {
+            let winner = adjudicate(game.session.state.clone()).await;
+            game.session.adjudicate(winner).ok()
+        } else {
+            game.session.abandon(color).ok()
+        };
+        if let Some(result) = result {
+            self.record_result(game_id, &game, result.winner);
+        }
+        let end = |result: SessionResult| ServerMessage::GameEnd {
+            winner: result.winner,
+            reason: result.reason.as_str().to_string(),
+        };
+        self.release_spectators(game_id, &game, result.map(end).as_ref());
+        drop(game);
+
+        tracing::info!(game_id = %game_id, disconnected_player = %id, opponent_id = %opponent_id, "Notifying opponent of disconnection");
+        let opponent_end = match result {
+            Some(result) if result.reason == EndReason::Adjudication => end(result),
+            _ => ServerMessage::GameEnd {
+                winner: Some(color.opposite()),
+                reason: "Opponent Disconnected".to_string(),
+            },
+        };
+        self.send_to(&opponent_id, ServerMessage::OpponentDisconnected);
+        self.send_to(&opponent_id, opponent_end);
+        self.remove_role(&opponent_id, game_id);
+        self.clear_game_owner([id, &opponent_id]).await;
+        self.broadcast_live_games().await;
+    }
 
-                if let Some(player) = self.players.get(&opponent_id) {
-                    let _ = player.tx.send(ServerMessage::OpponentDisconnected);
-                    let _ = player.tx.send(ServerMessage::GameEnd {
-                        winner: Some(winner),
-                        reason: "Opponent Disconnected".to_string(),
-                    });
+    /// Ends the games whose absent player did not come back within the reconnect window.
+    pub async fn expire_held_seats(&self, now: Instant) -> Vec<String> {
+        let mut expired = Vec::new();
+        for entry in self.games.iter() {
+            let game = entry.value().read().await;
+            if let Some((id, deadline)) = &game.away {
+                if now >= *deadline {
+                    expired.push((entry.key().clone(), id.clone()));
                 }
-                self.player_to_game.remove(&opponent_id);
             }
         }
+
+        for (game_id, id) in &expired {
+            tracing::info!(game_id = %game_id, player_id = %id, "Reconnect window expired");
+            self.leave_seat(id);
+            self.end_game_on_disconnect(id, game_id).await;
+        }
+        expired.into_iter().map(|(game_id, _)| game_id).collect()
+    }
+
+    /// The player id a returning client takes back: the one its token was issued for, unless
+    /// that player is still connected. Ratings and friends stay with this id.
+    pub fn returning_player(&self, token: &str) -> Option<String> {
+        let id = self.signer.verify(token)?;
+        (!self.players.contains_key(id)).then(|| id.to_string())
+    }
+
+    /// The returning player, if they also still hold a seat in an unfinished game.
+    pub async fn resumable_player(&self, token: &str) -> Option<String> {
+        let id = self.returning_player(token)?;
+        let game_id = self.game_of(&id)?;
+        let game_lock = self.games.get(&game_id)?;
+        let ongoing = !game_lock.read().await.session.is_over();
+        ongoing.then_some(id)
+    }
+
+    /// Seats a player accepted by `resumable_player` back in their game and brings their
+    /// board up to date, including a move still waiting for their verification.
+    pub async fn resume_game(&self, id: &str) {
+        let Some(game_id) = self.game_of(id) else {
+            return;
+        };
+        let Some(game_lock) = self.games.get(&game_id) else {
+            return;
+        };
+        let mut game = game_lock.write().await;
+        if game.away.as_ref().is_some_and(|(away, _)| away == id) {
+            game.away = None;
+        }
+        let (opponent_id, your_color, score) = if game.red_player == id {
+            (game.black_player.clone(), Color::Red, game.score)
+        } else {
+            (game.red_player.clone(), Color::Black, game.score.flipped())
+        };
+        let messages = [
+            ServerMessage::MatchFound {
+                opponent_id: opponent_id.clone(),
+                your_color,
+                game_id: game_id.clone(),
+                score,
+            },
+            ServerMessage::GameStart(Box::new(game.session.board().clone())),
+            game.correction(),
+        ];
+        let pending = game
+            .pending_move
+            .as_ref()
+            .filter(|(mover_id, _, _)| mover_id != id)
+            .map(|(_, mv, fen)| ServerMessage::OpponentMove {
+                move_data: *mv,
+                fen: fen.clone(),
+                seq: game.session.move_count(),
+            });
+        drop(game);
+
+        tracing::info!(player_id = %id, game_id = %game_id, "Player reconnected to their game");
+        for msg in messages.into_iter().chain(pending) {
+            self.send_to(id, msg);
+        }
+        self.send_to(&opponent_id, ServerMessage::OpponentReturned);
     }
 
     pub async fn handle_surrender(&self, player_id: String) {
-        if let Some(game_id) = self.player_to_game.get(&player_id) {
-            let game_id = game_id.value().clone();
+        if let Some(game_id) = self.game_of(&player_id) {
             tracing::info!(player_id = %player_id, game_id = %game_id, "Player surrendered");
             if let Some(game_lock) = self.games.get(&game_id) {
                 let mut game = game_lock.write().await;
-                if game.game_ended {
+                let color = if game.red_player == player_id {
+                    Color::Red
+                } else {
+                    Color::Black
+                };
+                let Ok(result) = game.session.resign(color) else {
                     tracing::debug!(game_id = %game_id, "Surrender ignored: game already ended");
                     return;
-                }
-
-                game.game_ended = true;
-                let is_red = game.red_player == player_id;
-                let winner = if is_red { Color::Black } else { Color::Red };
+                };
+                let winner = result.winner;
 
                 let red_id = game.red_player.clone();
                 let black_id = game.black_player.clone();
 
+                self.record_result(&game_id, &game, winner);
+                let end = ServerMessage::GameEnd {
+                    winner,
+                    reason: result.reason.as_str().to_string(),
+                };
+                self.send_to_spectators(&game, &end);
                 drop(game);
 
                 tracing::info!(game_id = %game_id, winner = ?winner, "Game ended by surrender");
-                if let Some(p) = self.players.get(&red_id) {
-                    let _ = p.tx.send(ServerMessage::GameEnd {
-                        winner: Some(winner),
-                        reason: "Surrender".to_string(),
-                    });
-                }
-                if let Some(p) = self.players.get(&black_id) {
-                    let _ = p.tx.send(ServerMessage::GameEnd {
-                        winner: Some(winner),
-                        reason: "Surrender".to_string(),
-                    });
-                }
+                self.send_to(&red_id, end.clone());
+                self.send_to(&black_id, end);
             }
         }
+        self.broadcast_live_games().await;
     }
 
     pub async fn handle_play_again(&self, player_id: String) {
-        if let Some(game_id) = self.player_to_game.get(&player_id) {
-            let game_id = game_id.value().clone();
+        if let Some(game_id) = self.game_of(&player_id) {
             tracing::info!(player_id = %player_id, game_id = %game_id, "Player requested rematch");
             if let Some(game_lock) = self.games.get(&game_id) {
                 let mut game = game_lock.write().await;
@@ -111,34 +350,40 @@ impl AppState {
 
                 if game.red_ready_for_rematch && game.black_ready_for_rematch {
                     tracing::info!(game_id = %game_id, "Both players ready, restarting game");
+                    if let Some(result) = game.session.result() {
+                        let red_won = result.winner.map(|winner| winner == Color::Red);
+                        game.score.record(red_won);
+                    }
+                    // Colors alternate between the games of a rematch series
+                    let GameSession {
+                        red_player,
+                        black_player,
+                        red_name,
+                        black_name,
+                        ..
+                    } = &mut *game;
+                    std::mem::swap(red_player, black_player);
+                    std::mem::swap(red_name, black_name);
+                    game.score = game.score.flipped();
+
                     let red_id = game.red_player.clone();
                     let black_id = game.black_player.clone();
+                    let handicap = game.handicap;
+                    let score = game.score;
 
-                    game.board = Board::new();
-                    game.turn = Color::Red;
-                    game.game_ended = false;
+                    game.session = Session::new(handicap, None, now_ms());
+                    let now = Instant::now();
+                    game.started_at = now;
+                    game.start_turn(now);
                     game.red_ready_for_rematch = false;
                     game.black_ready_for_rematch = false;
                     game.pending_move = None;
+                    self.send_to_spectators(&game, &game.spectator_update(&game_id));
 
                     drop(game);
 
-                    if let Some(p) = self.players.get(&red_id) {
-                        let _ = p.tx.send(ServerMessage::MatchFound {
-                            opponent_id: black_id.clone(),
-                            your_color: Color::Red,
-                            game_id: game_id.clone(),
-                        });
-                        let _ = p.tx.send(ServerMessage::GameStart(Box::new(Board::new())));
-                    }
-                    if let Some(p) = self.players.get(&black_id) {
-                        let _ = p.tx.send(ServerMessage::MatchFound {
-                            opponent_id: red_id.clone(),
-                            your_color: Color::Black,
-                            game_id: game_id.clone(),
-                        });
-                        let _ = p.tx.send(ServerMessage::GameStart(Box::new(Board::new())));
-                    }
+                    self.send_match_start(&game_id, &red_id, &black_id, handicap, score);
+                    self.broadcast_live_games().await;
                 }
             }
         }
@@ -146,12 +391,11 @@ impl AppState {
 
     pub async fn leave_game(&self, player_id: &str) {
         tracing::info!(player_id = %player_id, "Player leaving current game");
-        {
-            let mut queue = self.matchmaking_queue.lock().await;
-            queue.remove(player_id);
+        if let Err(e) = self.store.dequeue(player_id).await {
+            tracing::warn!(player_id = %player_id, error = %e, "Failed to leave matchmaking queue");
         }
 
-        if let Some((_, game_id)) = self.player_to_game.remove(player_id) {
+        if let Some(game_id) = self.leave_seat(player_id) {
             tracing::info!(player_id = %player_id, game_id = %game_id, "Cleaning up game session for left player");
             if let Some((_, game_lock)) = self.games.remove(&game_id) {
                 let game = game_lock.read().await;
@@ -165,26 +409,35 @@ impl AppState {
                 } else {
                     Color::Red
                 };
-                let game_ended = game.game_ended;
+                let game_ended = game.session.is_over();
+                if !game_ended {
+                    self.record_result(&game_id, &game, Some(winner));
+                }
+                self.release_spectators(
+                    &game_id,
+                    &game,
+                    (!game_ended).then(|| disconnect_end(winner)).as_ref(),
+                );
                 drop(game);
 
-                self.player_to_game.remove(&opponent_id);
+                self.remove_role(&opponent_id, &game_id);
+                self.clear_game_owner([player_id, &opponent_id]).await;
 
                 if !game_ended {
                     tracing::info!(game_id = %game_id, player_id = %player_id, opponent_id = %opponent_id, "In-progress game ended because player left");
-                    if let Some(player) = self.players.get(&opponent_id) {
-                        let _ = player.tx.send(ServerMessage::OpponentDisconnected);
-                        let _ = player.tx.send(ServerMessage::GameEnd {
+                    self.send_to(&opponent_id, ServerMessage::OpponentDisconnected);
+                    self.send_to(
+                        &opponent_id,
+                        ServerMessage::GameEnd {
                             winner: Some(winner),
                             reason: "Opponent Left".to_string(),
-                        });
-                    }
+                        },
+                    );
                 } else {
                     tracing::info!(game_id = %game_id, player_id = %player_id, opponent_id = %opponent_id, "Player left room after game ended");
-                    if let Some(player) = self.players.get(&opponent_id) {
-                        let _ = player.tx.send(ServerMessage::OpponentLeftGame);
-                    }
+                    self.send_to(&opponent_id, ServerMessage::OpponentLeftGame);
                 }
+                self.broadcast_live_games().await;
             }
         }
     }
@@ -193,12 +446,82 @@ impl AppState {
         self.leave_game(&player_id).await;
     }
 
+    /// Removes a game hosted here, sending the message `end` builds under the game's lock to
+    /// both players and its spectators. Returns `false` when there is no such game.
+    pub async fn close_game(
+        &self,
+        game_id: &str,
+        end: impl FnOnce(&mut GameSession) -> Option<ServerMessage>,
+    ) -> bool {
+        let Some((_, game_lock)) = self.games.remove(game_id) else {
+            return false;
+        };
+        let mut game = game_lock.write().await;
+        let end = end(&mut game);
+        let players = [game.red_player.clone(), game.black_player.clone()];
+        for player_id in &players {
+            if let Some(end) = &end {
+                self.send_to(player_id, end.clone());
+            }
+            self.remove_role(player_id, game_id);
+        }
+        self.release_spectators(game_id, &game, end.as_ref());
+        drop(game);
+
+        let [red, black] = &players;
+        self.clear_game_owner([red, black]).await;
+        self.broadcast_live_games().await;
+        true
+    }
+
+    /// Aborts every game still waiting for its first move after `first_move_timeout`, so
+    /// abandoned pairings do not hold on to their players.
+    pub async fn abort_stale_games(&self, now: Instant) -> Vec<String> {
+        let mut stale = Vec::new();
+        for entry in self.games.iter() {
+            let game = entry.value().read().await;
+            if !game.session.is_over()
+                && game.session.state.history.is_empty()
+                && game.pending_move.is_none()
+                && now.saturating_duration_since(game.started_at) > self.first_move_timeout
+            {
+                stale.push(entry.key().clone());
+            }
+        }
+
+        for game_id in &stale {
+            tracing::info!(game_id = %game_id, "Aborting game with no first move");
+            self.close_game(game_id, |_| {
+                Some(ServerMessage::GameAborted {
+                    reason: AbortReason::NoFirstMove,
+                })
+            })
+            .await;
+        }
+        stale
+    }
+
     pub fn spawn_cleanup_task(self: std::sync::Arc<Self>) {
         tokio::spawn(async move {
-            use std::time::{Duration, Instant};
             let mut interval = tokio::time::interval(Duration::from_secs(300)); // Every 5 mins
+            let mut heartbeat_interval = tokio::time::interval(self.heartbeat.sweep_interval());
+            let mut move_timer_interval = tokio::time::interval(Duration::from_secs(1));
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = heartbeat_interval.tick() => {
+                        self.reap_stale_players(Instant::now()).await;
+                        self.expire_held_seats(Instant::now()).await;
+                        self.abort_stale_games(Instant::now()).await;
+                        // Also refreshes move counts shown to live-list subscribers
+                        self.broadcast_live_games().await;
+                        continue;
+                    }
+                    _ = move_timer_interval.tick() => {
+                        self.check_move_timers(Instant::now()).await;
+                        continue;
+                    }
+                }
                 let now = Instant::now();
                 let mut games_to_remove = Vec::new();
 
@@ -213,11 +536,46 @@ impl AppState {
                     tracing::info!("Cleaning up inactive game: {}", game_id);
                     if let Some((_, game_lock)) = self.games.remove(&game_id) {
                         let game = game_lock.read().await;
-                        self.player_to_game.remove(&game.red_player);
-                        self.player_to_game.remove(&game.black_player);
+                        self.remove_role(&game.red_player, &game_id);
+                        self.remove_role(&game.black_player, &game_id);
+                        self.release_spectators(&game_id, &game, None);
+                        self.clear_game_owner([&game.red_player, &game.black_player])
+                            .await;
                     }
                 }
             }
         });
     }
 }
+
+/// Judges `state` with the engine, off the async runtime: the side the search finds clearly
+/// ahead wins, anything closer (or a failed search) is a draw.
+async fn adjudicate(state: GameState) -> Option<Color> {
+    let turn = state.turn;
+    let search = tokio::task::spawn_blocking(move || {
+        let mut engine = AlphaBetaEngine::new(Arc::new(EngineConfig {
+            tt_size_mb: ADJUDICATION_TT_MB,
+            ..Default::default()
+        }));
+        engine
+            .search(&state, SearchLimit::Depth(ADJUDICATION_DEPTH), &[])
+            .map(|(mv, _)| mv.score)
+    })
+    .await;
+    let score = search.ok().flatten()?;
+    if score >= ADJUDICATION_MARGIN {
+        Some(turn)
+    } else if score <= -ADJUDICATION_MARGIN {
+        Some(turn.opposite())
+    } else {
+        None
+    }
+}
+
+/// How a game that lost one of its players is reported to spectators.
+fn disconnect_end(winner: Color) -> ServerMessage {
+    ServerMessage::GameEnd {
+        winner: Some(winner),
+        reason: "Disconnect".to_string(),
+    }
+}
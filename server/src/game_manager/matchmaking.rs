@@ -1,42 +1,99 @@
-use crate::game_manager::{AppState, GameSession};
-use cotuong_core::logic::board::{Board, Color};
-use shared::ServerMessage;
+use crate::game_manager::{session::now_ms, AppState, GameRole, GameSession};
+use crate::storage::QueueOutcome;
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::handicap::HandicapKind;
+use cotuong_core::session::Session;
+use shared::{DisconnectPolicy, ErrorCode, MatchScore, ServerMessage};
 use tracing;
 use uuid::Uuid;
 
 impl AppState {
-    pub async fn find_match(&self, player_id: String) {
-        if self.player_to_game.contains_key(&player_id) {
+    pub async fn find_match(&self, player_id: String, handicap: HandicapKind) {
+        if self.is_playing(&player_id) {
             tracing::warn!(player_id = %player_id, "Player already in game, ignoring find_match");
             return;
         }
-
-        let mut queue = self.matchmaking_queue.lock().await;
-
-        if queue.contains(&player_id) {
-            tracing::debug!(player_id = %player_id, "Player already in queue");
-            return;
+        let name = self.players.get(&player_id).map(|p| p.name.clone());
+        if let Some(name) = name {
+            if self.is_name_banned(&name).await {
+                self.kick_banned(&player_id).await;
+                return;
+            }
         }
 
-        let opponent_opt = queue.iter().next().cloned();
+        match self.store.pair_or_enqueue(&player_id, handicap).await {
+            Ok(QueueOutcome::AlreadyQueued) => {
+                tracing::debug!(player_id = %player_id, "Player already in queue");
+            }
+            Ok(QueueOutcome::Paired(opponent_id)) => {
+                tracing::info!(player_id = %player_id, opponent_id = %opponent_id, "Opponent found, starting game");
+                self.start_game(
+                    player_id,
+                    opponent_id,
+                    handicap,
+                    None,
+                    DisconnectPolicy::Forfeit,
+                )
+                .await;
+            }
+            Ok(QueueOutcome::Queued) => {
+                tracing::info!(player_id = %player_id, "No opponent found, adding to queue");
+                self.send_to(&player_id, ServerMessage::WaitingForMatch);
+            }
+            Err(e) => {
+                tracing::error!(player_id = %player_id, error = %e, "Matchmaking storage failure");
+                self.send_error(&player_id, ErrorCode::MatchmakingUnavailable, None);
+            }
+        }
+    }
 
-        if let Some(opponent_id) = opponent_opt {
-            tracing::info!(player_id = %player_id, opponent_id = %opponent_id, "Opponent found, starting game");
-            queue.remove(&opponent_id);
-            drop(queue);
-            self.start_game(player_id, opponent_id).await;
-        } else {
-            tracing::info!(player_id = %player_id, "No opponent found, adding to queue");
-            queue.insert(player_id.clone());
-            drop(queue);
+    /// Tells both players their colors, the score so far (`score` is Red's) and the board.
+    pub fn send_match_start(
+        &self,
+        game_id: &str,
+        red_id: &str,
+        black_id: &str,
+        handicap: HandicapKind,
+        score: MatchScore,
+    ) {
+        for (player_id, opponent_id, color, score) in [
+            (red_id, black_id, Color::Red, score),
+            (black_id, red_id, Color::Black, score.flipped()),
+        ] {
+            self.send_to(
+                player_id,
+                ServerMessage::MatchFound {
+                    opponent_id: opponent_id.to_string(),
+                    your_color: color,
+                    game_id: game_id.to_string(),
+                    score,
+                },
+            );
+            self.send_to(
+                player_id,
+                ServerMessage::GameStart(Box::new(handicap.board())),
+            );
+        }
+    }
 
-            if let Some(player) = self.players.get(&player_id) {
-                let _ = player.tx.send(ServerMessage::WaitingForMatch);
+    pub async fn cancel_find_match(&self, player_id: &str) {
+        match self.store.dequeue(player_id).await {
+            Ok(_) => tracing::info!(player_id = %player_id, "Cancelled matchmaking"),
+            Err(e) => {
+                tracing::error!(player_id = %player_id, error = %e, "Failed to cancel matchmaking");
             }
         }
     }
 
-    async fn start_game(&self, p1_id: String, p2_id: String) {
+    /// Pairs two players in a new game with random colors.
+    pub async fn start_game(
+        &self,
+        p1_id: String,
+        p2_id: String,
+        handicap: HandicapKind,
+        time_control: Option<String>,
+        disconnect_policy: DisconnectPolicy,
+    ) {
         let game_id = Uuid::new_v4().to_string();
 
         let (red_id, black_id) = if rand::random() {
@@ -45,42 +102,58 @@ impl AppState {
             (p2_id.clone(), p1_id.clone())
         };
 
-        tracing::info!(game_id = %game_id, red = %red_id, black = %black_id, "Created new game session");
+        tracing::info!(game_id = %game_id, red = %red_id, black = %black_id, ?handicap, "Created new game session");
+
+        let player_name = |id: &str| {
+            self.players
+                .get(id)
+                .map_or_else(|| id.to_string(), |p| p.name.clone())
+        };
 
         use std::time::Instant;
         let game = GameSession {
             red_player: red_id.clone(),
             black_player: black_id.clone(),
-            board: Board::new(),
-            turn: Color::Red,
-            game_ended: false,
+            red_name: player_name(&red_id),
+            black_name: player_name(&black_id),
+            session: Session::new(handicap, None, now_ms()),
+            handicap,
             red_ready_for_rematch: false,
             black_ready_for_rematch: false,
             pending_move: None,
             last_activity: Instant::now(),
+            started_at: Instant::now(),
+            turn_started: Instant::now(),
+            move_warning_sent: false,
+            spectators: Vec::new(),
+            changes: watch::channel(()).0,
+            score: MatchScore::default(),
+            time_control,
+            disconnect_policy,
+            away: None,
         };
 
-        use tokio::sync::RwLock;
+        use tokio::sync::{watch, RwLock};
         self.games.insert(game_id.clone(), RwLock::new(game));
-        self.player_to_game.insert(p1_id.clone(), game_id.clone());
-        self.player_to_game.insert(p2_id.clone(), game_id.clone());
-
-        if let Some(p) = self.players.get(&red_id) {
-            let _ = p.tx.send(ServerMessage::MatchFound {
-                opponent_id: black_id.clone(),
-                your_color: Color::Red,
-                game_id: game_id.clone(),
-            });
-            let _ = p.tx.send(ServerMessage::GameStart(Box::new(Board::new())));
+        for id in [&p1_id, &p2_id] {
+            // Clients show one board, so a player's own game replaces the one they watch
+            self.stop_spectating(id).await;
+            self.close_challenges(id);
+            self.add_role(id, &game_id, GameRole::Player);
         }
-
-        if let Some(p) = self.players.get(&black_id) {
-            let _ = p.tx.send(ServerMessage::MatchFound {
-                opponent_id: red_id.clone(),
-                your_color: Color::Black,
-                game_id: game_id.clone(),
-            });
-            let _ = p.tx.send(ServerMessage::GameStart(Box::new(Board::new())));
+        for id in [&red_id, &black_id] {
+            if let Err(e) = self.store.set_game_owner(id).await {
+                tracing::warn!(player_id = %id, error = %e, "Failed to record game owner");
+            }
         }
+
+        self.send_match_start(
+            &game_id,
+            &red_id,
+            &black_id,
+            handicap,
+            MatchScore::default(),
+        );
+        self.broadcast_live_games().await;
     }
 }
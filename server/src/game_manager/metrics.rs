@@ -0,0 +1,178 @@
+use crate::game_manager::AppState;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bounds (in seconds) of the move-validation latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 8] = [0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5];
+
+struct RateWindow {
+    started: Instant,
+    count: u64,
+    last_rate: f64,
+}
+
+pub struct Metrics {
+    messages_total: AtomicU64,
+    rate: Mutex<RateWindow>,
+    latency_buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    latency_sum_micros: AtomicU64,
+    latency_count: AtomicU64,
+    outbox_overflows: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            messages_total: AtomicU64::new(0),
+            rate: Mutex::new(RateWindow {
+                started: Instant::now(),
+                count: 0,
+                last_rate: 0.0,
+            }),
+            latency_buckets: Default::default(),
+            latency_sum_micros: AtomicU64::new(0),
+            latency_count: AtomicU64::new(0),
+            outbox_overflows: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record_message(&self) {
+        self.messages_total.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut window) = self.rate.lock() {
+            window.count += 1;
+            let elapsed = window.started.elapsed();
+            if elapsed >= Duration::from_secs(1) {
+                window.last_rate = window.count as f64 / elapsed.as_secs_f64();
+                window.count = 0;
+                window.started = Instant::now();
+            }
+        }
+    }
+
+    /// Messages per second over the last completed one-second window.
+    pub fn messages_per_second(&self) -> f64 {
+        match self.rate.lock() {
+            // A window that has been open for a while means traffic stopped
+            Ok(window) if window.started.elapsed() < Duration::from_secs(2) => window.last_rate,
+            _ => 0.0,
+        }
+    }
+
+    pub fn messages_total(&self) -> u64 {
+        self.messages_total.load(Ordering::Relaxed)
+    }
+
+    pub fn observe_validation(&self, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.latency_buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        self.latency_sum_micros.fetch_add(micros, Ordering::Relaxed);
+        self.latency_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn validation_count(&self) -> u64 {
+        self.latency_count.load(Ordering::Relaxed)
+    }
+
+    /// Counts a connection closed because its client stopped reading.
+    pub fn record_outbox_overflow(&self) {
+        self.outbox_overflows.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn outbox_overflows(&self) -> u64 {
+        self.outbox_overflows.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppState {
+    /// Renders all server metrics in the Prometheus text exposition format.
+    pub async fn render_metrics(&self) -> String {
+        let queue_len = self.store.queue_len().await.unwrap_or(0);
+        let metrics = &self.metrics;
+        let mut out = String::new();
+
+        let gauges = [
+            (
+                "cotuong_active_games",
+                "Number of game sessions currently held in memory.",
+                self.games.len() as f64,
+            ),
+            (
+                "cotuong_connected_players",
+                "Number of connected WebSocket clients.",
+                self.players.len() as f64,
+            ),
+            (
+                "cotuong_matchmaking_queue_length",
+                "Number of players waiting for a match.",
+                queue_len as f64,
+            ),
+            (
+                "cotuong_messages_per_second",
+                "Client messages received per second over the last window.",
+                metrics.messages_per_second(),
+            ),
+            (
+                "cotuong_eval_cache_entries",
+                "Number of shared position evaluations held in memory.",
+                self.eval_cache.entry_count() as f64,
+            ),
+        ];
+        for (name, help, value) in gauges {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} gauge");
+            let _ = writeln!(out, "{name} {value}");
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP cotuong_messages_total Total client messages received."
+        );
+        let _ = writeln!(out, "# TYPE cotuong_messages_total counter");
+        let _ = writeln!(out, "cotuong_messages_total {}", metrics.messages_total());
+
+        let _ = writeln!(
+            out,
+            "# HELP cotuong_outbox_overflows_total Connections closed because the client stopped reading."
+        );
+        let _ = writeln!(out, "# TYPE cotuong_outbox_overflows_total counter");
+        let _ = writeln!(
+            out,
+            "cotuong_outbox_overflows_total {}",
+            metrics.outbox_overflows()
+        );
+
+        let name = "cotuong_move_validation_seconds";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Time spent validating moves server-side."
+        );
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&metrics.latency_buckets) {
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{le=\"{bound}\"}} {}",
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = metrics.validation_count();
+        let sum = metrics.latency_sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {count}");
+        let _ = writeln!(out, "{name}_sum {sum}");
+        let _ = writeln!(out, "{name}_count {count}");
+
+        out
+    }
+}
@@ -1,46 +1,169 @@
-use dashmap::DashMap;
-use std::collections::HashSet;
-use tokio::sync::{Mutex, RwLock};
+use crate::auth::{AdminToken, TokenSigner};
+use crate::storage::{MemoryStore, Storage};
+use dashmap::{DashMap, DashSet};
+use shared::{ErrorCode, GameMessage, ServerMessage};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
 
+pub mod admin;
+pub mod anticheat;
+pub mod arena;
+pub mod dispatch;
+pub mod eval_cache;
+pub mod friends;
+pub mod heartbeat;
 pub mod lifecycle;
 pub mod matchmaking;
+pub mod metrics;
 pub mod move_handler;
+pub mod move_timer;
+pub mod outbox;
+pub mod rate_limit;
+pub mod ratings;
+pub mod replays;
+pub mod roles;
 pub mod session;
+pub mod snapshot;
+pub mod spectate;
 #[cfg(test)]
 pub mod tests;
 
-pub use session::{GameSession, Player, Tx};
+pub use anticheat::AntiCheat;
+pub use arena::Arena;
+pub use eval_cache::EvalCache;
+pub use friends::Challenge;
+pub use heartbeat::HeartbeatConfig;
+pub use metrics::Metrics;
+pub use move_timer::MoveTimerConfig;
+pub use outbox::Tx;
+pub use rate_limit::RateLimitConfig;
+pub use ratings::PlayerRating;
+pub use replays::ReplayArchive;
+pub use roles::{GameRole, GameRoles};
+pub use session::{GameSession, Player};
 
 pub struct AppState {
     pub players: DashMap<String, Player>,
     pub games: DashMap<String, RwLock<GameSession>>,
-    pub player_to_game: DashMap<String, String>,
-    pub matchmaking_queue: Mutex<HashSet<String>>,
+    /// The games each player plays in or follows; players without any are left out.
+    pub game_roles: DashMap<String, GameRoles>,
+    /// Players receiving `ServerMessage::LiveGames` pushes.
+    pub live_subscribers: DashSet<String>,
+    /// Friend challenges waiting for an answer, by challenge id.
+    pub challenges: DashMap<String, Challenge>,
+    pub store: Arc<dyn Storage>,
+    pub signer: TokenSigner,
+    pub admin: AdminToken,
+    pub rate_limit_config: RateLimitConfig,
+    pub heartbeat: HeartbeatConfig,
+    /// Games nobody has moved in for this long after starting are aborted.
+    pub first_move_timeout: Duration,
+    pub move_timer: MoveTimerConfig,
+    /// Messages each player's outgoing queue holds, see `outbox::Tx`.
+    pub outbox_capacity: usize,
+    pub ratings: DashMap<String, PlayerRating>,
+    pub metrics: Metrics,
+    /// Position evaluations shared between clients.
+    pub eval_cache: EvalCache,
+    /// Finished rated games waiting for the engine-similarity check.
+    pub anticheat: AntiCheat,
+    /// Uploaded engine configs playing their round-robin.
+    pub arena: Arena,
+    /// Finished games for replay links.
+    pub replays: ReplayArchive,
+    pub shutting_down: AtomicBool,
 }
 
 impl AppState {
     pub fn new() -> Self {
+        Self::with_store(Arc::new(MemoryStore::new()))
+    }
+
+    pub fn with_store(store: Arc<dyn Storage>) -> Self {
         Self {
             players: DashMap::new(),
             games: DashMap::new(),
-            player_to_game: DashMap::new(),
-            matchmaking_queue: Mutex::new(HashSet::new()),
+            game_roles: DashMap::new(),
+            live_subscribers: DashSet::new(),
+            challenges: DashMap::new(),
+            store,
+            signer: TokenSigner::from_env(),
+            admin: AdminToken::from_env(),
+            rate_limit_config: RateLimitConfig::from_env(),
+            heartbeat: HeartbeatConfig::from_env(),
+            first_move_timeout: lifecycle::first_move_timeout_from_env(),
+            move_timer: MoveTimerConfig::from_env(),
+            outbox_capacity: outbox::outbox_capacity_from_env(),
+            ratings: DashMap::new(),
+            metrics: Metrics::new(),
+            eval_cache: EvalCache::from_env(),
+            anticheat: AntiCheat::from_env(),
+            arena: Arena::from_env(),
+            replays: ReplayArchive::from_env(),
+            shutting_down: AtomicBool::new(false),
         }
     }
 
-    pub fn check_rate_limit(&self, player_id: &str) -> bool {
-        use std::time::Instant;
-        if let Some(mut player) = self.players.get_mut(player_id) {
-            let now = Instant::now();
-            let elapsed = now.duration_since(player.last_msg_at).as_secs_f32();
-            if elapsed < 0.1 {
-                // Allow max 10 messages per second
-                return false;
+    /// Sends to a locally connected player, or routes through the store to another instance.
+    pub fn send_to(&self, player_id: &str, msg: ServerMessage) {
+        if let Some(player) = self.players.get(player_id) {
+            if player.tx.send(msg) == Err(outbox::SendError::Overflow) {
+                tracing::warn!(player_id = %player_id, "Outgoing queue overflowed, dropping slow connection");
+                self.metrics.record_outbox_overflow();
             }
-            player.last_msg_at = now;
-            true
         } else {
-            false
+            self.store.deliver_remote(player_id, msg);
+        }
+    }
+
+    pub fn send_error(&self, player_id: &str, code: ErrorCode, detail: Option<String>) {
+        self.send_to(player_id, ServerMessage::Error { code, detail });
+    }
+
+    /// Drops the shared routing entries of a finished or abandoned game.
+    pub async fn clear_game_owner(&self, player_ids: [&str; 2]) {
+        for id in player_ids {
+            if let Err(e) = self.store.clear_game_owner(id).await {
+                tracing::warn!(player_id = %id, error = %e, "Failed to clear game owner");
+            }
+        }
+    }
+
+    /// True when `token` was issued by this server for exactly `player_id`.
+    pub fn authenticate(&self, player_id: &str, token: &str) -> bool {
+        self.signer.verify(token) == Some(player_id)
+    }
+
+    /// Charges `msg` against the player's budget for its category.
+    /// On rejection the player is told which budget ran out and when to retry.
+    pub fn check_rate_limit(&self, player_id: &str, msg: &GameMessage) -> bool {
+        let Some(kind) = rate_limit::message_kind(msg) else {
+            return true;
+        };
+        let result = match self.players.get_mut(player_id) {
+            Some(mut player) => {
+                player
+                    .rate_limiter
+                    .check(&self.rate_limit_config, kind, Instant::now())
+            }
+            None => return false,
+        };
+
+        match result {
+            Ok(()) => true,
+            Err(retry_after) => {
+                tracing::warn!(player_id = %player_id, ?kind, "Rate limit exceeded");
+                self.send_to(
+                    player_id,
+                    ServerMessage::RateLimited {
+                        kind,
+                        retry_after_ms: u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX),
+                    },
+                );
+                false
+            }
         }
     }
 }
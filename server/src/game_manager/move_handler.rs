@@ -1,39 +1,41 @@
-use crate::game_manager::{session::has_any_valid_move, AppState};
-use cotuong_core::{
-    engine::Move,
-    logic::board::{Board, Color},
-};
-use shared::ServerMessage;
+use crate::game_manager::{session::now_ms, AppState};
+use cotuong_core::{engine::Move, logic::board::Color, session::SessionResult};
+use shared::{ErrorCode, ServerMessage};
+use std::time::Instant;
 use tracing;
 
 impl AppState {
     pub async fn handle_move(&self, player_id: String, mv: Move, fen: String) {
-        let game_id = if let Some(gid) = self.player_to_game.get(&player_id) {
-            gid.value().clone()
+        let game_id = if let Some(gid) = self.game_of(&player_id) {
+            gid
         } else {
             tracing::warn!(player_id = %player_id, "Received move from player not in a game");
+            self.send_error(&player_id, ErrorCode::NotInGame, None);
             return;
         };
 
         if let Some(game_lock) = self.games.get(&game_id) {
             let mut game = game_lock.write().await;
 
-            if game.game_ended {
+            if game.session.is_over() {
                 tracing::debug!(game_id = %game_id, player_id = %player_id, "Move ignored: game ended");
+                drop(game);
+                self.send_error(&player_id, ErrorCode::GameAlreadyEnded, None);
                 return;
             }
 
             let is_red = game.red_player == player_id;
             let player_color = if is_red { Color::Red } else { Color::Black };
 
-            if game.turn != player_color {
+            if game.session.turn() != player_color {
                 tracing::warn!(game_id = %game_id, player_id = %player_id, "Move ignored: not player's turn");
+                drop(game);
+                self.send_error(&player_id, ErrorCode::NotYourTurn, None);
                 return;
             }
 
             tracing::debug!(game_id = %game_id, player_id = %player_id, ?mv, "Processing move");
             game.pending_move = Some((player_id.clone(), mv, fen.clone()));
-            use std::time::Instant;
             game.last_activity = Instant::now();
 
             let opponent_id = if is_red {
@@ -41,19 +43,24 @@ impl AppState {
             } else {
                 game.red_player.clone()
             };
+            let seq = game.session.move_count();
 
             drop(game);
 
-            if let Some(p) = self.players.get(&opponent_id) {
-                let _ =
-                    p.tx.send(ServerMessage::OpponentMove { move_data: mv, fen });
-            }
+            self.send_to(
+                &opponent_id,
+                ServerMessage::OpponentMove {
+                    move_data: mv,
+                    fen,
+                    seq,
+                },
+            );
         }
     }
 
     pub async fn handle_verify_move(&self, player_id: String, _fen: String, is_valid: bool) {
-        let game_id = if let Some(gid) = self.player_to_game.get(&player_id) {
-            gid.value().clone()
+        let game_id = if let Some(gid) = self.game_of(&player_id) {
+            gid
         } else {
             tracing::warn!(player_id = %player_id, "Received verification from player not in a game");
             return;
@@ -84,36 +91,42 @@ impl AppState {
                 }
 
                 if is_valid {
-                    if let Ok((new_board, new_turn)) = Board::from_fen(&claimed_fen) {
-                        tracing::debug!(game_id = %game_id, "Move verified successfully");
-                        use std::time::Instant;
-                        game.last_activity = Instant::now();
-                        game.board = new_board;
-                        game.turn = new_turn;
-                        game.pending_move = None;
-
-                        let board_snapshot = game.board.clone();
-                        let turn_snapshot = game.turn;
-
-                        let has_moves = has_any_valid_move(&board_snapshot, turn_snapshot);
-                        if !has_moves {
-                            let winner = if game.turn == Color::Red {
-                                Color::Black
-                            } else {
-                                Color::Red
-                            };
-                            game.game_ended = true;
-                            tracing::info!(game_id = %game_id, winner = ?winner, "Game ended (Checkmate detected)");
-
-                            drop(game);
-
-                            self.notify_game_end(&game_id, winner, "Checkmate".to_string())
-                                .await;
-                        }
+                    let validation_start = Instant::now();
+                    let mover = if is_mover_red {
+                        Color::Red
                     } else {
-                        tracing::error!(game_id = %game_id, claimed_fen = %claimed_fen, "Failed to parse FEN from move verification");
+                        Color::Black
+                    };
+                    let seq = game.session.move_count();
+                    let played = game.session.play_move(mover, &mv, now_ms());
+                    self.metrics.observe_validation(validation_start.elapsed());
+                    let Ok(result) = played else {
+                        tracing::warn!(game_id = %game_id, ?mv, "Verified move breaks the rules, resolving conflict");
                         drop(game);
                         self.resolve_conflict(&game_id, &mv).await;
+                        return;
+                    };
+
+                    tracing::debug!(game_id = %game_id, "Move verified successfully");
+                    let now = Instant::now();
+                    game.last_activity = now;
+                    game.start_turn(now);
+                    game.pending_move = None;
+                    self.send_to_spectators(&game, &game.spectator_update(&game_id));
+                    let fen = game.session.board().to_fen_string(game.session.turn());
+                    let correction = game.correction();
+                    drop(game);
+
+                    self.send_to(&mover_id, ServerMessage::MoveAccepted { seq });
+                    // Both clients agreed on a position the move does not lead to
+                    if fen != claimed_fen {
+                        tracing::warn!(game_id = %game_id, claimed_fen = %claimed_fen, fen = %fen, "Claimed FEN does not match the move, correcting players");
+                        self.send_to(&red_id, correction.clone());
+                        self.send_to(&black_id, correction);
+                    }
+                    if let Some(result) = result {
+                        tracing::info!(game_id = %game_id, winner = ?result.winner, "Game ended (Checkmate detected)");
+                        self.notify_game_end(&game_id, result).await;
                     }
                 } else {
                     tracing::warn!(game_id = %game_id, player_id = %player_id, "Move rejected by opponent, resolving conflict");
@@ -131,101 +144,91 @@ impl AppState {
         if let Some(game_lock) = self.games.get(game_id) {
             let mut game = game_lock.write().await;
 
-            use cotuong_core::logic::rules::is_valid_move;
-
-            let from = if let Some(c) = cotuong_core::logic::board::BoardCoordinate::new(
-                mv.from_row as usize,
-                mv.from_col as usize,
-            ) {
-                c
-            } else {
-                return;
-            };
-
-            let to = if let Some(c) = cotuong_core::logic::board::BoardCoordinate::new(
-                mv.to_row as usize,
-                mv.to_col as usize,
-            ) {
-                c
-            } else {
-                return;
-            };
-
-            let is_legal = is_valid_move(&game.board, from, to, game.turn).is_ok();
-            tracing::info!(game_id = %game_id, is_legal = %is_legal, "Server-side move legality check");
-
-            let true_fen: String;
-            let true_turn: Color;
-
-            let current_turn = game.turn;
-
-            if is_legal {
-                game.board.apply_move(mv, current_turn);
-                game.turn = current_turn.opposite();
-                true_turn = game.turn;
-                true_fen = game.board.to_fen_string(true_turn);
-            } else {
-                true_turn = current_turn;
-                true_fen = game.board.to_fen_string(true_turn);
-            }
+            let validation_start = Instant::now();
+            let mover = game.session.turn();
+            let played = game.session.play_move(mover, mv, now_ms());
+            self.metrics.observe_validation(validation_start.elapsed());
+            tracing::info!(game_id = %game_id, is_legal = %played.is_ok(), "Server-side move legality check");
 
             game.pending_move = None;
+            if played.is_ok() {
+                game.start_turn(Instant::now());
+            }
 
-            let msg = ServerMessage::GameStateCorrection {
-                fen: true_fen.clone(),
-                turn: true_turn,
-            };
-            tracing::info!(game_id = %game_id, fen = %true_fen, turn = ?true_turn, "Sending GameStateCorrection to players");
-
-            let board_snapshot = game.board.clone();
-            let turn_snapshot = game.turn;
-            let has_moves = has_any_valid_move(&board_snapshot, turn_snapshot);
-
-            let end_data = if !has_moves {
-                let winner = if game.turn == Color::Red {
-                    Color::Black
-                } else {
-                    Color::Red
-                };
-                game.game_ended = true;
-                Some(winner)
-            } else {
-                None
-            };
+            let msg = game.correction();
+            tracing::info!(game_id = %game_id, ?msg, "Sending GameStateCorrection to players");
 
             let red_id = game.red_player.clone();
             let black_id = game.black_player.clone();
+            self.send_to_spectators(&game, &game.spectator_update(game_id));
 
             drop(game);
 
-            if let Some(p) = self.players.get(&red_id) {
-                let _ = p.tx.send(msg.clone());
-            }
-            if let Some(p) = self.players.get(&black_id) {
-                let _ = p.tx.send(msg);
-            }
+            self.send_to(&red_id, msg.clone());
+            self.send_to(&black_id, msg);
 
-            if let Some(winner) = end_data {
-                self.notify_game_end(game_id, winner, "Checkmate".to_string())
-                    .await;
+            match played {
+                Ok(Some(result)) => self.notify_game_end(game_id, result).await,
+                Ok(None) => {}
+                Err(e) => {
+                    let mover_id = if mover == Color::Red {
+                        &red_id
+                    } else {
+                        &black_id
+                    };
+                    self.send_error(mover_id, ErrorCode::IllegalMove, Some(format!("{e:?}")));
+                }
             }
         }
     }
 
-    pub async fn notify_game_end(&self, game_id: &str, winner: Color, reason: String) {
-        tracing::info!(game_id = %game_id, winner = ?winner, reason = %reason, "Notifying players of game end");
+    /// Answers `GameMessage::RequestMoves` with the moves the player is missing, or with the
+    /// full position when they are too far off. A move still waiting for this player's
+    /// verification is sent again after them.
+    pub async fn handle_request_moves(&self, player_id: String, from_seq: u32) {
+        let Some(game_id) = self.game_of(&player_id) else {
+            self.send_error(&player_id, ErrorCode::NotInGame, None);
+            return;
+        };
+        let Some(game_lock) = self.games.get(&game_id) else {
+            return;
+        };
+        let game = game_lock.read().await;
+        let sync = game.moves_since(from_seq).map_or_else(
+            || game.correction(),
+            |moves| ServerMessage::SyncMoves { from_seq, moves },
+        );
+        let pending = game
+            .pending_move
+            .as_ref()
+            .filter(|(mover_id, _, _)| *mover_id != player_id)
+            .map(|(_, mv, fen)| ServerMessage::OpponentMove {
+                move_data: *mv,
+                fen: fen.clone(),
+                seq: game.session.move_count(),
+            });
+        drop(game);
+
+        tracing::debug!(game_id = %game_id, player_id = %player_id, from_seq, "Syncing moves");
+        self.send_to(&player_id, sync);
+        if let Some(pending) = pending {
+            self.send_to(&player_id, pending);
+        }
+    }
+
+    pub async fn notify_game_end(&self, game_id: &str, result: SessionResult) {
+        tracing::info!(game_id = %game_id, winner = ?result.winner, reason = ?result.reason, "Notifying players of game end");
         if let Some(game_lock) = self.games.get(game_id) {
             let game = game_lock.read().await;
+            self.record_result(game_id, &game, result.winner);
             let msg = ServerMessage::GameEnd {
-                winner: Some(winner),
-                reason,
+                winner: result.winner,
+                reason: result.reason.as_str().to_string(),
             };
-            if let Some(p) = self.players.get(&game.red_player) {
-                let _ = p.tx.send(msg.clone());
-            }
-            if let Some(p) = self.players.get(&game.black_player) {
-                let _ = p.tx.send(msg);
-            }
+            self.send_to(&game.red_player, msg.clone());
+            self.send_to(&game.black_player, msg.clone());
+            self.send_to_spectators(&game, &msg);
         }
+        self.broadcast_live_games().await;
     }
 }
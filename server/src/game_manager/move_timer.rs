@@ -0,0 +1,96 @@
+use crate::game_manager::{session::now_ms, AppState, GameSession};
+use cotuong_core::logic::board::Color;
+use shared::ServerMessage;
+use std::time::{Duration, Instant};
+
+/// How long the player to move may think, and when they are warned that time is short.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveTimerConfig {
+    /// Limit per move in games without a clock; `None` lets untimed games wait forever.
+    pub move_timeout: Option<Duration>,
+    /// `ServerMessage::MoveTimeWarning` goes out once this little time is left.
+    pub warning: Duration,
+}
+
+impl Default for MoveTimerConfig {
+    fn default() -> Self {
+        Self {
+            move_timeout: Some(Duration::from_secs(300)),
+            warning: Duration::from_secs(30),
+        }
+    }
+}
+
+impl MoveTimerConfig {
+    /// Reads `MOVE_TIMEOUT_SECS` (0 turns the limit off) and `MOVE_WARNING_SECS`, falling
+    /// back to the defaults.
+    pub fn from_env() -> Self {
+        let secs = |name: &str| std::env::var(name).ok().and_then(|v| v.parse::<u64>().ok());
+        let default = Self::default();
+        Self {
+            move_timeout: secs("MOVE_TIMEOUT_SECS").map_or(default.move_timeout, |secs| {
+                (secs > 0).then(|| Duration::from_secs(secs))
+            }),
+            warning: secs("MOVE_WARNING_SECS").map_or(default.warning, Duration::from_secs),
+        }
+    }
+}
+
+impl GameSession {
+    /// Time the player to move has left: their clock in timed games, otherwise what remains
+    /// of `move_timeout` since the turn started. `None` when nothing limits the turn.
+    fn time_left(&self, move_timeout: Option<Duration>, now: Instant) -> Option<Duration> {
+        let turn = self.session.turn();
+        match self.session.remaining_ms(turn, now_ms()) {
+            Some(ms) => Some(Duration::from_millis(ms)),
+            None => move_timeout.map(|limit| {
+                limit.saturating_sub(now.saturating_duration_since(self.turn_started))
+            }),
+        }
+    }
+}
+
+impl AppState {
+    /// Warns players whose time to move is nearly up and ends the games of those who ran
+    /// out. Games waiting for their first move or for a verification are left alone; the
+    /// first move has its own timeout.
+    pub async fn check_move_timers(&self, now: Instant) {
+        let mut warnings = Vec::new();
+        let mut expired = Vec::new();
+        for entry in self.games.iter() {
+            let mut game = entry.value().write().await;
+            if game.session.is_over()
+                || game.session.state.history.is_empty()
+                || game.pending_move.is_some()
+            {
+                continue;
+            }
+            let Some(left) = game.time_left(self.move_timer.move_timeout, now) else {
+                continue;
+            };
+            let turn = game.session.turn();
+            if left.is_zero() {
+                if let Ok(result) = game.session.time_out(turn) {
+                    expired.push((entry.key().clone(), result));
+                }
+            } else if left <= self.move_timer.warning && !game.move_warning_sent {
+                game.move_warning_sent = true;
+                let player_id = match turn {
+                    Color::Red => game.red_player.clone(),
+                    Color::Black => game.black_player.clone(),
+                };
+                let seconds_left = (left + Duration::from_millis(999)).as_secs();
+                warnings.push((player_id, seconds_left));
+            }
+        }
+
+        for (player_id, seconds_left) in warnings {
+            tracing::debug!(player_id = %player_id, seconds_left, "Warning player about move time");
+            self.send_to(&player_id, ServerMessage::MoveTimeWarning { seconds_left });
+        }
+        for (game_id, result) in expired {
+            tracing::info!(game_id = %game_id, winner = ?result.winner, "Player ran out of time");
+            self.notify_game_end(&game_id, result).await;
+        }
+    }
+}
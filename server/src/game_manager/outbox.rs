@@ -0,0 +1,243 @@
+use shared::ServerMessage;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::Notify;
+
+/// Messages a player's queue holds before the overflow policy kicks in.
+pub const DEFAULT_OUTBOX_CAPACITY: usize = 256;
+
+/// Reads `OUTBOX_CAPACITY`: how many messages may wait for a slow client.
+pub fn outbox_capacity_from_env() -> usize {
+    std::env::var("OUTBOX_CAPACITY")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|capacity| *capacity > 0)
+        .unwrap_or(DEFAULT_OUTBOX_CAPACITY)
+}
+
+/// Messages that are safe to lose: snapshots a later message of the same kind replaces, and
+/// keepalive replies. Everything else changes the game and must reach the client.
+fn is_droppable(msg: &ServerMessage) -> bool {
+    matches!(
+        msg,
+        ServerMessage::LiveGames(_)
+            | ServerMessage::SpectatorUpdate { .. }
+            | ServerMessage::Friends(_)
+            | ServerMessage::Pong(_)
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendError {
+    /// The connection is gone.
+    Closed,
+    /// The queue was full of messages the client must receive, so the connection was closed.
+    Overflow,
+}
+
+struct Queue {
+    messages: VecDeque<ServerMessage>,
+    capacity: usize,
+    senders: usize,
+    closed: bool,
+}
+
+struct Shared {
+    queue: Mutex<Queue>,
+    notify: Notify,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Sending half of a player's outgoing queue. The queue is bounded so that a client that
+/// stops reading cannot make the server buffer messages without limit: once full, the oldest
+/// droppable message makes room, and if there is none the connection is closed.
+pub struct Tx {
+    shared: Arc<Shared>,
+}
+
+/// Receiving half, drained by the connection's socket task. `recv` returns `None` once every
+/// `Tx` is dropped or the queue overflowed.
+pub struct Rx {
+    shared: Arc<Shared>,
+}
+
+pub fn channel(capacity: usize) -> (Tx, Rx) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            messages: VecDeque::new(),
+            capacity: capacity.max(1),
+            senders: 1,
+            closed: false,
+        }),
+        notify: Notify::new(),
+    });
+    (
+        Tx {
+            shared: Arc::clone(&shared),
+        },
+        Rx { shared },
+    )
+}
+
+impl Tx {
+    pub fn send(&self, msg: ServerMessage) -> Result<(), SendError> {
+        let mut queue = self.shared.lock();
+        if queue.closed {
+            return Err(SendError::Closed);
+        }
+        if queue.messages.len() >= queue.capacity {
+            if let Some(oldest) = queue.messages.iter().position(is_droppable) {
+                queue.messages.remove(oldest);
+            } else if is_droppable(&msg) {
+                return Ok(());
+            } else {
+                queue.closed = true;
+                queue.messages.clear();
+                drop(queue);
+                self.shared.notify.notify_one();
+                return Err(SendError::Overflow);
+            }
+        }
+        queue.messages.push_back(msg);
+        drop(queue);
+        self.shared.notify.notify_one();
+        Ok(())
+    }
+}
+
+impl Clone for Tx {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl Drop for Tx {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.senders -= 1;
+        let last = queue.senders == 0;
+        drop(queue);
+        if last {
+            self.shared.notify.notify_one();
+        }
+    }
+}
+
+impl Rx {
+    pub async fn recv(&mut self) -> Option<ServerMessage> {
+        loop {
+            match self.try_recv() {
+                Ok(msg) => return Some(msg),
+                Err(TryRecvError::Disconnected) => return None,
+                // A permit stored by a send in between makes this return at once
+                Err(TryRecvError::Empty) => self.shared.notify.notified().await,
+            }
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<ServerMessage, TryRecvError> {
+        let mut queue = self.shared.lock();
+        if let Some(msg) = queue.messages.pop_front() {
+            return Ok(msg);
+        }
+        if queue.closed || queue.senders == 0 {
+            Err(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+}
+
+impl Drop for Rx {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.closed = true;
+        queue.messages.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::ErrorCode;
+
+    fn error() -> ServerMessage {
+        ServerMessage::Error {
+            code: ErrorCode::Internal,
+            detail: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_oldest_droppable_message() {
+        let (tx, mut rx) = channel(3);
+        tx.send(ServerMessage::Pong(1)).unwrap();
+        tx.send(error()).unwrap();
+        tx.send(ServerMessage::Pong(2)).unwrap();
+        tx.send(ServerMessage::Pong(3)).unwrap();
+        tx.send(ServerMessage::WaitingForMatch).unwrap();
+
+        assert!(matches!(rx.recv().await, Some(ServerMessage::Error { .. })));
+        assert!(matches!(rx.recv().await, Some(ServerMessage::Pong(3))));
+        assert!(matches!(
+            rx.recv().await,
+            Some(ServerMessage::WaitingForMatch)
+        ));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[tokio::test]
+    async fn test_droppable_message_is_dropped_when_queue_is_all_critical() {
+        let (tx, mut rx) = channel(2);
+        tx.send(error()).unwrap();
+        tx.send(error()).unwrap();
+        assert_eq!(tx.send(ServerMessage::Pong(1)), Ok(()));
+
+        assert!(matches!(rx.recv().await, Some(ServerMessage::Error { .. })));
+        assert!(matches!(rx.recv().await, Some(ServerMessage::Error { .. })));
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[tokio::test]
+    async fn test_overflow_of_critical_messages_closes_the_queue() {
+        let (tx, mut rx) = channel(2);
+        tx.send(error()).unwrap();
+        tx.send(error()).unwrap();
+        assert_eq!(tx.send(error()), Err(SendError::Overflow));
+        assert_eq!(tx.send(ServerMessage::Pong(1)), Err(SendError::Closed));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_recv_ends_after_last_sender_is_dropped() {
+        let (tx, mut rx) = channel(4);
+        let other = tx.clone();
+        tx.send(ServerMessage::WaitingForMatch).unwrap();
+        drop(tx);
+        let waiter = tokio::spawn(async move {
+            let first = rx.recv().await;
+            (first, rx.recv().await)
+        });
+        tokio::task::yield_now().await;
+        drop(other);
+        let (first, second) = waiter.await.unwrap();
+        assert!(matches!(first, Some(ServerMessage::WaitingForMatch)));
+        assert!(second.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_once_receiver_is_gone() {
+        let (tx, rx) = channel(4);
+        drop(rx);
+        assert_eq!(tx.send(ServerMessage::Pong(1)), Err(SendError::Closed));
+    }
+}
@@ -0,0 +1,177 @@
+use shared::{GameMessage, RateLimitKind};
+use std::time::{Duration, Instant};
+
+/// Budget of one message category: `burst` messages at once, refilled at `per_sec`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BucketConfig {
+    pub burst: f64,
+    pub per_sec: f64,
+}
+
+impl BucketConfig {
+    /// Reads `RATE_LIMIT_<NAME>_BURST` / `RATE_LIMIT_<NAME>_PER_SEC`, keeping defaults otherwise.
+    fn from_env(name: &str, default: Self) -> Self {
+        let read = |suffix: &str, fallback: f64| {
+            std::env::var(format!("RATE_LIMIT_{name}_{suffix}"))
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|v| *v > 0.0)
+                .unwrap_or(fallback)
+        };
+        Self {
+            burst: read("BURST", default.burst),
+            per_sec: read("PER_SEC", default.per_sec),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitConfig {
+    pub moves: BucketConfig,
+    pub chat: BucketConfig,
+    pub matchmaking: BucketConfig,
+    pub analysis: BucketConfig,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            moves: BucketConfig {
+                burst: 10.0,
+                per_sec: 5.0,
+            },
+            chat: BucketConfig {
+                burst: 5.0,
+                per_sec: 1.0,
+            },
+            matchmaking: BucketConfig {
+                burst: 3.0,
+                per_sec: 0.5,
+            },
+            analysis: BucketConfig {
+                burst: 20.0,
+                per_sec: 5.0,
+            },
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            moves: BucketConfig::from_env("MOVES", defaults.moves),
+            chat: BucketConfig::from_env("CHAT", defaults.chat),
+            matchmaking: BucketConfig::from_env("MATCHMAKING", defaults.matchmaking),
+            analysis: BucketConfig::from_env("ANALYSIS", defaults.analysis),
+        }
+    }
+
+    pub const fn bucket(&self, kind: RateLimitKind) -> BucketConfig {
+        match kind {
+            RateLimitKind::Move => self.moves,
+            RateLimitKind::Chat => self.chat,
+            RateLimitKind::Matchmaking => self.matchmaking,
+            RateLimitKind::Analysis => self.analysis,
+        }
+    }
+}
+
+/// Which budget a client message is charged against; `None` for unmetered keepalives.
+pub const fn message_kind(msg: &GameMessage) -> Option<RateLimitKind> {
+    match msg {
+        GameMessage::MakeMove { .. }
+        | GameMessage::VerifyMove { .. }
+        | GameMessage::RequestMoves { .. } => Some(RateLimitKind::Move),
+        GameMessage::FindMatch
+        | GameMessage::FindHandicapMatch(_)
+        | GameMessage::CancelFindMatch
+        | GameMessage::PlayAgain
+        | GameMessage::PlayerLeft
+        | GameMessage::SubscribeLiveGames
+        | GameMessage::UnsubscribeLiveGames
+        | GameMessage::Spectate(_)
+        | GameMessage::StopSpectating => Some(RateLimitKind::Matchmaking),
+        // Player-to-player and profile messages share the chat budget
+        GameMessage::Surrender
+        | GameMessage::RequestDraw
+        | GameMessage::AcceptDraw
+        | GameMessage::SetName(_)
+        | GameMessage::AddFriend(_)
+        | GameMessage::RemoveFriend(_)
+        | GameMessage::ListFriends
+        | GameMessage::ChallengeFriend { .. }
+        | GameMessage::AcceptChallenge(_)
+        | GameMessage::DeclineChallenge(_) => Some(RateLimitKind::Chat),
+        GameMessage::QueryEval(_) | GameMessage::SubmitEval(_) => Some(RateLimitKind::Analysis),
+        GameMessage::Ping(_) => None,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub const fn new(config: BucketConfig, now: Instant) -> Self {
+        Self {
+            tokens: config.burst,
+            last_refill: now,
+        }
+    }
+
+    /// Takes one token, or returns how long until one becomes available.
+    pub fn try_take(&mut self, config: BucketConfig, now: Instant) -> Result<(), Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * config.per_sec).min(config.burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / config.per_sec,
+            ))
+        }
+    }
+}
+
+/// Per-player buckets, one per message category.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    moves: TokenBucket,
+    chat: TokenBucket,
+    matchmaking: TokenBucket,
+    analysis: TokenBucket,
+}
+
+impl RateLimiter {
+    pub const fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        Self {
+            moves: TokenBucket::new(config.moves, now),
+            chat: TokenBucket::new(config.chat, now),
+            matchmaking: TokenBucket::new(config.matchmaking, now),
+            analysis: TokenBucket::new(config.analysis, now),
+        }
+    }
+
+    pub fn check(
+        &mut self,
+        config: &RateLimitConfig,
+        kind: RateLimitKind,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let bucket = match kind {
+            RateLimitKind::Move => &mut self.moves,
+            RateLimitKind::Chat => &mut self.chat,
+            RateLimitKind::Matchmaking => &mut self.matchmaking,
+            RateLimitKind::Analysis => &mut self.analysis,
+        };
+        bucket.try_take(config.bucket(kind), now)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::game_manager::lifecycle::name_key;
+use crate::game_manager::{session::now_ms, AppState, GameSession};
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::opening::classify_opening;
+use shared::{LeaderboardEntry, LeaderboardPage, OpeningCount, PlayerProfile, RatingPoint};
+use std::collections::{HashMap, VecDeque};
+
+pub const DEFAULT_RATING: i32 = 1200;
+pub const MAX_PAGE_SIZE: usize = 100;
+const K_FACTOR: f64 = 32.0;
+/// Rating points kept per player for the profile chart.
+const MAX_HISTORY: usize = 100;
+const FAVORITE_OPENINGS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct PlayerRating {
+    pub name: String,
+    pub rating: i32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub history: VecDeque<RatingPoint>,
+    /// Games per opening the player chose, by book name.
+    pub openings: HashMap<&'static str, u32>,
+    /// Sum of the accuracies of the games the engine reviewed.
+    pub accuracy_total: f64,
+    pub reviewed_games: u32,
+}
+
+impl PlayerRating {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rating: DEFAULT_RATING,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            history: VecDeque::new(),
+            openings: HashMap::new(),
+            accuracy_total: 0.0,
+            reviewed_games: 0,
+        }
+    }
+
+    pub const fn games_played(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    pub fn profile(&self) -> PlayerProfile {
+        let mut openings: Vec<OpeningCount> = self
+            .openings
+            .iter()
+            .map(|(name, games)| OpeningCount {
+                name: (*name).to_string(),
+                games: *games,
+            })
+            .collect();
+        openings.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.name.cmp(&b.name)));
+        openings.truncate(FAVORITE_OPENINGS);
+        PlayerProfile {
+            name: self.name.clone(),
+            rating: self.rating,
+            wins: self.wins,
+            losses: self.losses,
+            draws: self.draws,
+            rating_history: self.history.iter().copied().collect(),
+            favorite_openings: openings,
+            average_accuracy: (self.reviewed_games > 0)
+                .then(|| self.accuracy_total / f64::from(self.reviewed_games)),
+        }
+    }
+}
+
+/// Expected score of a player rated `rating` against `opponent` (standard Elo curve).
+fn expected_score(rating: i32, opponent: i32) -> f64 {
+    1.0 / (1.0 + 10f64.powf(f64::from(opponent - rating) / 400.0))
+}
+
+/// Rating change for a player scoring `score` (1.0 win, 0.5 draw, 0.0 loss).
+pub fn elo_delta(rating: i32, opponent: i32, score: f64) -> i32 {
+    (K_FACTOR * (score - expected_score(rating, opponent))).round() as i32
+}
+
+impl AppState {
+    /// Archives a finished game for replay links, applies its result to both players'
+    /// ratings and queues it for the anti-cheat check.
+    pub fn record_result(&self, game_id: &str, game: &GameSession, winner: Option<Color>) {
+        self.replays.insert(game.replay(game_id));
+        // Ratings are keyed by player id, which a returning client keeps through its token
+        let red_key = game.red_player.clone();
+        let black_key = game.black_player.clone();
+        if red_key == black_key {
+            return;
+        }
+
+        let red_rating = self
+            .ratings
+            .get(&red_key)
+            .map_or(DEFAULT_RATING, |r| r.rating);
+        let black_rating = self
+            .ratings
+            .get(&black_key)
+            .map_or(DEFAULT_RATING, |r| r.rating);
+
+        let red_score = match winner {
+            Some(Color::Red) => 1.0,
+            Some(Color::Black) => 0.0,
+            None => 0.5,
+        };
+        let red_delta = elo_delta(red_rating, black_rating, red_score);
+        let black_delta = elo_delta(black_rating, red_rating, 1.0 - red_score);
+
+        for (key, name, delta, color) in [
+            (red_key, &game.red_name, red_delta, Color::Red),
+            (black_key, &game.black_name, black_delta, Color::Black),
+        ] {
+            let mut entry = self
+                .ratings
+                .entry(key)
+                .or_insert_with(|| PlayerRating::new(name));
+            entry.name.clone_from(name);
+            entry.rating += delta;
+            match winner {
+                Some(w) if w == color => entry.wins += 1,
+                Some(_) => entry.losses += 1,
+                None => entry.draws += 1,
+            }
+            let point = RatingPoint {
+                time_ms: now_ms(),
+                rating: entry.rating,
+            };
+            if entry.history.len() == MAX_HISTORY {
+                entry.history.pop_front();
+            }
+            entry.history.push_back(point);
+            if let Some(opening) = classify_opening(&game.session.state, color) {
+                *entry.openings.entry(opening).or_insert(0) += 1;
+            }
+        }
+
+        tracing::info!(
+            red = %game.red_name,
+            black = %game.black_name,
+            red_delta,
+            black_delta,
+            "Ratings updated"
+        );
+        self.anticheat.submit(game_id, game);
+    }
+
+    /// Returns one page of the leaderboard, sorted by rating, optionally filtered by name.
+    pub fn leaderboard(
+        &self,
+        page: usize,
+        per_page: usize,
+        search: Option<&str>,
+    ) -> LeaderboardPage {
+        let per_page = per_page.clamp(1, MAX_PAGE_SIZE);
+        let page = page.max(1);
+        let needle = search
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_lowercase);
+
+        let mut ranked: Vec<PlayerRating> = self
+            .ratings
+            .iter()
+            .map(|entry| entry.value().clone())
+            .collect();
+        ranked.sort_by(|a, b| b.rating.cmp(&a.rating).then_with(|| a.name.cmp(&b.name)));
+
+        // Ranks are global, so they are assigned before the search filter is applied.
+        let matching: Vec<LeaderboardEntry> = ranked
+            .into_iter()
+            .enumerate()
+            .filter(|(_, r)| {
+                needle
+                    .as_ref()
+                    .is_none_or(|n| r.name.to_lowercase().contains(n.as_str()))
+            })
+            .map(|(i, r)| LeaderboardEntry {
+                rank: i + 1,
+                games_played: r.games_played(),
+                name: r.name,
+                rating: r.rating,
+                wins: r.wins,
+                losses: r.losses,
+                draws: r.draws,
+            })
+            .collect();
+
+        let total = matching.len();
+        let entries = matching
+            .into_iter()
+            .skip((page - 1) * per_page)
+            .take(per_page)
+            .collect();
+
+        LeaderboardPage {
+            entries,
+            page,
+            per_page,
+            total,
+        }
+    }
+
+    /// Counts the engine's accuracy for one of the player's rated games.
+    pub fn record_accuracy(&self, player_id: &str, accuracy: f64) {
+        if let Some(mut entry) = self.ratings.get_mut(player_id) {
+            entry.accuracy_total += accuracy;
+            entry.reviewed_games += 1;
+        }
+    }
+
+    /// The profile of the rated player going by `name`; names are unique among rated players.
+    pub fn player_profile(&self, name: &str) -> Option<PlayerProfile> {
+        let key = name_key(name.trim());
+        self.ratings
+            .iter()
+            .find(|entry| name_key(&entry.name) == key)
+            .map(|entry| entry.profile())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::game_manager::{AppState, GameSession};
+use cotuong_core::engine::match_runner::GameResult;
+use cotuong_core::engine::review::positions;
+use cotuong_core::logic::share::moves_to_param;
+use shared::GameReplay;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+const DEFAULT_CAPACITY: usize = 1000;
+
+impl GameSession {
+    /// The game so far, for `/replay/:game_id` links.
+    pub fn replay(&self, game_id: &str) -> GameReplay {
+        let state = &self.session.state;
+        let start = positions(state).into_iter().next().unwrap_or_default();
+        let moves: Vec<_> = state.history.iter().map(|r| (r.from, r.to)).collect();
+        GameReplay {
+            game_id: game_id.to_string(),
+            red_name: self.red_name.clone(),
+            black_name: self.black_name.clone(),
+            start_fen: start.board.to_fen_string(start.turn),
+            moves: moves_to_param(&moves),
+            result: self
+                .session
+                .result()
+                .map_or("*", |r| GameResult::from_session(r).pgn())
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Archive {
+    games: HashMap<String, GameReplay>,
+    /// Game ids, oldest first.
+    order: VecDeque<String>,
+}
+
+/// The most recently finished games, kept after their session is closed so shared replay
+/// links keep working. A rematch replaces the earlier game under the same id.
+pub struct ReplayArchive {
+    capacity: usize,
+    archive: Mutex<Archive>,
+}
+
+impl ReplayArchive {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            archive: Mutex::new(Archive::default()),
+        }
+    }
+
+    /// Reads `REPLAY_ARCHIVE_SIZE` (number of games), falling back to the default.
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var("REPLAY_ARCHIVE_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_CAPACITY),
+        )
+    }
+
+    pub fn insert(&self, replay: GameReplay) {
+        let Ok(mut archive) = self.archive.lock() else {
+            return;
+        };
+        if archive.games.contains_key(&replay.game_id) {
+            archive.order.retain(|id| *id != replay.game_id);
+        } else if archive.games.len() >= self.capacity {
+            if let Some(oldest) = archive.order.pop_front() {
+                archive.games.remove(&oldest);
+            }
+        }
+        archive.order.push_back(replay.game_id.clone());
+        archive.games.insert(replay.game_id.clone(), replay);
+    }
+
+    pub fn get(&self, game_id: &str) -> Option<GameReplay> {
+        self.archive.lock().ok()?.games.get(game_id).cloned()
+    }
+}
+
+impl AppState {
+    /// A game hosted here as played so far, or a finished one from the archive.
+    pub async fn game_replay(&self, game_id: &str) -> Option<GameReplay> {
+        if let Some(game_lock) = self.games.get(game_id) {
+            let game = game_lock.read().await;
+            return Some(game.replay(game_id));
+        }
+        self.replays.get(game_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replay(game_id: &str, result: &str) -> GameReplay {
+        GameReplay {
+            game_id: game_id.to_string(),
+            red_name: "Red".to_string(),
+            black_name: "Black".to_string(),
+            start_fen: String::new(),
+            moves: String::new(),
+            result: result.to_string(),
+        }
+    }
+
+    #[test]
+    fn oldest_game_is_evicted_first() {
+        let archive = ReplayArchive::new(2);
+        archive.insert(replay("a", "1-0"));
+        archive.insert(replay("b", "1-0"));
+        // A rematch replaces "a" and makes it the newest
+        archive.insert(replay("a", "0-1"));
+        archive.insert(replay("c", "1-0"));
+        assert!(archive.get("b").is_none());
+        assert_eq!(archive.get("a").map(|r| r.result), Some("0-1".to_string()));
+        assert!(archive.get("c").is_some());
+    }
+}
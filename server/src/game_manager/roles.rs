@@ -0,0 +1,94 @@
+use crate::game_manager::AppState;
+use std::collections::HashMap;
+
+/// How a player takes part in a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameRole {
+    Player,
+    Spectator,
+}
+
+/// The games one connected player takes part in.
+///
+/// A player plays at most one game at a time, but may follow others while queued or playing.
+#[derive(Debug, Default, Clone)]
+pub struct GameRoles(HashMap<String, GameRole>);
+
+impl GameRoles {
+    /// The game the player is seated at, if any.
+    pub fn playing(&self) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(_, role)| **role == GameRole::Player)
+            .map(|(game_id, _)| game_id.as_str())
+    }
+
+    pub fn spectating(&self) -> impl Iterator<Item = &str> {
+        self.0
+            .iter()
+            .filter(|(_, role)| **role == GameRole::Spectator)
+            .map(|(game_id, _)| game_id.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl AppState {
+    /// The game `player_id` plays in on this instance.
+    pub fn game_of(&self, player_id: &str) -> Option<String> {
+        self.game_roles
+            .get(player_id)
+            .and_then(|roles| roles.playing().map(str::to_string))
+    }
+
+    pub fn is_playing(&self, player_id: &str) -> bool {
+        self.game_roles
+            .get(player_id)
+            .is_some_and(|roles| roles.playing().is_some())
+    }
+
+    pub fn spectated_games(&self, player_id: &str) -> Vec<String> {
+        self.game_roles
+            .get(player_id)
+            .map(|roles| roles.spectating().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Gives `player_id` a role in `game_id`, replacing any role they had in it.
+    pub fn add_role(&self, player_id: &str, game_id: &str, role: GameRole) {
+        self.game_roles
+            .entry(player_id.to_string())
+            .or_default()
+            .0
+            .insert(game_id.to_string(), role);
+    }
+
+    /// Drops the role `player_id` has in `game_id` and returns it.
+    pub fn remove_role(&self, player_id: &str, game_id: &str) -> Option<GameRole> {
+        self.update_roles(player_id, |roles| roles.0.remove(game_id))
+    }
+
+    /// Unseats `player_id` from the game they play in, keeping the games they follow.
+    /// Returns that game.
+    pub fn leave_seat(&self, player_id: &str) -> Option<String> {
+        self.update_roles(player_id, |roles| {
+            let game_id = roles.playing()?.to_string();
+            roles.0.remove(&game_id);
+            Some(game_id)
+        })
+    }
+
+    /// Applies `f` to the player's roles, forgetting players left without any.
+    fn update_roles<R>(&self, player_id: &str, f: impl FnOnce(&mut GameRoles) -> R) -> R {
+        let Some(mut roles) = self.game_roles.get_mut(player_id) else {
+            return f(&mut GameRoles::default());
+        };
+        let result = f(&mut roles);
+        drop(roles);
+        self.game_roles
+            .remove_if(player_id, |_, roles| roles.is_empty());
+        result
+    }
+}
@@ -1,34 +1,96 @@
-use cotuong_core::{
-    engine::Move,
-    logic::board::{Board, Color},
-};
-use shared::ServerMessage;
-use tokio::sync::mpsc;
+use cotuong_core::{engine::Move, logic::handicap::HandicapKind, session::Session};
+use shared::{DisconnectPolicy, MatchScore, ServerMessage};
+use tokio::sync::watch;
 
+use crate::game_manager::outbox::Tx;
+use crate::game_manager::rate_limit::RateLimiter;
 use std::time::Instant;
 
-pub type Tx = mpsc::UnboundedSender<ServerMessage>;
-
 pub struct Player {
     pub tx: Tx,
-    pub last_msg_at: Instant,
+    pub name: String,
+    pub rate_limiter: RateLimiter,
+    /// When the last message (of any kind) arrived from this player.
+    pub last_seen: Instant,
 }
 
 pub struct GameSession {
     pub red_player: String,
     pub black_player: String,
-    pub board: Board,
-    pub turn: Color,
-    pub game_ended: bool,
+    pub red_name: String,
+    pub black_name: String,
+    /// Board, turn order and result, shared with the clients' rules.
+    pub session: Session,
+    /// Start position for rematches.
+    pub handicap: HandicapKind,
     pub red_ready_for_rematch: bool,
     pub black_ready_for_rematch: bool,
     pub pending_move: Option<(String, Move, String)>,
     pub last_activity: Instant,
+    /// When the current game (or rematch) started, to abort games nobody plays.
+    pub started_at: Instant,
+    /// When the side to move got the turn, for the per-move timeout.
+    pub turn_started: Instant,
+    /// Whether the side to move was already sent `ServerMessage::MoveTimeWarning`.
+    pub move_warning_sent: bool,
+    /// Players following this game via `GameMessage::Spectate`.
+    pub spectators: Vec<String>,
+    /// Bumped whenever spectators are told about a change, for PGN followers.
+    pub changes: watch::Sender<()>,
+    /// Earlier games between the two players, from the current red player's side.
+    pub score: MatchScore,
+    /// Label agreed on in a friend challenge; games have no clock yet.
+    pub time_control: Option<String>,
+    /// What happens when a player drops out mid-game.
+    pub disconnect_policy: DisconnectPolicy,
+    /// The player whose seat is held open after they dropped out, and until when.
+    pub away: Option<(String, Instant)>,
+}
+
+impl GameSession {
+    /// The verified moves from ply `from_seq` on, numbered like `OpponentMove::seq`. `None`
+    /// when `from_seq` is ahead of the game or before the first move this server saw, as in
+    /// games restored from a snapshot.
+    pub fn moves_since(&self, from_seq: u32) -> Option<Vec<Move>> {
+        let history = &self.session.state.history;
+        let played = self.session.move_count();
+        let first = played.saturating_sub(u32::try_from(history.len()).ok()?);
+        let skip = usize::try_from(from_seq.checked_sub(first)?).ok()?;
+        let missing = history.get(skip..)?;
+        Some(
+            missing
+                .iter()
+                .map(|record| Move {
+                    from_row: record.from.row as u8,
+                    from_col: record.from.col as u8,
+                    to_row: record.to.row as u8,
+                    to_col: record.to.col as u8,
+                    score: 0,
+                })
+                .collect(),
+        )
+    }
+
+    /// Hands the turn over: the next player's move timer starts now.
+    pub fn start_turn(&mut self, now: Instant) {
+        self.turn_started = now;
+        self.move_warning_sent = false;
+    }
+
+    /// The full position, for clients whose board disagrees with the server's.
+    pub fn correction(&self) -> ServerMessage {
+        let turn = self.session.turn();
+        ServerMessage::GameStateCorrection {
+            fen: self.session.board().to_fen_string(turn),
+            turn,
+            seq: self.session.move_count(),
+        }
+    }
 }
 
-pub fn has_any_valid_move(board: &Board, color: Color) -> bool {
-    use cotuong_core::logic::generator::MoveGenerator;
-    let generator = MoveGenerator::new();
-    let moves = generator.generate_moves(board, color);
-    !moves.is_empty()
+/// Wall-clock milliseconds, the time base for session clocks.
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
 }
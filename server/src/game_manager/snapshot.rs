@@ -0,0 +1,137 @@
+use crate::game_manager::{AppState, GameRole, GameSession};
+use cotuong_core::logic::board::{Board, Color};
+use cotuong_core::logic::handicap::HandicapKind;
+use cotuong_core::session::Session;
+use serde::{Deserialize, Serialize};
+use shared::{DisconnectPolicy, MatchScore, ServerMessage};
+use std::path::Path;
+use std::time::Instant;
+use tokio::sync::{watch, RwLock};
+
+/// Serializable view of a `GameSession`. Boards are stored as FEN so snapshots stay readable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub game_id: String,
+    pub red_player: String,
+    pub black_player: String,
+    pub red_name: String,
+    pub black_name: String,
+    pub fen: String,
+    pub turn: Color,
+    pub game_ended: bool,
+    #[serde(default)]
+    pub move_count: u32,
+    #[serde(default)]
+    pub handicap: HandicapKind,
+    /// Red's score in the earlier games of the rematch series.
+    #[serde(default)]
+    pub score: MatchScore,
+    #[serde(default)]
+    pub time_control: Option<String>,
+    #[serde(default)]
+    pub disconnect_policy: DisconnectPolicy,
+}
+
+impl AppState {
+    /// Tells every connected player that the server is about to go down.
+    pub fn notify_shutdown(&self, seconds: u64) {
+        tracing::info!(
+            seconds,
+            players = self.players.len(),
+            "Notifying players of shutdown"
+        );
+        for player in self.players.iter() {
+            let _ = player
+                .tx
+                .send(ServerMessage::ServerShuttingDown { seconds });
+        }
+    }
+
+    /// Collects all unfinished games. Pending (unverified) moves are dropped.
+    pub async fn snapshot_sessions(&self) -> Vec<SessionSnapshot> {
+        let mut snapshots = Vec::new();
+        for entry in self.games.iter() {
+            let game = entry.value().read().await;
+            if game.session.is_over() {
+                continue;
+            }
+            snapshots.push(SessionSnapshot {
+                game_id: entry.key().clone(),
+                red_player: game.red_player.clone(),
+                black_player: game.black_player.clone(),
+                red_name: game.red_name.clone(),
+                black_name: game.black_name.clone(),
+                fen: game.session.board().to_fen_string(game.session.turn()),
+                turn: game.session.turn(),
+                game_ended: false,
+                move_count: game.session.move_count(),
+                handicap: game.handicap,
+                score: game.score,
+                time_control: game.time_control.clone(),
+                disconnect_policy: game.disconnect_policy,
+            });
+        }
+        snapshots
+    }
+
+    pub async fn save_snapshot(&self, path: &Path) -> std::io::Result<usize> {
+        let snapshots = self.snapshot_sessions().await;
+        let json = serde_json::to_string_pretty(&snapshots)?;
+        tokio::fs::write(path, json).await?;
+        tracing::info!(path = %path.display(), games = snapshots.len(), "Saved game snapshot");
+        Ok(snapshots.len())
+    }
+
+    /// Loads sessions written by `save_snapshot`. Restored games keep their player ids, so they
+    /// are resumable once those players reattach; otherwise the cleanup task reaps them.
+    pub async fn restore_snapshot(&self, path: &Path) -> std::io::Result<usize> {
+        let json = tokio::fs::read_to_string(path).await?;
+        let snapshots: Vec<SessionSnapshot> = serde_json::from_str(&json)?;
+
+        let mut restored = 0;
+        for snap in snapshots {
+            // Only unfinished games are saved; a finished one has nothing to resume
+            if snap.game_ended {
+                continue;
+            }
+            let session = match Board::from_fen(&snap.fen) {
+                Ok((board, _)) => Session::from_position(board, snap.turn, snap.move_count),
+                Err(e) => {
+                    tracing::warn!(game_id = %snap.game_id, error = %e, "Skipping snapshot with invalid FEN");
+                    continue;
+                }
+            };
+
+            self.add_role(&snap.red_player, &snap.game_id, GameRole::Player);
+            self.add_role(&snap.black_player, &snap.game_id, GameRole::Player);
+            self.games.insert(
+                snap.game_id,
+                RwLock::new(GameSession {
+                    red_player: snap.red_player,
+                    black_player: snap.black_player,
+                    red_name: snap.red_name,
+                    black_name: snap.black_name,
+                    session,
+                    handicap: snap.handicap,
+                    red_ready_for_rematch: false,
+                    black_ready_for_rematch: false,
+                    pending_move: None,
+                    last_activity: Instant::now(),
+                    started_at: Instant::now(),
+                    turn_started: Instant::now(),
+                    move_warning_sent: false,
+                    spectators: Vec::new(),
+                    changes: watch::channel(()).0,
+                    score: snap.score,
+                    time_control: snap.time_control,
+                    disconnect_policy: snap.disconnect_policy,
+                    away: None,
+                }),
+            );
+            restored += 1;
+        }
+
+        tracing::info!(path = %path.display(), games = restored, "Restored game snapshot");
+        Ok(restored)
+    }
+}
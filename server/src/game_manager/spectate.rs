@@ -0,0 +1,179 @@
+use crate::game_manager::{AppState, GameRole, GameSession};
+use cotuong_core::engine::match_runner::{write_pgn, GameResult};
+use cotuong_core::engine::review::positions;
+use cotuong_core::logic::board::{Board, Color};
+use cotuong_core::logic::opening::opening_name;
+use shared::{ErrorCode, LiveGame, ServerMessage};
+use tokio::sync::watch;
+
+impl GameSession {
+    pub fn live_game(&self, game_id: &str) -> LiveGame {
+        LiveGame {
+            game_id: game_id.to_string(),
+            red_name: self.red_name.clone(),
+            black_name: self.black_name.clone(),
+            move_count: self.session.move_count(),
+            time_control: self.time_control.clone(),
+            opening: opening_name(&self.session.state),
+        }
+    }
+
+    /// The game so far as PGN; the result is `*` while it is being played.
+    pub fn pgn(&self, game_id: &str) -> String {
+        let state = &self.session.state;
+        let mut tags = vec![
+            ("Event", "Cờ tướng trực tuyến".to_string()),
+            ("GameId", game_id.to_string()),
+            ("Red", self.red_name.clone()),
+            ("Black", self.black_name.clone()),
+        ];
+        // Handicap games and games resumed from a snapshot do not start from the usual setup
+        let start = positions(state).into_iter().next().unwrap_or_default();
+        let start_fen = start.board.to_fen_string(start.turn);
+        if start_fen != Board::new().to_fen_string(Color::Red) {
+            tags.push(("FEN", start_fen));
+        }
+        if let Some(opening) = opening_name(state) {
+            tags.push(("Opening", opening));
+        }
+        let result = self.session.result();
+        if let Some(result) = result {
+            tags.push(("Termination", result.reason.as_str().to_string()));
+        }
+        let moves: Vec<_> = state.history.iter().map(|r| (r.from, r.to)).collect();
+        let result = result.map_or("*", |r| GameResult::from_session(r).pgn());
+        write_pgn(&tags, &moves, &[], result)
+    }
+
+    pub fn spectator_update(&self, game_id: &str) -> ServerMessage {
+        ServerMessage::SpectatorUpdate {
+            game: self.live_game(game_id),
+            fen: self.session.board().to_fen_string(self.session.turn()),
+        }
+    }
+}
+
+impl AppState {
+    /// Unfinished games hosted by this instance, longest-running first.
+    pub async fn live_games(&self) -> Vec<LiveGame> {
+        let mut live = Vec::new();
+        for entry in self.games.iter() {
+            let game = entry.value().read().await;
+            if !game.session.is_over() {
+                live.push(game.live_game(entry.key()));
+            }
+        }
+        live.sort_by(|a, b| {
+            b.move_count
+                .cmp(&a.move_count)
+                .then_with(|| a.game_id.cmp(&b.game_id))
+        });
+        live
+    }
+
+    pub async fn subscribe_live_games(&self, player_id: &str) {
+        self.live_subscribers.insert(player_id.to_string());
+        let games = self.live_games().await;
+        self.send_to(player_id, ServerMessage::LiveGames(games));
+    }
+
+    pub fn unsubscribe_live_games(&self, player_id: &str) {
+        self.live_subscribers.remove(player_id);
+    }
+
+    /// Pushes the current list to every subscriber.
+    ///
+    /// Must not be called while holding a game lock, since it reads every game.
+    pub async fn broadcast_live_games(&self) {
+        if self.live_subscribers.is_empty() {
+            return;
+        }
+        let games = self.live_games().await;
+        for subscriber in self.live_subscribers.iter() {
+            self.send_to(subscriber.key(), ServerMessage::LiveGames(games.clone()));
+        }
+    }
+
+    /// Starts following `game_id`, leaving any game the player was spectating before.
+    ///
+    /// Following a game does not touch the player's own game or their place in the queue.
+    pub async fn spectate(&self, player_id: &str, game_id: &str) {
+        self.stop_spectating(player_id).await;
+
+        let Some(game_lock) = self.games.get(game_id) else {
+            tracing::debug!(player_id = %player_id, game_id = %game_id, "Spectate request for unknown game");
+            self.send_error(player_id, ErrorCode::GameNotFound, None);
+            return;
+        };
+        let mut game = game_lock.write().await;
+        if game.red_player == player_id || game.black_player == player_id {
+            tracing::debug!(player_id = %player_id, game_id = %game_id, "Player tried to spectate their own game");
+            return;
+        }
+        if game.session.is_over() {
+            drop(game);
+            self.send_error(player_id, ErrorCode::GameAlreadyEnded, None);
+            return;
+        }
+
+        game.spectators.push(player_id.to_string());
+        let update = game.spectator_update(game_id);
+        drop(game);
+
+        tracing::info!(player_id = %player_id, game_id = %game_id, "Player started spectating");
+        self.add_role(player_id, game_id, GameRole::Spectator);
+        self.send_to(player_id, update);
+    }
+
+    /// Stops following every game the player spectates.
+    pub async fn stop_spectating(&self, player_id: &str) {
+        for game_id in self.spectated_games(player_id) {
+            self.remove_role(player_id, &game_id);
+            if let Some(game_lock) = self.games.get(&game_id) {
+                game_lock
+                    .write()
+                    .await
+                    .spectators
+                    .retain(|id| id != player_id);
+            }
+            tracing::info!(player_id = %player_id, game_id = %game_id, "Player stopped spectating");
+        }
+    }
+
+    /// The PGN of a game hosted here and whether it has finished.
+    pub async fn game_pgn(&self, game_id: &str) -> Option<(String, bool)> {
+        let game_lock = self.games.get(game_id)?;
+        let game = game_lock.read().await;
+        Some((game.pgn(game_id), game.session.is_over()))
+    }
+
+    /// Wakes up on every change to the game; closes when the game is removed.
+    pub async fn follow_game(&self, game_id: &str) -> Option<watch::Receiver<()>> {
+        let game_lock = self.games.get(game_id)?;
+        let receiver = game_lock.read().await.changes.subscribe();
+        Some(receiver)
+    }
+
+    /// Everything spectators are told about is also a change for PGN followers.
+    pub fn send_to_spectators(&self, game: &GameSession, msg: &ServerMessage) {
+        game.changes.send_replace(());
+        for spectator in &game.spectators {
+            self.send_to(spectator, msg.clone());
+        }
+    }
+
+    /// Tells the spectators of a game that is being removed how it ended and detaches them.
+    pub fn release_spectators(
+        &self,
+        game_id: &str,
+        game: &GameSession,
+        end: Option<&ServerMessage>,
+    ) {
+        for spectator in &game.spectators {
+            if let Some(msg) = end {
+                self.send_to(spectator, msg.clone());
+            }
+            self.remove_role(spectator, game_id);
+        }
+    }
+}
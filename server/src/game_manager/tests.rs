@@ -1,11 +1,16 @@
 use super::*;
+use crate::game_manager::outbox::{self, Rx};
 use cotuong_core::logic::board::{Board, Color};
+use cotuong_core::logic::handicap::HandicapKind;
 use shared::ServerMessage;
 use std::time::Duration;
-use tokio::sync::mpsc;
+
+fn channel() -> (Tx, Rx) {
+    outbox::channel(outbox::DEFAULT_OUTBOX_CAPACITY)
+}
 
 // Helper to receive next message with timeout
-async fn expect_msg_timeout(rx: &mut mpsc::UnboundedReceiver<ServerMessage>) -> ServerMessage {
+async fn expect_msg_timeout(rx: &mut Rx) -> ServerMessage {
     tokio::time::timeout(Duration::from_millis(1500), rx.recv())
         .await
         .expect("Timed out waiting for message")
@@ -13,14 +18,10 @@ async fn expect_msg_timeout(rx: &mut mpsc::UnboundedReceiver<ServerMessage>) ->
 }
 
 // Drain setup messages (MatchFound, GameStart, Waiting)
-async fn drain_setup_messages(rx: &mut mpsc::UnboundedReceiver<ServerMessage>) {
-    loop {
-        match tokio::time::timeout(Duration::from_millis(50), rx.recv()).await {
-            Ok(Some(msg)) => match msg {
-                ServerMessage::GameStart(_) => break,
-                _ => continue,
-            },
-            _ => break,
+async fn drain_setup_messages(rx: &mut Rx) {
+    while let Ok(Some(msg)) = tokio::time::timeout(Duration::from_millis(50), rx.recv()).await {
+        if let ServerMessage::GameStart(_) = msg {
+            break;
         }
     }
 }
@@ -28,8 +29,8 @@ async fn drain_setup_messages(rx: &mut mpsc::UnboundedReceiver<ServerMessage>) {
 #[tokio::test]
 async fn test_happy_path_distributed_validation() {
     let app_state = AppState::new();
-    let (tx1, mut rx1) = mpsc::unbounded_channel();
-    let (tx2, mut rx2) = mpsc::unbounded_channel();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
 
     let p1_id = "p1".to_string();
     let p2_id = "p2".to_string();
@@ -38,19 +39,18 @@ async fn test_happy_path_distributed_validation() {
     app_state.add_player(p2_id.clone(), tx2);
 
     // Matchmake
-    app_state.find_match(p1_id.clone()).await;
-    app_state.find_match(p2_id.clone()).await;
+    app_state
+        .find_match(p1_id.clone(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match(p2_id.clone(), HandicapKind::None)
+        .await;
 
     // Drain setup
     drain_setup_messages(&mut rx1).await;
     drain_setup_messages(&mut rx2).await;
 
-    let game_id = app_state
-        .player_to_game
-        .get(&p1_id)
-        .expect("Game should exist")
-        .value()
-        .clone();
+    let game_id = app_state.game_of(&p1_id).expect("Game should exist");
 
     let game_lock = app_state.games.get(&game_id).expect("Game session missing");
     let game = game_lock.read().await;
@@ -69,7 +69,7 @@ async fn test_happy_path_distributed_validation() {
     let board = Board::new();
     let gen = cotuong_core::logic::generator::MoveGenerator::new();
     let moves = gen.generate_moves(&board, Color::Red);
-    let valid_move = moves.first().expect("Should have moves").clone();
+    let valid_move = *moves.first().expect("Should have moves");
 
     // Calculate expected FEN
     let mut test_board = board.clone();
@@ -78,7 +78,7 @@ async fn test_happy_path_distributed_validation() {
 
     // P1 sends MakeMove
     app_state
-        .handle_move(red_id.clone(), valid_move.clone(), expected_fen.clone())
+        .handle_move(red_id.clone(), valid_move, expected_fen.clone())
         .await;
 
     // Verify Pending
@@ -94,42 +94,76 @@ async fn test_happy_path_distributed_validation() {
     }
 
     // Opponent (Black) should receive OpponentMove
-    let mut opponent_rx = if is_p1_red { &mut rx2 } else { &mut rx1 };
+    let opponent_rx = if is_p1_red { &mut rx2 } else { &mut rx1 };
 
-    match expect_msg_timeout(&mut opponent_rx).await {
-        ServerMessage::OpponentMove { move_data, fen } => {
+    match expect_msg_timeout(opponent_rx).await {
+        ServerMessage::OpponentMove {
+            move_data,
+            fen,
+            seq,
+        } => {
             assert_eq!(move_data.from_row, valid_move.from_row);
             assert_eq!(fen, expected_fen);
+            assert_eq!(seq, 0);
         }
-        other => match expect_msg_timeout(&mut opponent_rx).await {
-            ServerMessage::OpponentMove { move_data, fen } => {
+        other => match expect_msg_timeout(opponent_rx).await {
+            ServerMessage::OpponentMove {
+                move_data,
+                fen,
+                seq,
+            } => {
                 assert_eq!(move_data.from_row, valid_move.from_row);
                 assert_eq!(fen, expected_fen);
+                assert_eq!(seq, 0);
             }
             _ => panic!("Expected OpponentMove, got {:?}", other),
         },
     }
 
+    let mut changes = app_state.follow_game(&game_id).await.unwrap();
+    let (pgn, finished) = app_state.game_pgn(&game_id).await.unwrap();
+    assert!(!finished);
+    assert!(!pgn.contains("1. "));
+
     // Opponent Verifies (TRUE)
     app_state
         .handle_verify_move(black_id.clone(), expected_fen.clone(), true)
         .await;
 
+    // PGN followers see the verified move
+    assert!(changes.has_changed().unwrap());
+    changes.borrow_and_update();
+    let (pgn, _) = app_state.game_pgn(&game_id).await.unwrap();
+    assert!(pgn.contains(&format!("[GameId \"{game_id}\"]")));
+    assert!(pgn.contains("1. "));
+    assert!(pgn.trim_end().ends_with('*'));
+    assert!(!pgn.contains("[FEN"));
+
+    // The mover is told its move went through
+    let mover_rx = if is_p1_red { &mut rx1 } else { &mut rx2 };
+    assert!(matches!(
+        expect_msg_timeout(mover_rx).await,
+        ServerMessage::MoveAccepted { seq: 0 }
+    ));
+
     // Verify Server State Updated
     {
         let game_lock = app_state.games.get(&game_id).unwrap();
         let game = game_lock.read().await;
         assert!(game.pending_move.is_none());
-        assert_eq!(game.turn, Color::Black);
-        assert_eq!(game.board.to_fen_string(Color::Black), expected_fen);
+        assert_eq!(game.session.turn(), Color::Black);
+        assert_eq!(
+            game.session.board().to_fen_string(Color::Black),
+            expected_fen
+        );
     }
 }
 
 #[tokio::test]
 async fn test_conflict_resolution() {
     let app_state = AppState::new();
-    let (tx1, mut rx1) = mpsc::unbounded_channel();
-    let (tx2, mut rx2) = mpsc::unbounded_channel();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
 
     let p1_id = "p1".to_string();
     let p2_id = "p2".to_string();
@@ -137,18 +171,17 @@ async fn test_conflict_resolution() {
     app_state.add_player(p1_id.clone(), tx1);
     app_state.add_player(p2_id.clone(), tx2);
 
-    app_state.find_match(p1_id.clone()).await;
-    app_state.find_match(p2_id.clone()).await;
+    app_state
+        .find_match(p1_id.clone(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match(p2_id.clone(), HandicapKind::None)
+        .await;
 
     drain_setup_messages(&mut rx1).await;
     drain_setup_messages(&mut rx2).await;
 
-    let game_id = app_state
-        .player_to_game
-        .get(&p1_id)
-        .unwrap()
-        .value()
-        .clone();
+    let game_id = app_state.game_of(&p1_id).unwrap();
     let game_lock = app_state.games.get(&game_id).unwrap();
     let game = game_lock.read().await;
     let red_id = game.red_player.clone();
@@ -164,7 +197,7 @@ async fn test_conflict_resolution() {
     let board = Board::new();
     let gen = cotuong_core::logic::generator::MoveGenerator::new();
     let moves = gen.generate_moves(&board, Color::Red);
-    let valid_move = moves.first().unwrap().clone();
+    let valid_move = *moves.first().unwrap();
 
     // Correct FEN
     let mut test_board = board.clone();
@@ -176,7 +209,7 @@ async fn test_conflict_resolution() {
 
     // P1 sends VALID move but claims INITIAL FEN
     app_state
-        .handle_move(red_id.clone(), valid_move.clone(), initial_fen.clone())
+        .handle_move(red_id.clone(), valid_move, initial_fen.clone())
         .await;
 
     let (p1_rx, p2_rx) = if is_p1_red {
@@ -205,9 +238,10 @@ async fn test_conflict_resolution() {
     loop {
         let msg = expect_msg_timeout(&mut *p1_rx).await;
         match msg {
-            ServerMessage::GameStateCorrection { fen, turn } => {
+            ServerMessage::GameStateCorrection { fen, turn, seq } => {
                 assert_eq!(fen, valid_fen);
                 assert_eq!(turn, Color::Black);
+                assert_eq!(seq, 1);
                 break;
             }
             _ => continue,
@@ -218,9 +252,10 @@ async fn test_conflict_resolution() {
     loop {
         let msg = expect_msg_timeout(&mut *p2_rx).await;
         match msg {
-            ServerMessage::GameStateCorrection { fen, turn } => {
+            ServerMessage::GameStateCorrection { fen, turn, seq } => {
                 assert_eq!(fen, valid_fen);
                 assert_eq!(turn, Color::Black);
+                assert_eq!(seq, 1);
                 break;
             }
             _ => continue,
@@ -232,6 +267,1481 @@ async fn test_conflict_resolution() {
         let game_lock = app_state.games.get(&game_id).unwrap();
         let game = game_lock.read().await;
         assert!(game.pending_move.is_none());
-        assert_eq!(game.board.to_fen_string(Color::Black), valid_fen);
+        assert_eq!(game.session.board().to_fen_string(Color::Black), valid_fen);
+    }
+}
+
+#[tokio::test]
+async fn test_surrender_updates_leaderboard() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+
+    let p1_id = "p1".to_string();
+    let p2_id = "p2".to_string();
+
+    app_state.add_player(p1_id.clone(), tx1);
+    app_state.add_player(p2_id.clone(), tx2);
+    app_state.set_player_name(&p1_id, "Alice").await;
+    app_state.set_player_name(&p2_id, "Bob").await;
+
+    app_state
+        .find_match(p1_id.clone(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match(p2_id.clone(), HandicapKind::None)
+        .await;
+
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    // Alice resigns, Bob should gain rating
+    app_state.handle_surrender(p1_id.clone()).await;
+
+    let page = app_state.leaderboard(1, 10, None);
+    assert_eq!(page.total, 2);
+    let top = page
+        .entries
+        .first()
+        .expect("Leaderboard should not be empty");
+    assert_eq!(top.name, "Bob");
+    assert_eq!(top.rank, 1);
+    assert_eq!(top.wins, 1);
+    assert!(top.rating > ratings::DEFAULT_RATING);
+
+    let second = page.entries.get(1).expect("Missing second entry");
+    assert_eq!(second.name, "Alice");
+    assert_eq!(second.losses, 1);
+    assert!(second.rating < ratings::DEFAULT_RATING);
+
+    // Surrendering again must not count the game twice
+    app_state.handle_surrender(p1_id.clone()).await;
+    let page = app_state.leaderboard(1, 10, None);
+    assert_eq!(page.entries.first().unwrap().games_played, 1);
+
+    // The finished game can be replayed after its session is gone
+    let game_id = app_state
+        .games
+        .iter()
+        .next()
+        .map(|entry| entry.key().clone())
+        .expect("Game should still be open for a rematch");
+    app_state.games.clear();
+    let replay = app_state
+        .game_replay(&game_id)
+        .await
+        .expect("Finished game should be archived");
+    let alice_lost = if replay.red_name == "Alice" {
+        "0-1"
+    } else {
+        "1-0"
+    };
+    assert_eq!(replay.result, alice_lost);
+}
+
+#[tokio::test]
+async fn test_ratings_stay_with_the_player_not_the_name() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state.set_player_name("p1", "Alice").await;
+    app_state.set_player_name("p2", "Bob").await;
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+    app_state.handle_surrender("p1".to_string()).await;
+    let token = app_state.signer.issue("p1");
+    app_state.leave_game("p1").await;
+    app_state.remove_player("p1").await;
+
+    // Nobody else can go by a rated player's name, even while they are away
+    let (tx3, mut rx3) = channel();
+    app_state.add_player("p3".to_string(), tx3);
+    app_state.set_player_name("p3", "ALICE").await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx3).await,
+        ServerMessage::Error {
+            code: ErrorCode::NameTaken,
+            ..
+        }
+    ));
+    assert_ne!(app_state.players.get("p3").unwrap().name, "ALICE");
+    app_state.set_player_name("p2", "alice").await;
+    assert_eq!(app_state.players.get("p2").unwrap().name, "Bob");
+
+    // The returning client is the same player, rating included, under any name
+    assert_eq!(app_state.returning_player(&token).as_deref(), Some("p1"));
+    let (tx1, _rx1) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    assert_eq!(app_state.returning_player(&token), None);
+    app_state.set_player_name("p1", "Alicia").await;
+    let profile = app_state.player_profile("alicia").unwrap();
+    assert_eq!(profile.losses, 1);
+    assert!(app_state.player_profile("Alice").is_none());
+}
+
+#[tokio::test]
+async fn test_player_profile() {
+    use cotuong_core::logic::board::BoardCoordinate;
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state.set_player_name("p1", "Alice").await;
+    app_state.set_player_name("p2", "Bob").await;
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+    assert!(app_state.player_profile("Alice").is_none());
+
+    // Central cannon against a screen horse, then Red resigns
+    let game_id = app_state.game_of("p1").unwrap();
+    let red_id = {
+        let game_lock = app_state.games.get(&game_id).unwrap();
+        let mut game = game_lock.write().await;
+        let square = |row, col| BoardCoordinate::new(row, col).unwrap();
+        game.session
+            .play(Color::Red, square(2, 7), square(2, 4), 0)
+            .unwrap();
+        game.session
+            .play(Color::Black, square(9, 7), square(7, 6), 0)
+            .unwrap();
+        game.red_player.clone()
+    };
+    let opening = "Pháo đầu – Bình phong mã";
+    assert_eq!(
+        app_state.live_games().await[0].opening.as_deref(),
+        Some(opening)
+    );
+    let (pgn, _) = app_state.game_pgn(&game_id).await.unwrap();
+    assert!(pgn.contains(&format!("[Opening \"{opening}\"]")));
+
+    app_state.handle_surrender(red_id.clone()).await;
+    let (red_name, black_name) = if red_id == "p1" {
+        ("Alice", "Bob")
+    } else {
+        ("Bob", "Alice")
+    };
+
+    let red = app_state.player_profile(&red_name.to_uppercase()).unwrap();
+    assert_eq!(red.name, red_name);
+    assert_eq!(red.losses, 1);
+    assert_eq!(red.rating_history.len(), 1);
+    assert_eq!(red.rating_history[0].rating, red.rating);
+    assert_eq!(red.favorite_openings[0].name, "Pháo đầu");
+    assert_eq!(red.average_accuracy, None);
+
+    let black_id = if red_id == "p1" { "p2" } else { "p1" };
+    app_state.record_accuracy(black_id, 90.0);
+    app_state.record_accuracy(black_id, 80.0);
+    let black = app_state.player_profile(black_name).unwrap();
+    assert_eq!(black.wins, 1);
+    assert_eq!(black.favorite_openings[0].name, "Bình phong mã");
+    assert_eq!(black.average_accuracy, Some(85.0));
+}
+
+#[tokio::test]
+async fn test_admin_terminate_and_ban() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    let (tx3, mut rx3) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state.add_player("p3".to_string(), tx3);
+    app_state.set_player_name("p1", "Alice").await;
+
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+    app_state
+        .find_match("p3".to_string(), HandicapKind::Horse)
+        .await;
+    let queue = app_state.matchmaking_queue().await.unwrap();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].handicap, HandicapKind::Horse);
+
+    let games = app_state.admin_games().await;
+    assert_eq!(games.len(), 1);
+    let game_id = games[0].game.game_id.clone();
+    assert!(app_state.terminate_game(&game_id).await);
+    assert!(!app_state.terminate_game(&game_id).await);
+    assert!(app_state.games.is_empty());
+    assert!(!app_state.is_playing("p1"));
+    match expect_msg_timeout(&mut rx2).await {
+        ServerMessage::GameEnd { winner, reason } => {
+            assert_eq!(winner, None);
+            assert_eq!(reason, "Terminated");
+        }
+        other => panic!("Expected GameEnd, got {other:?}"),
+    }
+    // Terminated games are not rated
+    assert_eq!(app_state.leaderboard(1, 10, None).total, 0);
+
+    assert_eq!(app_state.announce("Bảo trì lúc 22h"), 3);
+    while let Ok(msg) = rx3.try_recv() {
+        if let ServerMessage::Announcement(text) = msg {
+            assert_eq!(text, "Bảo trì lúc 22h");
+        }
+    }
+
+    assert_eq!(app_state.ban_player("ALICE").await.unwrap(), 1);
+    assert!(!app_state.players.contains_key("p1"));
+    assert_eq!(app_state.banned_players().await.unwrap(), ["alice"]);
+    // The name cannot be taken again
+    app_state.set_player_name("p2", "alice").await;
+    assert!(!app_state.players.contains_key("p2"));
+    let mut banned = false;
+    while let Ok(msg) = rx2.try_recv() {
+        banned |= matches!(
+            msg,
+            ServerMessage::Error {
+                code: ErrorCode::Banned,
+                ..
+            }
+        );
+    }
+    assert!(banned);
+
+    app_state.unban_player("alice").await.unwrap();
+    assert!(!app_state.is_name_banned("Alice").await);
+}
+
+#[tokio::test]
+async fn test_game_without_first_move_is_aborted() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    let now = std::time::Instant::now();
+    assert!(app_state.abort_stale_games(now).await.is_empty());
+
+    let later = now + app_state.first_move_timeout + Duration::from_secs(1);
+    assert_eq!(app_state.abort_stale_games(later).await.len(), 1);
+    assert!(app_state.games.is_empty());
+    assert!(!app_state.is_playing("p2"));
+    for rx in [&mut rx1, &mut rx2] {
+        match expect_msg_timeout(rx).await {
+            ServerMessage::GameAborted { reason } => {
+                assert_eq!(reason, shared::AbortReason::NoFirstMove);
+            }
+            other => panic!("Expected GameAborted, got {other:?}"),
+        }
+    }
+    assert_eq!(app_state.leaderboard(1, 10, None).total, 0);
+}
+
+#[tokio::test]
+async fn test_idle_player_is_warned_then_times_out() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    let game_id = app_state.game_of("p1").unwrap();
+    let now = std::time::Instant::now();
+    let black_id = {
+        let game_lock = app_state.games.get(&game_id).unwrap();
+        let mut game = game_lock.write().await;
+        let mv = *cotuong_core::logic::generator::MoveGenerator::new()
+            .generate_moves(&Board::new(), Color::Red)
+            .first()
+            .unwrap();
+        game.session.play_move(Color::Red, &mv, 0).unwrap();
+        game.start_turn(now);
+        game.black_player.clone()
+    };
+    let (black_rx, red_rx) = if black_id == "p1" {
+        (&mut rx1, &mut rx2)
+    } else {
+        (&mut rx2, &mut rx1)
+    };
+
+    let config = app_state.move_timer;
+    let timeout = config.move_timeout.unwrap();
+    app_state.check_move_timers(now).await;
+    assert!(black_rx.try_recv().is_err());
+
+    let warn_at = now + timeout - config.warning;
+    app_state.check_move_timers(warn_at).await;
+    app_state.check_move_timers(warn_at).await;
+    match expect_msg_timeout(black_rx).await {
+        ServerMessage::MoveTimeWarning { seconds_left } => {
+            assert_eq!(seconds_left, config.warning.as_secs());
+        }
+        other => panic!("Expected MoveTimeWarning, got {other:?}"),
+    }
+    assert!(black_rx.try_recv().is_err());
+    assert!(red_rx.try_recv().is_err());
+
+    app_state.check_move_timers(now + timeout).await;
+    for rx in [black_rx, red_rx] {
+        match expect_msg_timeout(rx).await {
+            ServerMessage::GameEnd { winner, reason } => {
+                assert_eq!(winner, Some(Color::Red));
+                assert_eq!(reason, "Timeout");
+            }
+            other => panic!("Expected GameEnd, got {other:?}"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_rematch_swaps_colors_and_keeps_score() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    let game_id = app_state.game_of("p1").unwrap();
+    let first_red = app_state
+        .games
+        .get(&game_id)
+        .unwrap()
+        .read()
+        .await
+        .red_player
+        .clone();
+
+    // Red resigns, then both ask for a rematch
+    app_state.handle_surrender(first_red.clone()).await;
+    app_state.handle_play_again("p1".to_string()).await;
+    app_state.handle_play_again("p2".to_string()).await;
+
+    {
+        let game_lock = app_state.games.get(&game_id).unwrap();
+        let game = game_lock.read().await;
+        assert_eq!(game.black_player, first_red);
+        assert_eq!(game.score.wins, 1);
+        assert_eq!(game.score.losses, 0);
+        assert!(!game.session.is_over());
+    }
+
+    let first_red_rx = if first_red == "p1" {
+        &mut rx1
+    } else {
+        &mut rx2
+    };
+    let mut found = None;
+    while let Ok(msg) = first_red_rx.try_recv() {
+        if let ServerMessage::MatchFound {
+            your_color, score, ..
+        } = msg
+        {
+            found = Some((your_color, score));
+        }
+    }
+    let (color, score) = found.expect("MatchFound for the rematch");
+    assert_eq!(color, Color::Black);
+    assert_eq!(score.losses, 1);
+    assert_eq!(score.to_string(), "0–1");
+
+    let mut drawn = score;
+    drawn.record(None);
+    assert_eq!(drawn.to_string(), "½–1½");
+}
+
+#[test]
+fn test_leaderboard_pagination_and_search() {
+    let app_state = AppState::new();
+    for (i, name) in ["Alpha", "Bravo", "Charlie", "Delta", "Echo"]
+        .iter()
+        .enumerate()
+    {
+        let mut rating = PlayerRating::new(name);
+        rating.rating = 1500 - i32::try_from(i).unwrap() * 50;
+        app_state.ratings.insert(name.to_lowercase(), rating);
+    }
+
+    let page = app_state.leaderboard(2, 2, None);
+    assert_eq!(page.total, 5);
+    let names: Vec<&str> = page.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, vec!["Charlie", "Delta"]);
+
+    // Search is case-insensitive and keeps global ranks
+    let page = app_state.leaderboard(1, 10, Some("ECH"));
+    assert_eq!(page.total, 1);
+    let entry = page.entries.first().unwrap();
+    assert_eq!(entry.name, "Echo");
+    assert_eq!(entry.rank, 5);
+
+    // Page size is clamped
+    let page = app_state.leaderboard(1, 0, None);
+    assert_eq!(page.per_page, 1);
+    assert_eq!(page.entries.len(), 1);
+}
+
+#[tokio::test]
+async fn test_metrics_rendering() {
+    let app_state = AppState::new();
+    let (tx1, _rx1) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+
+    app_state.metrics.record_message();
+    app_state
+        .metrics
+        .observe_validation(Duration::from_micros(300));
+
+    let text = app_state.render_metrics().await;
+    assert!(text.contains("cotuong_connected_players 1"));
+    assert!(text.contains("cotuong_active_games 0"));
+    assert!(text.contains("cotuong_matchmaking_queue_length 1"));
+    assert!(text.contains("cotuong_messages_total 1"));
+    assert!(text.contains("cotuong_move_validation_seconds_bucket{le=\"0.0001\"} 0"));
+    assert!(text.contains("cotuong_move_validation_seconds_bucket{le=\"0.0005\"} 1"));
+    assert!(text.contains("cotuong_move_validation_seconds_count 1"));
+}
+
+#[tokio::test]
+async fn test_snapshot_roundtrip() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    app_state.notify_shutdown(3);
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::ServerShuttingDown { seconds } => assert_eq!(seconds, 3),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    let path = std::env::temp_dir().join(format!("snapshot-{}.json", uuid::Uuid::new_v4()));
+    let saved = app_state.save_snapshot(&path).await.unwrap();
+    assert_eq!(saved, 1);
+
+    let restored_state = AppState::new();
+    let restored = restored_state.restore_snapshot(&path).await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(restored, 1);
+
+    let game_id = restored_state.game_of("p1").unwrap();
+    assert_eq!(restored_state.game_of("p2"), Some(game_id.clone()));
+    let game_lock = restored_state.games.get(&game_id).unwrap();
+    let game = game_lock.read().await;
+    assert_eq!(game.session.turn(), Color::Red);
+    assert_eq!(
+        game.session.board().to_fen_string(Color::Red),
+        Board::new().to_fen_string(Color::Red)
+    );
+}
+
+#[tokio::test]
+async fn test_cheat_reports_review() {
+    use crate::storage::{CheatReport, ReportStatus};
+
+    let app_state = AppState::new();
+    for (id, created_ms) in [("r1", 1), ("r2", 2)] {
+        let report = CheatReport {
+            id: id.to_string(),
+            game_id: "g1".to_string(),
+            player: "Alice".to_string(),
+            color: Color::Red,
+            moves: 30,
+            match_rate: 0.95,
+            avg_cp_loss: 4.0,
+            created_ms,
+            status: ReportStatus::Open,
+        };
+        app_state.store.save_cheat_report(&report).await.unwrap();
+    }
+
+    let open = app_state
+        .cheat_reports(Some(ReportStatus::Open))
+        .await
+        .unwrap();
+    let ids: Vec<&str> = open.iter().map(|r| r.id.as_str()).collect();
+    assert_eq!(ids, ["r2", "r1"]);
+
+    assert!(app_state
+        .store
+        .set_report_status("r2", ReportStatus::Cleared)
+        .await
+        .unwrap());
+    assert!(!app_state
+        .store
+        .set_report_status("missing", ReportStatus::Cleared)
+        .await
+        .unwrap());
+    let open = app_state
+        .cheat_reports(Some(ReportStatus::Open))
+        .await
+        .unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(app_state.cheat_reports(None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_memory_store_queue() {
+    use crate::storage::{MemoryStore, QueueOutcome, Storage};
+
+    let store = MemoryStore::new();
+    assert_eq!(
+        store
+            .pair_or_enqueue("p1", HandicapKind::None)
+            .await
+            .unwrap(),
+        QueueOutcome::Queued
+    );
+    assert_eq!(
+        store
+            .pair_or_enqueue("p1", HandicapKind::None)
+            .await
+            .unwrap(),
+        QueueOutcome::AlreadyQueued
+    );
+    assert_eq!(
+        store
+            .pair_or_enqueue("p2", HandicapKind::None)
+            .await
+            .unwrap(),
+        QueueOutcome::Paired("p1".to_string())
+    );
+    assert_eq!(store.queue_len().await.unwrap(), 0);
+
+    store
+        .pair_or_enqueue("p3", HandicapKind::None)
+        .await
+        .unwrap();
+    assert!(store.dequeue("p3").await.unwrap());
+    assert_eq!(store.queue_len().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_handicap_matchmaking() {
+    let app_state = AppState::new();
+    let mut receivers = Vec::new();
+    for id in ["p1", "p2", "p3"] {
+        let (tx, rx) = channel();
+        app_state.add_player(id.to_string(), tx);
+        receivers.push(rx);
+    }
+
+    // Different handicaps never pair
+    app_state
+        .dispatch(
+            "p1",
+            shared::GameMessage::FindHandicapMatch(HandicapKind::TwoMoves),
+        )
+        .await;
+    app_state
+        .dispatch("p2", shared::GameMessage::FindMatch)
+        .await;
+    assert_eq!(app_state.store.queue_len().await.unwrap(), 2);
+
+    app_state
+        .dispatch(
+            "p3",
+            shared::GameMessage::FindHandicapMatch(HandicapKind::TwoMoves),
+        )
+        .await;
+    assert_eq!(app_state.store.queue_len().await.unwrap(), 1);
+
+    let game_id = app_state.game_of("p3").unwrap();
+    let game_lock = app_state.games.get(&game_id).unwrap();
+    let game = game_lock.read().await;
+    assert_eq!(game.handicap, HandicapKind::TwoMoves);
+    assert_eq!(game.session.turn(), Color::Black);
+    let (red_id, black_id) = (game.red_player.clone(), game.black_player.clone());
+    let first = *cotuong_core::logic::generator::MoveGenerator::new()
+        .generate_moves(game.session.board(), Color::Black)
+        .first()
+        .unwrap();
+    drop(game);
+    drop(game_lock);
+
+    // A rejected verification falls back to the server-side check, which keeps Black on move
+    app_state
+        .dispatch(
+            &black_id,
+            shared::GameMessage::MakeMove {
+                move_data: first,
+                fen: String::new(),
+            },
+        )
+        .await;
+    app_state
+        .dispatch(
+            &red_id,
+            shared::GameMessage::VerifyMove {
+                fen: String::new(),
+                is_valid: false,
+            },
+        )
+        .await;
+    let game_lock = app_state.games.get(&game_id).unwrap();
+    let game = game_lock.read().await;
+    assert_eq!(game.session.turn(), Color::Black);
+    assert_eq!(game.session.move_count(), 1);
+}
+
+#[tokio::test]
+async fn test_cancel_find_match() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+
+    app_state
+        .dispatch("p1", shared::GameMessage::FindMatch)
+        .await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx1).await,
+        ServerMessage::WaitingForMatch
+    ));
+    assert_eq!(app_state.store.queue_len().await.unwrap(), 1);
+
+    app_state
+        .dispatch("p1", shared::GameMessage::CancelFindMatch)
+        .await;
+    assert_eq!(app_state.store.queue_len().await.unwrap(), 0);
+}
+
+#[test]
+fn test_authenticate_rejects_spoofed_identity() {
+    let app_state = AppState::new();
+    let token = app_state.signer.issue("p1");
+
+    assert!(app_state.authenticate("p1", &token));
+    // A valid token for another player must not authorize this socket
+    assert!(!app_state.authenticate("p2", &token));
+    assert!(!app_state.authenticate("p1", "p1.deadbeef"));
+}
+
+#[test]
+fn test_token_bucket_budgets() {
+    use crate::game_manager::rate_limit::{BucketConfig, RateLimiter};
+    use shared::RateLimitKind;
+    use std::time::Instant;
+
+    let config = RateLimitConfig {
+        moves: BucketConfig {
+            burst: 2.0,
+            per_sec: 1.0,
+        },
+        ..RateLimitConfig::default()
+    };
+    let start = Instant::now();
+    let mut limiter = RateLimiter::new(&config, start);
+
+    assert!(limiter.check(&config, RateLimitKind::Move, start).is_ok());
+    assert!(limiter.check(&config, RateLimitKind::Move, start).is_ok());
+    let retry = limiter
+        .check(&config, RateLimitKind::Move, start)
+        .unwrap_err();
+    assert!(retry <= Duration::from_secs(1));
+
+    // Other budgets are independent
+    assert!(limiter
+        .check(&config, RateLimitKind::Matchmaking, start)
+        .is_ok());
+
+    // Tokens refill over time
+    let later = start + Duration::from_secs(1);
+    assert!(limiter.check(&config, RateLimitKind::Move, later).is_ok());
+}
+
+#[tokio::test]
+async fn test_rate_limited_message_sent() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+
+    let msg = shared::GameMessage::FindMatch;
+    let burst = app_state.rate_limit_config.matchmaking.burst as usize;
+    for _ in 0..burst {
+        assert!(app_state.check_rate_limit("p1", &msg));
+    }
+    assert!(!app_state.check_rate_limit("p1", &msg));
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::RateLimited { kind, .. } => {
+            assert_eq!(kind, shared::RateLimitKind::Matchmaking)
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_move_errors_are_typed() {
+    use shared::ErrorCode;
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+
+    let mv = *cotuong_core::logic::generator::MoveGenerator::new()
+        .generate_moves(&Board::new(), Color::Red)
+        .first()
+        .unwrap();
+
+    // Not in a game yet
+    app_state
+        .handle_move("p1".to_string(), mv, String::new())
+        .await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::Error { code, .. } => assert_eq!(code, ErrorCode::NotInGame),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    let game_id = app_state.game_of("p1").unwrap();
+    let black_id = app_state
+        .games
+        .get(&game_id)
+        .unwrap()
+        .read()
+        .await
+        .black_player
+        .clone();
+    let black_rx = if black_id == "p1" { &mut rx1 } else { &mut rx2 };
+
+    // Black tries to move first
+    app_state.handle_move(black_id, mv, String::new()).await;
+    match expect_msg_timeout(black_rx).await {
+        ServerMessage::Error { code, .. } => assert_eq!(code, ErrorCode::NotYourTurn),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_stale_player_is_reaped() {
+    use std::time::Instant;
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    // p2 keeps pinging, p1 goes silent behind a dead NAT mapping
+    let later = Instant::now() + app_state.heartbeat.timeout + Duration::from_secs(1);
+    app_state.dispatch("p2", shared::GameMessage::Ping(7)).await;
+    app_state.touch("p2", later);
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::Pong(7)
+    ));
+
+    let reaped = app_state.reap_stale_players(later).await;
+    assert_eq!(reaped, vec!["p1".to_string()]);
+    assert!(!app_state.players.contains_key("p1"));
+    assert!(app_state.game_roles.is_empty());
+
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::OpponentDisconnected
+    ));
+    match expect_msg_timeout(&mut rx2).await {
+        ServerMessage::GameEnd { reason, .. } => assert_eq!(reason, "Opponent Disconnected"),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_reconnect_policy_holds_seat_until_window_expires() {
+    use shared::DisconnectPolicy;
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .start_game(
+            "p1".to_string(),
+            "p2".to_string(),
+            HandicapKind::None,
+            None,
+            DisconnectPolicy::Reconnect { minutes: 2 },
+        )
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+    let token = app_state.signer.issue("p1");
+    // Connected players cannot be taken over
+    assert_eq!(app_state.resumable_player(&token).await, None);
+
+    app_state.remove_player("p1").await;
+    assert!(app_state.is_playing("p1"));
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::OpponentAway { seconds_left: 120 }
+    ));
+
+    // The client comes back with its old token and gets its game back
+    assert_eq!(
+        app_state.resumable_player(&token).await.as_deref(),
+        Some("p1")
+    );
+    let (tx1, mut rx1) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.resume_game("p1").await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx1).await,
+        ServerMessage::MatchFound { .. }
+    ));
+    assert!(matches!(
+        expect_msg_timeout(&mut rx1).await,
+        ServerMessage::GameStart(_)
+    ));
+    assert!(matches!(
+        expect_msg_timeout(&mut rx1).await,
+        ServerMessage::GameStateCorrection { seq: 0, .. }
+    ));
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::OpponentReturned
+    ));
+
+    // Dropping again and staying away past the window loses the game
+    app_state.remove_player("p1").await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::OpponentAway { .. }
+    ));
+    let now = std::time::Instant::now();
+    assert!(app_state.expire_held_seats(now).await.is_empty());
+    let later = now + Duration::from_secs(121);
+    assert_eq!(app_state.expire_held_seats(later).await.len(), 1);
+    assert!(app_state.games.is_empty());
+    assert!(app_state.game_roles.is_empty());
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::OpponentDisconnected
+    ));
+    match expect_msg_timeout(&mut rx2).await {
+        ServerMessage::GameEnd { reason, .. } => assert_eq!(reason, "Opponent Disconnected"),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+    assert_eq!(app_state.resumable_player(&token).await, None);
+}
+
+#[tokio::test]
+async fn test_adjudicate_policy_gives_game_to_side_ahead() {
+    use cotuong_core::session::Session;
+    use shared::DisconnectPolicy;
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .start_game(
+            "p1".to_string(),
+            "p2".to_string(),
+            HandicapKind::None,
+            None,
+            DisconnectPolicy::Adjudicate,
+        )
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    // Black is a chariot down when Red drops out
+    let game_id = app_state.game_of("p1").unwrap();
+    let red_id = {
+        let game_lock = app_state.games.get(&game_id).unwrap();
+        let mut game = game_lock.write().await;
+        let (board, turn) =
+            Board::from_fen("1nbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w")
+                .unwrap();
+        game.session = Session::from_position(board, turn, 0);
+        game.red_player.clone()
+    };
+    let (black_rx, black_id) = if red_id == "p1" {
+        (&mut rx2, "p2")
+    } else {
+        (&mut rx1, "p1")
+    };
+
+    app_state.remove_player(&red_id).await;
+    assert!(!app_state.is_playing(black_id));
+    assert!(matches!(
+        expect_msg_timeout(black_rx).await,
+        ServerMessage::OpponentDisconnected
+    ));
+    match expect_msg_timeout(black_rx).await {
+        ServerMessage::GameEnd { winner, reason } => {
+            assert_eq!(winner, Some(Color::Red));
+            assert_eq!(reason, "Adjudication");
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_live_games_and_spectating() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    let (tx3, mut rx3) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state.add_player("watcher".to_string(), tx3);
+    app_state.set_player_name("p1", "Alice").await;
+
+    app_state
+        .dispatch("watcher", shared::GameMessage::SubscribeLiveGames)
+        .await;
+    match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::LiveGames(games) => assert!(games.is_empty()),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    let game_id = match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::LiveGames(games) => {
+            let game = games.first().expect("Started game should be listed");
+            assert_eq!(games.len(), 1);
+            assert_eq!(game.move_count, 0);
+            assert!(game.red_name == "Alice" || game.black_name == "Alice");
+            game.game_id.clone()
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    };
+    assert_eq!(app_state.live_games().await.len(), 1);
+
+    app_state
+        .dispatch(
+            "watcher",
+            shared::GameMessage::Spectate("missing".to_string()),
+        )
+        .await;
+    match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::Error { code, .. } => assert_eq!(code, ErrorCode::GameNotFound),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    app_state
+        .dispatch("watcher", shared::GameMessage::Spectate(game_id.clone()))
+        .await;
+    match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::SpectatorUpdate { game, fen } => {
+            assert_eq!(game.game_id, game_id);
+            assert_eq!(fen, Board::new().to_fen_string(Color::Red));
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    // A verified move is relayed to the spectator with the new move count
+    let red_id = app_state
+        .games
+        .get(&game_id)
+        .unwrap()
+        .read()
+        .await
+        .red_player
+        .clone();
+    let black_id = if red_id == "p1" { "p2" } else { "p1" };
+    let mut board = Board::new();
+    let gen = cotuong_core::logic::generator::MoveGenerator::new();
+    let mv = *gen.generate_moves(&board, Color::Red).first().unwrap();
+    board.apply_move(&mv, Color::Red);
+    let fen = board.to_fen_string(Color::Black);
+    app_state.handle_move(red_id.clone(), mv, fen.clone()).await;
+    app_state
+        .handle_verify_move(black_id.to_string(), fen.clone(), true)
+        .await;
+    match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::SpectatorUpdate { game, fen: update } => {
+            assert_eq!(game.move_count, 1);
+            assert_eq!(update, fen);
+        }
+        other => panic!("Unexpected message: {other:?}"),
     }
+
+    // Spectators see the result, and the finished game drops off the list
+    app_state.handle_surrender(red_id).await;
+    match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::GameEnd { reason, .. } => assert_eq!(reason, "Surrender"),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+    match expect_msg_timeout(&mut rx3).await {
+        ServerMessage::LiveGames(games) => assert!(games.is_empty()),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    app_state.remove_player("watcher").await;
+    assert!(!app_state.game_roles.contains_key("watcher"));
+    assert!(app_state.live_subscribers.is_empty());
+    assert!(app_state
+        .games
+        .get(&game_id)
+        .unwrap()
+        .read()
+        .await
+        .spectators
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_spectating_while_queued() {
+    let app_state = AppState::new();
+    let mut rxs = Vec::new();
+    for id in ["p1", "p2", "p3", "p4"] {
+        let (tx, rx) = channel();
+        app_state.add_player(id.to_string(), tx);
+        rxs.push(rx);
+    }
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    let game_id = app_state.game_of("p1").unwrap();
+
+    // A queued player can follow a game and keeps their place in the queue
+    app_state
+        .find_match("p3".to_string(), HandicapKind::None)
+        .await;
+    app_state.spectate("p3", &game_id).await;
+    assert_eq!(app_state.spectated_games("p3"), vec![game_id.clone()]);
+    assert!(!app_state.is_playing("p3"));
+
+    // Players cannot spectate their own game
+    app_state.spectate("p1", &game_id).await;
+    assert_eq!(app_state.game_of("p1"), Some(game_id.clone()));
+    assert!(app_state.spectated_games("p1").is_empty());
+
+    // Getting paired replaces the watched game with the player's own
+    app_state
+        .find_match("p4".to_string(), HandicapKind::None)
+        .await;
+    let own_game = app_state.game_of("p3").unwrap();
+    assert_ne!(own_game, game_id);
+    assert!(app_state.spectated_games("p3").is_empty());
+    assert!(app_state
+        .games
+        .get(&game_id)
+        .unwrap()
+        .read()
+        .await
+        .spectators
+        .is_empty());
+
+    app_state.leave_game("p3").await;
+    assert!(!app_state.game_roles.contains_key("p3"));
+    assert!(!app_state.game_roles.contains_key("p4"));
+}
+
+#[tokio::test]
+async fn test_friend_challenge() {
+    use shared::{FriendStatus, GameMessage, Presence};
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state.set_player_name("p1", "Alice").await;
+    app_state.set_player_name("p2", "Bob").await;
+
+    let challenge = |time_control: Option<&str>| GameMessage::ChallengeFriend {
+        friend_id: "Bob".to_string(),
+        time_control: time_control.map(str::to_string),
+        disconnect_policy: shared::DisconnectPolicy::default(),
+    };
+
+    // Only friends can be challenged
+    app_state.dispatch("p1", challenge(None)).await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::Error { code, .. } => assert_eq!(code, ErrorCode::PlayerUnavailable),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    app_state
+        .dispatch("p1", GameMessage::AddFriend("bob".to_string()))
+        .await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::Friends(friends) => assert_eq!(
+            friends,
+            vec![FriendStatus {
+                name: "Bob".to_string(),
+                presence: Presence::Online,
+            }]
+        ),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    // A declined challenge is closed for the challenger
+    app_state.dispatch("p1", challenge(None)).await;
+    let ServerMessage::ChallengeSent { challenge_id, to } = expect_msg_timeout(&mut rx1).await
+    else {
+        panic!("Expected ChallengeSent");
+    };
+    assert_eq!(to, "Bob");
+    match expect_msg_timeout(&mut rx2).await {
+        ServerMessage::ChallengeReceived {
+            challenge_id: received,
+            from,
+            ..
+        } => {
+            assert_eq!(received, challenge_id);
+            assert_eq!(from, "Alice");
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    }
+    app_state
+        .dispatch("p2", GameMessage::DeclineChallenge(challenge_id.clone()))
+        .await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::ChallengeClosed {
+            challenge_id: closed,
+        } => {
+            assert_eq!(closed, challenge_id);
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    }
+    app_state
+        .dispatch("p2", GameMessage::AcceptChallenge(challenge_id))
+        .await;
+    match expect_msg_timeout(&mut rx2).await {
+        ServerMessage::Error { code, .. } => assert_eq!(code, ErrorCode::ChallengeNotFound),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    // An accepted challenge starts the game, even for a player waiting in the queue
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::WaitingForMatch
+    ));
+    app_state.dispatch("p1", challenge(Some("10+0"))).await;
+    let ServerMessage::ChallengeSent { challenge_id, .. } = expect_msg_timeout(&mut rx1).await
+    else {
+        panic!("Expected ChallengeSent");
+    };
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::ChallengeReceived { .. }
+    ));
+    app_state
+        .dispatch("p2", GameMessage::AcceptChallenge(challenge_id))
+        .await;
+    for rx in [&mut rx1, &mut rx2] {
+        assert!(matches!(
+            expect_msg_timeout(rx).await,
+            ServerMessage::MatchFound { .. }
+        ));
+    }
+    assert_eq!(app_state.store.queue_len().await.unwrap(), 0);
+    assert!(app_state.challenges.is_empty());
+    let live = app_state.live_games().await;
+    assert_eq!(live.len(), 1);
+    assert_eq!(live[0].time_control.as_deref(), Some("10+0"));
+
+    app_state.dispatch("p1", GameMessage::ListFriends).await;
+    drain_setup_messages(&mut rx1).await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::Friends(friends) => assert_eq!(friends[0].presence, Presence::Playing),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    // Lists hold the friend rather than the name
+    app_state.set_player_name("p2", "Robert").await;
+    app_state.dispatch("p1", GameMessage::ListFriends).await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::Friends(friends) => assert_eq!(friends[0].name, "Robert"),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+    app_state
+        .dispatch("p1", GameMessage::AddFriend("Nobody".to_string()))
+        .await;
+    match expect_msg_timeout(&mut rx1).await {
+        ServerMessage::Error { code, .. } => assert_eq!(code, ErrorCode::PlayerNotFound),
+        other => panic!("Unexpected message: {other:?}"),
+    }
+}
+
+#[test]
+fn test_eval_cache_keeps_deeper_and_evicts_oldest() {
+    use shared::CloudEval;
+
+    let eval = |hash, depth| CloudEval {
+        hash,
+        depth,
+        score: 10,
+        best_move: None,
+    };
+    let cache = EvalCache::new(2, 1);
+    assert!(cache.insert(eval(1, 8)));
+    assert!(!cache.insert(eval(1, 4)));
+    assert_eq!(cache.get(1).map(|e| e.depth), Some(8));
+
+    // Position 1 was used last, so position 2 is the one evicted
+    assert!(cache.insert(eval(2, 5)));
+    assert!(cache.get(1).is_some());
+    assert!(cache.insert(eval(3, 5)));
+    assert_eq!(cache.entry_count(), 2);
+    assert!(cache.get(2).is_none());
+    assert!(cache.get(1).is_some() && cache.get(3).is_some());
+}
+
+#[tokio::test]
+async fn test_cloud_eval_shared_between_players() {
+    use shared::{CloudEval, GameMessage};
+
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    let (tx3, _rx3) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state.add_player("p3".to_string(), tx3);
+
+    let hash = Board::new().zobrist_hash;
+    let eval = CloudEval {
+        hash,
+        depth: 12,
+        score: 35,
+        best_move: None,
+    };
+    app_state
+        .dispatch("p1", GameMessage::SubmitEval(eval))
+        .await;
+    // Implausible submissions never replace real analysis
+    app_state
+        .dispatch(
+            "p1",
+            GameMessage::SubmitEval(CloudEval { depth: 200, ..eval }),
+        )
+        .await;
+    // Resubmitting does not count twice, and one player alone is not trusted
+    app_state
+        .dispatch("p1", GameMessage::SubmitEval(eval))
+        .await;
+    app_state.dispatch("p2", GameMessage::QueryEval(hash)).await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::CloudEval { eval: None, .. }
+    ));
+
+    // A disagreeing player does not confirm it, an agreeing one does
+    app_state
+        .dispatch(
+            "p3",
+            GameMessage::SubmitEval(CloudEval {
+                score: -400,
+                ..eval
+            }),
+        )
+        .await;
+    app_state.dispatch("p1", GameMessage::QueryEval(hash)).await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx1).await,
+        ServerMessage::CloudEval { eval: None, .. }
+    ));
+    app_state
+        .dispatch(
+            "p2",
+            GameMessage::SubmitEval(CloudEval {
+                depth: 14,
+                score: 40,
+                ..eval
+            }),
+        )
+        .await;
+
+    app_state.dispatch("p2", GameMessage::QueryEval(hash)).await;
+    match expect_msg_timeout(&mut rx2).await {
+        ServerMessage::CloudEval {
+            hash: answered,
+            eval: Some(found),
+        } => {
+            assert_eq!(answered, hash);
+            assert_eq!(found, eval);
+        }
+        other => panic!("Unexpected message: {other:?}"),
+    }
+
+    app_state
+        .dispatch("p2", GameMessage::QueryEval(hash ^ 1))
+        .await;
+    assert!(matches!(
+        expect_msg_timeout(&mut rx2).await,
+        ServerMessage::CloudEval { eval: None, .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_request_moves_sends_missing_moves_then_pending_move() {
+    let app_state = AppState::new();
+    let (tx1, mut rx1) = channel();
+    let (tx2, mut rx2) = channel();
+    app_state.add_player("p1".to_string(), tx1);
+    app_state.add_player("p2".to_string(), tx2);
+    app_state
+        .find_match("p1".to_string(), HandicapKind::None)
+        .await;
+    app_state
+        .find_match("p2".to_string(), HandicapKind::None)
+        .await;
+    drain_setup_messages(&mut rx1).await;
+    drain_setup_messages(&mut rx2).await;
+
+    let game_id = app_state.game_of("p1").unwrap();
+    let red_id = app_state
+        .games
+        .get(&game_id)
+        .unwrap()
+        .read()
+        .await
+        .red_player
+        .clone();
+    let (black_id, red_rx) = if red_id == "p1" {
+        ("p2".to_string(), &mut rx1)
+    } else {
+        ("p1".to_string(), &mut rx2)
+    };
+
+    // Red's first move is verified, Black's reply waits for Red's verification
+    let gen = cotuong_core::logic::generator::MoveGenerator::new();
+    let mut board = Board::new();
+    let first = *gen
+        .generate_legal_moves(&board, Color::Red)
+        .first()
+        .unwrap();
+    board.apply_move(&first, Color::Red);
+    let fen = board.to_fen_string(Color::Black);
+    app_state
+        .handle_move(red_id.clone(), first, fen.clone())
+        .await;
+    app_state
+        .handle_verify_move(black_id.clone(), fen, true)
+        .await;
+    let reply = *gen
+        .generate_legal_moves(&board, Color::Black)
+        .first()
+        .unwrap();
+    board.apply_move(&reply, Color::Black);
+    let reply_fen = board.to_fen_string(Color::Red);
+    app_state
+        .handle_move(black_id.clone(), reply, reply_fen.clone())
+        .await;
+    while red_rx.try_recv().is_ok() {}
+
+    app_state
+        .dispatch(&red_id, GameMessage::RequestMoves { from_seq: 0 })
+        .await;
+    match expect_msg_timeout(red_rx).await {
+        ServerMessage::SyncMoves { from_seq, moves } => {
+            assert_eq!(from_seq, 0);
+            assert_eq!(moves.len(), 1);
+            assert_eq!(
+                (
+                    moves[0].from_row,
+                    moves[0].from_col,
+                    moves[0].to_row,
+                    moves[0].to_col
+                ),
+                (first.from_row, first.from_col, first.to_row, first.to_col)
+            );
+        }
+        other => panic!("Expected SyncMoves, got {other:?}"),
+    }
+    match expect_msg_timeout(red_rx).await {
+        ServerMessage::OpponentMove { fen, seq, .. } => {
+            assert_eq!(fen, reply_fen);
+            assert_eq!(seq, 1);
+        }
+        other => panic!("Expected the pending OpponentMove, got {other:?}"),
+    }
+
+    // Nothing to sync from ahead of the game: the full position is sent instead
+    app_state
+        .dispatch(&red_id, GameMessage::RequestMoves { from_seq: 5 })
+        .await;
+    assert!(matches!(
+        expect_msg_timeout(red_rx).await,
+        ServerMessage::GameStateCorrection { seq: 1, .. }
+    ));
 }
@@ -1,9 +1,27 @@
-use axum::{routing::get, Router};
+use api::{
+    admin_games_handler, admin_queue_handler, announcement_handler, arena_leaderboard_handler,
+    arena_remove_handler, arena_submit_handler, ban_handler, bans_handler, cheat_reports_handler,
+    eval_handler, healthz_handler, leaderboard_handler, live_games_handler, metrics_handler,
+    pgn_handler, profile_handler, replay_handler, review_report_handler, terminate_game_handler,
+    unban_handler,
+};
+use axum::{
+    routing::{delete, get, post, put},
+    Router,
+};
 use game_manager::AppState;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
+use storage::Storage;
+use tower_http::cors::CorsLayer;
 use ws::ws_handler;
 
+mod api;
+mod auth;
 mod game_manager;
+mod storage;
 mod ws;
 
 #[tokio::main]
@@ -17,13 +35,54 @@ async fn main() {
         )
         .init();
 
-    let state = Arc::new(AppState::new());
+    let state = Arc::new(match connect_store().await {
+        Some(store) => AppState::with_store(store),
+        None => AppState::new(),
+    });
+
+    let snapshot_path = PathBuf::from(
+        std::env::var("SNAPSHOT_PATH").unwrap_or_else(|_| "game_snapshot.json".to_string()),
+    );
+    if snapshot_path.exists() {
+        match state.restore_snapshot(&snapshot_path).await {
+            Ok(_) => {
+                if let Err(e) = tokio::fs::remove_file(&snapshot_path).await {
+                    tracing::warn!(error = %e, "Failed to remove restored snapshot");
+                }
+            }
+            Err(e) => tracing::error!(error = %e, "Failed to restore game snapshot"),
+        }
+    }
+
     state.clone().spawn_cleanup_task();
+    state.clone().spawn_relay().await;
+    state.clone().spawn_anticheat_job();
+    state.clone().spawn_arena_job();
 
     // build our application with a route
     let app = Router::new()
         .route("/ws", get(ws_handler))
-        .with_state(state);
+        .route("/leaderboard", get(leaderboard_handler))
+        .route("/live", get(live_games_handler))
+        .route("/players/:name/profile", get(profile_handler))
+        .route("/eval/:hash", get(eval_handler))
+        .route("/games/:file", get(pgn_handler))
+        .route("/games/:id/replay", get(replay_handler))
+        .route("/arena/configs", post(arena_submit_handler))
+        .route("/arena/leaderboard", get(arena_leaderboard_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/admin/cheat-reports", get(cheat_reports_handler))
+        .route("/admin/cheat-reports/:id", post(review_report_handler))
+        .route("/admin/games", get(admin_games_handler))
+        .route("/admin/games/:id", delete(terminate_game_handler))
+        .route("/admin/queue", get(admin_queue_handler))
+        .route("/admin/bans", get(bans_handler))
+        .route("/admin/bans/:name", put(ban_handler).delete(unban_handler))
+        .route("/admin/announcements", post(announcement_handler))
+        .route("/admin/arena/:name", delete(arena_remove_handler))
+        .layer(CorsLayer::permissive())
+        .with_state(state.clone());
 
     // run our app with hyper
     let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
@@ -36,6 +95,69 @@ async fn main() {
         .await
         .expect("Failed to bind to address");
     axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(state, snapshot_path))
         .await
         .expect("Failed to start server");
 }
+
+/// Shared storage for multi-instance deployments: Redis when `REDIS_URL` is set and the
+/// `redis` feature is enabled. `None` means a standalone in-memory server.
+async fn connect_store() -> Option<Arc<dyn Storage>> {
+    #[cfg(feature = "redis")]
+    if let Ok(url) = std::env::var("REDIS_URL") {
+        match storage::redis::RedisStore::connect(&url).await {
+            Ok(store) => return Some(Arc::new(store)),
+            Err(e) => tracing::error!(error = %e, "Failed to connect to Redis, using memory store"),
+        }
+    }
+
+    None
+}
+
+async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = %e, "Failed to listen for Ctrl+C");
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to install SIGTERM handler");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {},
+        () = terminate => {},
+    }
+}
+
+/// Resolves once the server should stop: refuses new sockets, warns players, then snapshots games.
+async fn shutdown_signal(state: Arc<AppState>, snapshot_path: PathBuf) {
+    wait_for_signal().await;
+
+    let grace_secs = std::env::var("SHUTDOWN_GRACE_SECS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(5);
+
+    tracing::info!(grace_secs, "Shutdown signal received");
+    state.shutting_down.store(true, Ordering::Relaxed);
+    state.notify_shutdown(grace_secs);
+    tokio::time::sleep(Duration::from_secs(grace_secs)).await;
+
+    if let Err(e) = state.save_snapshot(&snapshot_path).await {
+        tracing::error!(error = %e, "Failed to save game snapshot");
+    }
+}
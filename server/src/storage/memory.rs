@@ -0,0 +1,175 @@
+use crate::storage::{CheatReport, Envelope, QueueOutcome, ReportStatus, Storage, StorageResult};
+use async_trait::async_trait;
+use cotuong_core::logic::handicap::HandicapKind;
+use shared::{CloudEval, GameMessage, ServerMessage};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::{mpsc, Mutex};
+
+/// Single-instance storage: every player and game lives in this process.
+pub struct MemoryStore {
+    /// Waiting players and the handicap they asked for.
+    queue: Mutex<HashMap<String, HandicapKind>>,
+    reports: Mutex<Vec<CheatReport>>,
+    bans: Mutex<HashSet<String>>,
+    friends: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self {
+            queue: Mutex::new(HashMap::new()),
+            reports: Mutex::new(Vec::new()),
+            bans: Mutex::new(HashSet::new()),
+            friends: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStore {
+    fn instance_id(&self) -> &str {
+        "local"
+    }
+
+    async fn register_player(&self, _player_id: &str) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn unregister_player(&self, _player_id: &str) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn pair_or_enqueue(
+        &self,
+        player_id: &str,
+        handicap: HandicapKind,
+    ) -> StorageResult<QueueOutcome> {
+        let mut queue = self.queue.lock().await;
+        if queue.contains_key(player_id) {
+            return Ok(QueueOutcome::AlreadyQueued);
+        }
+
+        let opponent = queue
+            .iter()
+            .find(|(_, wanted)| **wanted == handicap)
+            .map(|(id, _)| id.clone());
+        if let Some(opponent_id) = opponent {
+            queue.remove(&opponent_id);
+            Ok(QueueOutcome::Paired(opponent_id))
+        } else {
+            queue.insert(player_id.to_string(), handicap);
+            Ok(QueueOutcome::Queued)
+        }
+    }
+
+    async fn dequeue(&self, player_id: &str) -> StorageResult<bool> {
+        Ok(self.queue.lock().await.remove(player_id).is_some())
+    }
+
+    async fn queue_len(&self) -> StorageResult<usize> {
+        Ok(self.queue.lock().await.len())
+    }
+
+    async fn queued_players(&self) -> StorageResult<Vec<(String, HandicapKind)>> {
+        let queue = self.queue.lock().await;
+        Ok(queue.iter().map(|(id, h)| (id.clone(), *h)).collect())
+    }
+
+    async fn set_game_owner(&self, _player_id: &str) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn clear_game_owner(&self, _player_id: &str) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn game_owner(&self, _player_id: &str) -> StorageResult<Option<String>> {
+        Ok(None)
+    }
+
+    fn deliver_remote(&self, player_id: &str, _msg: ServerMessage) {
+        tracing::debug!(player_id = %player_id, "Dropping message for unknown player");
+    }
+
+    fn forward(&self, _instance_id: &str, _player_id: &str, _msg: GameMessage) {}
+
+    // The in-process eval cache is the only tier when running alone
+    async fn load_eval(&self, _hash: u64) -> StorageResult<Option<CloudEval>> {
+        Ok(None)
+    }
+
+    async fn save_eval(&self, _eval: &CloudEval) -> StorageResult<()> {
+        Ok(())
+    }
+
+    async fn save_cheat_report(&self, report: &CheatReport) -> StorageResult<()> {
+        self.reports.lock().await.push(report.clone());
+        Ok(())
+    }
+
+    async fn cheat_reports(&self) -> StorageResult<Vec<CheatReport>> {
+        Ok(self.reports.lock().await.clone())
+    }
+
+    async fn set_report_status(&self, id: &str, status: ReportStatus) -> StorageResult<bool> {
+        let mut reports = self.reports.lock().await;
+        let report = reports.iter_mut().find(|r| r.id == id);
+        Ok(report.map(|r| r.status = status).is_some())
+    }
+
+    async fn set_banned(&self, name_key: &str, banned: bool) -> StorageResult<()> {
+        let mut bans = self.bans.lock().await;
+        if banned {
+            bans.insert(name_key.to_string());
+        } else {
+            bans.remove(name_key);
+        }
+        Ok(())
+    }
+
+    async fn is_banned(&self, name_key: &str) -> StorageResult<bool> {
+        Ok(self.bans.lock().await.contains(name_key))
+    }
+
+    async fn banned(&self) -> StorageResult<Vec<String>> {
+        Ok(self.bans.lock().await.iter().cloned().collect())
+    }
+
+    async fn set_friend(
+        &self,
+        player_id: &str,
+        friend_id: &str,
+        friend: bool,
+    ) -> StorageResult<()> {
+        let mut friends = self.friends.lock().await;
+        if friend {
+            friends
+                .entry(player_id.to_string())
+                .or_default()
+                .insert(friend_id.to_string());
+        } else if let Some(list) = friends.get_mut(player_id) {
+            list.remove(friend_id);
+        }
+        Ok(())
+    }
+
+    async fn friends(&self, player_id: &str) -> StorageResult<Vec<String>> {
+        Ok(self
+            .friends
+            .lock()
+            .await
+            .get(player_id)
+            .map(|list| list.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn subscribe(&self) -> StorageResult<Option<mpsc::UnboundedReceiver<Envelope>>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,129 @@
+use async_trait::async_trait;
+use cotuong_core::logic::board::Color;
+use cotuong_core::logic::handicap::HandicapKind;
+use serde::{Deserialize, Serialize};
+use shared::{CloudEval, GameMessage, ServerMessage};
+use std::fmt;
+use tokio::sync::mpsc;
+
+pub mod memory;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+pub use memory::MemoryStore;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageError(pub String);
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "storage error: {}", self.0)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueOutcome {
+    /// Another waiting player was taken off the queue.
+    Paired(String),
+    /// Nobody was waiting, so the player is now queued.
+    Queued,
+    AlreadyQueued,
+}
+
+/// Messages exchanged between server instances over the shared bus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Envelope {
+    /// Deliver a server message to a player connected to the receiving instance.
+    Deliver {
+        player_id: String,
+        msg: ServerMessage,
+    },
+    /// A player's message for a game owned by the receiving instance.
+    Forward { player_id: String, msg: GameMessage },
+}
+
+/// An admin's verdict on a cheat report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportStatus {
+    Open,
+    Cleared,
+    Confirmed,
+}
+
+/// A player whose moves in one rated game looked engine-assisted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheatReport {
+    pub id: String,
+    pub game_id: String,
+    pub player: String,
+    pub color: Color,
+    /// Moves judged, past the opening.
+    pub moves: usize,
+    /// Share of those moves that were the engine's choice, 0.0 to 1.0.
+    pub match_rate: f64,
+    pub avg_cp_loss: f64,
+    /// Unix milliseconds.
+    pub created_ms: u64,
+    pub status: ReportStatus,
+}
+
+/// State shared between server instances.
+///
+/// Game sessions stay in the memory of the instance that created them (the "owner"); the
+/// storage only records who owns which player's game and routes messages to it, so each
+/// session still has a single writer.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    fn instance_id(&self) -> &str;
+
+    async fn register_player(&self, player_id: &str) -> StorageResult<()>;
+    async fn unregister_player(&self, player_id: &str) -> StorageResult<()>;
+
+    /// Atomically pairs `player_id` with an opponent waiting for the same handicap, or queues it.
+    async fn pair_or_enqueue(
+        &self,
+        player_id: &str,
+        handicap: HandicapKind,
+    ) -> StorageResult<QueueOutcome>;
+    async fn dequeue(&self, player_id: &str) -> StorageResult<bool>;
+    async fn queue_len(&self) -> StorageResult<usize>;
+    /// Every waiting player and the handicap they asked for.
+    async fn queued_players(&self) -> StorageResult<Vec<(String, HandicapKind)>>;
+
+    async fn set_game_owner(&self, player_id: &str) -> StorageResult<()>;
+    async fn clear_game_owner(&self, player_id: &str) -> StorageResult<()>;
+    /// Instance that owns the player's current game, if any.
+    async fn game_owner(&self, player_id: &str) -> StorageResult<Option<String>>;
+
+    /// Fire-and-forget delivery to a player connected to another instance.
+    fn deliver_remote(&self, player_id: &str, msg: ServerMessage);
+    /// Fire-and-forget forwarding of a player's message to the instance owning their game.
+    fn forward(&self, instance_id: &str, player_id: &str, msg: GameMessage);
+
+    /// Shared evaluation of a position, looked up when the local cache misses.
+    async fn load_eval(&self, hash: u64) -> StorageResult<Option<CloudEval>>;
+    async fn save_eval(&self, eval: &CloudEval) -> StorageResult<()>;
+
+    async fn save_cheat_report(&self, report: &CheatReport) -> StorageResult<()>;
+    async fn cheat_reports(&self) -> StorageResult<Vec<CheatReport>>;
+    /// Records an admin's verdict; `false` when there is no report with that id.
+    async fn set_report_status(&self, id: &str, status: ReportStatus) -> StorageResult<bool>;
+
+    /// Bans are keyed by lowercase display name.
+    async fn set_banned(&self, name_key: &str, banned: bool) -> StorageResult<()>;
+    async fn is_banned(&self, name_key: &str) -> StorageResult<bool>;
+    async fn banned(&self) -> StorageResult<Vec<String>>;
+
+    /// Friend lists are keyed by player id and hold player ids; each list is its owner's
+    /// own, so friendship need not be mutual.
+    async fn set_friend(&self, player_id: &str, friend_id: &str, friend: bool)
+        -> StorageResult<()>;
+    async fn friends(&self, player_id: &str) -> StorageResult<Vec<String>>;
+
+    /// Envelopes addressed to this instance. `None` when running as a single instance.
+    async fn subscribe(&self) -> StorageResult<Option<mpsc::UnboundedReceiver<Envelope>>>;
+}
@@ -0,0 +1,358 @@
+use crate::storage::{
+    CheatReport, Envelope, QueueOutcome, ReportStatus, Storage, StorageError, StorageResult,
+};
+use async_trait::async_trait;
+use cotuong_core::logic::handicap::HandicapKind;
+use futures::StreamExt;
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use shared::{CloudEval, GameMessage, ServerMessage};
+use tokio::sync::mpsc;
+
+/// Hash of waiting player id to the handicap they asked for.
+const QUEUE_KEY: &str = "cotuong:match_queue";
+
+/// Pops a waiting player whose handicap is ARGV[2], or queues ARGV[1] with that handicap.
+const PAIR_OR_ENQUEUE_SCRIPT: &str = r"
+if redis.call('HEXISTS', KEYS[1], ARGV[1]) == 1 then
+    return 'already'
+end
+local entries = redis.call('HGETALL', KEYS[1])
+for i = 1, #entries, 2 do
+    if entries[i + 1] == ARGV[2] then
+        redis.call('HDEL', KEYS[1], entries[i])
+        return 'paired:' .. entries[i]
+    end
+end
+redis.call('HSET', KEYS[1], ARGV[1], ARGV[2])
+return 'queued'
+";
+
+/// Set of banned lowercase display names.
+const BANNED_KEY: &str = "cotuong:banned";
+
+/// Hash of cheat report id to the report as JSON.
+const REPORTS_KEY: &str = "cotuong:cheat_reports";
+
+/// Shared evaluations expire after 30 days so stale analysis does not pile up forever.
+const EVAL_TTL_SECS: u64 = 30 * 24 * 3600;
+
+fn player_key(player_id: &str) -> String {
+    format!("cotuong:player:{player_id}")
+}
+
+fn friends_key(player_id: &str) -> String {
+    format!("cotuong:friends:{player_id}")
+}
+
+fn owner_key(player_id: &str) -> String {
+    format!("cotuong:owner:{player_id}")
+}
+
+fn eval_key(hash: u64) -> String {
+    format!("cotuong:eval:{hash:016x}")
+}
+
+fn instance_channel(instance_id: &str) -> String {
+    format!("cotuong:instance:{instance_id}")
+}
+
+impl From<redis::RedisError> for StorageError {
+    fn from(e: redis::RedisError) -> Self {
+        Self(e.to_string())
+    }
+}
+
+enum Outgoing {
+    /// Resolve the player's instance, then publish.
+    ToPlayer(String, Envelope),
+    ToInstance(String, Envelope),
+}
+
+/// Redis-backed storage shared by every instance pointing at the same server.
+///
+/// Queue membership and routing live in plain keys; instances talk to each other over
+/// one pub/sub channel per instance.
+pub struct RedisStore {
+    client: redis::Client,
+    conn: MultiplexedConnection,
+    instance_id: String,
+    outgoing: mpsc::UnboundedSender<Outgoing>,
+}
+
+impl RedisStore {
+    pub async fn connect(url: &str) -> StorageResult<Self> {
+        let client = redis::Client::open(url)?;
+        let conn = client.get_multiplexed_async_connection().await?;
+        let instance_id = uuid::Uuid::new_v4().to_string();
+        let (outgoing, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(publisher_task(conn.clone(), rx));
+        tracing::info!(instance_id = %instance_id, "Connected to Redis");
+
+        Ok(Self {
+            client,
+            conn,
+            instance_id,
+            outgoing,
+        })
+    }
+}
+
+/// Publishes queued envelopes so callers never block on Redis while holding game locks.
+async fn publisher_task(
+    mut conn: MultiplexedConnection,
+    mut rx: mpsc::UnboundedReceiver<Outgoing>,
+) {
+    while let Some(outgoing) = rx.recv().await {
+        let (instance_id, envelope) = match outgoing {
+            Outgoing::ToInstance(instance_id, envelope) => (instance_id, envelope),
+            Outgoing::ToPlayer(player_id, envelope) => {
+                match conn.get::<_, Option<String>>(player_key(&player_id)).await {
+                    Ok(Some(instance_id)) => (instance_id, envelope),
+                    Ok(None) => {
+                        tracing::debug!(player_id = %player_id, "Player not connected to any instance");
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to resolve player instance");
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let Ok(payload) = serde_json::to_string(&envelope) else {
+            continue;
+        };
+        if let Err(e) = conn
+            .publish::<_, _, ()>(instance_channel(&instance_id), payload)
+            .await
+        {
+            tracing::error!(error = %e, "Failed to publish envelope");
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for RedisStore {
+    fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    async fn register_player(&self, player_id: &str) -> StorageResult<()> {
+        let mut conn = self.conn.clone();
+        conn.set::<_, _, ()>(player_key(player_id), &self.instance_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn unregister_player(&self, player_id: &str) -> StorageResult<()> {
+        let mut conn = self.conn.clone();
+        conn.del::<_, ()>(player_key(player_id)).await?;
+        Ok(())
+    }
+
+    async fn pair_or_enqueue(
+        &self,
+        player_id: &str,
+        handicap: HandicapKind,
+    ) -> StorageResult<QueueOutcome> {
+        let mut conn = self.conn.clone();
+        let result: String = redis::Script::new(PAIR_OR_ENQUEUE_SCRIPT)
+            .key(QUEUE_KEY)
+            .arg(player_id)
+            .arg(format!("{handicap:?}"))
+            .invoke_async(&mut conn)
+            .await?;
+
+        match result.as_str() {
+            "already" => Ok(QueueOutcome::AlreadyQueued),
+            "queued" => Ok(QueueOutcome::Queued),
+            other => other
+                .strip_prefix("paired:")
+                .map(|id| QueueOutcome::Paired(id.to_string()))
+                .ok_or_else(|| StorageError(format!("unexpected queue reply: {other}"))),
+        }
+    }
+
+    async fn dequeue(&self, player_id: &str) -> StorageResult<bool> {
+        let mut conn = self.conn.clone();
+        let removed: bool = conn.hdel(QUEUE_KEY, player_id).await?;
+        Ok(removed)
+    }
+
+    async fn queue_len(&self) -> StorageResult<usize> {
+        let mut conn = self.conn.clone();
+        let len: usize = conn.hlen(QUEUE_KEY).await?;
+        Ok(len)
+    }
+
+    async fn queued_players(&self) -> StorageResult<Vec<(String, HandicapKind)>> {
+        let mut conn = self.conn.clone();
+        let entries: Vec<(String, String)> = conn.hgetall(QUEUE_KEY).await?;
+        Ok(entries
+            .into_iter()
+            .filter_map(|(id, wanted)| {
+                let handicap = HandicapKind::ALL
+                    .into_iter()
+                    .find(|h| format!("{h:?}") == wanted)?;
+                Some((id, handicap))
+            })
+            .collect())
+    }
+
+    async fn set_game_owner(&self, player_id: &str) -> StorageResult<()> {
+        let mut conn = self.conn.clone();
+        conn.set::<_, _, ()>(owner_key(player_id), &self.instance_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn clear_game_owner(&self, player_id: &str) -> StorageResult<()> {
+        let mut conn = self.conn.clone();
+        conn.del::<_, ()>(owner_key(player_id)).await?;
+        Ok(())
+    }
+
+    async fn game_owner(&self, player_id: &str) -> StorageResult<Option<String>> {
+        let mut conn = self.conn.clone();
+        let owner: Option<String> = conn.get(owner_key(player_id)).await?;
+        Ok(owner)
+    }
+
+    async fn load_eval(&self, hash: u64) -> StorageResult<Option<CloudEval>> {
+        let mut conn = self.conn.clone();
+        let json: Option<String> = conn.get(eval_key(hash)).await?;
+        json.map(|json| serde_json::from_str(&json).map_err(|e| StorageError(e.to_string())))
+            .transpose()
+    }
+
+    async fn save_eval(&self, eval: &CloudEval) -> StorageResult<()> {
+        let json = serde_json::to_string(eval).map_err(|e| StorageError(e.to_string()))?;
+        let mut conn = self.conn.clone();
+        conn.set_ex::<_, _, ()>(eval_key(eval.hash), json, EVAL_TTL_SECS)
+            .await?;
+        Ok(())
+    }
+
+    async fn save_cheat_report(&self, report: &CheatReport) -> StorageResult<()> {
+        let json = serde_json::to_string(report).map_err(|e| StorageError(e.to_string()))?;
+        let mut conn = self.conn.clone();
+        conn.hset::<_, _, _, ()>(REPORTS_KEY, &report.id, json)
+            .await?;
+        Ok(())
+    }
+
+    async fn cheat_reports(&self) -> StorageResult<Vec<CheatReport>> {
+        let mut conn = self.conn.clone();
+        let jsons: Vec<String> = conn.hvals(REPORTS_KEY).await?;
+        jsons
+            .iter()
+            .map(|json| serde_json::from_str(json).map_err(|e| StorageError(e.to_string())))
+            .collect()
+    }
+
+    async fn set_report_status(&self, id: &str, status: ReportStatus) -> StorageResult<bool> {
+        let mut conn = self.conn.clone();
+        let json: Option<String> = conn.hget(REPORTS_KEY, id).await?;
+        let Some(json) = json else {
+            return Ok(false);
+        };
+        let mut report: CheatReport =
+            serde_json::from_str(&json).map_err(|e| StorageError(e.to_string()))?;
+        report.status = status;
+        self.save_cheat_report(&report).await?;
+        Ok(true)
+    }
+
+    async fn set_banned(&self, name_key: &str, banned: bool) -> StorageResult<()> {
+        let mut conn = self.conn.clone();
+        if banned {
+            conn.sadd::<_, _, ()>(BANNED_KEY, name_key).await?;
+        } else {
+            conn.srem::<_, _, ()>(BANNED_KEY, name_key).await?;
+        }
+        Ok(())
+    }
+
+    async fn is_banned(&self, name_key: &str) -> StorageResult<bool> {
+        let mut conn = self.conn.clone();
+        Ok(conn.sismember(BANNED_KEY, name_key).await?)
+    }
+
+    async fn banned(&self) -> StorageResult<Vec<String>> {
+        let mut conn = self.conn.clone();
+        Ok(conn.smembers(BANNED_KEY).await?)
+    }
+
+    async fn set_friend(
+        &self,
+        player_id: &str,
+        friend_id: &str,
+        friend: bool,
+    ) -> StorageResult<()> {
+        let mut conn = self.conn.clone();
+        if friend {
+            conn.sadd::<_, _, ()>(friends_key(player_id), friend_id)
+                .await?;
+        } else {
+            conn.srem::<_, _, ()>(friends_key(player_id), friend_id)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn friends(&self, player_id: &str) -> StorageResult<Vec<String>> {
+        let mut conn = self.conn.clone();
+        Ok(conn.smembers(friends_key(player_id)).await?)
+    }
+
+    fn deliver_remote(&self, player_id: &str, msg: ServerMessage) {
+        let envelope = Envelope::Deliver {
+            player_id: player_id.to_string(),
+            msg,
+        };
+        let _ = self
+            .outgoing
+            .send(Outgoing::ToPlayer(player_id.to_string(), envelope));
+    }
+
+    fn forward(&self, instance_id: &str, player_id: &str, msg: GameMessage) {
+        let envelope = Envelope::Forward {
+            player_id: player_id.to_string(),
+            msg,
+        };
+        let _ = self
+            .outgoing
+            .send(Outgoing::ToInstance(instance_id.to_string(), envelope));
+    }
+
+    async fn subscribe(&self) -> StorageResult<Option<mpsc::UnboundedReceiver<Envelope>>> {
+        let mut pubsub = self.client.get_async_pubsub().await?;
+        pubsub
+            .subscribe(instance_channel(&self.instance_id))
+            .await?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut messages = pubsub.into_on_message();
+            while let Some(msg) = messages.next().await {
+                let Ok(payload) = msg.get_payload::<String>() else {
+                    continue;
+                };
+                match serde_json::from_str::<Envelope>(&payload) {
+                    Ok(envelope) => {
+                        if tx.send(envelope).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => tracing::error!(error = %e, "Failed to parse envelope"),
+                }
+            }
+            tracing::warn!("Redis subscription ended");
+        });
+
+        Ok(Some(rx))
+    }
+}
@@ -1,89 +1,195 @@
-use crate::game_manager::AppState;
+use crate::game_manager::{outbox, AppState};
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
         State,
     },
-    response::IntoResponse,
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
-use futures::{sink::SinkExt, stream::StreamExt};
-use shared::GameMessage;
+use futures::{
+    sink::SinkExt,
+    stream::{SplitSink, SplitStream, StreamExt},
+};
+use shared::codec::{self, Frame};
+use shared::{ClientHello, ClientMessage, Encoding, ErrorCode, ServerMessage, PROTOCOL_VERSION};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
 
-pub async fn ws_handler(
-    ws: WebSocketUpgrade,
-    State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
+pub async fn ws_handler(ws: WebSocketUpgrade, State(state): State<Arc<AppState>>) -> Response {
+    if state.shutting_down.load(Ordering::Relaxed) {
+        tracing::info!("Rejecting WebSocket upgrade: server shutting down");
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
     tracing::info!("New WebSocket upgrade request");
     ws.on_upgrade(|socket| handle_socket(socket, state))
 }
 
+const HELLO_TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn send_direct(sender: &mut SplitSink<WebSocket, Message>, msg: &ServerMessage) -> bool {
+    match serde_json::to_string(msg) {
+        Ok(json) => sender.send(Message::Text(json)).await.is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Validates the hello and returns the frame encoding to use for the rest of the session,
+/// with the token of the earlier session the client wants to resume.
+fn check_client_hello(text: &str) -> Result<(Encoding, Option<String>), String> {
+    match serde_json::from_str::<ClientHello>(text) {
+        Ok(hello) if hello.protocol_version == PROTOCOL_VERSION => {
+            Ok((Encoding::negotiate(&hello.encodings), hello.resume_token))
+        }
+        Ok(hello) => Err(format!(
+            "Server speaks protocol v{PROTOCOL_VERSION}, client v{}",
+            hello.protocol_version
+        )),
+        Err(_) => Err(format!(
+            "Expected ClientHello for protocol v{PROTOCOL_VERSION}"
+        )),
+    }
+}
+
+/// Waits for the client's `ClientHello`, checks its protocol version and negotiates the
+/// frame encoding. Mismatched or legacy clients get a `ProtocolVersionMismatch` error
+/// before the socket closes.
+async fn handshake(
+    sender: &mut SplitSink<WebSocket, Message>,
+    receiver: &mut SplitStream<WebSocket>,
+) -> Option<(Encoding, Option<String>)> {
+    let first = match tokio::time::timeout(HELLO_TIMEOUT, receiver.next()).await {
+        Ok(Some(Ok(Message::Text(text)))) => text,
+        _ => {
+            tracing::info!("Connection closed before ClientHello");
+            return None;
+        }
+    };
+
+    let detail = match check_client_hello(&first) {
+        Ok((encoding, resume_token)) => {
+            let hello = ServerMessage::ServerHello {
+                protocol_version: PROTOCOL_VERSION,
+                encoding,
+            };
+            return send_direct(sender, &hello)
+                .await
+                .then_some((encoding, resume_token));
+        }
+        Err(detail) => detail,
+    };
+
+    tracing::warn!(detail = %detail, "Rejecting client with incompatible protocol");
+    send_direct(
+        sender,
+        &ServerMessage::Error {
+            code: ErrorCode::ProtocolVersionMismatch,
+            detail: Some(detail),
+        },
+    )
+    .await;
+    let _ = sender.close().await;
+    None
+}
+
+fn to_message(frame: Frame) -> Message {
+    match frame {
+        Frame::Text(text) => Message::Text(text),
+        Frame::Binary(bytes) => Message::Binary(bytes),
+    }
+}
+
 async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
     let (mut sender, mut receiver) = socket.split();
-    let (tx, mut rx) = mpsc::unbounded_channel();
+    let Some((encoding, resume_token)) = handshake(&mut sender, &mut receiver).await else {
+        return;
+    };
 
-    // Generate a random ID for the player
-    let player_id = uuid::Uuid::new_v4().to_string();
-    tracing::info!(player_id = %player_id, "Player connected");
+    let (tx, mut rx) = outbox::channel(state.outbox_capacity);
+
+    // A returning client keeps its old id, and with it its rating, friends and any held
+    // seat; everyone else gets a new one
+    let (returning, resumed) = match resume_token {
+        Some(token) => (
+            state.returning_player(&token),
+            state.resumable_player(&token).await,
+        ),
+        None => (None, None),
+    };
+    let player_id = returning.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    tracing::info!(player_id = %player_id, ?encoding, resumed = resumed.is_some(), "Player connected");
 
     let send_id = player_id.clone();
-    // Spawn a task to forward messages from the channel to the WebSocket
-    tokio::spawn(async move {
+    // Spawn a task to forward messages from the channel to the WebSocket.
+    // It ends once the player's sender is dropped, e.g. when the heartbeat reaper removes them.
+    let mut send_task = tokio::spawn(async move {
         while let Some(msg) = rx.recv().await {
-            if let Ok(json) = serde_json::to_string(&msg) {
-                if sender.send(Message::Text(json)).await.is_err() {
-                    tracing::debug!(player_id = %send_id, "Failed to send message to WebSocket (disconnected)");
-                    break;
+            match codec::encode(encoding, &msg) {
+                Ok(frame) => {
+                    if sender.send(to_message(frame)).await.is_err() {
+                        tracing::debug!(player_id = %send_id, "Failed to send message to WebSocket (disconnected)");
+                        break;
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(player_id = %send_id, error = %e, "Failed to encode ServerMessage")
                 }
             }
         }
+        let _ = sender.close().await;
+    });
+
+    // Handshake: hand out the token every later message must carry
+    let _ = tx.send(ServerMessage::Welcome {
+        player_id: player_id.clone(),
+        token: state.signer.issue(&player_id),
     });
 
     // Add player to manager
     state.add_player(player_id.clone(), tx);
+    if let Err(e) = state.store.register_player(&player_id).await {
+        tracing::warn!(player_id = %player_id, error = %e, "Failed to register player in store");
+    }
+    if resumed.is_some() {
+        state.resume_game(&player_id).await;
+    }
 
-    while let Some(msg_result) = receiver.next().await {
+    loop {
+        let msg_result = tokio::select! {
+            next = receiver.next() => match next {
+                Some(msg_result) => msg_result,
+                None => break,
+            },
+            _ = &mut send_task => {
+                tracing::info!(player_id = %player_id, "Outgoing channel closed, dropping connection");
+                break;
+            }
+        };
+        state.touch(&player_id, Instant::now());
         match msg_result {
             Ok(msg) => {
-                if let Message::Text(text) = msg {
-                    if !state.check_rate_limit(&player_id) {
-                        tracing::warn!(player_id = %player_id, "Rate limit exceeded, skipping message");
-                        continue;
-                    }
-                    match serde_json::from_str::<GameMessage>(&text) {
-                        Ok(game_msg) => {
-                            tracing::debug!(player_id = %player_id, msg = ?game_msg, "Received message");
-                            match game_msg {
-                                GameMessage::FindMatch => state.find_match(player_id.clone()).await,
-                                GameMessage::MakeMove { move_data, fen } => {
-                                    state.handle_move(player_id.clone(), move_data, fen).await
-                                }
-                                GameMessage::VerifyMove { fen, is_valid } => {
-                                    state
-                                        .handle_verify_move(player_id.clone(), fen, is_valid)
-                                        .await
-                                }
-                                GameMessage::CancelFindMatch => {
-                                    let mut queue = state.matchmaking_queue.lock().await;
-                                    queue.remove(&player_id);
-                                    tracing::info!(player_id = %player_id, "Cancelled matchmaking");
-                                }
-                                GameMessage::Surrender => {
-                                    state.handle_surrender(player_id.clone()).await
-                                }
-                                GameMessage::PlayAgain => {
-                                    state.handle_play_again(player_id.clone()).await
-                                }
-                                GameMessage::PlayerLeft => {
-                                    state.handle_player_left(player_id.clone()).await
-                                }
-                                _ => {}
-                            }
+                let frame = match msg {
+                    Message::Text(text) => Frame::Text(text),
+                    Message::Binary(bytes) => Frame::Binary(bytes),
+                    _ => continue,
+                };
+                state.metrics.record_message();
+                match codec::decode::<ClientMessage>(&frame) {
+                    Ok(ClientMessage { token, msg }) => {
+                        if !state.authenticate(&player_id, &token) {
+                            tracing::warn!(player_id = %player_id, "Rejected message with invalid or spoofed token");
+                            state.send_error(&player_id, ErrorCode::InvalidToken, None);
+                            continue;
                         }
-                        Err(e) => {
-                            tracing::error!(player_id = %player_id, error = %e, text = %text, "Failed to parse GameMessage");
+                        if !state.check_rate_limit(&player_id, &msg) {
+                            continue;
                         }
+                        tracing::debug!(player_id = %player_id, msg = ?msg, "Received message");
+                        state.dispatch(&player_id, msg).await;
+                    }
+                    Err(e) => {
+                        tracing::error!(player_id = %player_id, error = %e, frame = ?frame, "Failed to decode ClientMessage");
                     }
                 }
             }
@@ -98,3 +204,76 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
     tracing::info!(player_id = %player_id, "Player disconnected, cleaning up");
     state.remove_player(&player_id).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+    use shared::GameMessage;
+
+    #[test]
+    fn test_client_hello_version_check() {
+        let hello = |v: u32, encodings: Vec<Encoding>| {
+            serde_json::to_string(&ClientHello {
+                protocol_version: v,
+                encodings,
+                resume_token: None,
+            })
+            .unwrap()
+        };
+
+        assert_eq!(
+            check_client_hello(&hello(PROTOCOL_VERSION, vec![Encoding::Bincode])),
+            Ok((Encoding::Bincode, None))
+        );
+        // Clients that do not list encodings get JSON
+        assert_eq!(
+            check_client_hello(&format!("{{\"protocol_version\":{PROTOCOL_VERSION}}}")),
+            Ok((Encoding::Json, None))
+        );
+        assert!(check_client_hello(&hello(PROTOCOL_VERSION + 1, Vec::new())).is_err());
+        // Legacy clients open with a regular message instead of a hello
+        assert!(check_client_hello(r#"{"token":"t","msg":"FindMatch"}"#).is_err());
+    }
+
+    proptest! {
+        // Frames arrive straight from the socket: anything a client sends must be rejected
+        // as an error, never panic the connection task
+        #[test]
+        fn test_decoder_rejects_garbage_frames(
+            text in any::<String>(),
+            bytes in prop::collection::vec(any::<u8>(), 0..256),
+        ) {
+            let _ = check_client_hello(&text);
+            let _ = codec::decode::<ClientMessage>(&Frame::Text(text));
+            let _ = codec::decode::<ClientMessage>(&Frame::Binary(bytes));
+        }
+
+        #[test]
+        fn test_decoder_rejects_corrupted_messages(
+            flips in prop::collection::vec((any::<Index>(), any::<u8>()), 1..8),
+        ) {
+            let message = ClientMessage {
+                token: "token".to_string(),
+                msg: GameMessage::SetName("Tướng".to_string()),
+            };
+            for encoding in [Encoding::Json, Encoding::Bincode] {
+                let (is_text, mut bytes) = match codec::encode(encoding, &message).unwrap() {
+                    Frame::Text(text) => (true, text.into_bytes()),
+                    Frame::Binary(bytes) => (false, bytes),
+                };
+                for (index, value) in &flips {
+                    let i = index.index(bytes.len());
+                    bytes[i] = *value;
+                }
+                let frame = if is_text {
+                    Frame::Text(String::from_utf8_lossy(&bytes).into_owned())
+                } else {
+                    Frame::Binary(bytes)
+                };
+                let _ = codec::decode::<ClientMessage>(&frame);
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fmt;
+
+/// Wire encodings for post-handshake frames. The hello frames themselves are always JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Encoding {
+    /// Text frames, human readable.
+    #[default]
+    Json,
+    /// Binary frames, compact. Requires the `bincode` feature.
+    Bincode,
+}
+
+impl Encoding {
+    /// Encodings compiled into this build, most compact first.
+    pub fn supported() -> Vec<Self> {
+        let mut encodings = Vec::new();
+        if cfg!(feature = "bincode") {
+            encodings.push(Self::Bincode);
+        }
+        encodings.push(Self::Json);
+        encodings
+    }
+
+    /// Picks the first of the peer's preferred encodings that this build supports.
+    pub fn negotiate(preferred: &[Self]) -> Self {
+        let supported = Self::supported();
+        preferred
+            .iter()
+            .copied()
+            .find(|e| supported.contains(e))
+            .unwrap_or_default()
+    }
+}
+
+/// A WebSocket payload, independent of the WebSocket library in use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodecError(pub String);
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "codec error: {}", self.0)
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+pub fn encode<T: Serialize>(encoding: Encoding, value: &T) -> Result<Frame, CodecError> {
+    match encoding {
+        Encoding::Json => serde_json::to_string(value)
+            .map(Frame::Text)
+            .map_err(|e| CodecError(e.to_string())),
+        #[cfg(feature = "bincode")]
+        Encoding::Bincode => bincode::serialize(value)
+            .map(Frame::Binary)
+            .map_err(|e| CodecError(e.to_string())),
+        #[cfg(not(feature = "bincode"))]
+        Encoding::Bincode => Err(CodecError("bincode support not compiled in".to_string())),
+    }
+}
+
+/// Decodes a frame; the frame type (text or binary) determines the encoding.
+pub fn decode<T: DeserializeOwned>(frame: &Frame) -> Result<T, CodecError> {
+    match frame {
+        Frame::Text(text) => serde_json::from_str(text).map_err(|e| CodecError(e.to_string())),
+        #[cfg(feature = "bincode")]
+        Frame::Binary(bytes) => bincode::deserialize(bytes).map_err(|e| CodecError(e.to_string())),
+        #[cfg(not(feature = "bincode"))]
+        Frame::Binary(_) => Err(CodecError("bincode support not compiled in".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameMessage, ServerMessage};
+    use cotuong_core::engine::Move;
+    use cotuong_core::logic::board::Board;
+
+    fn sample_move() -> GameMessage {
+        GameMessage::MakeMove {
+            move_data: Move {
+                from_row: 3,
+                from_col: 4,
+                to_row: 4,
+                to_col: 4,
+                score: 0,
+            },
+            fen: Board::new().to_fen_string(cotuong_core::logic::board::Color::Black),
+        }
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let frame = encode(Encoding::Json, &sample_move()).unwrap();
+        assert!(matches!(frame, Frame::Text(_)));
+        let decoded: GameMessage = decode(&frame).unwrap();
+        assert!(matches!(decoded, GameMessage::MakeMove { .. }));
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_roundtrip_is_smaller() {
+        let msg = sample_move();
+        let binary = encode(Encoding::Bincode, &msg).unwrap();
+        let text = encode(Encoding::Json, &msg).unwrap();
+        match (&binary, &text) {
+            (Frame::Binary(bytes), Frame::Text(json)) => assert!(bytes.len() < json.len()),
+            _ => panic!("Unexpected frame types"),
+        }
+
+        let decoded: GameMessage = decode(&binary).unwrap();
+        assert!(matches!(decoded, GameMessage::MakeMove { .. }));
+
+        let start = ServerMessage::GameStart(Box::new(Board::new()));
+        let frame = encode(Encoding::Bincode, &start).unwrap();
+        let decoded: ServerMessage = decode(&frame).unwrap();
+        assert!(matches!(decoded, ServerMessage::GameStart(_)));
+    }
+
+    #[test]
+    fn test_negotiate_falls_back_to_json() {
+        assert_eq!(Encoding::negotiate(&[]), Encoding::Json);
+        assert_eq!(Encoding::negotiate(&[Encoding::Json]), Encoding::Json);
+        let expected = if cfg!(feature = "bincode") {
+            Encoding::Bincode
+        } else {
+            Encoding::Json
+        };
+        assert_eq!(
+            Encoding::negotiate(&[Encoding::Bincode, Encoding::Json]),
+            expected
+        );
+    }
+}
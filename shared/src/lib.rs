@@ -1,44 +1,449 @@
 use cotuong_core::{
-    engine::Move,
-    logic::board::{Board, Color},
+    engine::{config::EngineConfig, Move},
+    logic::{
+        board::{Board, Color},
+        handicap::HandicapKind,
+    },
 };
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub mod codec;
+
+pub use codec::Encoding;
+
+/// Bumped whenever `GameMessage`/`ServerMessage` change incompatibly.
+pub const PROTOCOL_VERSION: u32 = 7;
+
+/// First frame a client sends. Kept separate from `ClientMessage` so that its shape never
+/// depends on the protocol version being negotiated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientHello {
+    pub protocol_version: u32,
+    /// Frame encodings the client can speak, in order of preference.
+    #[serde(default)]
+    pub encodings: Vec<Encoding>,
+    /// Token from an earlier `ServerMessage::Welcome`, to come back as the same player: with
+    /// the same rating and friends, and any seat held open by `DisconnectPolicy::Reconnect`
+    /// or restored from a snapshot.
+    #[serde(default)]
+    pub resume_token: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GameMessage {
     FindMatch,
     CancelFindMatch,
-    MakeMove { move_data: Move, fen: String },
-    VerifyMove { fen: String, is_valid: bool },
+    MakeMove {
+        move_data: Move,
+        fen: String,
+    },
+    VerifyMove {
+        fen: String,
+        is_valid: bool,
+    },
+    /// Asks for the moves of the current game from ply `from_seq` on, after the client saw
+    /// an `OpponentMove` numbered past its own position.
+    RequestMoves {
+        from_seq: u32,
+    },
     Surrender,
     RequestDraw,
     AcceptDraw,
     PlayAgain,
     PlayerLeft,
+    SetName(String),
+    /// Keepalive; the server answers with `ServerMessage::Pong` carrying the same nonce.
+    Ping(u64),
+    /// Starts or stops `ServerMessage::LiveGames` pushes to this connection.
+    SubscribeLiveGames,
+    UnsubscribeLiveGames,
+    /// Follows a live game by id; the server answers with `ServerMessage::SpectatorUpdate`.
+    Spectate(String),
+    StopSpectating,
+    /// Like `FindMatch`, but only pairs with players asking for the same handicap.
+    /// The handicap is given by whoever is drawn as Red.
+    FindHandicapMatch(HandicapKind),
+    /// Asks for the shared evaluation of a position by its zobrist hash; the server answers
+    /// with `ServerMessage::CloudEval`.
+    QueryEval(u64),
+    /// Offers an evaluation found by the client's own analysis to the shared cache.
+    SubmitEval(CloudEval),
+    /// Adds a display name to the sender's friend list; answered with `ServerMessage::Friends`.
+    AddFriend(String),
+    RemoveFriend(String),
+    /// Asks for `ServerMessage::Friends`.
+    ListFriends,
+    /// Challenges a friend, by display name, who is online and not playing.
+    /// `time_control` is a label shown with the game.
+    ChallengeFriend {
+        friend_id: String,
+        time_control: Option<String>,
+        #[serde(default)]
+        disconnect_policy: DisconnectPolicy,
+    },
+    /// Accepts a challenge by its id; the game starts as with `MatchFound`.
+    AcceptChallenge(String),
+    /// Declines a received challenge, or withdraws a sent one.
+    DeclineChallenge(String),
+}
+
+/// Machine-readable reason carried by `ServerMessage::Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ErrorCode {
+    NotYourTurn,
+    IllegalMove,
+    NotInGame,
+    GameAlreadyEnded,
+    RateLimited,
+    ProtocolVersionMismatch,
+    InvalidToken,
+    InvalidName,
+    MatchmakingUnavailable,
+    Internal,
+    GameNotFound,
+    /// The player's name was banned by an operator.
+    Banned,
+    /// Another player already goes by the requested name.
+    NameTaken,
+    /// No player goes by the name given for a friend.
+    PlayerNotFound,
+    /// The challenged player is not a friend, not online, or already playing.
+    PlayerUnavailable,
+    /// The challenge was withdrawn, declined, or never existed.
+    ChallengeNotFound,
+}
+
+/// Budget categories used by the server's rate limiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateLimitKind {
+    Move,
+    Chat,
+    Matchmaking,
+    Analysis,
+}
+
+/// Wire format for client messages: every `GameMessage` carries the token issued in
+/// `ServerMessage::Welcome` so the server can tie it to the sending socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientMessage {
+    pub token: String,
+    pub msg: GameMessage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ServerMessage {
+    ServerHello {
+        protocol_version: u32,
+        encoding: Encoding,
+    },
+    Welcome {
+        player_id: String,
+        token: String,
+    },
     MatchFound {
         opponent_id: String,
         your_color: Color,
         game_id: String,
+        /// Earlier games against the same opponent, from the receiver's side.
+        score: MatchScore,
     },
     GameStart(Box<Board>),
+    /// `seq` is the ply number the move will have once verified, counted from the start of
+    /// the game.
     OpponentMove {
         move_data: Move,
         fen: String,
+        seq: u32,
     },
+    /// Full position, sent when the players disagree or a client is too far behind for
+    /// `SyncMoves`. `seq` is the number of plies played so far.
     GameStateCorrection {
         fen: String,
         turn: Color,
+        seq: u32,
+    },
+    /// The opponent verified the receiver's move number `seq`; until then the client shows
+    /// it as pending.
+    MoveAccepted {
+        seq: u32,
+    },
+    /// Answer to `GameMessage::RequestMoves`: the verified moves from ply `from_seq` on.
+    SyncMoves {
+        from_seq: u32,
+        moves: Vec<Move>,
+    },
+    /// Sent once per turn to the player to move when their clock, or the per-move timeout
+    /// of an untimed game, is about to run out.
+    MoveTimeWarning {
+        seconds_left: u64,
     },
     GameEnd {
         winner: Option<Color>,
-        reason: String, // "Checkmate", "Surrender", "Draw", "Disconnect"
+        reason: String, // "Checkmate", "Surrender", "Draw", "Disconnect", "Timeout", "Terminated"
+    },
+    Error {
+        code: ErrorCode,
+        detail: Option<String>,
     },
-    Error(String),
     WaitingForMatch,
     OpponentDisconnected,
+    /// The opponent lost the connection; the game waits `seconds_left` for them to come back
+    /// before they lose.
+    OpponentAway {
+        seconds_left: u64,
+    },
+    OpponentReturned,
     OpponentLeftGame,
+    ServerShuttingDown {
+        seconds: u64,
+    },
+    RateLimited {
+        kind: RateLimitKind,
+        retry_after_ms: u64,
+    },
+    Pong(u64),
+    LiveGames(Vec<LiveGame>),
+    /// Position of a spectated game, sent on `GameMessage::Spectate` and after every move.
+    SpectatorUpdate {
+        game: LiveGame,
+        fen: String,
+    },
+    /// Answer to `GameMessage::QueryEval`; `eval` is `None` when nobody analysed the position yet.
+    CloudEval {
+        hash: u64,
+        eval: Option<CloudEval>,
+    },
+    /// Message from the operators to everyone connected.
+    Announcement(String),
+    /// The game was called off without a result; ratings are unchanged.
+    GameAborted {
+        reason: AbortReason,
+    },
+    /// The receiver's friend list, with presence on this server.
+    Friends(Vec<FriendStatus>),
+    /// Confirms `GameMessage::ChallengeFriend`; `to` is the friend's display name.
+    ChallengeSent {
+        challenge_id: String,
+        to: String,
+    },
+    /// A friend challenged the receiver; answer with `GameMessage::AcceptChallenge` or
+    /// `GameMessage::DeclineChallenge`.
+    ChallengeReceived {
+        challenge_id: String,
+        from: String,
+        time_control: Option<String>,
+        disconnect_policy: DisconnectPolicy,
+    },
+    /// The challenge was declined, withdrawn, or can no longer be played.
+    ChallengeClosed {
+        challenge_id: String,
+    },
+}
+
+/// Whether a friend can be challenged right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Presence {
+    Offline,
+    Online,
+    Playing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriendStatus {
+    pub name: String,
+    pub presence: Presence,
+}
+
+/// Why a game was aborted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AbortReason {
+    /// Nobody moved for too long after `GameStart`.
+    NoFirstMove,
+}
+
+/// What happens to a game in progress when a player's connection drops, chosen in a
+/// friend challenge. Matchmade games always use `Forfeit`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisconnectPolicy {
+    /// The player who dropped loses at once.
+    #[default]
+    Forfeit,
+    /// The seat is held for `minutes`; the player loses if they are not back by then.
+    Reconnect { minutes: u8 },
+    /// The engine judges the position: a clear advantage wins, anything closer is a draw.
+    Adjudicate,
+}
+
+impl DisconnectPolicy {
+    /// Longest reconnect window a challenge may ask for.
+    pub const MAX_RECONNECT_MINUTES: u8 = 10;
+
+    /// The policy with its reconnect window kept within `1..=MAX_RECONNECT_MINUTES`.
+    #[must_use]
+    pub fn clamped(self) -> Self {
+        match self {
+            Self::Reconnect { minutes } => Self::Reconnect {
+                minutes: minutes.clamp(1, Self::MAX_RECONNECT_MINUTES),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Results of the games so far between the same two players, from one player's side.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchScore {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+impl MatchScore {
+    #[must_use]
+    pub const fn games(self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// The same score from the opponent's side.
+    #[must_use]
+    pub const fn flipped(self) -> Self {
+        Self {
+            wins: self.losses,
+            losses: self.wins,
+            draws: self.draws,
+        }
+    }
+
+    /// Counts one more game: `Some(true)` won, `Some(false)` lost, `None` drawn.
+    pub const fn record(&mut self, won: Option<bool>) {
+        match won {
+            Some(true) => self.wins += 1,
+            Some(false) => self.losses += 1,
+            None => self.draws += 1,
+        }
+    }
+}
+
+/// Points with a draw worth half, e.g. `2½–1½`.
+impl fmt::Display for MatchScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let points = |half_points: u32| match (half_points / 2, half_points % 2) {
+            (0, 1) => "½".to_string(),
+            (whole, 1) => format!("{whole}½"),
+            (whole, _) => whole.to_string(),
+        };
+        write!(
+            f,
+            "{}–{}",
+            points(self.wins * 2 + self.draws),
+            points(self.losses * 2 + self.draws)
+        )
+    }
+}
+
+/// A position evaluation shared between clients, keyed by `Board::zobrist_hash`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CloudEval {
+    pub hash: u64,
+    pub depth: u8,
+    /// Centipawns from the side to move's point of view.
+    pub score: i32,
+    pub best_move: Option<Move>,
+}
+
+/// An ongoing game as listed for spectators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveGame {
+    pub game_id: String,
+    pub red_name: String,
+    pub black_name: String,
+    pub move_count: u32,
+    /// Human-readable time control, `None` for untimed games.
+    pub time_control: Option<String>,
+    /// Named opening once the game has played a book move, e.g. "Pháo đầu – Bình phong mã".
+    pub opening: Option<String>,
+}
+
+/// A game as served at `GET /games/:game_id/replay` for shared `/replay/:game_id` links.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameReplay {
+    pub game_id: String,
+    pub red_name: String,
+    pub black_name: String,
+    /// Position the game started from, as a FEN.
+    pub start_fen: String,
+    /// Moves packed as in `cotuong_core::logic::share::moves_to_param`.
+    pub moves: String,
+    /// PGN result, `*` while the game is being played.
+    pub result: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub name: String,
+    pub rating: i32,
+    pub games_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardPage {
+    pub entries: Vec<LeaderboardEntry>,
+    pub page: usize,
+    pub per_page: usize,
+    pub total: usize,
+}
+
+/// A player's rating right after one of their games.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RatingPoint {
+    pub time_ms: u64,
+    pub rating: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpeningCount {
+    pub name: String,
+    pub games: u32,
+}
+
+/// Served at `GET /players/{name}/profile`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub name: String,
+    pub rating: i32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// Oldest first; only the most recent games are kept.
+    pub rating_history: Vec<RatingPoint>,
+    /// Openings the player chose, by their book name, most played first.
+    pub favorite_openings: Vec<OpeningCount>,
+    /// Average move accuracy (0–100) over the games the engine reviewed; `None` before any.
+    pub average_accuracy: Option<f64>,
+}
+
+/// Body of `POST /arena/configs`: an engine config entered into the arena under `name`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArenaSubmission {
+    pub name: String,
+    pub config: EngineConfig,
+}
+
+/// A config's row in the arena table served at `GET /arena/leaderboard`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArenaStanding {
+    pub rank: usize,
+    pub name: String,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Performance against the rest of the field; `None` before any game or after a sweep.
+    pub elo: Option<i32>,
+    /// Games still queued against the other entries.
+    pub pending: u32,
 }